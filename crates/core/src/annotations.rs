@@ -0,0 +1,201 @@
+use crate::check::{CheckReport, Finding, Level};
+use crate::sarif::to_sarif;
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+/// Most annotations the Checks API accepts per request
+const CHECKS_API_ANNOTATIONS: usize = 50;
+
+/// Where `check` findings are shown on a pull request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AnnotationFormat {
+    /// `::error file=...::` workflow commands, for a GitHub Actions step
+    Workflow,
+    /// A check run body for the Checks API (`POST /repos/{owner}/{repo}/check-runs`)
+    Checks,
+    /// A SARIF 2.1.0 log for code scanning
+    Sarif,
+}
+
+impl AnnotationFormat {
+    pub fn render(self, report: &CheckReport) -> String {
+        match self {
+            AnnotationFormat::Workflow => workflow_commands(report),
+            AnnotationFormat::Checks => format!("{:#}\n", check_run(report)),
+            AnnotationFormat::Sarif => format!("{:#}\n", to_sarif(report)),
+        }
+    }
+}
+
+/// One workflow command per finding, so Actions shows each inline on the
+/// diff, with the report as the step's log
+pub fn workflow_commands(report: &CheckReport) -> String {
+    let mut out = String::new();
+    for check in &report.checks {
+        for finding in &check.findings {
+            let command = match finding.level {
+                Level::Error => "error",
+                Level::Warning => "warning",
+                Level::Notice => "notice",
+            };
+            let mut properties = Vec::new();
+            if let Some(file) = &finding.file {
+                properties.push(format!("file={}", escape_property(file)));
+                if let Some(line) = finding.line {
+                    properties.push(format!("line={}", line));
+                }
+            }
+            properties.push(format!("title={}", escape_property(&title(check.name, finding))));
+            out.push_str(&format!("::{} {}::{}\n", command, properties.join(","), escape_data(&finding.message)));
+        }
+    }
+    out.push_str(&format!("::group::{}\n", escape_data(&format!("Checks for {}", report.commit))));
+    out.push_str(&report.to_text());
+    out.push_str("::endgroup::\n");
+    out
+}
+
+/// A completed check run for `report.commit`. Findings with a line become
+/// annotations (the API takes at most 50 per request); the rest are listed
+/// in the summary with the report.
+pub fn check_run(report: &CheckReport) -> Value {
+    let mut annotations = Vec::new();
+    let mut unplaced = Vec::new();
+    for check in &report.checks {
+        for finding in &check.findings {
+            let (Some(file), Some(line)) = (&finding.file, finding.line) else {
+                unplaced.push(finding);
+                continue;
+            };
+            let level = match finding.level {
+                Level::Error => "failure",
+                Level::Warning => "warning",
+                Level::Notice => "notice",
+            };
+            annotations.push(json!({
+                "path": file,
+                "start_line": line,
+                "end_line": line,
+                "annotation_level": level,
+                "title": title(check.name, finding),
+                "message": finding.message,
+            }));
+        }
+    }
+
+    let failed = report.checks.iter().filter(|check| check.gating && !check.passed).count();
+    let title = match failed {
+        0 => "All checks passed".to_string(),
+        1 => "1 check failed".to_string(),
+        n => format!("{} checks failed", n),
+    };
+    let mut summary = format!("```\n{}```\n", report.to_text());
+    for finding in unplaced {
+        summary.push_str(&format!("- **{}**: {}\n", finding.rule, finding.message));
+    }
+    if annotations.len() > CHECKS_API_ANNOTATIONS {
+        summary.push_str(&format!(
+            "\nShowing {} of {} annotations.\n",
+            CHECKS_API_ANNOTATIONS,
+            annotations.len()
+        ));
+        annotations.truncate(CHECKS_API_ANNOTATIONS);
+    }
+
+    json!({
+        "name": "reviewbot check",
+        "head_sha": report.commit,
+        "status": "completed",
+        "conclusion": if report.passed { "success" } else { "failure" },
+        "output": {
+            "title": title,
+            "summary": summary,
+            "annotations": annotations,
+        },
+    })
+}
+
+fn title(check: &str, finding: &Finding) -> String {
+    format!("{} ({})", finding.rule, check)
+}
+
+/// Escape a workflow command's message
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape a workflow command property, where `:` and `,` are delimiters
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check::CheckResult;
+
+    fn finding(rule: &'static str, level: Level, message: &str, file: Option<&str>) -> Finding {
+        Finding {
+            rule,
+            level,
+            message: message.to_string(),
+            file: file.map(str::to_string),
+            line: file.map(|_| 5),
+            symbol: None,
+        }
+    }
+
+    fn report() -> CheckReport {
+        CheckReport {
+            commit: "abc123".to_string(),
+            passed: false,
+            checks: vec![
+                CheckResult {
+                    name: "cycles",
+                    passed: false,
+                    gating: true,
+                    summary: "1 new cycles (max 0)".to_string(),
+                    findings: vec![finding(
+                        "cycle",
+                        Level::Error,
+                        "dependency cycle between a, b\n100%",
+                        Some("src/a,b:c.ts"),
+                    )],
+                },
+                CheckResult {
+                    name: "resolution",
+                    passed: true,
+                    gating: true,
+                    summary: "9 of 10 references resolved".to_string(),
+                    findings: vec![finding("resolution-coverage", Level::Warning, "90.0% resolve", None)],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_workflow_commands() {
+        let out = workflow_commands(&report());
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "::error file=src/a%2Cb%3Ac.ts,line=5,title=cycle (cycles)::dependency cycle between a, b%0A100%25"
+        );
+        assert_eq!(lines[1], "::warning title=resolution-coverage (resolution)::90.0%25 resolve");
+        assert_eq!(lines[2], "::group::Checks for abc123");
+        assert_eq!(lines.last(), Some(&"::endgroup::"));
+    }
+
+    #[test]
+    fn test_check_run() {
+        let run = check_run(&report());
+        assert_eq!(run["conclusion"], "failure");
+        assert_eq!(run["head_sha"], "abc123");
+        assert_eq!(run["output"]["title"], "1 check failed");
+        let annotations = run["output"]["annotations"].as_array().unwrap();
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0]["path"], "src/a,b:c.ts");
+        assert_eq!(annotations[0]["annotation_level"], "failure");
+        assert!(run["output"]["summary"].as_str().unwrap().contains("- **resolution-coverage**: 90.0% resolve"));
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Exit code used when a scan stops because of Ctrl-C / SIGTERM
+pub const CANCELLED_EXIT_CODE: i32 = 130;
+
+/// Cooperative cancellation flag shared between the signal handler and a scan.
+///
+/// Long-running loops poll [`CancellationToken::is_cancelled`] between units of
+/// work (files), so a cancelled scan stops on a clean boundary.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Cancel `token` on Ctrl-C or SIGTERM. A second signal exits immediately.
+pub fn install_signal_handlers(token: CancellationToken) {
+    tokio::spawn(async move {
+        loop {
+            wait_for_signal().await;
+            if token.is_cancelled() {
+                warn!("Second interrupt received, exiting immediately");
+                std::process::exit(CANCELLED_EXIT_CODE);
+            }
+            warn!("Interrupt received, finishing the current file before stopping (press Ctrl-C again to force)");
+            token.cancel();
+        }
+    });
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut sigterm) => {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = sigterm.recv() => {}
+            }
+        }
+        Err(_) => {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_state() {
+        let token = CancellationToken::new();
+        let observer = token.clone();
+        assert!(!observer.is_cancelled());
+        token.cancel();
+        assert!(observer.is_cancelled());
+    }
+}
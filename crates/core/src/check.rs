@@ -0,0 +1,899 @@
+use anyhow::{anyhow, Result};
+use crate::diff::ChangedLines;
+use crate::layers::Layers;
+use globset::{Glob, GlobMatcher};
+use crate::source::read_source;
+use protocol::{EdgeIR, EdgeType, SymbolFlags, SymbolIR, SymbolKind};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+use store::{CodeGraph, GraphStore};
+
+/// Exit code when `check` ran fine but a gate failed; 1 is left for the tool
+/// itself failing and 2 for bad arguments
+pub const CHECK_FAILED_EXIT_CODE: i32 = 3;
+
+/// Findings printed per check before the rest are summarized
+const FINDINGS_SHOWN: usize = 20;
+
+/// How much a finding matters: errors fail their check, warnings are
+/// findings of a check that stayed within its limit and notices are context
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+    Notice,
+}
+
+/// One problem a check found, located as precisely as the graph allows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    /// Stable id of the rule, e.g. `cycle` or `api-break`
+    pub rule: &'static str,
+    pub level: Level,
+    pub message: String,
+    /// Repo-relative file the finding points at
+    pub file: Option<String>,
+    /// 1-based line in `file`
+    pub line: Option<u32>,
+    /// FQN of the symbol the finding is about
+    pub symbol: Option<String>,
+}
+
+impl Finding {
+    fn new(rule: &'static str, message: String) -> Self {
+        Self { rule, level: Level::Error, message, file: None, line: None, symbol: None }
+    }
+
+    fn at(mut self, symbol: &SymbolIR) -> Self {
+        self.file = Some(symbol.file_path.clone());
+        self.line = Some(symbol.name_span.start_line + 1);
+        self.symbol = Some(symbol.fqn.clone());
+        self
+    }
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub passed: bool,
+    /// Whether the check can fail the run; informational checks always pass
+    /// and only report
+    pub gating: bool,
+    /// One line describing what was measured against what limit
+    pub summary: String,
+    pub findings: Vec<Finding>,
+}
+
+/// Outcome of every selected check against one snapshot
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckReport {
+    pub commit: String,
+    pub passed: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+impl CheckReport {
+    /// Human-readable report: a PASS/FAIL line per check and the first
+    /// findings of the failing ones
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for check in &self.checks {
+            let status = match (check.gating, check.passed) {
+                (false, _) => "INFO",
+                (true, true) => "PASS",
+                (true, false) => "FAIL",
+            };
+            out.push_str(&format!("{} {}: {}\n", status, check.name, check.summary));
+            if check.gating && check.passed {
+                continue;
+            }
+            for finding in check.findings.iter().take(FINDINGS_SHOWN) {
+                match (&finding.file, finding.line) {
+                    (Some(file), Some(line)) => out.push_str(&format!("  {}:{}: {}\n", file, line, finding.message)),
+                    _ => out.push_str(&format!("  {}\n", finding.message)),
+                }
+            }
+            if check.findings.len() > FINDINGS_SHOWN {
+                out.push_str(&format!("  ... and {} more\n", check.findings.len() - FINDINGS_SHOWN));
+            }
+        }
+        let failed = self.checks.iter().filter(|check| !check.passed).count();
+        if failed == 0 {
+            out.push_str(&format!("All {} checks passed for {}\n", self.checks.len(), self.commit));
+        } else {
+            out.push_str(&format!("{} of {} checks failed for {}\n", failed, self.checks.len(), self.commit));
+        }
+        out
+    }
+}
+
+/// Files under `from` must not depend on files under `to`, written
+/// `FROM->TO` with globs over repo-relative paths, e.g. `src/ui/**->src/db/**`
+#[derive(Debug, Clone)]
+pub struct DependencyRule {
+    pub text: String,
+    from: GlobMatcher,
+    to: GlobMatcher,
+}
+
+impl FromStr for DependencyRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (from, to) = s
+            .split_once("->")
+            .ok_or_else(|| format!("rule '{}' is not of the form FROM->TO", s))?;
+        let matcher = |glob: &str| {
+            Glob::new(glob.trim())
+                .map(|glob| glob.compile_matcher())
+                .map_err(|e| format!("bad glob '{}': {}", glob.trim(), e))
+        };
+        Ok(Self { text: s.to_string(), from: matcher(from)?, to: matcher(to)? })
+    }
+}
+
+impl DependencyRule {
+    fn forbids(&self, file_src: &str, file_dst: &str) -> bool {
+        file_src != file_dst && self.from.is_match(file_src) && self.to.is_match(file_dst)
+    }
+}
+
+/// Which checks to run and their limits; checks left unset are skipped
+#[derive(Debug, Clone, Default)]
+pub struct CheckOptions {
+    /// Snapshot the change is judged against: only cycles it doesn't have
+    /// count, and its public API is what must survive
+    pub base: Option<String>,
+    /// Most dependency cycles allowed (new ones only when there is a base)
+    pub max_cycles: Option<usize>,
+    pub forbidden: Vec<DependencyRule>,
+    /// Fail when public symbols of the base were removed or changed
+    pub api_breaks: bool,
+    /// Least share (0-1) of references that must resolve to a known symbol
+    pub min_resolution: Option<f64>,
+    /// Report changed symbols with at least this many dependents
+    pub hotspot_fan_in: Option<usize>,
+    /// Lines changed since the base, for hotspots
+    pub changes: Option<ChangedLines>,
+    /// Report private symbols nothing refers to
+    pub dead_code: bool,
+    /// Report functions with identical bodies at least this many lines long
+    pub duplicate_lines: Option<usize>,
+    /// Architecture layers dependencies must respect
+    pub layers: Option<Layers>,
+}
+
+impl CheckOptions {
+    pub fn is_empty(&self) -> bool {
+        self.max_cycles.is_none()
+            && self.forbidden.is_empty()
+            && !self.api_breaks
+            && self.min_resolution.is_none()
+            && self.hotspot_fan_in.is_none()
+            && !self.dead_code
+            && self.duplicate_lines.is_none()
+            && self.layers.is_none()
+    }
+}
+
+/// Run the checks in `options` against snapshot `commit`. Duplicates are
+/// compared in the sources under `repo_root`, which should be checked out at
+/// `commit`.
+pub fn run(store: &GraphStore, repo_root: &Path, commit: &str, options: &CheckOptions) -> Result<CheckReport> {
+    if options.is_empty() {
+        return Err(anyhow!(
+            "No checks selected; pass --max-cycles, --forbid, --api-breaks, --min-resolution, \
+             --hotspot-fan-in, --dead-code, --duplicates or --layers"
+        ));
+    }
+    let needs_base = options.api_breaks || options.hotspot_fan_in.is_some();
+    if needs_base && options.base.is_none() {
+        return Err(anyhow!("--api-breaks and --hotspot-fan-in compare against --base"));
+    }
+    let symbols = store.get_snapshot_symbols(commit)?;
+    let edges = store.get_snapshot_edges(commit)?;
+    let base = match &options.base {
+        Some(base) => Some((base.as_str(), store.get_snapshot_symbols(base)?)),
+        None => None,
+    };
+
+    let mut checks = Vec::new();
+    if let Some(max) = options.max_cycles {
+        let base_cycles = match &base {
+            Some((base, base_symbols)) => Some(cycle_names(base_symbols, &store.get_snapshot_edges(base)?)),
+            None => None,
+        };
+        checks.push(check_cycles(&symbols, &edges, max, base_cycles.as_ref()));
+    }
+    if !options.forbidden.is_empty() {
+        checks.push(check_forbidden(&edges, &options.forbidden));
+    }
+    if let Some(layers) = &options.layers {
+        checks.push(check_layers(&symbols, &edges, layers));
+    }
+    if let (true, Some((base, base_symbols))) = (options.api_breaks, &base) {
+        checks.push(check_api(base_symbols, &symbols, base));
+    }
+    if let Some(min) = options.min_resolution {
+        checks.push(check_resolution(&symbols, &edges, min));
+    }
+    if let Some(min_fan_in) = options.hotspot_fan_in {
+        let changes = options
+            .changes
+            .as_ref()
+            .ok_or_else(|| anyhow!("--hotspot-fan-in needs the lines changed since the base"))?;
+        checks.push(check_hotspots(&symbols, &edges, changes, min_fan_in));
+    }
+    if options.dead_code {
+        let entry_points = store.get_entry_points(commit)?;
+        let entry_points: HashSet<&str> = entry_points.keys().map(String::as_str).collect();
+        checks.push(check_dead_code(&symbols, &edges, &entry_points));
+    }
+    if let Some(min_lines) = options.duplicate_lines {
+        checks.push(check_duplicates(&symbols, min_lines, |file| {
+            read_source(&repo_root.join(file)).ok().map(|source| source.text)
+        }));
+    }
+
+    // Whatever a passing check found is worth a look but not a failure
+    for check in checks.iter_mut().filter(|check| check.passed) {
+        for finding in check.findings.iter_mut().filter(|f| f.level == Level::Error) {
+            finding.level = Level::Warning;
+        }
+    }
+
+    Ok(CheckReport {
+        commit: commit.to_string(),
+        passed: checks.iter().all(|check| check.passed),
+        checks,
+    })
+}
+
+/// Dependency cycles as sorted member ids; containment is structure, not
+/// dependency
+fn cycles(symbols: &[SymbolIR], edges: &[EdgeIR]) -> Vec<Vec<String>> {
+    let edges: Vec<EdgeIR> = edges.iter().filter(|e| e.edge_type != EdgeType::Contains).cloned().collect();
+    let mut cycles = CodeGraph::build_from_data(symbols, &edges).cycles();
+    for cycle in &mut cycles {
+        cycle.sort();
+    }
+    cycles.sort();
+    cycles
+}
+
+/// Members of a cycle by FQN (ids are per snapshot), falling back to the id
+/// of targets that aren't symbols
+fn member_names(cycle: &[String], by_id: &HashMap<&str, &SymbolIR>) -> Vec<String> {
+    let mut names: Vec<String> = cycle
+        .iter()
+        .map(|id| by_id.get(id.as_str()).map_or(id.as_str(), |s| s.fqn.as_str()).to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Cycles of a snapshot keyed by their members' names, to tell which cycles
+/// of another snapshot are new
+fn cycle_names(symbols: &[SymbolIR], edges: &[EdgeIR]) -> HashSet<Vec<String>> {
+    let by_id: HashMap<&str, &SymbolIR> = symbols.iter().map(|s| (s.id.as_str(), s)).collect();
+    cycles(symbols, edges).iter().map(|cycle| member_names(cycle, &by_id)).collect()
+}
+
+fn check_cycles(
+    symbols: &[SymbolIR],
+    edges: &[EdgeIR],
+    max: usize,
+    base: Option<&HashSet<Vec<String>>>,
+) -> CheckResult {
+    let by_id: HashMap<&str, &SymbolIR> = symbols.iter().map(|s| (s.id.as_str(), s)).collect();
+
+    let findings = cycles(symbols, edges)
+        .iter()
+        .filter_map(|cycle| {
+            let names = member_names(cycle, &by_id);
+            if base.is_some_and(|base| base.contains(&names)) {
+                return None;
+            }
+            let finding = Finding::new("cycle", format!("dependency cycle between {}", names.join(", ")));
+            let first = cycle.iter().filter_map(|id| by_id.get(id.as_str())).min_by_key(|s| s.fqn.as_str());
+            Some(match first {
+                Some(first) => finding.at(first),
+                None => finding,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let what = if base.is_some() { "new cycles" } else { "cycles" };
+    CheckResult {
+        name: "cycles",
+        passed: findings.len() <= max,
+        gating: true,
+        summary: format!("{} {} (max {})", findings.len(), what, max),
+        findings,
+    }
+}
+
+fn check_forbidden(edges: &[EdgeIR], rules: &[DependencyRule]) -> CheckResult {
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+    for edge in edges.iter().filter(|e| e.edge_type != EdgeType::Contains) {
+        let (Some(src), Some(dst)) = (&edge.file_src, &edge.file_dst) else {
+            continue;
+        };
+        for rule in rules.iter().filter(|rule| rule.forbids(src, dst)) {
+            // One finding per file pair and rule, however many references
+            if seen.insert((src.clone(), dst.clone(), rule.text.clone())) {
+                let mut finding = Finding::new(
+                    "forbidden-dependency",
+                    format!("{} depends on {} ({:?}), forbidden by {}", src, dst, edge.edge_type, rule.text),
+                );
+                finding.file = Some(src.clone());
+                findings.push(finding);
+            }
+        }
+    }
+
+    CheckResult {
+        name: "forbidden-deps",
+        passed: findings.is_empty(),
+        gating: true,
+        summary: format!("{} forbidden dependencies ({} rules)", findings.len(), rules.len()),
+        findings,
+    }
+}
+
+/// Dependencies from a symbol in one layer on a symbol in a layer it may
+/// not use, one finding per pair of symbols
+fn check_layers(symbols: &[SymbolIR], edges: &[EdgeIR], layers: &Layers) -> CheckResult {
+    let by_id: HashMap<&str, &SymbolIR> = symbols.iter().map(|s| (s.id.as_str(), s)).collect();
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+    for edge in edges.iter().filter(|e| e.edge_type != EdgeType::Contains) {
+        let src = edge.src.as_deref().and_then(|id| by_id.get(id).copied());
+        let dst = edge.dst.as_deref().and_then(|id| by_id.get(id).copied());
+        let file_src = src.map(|s| s.file_path.as_str()).or(edge.file_src.as_deref());
+        let file_dst = dst.map(|s| s.file_path.as_str()).or(edge.file_dst.as_deref());
+        let (Some(file_src), Some(file_dst)) = (file_src, file_dst) else {
+            continue;
+        };
+        let Some(why) = layers.violation(file_src, file_dst) else {
+            continue;
+        };
+        if !seen.insert((edge.src.clone(), edge.dst.clone(), file_src, file_dst)) {
+            continue;
+        }
+        let name = |symbol: Option<&SymbolIR>, file: &str| symbol.map_or(file.to_string(), |s| s.fqn.clone());
+        let mut finding = Finding::new(
+            "layer-violation",
+            format!("{} depends on {}: {}", name(src, file_src), name(dst, file_dst), why),
+        );
+        match src {
+            Some(src) => finding = finding.at(src),
+            None => finding.file = Some(file_src.to_string()),
+        }
+        findings.push(finding);
+    }
+
+    let members = symbols.iter().filter(|s| layers.layer_of(&s.file_path).is_some()).count();
+    CheckResult {
+        name: "layers",
+        passed: findings.is_empty(),
+        gating: true,
+        summary: format!("{} layer violations ({} of {} symbols in a layer)", findings.len(), members, symbols.len()),
+        findings,
+    }
+}
+
+/// Public symbols of `base` that are gone from `head` or whose signature changed.
+/// Symbols sharing an FQN (overloads, a C++ declaration and its definition, a
+/// `.d.ts` and its implementation) are judged together: a name breaks when
+/// one of its base signatures is no longer among its head signatures.
+fn check_api(base: &[SymbolIR], head: &[SymbolIR], base_commit: &str) -> CheckResult {
+    let signatures = |symbols: &[&SymbolIR]| -> BTreeSet<String> { symbols.iter().map(|s| signature(s)).collect() };
+    let head_by_fqn = public_by_fqn(head);
+
+    let mut findings = Vec::new();
+    let base_by_fqn = public_by_fqn(base);
+    let checked = base_by_fqn.len();
+    for (fqn, old) in base_by_fqn {
+        let Some(new) = head_by_fqn.get(fqn) else {
+            findings.push(Finding::new("api-break", format!("public {:?} {} was removed", old[0].kind, fqn)).at(old[0]));
+            continue;
+        };
+        if !new.iter().any(|new| old.iter().any(|old| old.kind == new.kind)) {
+            findings.push(
+                Finding::new("api-break", format!("{} changed from {:?} to {:?}", fqn, old[0].kind, new[0].kind))
+                    .at(new[0]),
+            );
+            continue;
+        }
+        let (old_signatures, new_signatures) = (signatures(&old), signatures(new));
+        if !old_signatures.is_subset(&new_signatures) {
+            let join = |signatures: BTreeSet<String>| signatures.into_iter().collect::<Vec<_>>().join("` | `");
+            findings.push(
+                Finding::new(
+                    "api-break",
+                    format!("signature of {} changed: `{}` -> `{}`", fqn, join(old_signatures), join(new_signatures)),
+                )
+                .at(new[0]),
+            );
+        }
+    }
+
+    CheckResult {
+        name: "api-breaks",
+        passed: findings.is_empty(),
+        gating: true,
+        summary: format!("{} breaking changes in {} public symbols since {}", findings.len(), checked, base_commit),
+        findings,
+    }
+}
+
+/// Comparable signature: the declared one with whitespace normalized, else
+/// one rebuilt from parameters and return type
+fn public_by_fqn(symbols: &[SymbolIR]) -> BTreeMap<&str, Vec<&SymbolIR>> {
+    let mut by_fqn: BTreeMap<&str, Vec<&SymbolIR>> = BTreeMap::new();
+    for symbol in symbols.iter().filter(|s| s.visibility.as_deref() == Some("public")) {
+        by_fqn.entry(symbol.fqn.as_str()).or_default().push(symbol);
+    }
+    by_fqn
+}
+
+fn signature(symbol: &SymbolIR) -> String {
+    match &symbol.signature {
+        Some(signature) => signature.split_whitespace().collect::<Vec<_>>().join(" "),
+        None => {
+            let params: Vec<String> = symbol
+                .parameters
+                .iter()
+                .map(|p| format!("{}: {}", p.name, p.type_name.as_deref().unwrap_or("_")))
+                .collect();
+            format!("({}) -> {}", params.join(", "), symbol.return_type.as_deref().unwrap_or("_"))
+        }
+    }
+}
+
+/// Share of references (non-containment edges) whose target is a symbol in
+/// the snapshot rather than a bare name the harness couldn't resolve
+fn check_resolution(symbols: &[SymbolIR], edges: &[EdgeIR], min: f64) -> CheckResult {
+    let ids: HashSet<&str> = symbols.iter().map(|s| s.id.as_str()).collect();
+    let references: Vec<&EdgeIR> = edges.iter().filter(|e| e.edge_type != EdgeType::Contains).collect();
+    let resolved = references
+        .iter()
+        .filter(|e| e.dst.as_deref().is_some_and(|dst| ids.contains(dst)))
+        .count();
+    let ratio = if references.is_empty() { 1.0 } else { resolved as f64 / references.len() as f64 };
+    let passed = ratio >= min;
+
+    let findings = if passed {
+        Vec::new()
+    } else {
+        vec![Finding::new(
+            "resolution-coverage",
+            format!("{:.1}% of references resolve, below the required {:.1}%", ratio * 100.0, min * 100.0),
+        )]
+    };
+
+    CheckResult {
+        name: "resolution",
+        passed,
+        gating: true,
+        summary: format!(
+            "{} of {} references resolved ({:.1}%, min {:.1}%)",
+            resolved,
+            references.len(),
+            ratio * 100.0,
+            min * 100.0
+        ),
+        findings,
+    }
+}
+
+/// Symbols the change touches that many others depend on, most depended on
+/// first; reported for reviewers, never failing. Generated code is left out.
+fn check_hotspots(symbols: &[SymbolIR], edges: &[EdgeIR], changes: &ChangedLines, min_fan_in: usize) -> CheckResult {
+    let mut dependents: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for edge in edges.iter().filter(|e| e.edge_type != EdgeType::Contains) {
+        if let (Some(src), Some(dst)) = (&edge.src, &edge.dst) {
+            if src != dst {
+                dependents.entry(dst.as_str()).or_default().insert(src.as_str());
+            }
+        }
+    }
+
+    let changed: Vec<&SymbolIR> = symbols
+        .iter()
+        .filter(|s| !s.flags.contains(SymbolFlags::GENERATED) && changes.touches(s))
+        .collect();
+    let mut hot: Vec<(&SymbolIR, usize)> = changed
+        .iter()
+        .map(|s| (*s, dependents.get(s.id.as_str()).map_or(0, HashSet::len)))
+        .filter(|(_, fan_in)| *fan_in >= min_fan_in)
+        .collect();
+    hot.sort_by(|(a, a_fan_in), (b, b_fan_in)| b_fan_in.cmp(a_fan_in).then_with(|| a.fqn.cmp(&b.fqn)));
+
+    let findings = hot
+        .iter()
+        .map(|(symbol, fan_in)| {
+            let mut finding = Finding::new(
+                "hotspot",
+                format!("{} changed and {} symbols depend on it", symbol.fqn, fan_in),
+            )
+            .at(symbol);
+            finding.level = Level::Notice;
+            finding
+        })
+        .collect::<Vec<_>>();
+
+    CheckResult {
+        name: "hotspots",
+        passed: true,
+        gating: false,
+        summary: format!(
+            "{} of {} changed symbols have {} or more dependents",
+            findings.len(),
+            changed.len(),
+            min_fan_in
+        ),
+        findings,
+    }
+}
+
+/// Functions, methods and types nothing live refers to. Everything that
+/// isn't a candidate itself (public API, tests, generated code, data) and
+/// detected entry points are live, and so is whatever they reach through
+/// edges other than containment; code only dead code uses is dead too.
+fn check_dead_code(symbols: &[SymbolIR], edges: &[EdgeIR], entry_points: &HashSet<&str>) -> CheckResult {
+    let candidates: Vec<&SymbolIR> = symbols
+        .iter()
+        .filter(|s| {
+            matches!(
+                s.kind,
+                SymbolKind::Function | SymbolKind::Method | SymbolKind::Class | SymbolKind::Struct | SymbolKind::Enum
+            )
+        })
+        .filter(|s| s.visibility.as_deref() != Some("public"))
+        .filter(|s| !s.flags.intersects(SymbolFlags::TEST_ONLY | SymbolFlags::GENERATED))
+        .filter(|s| !is_entry_point_name(&s.name) && !entry_points.contains(s.id.as_str()))
+        .collect();
+    let candidate_ids: HashSet<&str> = candidates.iter().map(|s| s.id.as_str()).collect();
+
+    let mut references: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut live: HashSet<&str> = HashSet::new();
+    for edge in edges.iter().filter(|e| e.edge_type != EdgeType::Contains && e.src != e.dst) {
+        let Some(dst) = edge.dst.as_deref() else { continue };
+        match edge.src.as_deref() {
+            Some(src) if candidate_ids.contains(src) => references.entry(src).or_default().push(dst),
+            // Referenced from live code or from a file's top level
+            _ => {
+                live.insert(dst);
+            }
+        }
+    }
+    let mut queue: Vec<&str> = live.iter().copied().collect();
+    while let Some(id) = queue.pop() {
+        for &dst in references.get(id).into_iter().flatten() {
+            if live.insert(dst) {
+                queue.push(dst);
+            }
+        }
+    }
+
+    let used_by_dead: HashSet<&str> = references.values().flatten().copied().collect();
+    let mut findings: Vec<Finding> = candidates
+        .iter()
+        .filter(|s| !live.contains(s.id.as_str()))
+        .map(|s| {
+            let message = if used_by_dead.contains(s.id.as_str()) {
+                format!("{:?} {} is only referenced from dead code", s.kind, s.fqn)
+            } else {
+                format!("{:?} {} is never referenced", s.kind, s.fqn)
+            };
+            Finding::new("dead-code", message).at(s)
+        })
+        .collect();
+    findings.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+    for finding in &mut findings {
+        finding.level = Level::Warning;
+    }
+
+    CheckResult {
+        name: "dead-code",
+        passed: true,
+        gating: false,
+        summary: format!("{} of {} private symbols are never referenced", findings.len(), candidates.len()),
+        findings,
+    }
+}
+
+/// Names the runtime, a framework or a test runner calls without a
+/// reference in the code
+fn is_entry_point_name(name: &str) -> bool {
+    matches!(name, "main" | "init" | "constructor" | "new" | "drop" | "setUp" | "tearDown")
+        || (name.starts_with("__") && name.ends_with("__"))
+        || name.starts_with("test")
+        || name.starts_with("Test")
+}
+
+/// Functions and methods whose bodies match once whitespace is dropped.
+/// Bodies are taken from after the name to the end of the declaration, so
+/// copies under different names still match.
+fn check_duplicates(
+    symbols: &[SymbolIR],
+    min_lines: usize,
+    read: impl Fn(&str) -> Option<String>,
+) -> CheckResult {
+    let mut by_file: BTreeMap<&str, Vec<&SymbolIR>> = BTreeMap::new();
+    for symbol in symbols.iter().filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method)) {
+        let lines = (symbol.full_span.end_line - symbol.full_span.start_line) as usize + 1;
+        if lines >= min_lines.max(1) {
+            by_file.entry(symbol.file_path.as_str()).or_default().push(symbol);
+        }
+    }
+
+    let mut bodies: HashMap<String, Vec<&SymbolIR>> = HashMap::new();
+    for (file, symbols) in by_file {
+        let Some(text) = read(file) else {
+            continue;
+        };
+        let lines: Vec<&str> = text.lines().collect();
+        for symbol in symbols {
+            if let Some(body) = body_after_name(&lines, symbol) {
+                bodies.entry(body).or_default().push(symbol);
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<&SymbolIR>> = bodies.into_values().filter(|group| group.len() > 1).collect();
+    for group in &mut groups {
+        group.sort_by(|a, b| (&a.file_path, a.full_span.start_line).cmp(&(&b.file_path, b.full_span.start_line)));
+    }
+    groups.sort_by(|a, b| (&a[0].file_path, a[0].full_span.start_line).cmp(&(&b[0].file_path, b[0].full_span.start_line)));
+
+    let mut findings = Vec::new();
+    for group in &groups {
+        let original = group[0];
+        for copy in &group[1..] {
+            let mut finding = Finding::new(
+                "duplicate-code",
+                format!(
+                    "{} duplicates the body of {} ({}:{})",
+                    copy.fqn,
+                    original.fqn,
+                    original.file_path,
+                    original.name_span.start_line + 1
+                ),
+            )
+            .at(copy);
+            finding.level = Level::Warning;
+            findings.push(finding);
+        }
+    }
+
+    CheckResult {
+        name: "duplicates",
+        passed: true,
+        gating: false,
+        summary: format!(
+            "{} functions duplicate another ({} groups, {}+ lines)",
+            findings.len(),
+            groups.len(),
+            min_lines
+        ),
+        findings,
+    }
+}
+
+/// Source from the end of the symbol's name to the end of its declaration,
+/// with all whitespace removed
+fn body_after_name(lines: &[&str], symbol: &SymbolIR) -> Option<String> {
+    let span = &symbol.full_span;
+    let name = &symbol.name_span;
+    let (start, end) = (name.end_line as usize, span.end_line as usize);
+    if end >= lines.len() || start > end {
+        return None;
+    }
+    let mut body = String::new();
+    for (row, line) in lines.iter().enumerate().take(end + 1).skip(start) {
+        let from = if row == start { name.end_col as usize } else { 0 };
+        let to = if row == end { (span.end_col as usize).min(line.len()) } else { line.len() };
+        body.extend(line.get(from.min(to)..to)?.chars().filter(|c| !c.is_whitespace()));
+    }
+    Some(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Resolution, Span, SymbolFlags, test_util};
+
+    fn symbol(id: &str, file: &str, signature: &str) -> SymbolIR {
+        SymbolIR {
+            signature: Some(signature.to_string()),
+            name_span: Span { start_line: 4, start_col: 0, end_line: 4, end_col: 3 },
+            full_span: Span { start_line: 4, start_col: 0, end_line: 9, end_col: 1 },
+            visibility: Some("public".to_string()),
+            ..test_util::symbol(id, id, file)
+        }
+    }
+
+    fn edge(src: &str, dst: &str, file_src: &str, file_dst: &str) -> EdgeIR {
+        EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: Some(file_src.to_string()),
+            file_dst: Some(file_dst.to_string()),
+            resolution: Resolution::Syntactic,
+            meta: Default::default(),
+            provenance: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_checks() {
+        let symbols = vec![
+            symbol("render", "src/ui/view.ts", "render(): void"),
+            symbol("query", "src/db/conn.ts", "query(sql: string): Row[]"),
+            symbol("save", "src/db/conn.ts", "save(row: Row): void"),
+        ];
+        let edges = vec![
+            edge("render", "query", "src/ui/view.ts", "src/db/conn.ts"),
+            edge("query", "save", "src/db/conn.ts", "src/db/conn.ts"),
+            edge("save", "query", "src/db/conn.ts", "src/db/conn.ts"),
+            edge("render", "console.log", "src/ui/view.ts", "src/ui/view.ts"),
+        ];
+
+        let cycles = check_cycles(&symbols, &edges, 0, None);
+        assert!(!cycles.passed);
+        assert_eq!(cycles.findings[0].message, "dependency cycle between query, save");
+        assert_eq!(cycles.findings[0].line, Some(5));
+        assert!(check_cycles(&symbols, &edges, 1, None).passed);
+
+        // Only cycles the base doesn't have count against the limit
+        let base = cycle_names(&symbols, &edges);
+        let cycles = check_cycles(&symbols, &edges, 0, Some(&base));
+        assert!(cycles.passed && cycles.findings.is_empty());
+        assert_eq!(cycles.summary, "0 new cycles (max 0)");
+
+        let rule: DependencyRule = "src/ui/**->src/db/**".parse().unwrap();
+        let forbidden = check_forbidden(&edges, &[rule]);
+        assert_eq!(forbidden.findings.len(), 1);
+        assert_eq!(forbidden.findings[0].file.as_deref(), Some("src/ui/view.ts"));
+        assert!("src/ui/**".parse::<DependencyRule>().is_err());
+
+        let layers = Layers::from_config(&[
+            crate::config::LayerConfig { name: "ui".to_string(), paths: vec!["src/ui/**".to_string()], may_use: None },
+            crate::config::LayerConfig { name: "db".to_string(), paths: vec!["src/db/**".to_string()], may_use: None },
+        ])
+        .unwrap();
+        assert!(check_layers(&symbols, &edges, &layers).passed);
+        let backwards = [edge("save", "render", "src/db/conn.ts", "src/ui/view.ts")];
+        let violations = check_layers(&symbols, &backwards, &layers);
+        assert_eq!(violations.findings.len(), 1);
+        assert_eq!(
+            violations.findings[0].message,
+            "save depends on render: db may not depend on ui (it may not depend on other layers)"
+        );
+
+        let resolution = check_resolution(&symbols, &edges, 0.8);
+        assert!(!resolution.passed);
+        assert!(resolution.summary.starts_with("3 of 4 references resolved"));
+        assert!(check_resolution(&symbols, &edges, 0.75).passed);
+    }
+
+    #[test]
+    fn test_api_breaks() {
+        let base = vec![
+            symbol("render", "src/ui/view.ts", "render(): void"),
+            symbol("query", "src/db/conn.ts", "query(sql: string): Row[]"),
+            symbol("save", "src/db/conn.ts", "save(row: Row): void"),
+        ];
+        let mut head = vec![
+            symbol("render", "src/ui/view.ts", "render():   void"),
+            symbol("query", "src/db/conn.ts", "query(sql: string, args: unknown[]): Row[]"),
+            symbol("load", "src/db/conn.ts", "load(): Row[]"),
+        ];
+        head[0].name_span.start_line = 7;
+
+        let api = check_api(&base, &head, "base");
+        assert!(!api.passed);
+        let messages: Vec<&str> = api.findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "signature of query changed: `query(sql: string): Row[]` -> `query(sql: string, args: unknown[]): Row[]`",
+                "public Function save was removed",
+            ]
+        );
+        assert!(check_api(&base, &base, "base").passed);
+
+        // A header declaration and its definition share an FQN: each side is
+        // judged as a whole, not whichever copy a map kept
+        let mut declaration = symbol("save", "src/db/conn.d.ts", "save(row: Row): Promise<void>");
+        declaration.flags = SymbolFlags::DECLARATION;
+        let base = vec![declaration.clone(), symbol("save", "src/db/conn.ts", "save(row: Row): void")];
+        let head = vec![base[1].clone(), declaration];
+        assert!(check_api(&base, &head, "base").passed);
+        let api = check_api(&base, &head[..1], "base");
+        assert_eq!(
+            api.findings[0].message,
+            "signature of save changed: `save(row: Row): Promise<void>` | `save(row: Row): void` -> `save(row: Row): void`"
+        );
+    }
+
+    #[test]
+    fn test_hotspots() {
+        let symbols = vec![
+            symbol("render", "src/ui/view.ts", "render(): void"),
+            symbol("query", "src/db/conn.ts", "query(sql: string): Row[]"),
+            symbol("save", "src/db/conn.ts", "save(row: Row): void"),
+            SymbolIR { flags: SymbolFlags::GENERATED, ..symbol("decode", "src/db/conn.ts", "decode(raw: Buffer): Row") },
+        ];
+        let edges = vec![
+            edge("render", "query", "src/ui/view.ts", "src/db/conn.ts"),
+            edge("save", "query", "src/db/conn.ts", "src/db/conn.ts"),
+            edge("render", "save", "src/ui/view.ts", "src/db/conn.ts"),
+            edge("render", "decode", "src/ui/view.ts", "src/db/conn.ts"),
+            edge("query", "decode", "src/db/conn.ts", "src/db/conn.ts"),
+        ];
+        // Spans cover lines 5-10 of each file
+        let changes = ChangedLines::parse("+++ b/src/db/conn.ts\n@@ -6 +6 @@\n");
+
+        let hotspots = check_hotspots(&symbols, &edges, &changes, 1);
+        assert!(hotspots.passed && !hotspots.gating);
+        let messages: Vec<&str> = hotspots.findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["query changed and 2 symbols depend on it", "save changed and 1 symbols depend on it"]
+        );
+        assert_eq!(hotspots.findings[0].level, Level::Notice);
+        // Generated `decode` is depended on as much as `query`, but left out
+        assert_eq!(hotspots.summary, "2 of 2 changed symbols have 1 or more dependents");
+        assert!(check_hotspots(&symbols, &edges, &changes, 3).findings.is_empty());
+    }
+
+    #[test]
+    fn test_dead_code_and_duplicates() {
+        let mut symbols = vec![
+            symbol("render", "src/ui/view.ts", "render(): void"),
+            symbol("format", "src/ui/view.ts", "format(): string"),
+            symbol("unused", "src/ui/util.ts", "unused(): string"),
+            symbol("main", "src/ui/util.ts", "main(): void"),
+            symbol("pad", "src/ui/util.ts", "pad(): string"),
+            symbol("onClick", "src/ui/util.ts", "onClick(): void"),
+        ];
+        for symbol in &mut symbols[1..] {
+            symbol.visibility = None;
+        }
+        let edges = vec![
+            edge("render", "format", "src/ui/view.ts", "src/ui/view.ts"),
+            edge("unused", "pad", "src/ui/util.ts", "src/ui/util.ts"),
+        ];
+
+        let dead = check_dead_code(&symbols, &edges, &HashSet::from(["onClick"]));
+        assert!(dead.passed && !dead.gating);
+        let messages: Vec<&str> = dead.findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(messages, ["Function unused is never referenced", "Function pad is only referenced from dead code"]);
+        assert_eq!(dead.findings[0].level, Level::Warning);
+
+        // Spans cover rows 4-9, names end at column 3 of row 4
+        let source = |body: &str| format!("\n\n\n\nfn {}\n{}\n", body, "  x += 1;\n".repeat(5));
+        let sources: HashMap<&str, String> = HashMap::from([
+            ("src/ui/view.ts", source("(a) {")),
+            ("src/ui/util.ts", source("(a)   {")),
+        ]);
+        let read = |file: &str| sources.get(file).cloned();
+        let duplicates = check_duplicates(&symbols[1..3], 3, read);
+        assert_eq!(duplicates.findings.len(), 1);
+        assert_eq!(
+            duplicates.findings[0].message,
+            "format duplicates the body of unused (src/ui/util.ts:5)"
+        );
+        assert!(check_duplicates(&symbols[1..3], 7, read).findings.is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+use anyhow::{anyhow, Result};
+use protocol::{EdgeIR, EdgeType, SymbolFlags, SymbolIR};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// A symbol, how often its signature changed and how much depends on it
+#[derive(Debug, Clone, Serialize)]
+pub struct ChurnedSymbol {
+    pub fqn: String,
+    pub file: String,
+    /// 1-based line of the symbol's name
+    pub line: u32,
+    /// Snapshots in which its `sig_hash` differed from the one before
+    pub changes: usize,
+    /// Distinct symbols referring to this one
+    pub fan_in: usize,
+}
+
+/// The symbols that change most often while many others depend on them
+#[derive(Debug, Clone, Serialize)]
+pub struct ChurnReport {
+    pub commit: String,
+    /// Snapshots of the history compared
+    pub snapshots: usize,
+    pub min_fan_in: usize,
+    /// Most changes first, then most dependents
+    pub symbols: Vec<ChurnedSymbol>,
+}
+
+impl ChurnReport {
+    pub fn to_text(&self, shown: usize) -> String {
+        let mut out = format!(
+            "{} symbols with fan-in of at least {} changed across {} snapshots up to {}\n",
+            self.symbols.len(),
+            self.min_fan_in,
+            self.snapshots,
+            self.commit
+        );
+        for symbol in self.symbols.iter().take(shown) {
+            out.push_str(&format!(
+                "  {:>3} changes  fan-in {:<4} {} ({}:{})\n",
+                symbol.changes, symbol.fan_in, symbol.fqn, symbol.file, symbol.line
+            ));
+        }
+        out
+    }
+}
+
+/// The snapshotted `commits` in the git history of `head`, oldest first
+pub fn history_order(repo_root: &Path, head: &str, commits: &[String]) -> Result<Vec<String>> {
+    let output = std::process::Command::new("git")
+        .args(["rev-list", "--reverse", "--topo-order", head])
+        .current_dir(repo_root)
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow!("git rev-list failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let snapshotted: HashSet<&str> = commits.iter().map(String::as_str).collect();
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|sha| snapshotted.contains(sha))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Rank the symbols of snapshot `commit` with at least `min_fan_in`
+/// dependents by `changes`, the per-symbol counts from
+/// [`GraphStore::symbol_churn`](store::GraphStore::symbol_churn) over
+/// `snapshots` snapshots. Symbols that never changed and generated code are
+/// left out.
+pub fn report(
+    commit: &str,
+    snapshots: usize,
+    symbols: &[SymbolIR],
+    edges: &[EdgeIR],
+    changes: &HashMap<String, usize>,
+    min_fan_in: usize,
+) -> ChurnReport {
+    // Containment is structure, not dependency
+    let mut dependents: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for edge in edges.iter().filter(|e| e.edge_type != EdgeType::Contains) {
+        if let (Some(src), Some(dst)) = (edge.src.as_deref(), edge.dst.as_deref()) {
+            if src != dst {
+                dependents.entry(dst).or_default().insert(src);
+            }
+        }
+    }
+
+    let mut churned: Vec<ChurnedSymbol> = symbols
+        .iter()
+        .filter(|s| !s.flags.contains(SymbolFlags::GENERATED))
+        .map(|s| ChurnedSymbol {
+            fqn: s.fqn.clone(),
+            file: s.file_path.clone(),
+            line: s.name_span.start_line + 1,
+            changes: changes.get(&s.id).copied().unwrap_or(0),
+            fan_in: dependents.get(s.id.as_str()).map_or(0, HashSet::len),
+        })
+        .filter(|s| s.changes > 0 && s.fan_in >= min_fan_in)
+        .collect();
+    churned.sort_by(|a, b| {
+        b.changes
+            .cmp(&a.changes)
+            .then_with(|| b.fan_in.cmp(&a.fan_in))
+            .then_with(|| a.fqn.cmp(&b.fqn))
+    });
+
+    ChurnReport {
+        commit: commit.to_string(),
+        snapshots,
+        min_fan_in,
+        symbols: churned,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Language, Resolution, Span, test_util};
+
+    fn symbol(id: &str) -> SymbolIR {
+        SymbolIR {
+            lang: Language::Python,
+            name: id.to_string(),
+            name_span: Span { start_line: 4, start_col: 4, end_line: 4, end_col: 8 },
+            full_span: test_util::lines(4, 6),
+            ..test_util::symbol(id, &format!("app.{}", id), "app/db.py")
+        }
+    }
+
+    fn edge(edge_type: EdgeType, src: &str, dst: &str) -> EdgeIR {
+        EdgeIR {
+            edge_type,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: Default::default(),
+            provenance: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_report_ranks_churned_dependencies() {
+        let mut symbols: Vec<SymbolIR> = ["load", "save", "connect", "main", "cli"].map(symbol).into();
+        symbols.push(SymbolIR { flags: SymbolFlags::GENERATED, ..symbol("schema") });
+        let edges = vec![
+            edge(EdgeType::Calls, "main", "load"),
+            edge(EdgeType::Calls, "cli", "load"),
+            edge(EdgeType::Calls, "cli", "load"),
+            edge(EdgeType::Calls, "main", "save"),
+            edge(EdgeType::Calls, "cli", "save"),
+            edge(EdgeType::Calls, "main", "connect"),
+            edge(EdgeType::Contains, "cli", "connect"),
+            edge(EdgeType::Calls, "connect", "connect"),
+            edge(EdgeType::Calls, "main", "schema"),
+            edge(EdgeType::Calls, "cli", "schema"),
+        ];
+        let changes = HashMap::from([
+            ("load".to_string(), 3),
+            ("save".to_string(), 5),
+            ("connect".to_string(), 7),
+            ("main".to_string(), 0),
+            ("schema".to_string(), 9),
+        ]);
+
+        let report = report("abc123", 8, &symbols, &edges, &changes, 2);
+        let ranked: Vec<(&str, usize, usize)> =
+            report.symbols.iter().map(|s| (s.fqn.as_str(), s.changes, s.fan_in)).collect();
+        // `connect` has one real dependent and `schema` is generated
+        assert_eq!(ranked, [("app.save", 5, 2), ("app.load", 3, 2)]);
+        assert_eq!(report.symbols[0].line, 5);
+
+        let text = report.to_text(1);
+        assert!(text.starts_with("2 symbols with fan-in of at least 2 changed across 8 snapshots up to abc123\n"));
+        assert_eq!(text.lines().count(), 2);
+    }
+}
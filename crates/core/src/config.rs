@@ -0,0 +1,97 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+use store::NoiseMode;
+
+/// Project settings checked in at the repository root
+pub const CONFIG_FILE: &str = "reviewbot.json";
+
+/// Contents of [`CONFIG_FILE`]; every section is optional
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Architecture layers, from the top down
+    #[serde(default)]
+    pub layers: Vec<LayerConfig>,
+    /// Standard library calls in graph queries
+    #[serde(default)]
+    pub call_noise: CallNoiseConfig,
+    /// Scope separators symbol lookups treat as equal, e.g. `["::", "."]`;
+    /// `::`, `.`, `#` and `/` when unset
+    #[serde(default)]
+    pub fqn_separators: Option<Vec<String>>,
+}
+
+/// One architecture layer, e.g. `{"name": "domain", "paths": ["src/domain/**"]}`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayerConfig {
+    pub name: String,
+    /// Globs over repo-relative paths of the files in the layer
+    pub paths: Vec<String>,
+    /// Layers this one may depend on besides itself; all layers listed
+    /// below it when unset
+    #[serde(default)]
+    pub may_use: Option<Vec<String>>,
+}
+
+/// e.g. `{"mode": "collapse", "extra": ["logger.*"]}`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CallNoiseConfig {
+    #[serde(default)]
+    pub mode: NoiseMode,
+    /// Callees to treat as standard library on top of the built-in list;
+    /// a trailing `*` matches any rest of the name
+    #[serde(default)]
+    pub extra: Vec<String>,
+}
+
+impl Config {
+    /// Read [`CONFIG_FILE`] under `repo_root`; defaults when there is none
+    pub fn load(repo_root: &Path) -> Result<Self> {
+        let path = repo_root.join(CONFIG_FILE);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&text).with_context(|| format!("Invalid {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_config() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(Config::load(dir.path()).unwrap(), Config::default());
+
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            r#"{"layers": [
+                {"name": "api", "paths": ["src/api/**"], "may_use": ["domain"]},
+                {"name": "domain", "paths": ["src/domain/**"]}
+            ]}"#,
+        )
+        .unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.layers.len(), 2);
+        assert_eq!(config.layers[0].may_use, Some(vec!["domain".to_string()]));
+        assert_eq!(config.layers[1].may_use, None);
+        assert_eq!(config.call_noise, CallNoiseConfig::default());
+        
+        std::fs::write(dir.path().join(CONFIG_FILE), r#"{"call_noise": {"mode": "drop", "extra": ["log.*"]}}"#).unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.call_noise.mode, NoiseMode::Drop);
+        assert_eq!(config.call_noise.extra, ["log.*"]);
+        assert_eq!(config.fqn_separators, None);
+
+        std::fs::write(dir.path().join(CONFIG_FILE), r#"{"fqn_separators": ["::", "."]}"#).unwrap();
+        assert_eq!(Config::load(dir.path()).unwrap().fqn_separators, Some(vec!["::".to_string(), ".".to_string()]));
+
+        std::fs::write(dir.path().join(CONFIG_FILE), r#"{"layer": []}"#).unwrap();
+        assert!(Config::load(dir.path()).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use crate::reviewers::CodeOwners;
+use protocol::{EdgeIR, EdgeType, SymbolFlags, SymbolIR};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Team of call sites no CODEOWNERS rule assigns
+const UNOWNED: &str = "(unowned)";
+
+/// A symbol still using a deprecated API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeprecatedUse {
+    /// FQN of the deprecated symbol
+    pub api: String,
+    /// FQN of the symbol using it
+    pub caller: String,
+    pub edge_type: String,
+    pub file: String,
+    /// 1-based line of the caller's name
+    pub line: u32,
+    /// Whether the edge named the API only by its simple name, which no
+    /// other symbol of the snapshot has
+    pub by_name: bool,
+}
+
+/// A deprecated symbol and how much it is still used
+#[derive(Debug, Clone, Serialize)]
+pub struct DeprecatedApi {
+    pub fqn: String,
+    pub file: String,
+    pub line: u32,
+    /// What the deprecation says, usually the replacement
+    pub note: Option<String>,
+    pub uses: usize,
+}
+
+/// The uses a team's code makes of deprecated APIs
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamDeprecations {
+    pub team: String,
+    pub uses: usize,
+    /// Uses per snapshot, aligned with [`DeprecationReport::snapshots`]
+    pub trend: Vec<usize>,
+    pub sites: Vec<DeprecatedUse>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeprecationReport {
+    pub commit: String,
+    /// Snapshots the trend covers, oldest first, ending with `commit`
+    pub snapshots: Vec<String>,
+    /// Uses per snapshot
+    pub trend: Vec<usize>,
+    /// Deprecated symbols in `commit`
+    pub deprecated: usize,
+    /// Used deprecated APIs, most used first
+    pub apis: Vec<DeprecatedApi>,
+    /// Most uses first
+    pub teams: Vec<TeamDeprecations>,
+}
+
+impl DeprecationReport {
+    pub fn to_text(&self, shown: usize) -> String {
+        let uses = self.trend.last().copied().unwrap_or(0);
+        let mut out = format!(
+            "{} uses of {} of {} deprecated APIs in {}",
+            uses,
+            self.apis.len(),
+            self.deprecated,
+            self.commit
+        );
+        if self.trend.len() > 1 {
+            out.push_str(&format!(" ({} over {} snapshots)", trend_text(&self.trend), self.trend.len()));
+        }
+        out.push('\n');
+        for team in &self.teams {
+            out.push_str(&format!("  {} {} uses ({})\n", team.team, team.uses, trend_text(&team.trend)));
+            for site in team.sites.iter().take(shown) {
+                out.push_str(&format!("    {} in {} ({}:{})\n", site.api, site.caller, site.file, site.line));
+            }
+            if team.sites.len() > shown {
+                out.push_str(&format!("    ... {} more\n", team.sites.len() - shown));
+            }
+        }
+        if !self.apis.is_empty() {
+            out.push_str("Most used:\n");
+        }
+        for api in self.apis.iter().take(shown) {
+            out.push_str(&format!("  {:>4}  {} ({}:{})", api.uses, api.fqn, api.file, api.line));
+            if let Some(note) = api.note.as_deref().filter(|note| !note.is_empty()) {
+                out.push_str(&format!(": {}", note));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn trend_text(trend: &[usize]) -> String {
+    trend.iter().map(usize::to_string).collect::<Vec<_>>().join(" -> ")
+}
+
+/// Every edge into a deprecated symbol, from a symbol that isn't itself
+/// deprecated: code going away with the API is no migration work. An edge
+/// that names its target rather than giving its id matches by FQN, or by
+/// simple name when only deprecated symbols have that name.
+/// Structure and declarations (containment, exports, aliases, `.d.ts`
+/// declarations, `permits`) aren't uses.
+pub fn uses(symbols: &[SymbolIR], edges: &[EdgeIR]) -> Vec<DeprecatedUse> {
+    let by_id: HashMap<&str, &SymbolIR> = symbols.iter().map(|s| (s.id.as_str(), s)).collect();
+    let by_fqn: HashMap<&str, &SymbolIR> = symbols.iter().map(|s| (s.fqn.as_str(), s)).collect();
+    let mut by_name: HashMap<&str, Vec<&SymbolIR>> = HashMap::new();
+    for symbol in symbols {
+        by_name.entry(symbol.name.as_str()).or_default().push(symbol);
+    }
+    let deprecated = |symbol: &SymbolIR| symbol.flags.contains(SymbolFlags::DEPRECATED);
+
+    let mut uses = Vec::new();
+    let mut seen = HashSet::new();
+    for edge in edges {
+        if matches!(
+            edge.edge_type,
+            EdgeType::Contains | EdgeType::Declares | EdgeType::Aliases | EdgeType::Exports | EdgeType::Permits
+        ) {
+            continue;
+        }
+        let (Some(src), Some(dst)) = (edge.src.as_deref(), edge.dst.as_deref()) else {
+            continue;
+        };
+        let Some(caller) = by_id.get(src).copied().filter(|caller| !deprecated(caller)) else {
+            continue;
+        };
+        let (api, by_name) = match by_id.get(dst).or_else(|| by_fqn.get(dst)) {
+            Some(api) => (*api, false),
+            None => {
+                let name = dst.rsplit(['.', ':', '/', '#']).next().unwrap_or(dst);
+                match by_name.get(name) {
+                    Some(named) if named.iter().all(|s| deprecated(s)) && named.len() == 1 => (named[0], true),
+                    _ => continue,
+                }
+            }
+        };
+        let edge_type = format!("{:?}", edge.edge_type);
+        if !deprecated(api) || !seen.insert((caller.id.as_str(), api.id.as_str(), edge_type.clone())) {
+            continue;
+        }
+        uses.push(DeprecatedUse {
+            api: api.fqn.clone(),
+            caller: caller.fqn.clone(),
+            edge_type,
+            file: edge.file_src.clone().unwrap_or_else(|| caller.file_path.clone()),
+            line: caller.name_span.start_line + 1,
+            by_name,
+        });
+    }
+    uses.sort_by(|a, b| (&a.file, a.line, &a.api).cmp(&(&b.file, b.line, &b.api)));
+    uses
+}
+
+/// Group the uses in the last of `history` (oldest first, each snapshot's
+/// [`uses`]) by the CODEOWNERS teams of the files they are in, with each
+/// team's count per snapshot. Teams are assigned by today's CODEOWNERS
+/// throughout, so a trend follows the code rather than past ownership.
+pub fn report(
+    symbols: &[SymbolIR],
+    history: &[(String, Vec<DeprecatedUse>)],
+    owners: Option<&CodeOwners>,
+) -> DeprecationReport {
+    let teams_of = |file: &str| -> Vec<String> {
+        match owners.map(|owners| owners.owners(file)) {
+            Some(teams) if !teams.is_empty() => teams.to_vec(),
+            _ => vec![UNOWNED.to_string()],
+        }
+    };
+
+    let mut teams: BTreeMap<String, TeamDeprecations> = BTreeMap::new();
+    for (index, (_, uses)) in history.iter().enumerate() {
+        let last = index + 1 == history.len();
+        for site in uses {
+            for team in teams_of(&site.file) {
+                let entry = teams.entry(team.clone()).or_insert_with(|| TeamDeprecations {
+                    team,
+                    uses: 0,
+                    trend: vec![0; history.len()],
+                    sites: Vec::new(),
+                });
+                entry.trend[index] += 1;
+                if last {
+                    entry.uses += 1;
+                    entry.sites.push(site.clone());
+                }
+            }
+        }
+    }
+    let mut teams: Vec<TeamDeprecations> = teams.into_values().filter(|team| team.uses > 0).collect();
+    teams.sort_by(|a, b| b.uses.cmp(&a.uses).then_with(|| a.team.cmp(&b.team)));
+
+    let current = history.last().map_or(&[][..], |(_, uses)| uses.as_slice());
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for site in current {
+        *counts.entry(site.api.as_str()).or_default() += 1;
+    }
+    let mut apis: Vec<DeprecatedApi> = symbols
+        .iter()
+        .filter_map(|symbol| {
+            let uses = counts.remove(symbol.fqn.as_str())?;
+            Some(DeprecatedApi {
+                fqn: symbol.fqn.clone(),
+                file: symbol.file_path.clone(),
+                line: symbol.name_span.start_line + 1,
+                note: symbol.doc_tags.as_ref().and_then(|tags| tags.deprecated.clone()),
+                uses,
+            })
+        })
+        .collect();
+    apis.sort_by(|a, b| b.uses.cmp(&a.uses).then_with(|| a.fqn.cmp(&b.fqn)));
+
+    DeprecationReport {
+        commit: history.last().map(|(commit, _)| commit.clone()).unwrap_or_default(),
+        snapshots: history.iter().map(|(commit, _)| commit.clone()).collect(),
+        trend: history.iter().map(|(_, uses)| uses.len()).collect(),
+        deprecated: symbols.iter().filter(|s| s.flags.contains(SymbolFlags::DEPRECATED)).count(),
+        apis,
+        teams,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{DocTags, Resolution, Span, test_util};
+
+    fn symbol(fqn: &str, file: &str, deprecated: bool) -> SymbolIR {
+        SymbolIR {
+            name_span: Span { start_line: 4, start_col: 0, end_line: 4, end_col: 3 },
+            flags: if deprecated { SymbolFlags::DEPRECATED } else { SymbolFlags::empty() },
+            doc_tags: deprecated.then(|| DocTags { deprecated: Some("use fetchV2".to_string()), ..Default::default() }),
+            ..test_util::symbol(&format!("id:{}", fqn), fqn, file)
+        }
+    }
+
+    fn edge(edge_type: EdgeType, src: &str, dst: &str) -> EdgeIR {
+        EdgeIR {
+            edge_type,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_uses_and_report_by_team() {
+        let symbols = vec![
+            symbol("api.fetch", "lib/api.ts", true),
+            symbol("api.legacyParse", "lib/api.ts", true),
+            symbol("api.get", "lib/api.ts", true),
+            symbol("web.page", "web/page.ts", false),
+            symbol("web.form", "web/form.ts", false),
+            symbol("jobs.sync", "jobs/sync.ts", false),
+            symbol("other.get", "jobs/other.ts", false),
+        ];
+        let edges = vec![
+            edge(EdgeType::Calls, "id:web.page", "id:api.fetch"),
+            edge(EdgeType::Calls, "id:web.page", "id:api.fetch"),
+            edge(EdgeType::Imports, "id:web.form", "api.fetch"),
+            edge(EdgeType::Calls, "id:jobs.sync", "legacyParse"),
+            // `get` is ambiguous, and deprecated code calling deprecated code is no use
+            edge(EdgeType::Calls, "id:jobs.sync", "get"),
+            edge(EdgeType::Calls, "id:api.legacyParse", "id:api.fetch"),
+            edge(EdgeType::Contains, "id:web.page", "id:api.get"),
+        ];
+        let current = uses(&symbols, &edges);
+        let found: Vec<(&str, &str, &str, bool)> = current
+            .iter()
+            .map(|u| (u.caller.as_str(), u.api.as_str(), u.edge_type.as_str(), u.by_name))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("jobs.sync", "api.legacyParse", "Calls", true),
+                ("web.form", "api.fetch", "Imports", false),
+                ("web.page", "api.fetch", "Calls", false),
+            ]
+        );
+        assert_eq!(current[0].line, 5);
+
+        let owners = CodeOwners::parse("/web/ @acme/web\n/lib/ @acme/platform\n");
+        let older = vec![current[1].clone(), current[2].clone(), current[2].clone(), current[0].clone()];
+        let history = vec![("c1".to_string(), older), ("c2".to_string(), current)];
+        let report = report(&symbols, &history, Some(&owners));
+
+        let teams: Vec<(&str, usize, Vec<usize>)> =
+            report.teams.iter().map(|t| (t.team.as_str(), t.uses, t.trend.clone())).collect();
+        assert_eq!(teams, [("@acme/web", 2, vec![3, 2]), ("(unowned)", 1, vec![1, 1])]);
+        assert_eq!(report.trend, [4, 3]);
+        assert_eq!(report.deprecated, 3);
+        let apis: Vec<(&str, usize)> = report.apis.iter().map(|a| (a.fqn.as_str(), a.uses)).collect();
+        assert_eq!(apis, [("api.fetch", 2), ("api.legacyParse", 1)]);
+
+        let text = report.to_text(10);
+        assert!(text.starts_with("3 uses of 2 of 3 deprecated APIs in c2 (4 -> 3 over 2 snapshots)"));
+        assert!(text.contains("  @acme/web 2 uses (3 -> 2)\n    api.fetch in web.form (web/form.ts:5)"));
+        assert!(text.contains("     2  api.fetch (lib/api.ts:5): use fetchV2"));
+    }
+}
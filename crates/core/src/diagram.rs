@@ -0,0 +1,141 @@
+use clap::ValueEnum;
+use std::collections::HashMap;
+use store::{AggregateGraph, Granularity};
+
+/// What `query deps` groups symbols by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    File,
+    /// Directory, cut to `--depth` components
+    Dir,
+    /// Package, namespace or module
+    Package,
+}
+
+impl GroupBy {
+    pub fn granularity(self, depth: Option<usize>) -> Granularity {
+        match self {
+            GroupBy::File => Granularity::File,
+            GroupBy::Dir => Granularity::Directory { depth },
+            GroupBy::Package => Granularity::Package,
+        }
+    }
+}
+
+/// How `query deps` draws the aggregated graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiagramFormat {
+    /// Dependency report: each group and the groups it uses
+    Text,
+    /// Graphviz, e.g. `| dot -Tsvg > deps.svg`
+    Dot,
+    /// Mermaid flowchart for Markdown
+    Mermaid,
+}
+
+impl DiagramFormat {
+    pub fn render(self, graph: &AggregateGraph) -> String {
+        match self {
+            DiagramFormat::Text => to_text(graph),
+            DiagramFormat::Dot => to_dot(graph),
+            DiagramFormat::Mermaid => to_mermaid(graph),
+        }
+    }
+}
+
+/// Each group with its outgoing dependencies, heaviest first
+fn to_text(graph: &AggregateGraph) -> String {
+    let mut out = String::new();
+    for node in &graph.nodes {
+        out.push_str(&format!(
+            "{} ({} symbols, {} internal references)\n",
+            node.id, node.symbols, node.internal_references
+        ));
+        for edge in graph.edges.iter().filter(|edge| edge.from == node.id) {
+            let types: Vec<String> = edge.by_type.iter().map(|(t, n)| format!("{} {}", t, n)).collect();
+            out.push_str(&format!("  -> {} {} ({})\n", edge.to, edge.weight, types.join(", ")));
+        }
+    }
+    out
+}
+
+fn to_dot(graph: &AggregateGraph) -> String {
+    let heaviest = graph.edges.first().map_or(1, |edge| edge.weight) as f64;
+    let mut out = String::from("digraph deps {\n  rankdir=LR;\n  node [shape=box];\n");
+    for node in &graph.nodes {
+        out.push_str(&format!(
+            "  {} [label={}];\n",
+            dot_string(&node.id),
+            dot_string(&format!("{}\\n{} symbols", node.id, node.symbols))
+        ));
+    }
+    for edge in &graph.edges {
+        // Line width follows the weight, from 1 to 5
+        let width = 1.0 + 4.0 * edge.weight as f64 / heaviest;
+        out.push_str(&format!(
+            "  {} -> {} [label=\"{}\", penwidth={:.1}];\n",
+            dot_string(&edge.from),
+            dot_string(&edge.to),
+            edge.weight,
+            width
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Quoted DOT id; `\n` in `s` is kept as DOT's line break
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\\\""))
+}
+
+fn to_mermaid(graph: &AggregateGraph) -> String {
+    // Group names aren't valid Mermaid ids, so nodes are numbered
+    let ids: HashMap<&str, String> =
+        graph.nodes.iter().enumerate().map(|(i, node)| (node.id.as_str(), format!("n{}", i))).collect();
+    let mut out = String::from("flowchart LR\n");
+    for node in &graph.nodes {
+        out.push_str(&format!("  {}[\"{}\"]\n", ids[node.id.as_str()], node.id.replace('"', "#quot;")));
+    }
+    for edge in &graph.edges {
+        let (Some(from), Some(to)) = (ids.get(edge.from.as_str()), ids.get(edge.to.as_str())) else {
+            continue;
+        };
+        out.push_str(&format!("  {} -->|{}| {}\n", from, edge.weight, to));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use store::{AggregateEdge, AggregateNode};
+
+    #[test]
+    fn test_render_formats() {
+        let node = |id: &str, symbols| AggregateNode { id: id.to_string(), symbols, internal_references: 2 };
+        let graph = AggregateGraph {
+            nodes: vec![node("src/app", 4), node("src/\"db\"", 2)],
+            edges: vec![AggregateEdge {
+                from: "src/app".to_string(),
+                to: "src/\"db\"".to_string(),
+                weight: 3,
+                by_type: BTreeMap::from([("Calls".to_string(), 2), ("Imports".to_string(), 1)]),
+            }],
+        };
+
+        assert_eq!(
+            to_text(&graph),
+            "src/app (4 symbols, 2 internal references)\n  -> src/\"db\" 3 (Calls 2, Imports 1)\n\
+             src/\"db\" (2 symbols, 2 internal references)\n"
+        );
+        let dot = to_dot(&graph);
+        assert!(dot.contains("  \"src/app\" -> \"src/\\\"db\\\"\" [label=\"3\", penwidth=5.0];\n"));
+        assert!(dot.contains("[label=\"src/app\\n4 symbols\"]"));
+        assert_eq!(
+            to_mermaid(&graph),
+            "flowchart LR\n  n0[\"src/app\"]\n  n1[\"src/#quot;db#quot;\"]\n  n0 -->|3| n1\n"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use anyhow::{anyhow, Result};
+use protocol::path::normalize_path;
+use protocol::SymbolIR;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Lines a change touches, per file, as 1-based inclusive ranges in the new
+/// version of the file. Deleted lines are recorded at the line they were
+/// removed before, so a pure deletion still touches its enclosing symbol.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedLines {
+    files: BTreeMap<String, Vec<(u32, u32)>>,
+}
+
+impl ChangedLines {
+    /// Read a unified diff (`git diff`, `diff -u`); context lines are ignored
+    pub fn parse(diff: &str) -> Self {
+        let mut changes = Self::default();
+        let mut file: Option<String> = None;
+        // New-side lines of the current hunk not seen yet; while any are left,
+        // `+++ ` starts an added line (`++ x`), not a file header
+        let mut remaining = 0u32;
+        for line in diff.lines() {
+            if line.starts_with("diff ") {
+                remaining = 0;
+            } else if remaining > 0 && !line.starts_with("@@ ") {
+                if line.starts_with(['+', ' ']) || line.is_empty() {
+                    remaining -= 1;
+                }
+            } else if let Some(path) = line.strip_prefix("+++ ") {
+                let path = path.split('\t').next().unwrap_or(path).trim();
+                file = match path {
+                    "/dev/null" => None,
+                    _ => Some(normalize_path(path.strip_prefix("b/").unwrap_or(path))),
+                };
+                if let Some(file) = &file {
+                    changes.files.entry(file.clone()).or_default();
+                }
+            } else if let Some(hunk) = line.strip_prefix("@@ ") {
+                let Some((start, count)) = parse_new_range(hunk) else {
+                    continue;
+                };
+                remaining = count;
+                let Some(file) = &file else {
+                    continue;
+                };
+                let end = start + count.saturating_sub(1);
+                changes.files.entry(file.clone()).or_default().push((start.max(1), end.max(1)));
+            }
+        }
+        changes
+    }
+
+    /// Changes between two commits of the repository at `repo_root`
+    pub fn from_git(repo_root: &Path, base: &str, head: &str) -> Result<Self> {
+        let output = std::process::Command::new("git")
+            .args(["diff", "--unified=0", "--no-color", "--no-ext-diff", base, head])
+            .current_dir(repo_root)
+            .output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "git diff {} {} failed: {}",
+                base,
+                head,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(Self::parse(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Read a diff file, or stdin for `-`
+    pub fn read(path: &Path) -> Result<Self> {
+        let diff = if path.as_os_str() == "-" {
+            std::io::read_to_string(std::io::stdin())?
+        } else {
+            std::fs::read_to_string(path)?
+        };
+        Ok(Self::parse(&diff))
+    }
+
+    /// Files added or modified by the change
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    /// Whether the change touches `symbol`'s declaration or body
+    pub fn touches(&self, symbol: &SymbolIR) -> bool {
+        let Some(ranges) = self.files.get(&normalize_path(&symbol.file_path)) else {
+            return false;
+        };
+        // Spans are 0-based
+        let (first, last) = (symbol.full_span.start_line + 1, symbol.full_span.end_line + 1);
+        ranges.iter().any(|&(start, end)| start <= last && first <= end)
+    }
+}
+
+/// `+start,count` of a hunk header (after the leading `@@ `); a missing
+/// count means one line
+fn parse_new_range(hunk: &str) -> Option<(u32, u32)> {
+    let range = hunk.split_whitespace().find_map(|part| part.strip_prefix('+'))?;
+    let (start, count) = match range.split_once(',') {
+        Some((start, count)) => (start.parse().ok()?, count.parse().ok()?),
+        None => (range.parse().ok()?, 1),
+    };
+    Some((start, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Language, Span, test_util};
+
+    fn symbol(file: &str, start_line: u32, end_line: u32) -> SymbolIR {
+        SymbolIR {
+            lang: Language::Go,
+            name_span: Span { start_line, start_col: 0, end_line: start_line, end_col: 1 },
+            full_span: Span { start_line, start_col: 0, end_line, end_col: 1 },
+            ..test_util::symbol("s", "s", file)
+        }
+    }
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let diff = "\
+diff --git a/pkg/server.go b/pkg/server.go
+--- a/pkg/server.go
++++ b/pkg/server.go
+@@ -10,2 +10,3 @@ func Serve() {
+@@ -40 +41,0 @@ func stop() {
+diff --git a/old.go b/old.go
+--- a/old.go
++++ /dev/null
+@@ -1,3 +0,0 @@
+";
+        let changes = ChangedLines::parse(diff);
+        assert_eq!(changes.files().collect::<Vec<_>>(), ["pkg/server.go"]);
+
+        // Lines 10-12 changed, a line was deleted before line 41
+        assert!(changes.touches(&symbol("pkg/server.go", 8, 11)));
+        assert!(changes.touches(&symbol("pkg/server.go", 39, 45)));
+        assert!(!changes.touches(&symbol("pkg/server.go", 20, 30)));
+        assert!(!changes.touches(&symbol("old.go", 0, 2)));
+    }
+
+    #[test]
+    fn test_added_line_like_a_file_header() {
+        // Adds `++ counter` and `--- note`, removes `-- note`
+        let diff = "\
+--- a/src/count.c
++++ b/src/count.c
+@@ -3,2 +3,3 @@
+ int next(void) {
++++ counter;
+--- note
++--- note
+\\ No newline at end of file
+--- a/src/other.c
++++ b/src/other.c
+@@ -9 +9 @@
+-old
++new
+";
+        let changes = ChangedLines::parse(diff);
+        assert_eq!(changes.files().collect::<Vec<_>>(), ["src/count.c", "src/other.c"]);
+        assert!(changes.touches(&symbol("src/count.c", 2, 2)));
+        assert!(changes.touches(&symbol("src/other.c", 8, 8)));
+    }
+}
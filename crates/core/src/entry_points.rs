@@ -0,0 +1,321 @@
+use clap::ValueEnum;
+use protocol::{EdgeIR, EdgeType, Language, SymbolFlags, SymbolIR, SymbolKind};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// How the program is entered through a symbol; declared in order of
+/// precedence for symbols that are several at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    /// `main` of a program
+    Main,
+    /// Test function or method run by a test runner
+    Test,
+    /// Request handler registered with a web framework
+    Http,
+    /// Command or subcommand of a CLI framework
+    Cli,
+    /// Public API of a library
+    Export,
+}
+
+impl EntryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EntryKind::Main => "main",
+            EntryKind::Test => "test",
+            EntryKind::Http => "http",
+            EntryKind::Cli => "cli",
+            EntryKind::Export => "export",
+        }
+    }
+}
+
+/// Decorators, annotations and attributes marking test cases:
+/// pytest/JUnit/Rust/xUnit/NUnit/MSTest
+const TEST_MARKERS: &[&str] = &[
+    "Test", "ParameterizedTest", "RepeatedTest", "TestFactory", "test", "rstest", "Fact", "Theory", "TestMethod",
+    "TestCase",
+];
+
+/// Route decorators of Flask/FastAPI/Django REST, Spring/JAX-RS, NestJS,
+/// actix/rocket/axum and ASP.NET
+const HTTP_MARKERS: &[&str] = &[
+    "route", "get", "post", "put", "delete", "patch", "api_route", "websocket", "api_view", "GetMapping",
+    "PostMapping", "PutMapping", "DeleteMapping", "PatchMapping", "RequestMapping", "GET", "POST", "PUT", "DELETE",
+    "PATCH", "Get", "Post", "Put", "Delete", "Patch", "handler", "debug_handler", "HttpGet", "HttpPost", "HttpPut",
+    "HttpDelete", "HttpPatch", "Route",
+];
+
+/// Handler parameter types of net/http, gin, echo and fiber
+const GO_HANDLER_TYPES: &[&str] = &["http.ResponseWriter", "gin.Context", "echo.Context", "fiber.Ctx"];
+
+/// Commands of click/typer, picocli and clap
+const CLI_MARKERS: &[&str] = &["command", "group", "Command", "Parser", "Subcommand"];
+
+/// Entry points among `symbols` as `(symbol id, kind)`. Handlers and
+/// commands are recognised by the decorators, annotations or attributes
+/// above their declaration, so sources are read through `read`.
+pub fn detect(
+    symbols: &[SymbolIR],
+    edges: &[EdgeIR],
+    read: impl Fn(&str) -> Option<String>,
+) -> Vec<(String, EntryKind)> {
+    let exported: HashSet<&str> = edges
+        .iter()
+        .filter(|e| e.edge_type == EdgeType::Exports)
+        .filter_map(|e| e.dst.as_deref())
+        .collect();
+    let mut by_file: BTreeMap<&str, Vec<&SymbolIR>> = BTreeMap::new();
+    for symbol in symbols {
+        by_file.entry(symbol.file_path.as_str()).or_default().push(symbol);
+    }
+
+    let mut entry_points = Vec::new();
+    for (file, symbols) in by_file {
+        let source = read(file).unwrap_or_default();
+        let lines: Vec<&str> = source.lines().collect();
+        // A program's own items aren't library API
+        let is_program = symbols.iter().any(|s| is_main(s, &lines));
+        for symbol in symbols {
+            let markers = markers(&lines, symbol);
+            let kind = if is_main(symbol, &lines) {
+                Some(EntryKind::Main)
+            } else if is_test(symbol, &markers) {
+                Some(EntryKind::Test)
+            } else if is_http_handler(symbol, &markers) {
+                Some(EntryKind::Http)
+            } else if is_command(symbol, &markers) {
+                Some(EntryKind::Cli)
+            } else if !is_program && is_exported(symbol, &exported) {
+                Some(EntryKind::Export)
+            } else {
+                None
+            };
+            if let Some(kind) = kind {
+                entry_points.push((symbol.id.clone(), kind));
+            }
+        }
+    }
+    entry_points
+}
+
+fn is_callable(symbol: &SymbolIR) -> bool {
+    matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method)
+}
+
+/// `main`, which Java and C# only call when it's static
+fn is_main(symbol: &SymbolIR, lines: &[&str]) -> bool {
+    if !is_callable(symbol) {
+        return false;
+    }
+    match symbol.lang {
+        Language::Java | Language::CSharp => {
+            matches!(symbol.name.as_str(), "main" | "Main")
+                && declaration(lines, symbol).split_whitespace().any(|word| word == "static")
+        }
+        Language::Go => symbol.name == "main" && symbol.fqn == "main.main",
+        _ => symbol.name == "main",
+    }
+}
+
+fn is_test(symbol: &SymbolIR, markers: &[String]) -> bool {
+    if !is_callable(symbol) {
+        return false;
+    }
+    if markers.iter().any(|m| TEST_MARKERS.contains(&last_segment(m))) {
+        return true;
+    }
+    // pytest, go test and gtest-style files go by naming alone
+    symbol.flags.contains(SymbolFlags::TEST_ONLY)
+        && ["test", "Test", "Benchmark", "Fuzz", "Example"].iter().any(|prefix| symbol.name.starts_with(prefix))
+}
+
+fn is_http_handler(symbol: &SymbolIR, markers: &[String]) -> bool {
+    if !is_callable(symbol) {
+        return false;
+    }
+    if symbol.lang == Language::Go {
+        return symbol.parameters.iter().any(|p| {
+            p.type_name.as_deref().is_some_and(|t| GO_HANDLER_TYPES.iter().any(|h| t.ends_with(h)))
+        });
+    }
+    markers.iter().any(|m| HTTP_MARKERS.contains(&last_segment(m)))
+}
+
+fn is_command(symbol: &SymbolIR, markers: &[String]) -> bool {
+    let is_type = matches!(symbol.kind, SymbolKind::Class | SymbolKind::Struct | SymbolKind::Enum);
+    (is_callable(symbol) || is_type) && markers.iter().any(|m| CLI_MARKERS.contains(&last_segment(m)))
+}
+
+/// Public API by each language's notion of it; TypeScript and JavaScript
+/// modules say what they export
+fn is_exported(symbol: &SymbolIR, exported: &HashSet<&str>) -> bool {
+    let is_api = matches!(
+        symbol.kind,
+        SymbolKind::Function
+            | SymbolKind::Method
+            | SymbolKind::Class
+            | SymbolKind::Interface
+            | SymbolKind::Struct
+            | SymbolKind::Enum
+            | SymbolKind::Trait
+            | SymbolKind::TypeAlias
+    );
+    if !is_api || symbol.flags.contains(SymbolFlags::TEST_ONLY) {
+        return false;
+    }
+    match symbol.lang {
+        Language::TypeScript | Language::JavaScript => exported.contains(symbol.id.as_str()),
+        Language::Go => symbol.name.starts_with(|c: char| c.is_ascii_uppercase()),
+        Language::Python => !symbol.name.starts_with('_'),
+        _ => symbol.visibility.as_deref() == Some("public"),
+    }
+}
+
+/// Source from the start of the declaration through its name
+fn declaration(lines: &[&str], symbol: &SymbolIR) -> String {
+    let (start, end) = (symbol.full_span.start_line as usize, symbol.name_span.start_line as usize);
+    lines.get(start..=end.max(start)).map(|lines| lines.join("\n")).unwrap_or_default()
+}
+
+/// Names of the decorators, annotations and attributes on a declaration:
+/// `@app.route`, `#[actix_web::get]`, `[HttpGet]`, and each trait of a
+/// `#[derive(...)]`. Python decorators and Rust attributes sit above the
+/// declaration's span, so the lines right above it are taken in too.
+fn markers(lines: &[&str], symbol: &SymbolIR) -> Vec<String> {
+    let mut start = symbol.full_span.start_line as usize;
+    while start > 0 && start <= lines.len() {
+        let line = lines[start - 1].trim_start();
+        if !(line.starts_with('@') || line.starts_with("#[") || line.starts_with('[')) {
+            break;
+        }
+        start -= 1;
+    }
+    let end = (symbol.name_span.start_line as usize).max(symbol.full_span.start_line as usize);
+    let Some(lines) = lines.get(start..=end) else {
+        return Vec::new();
+    };
+
+    let mut markers = Vec::new();
+    let name_end = |s: &str| s.find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | ':'))).unwrap_or(s.len());
+    for line in lines {
+        let line = line.trim();
+        if let Some(attribute) = line.strip_prefix("#[") {
+            let name = &attribute[..name_end(attribute)];
+            if name == "derive" {
+                let traits = attribute.trim_start_matches("derive(").split(')').next().unwrap_or("");
+                markers.extend(traits.split(',').map(|t| t.trim().to_string()));
+            } else {
+                markers.push(name.to_string());
+            }
+        } else if let Some(attributes) = line.strip_prefix('[') {
+            // `[HttpGet, Route("x")]`, cut at the closing bracket
+            for attribute in attributes.split(',') {
+                let attribute = attribute.trim();
+                markers.push(attribute[..name_end(attribute)].to_string());
+            }
+        } else {
+            // Annotations may share a line with the modifiers that follow
+            for word in line.split_whitespace().filter_map(|word| word.strip_prefix('@')) {
+                markers.push(word[..name_end(word)].to_string());
+            }
+        }
+    }
+    markers.retain(|m| !m.is_empty());
+    markers
+}
+
+/// `get` of `actix_web::get`, `route` of `app.route`
+fn last_segment(name: &str) -> &str {
+    name.rsplit(['.', ':']).next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Parameter, Resolution, test_util};
+    use std::collections::HashMap;
+
+    fn symbol(id: &str, lang: Language, kind: SymbolKind, file: &str, line: u32) -> SymbolIR {
+        SymbolIR {
+            lang,
+            kind,
+            name: id.to_string(),
+            name_span: test_util::lines(line, line + 1),
+            full_span: test_util::lines(line, line + 1),
+            visibility: Some("public".to_string()),
+            ..test_util::symbol(id, id, file)
+        }
+    }
+
+    #[test]
+    fn test_detect_entry_points() {
+        let sources: HashMap<&str, &str> = HashMap::from([
+            (
+                "app/views.py",
+                "import flask\n\n@app.route(\"/users\")\n@login_required\ndef users():\n    pass\n\n\
+                 @click.command()\ndef seed():\n    pass\n\ndef _helper():\n    pass\n",
+            ),
+            (
+                "src/Api.java",
+                "class Api {\n  @GetMapping(\"/x\") public String x() {}\n  public static void main(String[] a) {}\n\
+                 \x20 @Test void checksX() {}\n}\n",
+            ),
+            ("src/main.rs", "#[derive(Debug, Parser)]\nstruct Cli {}\n\npub fn run() {}\n\nfn main() {}\n"),
+        ]);
+        let mut go_handler = symbol("Serve", Language::Go, SymbolKind::Function, "server.go", 0);
+        go_handler.parameters = vec![Parameter {
+            name: "w".to_string(),
+            type_name: Some("http.ResponseWriter".to_string()),
+            default: None,
+            variadic: false,
+        }];
+        let mut ts_private = symbol("internal", Language::TypeScript, SymbolKind::Function, "lib.ts", 0);
+        ts_private.visibility = None;
+        let symbols = vec![
+            symbol("users", Language::Python, SymbolKind::Function, "app/views.py", 4),
+            symbol("seed", Language::Python, SymbolKind::Function, "app/views.py", 8),
+            symbol("_helper", Language::Python, SymbolKind::Function, "app/views.py", 11),
+            symbol("x", Language::Java, SymbolKind::Method, "src/Api.java", 1),
+            symbol("main", Language::Java, SymbolKind::Method, "src/Api.java", 2),
+            symbol("checksX", Language::Java, SymbolKind::Method, "src/Api.java", 3),
+            symbol("Cli", Language::Rust, SymbolKind::Struct, "src/main.rs", 1),
+            symbol("run", Language::Rust, SymbolKind::Function, "src/main.rs", 3),
+            symbol("main", Language::Rust, SymbolKind::Function, "src/main.rs", 5),
+            go_handler,
+            symbol("parse", Language::TypeScript, SymbolKind::Function, "lib.ts", 0),
+            ts_private,
+        ];
+        let edges = vec![EdgeIR {
+            edge_type: EdgeType::Exports,
+            src: Some("lib.ts".to_string()),
+            dst: Some("parse".to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: Default::default(),
+            provenance: Default::default(),
+        }];
+
+        let found = detect(&symbols, &edges, |file| sources.get(file).map(|s| s.to_string()));
+        let mut found: Vec<(String, &str)> = found.into_iter().map(|(id, kind)| (id, kind.as_str())).collect();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                ("Cli", "cli"),
+                ("Serve", "http"),
+                ("checksX", "test"),
+                ("main", "main"),
+                ("main", "main"),
+                ("parse", "export"),
+                ("seed", "cli"),
+                ("users", "http"),
+                ("x", "http"),
+            ]
+            .map(|(id, kind)| (id.to_string(), kind))
+        );
+    }
+}
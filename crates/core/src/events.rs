@@ -0,0 +1,607 @@
+use protocol::{EdgeIR, EdgeType, Resolution, SymbolIR, SymbolKind};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Calls that send an event or message named by their first argument:
+/// Node's EventEmitter, pub-sub clients, NATS/Redis, Go event buses
+const PUBLISH_METHODS: &[&str] = &["emit", "publish", "dispatch", "trigger", "fire", "broadcast", "produce"];
+
+/// Calls that register a handler, usually their second argument, for the
+/// event named by their first
+const SUBSCRIBE_METHODS: &[&str] = &["on", "once", "addListener", "addEventListener", "subscribe", "consume"];
+
+/// Decorators and annotations making the symbol below them a handler:
+/// NestJS, Spring Kafka/AMQP/JMS/SQS
+const SUBSCRIBE_DECORATORS: &[&str] = &[
+    "OnEvent", "EventPattern", "MessagePattern", "Subscribe", "KafkaListener", "RabbitListener", "JmsListener",
+    "SqsListener",
+];
+
+/// Prefix of the node naming a topic in `PublishesTo`/`SubscribesTo` edges
+pub const TOPIC_PREFIX: &str = "event:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Publish,
+    Subscribe,
+}
+
+/// A publish or subscribe call as written
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EventCall {
+    direction: Direction,
+    method: String,
+    /// 0-based
+    line: u32,
+    decorator: bool,
+    /// First argument, as written
+    topic: String,
+    /// Second argument of a subscription, as written
+    handler: Option<String>,
+}
+
+/// The publish and subscribe calls of one file and the string constants
+/// it defines, gathered while the file is indexed so no source is read twice
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileEvents {
+    calls: Vec<EventCall>,
+    constants: Vec<(String, String)>,
+}
+
+impl FileEvents {
+    pub fn scan(text: &str) -> Self {
+        Self { calls: event_calls(text), constants: string_constants(text) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty() && self.constants.is_empty()
+    }
+}
+
+/// `PublishesTo` and `SubscribesTo` edges from the symbols making the
+/// publish and subscribe calls of `files` to their topics, so a publisher
+/// and a subscriber of `user.created` meet at `event:user.created`. Topics
+/// are string literals or constants holding one (`emit(Events.CREATED)`),
+/// defined in any of `files` or named by one of the snapshot's `kept` event
+/// edges, which covers files a partial scan didn't re-read. A
+/// subscription's handler is its second argument when that names a
+/// function, else the symbol making it; calls outside any symbol with no
+/// named handler are left out.
+pub fn detect(symbols: &[SymbolIR], files: &[(String, FileEvents)], kept: &[EdgeIR]) -> Vec<EdgeIR> {
+    let mut by_file: HashMap<&str, Vec<&SymbolIR>> = HashMap::new();
+    for symbol in symbols {
+        by_file.entry(symbol.file_path.as_str()).or_default().push(symbol);
+    }
+
+    // A constant counts only while every assignment agrees on its value
+    let mut constants: HashMap<String, Option<String>> = HashMap::new();
+    let mut define = |name: &str, value: &str| {
+        let entry = constants.entry(name.to_string()).or_insert_with(|| Some(value.to_string()));
+        if entry.as_deref() != Some(value) {
+            *entry = None;
+        }
+    };
+    for (_, events) in files {
+        for (name, value) in &events.constants {
+            define(name, value);
+        }
+    }
+    let rescanned: HashSet<&str> = files.iter().map(|(file, _)| file.as_str()).collect();
+    for edge in kept {
+        if edge.file_src.as_deref().is_some_and(|file| rescanned.contains(file)) {
+            continue;
+        }
+        if let (Some(name), Some(value)) = (
+            edge.meta.get("constant").and_then(|name| name.as_str()),
+            edge.meta.get("topic").and_then(|topic| topic.as_str()),
+        ) {
+            define(name, value);
+        }
+    }
+    let mut functions_by_name: HashMap<&str, Vec<&SymbolIR>> = HashMap::new();
+    for symbol in symbols.iter().filter(|s| is_function(s)) {
+        functions_by_name.entry(symbol.name.as_str()).or_default().push(symbol);
+    }
+
+    let mut edges = Vec::new();
+    for (file, events) in files {
+        let in_file = by_file.get(file.as_str()).map_or(&[][..], Vec::as_slice);
+        for call in &events.calls {
+            let Some((topic, constant)) = topic_of(&call.topic, &constants) else {
+                continue;
+            };
+            let handler = call.handler.as_deref().and_then(|handler| {
+                let name = last_segment(handler);
+                let named = functions_by_name.get(name)?;
+                named.iter().find(|s| s.file_path == *file).or((named.len() == 1).then(|| &named[0])).copied()
+            });
+            let symbol = if call.decorator {
+                declared_below(in_file, call.line)
+            } else {
+                handler.filter(|_| call.direction == Direction::Subscribe).or_else(|| enclosing(in_file, call.line))
+            };
+            let Some(symbol) = symbol else {
+                continue;
+            };
+
+            let mut meta = HashMap::new();
+            meta.insert("topic".to_string(), serde_json::json!(topic));
+            meta.insert("method".to_string(), serde_json::json!(call.method));
+            meta.insert("line".to_string(), serde_json::json!(call.line + 1));
+            if let Some(constant) = constant {
+                meta.insert("constant".to_string(), serde_json::json!(constant));
+            }
+            edges.push(EdgeIR {
+                edge_type: match call.direction {
+                    Direction::Publish => EdgeType::PublishesTo,
+                    Direction::Subscribe => EdgeType::SubscribesTo,
+                },
+                src: Some(symbol.id.clone()),
+                dst: Some(format!("{}{}", TOPIC_PREFIX, topic)),
+                file_src: Some(file.clone()),
+                file_dst: None,
+                resolution: Resolution::Heuristic,
+                meta,
+                provenance: HashMap::new(),
+            });
+        }
+    }
+    edges
+}
+
+fn is_function(symbol: &SymbolIR) -> bool {
+    matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method)
+}
+
+fn contains(symbol: &SymbolIR, line: u32) -> bool {
+    symbol.full_span.start_line <= line && line <= symbol.full_span.end_line
+}
+
+/// The innermost function or method around `line`, else the innermost type
+fn enclosing<'a>(symbols: &[&'a SymbolIR], line: u32) -> Option<&'a SymbolIR> {
+    let innermost = |wanted: fn(&SymbolIR) -> bool| {
+        symbols
+            .iter()
+            .filter(|s| wanted(s) && contains(s, line))
+            .min_by_key(|s| s.full_span.end_line - s.full_span.start_line)
+            .copied()
+    };
+    innermost(is_function).or_else(|| {
+        innermost(|s| matches!(s.kind, SymbolKind::Class | SymbolKind::Struct | SymbolKind::Interface))
+    })
+}
+
+/// The function or method a decorator on `line` belongs to: the first one
+/// declared within a few lines below it
+fn declared_below<'a>(symbols: &[&'a SymbolIR], line: u32) -> Option<&'a SymbolIR> {
+    symbols
+        .iter()
+        .filter(|s| is_function(s) && (line..=line + 5).contains(&s.name_span.start_line))
+        .min_by_key(|s| s.name_span.start_line)
+        .copied()
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit(['.', ':']).next().unwrap_or(path)
+}
+
+/// The topic an argument names: a string literal, a constant holding one,
+/// or for `topics = "orders"` annotation arguments, the value; with the
+/// constant's name when it came from one
+fn topic_of(argument: &str, constants: &HashMap<String, Option<String>>) -> Option<(String, Option<String>)> {
+    let mut argument = argument.trim();
+    if let Some((key, value)) = argument.split_once('=') {
+        if key.trim().chars().all(|c| c.is_alphanumeric() || c == '_') {
+            argument = value.trim();
+        }
+    }
+    // The first of a list, `topics = {"a", "b"}`
+    if let Some(list) = argument.strip_prefix(['{', '[']) {
+        argument = list.split(',').next().unwrap_or(list).trim().trim_end_matches(['}', ']']).trim();
+    }
+    if let Some(literal) = string_literal(argument) {
+        return (!literal.is_empty()).then(|| (literal.to_string(), None));
+    }
+    if !argument.is_empty() && argument.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | ':')) {
+        let name = last_segment(argument);
+        let value = constants.get(name).cloned().flatten()?;
+        return Some((value, Some(name.to_string())));
+    }
+    None
+}
+
+/// The contents of a quoted literal without interpolation
+fn string_literal(text: &str) -> Option<&str> {
+    let quote = text.chars().next().filter(|c| matches!(c, '"' | '\'' | '`'))?;
+    let inner = text.strip_prefix(quote)?.strip_suffix(quote)?;
+    (!inner.contains(quote) && !inner.contains("${") && !inner.contains('\\')).then_some(inner)
+}
+
+/// `NAME = "value"` assignments of a string literal, whatever declares
+/// them: `const`, `static final String`, enum members, Go and Python
+/// constants; and `NAME: "value"` object properties
+fn string_constants(text: &str) -> Vec<(String, String)> {
+    let mut constants = Vec::new();
+    for line in text.lines() {
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim();
+            let value = value.trim().trim_end_matches(',').trim_end();
+            if let Some(value) = string_literal(value).filter(|value| !value.is_empty()) {
+                if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    constants.push((name.to_string(), value.to_string()));
+                    continue;
+                }
+            }
+        }
+        let Some((left, right)) = line.split_once('=') else {
+            continue;
+        };
+        if right.starts_with('=') || left.ends_with(['!', '<', '>', '=']) {
+            continue;
+        }
+        let value = right.trim().trim_end_matches([';', ',']).trim_end();
+        let Some(value) = string_literal(value) else {
+            continue;
+        };
+        // `const NAME: string = ...`
+        let left = left.split(':').next().unwrap_or(left);
+        let Some(name) = left.split_whitespace().last() else {
+            continue;
+        };
+        if !value.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            constants.push((name.to_string(), value.to_string()));
+        }
+    }
+    constants
+}
+
+/// Publish and subscribe calls and subscribing decorators in `text`, with
+/// commented-out lines skipped
+fn event_calls(text: &str) -> Vec<EventCall> {
+    let bytes = text.as_bytes();
+    let mut line_starts = vec![0];
+    line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+
+    let mut calls = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if !is_ident_byte(bytes[i]) || (i > 0 && is_ident_byte(bytes[i - 1])) {
+            i += 1;
+            continue;
+        }
+        let end = (i..bytes.len()).find(|&j| !is_ident_byte(bytes[j])).unwrap_or(bytes.len());
+        let word = &text[i..end];
+        let start = i;
+        i = end;
+        if bytes.get(end) != Some(&b'(') {
+            continue;
+        }
+        let decorator = start > 0 && bytes[start - 1] == b'@';
+        let direction = if decorator {
+            SUBSCRIBE_DECORATORS.contains(&word).then_some(Direction::Subscribe)
+        } else if matches_method(PUBLISH_METHODS, word) {
+            Some(Direction::Publish)
+        } else if matches_method(SUBSCRIBE_METHODS, word) {
+            Some(Direction::Subscribe)
+        } else {
+            None
+        };
+        let Some(direction) = direction else {
+            continue;
+        };
+        let line = line_of(start);
+        let line_text = text[line_starts[line]..].lines().next().unwrap_or("").trim_start();
+        if line_text.starts_with("//") || line_text.starts_with('#') || line_text.starts_with('*') {
+            continue;
+        }
+        let arguments = arguments(&text[end + 1..]);
+        let Some(topic) = arguments.first() else {
+            continue;
+        };
+        calls.push(EventCall {
+            direction,
+            method: word.to_string(),
+            line: line as u32,
+            decorator,
+            topic: topic.to_string(),
+            handler: arguments.get(1).map(|handler| handler.to_string()),
+        });
+    }
+    calls
+}
+
+/// Go exports by capitalizing, so `Publish` is `publish`
+fn matches_method(methods: &[&str], word: &str) -> bool {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    let lowered = format!("{}{}", first.to_ascii_lowercase(), chars.as_str());
+    methods.contains(&word) || methods.contains(&lowered.as_str())
+}
+
+fn is_ident_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$'
+}
+
+/// The first two top-level arguments of a call, `rest` starting after its
+/// opening parenthesis; a call still open at the end of the text has none
+fn arguments(rest: &str) -> Vec<&str> {
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in rest.char_indices() {
+        if let Some(open) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == open {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth > 0 => depth -= 1,
+            ',' | ')' if depth == 0 => {
+                let argument = rest[start..i].trim();
+                if !argument.is_empty() {
+                    arguments.push(argument);
+                }
+                if c == ')' || arguments.len() == 2 {
+                    return arguments;
+                }
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    Vec::new()
+}
+
+/// A symbol at one end of a topic
+#[derive(Debug, Clone, Serialize)]
+pub struct Endpoint {
+    pub fqn: String,
+    pub file: String,
+    /// 1-based line of the publish or subscribe call
+    pub line: u32,
+    /// The call or decorator, e.g. `emit` or `KafkaListener`
+    pub via: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventFlow {
+    pub topic: String,
+    pub publishers: Vec<Endpoint>,
+    pub subscribers: Vec<Endpoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventFlowReport {
+    pub commit: String,
+    /// By topic
+    pub topics: Vec<EventFlow>,
+}
+
+impl EventFlowReport {
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{} event topics in {}\n", self.topics.len(), self.commit);
+        for flow in &self.topics {
+            out.push_str(&format!("  {}\n", flow.topic));
+            for (arrow, endpoints, none) in
+                [("->", &flow.publishers, "no publishers"), ("<-", &flow.subscribers, "no subscribers")]
+            {
+                if endpoints.is_empty() {
+                    out.push_str(&format!("    {} ({})\n", arrow, none));
+                }
+                for endpoint in endpoints {
+                    out.push_str(&format!(
+                        "    {} {} via {} ({}:{})\n",
+                        arrow, endpoint.fqn, endpoint.via, endpoint.file, endpoint.line
+                    ));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Publishers and subscribers of each topic of the snapshot, or of those
+/// whose name contains `topic`
+pub fn flows(commit: &str, symbols: &[SymbolIR], edges: &[EdgeIR], topic: Option<&str>) -> EventFlowReport {
+    let by_id: HashMap<&str, &SymbolIR> = symbols.iter().map(|s| (s.id.as_str(), s)).collect();
+    let mut topics: BTreeMap<&str, EventFlow> = BTreeMap::new();
+    for edge in edges {
+        let subscribes = match edge.edge_type {
+            EdgeType::PublishesTo => false,
+            EdgeType::SubscribesTo => true,
+            _ => continue,
+        };
+        let (Some(src), Some(name)) =
+            (edge.src.as_deref(), edge.dst.as_deref().and_then(|dst| dst.strip_prefix(TOPIC_PREFIX)))
+        else {
+            continue;
+        };
+        if topic.is_some_and(|topic| !name.contains(topic)) {
+            continue;
+        }
+        let Some(symbol) = by_id.get(src) else {
+            continue;
+        };
+        let endpoint = Endpoint {
+            fqn: symbol.fqn.clone(),
+            file: symbol.file_path.clone(),
+            line: edge
+                .meta
+                .get("line")
+                .and_then(|line| line.as_u64())
+                .map_or(symbol.name_span.start_line + 1, |line| line as u32),
+            via: edge.meta.get("method").and_then(|m| m.as_str()).unwrap_or_default().to_string(),
+        };
+        let flow = topics.entry(name).or_insert_with(|| EventFlow {
+            topic: name.to_string(),
+            publishers: Vec::new(),
+            subscribers: Vec::new(),
+        });
+        if subscribes {
+            flow.subscribers.push(endpoint);
+        } else {
+            flow.publishers.push(endpoint);
+        }
+    }
+    let mut topics: Vec<EventFlow> = topics.into_values().collect();
+    for flow in &mut topics {
+        for endpoints in [&mut flow.publishers, &mut flow.subscribers] {
+            endpoints.sort_by(|a, b| (&a.file, a.line, &a.fqn).cmp(&(&b.file, b.line, &b.fqn)));
+        }
+    }
+    EventFlowReport { commit: commit.to_string(), topics }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Span, test_util};
+
+    fn symbol(fqn: &str, kind: SymbolKind, file: &str, lines: (u32, u32)) -> SymbolIR {
+        SymbolIR {
+            kind,
+            name_span: Span { start_line: lines.0, start_col: 0, end_line: lines.0, end_col: 1 },
+            full_span: Span { start_line: lines.0, start_col: 0, end_line: lines.1, end_col: 1 },
+            ..test_util::symbol(&format!("id:{}", fqn), fqn, file)
+        }
+    }
+
+    #[test]
+    fn test_detect_links_publishers_and_subscribers() {
+        let events = "export const Events = {\n  USER_CREATED: 'user.created',\n};\nexport const ORDER_PAID = \"order.paid\";\n";
+        let users = r#"import { Events } from './events';
+
+export class UserService {
+  create(user) {
+    this.bus.emit(Events.USER_CREATED, user);
+    bus.emit(`user.${kind}`, user);
+    // bus.emit("user.deleted")
+  }
+}
+"#;
+        let mailer = r#"function sendWelcome(user) {}
+
+bus.on("user.created", sendWelcome);
+bus.once('order.paid', (order) => log(order));
+
+export class Billing {
+  @OnEvent('order.paid')
+  charge(order) {}
+
+  start() {
+    nats.Subscribe(ORDER_PAID, func);
+    client.publish(
+      "audit.log",
+      { at: now() },
+    );
+  }
+}
+"#;
+        let symbols = vec![
+            symbol("users.UserService", SymbolKind::Class, "users.ts", (2, 8)),
+            symbol("users.UserService.create", SymbolKind::Method, "users.ts", (3, 7)),
+            symbol("mailer.sendWelcome", SymbolKind::Function, "mailer.ts", (0, 0)),
+            symbol("mailer.Billing", SymbolKind::Class, "mailer.ts", (5, 16)),
+            symbol("mailer.Billing.charge", SymbolKind::Method, "mailer.ts", (7, 7)),
+            symbol("mailer.Billing.start", SymbolKind::Method, "mailer.ts", (9, 15)),
+            symbol("events.Events", SymbolKind::Variable, "events.ts", (0, 2)),
+        ];
+        let files: Vec<(String, FileEvents)> = [("events.ts", events), ("mailer.ts", mailer), ("users.ts", users)]
+            .into_iter()
+            .map(|(file, text)| (file.to_string(), FileEvents::scan(text)))
+            .collect();
+        let edges = detect(&symbols, &files, &[]);
+        let found: Vec<(&str, &str, &str, u64)> = edges
+            .iter()
+            .map(|e| {
+                let kind = if e.edge_type == EdgeType::PublishesTo { "pub" } else { "sub" };
+                (kind, e.src.as_deref().unwrap(), e.dst.as_deref().unwrap(), e.meta["line"].as_u64().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("sub", "id:mailer.sendWelcome", "event:user.created", 3),
+                ("sub", "id:mailer.Billing.charge", "event:order.paid", 7),
+                ("sub", "id:mailer.Billing.start", "event:order.paid", 11),
+                ("pub", "id:mailer.Billing.start", "event:audit.log", 12),
+                ("pub", "id:users.UserService.create", "event:user.created", 5),
+            ]
+        );
+
+        let report = flows("abc", &symbols, &edges, None);
+        let topics: Vec<(&str, usize, usize)> =
+            report.topics.iter().map(|f| (f.topic.as_str(), f.publishers.len(), f.subscribers.len())).collect();
+        assert_eq!(topics, [("audit.log", 1, 0), ("order.paid", 0, 2), ("user.created", 1, 1)]);
+        let text = report.to_text();
+        assert!(text.contains(
+            "  user.created\n    -> users.UserService.create via emit (users.ts:5)\n    <- mailer.sendWelcome via on (mailer.ts:3)\n"
+        ));
+        assert!(text.contains("  audit.log\n    -> mailer.Billing.start via publish (mailer.ts:12)\n    <- (no subscribers)\n"));
+        assert_eq!(flows("abc", &symbols, &edges, Some("order")).topics.len(), 1);
+    }
+
+    #[test]
+    fn test_detect_for_rescanned_files_only() {
+        let topics = "export const Topics = {\n  PAID: 'order.paid',\n};\n";
+        let send = "function send() {\n  bus.emit(Topics.PAID);\n}\n";
+        let notify = "function notify() {\n  bus.publish(Topics.PAID);\n}\n";
+        let symbols = vec![
+            symbol("send.send", SymbolKind::Function, "send.ts", (0, 2)),
+            symbol("notify.notify", SymbolKind::Function, "notify.ts", (0, 2)),
+        ];
+        let scan = |files: &[(&str, &str)]| -> Vec<(String, FileEvents)> {
+            files.iter().map(|(file, text)| (file.to_string(), FileEvents::scan(text))).collect()
+        };
+        let all = detect(&symbols, &scan(&[("topics.ts", topics), ("send.ts", send), ("notify.ts", notify)]), &[]);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].meta["constant"], "PAID");
+
+        // Only notify.ts is re-read: the constant is known from send.ts's edge
+        let rescanned = detect(&symbols, &scan(&[("notify.ts", notify)]), &all);
+        assert_eq!(rescanned.len(), 1);
+        assert_eq!(rescanned[0].src.as_deref(), Some("id:notify.notify"));
+        assert_eq!(rescanned[0].dst.as_deref(), Some("event:order.paid"));
+        // ...but not from the rescanned file's own, now replaced, edges
+        let kept: Vec<EdgeIR> = all.iter().filter(|e| e.file_src.as_deref() == Some("notify.ts")).cloned().collect();
+        assert!(detect(&symbols, &scan(&[("notify.ts", notify)]), &kept).is_empty());
+        assert!(FileEvents::scan("let x = 1;\n").is_empty());
+    }
+
+    #[test]
+    fn test_topic_of_arguments() {
+        let constants = HashMap::from([
+            ("CREATED".to_string(), Some("created".to_string())),
+            ("CLASH".to_string(), None),
+        ]);
+        let topic = |argument: &str| topic_of(argument, &constants).map(|(topic, _)| topic);
+        assert_eq!(topic("'a.b'").as_deref(), Some("a.b"));
+        assert_eq!(topic("topics = \"orders\"").as_deref(), Some("orders"));
+        assert_eq!(topic("topics = {\"a\", \"b\"}").as_deref(), Some("a"));
+        assert_eq!(
+            topic_of("Events.CREATED", &constants),
+            Some(("created".to_string(), Some("CREATED".to_string())))
+        );
+        assert_eq!(topic_of("CLASH", &constants), None);
+        assert_eq!(topic_of("`user.${id}`", &constants), None);
+        assert_eq!(topic_of("event", &constants), None);
+        assert_eq!(arguments("'x', (a, b) => f(a), 3)"), ["'x'", "(a, b) => f(a)"]);
+        assert_eq!(arguments("\"a,b\")"), ["\"a,b\""]);
+        assert_eq!(
+            string_constants("const A = 'a';\n  B: \"b\",\nif (x == \"c\") {}\nstatic final String C = \"c\";"),
+            [("A".to_string(), "a".to_string()), ("B".to_string(), "b".to_string()), ("C".to_string(), "c".to_string())]
+        );
+    }
+}
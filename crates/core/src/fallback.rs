@@ -0,0 +1,314 @@
+//! ctags-style indexing for languages without a harness: the file as a
+//! Module symbol and definitions found by matching lines against a few
+//! patterns per language. Nothing is resolved, so every edge is
+//! `Resolution::Heuristic`.
+
+use anyhow::Result;
+use protocol::{
+    Capabilities, EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Resolution, Span, SymbolFlags, SymbolIR, SymbolKind,
+};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+/// A language by its file extensions, with patterns tried in order on each
+/// line. Each pattern captures the defined name as `name`.
+struct FallbackLanguage {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    patterns: &'static [(SymbolKind, &'static str)],
+}
+
+const LANGUAGES: &[FallbackLanguage] = &[
+    FallbackLanguage {
+        name: "ruby",
+        extensions: &["rb", "rake"],
+        patterns: &[
+            (SymbolKind::Class, r"^\s*class\s+(?P<name>[A-Z][\w:]*)"),
+            (SymbolKind::Module, r"^\s*module\s+(?P<name>[A-Z][\w:]*)"),
+            (SymbolKind::Method, r"^\s*def\s+(?:self\.)?(?P<name>[\w?!=]+)"),
+        ],
+    },
+    FallbackLanguage {
+        name: "php",
+        extensions: &["php"],
+        patterns: &[
+            (SymbolKind::Class, r"^\s*(?:(?:abstract|final|readonly)\s+)*class\s+(?P<name>\w+)"),
+            (SymbolKind::Interface, r"^\s*interface\s+(?P<name>\w+)"),
+            (SymbolKind::Trait, r"^\s*trait\s+(?P<name>\w+)"),
+            (SymbolKind::Function, r"^\s*(?:(?:public|protected|private|static|abstract|final)\s+)*function\s+&?(?P<name>\w+)"),
+        ],
+    },
+    FallbackLanguage {
+        name: "kotlin",
+        extensions: &["kt", "kts"],
+        patterns: &[
+            (SymbolKind::Interface, r"^\s*(?:(?:public|private|internal|protected|sealed|fun)\s+)*interface\s+(?P<name>\w+)"),
+            (SymbolKind::Class, r"^\s*(?:(?:public|private|internal|protected|open|abstract|sealed|data|enum|inner|annotation|value)\s+)*(?:class|object)\s+(?P<name>\w+)"),
+            (SymbolKind::Function, r"^\s*(?:(?:public|private|internal|protected|open|override|abstract|suspend|inline|operator|infix|tailrec)\s+)*fun\s+(?:<[^>]*>\s*)?(?:[\w.]+\.)?(?P<name>\w+)"),
+        ],
+    },
+    FallbackLanguage {
+        name: "swift",
+        extensions: &["swift"],
+        patterns: &[
+            (SymbolKind::Class, r"^\s*(?:(?:public|private|internal|fileprivate|open|final)\s+)*class\s+(?P<name>\w+)"),
+            (SymbolKind::Struct, r"^\s*(?:(?:public|private|internal|fileprivate)\s+)*struct\s+(?P<name>\w+)"),
+            (SymbolKind::Enum, r"^\s*(?:(?:public|private|internal|fileprivate|indirect)\s+)*enum\s+(?P<name>\w+)"),
+            (SymbolKind::Interface, r"^\s*(?:(?:public|private|internal|fileprivate)\s+)*protocol\s+(?P<name>\w+)"),
+            (SymbolKind::Function, r"^\s*(?:(?:public|private|internal|fileprivate|open|static|class|override|mutating|final)\s+)*func\s+(?P<name>\w+)"),
+        ],
+    },
+    FallbackLanguage {
+        name: "scala",
+        extensions: &["scala", "sc"],
+        patterns: &[
+            (SymbolKind::Class, r"^\s*(?:(?:abstract|final|sealed|case|private|protected|implicit)\s+)*(?:class|object)\s+(?P<name>\w+)"),
+            (SymbolKind::Trait, r"^\s*(?:(?:sealed|private|protected)\s+)*trait\s+(?P<name>\w+)"),
+            (SymbolKind::Function, r"^\s*(?:(?:override|private|protected|final|implicit|inline)\s+)*def\s+(?P<name>\w+)"),
+        ],
+    },
+    FallbackLanguage {
+        name: "lua",
+        extensions: &["lua"],
+        patterns: &[(SymbolKind::Function, r"^\s*(?:local\s+)?function\s+(?:[\w.:]+[.:])?(?P<name>\w+)")],
+    },
+    FallbackLanguage {
+        name: "shell",
+        extensions: &["sh", "bash", "zsh"],
+        patterns: &[(SymbolKind::Function, r"^\s*(?:function\s+(?P<name>[\w-]+)|(?P<name2>[\w-]+)\s*\(\s*\))")],
+    },
+    FallbackLanguage {
+        name: "perl",
+        extensions: &["pl", "pm"],
+        patterns: &[
+            (SymbolKind::Package, r"^\s*package\s+(?P<name>[\w:]+)"),
+            (SymbolKind::Function, r"^\s*sub\s+(?P<name>\w+)"),
+        ],
+    },
+    FallbackLanguage {
+        name: "elixir",
+        extensions: &["ex", "exs"],
+        patterns: &[
+            (SymbolKind::Module, r"^\s*defmodule\s+(?P<name>[\w.]+)"),
+            (SymbolKind::Function, r"^\s*defp?\s+(?P<name>[\w?!]+)"),
+        ],
+    },
+];
+
+/// Extensions the fallback indexer picks up, for the file walker
+pub fn extensions() -> impl Iterator<Item = &'static str> {
+    LANGUAGES.iter().flat_map(|language| language.extensions.iter().copied())
+}
+
+/// The same for every fallback language but its name and extensions
+const CAPABILITIES: Capabilities = Capabilities {
+    name: "",
+    extensions: &[],
+    edges: &[EdgeType::Contains],
+    occurrences: &[OccurrenceRole::Definition],
+    docs: false,
+    resolution: Resolution::Heuristic,
+};
+
+/// What the fallback indexer extracts, one declaration per language
+pub fn capabilities() -> impl Iterator<Item = Capabilities> {
+    LANGUAGES.iter().map(|language| Capabilities {
+        name: language.name,
+        extensions: language.extensions,
+        ..CAPABILITIES
+    })
+}
+
+pub struct FallbackIndexer {
+    languages: Vec<(&'static FallbackLanguage, Vec<(SymbolKind, Regex)>)>,
+}
+
+impl FallbackIndexer {
+    pub fn new() -> Self {
+        let languages = LANGUAGES
+            .iter()
+            .map(|language| {
+                let patterns = language
+                    .patterns
+                    .iter()
+                    .map(|(kind, pattern)| (kind.clone(), Regex::new(pattern).expect("built-in pattern")))
+                    .collect();
+                (language, patterns)
+            })
+            .collect();
+        Self { languages }
+    }
+
+    fn language_for(&self, file_path: &str) -> Option<&(&'static FallbackLanguage, Vec<(SymbolKind, Regex)>)> {
+        let extension = file_path.rsplit_once('.')?.1;
+        self.languages.iter().find(|(language, _)| language.extensions.contains(&extension))
+    }
+
+    /// The language's name and what was found, or `None` if no fallback
+    /// language claims the file
+    #[allow(clippy::type_complexity)]
+    pub fn parse(
+        &self,
+        file_path: &str,
+        content: &str,
+    ) -> Result<Option<(&'static str, (Vec<SymbolIR>, Vec<EdgeIR>, Vec<OccurrenceIR>))>> {
+        let Some((language, patterns)) = self.language_for(file_path) else {
+            return Ok(None);
+        };
+        let module = file_symbol(file_path, content);
+        let module_fqn = file_path.rsplit_once('.').map_or(file_path, |(stem, _)| stem);
+        let mut symbols = Vec::new();
+        let mut edges = Vec::new();
+        let mut occurrences = Vec::new();
+        let mut ids = HashSet::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let Some((kind, name)) = patterns.iter().find_map(|(kind, regex)| {
+                let captures = regex.captures(line)?;
+                captures.name("name").or_else(|| captures.name("name2")).map(|name| (kind.clone(), name))
+            }) else {
+                continue;
+            };
+            let span = Span {
+                start_line: line_number as u32,
+                start_col: name.start() as u32,
+                end_line: line_number as u32,
+                end_col: name.end() as u32,
+            };
+            // Same-named definitions (`def initialize` in two classes) get
+            // distinct ids in file order
+            let mut id = format!("{}#{}", file_path, name.as_str());
+            let mut n = 1;
+            while !ids.insert(id.clone()) {
+                n += 1;
+                id = format!("{}#{}#{}", file_path, name.as_str(), n);
+            }
+            let symbol = SymbolIR {
+                id: id.clone(),
+                lang: Language::Unknown,
+                lang_version: None,
+                kind,
+                name: name.as_str().to_string(),
+                fqn: format!("{}/{}", module_fqn, name.as_str()),
+                signature: Some(line.trim().to_string()),
+                parameters: Vec::new(),
+                return_type: None,
+                file_path: file_path.to_string(),
+                name_span: span.clone(),
+                full_span: Span { start_col: 0, end_col: line.len() as u32, ..span.clone() },
+                visibility: None,
+                flags: SymbolFlags::empty(),
+                configs: Vec::new(),
+                doc: None,
+                doc_tags: None,
+                sig_hash: format!("{:x}", md5::compute(line.trim())),
+            };
+            edges.push(EdgeIR {
+                edge_type: EdgeType::Contains,
+                src: Some(module.id.clone()),
+                dst: Some(id.clone()),
+                file_src: Some(file_path.to_string()),
+                file_dst: Some(file_path.to_string()),
+                resolution: Resolution::Heuristic,
+                meta: HashMap::new(),
+                provenance: HashMap::new(),
+            });
+            occurrences.push(OccurrenceIR {
+                file_path: file_path.to_string(),
+                symbol_id: Some(id),
+                role: OccurrenceRole::Definition,
+                span,
+                token: symbol.name.clone(),
+            });
+            symbols.push(symbol);
+        }
+
+        symbols.insert(0, module);
+        Ok(Some((language.name, (symbols, edges, occurrences))))
+    }
+}
+
+impl Default for FallbackIndexer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn file_symbol(file_path: &str, content: &str) -> SymbolIR {
+    SymbolIR {
+        id: format!("file_{}", file_path),
+        lang: Language::Unknown,
+        lang_version: None,
+        kind: SymbolKind::Module,
+        name: file_path.rsplit('/').next().unwrap_or(file_path).to_string(),
+        fqn: file_path.to_string(),
+        signature: None,
+        parameters: Vec::new(),
+        return_type: None,
+        file_path: file_path.to_string(),
+        name_span: Default::default(),
+        full_span: Span { start_line: 0, start_col: 0, end_line: content.lines().count() as u32, end_col: 0 },
+        visibility: Some("public".to_string()),
+        flags: SymbolFlags::empty(),
+        configs: Vec::new(),
+        doc: None,
+        doc_tags: None,
+        sig_hash: format!("{:x}", md5::compute(file_path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_definitions() -> Result<()> {
+        let source = r#"module Billing
+  class Invoice
+    def initialize(total)
+    end
+
+    def self.paid?
+    end
+  end
+
+  class Refund
+    def initialize
+    end
+  end
+end
+"#;
+        let indexer = FallbackIndexer::new();
+        let (language, (symbols, edges, occurrences)) = indexer.parse("app/billing.rb", source)?.unwrap();
+        assert_eq!(language, "ruby");
+
+        let found: Vec<(&str, &str, SymbolKind)> =
+            symbols.iter().map(|s| (s.id.as_str(), s.fqn.as_str(), s.kind.clone())).collect();
+        assert_eq!(
+            found,
+            [
+                ("file_app/billing.rb", "app/billing.rb", SymbolKind::Module),
+                ("app/billing.rb#Billing", "app/billing/Billing", SymbolKind::Module),
+                ("app/billing.rb#Invoice", "app/billing/Invoice", SymbolKind::Class),
+                ("app/billing.rb#initialize", "app/billing/initialize", SymbolKind::Method),
+                ("app/billing.rb#paid?", "app/billing/paid?", SymbolKind::Method),
+                ("app/billing.rb#Refund", "app/billing/Refund", SymbolKind::Class),
+                ("app/billing.rb#initialize#2", "app/billing/initialize", SymbolKind::Method),
+            ]
+        );
+        assert!(symbols.iter().all(|s| s.lang == Language::Unknown));
+        assert_eq!(symbols[3].signature.as_deref(), Some("def initialize(total)"));
+
+        assert_eq!(edges.len(), 6);
+        assert!(edges.iter().all(|e| e.resolution == Resolution::Heuristic && e.src.as_deref() == Some("file_app/billing.rb")));
+        let paid = occurrences.iter().find(|o| o.token == "paid?").unwrap();
+        assert_eq!((paid.span.start_line, paid.span.start_col, paid.span.end_col), (5, 13, 18));
+
+        let (_, (symbols, _, _)) = indexer.parse("scripts/deploy.sh", "function build {\n}\nrelease() {\n}\n")?.unwrap();
+        let names: Vec<&str> = symbols.iter().skip(1).map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["build", "release"]);
+
+        assert!(indexer.parse("notes.txt", "def nope")?.is_none());
+        Ok(())
+    }
+}
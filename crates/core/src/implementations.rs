@@ -0,0 +1,479 @@
+use protocol::{EdgeIR, EdgeType, Span, SymbolIR, SymbolKind};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// A concrete type implementing an interface, directly or through a
+/// sub-interface or superclass
+#[derive(Debug, Clone, Serialize)]
+pub struct Implementation {
+    pub fqn: String,
+    pub kind: String,
+    pub file: String,
+    /// 1-based line of the type's name
+    pub line: u32,
+    /// The sub-interface or superclass it gets the interface through
+    pub via: Option<String>,
+    /// Required methods of the interface with no method of that name in
+    /// the type or its superclasses; `None` when the method sets aren't known
+    pub missing: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImplementationsReport {
+    pub commit: String,
+    pub interface: String,
+    /// Methods declared without a body by the interface and the interfaces
+    /// it extends; `None` when its source couldn't be read
+    pub required: Option<Vec<String>>,
+    pub implementations: Vec<Implementation>,
+}
+
+impl ImplementationsReport {
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "{} implementations of {} at {}\n",
+            self.implementations.len(),
+            self.interface,
+            self.commit
+        );
+        for implementation in &self.implementations {
+            let via = implementation.via.as_ref().map(|via| format!(" via {}", via)).unwrap_or_default();
+            out.push_str(&format!(
+                "  {:<8} {} ({}:{}){}\n",
+                implementation.kind, implementation.fqn, implementation.file, implementation.line, via
+            ));
+            if let Some(missing) = implementation.missing.as_ref().filter(|m| !m.is_empty()) {
+                out.push_str(&format!("           missing {}\n", missing.join(", ")));
+            }
+        }
+        out
+    }
+}
+
+/// An implementor lacking required methods of an interface
+#[derive(Debug, Clone, Serialize)]
+pub struct Gap {
+    pub interface: String,
+    pub implementor: String,
+    pub file: String,
+    pub line: u32,
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GapReport {
+    pub commit: String,
+    pub gaps: Vec<Gap>,
+}
+
+impl GapReport {
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{} implementors missing interface methods at {}\n", self.gaps.len(), self.commit);
+        for gap in &self.gaps {
+            out.push_str(&format!(
+                "  {} ({}:{}) implements {} without {}\n",
+                gap.implementor,
+                gap.file,
+                gap.line,
+                gap.interface,
+                gap.missing.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+fn is_type(symbol: &SymbolIR) -> bool {
+    matches!(
+        symbol.kind,
+        SymbolKind::Class | SymbolKind::Interface | SymbolKind::Trait | SymbolKind::Struct | SymbolKind::Enum
+    )
+}
+
+fn is_interface(symbol: &SymbolIR) -> bool {
+    matches!(symbol.kind, SymbolKind::Interface | SymbolKind::Trait)
+}
+
+/// `Shape` for `com.geo.Shape`, `geo::Shape` or `Shape<T>`
+fn simple_name(name: &str) -> &str {
+    let name = name.split('<').next().unwrap_or(name).trim();
+    name.rsplit(['.', ':', '/', '#']).next().unwrap_or(name)
+}
+
+/// Text of a symbol's source between two positions
+fn span_text(source: &str, start: (u32, u32), end: (u32, u32)) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut out = String::new();
+    for line in start.0..=end.0 {
+        let Some(text) = lines.get(line as usize) else {
+            break;
+        };
+        let from = if line == start.0 { start.1 as usize } else { 0 };
+        let to = if line == end.0 { (end.1 as usize).min(text.len()) } else { text.len() };
+        out.push_str(text.get(from..to.max(from)).unwrap_or(text));
+        out.push('\n');
+    }
+    out
+}
+
+/// Types and their inheritance in a snapshot. Supertypes are matched by
+/// simple name, as harnesses keep them as written, so same-named types
+/// in different packages are not told apart.
+pub struct Hierarchy<'a, R: Fn(&str) -> Option<String>> {
+    symbols: &'a [SymbolIR],
+    by_id: HashMap<&'a str, &'a SymbolIR>,
+    /// Types by simple name
+    types: HashMap<&'a str, Vec<&'a SymbolIR>>,
+    /// Types extending or implementing each simple name
+    subtypes: HashMap<&'a str, Vec<&'a SymbolIR>>,
+    /// Simple names each type extends or implements, by type id
+    supertypes: HashMap<&'a str, Vec<&'a str>>,
+    methods: HashMap<&'a str, Vec<&'a SymbolIR>>,
+    read: R,
+    sources: RefCell<HashMap<String, Option<String>>>,
+}
+
+impl<'a, R: Fn(&str) -> Option<String>> Hierarchy<'a, R> {
+    /// `read` returns a file's source, used to tell methods with a body
+    /// from those an implementor must provide
+    pub fn new(symbols: &'a [SymbolIR], edges: &'a [EdgeIR], read: R) -> Self {
+        let by_id: HashMap<&str, &SymbolIR> = symbols.iter().map(|s| (s.id.as_str(), s)).collect();
+        let mut types: HashMap<&str, Vec<&SymbolIR>> = HashMap::new();
+        for symbol in symbols.iter().filter(|s| is_type(s)) {
+            types.entry(symbol.name.as_str()).or_default().push(symbol);
+        }
+
+        let mut subtypes: HashMap<&str, Vec<&SymbolIR>> = HashMap::new();
+        let mut supertypes: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut methods: HashMap<&str, Vec<&SymbolIR>> = HashMap::new();
+        for edge in edges {
+            let (Some(src), Some(dst)) = (edge.src.as_deref(), edge.dst.as_deref()) else {
+                continue;
+            };
+            match edge.edge_type {
+                EdgeType::Implements | EdgeType::Extends => {
+                    let Some(subtype) = by_id.get(src).copied().filter(|s| is_type(s)) else {
+                        continue;
+                    };
+                    let supertype = by_id.get(dst).map_or(simple_name(dst), |s| s.name.as_str());
+                    subtypes.entry(supertype).or_default().push(subtype);
+                    supertypes.entry(subtype.id.as_str()).or_default().push(supertype);
+                }
+                EdgeType::Contains => {
+                    if let Some(method) = by_id.get(dst).filter(|s| s.kind == SymbolKind::Method) {
+                        methods.entry(src).or_default().push(method);
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Harnesses that don't link members by `Contains` nest their FQNs
+        let by_fqn: HashMap<&str, &SymbolIR> =
+            symbols.iter().filter(|s| is_type(s)).map(|s| (s.fqn.as_str(), s)).collect();
+        for method in symbols.iter().filter(|s| s.kind == SymbolKind::Method) {
+            let owner = method.fqn.strip_suffix(method.name.as_str()).map(|p| p.trim_end_matches(['.', ':', '/', '#']));
+            if let Some(owner) = owner.and_then(|owner| by_fqn.get(owner)) {
+                let members = methods.entry(owner.id.as_str()).or_default();
+                if !members.iter().any(|m| m.id == method.id) {
+                    members.push(method);
+                }
+            }
+        }
+
+        Hierarchy {
+            symbols,
+            by_id,
+            types,
+            subtypes,
+            supertypes,
+            methods,
+            read,
+            sources: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn source_text(&self, symbol: &SymbolIR, start: (u32, u32), end: (u32, u32)) -> Option<String> {
+        let mut sources = self.sources.borrow_mut();
+        let source = sources.entry(symbol.file_path.clone()).or_insert_with(|| (self.read)(&symbol.file_path));
+        source.as_deref().map(|source| span_text(source, start, end))
+    }
+
+    fn position(span: &Span, end: bool) -> (u32, u32) {
+        if end { (span.end_line, span.end_col) } else { (span.start_line, span.start_col) }
+    }
+
+    /// Whether a method has a body: `None` when its source can't be read
+    fn has_body(&self, method: &SymbolIR) -> Option<bool> {
+        let text = self.source_text(method, Self::position(&method.full_span, false), Self::position(&method.full_span, true))?;
+        Some(text.contains('{'))
+    }
+
+    /// `abstract` among the modifiers before the type's name
+    fn is_abstract(&self, symbol: &SymbolIR) -> bool {
+        self.source_text(symbol, Self::position(&symbol.full_span, false), Self::position(&symbol.name_span, false))
+            .is_some_and(|text| text.split_whitespace().any(|word| word == "abstract"))
+    }
+
+    fn types_named(&self, name: &str) -> &[&'a SymbolIR] {
+        self.types.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The type and its supertypes, each once
+    fn ancestry(&self, symbol: &'a SymbolIR) -> Vec<&'a SymbolIR> {
+        let mut seen: HashSet<&str> = HashSet::from([symbol.id.as_str()]);
+        let mut ancestry = vec![symbol];
+        let mut next = 0;
+        while let Some(current) = ancestry.get(next).copied() {
+            next += 1;
+            for supertype in self.supertypes.get(current.id.as_str()).into_iter().flatten() {
+                for parent in self.types_named(supertype) {
+                    if seen.insert(parent.id.as_str()) {
+                        ancestry.push(parent);
+                    }
+                }
+            }
+        }
+        ancestry
+    }
+
+    /// Names of the bodiless methods of `interface` and the interfaces it
+    /// extends; `None` when any of their sources can't be read
+    pub fn required_methods(&self, interface: &'a SymbolIR) -> Option<BTreeSet<String>> {
+        let mut required = BTreeSet::new();
+        for ancestor in self.ancestry(interface).into_iter().filter(|s| is_interface(s)) {
+            for method in self.methods.get(ancestor.id.as_str()).into_iter().flatten() {
+                if !self.has_body(method)? {
+                    required.insert(method.name.clone());
+                }
+            }
+        }
+        Some(required)
+    }
+
+    /// Names of the methods a type has a body for, its own and inherited
+    fn implemented_methods(&self, symbol: &'a SymbolIR) -> Option<HashSet<String>> {
+        let mut implemented = HashSet::new();
+        for (i, ancestor) in self.ancestry(symbol).into_iter().enumerate() {
+            for method in self.methods.get(ancestor.id.as_str()).into_iter().flatten() {
+                // Own methods count even if abstract: the gap is then its subclasses'
+                if i == 0 || self.has_body(method)? {
+                    implemented.insert(method.name.clone());
+                }
+            }
+        }
+        Some(implemented)
+    }
+
+    /// Concrete types below `interface`, each with the type it was reached
+    /// through when not direct
+    fn implementors(&self, interface: &'a SymbolIR) -> Vec<(&'a SymbolIR, Option<&'a SymbolIR>)> {
+        let mut seen: HashSet<&str> = HashSet::from([interface.id.as_str()]);
+        let mut pending: Vec<(&SymbolIR, Option<&SymbolIR>)> = vec![(interface, None)];
+        let mut found = Vec::new();
+        let mut next = 0;
+        while let Some((current, _)) = pending.get(next).copied() {
+            next += 1;
+            for subtype in self.subtypes.get(current.name.as_str()).into_iter().flatten() {
+                if !seen.insert(subtype.id.as_str()) {
+                    continue;
+                }
+                // The nearest type in between, if any
+                let through = (current.id != interface.id).then_some(current);
+                pending.push((subtype, through));
+                if !is_interface(subtype) {
+                    found.push((*subtype, through));
+                }
+            }
+        }
+        found
+    }
+
+    /// Types implementing `interface`, with the required methods each lacks
+    pub fn implementations(&self, commit: &str, interface: &'a SymbolIR) -> ImplementationsReport {
+        let required = self.required_methods(interface);
+        let mut implementations: Vec<Implementation> = self
+            .implementors(interface)
+            .into_iter()
+            .map(|(symbol, via)| {
+                let missing = match &required {
+                    Some(required) if !self.is_abstract(symbol) => self.implemented_methods(symbol).map(|implemented| {
+                        required.iter().filter(|m| !implemented.contains(*m)).cloned().collect()
+                    }),
+                    _ => None,
+                };
+                Implementation {
+                    fqn: symbol.fqn.clone(),
+                    kind: format!("{:?}", symbol.kind),
+                    file: symbol.file_path.clone(),
+                    line: symbol.name_span.start_line + 1,
+                    via: via.map(|via| via.fqn.clone()),
+                    missing,
+                }
+            })
+            .collect();
+        implementations.sort_by(|a, b| (&a.file, a.line, &a.fqn).cmp(&(&b.file, b.line, &b.fqn)));
+        ImplementationsReport {
+            commit: commit.to_string(),
+            interface: interface.fqn.clone(),
+            required: required.map(|r| r.into_iter().collect()),
+            implementations,
+        }
+    }
+
+    /// Implementors lacking required methods, across every interface and
+    /// trait whose implementors' method sets are known
+    pub fn gaps(&self, commit: &str) -> GapReport {
+        let mut gaps = Vec::new();
+        for interface in self.symbols.iter().filter(|s| is_interface(s)) {
+            if !self.subtypes.contains_key(interface.name.as_str()) {
+                continue;
+            }
+            for implementation in self.implementations(commit, interface).implementations {
+                if let Some(missing) = implementation.missing.filter(|m| !m.is_empty()) {
+                    gaps.push(Gap {
+                        interface: interface.fqn.clone(),
+                        implementor: implementation.fqn,
+                        file: implementation.file,
+                        line: implementation.line,
+                        missing,
+                    });
+                }
+            }
+        }
+        gaps.sort_by(|a, b| (&a.file, a.line, &a.interface).cmp(&(&b.file, b.line, &b.interface)));
+        GapReport { commit: commit.to_string(), gaps }
+    }
+
+    /// The interface, trait or class called `name`: by FQN, else the only
+    /// one with that simple name
+    pub fn find_type(&self, name: &str) -> anyhow::Result<&'a SymbolIR> {
+        if let Some(symbol) = self.by_id.values().find(|s| is_type(s) && s.fqn == name) {
+            return Ok(symbol);
+        }
+        match self.types_named(simple_name(name)) {
+            [] => anyhow::bail!("No interface or type named {}", name),
+            [symbol] => Ok(symbol),
+            many => {
+                let mut fqns: Vec<&str> = many.iter().map(|s| s.fqn.as_str()).collect();
+                fqns.sort();
+                anyhow::bail!("{} is ambiguous, pass one of: {}", name, fqns.join(", "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Language, Resolution, test_util};
+
+    /// An implementation as `(fqn, via, missing)`
+    type Found<'a> = (&'a str, Option<&'a str>, Option<Vec<String>>);
+
+    fn symbol(fqn: &str, kind: SymbolKind, file: &str, lines: (u32, u32)) -> SymbolIR {
+        let name = fqn.rsplit('.').next().unwrap();
+        let name_col = SHAPES.lines().nth(lines.0 as usize).and_then(|line| line.find(name)).unwrap_or(0) as u32;
+        SymbolIR {
+            lang: Language::Java,
+            kind,
+            name_span: Span { start_line: lines.0, start_col: name_col, end_line: lines.0, end_col: 0 },
+            full_span: Span { start_line: lines.0, start_col: 0, end_line: lines.1, end_col: 200 },
+            ..test_util::symbol(&format!("{}#{}", file, fqn), fqn, file)
+        }
+    }
+
+    fn edge(edge_type: EdgeType, src: &SymbolIR, dst: &str) -> EdgeIR {
+        EdgeIR {
+            edge_type,
+            src: Some(src.id.clone()),
+            dst: Some(dst.to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        }
+    }
+
+    const SHAPES: &str = "\
+interface Shape {
+    double area();
+    default String label() { return \"shape\"; }
+}
+interface Polygon extends Shape {
+    int sides();
+}
+abstract class Base implements Polygon {
+    public double area() { return 0; }
+}
+class Square extends Base {
+    public int sides() { return 4; }
+}
+class Circle implements Shape {
+    public double radius() { return 1; }
+}
+";
+
+    #[test]
+    fn test_implementations_and_gaps() -> anyhow::Result<()> {
+        let file = "src/Shapes.java";
+        let shape = symbol("geo.Shape", SymbolKind::Interface, file, (0, 3));
+        let area = symbol("geo.Shape.area", SymbolKind::Method, file, (1, 1));
+        let label = symbol("geo.Shape.label", SymbolKind::Method, file, (2, 2));
+        let polygon = symbol("geo.Polygon", SymbolKind::Interface, file, (4, 6));
+        let sides = symbol("geo.Polygon.sides", SymbolKind::Method, file, (5, 5));
+        let base = symbol("geo.Base", SymbolKind::Class, file, (7, 9));
+        let base_area = symbol("geo.Base.area", SymbolKind::Method, file, (8, 8));
+        let square = symbol("geo.Square", SymbolKind::Class, file, (10, 12));
+        let square_sides = symbol("geo.Square.sides", SymbolKind::Method, file, (11, 11));
+        let circle = symbol("geo.Circle", SymbolKind::Class, file, (13, 15));
+        let radius = symbol("geo.Circle.radius", SymbolKind::Method, file, (14, 14));
+        let edges = vec![
+            edge(EdgeType::Extends, &polygon, "Shape"),
+            edge(EdgeType::Implements, &base, "geo.Polygon"),
+            edge(EdgeType::Extends, &square, "Base"),
+            edge(EdgeType::Implements, &circle, &shape.id),
+            edge(EdgeType::Contains, &shape, &area.id),
+        ];
+        let symbols = vec![shape, area, label, polygon, sides, base, base_area, square, square_sides, circle, radius];
+        let hierarchy = Hierarchy::new(&symbols, &edges, |path| (path == file).then(|| SHAPES.to_string()));
+
+        let shape = hierarchy.find_type("Shape")?;
+        let report = hierarchy.implementations("abc123", shape);
+        assert_eq!(report.required.as_deref(), Some(&["area".to_string()][..]));
+        let found: Vec<Found> = report
+            .implementations
+            .iter()
+            .map(|i| (i.fqn.as_str(), i.via.as_deref(), i.missing.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                // Abstract classes may leave methods to their subclasses
+                ("geo.Base", Some("geo.Polygon"), None),
+                ("geo.Square", Some("geo.Base"), Some(vec![])),
+                ("geo.Circle", None, Some(vec!["area".to_string()])),
+            ]
+        );
+
+        let polygon = hierarchy.find_type("geo.Polygon")?;
+        assert_eq!(
+            hierarchy.implementations("abc123", polygon).required,
+            Some(vec!["area".to_string(), "sides".to_string()])
+        );
+
+        let gaps = hierarchy.gaps("abc123");
+        let gaps: Vec<(&str, &str, &[String])> =
+            gaps.gaps.iter().map(|g| (g.interface.as_str(), g.implementor.as_str(), g.missing.as_slice())).collect();
+        assert_eq!(gaps, [("geo.Shape", "geo.Circle", &["area".to_string()][..])]);
+
+        // Without sources nothing is known to be missing
+        let blind = Hierarchy::new(&symbols, &edges, |_| None);
+        let report = blind.implementations("abc123", blind.find_type("Shape")?);
+        assert_eq!(report.implementations.len(), 3);
+        assert!(report.required.is_none() && report.implementations.iter().all(|i| i.missing.is_none()));
+        assert!(blind.find_type("Missing").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,394 @@
+use protocol::{EdgeIR, EdgeType, SymbolIR};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// An import statement behind a link between two files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportStatement {
+    pub file: String,
+    /// 1-based line of the statement, when it could be found in the source
+    pub line: Option<u32>,
+    pub text: Option<String>,
+}
+
+/// The imports of one file from another
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportLink {
+    pub from: String,
+    pub to: String,
+    /// Number of import edges between the two files
+    pub imports: usize,
+    pub statements: Vec<ImportStatement>,
+}
+
+/// Files importing each other in a cycle, and the links to cut to break it
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportCycle {
+    /// Sorted
+    pub files: Vec<String>,
+    /// Links between the files of the cycle
+    pub links: usize,
+    /// Removing these leaves the files without a cycle. Small, though not
+    /// necessarily the smallest such set: finding that is NP-hard.
+    pub break_links: Vec<ImportLink>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportCyclesReport {
+    pub commit: String,
+    /// Largest first
+    pub cycles: Vec<ImportCycle>,
+}
+
+impl ImportCyclesReport {
+    pub fn to_text(&self, shown: usize) -> String {
+        let mut out = format!("{} import cycles at {}\n", self.cycles.len(), self.commit);
+        for cycle in self.cycles.iter().take(shown) {
+            out.push_str(&format!(
+                "\n{} files, {} links: {}\n",
+                cycle.files.len(),
+                cycle.links,
+                cycle.files.join(", ")
+            ));
+            for link in &cycle.break_links {
+                let plural = if link.imports == 1 { "" } else { "s" };
+                out.push_str(&format!("  break {} -> {} ({} import{})\n", link.from, link.to, link.imports, plural));
+                for statement in &link.statements {
+                    match (statement.line, &statement.text) {
+                        (Some(line), Some(text)) => out.push_str(&format!("    {}:{}  {}\n", statement.file, line, text)),
+                        _ => out.push_str(&format!("    {} (statement not found)\n", statement.file)),
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Import cycles between the files of a snapshot, each with a set of
+/// links whose removal breaks it and the import statements behind those
+/// links, looked up in the sources `read` returns by path
+pub fn analyze(
+    commit: &str,
+    symbols: &[SymbolIR],
+    edges: &[EdgeIR],
+    read: impl Fn(&str) -> Option<String>,
+) -> ImportCyclesReport {
+    let links = import_links(symbols, edges);
+
+    let mut files: BTreeSet<&str> = BTreeSet::new();
+    for (from, to) in links.keys() {
+        files.insert(from);
+        files.insert(to);
+    }
+    let files: Vec<&str> = files.into_iter().collect();
+    let index: HashMap<&str, usize> = files.iter().enumerate().map(|(i, file)| (*file, i)).collect();
+    let arcs: BTreeMap<(usize, usize), usize> = links
+        .iter()
+        .map(|((from, to), imports)| ((index[from.as_str()], index[to.as_str()]), imports.len()))
+        .collect();
+
+    let mut sources: HashMap<String, Option<String>> = HashMap::new();
+    let mut cycles = Vec::new();
+    for component in strongly_connected(files.len(), &arcs) {
+        let members: HashSet<usize> = component.iter().copied().collect();
+        let inner: BTreeMap<(usize, usize), usize> = arcs
+            .iter()
+            .filter(|((from, to), _)| members.contains(from) && members.contains(to))
+            .map(|(&arc, &weight)| (arc, weight))
+            .collect();
+        let break_links = break_set(&component, &inner)
+            .into_iter()
+            .map(|(from, to)| {
+                let imports = &links[&(files[from].to_string(), files[to].to_string())];
+                let statements = statements(files[from], files[to], imports, &mut sources, &read);
+                ImportLink { from: files[from].to_string(), to: files[to].to_string(), imports: imports.len(), statements }
+            })
+            .collect();
+        let mut cycle_files: Vec<String> = component.iter().map(|&i| files[i].to_string()).collect();
+        cycle_files.sort();
+        cycles.push(ImportCycle { files: cycle_files, links: inner.len(), break_links });
+    }
+    cycles.sort_by(|a, b| b.files.len().cmp(&a.files.len()).then_with(|| a.files.cmp(&b.files)));
+    ImportCyclesReport { commit: commit.to_string(), cycles }
+}
+
+/// `Imports` edges grouped by the pair of files they link. The imported
+/// file is the edge's `file_dst`, else that of the symbol its `dst` names.
+fn import_links<'a>(symbols: &[SymbolIR], edges: &'a [EdgeIR]) -> BTreeMap<(String, String), Vec<&'a EdgeIR>> {
+    let mut file_of: HashMap<&str, &str> = HashMap::new();
+    for symbol in symbols {
+        file_of.insert(symbol.fqn.as_str(), symbol.file_path.as_str());
+    }
+    for symbol in symbols {
+        file_of.insert(symbol.id.as_str(), symbol.file_path.as_str());
+    }
+
+    let mut links: BTreeMap<(String, String), Vec<&EdgeIR>> = BTreeMap::new();
+    for edge in edges.iter().filter(|e| e.edge_type == EdgeType::Imports) {
+        let Some(from) = edge.file_src.as_deref() else {
+            continue;
+        };
+        let to = edge.file_dst.as_deref().or_else(|| edge.dst.as_deref().and_then(|dst| file_of.get(dst).copied()));
+        match to {
+            Some(to) if to != from => links.entry((from.to_string(), to.to_string())).or_default().push(edge),
+            _ => {}
+        }
+    }
+    links
+}
+
+/// Components of more than one node, by Tarjan's algorithm
+fn strongly_connected(nodes: usize, arcs: &BTreeMap<(usize, usize), usize>) -> Vec<Vec<usize>> {
+    let mut successors = vec![Vec::new(); nodes];
+    for &(from, to) in arcs.keys() {
+        successors[from].push(to);
+    }
+
+    let mut index = vec![usize::MAX; nodes];
+    let mut low = vec![0; nodes];
+    let mut on_stack = vec![false; nodes];
+    let mut stack = Vec::new();
+    let mut next = 0;
+    let mut components = Vec::new();
+    for root in 0..nodes {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        // Iterative, as import chains can be deeper than the call stack
+        let mut work = vec![(root, 0)];
+        while let Some(&mut (node, ref mut child)) = work.last_mut() {
+            if *child == 0 {
+                index[node] = next;
+                low[node] = next;
+                next += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+            if let Some(&successor) = successors[node].get(*child) {
+                *child += 1;
+                if index[successor] == usize::MAX {
+                    work.push((successor, 0));
+                } else if on_stack[successor] {
+                    low[node] = low[node].min(index[successor]);
+                }
+                continue;
+            }
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            if low[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                if component.len() > 1 {
+                    component.sort();
+                    components.push(component);
+                }
+            }
+        }
+    }
+    components
+}
+
+/// Arcs whose removal leaves `arcs` acyclic, weighted by the imports behind
+/// them. Orders the nodes by the greedy heuristic of Eades, Lin and Smyth
+/// and takes the arcs pointing backwards, then puts back any of those, the
+/// heaviest first, that no longer close a cycle.
+fn break_set(nodes: &[usize], arcs: &BTreeMap<(usize, usize), usize>) -> Vec<(usize, usize)> {
+    let mut remaining: BTreeSet<usize> = nodes.iter().copied().collect();
+    let weight_among = |node: usize, remaining: &BTreeSet<usize>, outgoing: bool| -> usize {
+        arcs.iter()
+            .filter(|((from, to), _)| {
+                let (this, other) = if outgoing { (from, to) } else { (to, from) };
+                *this == node && remaining.contains(other)
+            })
+            .map(|(_, weight)| weight)
+            .sum()
+    };
+
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    while !remaining.is_empty() {
+        if let Some(sink) = remaining.iter().copied().find(|&n| weight_among(n, &remaining, true) == 0) {
+            remaining.remove(&sink);
+            back.push(sink);
+            continue;
+        }
+        if let Some(source) = remaining.iter().copied().find(|&n| weight_among(n, &remaining, false) == 0) {
+            remaining.remove(&source);
+            front.push(source);
+            continue;
+        }
+        let best = remaining
+            .iter()
+            .copied()
+            .max_by_key(|&n| {
+                let delta = weight_among(n, &remaining, true) as i64 - weight_among(n, &remaining, false) as i64;
+                (delta, std::cmp::Reverse(n))
+            })
+            .expect("remaining is not empty");
+        remaining.remove(&best);
+        front.push(best);
+    }
+    front.extend(back.into_iter().rev());
+    let position: HashMap<usize, usize> = front.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+
+    let mut cut: Vec<(usize, usize)> =
+        arcs.keys().copied().filter(|(from, to)| position[from] > position[to]).collect();
+    cut.sort_by(|a, b| arcs[b].cmp(&arcs[a]).then_with(|| a.cmp(b)));
+    let mut kept = cut.clone();
+    for arc in cut {
+        kept.retain(|&other| other != arc);
+        // Putting `from -> to` back closes a cycle if `to` still reaches `from`
+        if reaches(arc.1, arc.0, arcs, &kept) {
+            kept.push(arc);
+        }
+    }
+    kept.sort();
+    kept
+}
+
+fn reaches(start: usize, target: usize, arcs: &BTreeMap<(usize, usize), usize>, cut: &[(usize, usize)]) -> bool {
+    let mut seen = HashSet::from([start]);
+    let mut pending = vec![start];
+    while let Some(node) = pending.pop() {
+        if node == target {
+            return true;
+        }
+        for &(from, to) in arcs.keys() {
+            if from == node && !cut.contains(&(from, to)) && seen.insert(to) {
+                pending.push(to);
+            }
+        }
+    }
+    false
+}
+
+/// The lines of `from` importing `to`. Most harnesses don't keep where an
+/// import was written, so the statement is found by what it names: the
+/// imported path or name, else the imported file's name.
+fn statements(
+    from: &str,
+    to: &str,
+    imports: &[&EdgeIR],
+    sources: &mut HashMap<String, Option<String>>,
+    read: &impl Fn(&str) -> Option<String>,
+) -> Vec<ImportStatement> {
+    let source = sources.entry(from.to_string()).or_insert_with(|| read(from));
+    let file_name = to.rsplit('/').next().unwrap_or(to);
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+    // `pkg/index.ts`, `pkg/__init__.py` and `pkg/mod.rs` are imported by their directory's name
+    let stem = match stem {
+        "index" | "__init__" | "mod" => to.rsplit('/').nth(1).unwrap_or(stem),
+        _ => stem,
+    };
+
+    let mut statements: Vec<ImportStatement> = Vec::new();
+    for import in imports {
+        let named = import.dst.as_deref().filter(|dst| !dst.is_empty());
+        let last_segment = named.and_then(|dst| dst.rsplit(['.', ':', '/']).next());
+        let needles: Vec<&str> = [named, last_segment, Some(stem)].into_iter().flatten().collect();
+        let found = source.as_deref().and_then(|source| {
+            let lines: Vec<(usize, &str)> =
+                source.lines().enumerate().filter(|(_, line)| is_import_line(line)).collect();
+            needles.iter().find_map(|needle| lines.iter().find(|(_, line)| line.contains(needle)).copied())
+        });
+        let statement = match found {
+            Some((line, text)) => ImportStatement {
+                file: from.to_string(),
+                line: Some(line as u32 + 1),
+                text: Some(text.trim().to_string()),
+            },
+            None => ImportStatement { file: from.to_string(), line: None, text: None },
+        };
+        if !statements.contains(&statement) {
+            statements.push(statement);
+        }
+    }
+    statements
+}
+
+fn is_import_line(line: &str) -> bool {
+    let line = line.trim_start();
+    ["import ", "from ", "use ", "pub use ", "#include", "using ", "export "]
+        .iter()
+        .any(|keyword| line.starts_with(keyword))
+        || line.contains("require(")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::Resolution;
+
+    fn import(from: &str, to: &str) -> EdgeIR {
+        EdgeIR {
+            edge_type: EdgeType::Imports,
+            src: None,
+            dst: None,
+            file_src: Some(from.to_string()),
+            file_dst: Some(to.to_string()),
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_break_set_cuts_lightest_links() {
+        // a <-> b twice over, b -> c -> a once
+        let arcs = BTreeMap::from([((0, 1), 3), ((1, 0), 1), ((1, 2), 2), ((2, 0), 1)]);
+        assert_eq!(break_set(&[0, 1, 2], &arcs), [(1, 0), (2, 0)]);
+
+        // A single back arc is enough for a ring
+        let ring = BTreeMap::from([((0, 1), 1), ((1, 2), 1), ((2, 3), 1), ((3, 0), 5)]);
+        let cut = break_set(&[0, 1, 2, 3], &ring);
+        assert_eq!(cut.len(), 1);
+        assert_ne!(cut, [(3, 0)]);
+    }
+
+    #[test]
+    fn test_analyze_lists_statements_to_remove() {
+        let edges = vec![
+            import("src/a.ts", "src/b.ts"),
+            import("src/a.ts", "src/b.ts"),
+            import("src/b.ts", "src/a.ts"),
+            import("src/b.ts", "src/c.ts"),
+            // Not part of any cycle
+            import("src/c.ts", "src/d.ts"),
+            import("src/a.ts", "src/a.ts"),
+        ];
+        let read = |file: &str| match file {
+            "src/b.ts" => Some("// uses a\nimport { c } from './c';\nimport { a } from './a';\n".to_string()),
+            _ => None,
+        };
+        let report = analyze("abc123", &[], &edges, read);
+
+        assert_eq!(report.cycles.len(), 1);
+        let cycle = &report.cycles[0];
+        assert_eq!(cycle.files, ["src/a.ts", "src/b.ts"]);
+        assert_eq!(cycle.links, 2);
+        assert_eq!(cycle.break_links.len(), 1);
+        let link = &cycle.break_links[0];
+        assert_eq!((link.from.as_str(), link.to.as_str(), link.imports), ("src/b.ts", "src/a.ts", 1));
+        assert_eq!(
+            link.statements,
+            [ImportStatement {
+                file: "src/b.ts".to_string(),
+                line: Some(3),
+                text: Some("import { a } from './a';".to_string()),
+            }]
+        );
+
+        let text = report.to_text(10);
+        assert!(text.contains("break src/b.ts -> src/a.ts (1 import)"));
+        assert!(text.contains("src/b.ts:3  import { a } from './a';"));
+    }
+}
@@ -21,6 +21,11 @@ pub trait LanguageStrategy {
     fn can_handle(&self, path: &Path) -> bool {
         !self.detect_files(path).is_empty()
     }
+    
+    /// Whether `run_indexer` is implemented for this language
+    fn has_indexer(&self) -> bool {
+        true
+    }
 }
 
 /// TypeScript/JavaScript strategy
@@ -145,6 +150,10 @@ impl LanguageStrategy for GoStrategy {
     fn name(&self) -> &'static str {
         "Go"
     }
+    
+    fn has_indexer(&self) -> bool {
+        false
+    }
 }
 
 /// Rust strategy for detecting and processing Rust projects
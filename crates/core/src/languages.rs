@@ -0,0 +1,111 @@
+use crate::fallback;
+use crate::language_strategy::LanguageStrategyRegistry;
+use protocol::{Capabilities, Language, Resolution};
+use serde::Serialize;
+
+/// One row of the capability matrix: a harness and the fidelity of the
+/// graph it builds
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageSupport {
+    #[serde(flatten)]
+    pub capabilities: Capabilities,
+    pub call_edges: bool,
+    pub type_edges: bool,
+    /// Whether `index scan --semantic` adds a SCIP indexer's resolved
+    /// references on top of the harness's own
+    pub semantic: bool,
+}
+
+/// A row per harness in `harnesses`, each with the languages its SCIP
+/// strategy is registered under, then the languages only the fallback
+/// indexer covers
+pub fn matrix(harnesses: Vec<(Capabilities, &[Language])>) -> Vec<LanguageSupport> {
+    let registry = LanguageStrategyRegistry::new();
+    let harnesses = harnesses.into_iter().map(|(capabilities, languages)| {
+        let semantic = languages
+            .iter()
+            .any(|language| registry.get_strategy(language.clone()).is_some_and(|s| s.has_indexer()));
+        (capabilities, semantic)
+    });
+    harnesses
+        .chain(fallback::capabilities().map(|capabilities| (capabilities, false)))
+        .map(|(capabilities, semantic)| LanguageSupport {
+            call_edges: capabilities.call_edges(),
+            type_edges: capabilities.type_edges(),
+            semantic,
+            capabilities,
+        })
+        .collect()
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "-" }
+}
+
+pub fn to_text(matrix: &[LanguageSupport]) -> String {
+    let mut out = format!(
+        "{:<22} {:<5} {:<5} {:<5} {:<22} {}\n",
+        "Language", "Calls", "Types", "Docs", "Resolution", "Edges"
+    );
+    for row in matrix {
+        let resolution = match (&row.capabilities.resolution, row.semantic) {
+            (Resolution::Heuristic, _) => "heuristic",
+            (_, true) => "syntactic (+semantic)",
+            (Resolution::Semantic, false) => "semantic",
+            (Resolution::Syntactic, false) => "syntactic",
+        };
+        let edges: Vec<String> = row.capabilities.edges.iter().map(|e| format!("{:?}", e)).collect();
+        out.push_str(&format!(
+            "{:<22} {:<5} {:<5} {:<5} {:<22} {}\n",
+            row.capabilities.name,
+            yes_no(row.call_edges),
+            yes_no(row.type_edges),
+            yes_no(row.capabilities.docs),
+            resolution,
+            edges.join(", ")
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{EdgeType, OccurrenceRole};
+
+    fn harness(name: &'static str, edges: &'static [EdgeType], docs: bool) -> Capabilities {
+        Capabilities {
+            name,
+            extensions: &["x"],
+            edges,
+            occurrences: &[OccurrenceRole::Definition],
+            docs,
+            resolution: Resolution::Syntactic,
+        }
+    }
+
+    #[test]
+    fn test_matrix_lists_harnesses_then_fallback() {
+        let matrix = matrix(vec![
+            (harness("Java", &[EdgeType::Calls, EdgeType::Extends], true), &[Language::Java]),
+            // Go has a strategy but no SCIP indexer behind it yet
+            (harness("Go", &[EdgeType::Calls], false), &[Language::Go]),
+            (harness("C#", &[EdgeType::Implements], true), &[Language::CSharp]),
+        ]);
+        let flags: Vec<(&str, bool, bool, bool)> =
+            matrix.iter().take(3).map(|r| (r.capabilities.name, r.call_edges, r.type_edges, r.semantic)).collect();
+        assert_eq!(flags, [("Java", true, true, true), ("Go", true, false, false), ("C#", false, true, false)]);
+
+        let ruby = matrix.iter().find(|row| row.capabilities.name == "ruby").unwrap();
+        assert_eq!(ruby.capabilities.resolution, Resolution::Heuristic);
+
+        let text = to_text(&matrix);
+        assert!(text.lines().any(|line| line.starts_with("ruby") && line.contains("heuristic")));
+        assert!(text.lines().any(|line| line.starts_with("Java ") && line.contains("syntactic (+semantic)")));
+
+        let json = serde_json::to_value(&matrix[0]).unwrap();
+        assert_eq!(json["name"], "Java");
+        assert_eq!(json["edges"], serde_json::json!(["Calls", "Extends"]));
+        assert_eq!(json["call_edges"], true);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::config::LayerConfig;
+use anyhow::{anyhow, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use protocol::SymbolIR;
+use std::collections::HashSet;
+
+/// Architecture layers a file can belong to, and which may depend on which
+#[derive(Debug, Clone, Default)]
+pub struct Layers {
+    layers: Vec<Layer>,
+}
+
+#[derive(Debug, Clone)]
+struct Layer {
+    name: String,
+    paths: GlobSet,
+    /// Indices of the layers this one may depend on, itself included
+    may_use: HashSet<usize>,
+}
+
+impl Layers {
+    /// Layers as configured, top down: unless a layer lists what it may use,
+    /// it may depend on itself and every layer below it
+    pub fn from_config(config: &[LayerConfig]) -> Result<Self> {
+        let index = |name: &str| config.iter().position(|layer| layer.name == name);
+        let mut layers = Vec::new();
+        for (i, layer) in config.iter().enumerate() {
+            if index(&layer.name) != Some(i) {
+                return Err(anyhow!("Layer '{}' is declared twice", layer.name));
+            }
+            let mut paths = GlobSetBuilder::new();
+            for path in &layer.paths {
+                paths.add(Glob::new(path).map_err(|e| anyhow!("Bad path '{}' in layer '{}': {}", path, layer.name, e))?);
+            }
+            let mut may_use: HashSet<usize> = match &layer.may_use {
+                Some(names) => names
+                    .iter()
+                    .map(|name| {
+                        index(name).ok_or_else(|| anyhow!("Layer '{}' may use unknown layer '{}'", layer.name, name))
+                    })
+                    .collect::<Result<_>>()?,
+                None => (i..config.len()).collect(),
+            };
+            may_use.insert(i);
+            layers.push(Layer { name: layer.name.clone(), paths: paths.build()?, may_use });
+        }
+        Ok(Self { layers })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    fn index_of(&self, path: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.paths.is_match(path))
+    }
+
+    /// Layer of a repo-relative file; the first declared layer wins when
+    /// several match
+    pub fn layer_of(&self, path: &str) -> Option<&str> {
+        self.index_of(path).map(|i| self.layers[i].name.as_str())
+    }
+
+    /// Why a dependency from `file_src` on `file_dst` breaks the layering,
+    /// or `None` when it doesn't (including files outside every layer)
+    pub fn violation(&self, file_src: &str, file_dst: &str) -> Option<String> {
+        let (from, to) = (self.index_of(file_src)?, self.index_of(file_dst)?);
+        if self.layers[from].may_use.contains(&to) {
+            return None;
+        }
+        let mut allowed: Vec<usize> = self.layers[from].may_use.iter().copied().filter(|&i| i != from).collect();
+        allowed.sort();
+        let allowed: Vec<&str> = allowed.iter().map(|&i| self.layers[i].name.as_str()).collect();
+        Some(format!(
+            "{} may not depend on {}{}",
+            self.layers[from].name,
+            self.layers[to].name,
+            match allowed.len() {
+                0 => " (it may not depend on other layers)".to_string(),
+                _ => format!(" (only on {})", allowed.join(", ")),
+            }
+        ))
+    }
+
+    /// `(symbol id, layer)` for every symbol in a layer
+    pub fn assign(&self, symbols: &[SymbolIR]) -> Vec<(String, String)> {
+        symbols
+            .iter()
+            .filter_map(|symbol| Some((symbol.id.clone(), self.layer_of(&symbol.file_path)?.to_string())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(name: &str, path: &str, may_use: Option<&[&str]>) -> LayerConfig {
+        LayerConfig {
+            name: name.to_string(),
+            paths: vec![path.to_string()],
+            may_use: may_use.map(|names| names.iter().map(|n| n.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn test_layer_ordering() {
+        let layers = Layers::from_config(&[
+            layer("web", "src/web/**", None),
+            layer("app", "src/app/**", None),
+            layer("domain", "src/domain/**", None),
+        ])
+        .unwrap();
+        assert_eq!(layers.layer_of("src/app/orders.py"), Some("app"));
+        assert_eq!(layers.layer_of("scripts/seed.py"), None);
+
+        assert_eq!(layers.violation("src/web/views.py", "src/domain/order.py"), None);
+        assert_eq!(layers.violation("src/app/orders.py", "src/app/cart.py"), None);
+        assert_eq!(
+            layers.violation("src/domain/order.py", "src/app/orders.py").as_deref(),
+            Some("domain may not depend on app (it may not depend on other layers)")
+        );
+        assert_eq!(
+            layers.violation("src/app/orders.py", "src/web/views.py").as_deref(),
+            Some("app may not depend on web (only on domain)")
+        );
+        assert_eq!(layers.violation("scripts/seed.py", "src/web/views.py"), None);
+    }
+
+    #[test]
+    fn test_explicit_may_use() {
+        let layers = Layers::from_config(&[
+            layer("infra", "src/infra/**", Some(&["domain"])),
+            layer("app", "src/app/**", Some(&["domain"])),
+            layer("domain", "src/domain/**", Some(&[])),
+        ])
+        .unwrap();
+        assert!(layers.violation("src/infra/db.py", "src/app/orders.py").is_some());
+        assert!(layers.violation("src/infra/db.py", "src/domain/order.py").is_none());
+
+        assert!(Layers::from_config(&[layer("a", "a/**", Some(&["b"]))]).is_err());
+        assert!(Layers::from_config(&[layer("a", "a/**", None), layer("a", "b/**", None)]).is_err());
+    }
+}
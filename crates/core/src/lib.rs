@@ -1,4 +1,28 @@
 pub mod language_strategy;
 pub mod resolution;
 pub mod metrics;
-pub mod walker;
\ No newline at end of file
+pub mod walker;
+pub mod telemetry;
+pub mod cancel;
+pub mod source;
+pub mod sync;
+pub mod output;
+pub mod check;
+pub mod diff;
+pub mod annotations;
+pub mod sarif;
+pub mod reviewers;
+pub mod risk;
+pub mod churn;
+pub mod throwers;
+pub mod import_cycles;
+pub mod config;
+pub mod layers;
+pub mod diagram;
+pub mod entry_points;
+pub mod fallback;
+pub mod languages;
+pub mod tour;
+pub mod implementations;
+pub mod deprecations;
+pub mod events;
@@ -1,26 +1,85 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
-use store::GraphStore;
-use tracing::{info, Level};
-use tracing_subscriber::FmtSubscriber;
-use ts_harness::TypeScriptHarness;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use store::{FileSummary, FqnSeparators, GraphStore, NeighborhoodLimits, NoiseFilter, NoiseMode, ParseQuality, SearchFilter};
+use protocol::path::{normalize_path, relative_path};
+use protocol::generated::is_generated_file;
+use protocol::{EdgeType, Language, SymbolFlags, SymbolKind};
+use serde_json::json;
+use tracing::{info, info_span, warn, Instrument};
+use ts_harness::{ImportResolver, TypeScriptHarness};
 use py_harness::PythonHarness;
 use go_harness::GoHarness;
-use rust_harness::RustHarness;
-use java_harness::JavaHarness;
-use cpp_harness::CppHarness;
+use rust_harness::{CargoWorkspace, RustHarness};
+use java_harness::{JavaHarness, ModuleGraph, SourceRoots};
+use cpp_harness::{CppConfig, CppHarness};
 use csharp_harness::CSharpHarness;
 
 mod walker;
-use walker::FileWalker;
+use walker::{FileWalker, Shard};
 
 mod resolution;
 use resolution::ResolutionEngine;
 
 mod language_strategy;
 mod metrics;
-use metrics::MetricsCollector;
+use metrics::{MetricsCollector, PerformanceMetrics};
+
+mod telemetry;
+use telemetry::LogFormat;
+
+mod cancel;
+use cancel::{CancellationToken, CANCELLED_EXIT_CODE};
+
+mod source;
+
+mod sync;
+
+mod output;
+use output::{print_json, Output, OutputArgs};
+
+mod check;
+use check::{CheckOptions, DependencyRule, CHECK_FAILED_EXIT_CODE};
+
+mod diff;
+use diff::ChangedLines;
+
+mod annotations;
+use annotations::AnnotationFormat;
+
+mod sarif;
+
+mod reviewers;
+use reviewers::CodeOwners;
+
+mod risk;
+
+mod churn;
+mod throwers;
+mod import_cycles;
+mod tour;
+mod implementations;
+mod deprecations;
+mod events;
+use events::FileEvents;
+
+mod config;
+use config::Config;
+
+mod layers;
+use layers::Layers;
+
+mod diagram;
+use diagram::{DiagramFormat, GroupBy};
+
+mod entry_points;
+use entry_points::EntryKind;
+
+mod fallback;
+mod languages;
+use fallback::FallbackIndexer;
 
 #[derive(Parser)]
 #[command(name = "reviewbot")]
@@ -31,22 +90,90 @@ struct Cli {
     
     #[arg(long, global = true)]
     repo: Option<PathBuf>,
+    
+    /// Log output format; `json` emits one JSON object per event for CI
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+    
+    /// Export tracing spans to this OTLP/gRPC collector (requires the `otel` feature).
+    /// Defaults to `OTEL_EXPORTER_OTLP_ENDPOINT` when set.
+    #[arg(long, global = true)]
+    otlp_endpoint: Option<String>,
+    
+    /// Drop or collapse calls into the standard library in graph queries
+    /// (`keep`, `drop` or `collapse`); overrides `call_noise` in reviewbot.json
+    #[arg(long, global = true)]
+    call_noise: Option<NoiseMode>,
+    
+    #[command(flatten)]
+    output: OutputArgs,
 }
 
 #[derive(Subcommand)]
-enum GraphCommands {
-    Stats,
-    Cycles {
-        symbol: String,
+enum Commands {
+    /// Build, move and combine snapshots of the graph
+    Index {
+        #[command(subcommand)]
+        cmd: IndexCommands,
     },
-    Path {
-        from: String,
-        to: String,
+    
+    /// Read symbols, identifiers and graph structure from the latest snapshot
+    Query {
+        #[command(subcommand)]
+        cmd: QueryCommands,
+    },
+    
+    /// Gate CI on the graph: exits with status 3 and a report when a check fails
+    Check {
+        /// Snapshot to check; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Snapshot to compare against, e.g. the merge base of a pull request
+        #[arg(long)]
+        base: Option<String>,
+        
+        /// Fail when there are more dependency cycles than this (0 forbids any);
+        /// with --base only new cycles count
+        #[arg(long)]
+        max_cycles: Option<usize>,
+        
+        /// Forbid dependencies between files, e.g. `src/ui/**->src/db/**`; repeatable
+        #[arg(long)]
+        forbid: Vec<DependencyRule>,
+        
+        /// Fail when public symbols of --base were removed or changed signature
+        #[arg(long, requires = "base")]
+        api_breaks: bool,
+        
+        /// Fail when less than this share (0-1) of references resolve to a known symbol
+        #[arg(long)]
+        min_resolution: Option<f64>,
+        
+        /// Report symbols changed since --base that at least this many symbols depend on
+        #[arg(long, requires = "base")]
+        hotspot_fan_in: Option<usize>,
+        
+        /// Report private functions and types no entry point or public API reaches
+        #[arg(long)]
+        dead_code: bool,
+        
+        /// Report functions whose bodies duplicate another's, from this many lines
+        #[arg(long, value_name = "MIN_LINES")]
+        duplicates: Option<usize>,
+        
+        /// Fail on dependencies against the layer ordering in reviewbot.json
+        #[arg(long)]
+        layers: bool,
+        
+        /// Print findings as GitHub annotations or SARIF instead of the report
+        #[arg(long, value_enum)]
+        format: Option<AnnotationFormat>,
     },
 }
 
 #[derive(Subcommand)]
-enum Commands {
+enum IndexCommands {
     Scan {
         #[arg(long)]
         no_semantic: bool,
@@ -68,8 +195,57 @@ enum Commands {
         
         #[arg(long, value_delimiter = ',')]
         lang: Vec<String>,
+        
+        /// Follow symlinked files and directories (cycles are skipped)
+        #[arg(long)]
+        follow_symlinks: bool,
+        
+        /// Index C/C++ under a named define set, e.g. `linux:__linux__,HAVE_EPOLL=1`.
+        /// Repeat for several configurations; symbols are tagged with the ones they exist in.
+        #[arg(long = "cpp-config")]
+        cpp_configs: Vec<CppConfig>,
+        
+        /// Also index Java parameters and local variables, with their reads and writes
+        #[arg(long)]
+        java_locals: bool,
+        
+        /// Only index slice K of N (e.g. `2/8`), split by directory, for a scan spread
+        /// over several machines; combine the exported bundles with `index merge`
+        #[arg(long)]
+        shard: Option<Shard>,
+        
+        /// Only (re)index this directory, relative to the repository root, into the
+        /// current commit's snapshot; edges crossing its boundary that lead nowhere
+        /// are marked dangling
+        #[arg(long, conflicts_with_all = ["incremental", "shard"])]
+        path: Option<String>,
+    },
+    
+    Export {
+        #[command(subcommand)]
+        cmd: ExportCommands,
+    },
+    
+    Import {
+        #[command(subcommand)]
+        cmd: ImportCommands,
     },
     
+    /// Combine the bundles of a sharded scan (`index scan --shard`) into one snapshot
+    Merge {
+        #[arg(required = true)]
+        bundles: Vec<PathBuf>,
+    },
+    
+    /// Share snapshots through a remote instead of rescanning on every machine
+    Sync {
+        #[command(subcommand)]
+        cmd: SyncCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum QueryCommands {
     Show {
         #[arg(long)]
         symbol: String,
@@ -85,6 +261,10 @@ enum Commands {
         
         #[arg(long, default_value = "1")]
         depth: usize,
+        
+        /// Only match a symbol in this language
+        #[arg(long)]
+        lang: Option<Language>,
     },
     
     Search {
@@ -95,32 +275,386 @@ enum Commands {
         
         #[arg(long)]
         hybrid: bool,
+        
+        /// Only symbols of these kinds, e.g. `function,class`
+        #[arg(long, value_delimiter = ',')]
+        kind: Vec<SymbolKind>,
+        
+        /// Only symbols in these languages, e.g. `ts,py`
+        #[arg(long, value_delimiter = ',')]
+        lang: Vec<Language>,
+        
+        /// Only symbols in files matching this glob, e.g. `src/*.ts`
+        #[arg(long)]
+        file: Option<String>,
+        
+        #[arg(long)]
+        visibility: Option<String>,
+        
+        /// Only functions whose return type matches this glob, e.g. `Result<*, io::Error>`
+        #[arg(long)]
+        returns: Option<String>,
+        
+        /// Only symbols carrying all of these flags, e.g. `deprecated`
+        #[arg(long = "flag", value_delimiter = ',')]
+        flags: Vec<SymbolFlags>,
+        
+        /// Hide symbols carrying any of these flags, e.g. `test-only,generated`
+        #[arg(long = "exclude-flag", value_delimiter = ',')]
+        exclude_flags: Vec<SymbolFlags>,
+        
+        /// Only symbols in this architecture layer (see `layers` in reviewbot.json)
+        #[arg(long)]
+        layer: Option<String>,
+        
+        /// Only entry points of this kind, e.g. `http`
+        #[arg(long, value_enum)]
+        entry_point: Option<EntryKind>,
     },
     
-    Graph {
-        #[command(subcommand)]
-        cmd: GraphCommands,
+    /// Find identifier occurrences by token, with the symbol each one
+    /// refers to and the symbol it appears in
+    Grep {
+        query: String,
+        
+        #[arg(long, default_value = "50")]
+        k: usize,
+    },
+    
+    /// Per-line definition and reference counts of a file, for gutter
+    /// indicators of how heavily each region is used elsewhere
+    Heatmap {
+        file: String,
+    },
+    
+    /// Node and edge counts of the whole graph
+    Stats,
+    
+    /// Dependency cycles running through a symbol
+    Cycles {
+        symbol: String,
+    },
+    
+    /// Shortest dependency path between two symbols
+    Path {
+        from: String,
+        to: String,
+    },
+    
+    /// Symbols within a few edges of a symbol and the edges between them
+    Neighborhood {
+        symbol: String,
+        
+        #[arg(long, default_value = "1")]
+        radius: usize,
+        
+        /// Only follow these edge types, e.g. `calls,imports`
+        #[arg(long, value_delimiter = ',')]
+        edges: Vec<EdgeType>,
+        
+        #[arg(long, default_value = "200")]
+        max_nodes: usize,
+        
+        #[arg(long, default_value = "1000")]
+        max_edges: usize,
+    },
+    
+    /// Rank people and teams to review a change by CODEOWNERS and blame of
+    /// the symbols it touches and their direct dependents
+    SuggestReviewers {
+        /// Unified diff of the change (`git diff` output); `-` reads stdin
+        diff: PathBuf,
+        
+        /// Snapshot the diff applies to; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Revision to blame, e.g. the pull request's base so its author
+        /// doesn't come first
+        #[arg(long, default_value = "HEAD")]
+        rev: String,
+        
+        /// Leave out reviewers whose handle, name or email contains this; repeatable
+        #[arg(long)]
+        exclude: Vec<String>,
+        
+        #[arg(long, default_value = "5")]
+        k: usize,
+    },
+    
+    /// Which people and teams own the most depended-upon code, by
+    /// CODEOWNERS and blame weighted by fan-in (bus-factor analysis)
+    Ownership {
+        /// Snapshot to report on; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Revision to blame
+        #[arg(long, default_value = "HEAD")]
+        rev: String,
+        
+        /// Leave out symbols with fewer dependents than this
+        #[arg(long, default_value = "1")]
+        min_fan_in: usize,
+        
+        #[arg(long, default_value = "20")]
+        k: usize,
+    },
+    
+    /// Score how risky a change is from the centrality, dependents, test
+    /// reach, churn and cycles of the symbols it touches
+    Risk {
+        /// Unified diff of the change (`git diff` output); `-` reads stdin
+        diff: PathBuf,
+        
+        /// Snapshot the diff applies to; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Count commits to the touched files over this many days for churn
+        #[arg(long, default_value = "90")]
+        churn_days: u32,
+        
+        /// Symbols listed in the breakdown
+        #[arg(long, default_value = "10")]
+        k: usize,
+    },
+    
+    /// Symbols whose signature changes most often across the scanned
+    /// history while many others depend on them
+    Churn {
+        /// Last snapshot of the history; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Leave out symbols with fewer dependents than this
+        #[arg(long, default_value = "5")]
+        min_fan_in: usize,
+        
+        #[arg(long, default_value = "20")]
+        k: usize,
+    },
+    
+    /// Call sites still using deprecated APIs, grouped by CODEOWNERS team,
+    /// with each team's count across the latest snapshots
+    Deprecations {
+        /// Last snapshot of the trend; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Snapshots the trend covers, ending with `--commit`
+        #[arg(long, default_value = "10")]
+        snapshots: usize,
+        
+        /// Call sites listed per team, and APIs listed
+        #[arg(long, default_value = "20")]
+        k: usize,
+    },
+    
+    /// Event and message topics with the symbols publishing and
+    /// subscribing to each, from `emit("x")`/`on("x")`-style calls
+    Events {
+        /// Only topics whose name contains this
+        topic: Option<String>,
+        
+        /// Snapshot to search; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+    },
+    
+    /// Methods that declare or throw an exception type or a subclass of it
+    Throwers {
+        /// Exception type, simple or qualified: `IOException`, `java.io.IOException`
+        exception: String,
+        
+        /// Snapshot to search; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        #[arg(long, default_value = "50")]
+        k: usize,
+    },
+    
+    /// Files importing each other in cycles, with the imports to remove to
+    /// break each cycle
+    ImportCycles {
+        /// Snapshot to search; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Cycles shown, largest first
+        #[arg(long, default_value = "20")]
+        k: usize,
+    },
+    
+    /// Dependencies between files, directories or packages, weighted by the
+    /// number of references behind them
+    Deps {
+        /// Snapshot to report on; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        #[arg(long, value_enum, default_value = "dir")]
+        by: GroupBy,
+        
+        /// Directory components kept with `--by dir`, e.g. 2 for `src/api`
+        #[arg(long)]
+        depth: Option<usize>,
+        
+        /// Hide dependencies backed by fewer references than this
+        #[arg(long, default_value = "1")]
+        min_weight: usize,
+        
+        #[arg(long, value_enum, default_value = "text")]
+        format: DiagramFormat,
+    },
+    
+    /// Per-directory totals of symbols, imports and complexity, read from
+    /// the file summaries kept at scan time
+    Summary {
+        /// Snapshot to report on; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Only files under this directory
+        #[arg(long)]
+        path: Option<String>,
+        
+        /// Directory components kept, e.g. 2 for `src/api`
+        #[arg(long)]
+        depth: Option<usize>,
+    },
+    
+    /// What each language's harness extracts: call and type edges, doc
+    /// comments and how references are resolved
+    Languages,
+    
+    /// Reading list for a feature: the symbols reachable through calls
+    /// from an entry point, in the order to read them, with their docs
+    Tour {
+        /// FQN or name of the symbol to start from, e.g. `OrderService.checkout`
+        entrypoint: String,
+        
+        /// Snapshot to walk; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Calls to follow away from the entry point
+        #[arg(long, default_value = "4")]
+        depth: usize,
+        
+        #[arg(long, default_value = "40")]
+        max_stops: usize,
+    },
+    
+    /// Types implementing an interface or trait, directly or through
+    /// subtypes, and the required methods each lacks; without an interface,
+    /// every implementor missing methods
+    Implementations {
+        /// FQN or simple name, e.g. `com.shop.PaymentGateway` or `Iterator`
+        interface: Option<String>,
+        
+        /// Snapshot to search; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SyncCommands {
+    /// Upload a snapshot, as a delta against one the remote already has
+    Push {
+        /// Directory, `http://host/prefix` or `s3://bucket/prefix`; defaults to `REVIEWBOT_REMOTE`
+        #[arg(long)]
+        remote: Option<String>,
+        
+        /// Commit to push; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Upload the whole snapshot even when a delta base exists
+        #[arg(long)]
+        full: bool,
+    },
+    
+    /// Download a snapshot (the newest pushed one by default) and its delta bases
+    Pull {
+        #[arg(long)]
+        remote: Option<String>,
+        
+        #[arg(long)]
+        commit: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportCommands {
+    /// Canonical JSON lines dump of one snapshot (byte-identical across rescans)
+    Jsonl {
+        /// Commit to export; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        /// Output file; defaults to stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    
+    /// Single-file copy of one snapshot, loadable elsewhere with `index import bundle`
+    Bundle {
+        /// Commit to export; defaults to the last scanned commit
+        #[arg(long)]
+        commit: Option<String>,
+        
+        #[arg(long)]
+        output: PathBuf,
+        
+        /// Keep the full-text indexes so the bundle can be queried once unpacked
+        #[arg(long)]
+        with_fts: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ImportCommands {
+    /// Load a snapshot written by `index export bundle`, replacing any earlier copy of it
+    Bundle {
+        path: PathBuf,
     },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)?;
-    
     let cli = Cli::parse();
     
+    let otlp_endpoint = cli.otlp_endpoint.clone()
+        .or_else(|| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok());
+    let output = cli.output.mode();
+    // Annotations and diagrams are read off stdout too
+    let annotating = matches!(cli.command, Commands::Check { format: Some(_), .. })
+        || matches!(
+            cli.command,
+            Commands::Query { cmd: QueryCommands::Deps { format: DiagramFormat::Dot | DiagramFormat::Mermaid, .. } }
+        );
+    let telemetry = telemetry::init(
+        cli.log_format,
+        otlp_endpoint.as_deref(),
+        output.is_machine_readable() || annotating,
+    )?;
+    
     let repo_root = cli.repo.unwrap_or_else(|| {
         std::env::current_dir().expect("Failed to get current directory")
     });
     
     match cli.command {
-        Commands::Scan { no_write, semantic, no_semantic, incremental, .. } => {
+        Commands::Index { cmd: IndexCommands::Scan { no_write, semantic, no_semantic, incremental, follow_symlinks, cpp_configs, java_locals, shard, path, .. } } => {
             let mut metrics = MetricsCollector::new();
             metrics.start_phase("initialization");
             
+            // Ctrl-C / SIGTERM stop the scan after the current file
+            let cancel = CancellationToken::new();
+            cancel::install_signal_handlers(cancel.clone());
+            
             if no_write {
                 info!("Running scan in dry-run mode (--no-write)");
             }
@@ -133,6 +667,12 @@ async fn main() -> Result<()> {
             
             let commit_sha = get_current_commit(&repo_root)?;
             info!("Scanning repository at commit: {}", commit_sha);
+            let subtree = path.map(|path| normalize_path(path.trim_end_matches('/')));
+            if subtree.as_deref().is_some_and(|s| s.is_empty() || s == ".") {
+                return Err(anyhow::anyhow!("--path must name a directory below the repository root"));
+            }
+            // Parent of the per-stage spans exported over OTLP
+            let scan_span = info_span!("scan", commit = %commit_sha);
             
             metrics.end_phase("initialization");
             metrics.update_memory_usage();
@@ -141,10 +681,24 @@ async fn main() -> Result<()> {
             metrics.start_phase("file_discovery");
             let mut files_to_process = Vec::new();
             let mut incremental = false;
+            // Files already indexed by an interrupted scan of this commit
+            let mut resumed: Option<Vec<String>> = None;
             
-            if !no_write {
+            // Whether the commit already has a finished snapshot for a subtree
+            // scan to update; otherwise the rest is left for a full scan
+            let mut complete_snapshot = false;
+            
+            if let (false, Some(subtree)) = (no_write, &subtree) {
+                let store = GraphStore::new(&repo_root)?;
+                complete_snapshot = store.snapshot_commits()?.contains(&commit_sha);
+                resumed = store.get_checkpoint(&commit_sha)?;
+                info!("Rescanning {} only", subtree);
+            } else if !no_write {
                 let store = GraphStore::new(&repo_root)?;
-                if let Some(last_commit) = store.get_last_scanned_commit()? {
+                if let Some(done) = store.get_checkpoint(&commit_sha)? {
+                    info!("Resuming interrupted scan: {} files already indexed", done.len());
+                    resumed = Some(done);
+                } else if let Some(last_commit) = store.get_last_scanned_commit()? {
                     if last_commit != commit_sha {
                         // Get changed files since last scan
                         let changed = get_changed_files(&repo_root, &last_commit, &commit_sha)?;
@@ -152,7 +706,7 @@ async fn main() -> Result<()> {
                             info!("Incremental scan: {} files changed since {}", changed.len(), &last_commit[0..7]);
                             
                             // Get impacted files (files that import changed files)
-                            let mut impacted = std::collections::HashSet::new();
+                            let mut impacted = std::collections::BTreeSet::new();
                             for file in &changed {
                                 impacted.insert(file.clone());
                                 for dependent in store.get_file_dependents(file)? {
@@ -168,7 +722,11 @@ async fn main() -> Result<()> {
                         }
                     } else {
                         info!("Repository unchanged since last scan");
-                        println!("Repository unchanged since last scan");
+                        output.summary(
+                            "Repository unchanged since last scan",
+                            &[&commit_sha],
+                            json!({ "commit": commit_sha, "action": "unchanged" }),
+                        )?;
                         return Ok(());
                     }
                 }
@@ -176,273 +734,297 @@ async fn main() -> Result<()> {
             
             // If not incremental, walk all files
             if !incremental {
-                let walker = FileWalker::new(repo_root.clone());
-                files_to_process = walker.walk()?;
+                let walker = FileWalker::new(repo_root.clone()).with_follow_symlinks(follow_symlinks);
+                files_to_process = info_span!(parent: &scan_span, "walk").in_scope(|| walker.walk())?;
+            }
+            
+            if let Some(subtree) = &subtree {
+                files_to_process.retain(|f| {
+                    let path = relative_path(&repo_root, f);
+                    path.strip_prefix(subtree.as_str()).is_some_and(|rest| rest.starts_with('/'))
+                });
+            }
+            
+            if let Some(shard) = shard {
+                let total = files_to_process.len();
+                files_to_process.retain(|f| shard.contains(&relative_path(&repo_root, f)));
+                info!("Shard {}/{}: {} of {} files", shard.index, shard.count, files_to_process.len(), total);
+            }
+            
+            if let (Some(done), None) = (&resumed, &subtree) {
+                let done: std::collections::HashSet<&str> = done.iter().map(|f| f.as_str()).collect();
+                files_to_process.retain(|f| {
+                    !done.contains(relative_path(&repo_root, f).as_str())
+                });
             }
             
             metrics.end_phase("file_discovery");
             metrics.update_memory_usage();
             
-            if files_to_process.is_empty() {
-                println!("No files found to index");
+            // A resumed scan may have nothing left but still needs finishing,
+            // and a subtree emptied since the last scan still needs clearing
+            if files_to_process.is_empty() && resumed.is_none() && subtree.is_none() {
+                output.summary(
+                    "No files found to index",
+                    &[],
+                    json!({ "commit": commit_sha, "action": "empty", "files": 0 }),
+                )?;
                 return Ok(());
             }
             
             if !no_write {
                 metrics.start_phase("syntactic_analysis");
                 let store = GraphStore::new(&repo_root)?;
-                let commit_id = store.create_commit_snapshot(&commit_sha)?;
+                // Readers only see this commit once every file has been written
+                let snapshot = store.begin_snapshot(&commit_sha)?;
+                let commit_id = snapshot.commit_id();
                 
-                let mut ts_harness = TypeScriptHarness::new()?;
+                let mut ts_harness = TypeScriptHarness::new()?.with_resolver(ImportResolver::detect(&repo_root));
                 let mut py_harness = PythonHarness::new()?;
                 let mut go_harness = GoHarness::new()?;
-                let mut rust_harness = RustHarness::new()?;
-                let mut java_harness = JavaHarness::new()?;
-                let mut cpp_harness = CppHarness::new_cpp()?;
-                let mut c_harness = CppHarness::new_c()?;
+                let cargo_workspace = CargoWorkspace::detect(&repo_root);
+                let mut rust_harness = RustHarness::new()?.with_workspace(cargo_workspace.clone());
+                let modules = ModuleGraph::detect(&repo_root);
+                let mut java_harness = JavaHarness::new()?
+                    .with_source_roots(SourceRoots::detect(&repo_root))
+                    .with_modules(modules.clone())
+                    .with_local_symbols(java_locals);
+                let mut cpp_harness = CppHarness::new_cpp()?.with_configs(cpp_configs.clone());
+                let mut c_harness = CppHarness::new_c()?.with_configs(cpp_configs.clone());
                 let mut csharp_harness = CSharpHarness::new()?;
+                let fallback = FallbackIndexer::new();
+                let layers = Layers::from_config(&Config::load(&repo_root)?.layers)?;
                 let mut total_symbols = 0;
                 let mut total_edges = 0;
                 let mut total_lines = 0;
                 
-                // If incremental, delete old data for files we're reprocessing
-                if incremental {
+                // If incremental or resuming, delete old (possibly partial) data
+                // for files we're reprocessing
+                if let Some(subtree) = &subtree {
+                    let cleared = store.clear_subtree_data(commit_id, subtree)?;
+                    info!("Cleared {} previously indexed files under {}", cleared, subtree);
+                } else if incremental || resumed.is_some() {
                     for file_path in &files_to_process {
-                        if let Ok(relative_path) = file_path.strip_prefix(&repo_root) {
-                            let path_str = relative_path.to_string_lossy();
-                            store.delete_file_data(commit_id, &path_str)?;
-                        }
+                        store.delete_file_data(commit_id, &relative_path(&repo_root, file_path))?;
                     }
                 }
                 
+                // Build modules and crates are re-derived from the build scripts on every scan
+                let (mut module_symbols, mut module_edges) = modules.to_ir();
+                let (crate_symbols, crate_edges) = cargo_workspace.to_ir();
+                module_symbols.extend(crate_symbols);
+                module_edges.extend(crate_edges);
+                for module in modules.modules() {
+                    store.delete_file_data(commit_id, &module.build_file)?;
+                }
+                for krate in cargo_workspace.crates() {
+                    store.delete_file_data(commit_id, &krate.manifest)?;
+                }
+                for symbol in &module_symbols {
+                    store.insert_symbol(commit_id, symbol)?;
+                }
+                for edge in &module_edges {
+                    store.insert_edge(commit_id, edge)?;
+                }
+                
                 // Process each file
+                let mut completed = Vec::new();
+                let mut file_events = Vec::new();
+                let mut undeclared_imports = 0;
                 for file_path in &files_to_process {
-                    let relative_path = file_path.strip_prefix(&repo_root)
-                        .unwrap_or(file_path)
-                        .to_string_lossy()
-                        .to_string();
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+                    
+                    let relative_path = relative_path(&repo_root, file_path);
+                    let _file_span = info_span!(parent: &scan_span, "index_file", file = %relative_path).entered();
                     
-                    let content = std::fs::read_to_string(file_path)?;
+                    let source = source::read_source(file_path)?;
+                    if source.lossy {
+                        warn!("{} could not be fully decoded as {}, undecodable bytes were replaced", relative_path, source.encoding);
+                    }
+                    let encoding = source.encoding;
+                    let mut quality = if source.lossy { ParseQuality::Lossy } else { ParseQuality::Syntactic };
+                    let content = source.text;
                     let hash = FileWalker::compute_file_hash(&content);
                     let lines = content.lines().count();
                     total_lines += lines;
                     
                     // Store file information
                     store.insert_file(commit_id, &relative_path, &hash, content.len())?;
+                    store.set_file_encoding(commit_id, &relative_path, encoding)?;
                     
-                    // Parse TypeScript/JavaScript files
-                    if relative_path.ends_with(".ts") || relative_path.ends_with(".tsx") ||
+                    let parse_start = Instant::now();
+                    let parsed = if relative_path.ends_with(".ts") || relative_path.ends_with(".tsx") ||
                        relative_path.ends_with(".js") || relative_path.ends_with(".jsx") {
-                        let (symbols, edges, occurrences) = ts_harness.parse_file(
-                            &content,
-                            &relative_path,
-                            &commit_sha
-                        )?;
-                        
-                        // Store symbols
-                        for symbol in &symbols {
-                            store.insert_symbol(commit_id, symbol)?;
-                        }
-                        
-                        // Store edges
-                        for edge in &edges {
-                            store.insert_edge(commit_id, edge)?;
-                        }
-                        
-                        // Store occurrences
-                        for occurrence in &occurrences {
-                            store.insert_occurrence(commit_id, occurrence)?;
-                        }
-                        
-                        total_symbols += symbols.len();
-                        total_edges += edges.len();
-                    }
-                    // Parse Python files
-                    else if relative_path.ends_with(".py") {
-                        let (symbols, edges, occurrences) = py_harness.parse_file(
-                            &content,
-                            &relative_path,
-                            &commit_sha
-                        )?;
-                        
-                        // Store symbols
-                        for symbol in &symbols {
-                            store.insert_symbol(commit_id, symbol)?;
-                        }
-                        
-                        // Store edges
-                        for edge in &edges {
-                            store.insert_edge(commit_id, edge)?;
-                        }
-                        
-                        // Store occurrences
-                        for occurrence in &occurrences {
-                            store.insert_occurrence(commit_id, occurrence)?;
-                        }
-                        
-                        total_symbols += symbols.len();
-                        total_edges += edges.len();
-                    }
-                    // Parse Go files
-                    else if relative_path.ends_with(".go") {
-                        let (symbols, edges, occurrences) = go_harness.parse_file(
-                            &content,
-                            &relative_path,
-                            &commit_sha
-                        )?;
-                        
-                        // Store symbols
-                        for symbol in &symbols {
-                            store.insert_symbol(commit_id, symbol)?;
-                        }
-                        
-                        // Store edges
-                        for edge in &edges {
-                            store.insert_edge(commit_id, edge)?;
-                        }
-                        
-                        // Store occurrences
-                        for occurrence in &occurrences {
-                            store.insert_occurrence(commit_id, occurrence)?;
-                        }
-                        
-                        total_symbols += symbols.len();
-                        total_edges += edges.len();
-                    }
-                    // Parse Rust files
-                    else if relative_path.ends_with(".rs") {
-                        let (symbols, edges, occurrences) = rust_harness.parse(
-                            &relative_path,
-                            &content
-                        )?;
-                        
-                        // Store symbols
-                        for symbol in &symbols {
-                            store.insert_symbol(commit_id, symbol)?;
-                        }
-                        
-                        // Store edges
-                        for edge in &edges {
-                            store.insert_edge(commit_id, edge)?;
-                        }
-                        
-                        // Store occurrences
-                        for occurrence in &occurrences {
-                            store.insert_occurrence(commit_id, occurrence)?;
-                        }
-                        
-                        total_symbols += symbols.len();
-                        total_edges += edges.len();
-                    }
-                    // Parse Java files
-                    else if relative_path.ends_with(".java") {
-                        let (symbols, edges, occurrences) = java_harness.parse(
-                            &relative_path,
-                            &content
-                        )?;
-                        
-                        // Store symbols
-                        for symbol in &symbols {
-                            store.insert_symbol(commit_id, symbol)?;
-                        }
-                        
-                        // Store edges
-                        for edge in &edges {
-                            store.insert_edge(commit_id, edge)?;
-                        }
-                        
-                        // Store occurrences
-                        for occurrence in &occurrences {
-                            store.insert_occurrence(commit_id, occurrence)?;
-                        }
-                        
-                        total_symbols += symbols.len();
-                        total_edges += edges.len();
-                    }
-                    // Parse C++ files
-                    else if relative_path.ends_with(".cpp") || relative_path.ends_with(".cc") 
+                        Some(("typescript", ts_harness.parse_file(&content, &relative_path, &commit_sha)?))
+                    } else if relative_path.ends_with(".py") {
+                        Some(("python", py_harness.parse_file(&content, &relative_path, &commit_sha)?))
+                    } else if relative_path.ends_with(".go") {
+                        Some(("go", go_harness.parse_file(&content, &relative_path, &commit_sha)?))
+                    } else if relative_path.ends_with(".rs") {
+                        Some(("rust", rust_harness.parse(&relative_path, &content)?))
+                    } else if relative_path.ends_with(".java") {
+                        Some(("java", java_harness.parse(&relative_path, &content)?))
+                    } else if relative_path.ends_with(".cpp") || relative_path.ends_with(".cc") 
                         || relative_path.ends_with(".cxx") || relative_path.ends_with(".hpp") 
                         || relative_path.ends_with(".hh") || relative_path.ends_with(".hxx") {
-                        let (symbols, edges, occurrences) = cpp_harness.parse(
-                            &relative_path,
-                            &content
-                        )?;
-                        
-                        // Store symbols
-                        for symbol in &symbols {
-                            store.insert_symbol(commit_id, symbol)?;
-                        }
-                        
-                        // Store edges
-                        for edge in &edges {
-                            store.insert_edge(commit_id, edge)?;
-                        }
-                        
-                        // Store occurrences
-                        for occurrence in &occurrences {
-                            store.insert_occurrence(commit_id, occurrence)?;
-                        }
-                        
-                        total_symbols += symbols.len();
-                        total_edges += edges.len();
-                    }
-                    // Parse C files
-                    else if relative_path.ends_with(".c") || relative_path.ends_with(".h") {
-                        let (symbols, edges, occurrences) = c_harness.parse(
-                            &relative_path,
-                            &content
-                        )?;
-                        
-                        // Store symbols
-                        for symbol in &symbols {
-                            store.insert_symbol(commit_id, symbol)?;
-                        }
-                        
-                        // Store edges
-                        for edge in &edges {
-                            store.insert_edge(commit_id, edge)?;
-                        }
-                        
-                        // Store occurrences
-                        for occurrence in &occurrences {
-                            store.insert_occurrence(commit_id, occurrence)?;
-                        }
-                        
-                        total_symbols += symbols.len();
-                        total_edges += edges.len();
-                    }
-                    // Parse C# files
-                    else if relative_path.ends_with(".cs") {
-                        let (symbols, edges, occurrences) = csharp_harness.parse_file(
-                            &relative_path,
-                            &content
-                        )?;
-                        
-                        // Store symbols
-                        for symbol in &symbols {
-                            store.insert_symbol(commit_id, symbol)?;
+                        Some(("cpp", cpp_harness.parse(&relative_path, &content)?))
+                    } else if relative_path.ends_with(".c") || relative_path.ends_with(".h") {
+                        Some(("c", c_harness.parse(&relative_path, &content)?))
+                    } else if relative_path.ends_with(".cs") {
+                        Some(("csharp", csharp_harness.parse_file(&relative_path, &content)?))
+                    } else {
+                        if quality == ParseQuality::Syntactic {
+                            quality = ParseQuality::Heuristic;
                         }
-                        
-                        // Store edges
-                        for edge in &edges {
-                            store.insert_edge(commit_id, edge)?;
+                        fallback.parse(&relative_path, &content)?
+                    };
+                    
+                    let Some((language, (mut symbols, edges, occurrences))) = parsed else {
+                        continue;
+                    };
+                    if is_generated_file(&relative_path, &content) {
+                        for symbol in &mut symbols {
+                            symbol.flags.insert(SymbolFlags::GENERATED);
                         }
-                        
-                        // Store occurrences
-                        for occurrence in &occurrences {
-                            store.insert_occurrence(commit_id, occurrence)?;
+                    }
+                    metrics.add_phase_duration(&format!("parse:{}", language), parse_start.elapsed());
+                    
+                    let insert_start = Instant::now();
+                    for symbol in &symbols {
+                        store.insert_symbol(commit_id, symbol)?;
+                    }
+                    for edge in &edges {
+                        store.insert_edge(commit_id, edge)?;
+                        if edge.meta.contains_key("undeclared_dependency") {
+                            undeclared_imports += 1;
                         }
-                        
-                        total_symbols += symbols.len();
-                        total_edges += edges.len();
                     }
+                    for occurrence in &occurrences {
+                        store.insert_occurrence(commit_id, occurrence)?;
+                    }
+                    store.insert_file_summary(
+                        commit_id,
+                        &FileSummary::new(&relative_path, language, &content, &symbols, &edges, quality),
+                    )?;
+                    metrics.add_phase_duration("insert", insert_start.elapsed());
+                    metrics.add_file_counts(language, symbols.len(), edges.len(), occurrences.len());
+                    
+                    total_symbols += symbols.len();
+                    total_edges += edges.len();
+                    let events = FileEvents::scan(&content);
+                    if !events.is_empty() {
+                        file_events.push((relative_path.clone(), events));
+                    }
+                    completed.push(relative_path);
+                }
+                
+                if cancel.is_cancelled() && subtree.is_some() {
+                    // Small enough to redo; leave the snapshot as it was
+                    drop(snapshot);
+                    output.summary(
+                        "Scan interrupted; nothing was written",
+                        &[],
+                        json!({ "commit": commit_sha, "action": "interrupted", "files": 0, "files_total": files_to_process.len() }),
+                    )?;
+                    let _ = metrics.finalize();
+                    drop(telemetry);
+                    std::process::exit(CANCELLED_EXIT_CODE);
+                }
+                if cancel.is_cancelled() {
+                    // Everything written so far is whole files; remember them so
+                    // the next scan of this commit picks up where we stopped
+                    let symbols = store.get_snapshot_symbols(&commit_sha)?;
+                    let kept = store.get_snapshot_edges(&commit_sha)?;
+                    let event_edges = events::detect(&symbols, &file_events, &kept);
+                    store.set_event_edges(commit_id, &event_files(&file_events), &event_edges)?;
+                    let indexed = completed.len();
+                    completed.extend(resumed.unwrap_or_default());
+                    store.save_checkpoint(commit_id, &completed)?;
+                    snapshot.commit()?;
+                    output.summary(
+                        &format!(
+                            "Scan interrupted after {} of {} files; run scan again to resume",
+                            indexed,
+                            files_to_process.len()
+                        ),
+                        &[],
+                        json!({
+                            "commit": commit_sha,
+                            "action": "interrupted",
+                            "files": indexed,
+                            "files_total": files_to_process.len(),
+                        }),
+                    )?;
+                    let _ = metrics.finalize();
+                    drop(telemetry);
+                    std::process::exit(CANCELLED_EXIT_CODE);
+                }
+                if subtree.is_some() && !complete_snapshot {
+                    // The rest of the repository is still to scan: leave the
+                    // snapshot unfinished so a full scan picks up from here
+                    let mut done = resumed.clone().unwrap_or_default();
+                    done.retain(|f| !completed.contains(f));
+                    done.extend(completed.iter().cloned());
+                    store.save_checkpoint(commit_id, &done)?;
+                } else {
+                    store.clear_checkpoint(commit_id)?;
+                }
+                if undeclared_imports > 0 {
+                    warn!("{} imports reach into build modules their module does not depend on", undeclared_imports);
                 }
                 
                 metrics.end_phase("syntactic_analysis");
+                
+                // Point usages of re-exported names at their original definitions
+                metrics.start_phase("resolve");
+                let attributed = info_span!(parent: &scan_span, "resolve")
+                    .in_scope(|| store.attribute_reexports(commit_id))?;
+                if attributed > 0 {
+                    info!("Attributed {} references through re-export chains", attributed);
+                }
+                let linked = store.link_declarations(commit_id)?;
+                if linked > 0 {
+                    info!("Linked {} declarations to their implementations", linked);
+                }
+                let annotations = store.link_annotations(commit_id)?;
+                if annotations > 0 {
+                    info!("Linked {} annotation usages to their declarations", annotations);
+                }
+                let dangling = match &subtree {
+                    Some(subtree) => store.mark_dangling_edges(commit_id, subtree)?,
+                    None => 0,
+                };
+                if dangling > 0 {
+                    warn!("{} edges crossing {} lead to code not in the snapshot", dangling, subtree.as_deref().unwrap_or_default());
+                }
+                metrics.end_phase("resolve");
+                
+                let symbols = store.get_snapshot_symbols(&commit_sha)?;
+                if !layers.is_empty() {
+                    let assigned = layers.assign(&symbols);
+                    info!("{} of {} symbols belong to an architecture layer", assigned.len(), symbols.len());
+                    store.set_symbol_layers(commit_id, &assigned)?;
+                }
+                
+                let edges = store.get_snapshot_edges(&commit_sha)?;
+                let entry_points: Vec<(String, String)> = entry_points::detect(&symbols, &edges, |file| {
+                    source::read_source(&repo_root.join(file)).ok().map(|source| source.text)
+                })
+                .into_iter()
+                .map(|(id, kind)| (id, kind.as_str().to_string()))
+                .collect();
+                info!("Found {} entry points", entry_points.len());
+                store.set_symbol_entry_points(commit_id, &entry_points)?;
+                
+                let event_edges = events::detect(&symbols, &file_events, &edges);
+                info!("Found {} publish and subscribe sites", event_edges.len());
+                store.set_event_edges(commit_id, &event_files(&file_events), &event_edges)?;
+                
+                snapshot.commit()?;
+                
                 metrics.record_lines_of_code(total_lines);
-                metrics.record_file_count("total", files_to_process.len());
-                metrics.record_symbol_count("total", total_symbols);
-                metrics.record_edge_count("total", total_edges);
                 metrics.update_memory_usage();
                 
                 // Run semantic analysis if enabled
@@ -455,12 +1037,17 @@ async fn main() -> Result<()> {
                     let mut resolution_engine = ResolutionEngine::new(store_for_resolution);
                     
                     // Choose between incremental and full semantic analysis
-                    let result = if incremental {
+                    let semantic_span = info_span!(parent: &scan_span, "semantic_analysis", incremental);
+                    let result = if incremental || subtree.is_some() {
                         info!("Running incremental semantic analysis");
-                        resolution_engine.resolve_project_incremental(&repo_root, &commit_sha).await
+                        resolution_engine.resolve_project_incremental(&repo_root, &commit_sha)
+                            .instrument(semantic_span)
+                            .await
                     } else {
                         info!("Running full semantic analysis");
-                        resolution_engine.resolve_project(&repo_root, &commit_sha).await
+                        resolution_engine.resolve_project(&repo_root, &commit_sha)
+                            .instrument(semantic_span)
+                            .await
                     };
                     
                     // Handle result
@@ -477,142 +1064,915 @@ async fn main() -> Result<()> {
                     metrics.update_memory_usage();
                 }
                 
-                let action = if incremental { "Updated" } else { "Indexed" };
+                let action = if subtree.is_some() {
+                    "Rescanned"
+                } else if incremental {
+                    "Updated"
+                } else {
+                    "Indexed"
+                };
                 let analysis_type = if run_semantic { "semantic + syntactic" } else { "syntactic" };
                 info!("{} {} files, {} symbols, {} edges ({})", action, files_to_process.len(), total_symbols, total_edges, analysis_type);
-                println!("{} {} files, {} symbols, {} edges ({})", action, files_to_process.len(), total_symbols, total_edges, analysis_type);
                 
                 // Finalize and display performance metrics
                 let performance_metrics = metrics.finalize();
+                emit_metrics(&performance_metrics, cli.log_format);
+                output.summary(
+                    &format!("{} {} files, {} symbols, {} edges ({})", action, files_to_process.len(), total_symbols, total_edges, analysis_type),
+                    &[&commit_sha],
+                    json!({
+                        "commit": commit_sha,
+                        "action": action.to_lowercase(),
+                        "files": files_to_process.len(),
+                        "symbols": total_symbols,
+                        "edges": total_edges,
+                        "dangling_edges": dangling,
+                        "semantic": run_semantic,
+                        "metrics": performance_metrics.to_json(),
+                    }),
+                )?;
             } else {
-                println!("Found {} files (dry run)", files_to_process.len());
                 metrics.record_file_count("total", files_to_process.len());
-                let _ = metrics.finalize();
+                let performance_metrics = metrics.finalize();
+                emit_metrics(&performance_metrics, cli.log_format);
+                let files: Vec<String> = files_to_process.iter().map(|f| relative_path(&repo_root, f)).collect();
+                output.summary(
+                    &format!("Found {} files (dry run)", files.len()),
+                    &files.iter().map(String::as_str).collect::<Vec<_>>(),
+                    json!({ "commit": commit_sha, "action": "dry_run", "files": files }),
+                )?;
             }
         }
         
-        Commands::Show { symbol, callers, callees, importers, depth } => {
-            let store = GraphStore::new(&repo_root)?;
+        Commands::Query { cmd: QueryCommands::Show { symbol, callers, callees, importers, depth, lang } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
             
-            // Find the symbol
-            if let Some(sym) = store.find_symbol_by_fqn(&symbol)? {
-                println!("Symbol: {}", sym.name);
-                println!("  Type: {:?}", sym.kind);
-                println!("  FQN: {}", sym.fqn);
-                println!("  File: {}:{}-{}", sym.file_path, sym.span.start_line + 1, sym.span.end_line + 1);
-                
-                if callers {
-                    println!("\nCallers (depth={}):", depth);
-                    let callers = store.get_callers(&sym.id, depth)?;
-                    if callers.is_empty() {
-                        println!("  (none found)");
-                    } else {
-                        for caller in callers {
-                            println!("  - {} ({}:{})", caller.fqn, caller.file_path, caller.span.start_line + 1);
+            // Find the symbol, following aliases (re-exports, renames) to the definition
+            let (found, alias_chain) = match store.find_symbol_by_fqn(&symbol, lang.as_ref())? {
+                Some(m) => (Some(m.symbol), m.alias_chain),
+                None => (None, Vec::new()),
+            };
+            let callers = match &found {
+                Some(sym) if callers => Some(store.get_callers(&sym.id, depth)?),
+                _ => None,
+            };
+            let callees = match &found {
+                Some(sym) if callees => Some(store.get_callees(&sym.id, depth)?),
+                _ => None,
+            };
+            let importers = match &found {
+                Some(sym) if importers => Some(store.get_importers(&sym.id)?),
+                _ => None,
+            };
+            
+            match output {
+                Output::Json => print_json(&json!({
+                    "query": symbol,
+                    "symbol": found,
+                    "alias_chain": alias_chain,
+                    "depth": depth,
+                    "callers": callers,
+                    "callees": callees,
+                    "importers": importers,
+                }))?,
+                Output::Quiet => {
+                    // The walk results when one was asked for, otherwise the symbol itself
+                    if callers.is_none() && callees.is_none() && importers.is_none() {
+                        if let Some(sym) = &found {
+                            println!("{}", sym.fqn);
                         }
                     }
+                    for sym in callers.iter().chain(callees.iter()).flatten() {
+                        println!("{}", sym.fqn);
+                    }
+                    for importer in importers.iter().flatten() {
+                        println!("{}", importer.file);
+                    }
                 }
-                
-                if callees {
-                    println!("\nCallees (depth={}):", depth);
-                    let callees = store.get_callees(&sym.id, depth)?;
-                    if callees.is_empty() {
-                        println!("  (none found)");
-                    } else {
-                        for callee in callees {
-                            println!("  - {} ({}:{})", callee.fqn, callee.file_path, callee.span.start_line + 1);
+                Output::Text => {
+                    if let Some(sym) = found {
+                        println!("Symbol: {}", sym.name);
+                        println!("  Type: {:?}", sym.kind);
+                        println!("  FQN: {}", sym.fqn);
+                        println!("  File: {}:{}-{}", sym.file_path, sym.full_span.start_line + 1, sym.full_span.end_line + 1);
+                        if !alias_chain.is_empty() {
+                            println!("  Via: {}", alias_chain.join(" -> "));
+                        }
+                        if !sym.flags.is_empty() {
+                            println!("  Flags: {}", sym.flags);
+                        }
+                        if !sym.configs.is_empty() {
+                            println!("  Configs: {}", sym.configs.join(", "));
+                        }
+                        
+                        if let Some(callers) = callers {
+                            println!("\nCallers (depth={}):", depth);
+                            if callers.is_empty() {
+                                println!("  (none found)");
+                            } else {
+                                for caller in callers {
+                                    println!("  - {} ({}:{})", caller.fqn, caller.file_path, caller.name_span.start_line + 1);
+                                }
+                            }
+                        }
+                        
+                        if let Some(callees) = callees {
+                            println!("\nCallees (depth={}):", depth);
+                            if callees.is_empty() {
+                                println!("  (none found)");
+                            } else {
+                                for callee in callees {
+                                    println!("  - {} ({}:{})", callee.fqn, callee.file_path, callee.name_span.start_line + 1);
+                                }
+                            }
+                        }
+                        
+                        if let Some(importers) = importers {
+                            println!("\nImporters:");
+                            if importers.is_empty() {
+                                println!("  (none found)");
+                            } else {
+                                for importer in importers {
+                                    if importer.local == importer.imported {
+                                        println!("  - {} ({})", importer.file, importer.imported);
+                                    } else {
+                                        println!("  - {} ({} as {})", importer.file, importer.imported, importer.local);
+                                    }
+                                }
+                            }
                         }
+                    } else {
+                        println!("Symbol not found: {}", symbol);
+                        println!("Try searching with: reviewbot query search '{}'", symbol);
                     }
                 }
-                
-                if importers {
-                    println!("\nImporters:");
-                    println!("  (not yet implemented)");
+            }
+        }
+        
+        Commands::Query { cmd: QueryCommands::Search { query, k, kind, lang, file, visibility, returns, flags, exclude_flags, layer, entry_point, .. } } => {
+            let store = GraphStore::new(&repo_root)?;
+            let filter = SearchFilter {
+                kinds: kind,
+                langs: lang,
+                file_glob: file,
+                visibility,
+                return_type: returns,
+                with_flags: flags.into_iter().fold(SymbolFlags::empty(), |acc, f| acc | f),
+                without_flags: exclude_flags.into_iter().fold(SymbolFlags::empty(), |acc, f| acc | f),
+                layer,
+                entry_point: entry_point.map(|kind| kind.as_str().to_string()),
+            };
+            let cards = store.search_symbol_cards(&query, k, &filter)?;
+            
+            match output {
+                Output::Json => print_json(&cards)?,
+                Output::Quiet => {
+                    for card in &cards {
+                        println!("{}", card.symbol.fqn);
+                    }
+                }
+                Output::Text if cards.is_empty() => println!("No symbols found matching '{}'", query),
+                Output::Text => {
+                    println!("Found {} symbols matching '{}':", cards.len(), query);
+                    for card in cards {
+                        let sym = &card.symbol;
+                        if sym.flags.is_empty() {
+                            println!("  {} ({:?})", sym.fqn, sym.kind);
+                        } else {
+                            println!("  {} ({:?}) [{}]", sym.fqn, sym.kind, sym.flags);
+                        }
+                        for location in &card.locations {
+                            let label = if location.declaration { "Declared" } else { "File" };
+                            println!("    {}: {}:{}", label, location.file_path, location.name_span.start_line + 1);
+                        }
+                    }
                 }
-            } else {
-                println!("Symbol not found: {}", symbol);
-                println!("Try searching with: reviewbot search '{}'", symbol);
             }
         }
         
-        Commands::Search { query, k, .. } => {
+        Commands::Query { cmd: QueryCommands::Grep { query, k } } => {
             let store = GraphStore::new(&repo_root)?;
-            let results = store.search_symbols(&query, k)?;
+            let hits = store.search_occurrences(&query, k)?;
             
-            if results.is_empty() {
-                println!("No symbols found matching '{}'", query);
-            } else {
-                println!("Found {} symbols matching '{}':", results.len(), query);
-                for sym in results {
-                    println!("  {} ({:?})", sym.fqn, sym.kind);
-                    println!("    File: {}:{}", sym.file_path, sym.span.start_line + 1);
+            match output {
+                Output::Json => print_json(&hits)?,
+                Output::Quiet => {
+                    for hit in &hits {
+                        let occurrence = &hit.occurrence;
+                        println!("{}:{}:{}", occurrence.file_path, occurrence.span.start_line + 1, occurrence.span.start_col + 1);
+                    }
+                }
+                Output::Text if hits.is_empty() => println!("No occurrences found matching '{}'", query),
+                Output::Text => {
+                    println!("Found {} occurrences matching '{}':", hits.len(), query);
+                    for hit in hits {
+                        let occurrence = &hit.occurrence;
+                        println!(
+                            "  {}:{}:{}  {} ({:?})",
+                            occurrence.file_path,
+                            occurrence.span.start_line + 1,
+                            occurrence.span.start_col + 1,
+                            occurrence.token,
+                            occurrence.role,
+                        );
+                        if let Some(symbol) = &hit.symbol {
+                            println!("    Refers to: {} ({}:{})", symbol.fqn, symbol.file_path, symbol.name_span.start_line + 1);
+                        }
+                        if let Some(enclosing) = &hit.enclosing {
+                            println!("    In: {}", enclosing.fqn);
+                        }
+                    }
                 }
             }
         }
         
-        Commands::Graph { cmd } => {
+        Commands::Query { cmd: QueryCommands::Heatmap { file } } => {
             let store = GraphStore::new(&repo_root)?;
+            let lines = store.file_heatmap(&file)?;
             
-            match cmd {
-                GraphCommands::Stats => {
-                    let graph = store.build_graph()?;
-                    let stats = graph.stats();
-                    
+            match output {
+                Output::Json => print_json(&lines)?,
+                Output::Quiet => {
+                    for heat in &lines {
+                        println!("{}\t{}\t{}", heat.line + 1, heat.definitions, heat.references);
+                    }
+                }
+                Output::Text if lines.is_empty() => println!("No symbols defined in {}", file),
+                Output::Text => {
+                    println!("{}:", file);
+                    for heat in lines {
+                        println!("  {:>5}  {} definitions, {} references", heat.line + 1, heat.definitions, heat.references);
+                    }
+                }
+            }
+        }
+        
+        Commands::Query { cmd: QueryCommands::Stats } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let stats = store.build_graph()?.stats();
+            
+            match output {
+                Output::Json => print_json(&json!({
+                    "nodes": stats.node_count,
+                    "edges": stats.edge_count,
+                    "cyclic": stats.is_cyclic,
+                }))?,
+                Output::Quiet => println!("{}\t{}\t{}", stats.node_count, stats.edge_count, stats.is_cyclic),
+                Output::Text => {
                     println!("Graph Statistics:");
                     println!("  Nodes (symbols): {}", stats.node_count);
                     println!("  Edges (relationships): {}", stats.edge_count);
                     println!("  Has cycles: {}", if stats.is_cyclic { "Yes" } else { "No" });
                 }
-                
-                GraphCommands::Cycles { symbol } => {
+            }
+        }
+        
+        Commands::Query { cmd: QueryCommands::Cycles { symbol } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let graph = store.build_graph()?;
+            let mut cycles = Vec::new();
+            for cycle in graph.find_cycles_containing(&symbol) {
+                let mut members = Vec::new();
+                for sym_id in &cycle {
+                    members.extend(store.find_symbol_by_id(sym_id)?);
+                }
+                cycles.push(members);
+            }
+            
+            match output {
+                Output::Json => print_json(&cycles)?,
+                Output::Quiet => {
+                    for cycle in &cycles {
+                        let fqns: Vec<&str> = cycle.iter().map(|sym| sym.fqn.as_str()).collect();
+                        println!("{}", fqns.join(" -> "));
+                    }
+                }
+                Output::Text if cycles.is_empty() => println!("No cycles found containing '{}'", symbol),
+                Output::Text => {
+                    println!("Found {} cycle(s) containing '{}':", cycles.len(), symbol);
+                    for (i, cycle) in cycles.iter().enumerate() {
+                        println!("\nCycle {}:", i + 1);
+                        for sym in cycle {
+                            println!("  - {} ({})", sym.fqn, sym.file_path);
+                        }
+                    }
+                }
+            }
+        }
+        
+        Commands::Query { cmd: QueryCommands::Path { from, to } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            
+            // Find symbols by FQN first
+            let from_sym = store.find_symbol_by_fqn(&from, None)?.map(|m| m.symbol);
+            let to_sym = store.find_symbol_by_fqn(&to, None)?.map(|m| m.symbol);
+            let path = match (&from_sym, &to_sym) {
+                (Some(from_sym), Some(to_sym)) => {
                     let graph = store.build_graph()?;
-                    let cycles = graph.find_cycles_containing(&symbol);
-                    
-                    if cycles.is_empty() {
-                        println!("No cycles found containing '{}'", symbol);
-                    } else {
-                        println!("Found {} cycle(s) containing '{}':", cycles.len(), symbol);
-                        for (i, cycle) in cycles.iter().enumerate() {
-                            println!("\nCycle {}:", i + 1);
-                            for sym_id in cycle {
-                                if let Some(sym) = store.find_symbol_by_id(sym_id)? {
-                                    println!("  - {} ({})", sym.fqn, sym.file_path);
-                                }
+                    match graph.find_path(&from_sym.id, &to_sym.id) {
+                        Some(ids) => {
+                            let mut path = Vec::new();
+                            for sym_id in &ids {
+                                path.extend(store.find_symbol_by_id(sym_id)?);
                             }
+                            Some(path)
                         }
+                        None => None,
                     }
                 }
-                
-                GraphCommands::Path { from, to } => {
-                    let graph = store.build_graph()?;
-                    
-                    // Find symbols by FQN first
-                    let from_sym = store.find_symbol_by_fqn(&from)?;
-                    let to_sym = store.find_symbol_by_fqn(&to)?;
-                    
-                    if from_sym.is_none() {
-                        println!("Source symbol not found: {}", from);
-                        return Ok(());
+                _ => None,
+            };
+            
+            match output {
+                Output::Json => print_json(&json!({
+                    "from": from_sym,
+                    "to": to_sym,
+                    "path": path,
+                }))?,
+                Output::Quiet => {
+                    for sym in path.iter().flatten() {
+                        println!("{}", sym.fqn);
                     }
-                    if to_sym.is_none() {
-                        println!("Target symbol not found: {}", to);
-                        return Ok(());
+                }
+                Output::Text if from_sym.is_none() => println!("Source symbol not found: {}", from),
+                Output::Text if to_sym.is_none() => println!("Target symbol not found: {}", to),
+                Output::Text => match path {
+                    Some(path) => {
+                        println!("Path from '{}' to '{}':", from, to);
+                        for sym in path {
+                            println!("  -> {} ({})", sym.fqn, sym.file_path);
+                        }
                     }
+                    None => println!("No path found from '{}' to '{}'", from, to),
+                },
+            }
+        }
+        
+        Commands::Query { cmd: QueryCommands::Neighborhood { symbol, radius, edges, max_nodes, max_edges } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let Some(center) = store.find_symbol_by_fqn(&symbol, None)?.map(|m| m.symbol) else {
+                anyhow::bail!("Symbol not found: {}", symbol);
+            };
+            let limits = NeighborhoodLimits { max_nodes, max_edges };
+            let neighborhood = store.build_graph()?.neighborhood(&center.id, radius, &edges, limits);
+            // Edges to names that never resolved have no symbol
+            let mut fqns = HashMap::new();
+            for node in &neighborhood.nodes {
+                let fqn = store.find_symbol_by_id(&node.id)?.map(|sym| sym.fqn).unwrap_or_else(|| node.id.clone());
+                fqns.insert(node.id.clone(), fqn);
+            }
+            
+            match output {
+                Output::Json => print_json(&json!({
+                    "symbol": center,
+                    "radius": radius,
+                    "nodes": neighborhood.nodes.iter().map(|node| json!({
+                        "id": node.id,
+                        "fqn": fqns[&node.id],
+                        "distance": node.distance,
+                    })).collect::<Vec<_>>(),
+                    "edges": neighborhood.edges,
+                    "truncated": neighborhood.truncated,
+                }))?,
+                Output::Quiet => {
+                    for node in &neighborhood.nodes {
+                        println!("{}", fqns[&node.id]);
+                    }
+                }
+                Output::Text => {
+                    println!("Neighborhood of '{}' (radius={}): {} symbol(s), {} edge(s)",
+                        center.fqn, radius, neighborhood.nodes.len(), neighborhood.edges.len());
+                    for node in &neighborhood.nodes {
+                        println!("  [{}] {}", node.distance, fqns[&node.id]);
+                    }
+                    if !neighborhood.edges.is_empty() {
+                        println!("\nEdges:");
+                        for edge in &neighborhood.edges {
+                            println!("  {} -[{:?}]-> {}", fqns[&edge.src], edge.edge_type, fqns[&edge.dst]);
+                        }
+                    }
+                    if neighborhood.truncated {
+                        println!("\n(truncated at {} symbols / {} edges)", max_nodes, max_edges);
+                    }
+                }
+            }
+        }
+        
+        Commands::Query { cmd: QueryCommands::SuggestReviewers { diff, commit, rev, exclude, k } } => {
+            let store = GraphStore::new(&repo_root)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to suggest reviewers for"))?,
+            };
+            let changes = ChangedLines::read(&diff)?;
+            let owners = CodeOwners::load(&repo_root)?;
+            let symbols = store.get_snapshot_symbols(&commit)?;
+            let edges = store.get_snapshot_edges(&commit)?;
+            let blame = |file: &str| match reviewers::blame(&repo_root, &rev, file) {
+                Ok(lines) => lines,
+                Err(e) => {
+                    warn!("Could not blame {}: {}", file, e);
+                    None
+                }
+            };
+            let mut suggestions = reviewers::suggest(&symbols, &edges, &changes, owners.as_ref(), blame, &exclude);
+            suggestions.truncate(k);
+            
+            match output {
+                Output::Json => print_json(&suggestions)?,
+                Output::Quiet => {
+                    for suggestion in &suggestions {
+                        println!("{}", suggestion.reviewer);
+                    }
+                }
+                Output::Text if suggestions.is_empty() => {
+                    println!("No reviewers found: the diff touches no indexed symbols, or nobody owns them");
+                }
+                Output::Text => {
+                    println!("Suggested reviewers:");
+                    for suggestion in &suggestions {
+                        println!(
+                            "  {:.2}  {}  ({} owned, {} authored: {})",
+                            suggestion.score,
+                            suggestion.reviewer,
+                            suggestion.owned,
+                            suggestion.authored,
+                            suggestion.symbols.join(", ")
+                        );
+                    }
+                }
+            }
+        }
+        
+        Commands::Query { cmd: QueryCommands::Ownership { commit, rev, min_fan_in, k } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to report on"))?,
+            };
+            let owners = CodeOwners::load(&repo_root)?;
+            let symbols = store.get_snapshot_symbols(&commit)?;
+            let edges = store.get_snapshot_edges(&commit)?;
+            let blame = |file: &str| match reviewers::blame(&repo_root, &rev, file) {
+                Ok(lines) => lines,
+                Err(e) => {
+                    warn!("Could not blame {}: {}", file, e);
+                    None
+                }
+            };
+            let report = reviewers::ownership(&commit, &symbols, &edges, owners.as_ref(), blame, min_fan_in);
+            
+            let top: Vec<&str> = report.owners.iter().take(k).map(|h| h.owner.as_str()).collect();
+            output.summary(
+                report.to_text(k).trim_end(),
+                &top,
+                serde_json::to_value(&report)?,
+            )?;
+        }
+        
+        Commands::Query { cmd: QueryCommands::Risk { diff, commit, churn_days, k } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to assess"))?,
+            };
+            let changes = ChangedLines::read(&diff)?;
+            let churn = risk::churn(&repo_root, churn_days, changes.files()).unwrap_or_else(|e| {
+                warn!("No churn data: {}", e);
+                Default::default()
+            });
+            let symbols = store.get_snapshot_symbols(&commit)?;
+            let edges = store.get_snapshot_edges(&commit)?;
+            let report = risk::assess(&commit, &symbols, &edges, &changes, &churn);
+            
+            let score = format!("{:.0}", report.score);
+            output.summary(
+                report.to_text(k).trim_end(),
+                &[&score],
+                serde_json::to_value(&report)?,
+            )?;
+        }
+        
+        Commands::Query { cmd: QueryCommands::Churn { commit, min_fan_in, k } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to report on"))?,
+            };
+            let snapshots = store.snapshot_commits()?;
+            let history = churn::history_order(&repo_root, &commit, &snapshots).unwrap_or_else(|e| {
+                warn!("No git history, comparing snapshots in scan order: {}", e);
+                let scanned_up_to = snapshots.iter().position(|c| *c == commit).map_or(snapshots.len(), |i| i + 1);
+                snapshots[..scanned_up_to].to_vec()
+            });
+            let changes = store.symbol_churn(&history)?;
+            let symbols = store.get_snapshot_symbols(&commit)?;
+            let edges = store.get_snapshot_edges(&commit)?;
+            let report = churn::report(&commit, history.len(), &symbols, &edges, &changes, min_fan_in);
+            
+            let fqns: Vec<&str> = report.symbols.iter().take(k).map(|s| s.fqn.as_str()).collect();
+            output.summary(
+                report.to_text(k).trim_end(),
+                &fqns,
+                serde_json::to_value(&report)?,
+            )?;
+        }
+        
+        Commands::Query { cmd: QueryCommands::Deprecations { commit, snapshots, k } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to report on"))?,
+            };
+            let scanned = store.snapshot_commits()?;
+            let mut history = churn::history_order(&repo_root, &commit, &scanned).unwrap_or_else(|e| {
+                warn!("No git history, trending snapshots in scan order: {}", e);
+                let scanned_up_to = scanned.iter().position(|c| *c == commit).map_or(scanned.len(), |i| i + 1);
+                scanned[..scanned_up_to].to_vec()
+            });
+            if history.last() != Some(&commit) {
+                history.push(commit.clone());
+            }
+            let history = &history[history.len().saturating_sub(snapshots.max(1))..];
+            
+            let mut uses = Vec::new();
+            for snapshot in history {
+                let symbols = store.get_snapshot_symbols(snapshot)?;
+                let edges = store.get_snapshot_edges(snapshot)?;
+                uses.push((snapshot.clone(), deprecations::uses(&symbols, &edges)));
+            }
+            let owners = CodeOwners::load(&repo_root)?;
+            let symbols = store.get_snapshot_symbols(&commit)?;
+            let report = deprecations::report(&symbols, &uses, owners.as_ref());
+            
+            let teams: Vec<&str> = report.teams.iter().map(|t| t.team.as_str()).collect();
+            output.summary(
+                report.to_text(k).trim_end(),
+                &teams,
+                serde_json::to_value(&report)?,
+            )?;
+        }
+        
+        Commands::Query { cmd: QueryCommands::Events { topic, commit } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to search"))?,
+            };
+            let symbols = store.get_snapshot_symbols(&commit)?;
+            let edges = store.get_snapshot_edges(&commit)?;
+            let report = events::flows(&commit, &symbols, &edges, topic.as_deref());
+            
+            let topics: Vec<&str> = report.topics.iter().map(|flow| flow.topic.as_str()).collect();
+            output.summary(
+                report.to_text().trim_end(),
+                &topics,
+                serde_json::to_value(&report)?,
+            )?;
+        }
+        
+        Commands::Query { cmd: QueryCommands::Throwers { exception, commit, k } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to search"))?,
+            };
+            let symbols = store.get_snapshot_symbols(&commit)?;
+            let edges = store.get_snapshot_edges(&commit)?;
+            let report = throwers::throwers(&commit, &exception, &symbols, &edges);
+            
+            let fqns: Vec<&str> = report.throwers.iter().take(k).map(|t| t.fqn.as_str()).collect();
+            output.summary(
+                report.to_text(k).trim_end(),
+                &fqns,
+                serde_json::to_value(&report)?,
+            )?;
+        }
+        
+        Commands::Query { cmd: QueryCommands::ImportCycles { commit, k } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to search"))?,
+            };
+            let symbols = store.get_snapshot_symbols(&commit)?;
+            let edges = store.get_snapshot_edges(&commit)?;
+            let report = import_cycles::analyze(&commit, &symbols, &edges, |file| {
+                source::read_source(&repo_root.join(file)).ok().map(|source| source.text)
+            });
+            
+            let links: Vec<String> = report
+                .cycles
+                .iter()
+                .take(k)
+                .flat_map(|cycle| cycle.break_links.iter().map(|link| format!("{} -> {}", link.from, link.to)))
+                .collect();
+            let links: Vec<&str> = links.iter().map(String::as_str).collect();
+            output.summary(
+                report.to_text(k).trim_end(),
+                &links,
+                serde_json::to_value(&report)?,
+            )?;
+        }
+        
+        Commands::Query { cmd: QueryCommands::Deps { commit, by, depth, min_weight, format } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to report on"))?,
+            };
+            let graph = store.aggregate_graph(&commit, by.granularity(depth))?.prune(min_weight);
+            
+            match (format, output) {
+                (DiagramFormat::Text, Output::Json) => print_json(&graph)?,
+                (DiagramFormat::Text, Output::Quiet) => {
+                    for edge in &graph.edges {
+                        println!("{} -> {} {}", edge.from, edge.to, edge.weight);
+                    }
+                }
+                (format, _) => print!("{}", format.render(&graph)),
+            }
+        }
+        
+        Commands::Query { cmd: QueryCommands::Summary { commit, path, depth } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to report on"))?,
+            };
+            let directories = store.directory_summaries(&commit, path.as_deref(), depth)?;
+            
+            let mut text = format!(
+                "{:<40} {:>6} {:>8} {:>8} {:>10} {:>9}\n",
+                "Directory", "Files", "Symbols", "Imports", "Complexity", "Degraded"
+            );
+            for directory in &directories {
+                text.push_str(&format!(
+                    "{:<40} {:>6} {:>8} {:>8} {:>10} {:>9}\n",
+                    directory.directory,
+                    directory.files,
+                    directory.symbols,
+                    directory.imports,
+                    directory.complexity,
+                    directory.degraded_files
+                ));
+            }
+            let names: Vec<&str> = directories.iter().map(|d| d.directory.as_str()).collect();
+            output.summary(
+                text.trim_end(),
+                &names,
+                json!({ "commit": commit, "directories": directories }),
+            )?;
+        }
+        
+        Commands::Query { cmd: QueryCommands::Languages } => {
+            let matrix = languages::matrix(vec![
+                (ts_harness::CAPABILITIES, &[Language::TypeScript, Language::JavaScript]),
+                (py_harness::CAPABILITIES, &[Language::Python]),
+                (go_harness::CAPABILITIES, &[Language::Go]),
+                (rust_harness::CAPABILITIES, &[Language::Rust]),
+                (java_harness::CAPABILITIES, &[Language::Java]),
+                (cpp_harness::CAPABILITIES, &[Language::C, Language::Cpp]),
+                (csharp_harness::CAPABILITIES, &[Language::CSharp]),
+            ]);
+            let names: Vec<&str> = matrix.iter().map(|row| row.capabilities.name).collect();
+            output.summary(
+                languages::to_text(&matrix).trim_end(),
+                &names,
+                serde_json::to_value(&matrix)?,
+            )?;
+        }
+        
+        Commands::Query { cmd: QueryCommands::Tour { entrypoint, commit, depth, max_stops } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to walk"))?,
+            };
+            let symbols = store.get_snapshot_symbols(&commit)?;
+            let edges = store.get_snapshot_edges(&commit)?;
+            let entry = tour::find_entry(&symbols, &entrypoint)?;
+            let tour = tour::build(&commit, entry, &symbols, &edges, depth, max_stops);
+            
+            let fqns: Vec<&str> = tour.stops.iter().map(|stop| stop.fqn.as_str()).collect();
+            output.summary(tour.to_text().trim_end(), &fqns, serde_json::to_value(&tour)?)?;
+        }
+        
+        Commands::Query { cmd: QueryCommands::Implementations { interface, commit } } => {
+            let store = open_query_store(&repo_root, cli.call_noise)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to search"))?,
+            };
+            let symbols = store.get_snapshot_symbols(&commit)?;
+            let edges = store.get_snapshot_edges(&commit)?;
+            let hierarchy = implementations::Hierarchy::new(&symbols, &edges, |file| {
+                source::read_source(&repo_root.join(file)).ok().map(|source| source.text)
+            });
+            
+            match interface {
+                Some(interface) => {
+                    let report = hierarchy.implementations(&commit, hierarchy.find_type(&interface)?);
+                    let fqns: Vec<&str> = report.implementations.iter().map(|i| i.fqn.as_str()).collect();
+                    output.summary(report.to_text().trim_end(), &fqns, serde_json::to_value(&report)?)?;
+                }
+                None => {
+                    let report = hierarchy.gaps(&commit);
+                    let fqns: Vec<&str> = report.gaps.iter().map(|gap| gap.implementor.as_str()).collect();
+                    output.summary(report.to_text().trim_end(), &fqns, serde_json::to_value(&report)?)?;
+                }
+            }
+        }
+        
+        Commands::Check {
+            commit,
+            base,
+            max_cycles,
+            forbid,
+            api_breaks,
+            min_resolution,
+            hotspot_fan_in,
+            dead_code,
+            duplicates,
+            layers,
+            format,
+        } => {
+            let store = GraphStore::new(&repo_root)?;
+            let commit = match commit {
+                Some(commit) => commit,
+                None => store.get_latest_commit()?
+                    .ok_or_else(|| anyhow::anyhow!("No scanned commit to check"))?,
+            };
+            // Snapshots are keyed by commit, so the base diffs straight in git
+            let changes = match (&base, hotspot_fan_in) {
+                (Some(base), Some(_)) => Some(ChangedLines::from_git(&repo_root, base, &commit)?),
+                _ => None,
+            };
+            let layers = if layers {
+                let layers = Layers::from_config(&Config::load(&repo_root)?.layers)?;
+                if layers.is_empty() {
+                    return Err(anyhow::anyhow!("--layers needs `layers` declared in {}", config::CONFIG_FILE));
+                }
+                Some(layers)
+            } else {
+                None
+            };
+            let options = CheckOptions {
+                base,
+                max_cycles,
+                forbidden: forbid,
+                api_breaks,
+                min_resolution,
+                hotspot_fan_in,
+                changes,
+                dead_code,
+                duplicate_lines: duplicates,
+                layers,
+            };
+            let report = check::run(&store, &repo_root, &commit, &options)?;
+            
+            match (format, output) {
+                (Some(format), _) => print!("{}", format.render(&report)),
+                (None, Output::Json) => print_json(&report)?,
+                (None, Output::Quiet) => {
+                    for check in report.checks.iter().filter(|check| !check.passed) {
+                        println!("{}", check.name);
+                    }
+                }
+                (None, Output::Text) => print!("{}", report.to_text()),
+            }
+            
+            if !report.passed {
+                drop(telemetry);
+                std::process::exit(CHECK_FAILED_EXIT_CODE);
+            }
+        }
+        
+        Commands::Index { cmd: IndexCommands::Export { cmd } } => {
+            let store = GraphStore::new(&repo_root)?;
+            
+            match cmd {
+                ExportCommands::Jsonl { commit, output: path } => {
+                    let commit = match commit {
+                        Some(commit) => commit,
+                        None => store.get_latest_commit()?
+                            .ok_or_else(|| anyhow::anyhow!("No scanned commit to export"))?,
+                    };
                     
-                    let from_id = from_sym.unwrap().id;
-                    let to_id = to_sym.unwrap().id;
-                    
-                    if let Some(path) = graph.find_path(&from_id, &to_id) {
-                        println!("Path from '{}' to '{}':", from, to);
-                        for sym_id in path {
-                            if let Some(sym) = store.find_symbol_by_id(&sym_id)? {
-                                println!("  -> {} ({})", sym.fqn, sym.file_path);
-                            }
+                    // Already machine-readable: JSON lines regardless of --json
+                    match path {
+                        Some(path) => {
+                            let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+                            store.export_jsonl(&commit, &mut file)?;
                         }
-                    } else {
-                        println!("No path found from '{}' to '{}'", from, to);
+                        None => store.export_jsonl(&commit, &mut std::io::stdout().lock())?,
                     }
                 }
+                
+                ExportCommands::Bundle { commit, output: bundle, with_fts } => {
+                    let commit = match commit {
+                        Some(commit) => commit,
+                        None => store.get_latest_commit()?
+                            .ok_or_else(|| anyhow::anyhow!("No scanned commit to export"))?,
+                    };
+                    store.export_bundle(&commit, &bundle, with_fts)?;
+                    let size = std::fs::metadata(&bundle)?.len();
+                    output.summary(
+                        &format!("Exported snapshot {} to {} ({} bytes)", commit, bundle.display(), size),
+                        &[&commit],
+                        json!({ "commit": commit, "path": bundle, "bytes": size }),
+                    )?;
+                }
+            }
+        }
+        
+        Commands::Index { cmd: IndexCommands::Merge { bundles } } => {
+            let store = GraphStore::new(&repo_root)?;
+            let summary = store.merge_bundles(&bundles)?;
+            output.summary(
+                &format!(
+                    "Merged {} shards into snapshot {} ({} duplicate rows dropped)",
+                    summary.shards, summary.commit, summary.duplicates
+                ),
+                &[&summary.commit],
+                json!({ "commit": summary.commit, "shards": summary.shards, "duplicates": summary.duplicates }),
+            )?;
+        }
+        
+        Commands::Index { cmd: IndexCommands::Sync { cmd } } => {
+            let store = GraphStore::new(&repo_root)?;
+            let open = |remote: Option<String>| -> Result<Box<dyn sync::Remote>> {
+                let url = remote.or_else(sync::default_remote)
+                    .ok_or_else(|| anyhow::anyhow!("No remote given (--remote or REVIEWBOT_REMOTE)"))?;
+                sync::open_remote(&url)
+            };
+            
+            match cmd {
+                SyncCommands::Push { remote, commit, full } => {
+                    let remote = open(remote)?;
+                    let commit = match commit {
+                        Some(commit) => commit,
+                        None => store.get_latest_commit()?
+                            .ok_or_else(|| anyhow::anyhow!("No scanned commit to push"))?,
+                    };
+                    let (text, json) = match sync::push(&store, remote.as_ref(), &commit, full)? {
+                        sync::PushOutcome::AlreadyPresent => (
+                            format!("Remote already has {}", commit),
+                            json!({ "commit": commit, "pushed": false }),
+                        ),
+                        sync::PushOutcome::Full { bytes } => (
+                            format!("Pushed {} ({} bytes)", commit, bytes),
+                            json!({ "commit": commit, "pushed": true, "base": null, "bytes": bytes }),
+                        ),
+                        sync::PushOutcome::Delta { base, bytes } => (
+                            format!("Pushed {} as a delta against {} ({} bytes)", commit, base, bytes),
+                            json!({ "commit": commit, "pushed": true, "base": base, "bytes": bytes }),
+                        ),
+                    };
+                    output.summary(&text, &[&commit], json)?;
+                }
+                
+                SyncCommands::Pull { remote, commit } => {
+                    let remote = open(remote)?;
+                    let imported = sync::pull(&store, remote.as_ref(), commit.as_deref())?;
+                    let text = if imported.is_empty() {
+                        "Already up to date".to_string()
+                    } else {
+                        format!("Pulled {}", imported.join(", "))
+                    };
+                    output.summary(
+                        &text,
+                        &imported.iter().map(String::as_str).collect::<Vec<_>>(),
+                        json!({ "imported": imported }),
+                    )?;
+                }
+            }
+        }
+        
+        Commands::Index { cmd: IndexCommands::Import { cmd } } => {
+            let store = GraphStore::new(&repo_root)?;
+            
+            match cmd {
+                ImportCommands::Bundle { path } => {
+                    let commit = store.import_bundle(&path)?;
+                    output.summary(
+                        &format!("Imported snapshot {} from {}", commit, path.display()),
+                        &[&commit],
+                        json!({ "commit": commit, "path": path }),
+                    )?;
+                }
             }
         }
     }
@@ -620,6 +1980,28 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Export end-of-scan counters. In JSON mode they go out as a single
+/// `scan_metrics` event so CI can track indexing performance over time.
+/// Store for graph queries, with standard library calls dropped or
+/// collapsed as configured
+/// Files whose event edges a scan recomputes
+fn event_files(file_events: &[(String, FileEvents)]) -> Vec<String> {
+    file_events.iter().map(|(file, _)| file.clone()).collect()
+}
+
+fn open_query_store(repo_root: &Path, mode: Option<NoiseMode>) -> Result<GraphStore> {
+    let config = Config::load(repo_root)?;
+    let noise = NoiseFilter::new(mode.unwrap_or(config.call_noise.mode), &config.call_noise.extra);
+    let separators = config.fqn_separators.map(FqnSeparators::new).unwrap_or_default();
+    Ok(GraphStore::new(repo_root)?.with_noise_filter(noise).with_fqn_separators(separators))
+}
+
+fn emit_metrics(metrics: &PerformanceMetrics, log_format: LogFormat) {
+    if log_format == LogFormat::Json {
+        info!(event = "scan_metrics", metrics = %metrics.to_json());
+    }
+}
+
 fn get_current_commit(repo_root: &PathBuf) -> Result<String> {
     let output = std::process::Command::new("git")
         .arg("rev-parse")
@@ -90,6 +90,19 @@ impl MetricsCollector {
         debug!("Found {} {} occurrences", count, language);
     }
 
+    /// Add time spent in a phase that runs many times (e.g. per-file parsing)
+    pub fn add_phase_duration(&mut self, phase: &str, duration: Duration) {
+        *self.phase_durations.entry(phase.to_string()).or_default() += duration;
+    }
+
+    /// Accumulate per-language counters for one processed file
+    pub fn add_file_counts(&mut self, language: &str, symbols: usize, edges: usize, occurrences: usize) {
+        *self.file_counts.entry(language.to_string()).or_default() += 1;
+        *self.symbol_counts.entry(language.to_string()).or_default() += symbols;
+        *self.edge_counts.entry(language.to_string()).or_default() += edges;
+        *self.occurrence_counts.entry(language.to_string()).or_default() += occurrences;
+    }
+
     pub fn record_lines_of_code(&mut self, lines: usize) {
         self.total_lines_of_code += lines;
     }
@@ -183,6 +196,29 @@ impl MetricsCollector {
     }
 }
 
+impl PerformanceMetrics {
+    /// Machine-readable form with durations in milliseconds, for CI dashboards
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut phases: Vec<(&String, &Duration)> = self.phase_durations.iter().collect();
+        phases.sort();
+        let phase_ms: serde_json::Map<String, serde_json::Value> = phases
+            .into_iter()
+            .map(|(phase, duration)| (phase.clone(), serde_json::json!(duration.as_secs_f64() * 1000.0)))
+            .collect();
+
+        serde_json::json!({
+            "total_ms": self.total_duration.as_secs_f64() * 1000.0,
+            "phase_ms": phase_ms,
+            "files": self.file_counts,
+            "symbols": self.symbol_counts,
+            "edges": self.edge_counts,
+            "occurrences": self.occurrence_counts,
+            "memory": self.memory_usage,
+            "throughput": self.throughput_metrics,
+        })
+    }
+}
+
 impl Default for MetricsCollector {
     fn default() -> Self {
         Self::new()
@@ -268,6 +304,21 @@ mod tests {
         assert!(metrics.throughput_metrics.files_per_second > 0.0);
     }
     
+    #[test]
+    fn test_accumulated_counters_and_json() {
+        let mut collector = MetricsCollector::new();
+        collector.add_phase_duration("parse:rust", Duration::from_millis(5));
+        collector.add_phase_duration("parse:rust", Duration::from_millis(7));
+        collector.add_file_counts("rust", 10, 4, 20);
+        collector.add_file_counts("rust", 2, 1, 3);
+
+        let json = collector.finalize().to_json();
+        assert_eq!(json["files"]["rust"], 2);
+        assert_eq!(json["symbols"]["rust"], 12);
+        assert_eq!(json["occurrences"]["rust"], 23);
+        assert!(json["phase_ms"]["parse:rust"].as_f64().unwrap() >= 12.0);
+    }
+
     #[test]
     fn test_memory_usage() {
         // Memory usage function should not panic
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+
+/// Output flags accepted by every command
+#[derive(Debug, Clone, Copy, Default, Args)]
+pub struct OutputArgs {
+    /// Print the result as a single JSON document on stdout
+    #[arg(long, global = true, conflicts_with = "quiet")]
+    pub json: bool,
+
+    /// Print only the bare results, one per line, without headings or hints
+    #[arg(long, global = true)]
+    pub quiet: bool,
+}
+
+/// How a command prints its result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    /// Headings and indented detail for people
+    Text,
+    /// One JSON document for scripts
+    Json,
+    /// Result keys (FQNs, locations, commits) one per line, for `xargs` and friends
+    Quiet,
+}
+
+impl OutputArgs {
+    pub fn mode(&self) -> Output {
+        if self.json {
+            Output::Json
+        } else if self.quiet {
+            Output::Quiet
+        } else {
+            Output::Text
+        }
+    }
+}
+
+impl Output {
+    /// Whether stdout carries results a script will parse, so logs have to
+    /// go elsewhere
+    pub fn is_machine_readable(self) -> bool {
+        self != Output::Text
+    }
+
+    /// Report the outcome of a command: `text` for people, `json` for
+    /// scripts and `keys` (one per line) when quiet
+    pub fn summary(self, text: &str, keys: &[&str], json: serde_json::Value) -> Result<()> {
+        match self {
+            Output::Text => println!("{}", text),
+            Output::Json => print_json(&json)?,
+            Output::Quiet => {
+                for key in keys {
+                    println!("{}", key);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Print `value` as pretty JSON on stdout
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Parser, Subcommand};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        output: OutputArgs,
+
+        #[command(subcommand)]
+        command: Commands,
+    }
+
+    #[derive(Subcommand)]
+    enum Commands {
+        Search { query: String },
+    }
+
+    #[test]
+    fn test_output_flags_after_subcommand() {
+        let cli = Cli::try_parse_from(["reviewbot", "search", "User", "--json"]).unwrap();
+        assert_eq!(cli.output.mode(), Output::Json);
+        let cli = Cli::try_parse_from(["reviewbot", "--quiet", "search", "User"]).unwrap();
+        assert_eq!(cli.output.mode(), Output::Quiet);
+        let cli = Cli::try_parse_from(["reviewbot", "search", "User"]).unwrap();
+        assert_eq!(cli.output.mode(), Output::Text);
+        assert!(!Output::Text.is_machine_readable());
+
+        assert!(Cli::try_parse_from(["reviewbot", "search", "User", "--json", "--quiet"]).is_err());
+    }
+}
@@ -302,7 +302,7 @@ impl ResolutionEngine {
                 // Try to resolve using stored semantic data
                 if let Some(ref dst_name) = edge.dst {
                     // Look up symbol by FQN
-                    if let Ok(Some(symbol)) = self.store.find_symbol_by_fqn(dst_name) {
+                    if let Ok(Some(symbol)) = self.store.find_symbol_by_fqn(dst_name, None).map(|m| m.map(|m| m.symbol)) {
                         // Found a match - upgrade to semantic resolution
                         edge.dst = Some(symbol.id.clone());
                         edge.resolution = Resolution::Semantic;
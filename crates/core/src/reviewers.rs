@@ -0,0 +1,496 @@
+use crate::diff::ChangedLines;
+use anyhow::Result;
+use globset::{GlobBuilder, GlobMatcher};
+use protocol::{EdgeIR, EdgeType, Span, SymbolIR};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+/// Where GitHub looks for a CODEOWNERS file, in order
+const CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Weight of a symbol the change touches; its direct dependents count for
+/// [`DEPENDENT_WEIGHT`] since they are only affected through it
+const TOUCHED_WEIGHT: f64 = 1.0;
+const DEPENDENT_WEIGHT: f64 = 0.5;
+
+/// Symbols listed per reviewer as the reason for suggesting them
+const REASONS_SHOWN: usize = 5;
+
+/// Share of the depended-upon code whose authors count towards the bus factor
+const BUS_FACTOR_SHARE: f64 = 0.5;
+
+/// Owners of paths per a CODEOWNERS file: the last matching pattern wins
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<(GlobMatcher, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Parse CODEOWNERS text; patterns that don't compile are skipped
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = owners_glob(parts.next()?)?;
+                Some((pattern, parts.take_while(|p| !p.starts_with('#')).map(str::to_string).collect()))
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// The repository's CODEOWNERS file, if it has one
+    pub fn load(repo_root: &Path) -> Result<Option<Self>> {
+        for path in CODEOWNERS_PATHS {
+            let path = repo_root.join(path);
+            if path.is_file() {
+                return Ok(Some(Self::parse(&std::fs::read_to_string(path)?)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Owners of a repo-relative path; empty when the last match assigns none
+    pub fn owners(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern.is_match(path))
+            .map_or(&[], |(_, owners)| owners.as_slice())
+    }
+}
+
+/// Compile a gitignore-style CODEOWNERS pattern. A pattern without a slash
+/// (other than a trailing one) matches at any depth, and one naming a
+/// directory covers everything under it.
+fn owners_glob(pattern: &str) -> Option<GlobMatcher> {
+    let anchored = pattern.trim_end_matches('/').contains('/');
+    let mut glob = pattern.trim_start_matches('/').to_string();
+    if !anchored && !glob.starts_with("**/") {
+        glob = format!("**/{}", glob);
+    }
+    if glob.ends_with('/') {
+        glob.push_str("**");
+    } else if !glob.ends_with("/**") && !glob.ends_with("/*") {
+        // `docs` matches the file or the directory
+        glob = format!("{{{},{}/**}}", glob, glob);
+    }
+    GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .build()
+        .ok()
+        .map(|glob| glob.compile_matcher())
+}
+
+/// Author of each line of `file` at `rev`, as `Name <email>`; `None` when git
+/// doesn't know the file there
+pub fn blame(repo_root: &Path, rev: &str, file: &str) -> Result<Option<Vec<String>>> {
+    let output = std::process::Command::new("git")
+        .args(["blame", "--line-porcelain", rev, "--", file])
+        .current_dir(repo_root)
+        .output()?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(parse_blame(&String::from_utf8_lossy(&output.stdout))))
+}
+
+fn parse_blame(porcelain: &str) -> Vec<String> {
+    let mut authors = Vec::new();
+    let mut name = "";
+    for line in porcelain.lines() {
+        if let Some(author) = line.strip_prefix("author ") {
+            name = author;
+        } else if let Some(mail) = line.strip_prefix("author-mail ") {
+            authors.push(format!("{} {}", name, mail));
+        }
+    }
+    authors
+}
+
+/// A person or team worth asking to review, with why
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Suggestion {
+    /// CODEOWNERS owner (`@org/team`, `@user`, email) or blamed author
+    pub reviewer: String,
+    pub score: f64,
+    /// Impacted symbols the reviewer owns per CODEOWNERS
+    pub owned: usize,
+    /// Impacted symbols the reviewer wrote lines of
+    pub authored: usize,
+    /// Most relevant impacted symbols, by FQN
+    pub symbols: Vec<String>,
+}
+
+#[derive(Default)]
+struct Tally {
+    score: f64,
+    owned: usize,
+    authored: usize,
+    symbols: Vec<(f64, String)>,
+}
+
+/// Rank reviewers for a change. Impacted symbols are the ones the change
+/// touches and their direct dependents; each credits its CODEOWNERS owners
+/// with its weight and its blamed authors with their share of its lines.
+pub fn suggest(
+    symbols: &[SymbolIR],
+    edges: &[EdgeIR],
+    changes: &ChangedLines,
+    owners: Option<&CodeOwners>,
+    mut blame: impl FnMut(&str) -> Option<Vec<String>>,
+    exclude: &[String],
+) -> Vec<Suggestion> {
+    let by_id: HashMap<&str, &SymbolIR> = symbols.iter().map(|s| (s.id.as_str(), s)).collect();
+    let mut impacted: HashMap<&str, f64> = HashMap::new();
+    for symbol in symbols.iter().filter(|s| changes.touches(s)) {
+        impacted.insert(symbol.id.as_str(), TOUCHED_WEIGHT);
+    }
+    let touched: HashSet<&str> = impacted.keys().copied().collect();
+    for edge in edges.iter().filter(|e| e.edge_type != EdgeType::Contains) {
+        if let (Some(src), Some(dst)) = (edge.src.as_deref(), edge.dst.as_deref()) {
+            if touched.contains(dst) && by_id.contains_key(src) {
+                impacted.entry(src).or_insert(DEPENDENT_WEIGHT);
+            }
+        }
+    }
+
+    let mut blames: HashMap<&str, Option<Vec<String>>> = HashMap::new();
+    let mut tallies: HashMap<String, Tally> = HashMap::new();
+    let mut ordered: Vec<(&str, f64)> = impacted.into_iter().collect();
+    ordered.sort_by(|a, b| a.0.cmp(b.0));
+    for (id, weight) in ordered {
+        let symbol = by_id[id];
+        let file = symbol.file_path.as_str();
+
+        for owner in owners.map_or(&[][..], |owners| owners.owners(file)) {
+            let tally = tallies.entry(owner.clone()).or_default();
+            tally.score += weight;
+            tally.owned += 1;
+            tally.symbols.push((weight, symbol.fqn.clone()));
+        }
+
+        let Some(lines) = blames.entry(file).or_insert_with(|| blame(file)) else {
+            continue;
+        };
+        for (author, line_share) in authors_of(lines, &symbol.full_span) {
+            let share = weight * line_share;
+            let tally = tallies.entry(author.to_string()).or_default();
+            tally.score += share;
+            tally.authored += 1;
+            tally.symbols.push((share, symbol.fqn.clone()));
+        }
+    }
+
+    let mut suggestions: Vec<Suggestion> = tallies
+        .into_iter()
+        .filter(|(reviewer, _)| !exclude.iter().any(|who| reviewer.contains(who.as_str())))
+        .map(|(reviewer, mut tally)| {
+            tally.symbols.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+            let mut seen = BTreeSet::new();
+            let symbols = tally
+                .symbols
+                .into_iter()
+                .map(|(_, fqn)| fqn)
+                .filter(|fqn| seen.insert(fqn.clone()))
+                .take(REASONS_SHOWN)
+                .collect();
+            Suggestion { reviewer, score: tally.score, owned: tally.owned, authored: tally.authored, symbols }
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.reviewer.cmp(&b.reviewer)));
+    suggestions
+}
+
+/// Each author of the `span` lines of a blamed file, with their share of them
+fn authors_of<'a>(lines: &'a [String], span: &Span) -> HashMap<&'a str, f64> {
+    let rows = lines
+        .get(span.start_line as usize..=(span.end_line as usize).min(lines.len().saturating_sub(1)))
+        .unwrap_or(&[]);
+    let mut counts: HashMap<&str, f64> = HashMap::new();
+    for author in rows {
+        *counts.entry(author.as_str()).or_default() += 1.0 / rows.len() as f64;
+    }
+    counts
+}
+
+/// How much depended-upon code a person or team owns
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OwnerHeat {
+    /// CODEOWNERS owner or blamed author
+    pub owner: String,
+    /// `codeowners` or `blame`
+    pub via: &'static str,
+    /// Dependents of the symbols they own, each symbol's split among its
+    /// authors by lines written
+    pub fan_in: f64,
+    /// Their share of all such dependents, among owners found the same way
+    pub share: f64,
+    pub symbols: usize,
+    /// Their most depended-upon symbols, by FQN
+    pub top_symbols: Vec<String>,
+}
+
+/// Who owns the code the rest of the repo depends on
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnershipReport {
+    pub commit: String,
+    /// Symbols with enough dependents to count
+    pub symbols: usize,
+    /// Fewest blamed authors who together wrote half of the depended-upon
+    /// code; 0 without blame data
+    pub bus_factor: usize,
+    /// Most depended-upon first
+    pub owners: Vec<OwnerHeat>,
+}
+
+impl OwnershipReport {
+    pub fn to_text(&self, shown: usize) -> String {
+        let mut out = format!(
+            "Ownership of {} symbols with dependents in {} (bus factor {})\n",
+            self.symbols, self.commit, self.bus_factor
+        );
+        for heat in self.owners.iter().take(shown) {
+            out.push_str(&format!(
+                "  {:>7.1}  {:>3.0}%  {:<10} {} ({} symbols: {})\n",
+                heat.fan_in,
+                heat.share * 100.0,
+                heat.via,
+                heat.owner,
+                heat.symbols,
+                heat.top_symbols.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+/// Credit the dependents of every symbol with at least `min_fan_in` of
+/// them to its CODEOWNERS owners in full and to its blamed authors by
+/// their share of its lines
+pub fn ownership(
+    commit: &str,
+    symbols: &[SymbolIR],
+    edges: &[EdgeIR],
+    owners: Option<&CodeOwners>,
+    mut blame: impl FnMut(&str) -> Option<Vec<String>>,
+    min_fan_in: usize,
+) -> OwnershipReport {
+    let mut dependents: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for edge in edges.iter().filter(|e| e.edge_type != EdgeType::Contains) {
+        if let (Some(src), Some(dst)) = (edge.src.as_deref(), edge.dst.as_deref()) {
+            if src != dst {
+                dependents.entry(dst).or_default().insert(src);
+            }
+        }
+    }
+    let mut depended: Vec<(&SymbolIR, usize)> = symbols
+        .iter()
+        .map(|s| (s, dependents.get(s.id.as_str()).map_or(0, HashSet::len)))
+        .filter(|(_, fan_in)| *fan_in > 0 && *fan_in >= min_fan_in)
+        .collect();
+    depended.sort_by(|a, b| a.0.file_path.cmp(&b.0.file_path).then_with(|| a.0.id.cmp(&b.0.id)));
+
+    let mut blames: HashMap<&str, Option<Vec<String>>> = HashMap::new();
+    let mut tallies: HashMap<(&'static str, String), Tally> = HashMap::new();
+    let mut credit = |via: &'static str, owner: &str, weight: f64, fqn: &str| {
+        let tally = tallies.entry((via, owner.to_string())).or_default();
+        tally.score += weight;
+        tally.owned += 1;
+        tally.symbols.push((weight, fqn.to_string()));
+    };
+    for &(symbol, fan_in) in &depended {
+        let file = symbol.file_path.as_str();
+        for owner in owners.map_or(&[][..], |owners| owners.owners(file)) {
+            credit("codeowners", owner, fan_in as f64, &symbol.fqn);
+        }
+        if let Some(lines) = blames.entry(file).or_insert_with(|| blame(file)) {
+            for (author, share) in authors_of(lines, &symbol.full_span) {
+                credit("blame", author, fan_in as f64 * share, &symbol.fqn);
+            }
+        }
+    }
+
+    let mut totals: HashMap<&str, f64> = HashMap::new();
+    for ((via, _), tally) in &tallies {
+        *totals.entry(via).or_default() += tally.score;
+    }
+    let mut heats: Vec<OwnerHeat> = tallies
+        .into_iter()
+        .map(|((via, owner), mut tally)| {
+            tally.symbols.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+            OwnerHeat {
+                share: tally.score / totals[via],
+                owner,
+                via,
+                fan_in: tally.score,
+                symbols: tally.owned,
+                top_symbols: tally.symbols.into_iter().take(REASONS_SHOWN).map(|(_, fqn)| fqn).collect(),
+            }
+        })
+        .collect();
+    heats.sort_by(|a, b| {
+        b.fan_in
+            .total_cmp(&a.fan_in)
+            .then_with(|| a.via.cmp(b.via))
+            .then_with(|| a.owner.cmp(&b.owner))
+    });
+
+    let mut covered = 0.0;
+    let bus_factor = heats
+        .iter()
+        .filter(|h| h.via == "blame")
+        .take_while(|h| {
+            let short = covered < BUS_FACTOR_SHARE;
+            covered += h.share;
+            short
+        })
+        .count();
+
+    OwnershipReport {
+        commit: commit.to_string(),
+        symbols: depended.len(),
+        bus_factor,
+        owners: heats,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Language, Resolution, Span, test_util};
+
+    fn symbol(id: &str, file: &str, start_line: u32, end_line: u32) -> SymbolIR {
+        SymbolIR {
+            lang: Language::Go,
+            name: id.to_string(),
+            name_span: Span { start_line, start_col: 5, end_line: start_line, end_col: 8 },
+            full_span: Span { start_line, start_col: 0, end_line, end_col: 1 },
+            ..test_util::symbol(id, &format!("pkg.{}", id), file)
+        }
+    }
+
+    #[test]
+    fn test_codeowners_last_match_wins() {
+        let owners = CodeOwners::parse(
+            "# Owners\n\
+             *           @org/everyone\n\
+             *.go        @org/go-team  # backend\n\
+             /docs/      @org/writers\n\
+             api/v1/     @alice bob@example.com\n\
+             /vendor/**\n",
+        );
+        assert_eq!(owners.owners("README.md"), ["@org/everyone"]);
+        assert_eq!(owners.owners("cmd/server/main.go"), ["@org/go-team"]);
+        assert_eq!(owners.owners("docs/guide/intro.md"), ["@org/writers"]);
+        assert_eq!(owners.owners("src/docs/x.md"), ["@org/everyone"]);
+        assert_eq!(owners.owners("api/v1/handler.go"), ["@alice", "bob@example.com"]);
+        assert!(owners.owners("vendor/lib/x.go").is_empty());
+    }
+
+    #[test]
+    fn test_parse_blame() {
+        let porcelain = "\
+4e1d 1 1 1
+author Ada Lovelace
+author-mail <ada@example.com>
+summary first
+\tfunc main() {
+9f2c 2 2 1
+author Alan Turing
+author-mail <alan@example.com>
+\t}
+";
+        assert_eq!(parse_blame(porcelain), ["Ada Lovelace <ada@example.com>", "Alan Turing <alan@example.com>"]);
+    }
+
+    #[test]
+    fn test_suggest_ranks_owners_and_authors() {
+        let symbols = vec![
+            symbol("serve", "server.go", 0, 3),
+            symbol("handle", "handler.go", 0, 1),
+            symbol("unrelated", "handler.go", 5, 6),
+        ];
+        let edges = vec![EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some("handle".to_string()),
+            dst: Some("serve".to_string()),
+            file_src: Some("handler.go".to_string()),
+            file_dst: Some("server.go".to_string()),
+            resolution: Resolution::Syntactic,
+            meta: Default::default(),
+            provenance: Default::default(),
+        }];
+        let changes = ChangedLines::parse("+++ b/server.go\n@@ -2 +2 @@\n");
+        let owners = CodeOwners::parse("server.go @org/net\n");
+        let blame = |file: &str| {
+            let lines = match file {
+                "server.go" => vec!["ada", "ada", "ada", "alan"],
+                _ => vec!["alan", "alan", "", "", "", "grace", "grace"],
+            };
+            Some(lines.into_iter().map(str::to_string).collect())
+        };
+
+        let suggestions = suggest(&symbols, &edges, &changes, Some(&owners), blame, &[]);
+        let ranked: Vec<(&str, f64)> = suggestions.iter().map(|s| (s.reviewer.as_str(), s.score)).collect();
+        // Ties go by name
+        assert_eq!(ranked, [("@org/net", 1.0), ("ada", 0.75), ("alan", 0.75)]);
+        assert_eq!(suggestions[0].owned, 1);
+        assert_eq!(suggestions[2].symbols, ["pkg.handle", "pkg.serve"]);
+
+        let excluded = suggest(&symbols, &edges, &changes, None, blame, &["alan".to_string()]);
+        assert_eq!(excluded.iter().map(|s| s.reviewer.as_str()).collect::<Vec<_>>(), ["ada"]);
+    }
+
+    #[test]
+    fn test_ownership_weighs_owners_by_fan_in() {
+        let symbols = vec![
+            symbol("serve", "server.go", 0, 3),
+            symbol("handle", "handler.go", 0, 1),
+            symbol("lonely", "handler.go", 5, 6),
+        ];
+        let edge = |edge_type: EdgeType, src: &str, dst: &str| EdgeIR {
+            edge_type,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: Default::default(),
+            provenance: Default::default(),
+        };
+        let edges = vec![
+            edge(EdgeType::Calls, "handle", "serve"),
+            edge(EdgeType::Calls, "main", "serve"),
+            edge(EdgeType::Calls, "main", "handle"),
+            edge(EdgeType::Calls, "serve", "serve"),
+            edge(EdgeType::Contains, "pkg", "serve"),
+        ];
+        let owners = CodeOwners::parse("server.go @org/net\n");
+        let blame = |file: &str| {
+            let lines = match file {
+                "server.go" => vec!["ada", "ada", "ada", "alan"],
+                _ => vec!["alan", "alan", "", "", "", "grace", "grace"],
+            };
+            Some(lines.into_iter().map(str::to_string).collect())
+        };
+
+        let report = ownership("abc123", &symbols, &edges, Some(&owners), blame, 1);
+        let ranked: Vec<(&str, &str, f64, f64)> =
+            report.owners.iter().map(|h| (h.owner.as_str(), h.via, h.fan_in, h.share)).collect();
+        assert_eq!(
+            ranked,
+            [("@org/net", "codeowners", 2.0, 1.0), ("ada", "blame", 1.5, 0.5), ("alan", "blame", 1.5, 0.5)]
+        );
+        assert_eq!((report.symbols, report.bus_factor), (2, 1));
+        assert_eq!(report.owners[2].top_symbols, ["pkg.handle", "pkg.serve"]);
+        assert!(report.to_text(1).contains("100%  codeowners @org/net (1 symbols: pkg.serve)"));
+
+        let report = ownership("abc123", &symbols, &edges, None, blame, 2);
+        assert_eq!(report.symbols, 1);
+        assert_eq!(report.owners.iter().map(|h| h.owner.as_str()).collect::<Vec<_>>(), ["ada", "alan"]);
+    }
+}
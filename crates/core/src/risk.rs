@@ -0,0 +1,370 @@
+use crate::diff::ChangedLines;
+use anyhow::{anyhow, Result};
+use protocol::{EdgeIR, EdgeType, SymbolFlags, SymbolIR};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use store::CodeGraph;
+
+/// Share of the score each factor carries; they sum to 1
+const CENTRALITY_WEIGHT: f64 = 0.25;
+const FAN_IN_WEIGHT: f64 = 0.2;
+const UNTESTED_WEIGHT: f64 = 0.25;
+const CHURN_WEIGHT: f64 = 0.15;
+const CYCLE_WEIGHT: f64 = 0.15;
+
+/// Dependents and recent commits at which their factors max out
+const FAN_IN_SATURATION: usize = 50;
+const CHURN_SATURATION: usize = 20;
+
+const PAGE_RANK_DAMPING: f64 = 0.85;
+const PAGE_RANK_ITERATIONS: usize = 20;
+
+/// One input to the score, with its 0-1 value and what it was based on
+#[derive(Debug, Clone, Serialize)]
+pub struct Factor {
+    pub name: &'static str,
+    pub weight: f64,
+    pub value: f64,
+    pub detail: String,
+}
+
+/// The factors as measured on one symbol the change touches
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolRisk {
+    pub fqn: String,
+    pub file: String,
+    /// 1-based line of the symbol's name
+    pub line: u32,
+    /// Share of the graph's symbols with a lower PageRank
+    pub centrality: f64,
+    /// Distinct symbols referring to this one
+    pub fan_in: usize,
+    /// Reached from a test through references
+    pub tested: bool,
+    /// Commits to its file in the churn window
+    pub churn: usize,
+    pub in_cycle: bool,
+    /// Weighted 0-100 score of this symbol alone
+    pub score: f64,
+}
+
+/// How risky a change is, 0-100, and why
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskReport {
+    pub commit: String,
+    pub score: f64,
+    /// `low` below 30, `high` from 60
+    pub level: &'static str,
+    pub factors: Vec<Factor>,
+    /// Touched symbols, riskiest first
+    pub symbols: Vec<SymbolRisk>,
+}
+
+impl RiskReport {
+    pub fn to_text(&self, shown: usize) -> String {
+        let mut out = format!(
+            "Risk {:.0}/100 ({}) for {} changed symbols in {}\n",
+            self.score,
+            self.level,
+            self.symbols.len(),
+            self.commit
+        );
+        for factor in &self.factors {
+            out.push_str(&format!(
+                "  {:<10} {:>3.0}% x {:.2}  {}\n",
+                factor.name,
+                factor.weight * 100.0,
+                factor.value,
+                factor.detail
+            ));
+        }
+        if !self.symbols.is_empty() {
+            out.push_str("Riskiest symbols:\n");
+        }
+        for symbol in self.symbols.iter().take(shown) {
+            out.push_str(&format!(
+                "  {:>3.0}  {} ({}:{}) fan-in {}, {}{}{}\n",
+                symbol.score,
+                symbol.fqn,
+                symbol.file,
+                symbol.line,
+                symbol.fan_in,
+                if symbol.tested { "tested" } else { "untested" },
+                if symbol.churn > 0 { format!(", {} recent commits", symbol.churn) } else { String::new() },
+                if symbol.in_cycle { ", in a cycle" } else { "" },
+            ));
+        }
+        out
+    }
+}
+
+/// Commits touching each of `files` in the last `days` days
+pub fn churn<'a>(repo_root: &Path, days: u32, files: impl IntoIterator<Item = &'a str>) -> Result<HashMap<String, usize>> {
+    let files: Vec<&str> = files.into_iter().collect();
+    let mut counts = HashMap::new();
+    if files.is_empty() {
+        return Ok(counts);
+    }
+    let output = std::process::Command::new("git")
+        .args(["log", &format!("--since={} days ago", days), "--format=", "--name-only", "--"])
+        .args(&files)
+        .current_dir(repo_root)
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow!("git log failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    for file in String::from_utf8_lossy(&output.stdout).lines().filter(|line| !line.is_empty()) {
+        *counts.entry(file.to_string()).or_default() += 1;
+    }
+    Ok(counts)
+}
+
+/// Score the change `changes` makes to snapshot `commit`. Tests the change
+/// touches don't count; `churn` maps files to recent commits.
+pub fn assess(
+    commit: &str,
+    symbols: &[SymbolIR],
+    edges: &[EdgeIR],
+    changes: &ChangedLines,
+    churn: &HashMap<String, usize>,
+) -> RiskReport {
+    // Containment is structure, not dependency
+    let edges: Vec<EdgeIR> = edges.iter().filter(|e| e.edge_type != EdgeType::Contains).cloned().collect();
+    let graph = CodeGraph::build_from_data(symbols, &edges);
+
+    let ranks = graph.page_rank(PAGE_RANK_DAMPING, PAGE_RANK_ITERATIONS);
+    let mut sorted_ranks: Vec<f64> = symbols.iter().map(|s| ranks.get(&s.id).copied().unwrap_or(0.0)).collect();
+    sorted_ranks.sort_by(f64::total_cmp);
+    let percentile = |id: &str| {
+        let rank = ranks.get(id).copied().unwrap_or(0.0);
+        sorted_ranks.partition_point(|r| *r < rank) as f64 / sorted_ranks.len().max(1) as f64
+    };
+
+    let mut dependents: HashMap<&str, HashSet<&str>> = HashMap::new();
+    let mut callees: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in &edges {
+        if let (Some(src), Some(dst)) = (edge.src.as_deref(), edge.dst.as_deref()) {
+            if src != dst {
+                dependents.entry(dst).or_default().insert(src);
+                callees.entry(src).or_default().push(dst);
+            }
+        }
+    }
+    let tested = reached_from_tests(symbols, &callees);
+    let in_cycle: HashSet<String> = graph.cycles().into_iter().flatten().collect();
+
+    let mut touched: Vec<SymbolRisk> = symbols
+        .iter()
+        .filter(|s| !s.flags.contains(SymbolFlags::TEST_ONLY) && changes.touches(s))
+        .map(|s| {
+            let mut risk = SymbolRisk {
+                fqn: s.fqn.clone(),
+                file: s.file_path.clone(),
+                line: s.name_span.start_line + 1,
+                centrality: percentile(&s.id),
+                fan_in: dependents.get(s.id.as_str()).map_or(0, HashSet::len),
+                tested: tested.contains(s.id.as_str()),
+                churn: churn.get(&s.file_path).copied().unwrap_or(0),
+                in_cycle: in_cycle.contains(&s.id),
+                score: 0.0,
+            };
+            risk.score = 100.0
+                * weighted(
+                    risk.centrality,
+                    saturate(risk.fan_in, FAN_IN_SATURATION),
+                    if risk.tested { 0.0 } else { 1.0 },
+                    saturate(risk.churn, CHURN_SATURATION),
+                    if risk.in_cycle { 1.0 } else { 0.0 },
+                );
+            risk
+        })
+        .collect();
+    touched.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.fqn.cmp(&b.fqn)));
+
+    let factors = factors(&touched);
+    let score = 100.0 * factors.iter().map(|f| f.weight * f.value).sum::<f64>();
+    RiskReport {
+        commit: commit.to_string(),
+        score,
+        level: match score {
+            s if s < 30.0 => "low",
+            s if s < 60.0 => "medium",
+            _ => "high",
+        },
+        factors,
+        symbols: touched,
+    }
+}
+
+/// The change as a whole: its most central, most depended on and most
+/// churned symbol, and the share of its symbols untested or in cycles
+fn factors(touched: &[SymbolRisk]) -> Vec<Factor> {
+    let factor = |name, weight, value, detail| Factor { name, weight, value, detail };
+    let Some(central) = touched.iter().max_by(|a, b| a.centrality.total_cmp(&b.centrality)) else {
+        let none = || "no indexed symbols touched".to_string();
+        return vec![
+            factor("centrality", CENTRALITY_WEIGHT, 0.0, none()),
+            factor("fan-in", FAN_IN_WEIGHT, 0.0, none()),
+            factor("untested", UNTESTED_WEIGHT, 0.0, none()),
+            factor("churn", CHURN_WEIGHT, 0.0, none()),
+            factor("cycles", CYCLE_WEIGHT, 0.0, none()),
+        ];
+    };
+    let depended = touched.iter().max_by_key(|s| s.fan_in).unwrap_or(central);
+    let churned = touched.iter().max_by_key(|s| s.churn).unwrap_or(central);
+    let untested = touched.iter().filter(|s| !s.tested).count();
+    let cyclic = touched.iter().filter(|s| s.in_cycle).count();
+    let share = |count: usize| count as f64 / touched.len() as f64;
+
+    vec![
+        factor(
+            "centrality",
+            CENTRALITY_WEIGHT,
+            central.centrality,
+            format!("{} ranks above {:.0}% of symbols", central.fqn, central.centrality * 100.0),
+        ),
+        factor(
+            "fan-in",
+            FAN_IN_WEIGHT,
+            saturate(depended.fan_in, FAN_IN_SATURATION),
+            format!("{} symbols depend on {}", depended.fan_in, depended.fqn),
+        ),
+        factor(
+            "untested",
+            UNTESTED_WEIGHT,
+            share(untested),
+            format!("{} of {} changed symbols are not reached from tests", untested, touched.len()),
+        ),
+        factor(
+            "churn",
+            CHURN_WEIGHT,
+            saturate(churned.churn, CHURN_SATURATION),
+            format!("{} recent commits to {}", churned.churn, churned.file),
+        ),
+        factor(
+            "cycles",
+            CYCLE_WEIGHT,
+            share(cyclic),
+            format!("{} of {} changed symbols are in a dependency cycle", cyclic, touched.len()),
+        ),
+    ]
+}
+
+fn weighted(centrality: f64, fan_in: f64, untested: f64, churn: f64, cycles: f64) -> f64 {
+    CENTRALITY_WEIGHT * centrality
+        + FAN_IN_WEIGHT * fan_in
+        + UNTESTED_WEIGHT * untested
+        + CHURN_WEIGHT * churn
+        + CYCLE_WEIGHT * cycles
+}
+
+/// 0 at zero, 1 at `at` and beyond, logarithmic in between so the first few
+/// count most
+fn saturate(count: usize, at: usize) -> f64 {
+    ((1.0 + count as f64).ln() / (1.0 + at as f64).ln()).min(1.0)
+}
+
+/// Symbols some test reaches by following references
+fn reached_from_tests<'a>(symbols: &'a [SymbolIR], callees: &HashMap<&'a str, Vec<&'a str>>) -> HashSet<&'a str> {
+    let mut reached: HashSet<&str> = HashSet::new();
+    let mut queue: VecDeque<&str> = symbols
+        .iter()
+        .filter(|s| s.flags.contains(SymbolFlags::TEST_ONLY))
+        .map(|s| s.id.as_str())
+        .collect();
+    while let Some(id) = queue.pop_front() {
+        for &callee in callees.get(id).into_iter().flatten() {
+            if reached.insert(callee) {
+                queue.push_back(callee);
+            }
+        }
+    }
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Language, Resolution, Span, test_util};
+
+    fn symbol(id: &str, file: &str, line: u32) -> SymbolIR {
+        SymbolIR {
+            lang: Language::Python,
+            name: id.to_string(),
+            name_span: Span { start_line: line, start_col: 4, end_line: line, end_col: 8 },
+            full_span: test_util::lines(line, line + 2),
+            ..test_util::symbol(id, &format!("app.{}", id), file)
+        }
+    }
+
+    fn calls(src: &str, dst: &str) -> EdgeIR {
+        EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: Default::default(),
+            provenance: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_assess_breaks_down_the_score() {
+        let mut test = symbol("test_save", "tests/test_db.py", 0);
+        test.flags = SymbolFlags::TEST_ONLY;
+        let symbols = vec![
+            symbol("save", "app/db.py", 0),
+            symbol("load", "app/db.py", 10),
+            symbol("view", "app/web.py", 0),
+            symbol("api", "app/web.py", 10),
+            test,
+        ];
+        let edges = vec![
+            calls("view", "save"),
+            calls("api", "save"),
+            calls("view", "load"),
+            calls("load", "view"),
+            calls("test_save", "save"),
+        ];
+        let churn = HashMap::from([("app/db.py".to_string(), 4)]);
+
+        // Touches save (lines 1-3) and load (lines 11-13)
+        let changes = ChangedLines::parse("+++ b/app/db.py\n@@ -2 +2 @@\n@@ -12 +12 @@\n");
+        let report = assess("abc123", &symbols, &edges, &changes, &churn);
+
+        let names: Vec<&str> = report.symbols.iter().map(|s| s.fqn.as_str()).collect();
+        assert_eq!(names, ["app.load", "app.save"]);
+        let load = &report.symbols[0];
+        assert!(!load.tested && load.in_cycle);
+        assert_eq!((load.fan_in, load.churn, load.line), (1, 4, 11));
+        let save = &report.symbols[1];
+        assert!(save.tested && !save.in_cycle);
+        assert_eq!(save.fan_in, 3);
+
+        let factor = |name: &str| report.factors.iter().find(|f| f.name == name).unwrap().clone();
+        assert_eq!(factor("untested").value, 0.5);
+        assert_eq!(factor("cycles").value, 0.5);
+        assert_eq!(factor("fan-in").detail, "3 symbols depend on app.save");
+        assert_eq!(factor("churn").detail, "4 recent commits to app/db.py");
+        let total: f64 = report.factors.iter().map(|f| f.weight * f.value).sum();
+        assert!((report.score - 100.0 * total).abs() < 1e-9);
+        assert!(report.score > 0.0 && report.score < 100.0);
+
+        // Only a test changed
+        let changes = ChangedLines::parse("+++ b/tests/test_db.py\n@@ -1 +1 @@\n");
+        let report = assess("abc123", &symbols, &edges, &changes, &churn);
+        assert!(report.symbols.is_empty());
+        assert_eq!((report.score, report.level), (0.0, "low"));
+    }
+
+    #[test]
+    fn test_saturate() {
+        assert_eq!(saturate(0, 20), 0.0);
+        assert_eq!(saturate(20, 20), 1.0);
+        assert_eq!(saturate(500, 20), 1.0);
+        assert!(saturate(3, 20) > 3.0 / 20.0);
+    }
+}
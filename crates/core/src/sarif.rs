@@ -0,0 +1,172 @@
+use crate::check::{CheckReport, Finding, Level};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// What each rule reports, for the rule metadata code scanning shows next to
+/// an alert
+fn rule_description(rule: &str) -> &'static str {
+    match rule {
+        "cycle" => "Symbols that depend on each other in a cycle",
+        "forbidden-dependency" => "A dependency the configured rules forbid",
+        "layer-violation" => "A dependency against the configured layer ordering",
+        "api-break" => "A public symbol of the base was removed or changed signature",
+        "resolution-coverage" => "Too few references resolve to a known symbol",
+        "hotspot" => "A changed symbol that many others depend on",
+        "dead-code" => "A private symbol nothing refers to",
+        "duplicate-code" => "A function whose body duplicates another",
+        _ => "Finding of reviewbot check",
+    }
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Notice => "note",
+    }
+}
+
+/// A SARIF 2.1.0 log of `report`, for GitHub code scanning
+/// (`github/codeql-action/upload-sarif`) and other SARIF consumers.
+///
+/// Results need a location there, so findings without a file (coverage
+/// ratios) are reported as notifications of the invocation instead.
+pub fn to_sarif(report: &CheckReport) -> Value {
+    let findings: Vec<&Finding> = report.checks.iter().flat_map(|check| &check.findings).collect();
+
+    // Rules in first-seen order; results refer to them by index
+    let mut rule_index: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut rules = Vec::new();
+    for finding in &findings {
+        if !rule_index.contains_key(finding.rule) {
+            rule_index.insert(finding.rule, rules.len());
+            rules.push(json!({
+                "id": finding.rule,
+                "shortDescription": { "text": rule_description(finding.rule) },
+                "defaultConfiguration": { "level": sarif_level(finding.level) },
+            }));
+        }
+    }
+
+    let mut results = Vec::new();
+    let mut notifications = Vec::new();
+    for finding in findings {
+        let Some(file) = &finding.file else {
+            notifications.push(json!({
+                "level": sarif_level(finding.level),
+                "message": { "text": finding.message },
+                "descriptor": { "id": finding.rule },
+            }));
+            continue;
+        };
+        let mut location = json!({
+            "physicalLocation": {
+                "artifactLocation": { "uri": file, "uriBaseId": "%SRCROOT%" },
+            },
+        });
+        if let Some(line) = finding.line {
+            location["physicalLocation"]["region"] = json!({ "startLine": line });
+        }
+        if let Some(symbol) = &finding.symbol {
+            location["logicalLocations"] = json!([{ "fullyQualifiedName": symbol }]);
+        }
+        let mut result = json!({
+            "ruleId": finding.rule,
+            "ruleIndex": rule_index[finding.rule],
+            "level": sarif_level(finding.level),
+            "message": { "text": finding.message },
+            "locations": [location],
+        });
+        // Lets code scanning follow an alert as lines move
+        if let Some(symbol) = &finding.symbol {
+            result["partialFingerprints"] = json!({ "reviewbotSymbol/v1": format!("{}:{}", finding.rule, symbol) });
+        }
+        results.push(result);
+    }
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "reviewbot",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "invocations": [{
+                "executionSuccessful": true,
+                "toolExecutionNotifications": notifications,
+            }],
+            "properties": { "commit": report.commit },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check::CheckResult;
+
+    #[test]
+    fn test_sarif_log() {
+        let located = Finding {
+            rule: "dead-code",
+            level: Level::Warning,
+            message: "Function helper is never referenced".to_string(),
+            file: Some("src/util.ts".to_string()),
+            line: Some(12),
+            symbol: Some("util.helper".to_string()),
+        };
+        let forbidden = Finding {
+            rule: "forbidden-dependency",
+            level: Level::Error,
+            message: "src/ui/view.ts depends on src/db/conn.ts".to_string(),
+            file: Some("src/ui/view.ts".to_string()),
+            line: None,
+            symbol: None,
+        };
+        let coverage = Finding {
+            rule: "resolution-coverage",
+            level: Level::Error,
+            message: "50.0% of references resolve".to_string(),
+            file: None,
+            line: None,
+            symbol: None,
+        };
+        let check = |name, findings| CheckResult { name, passed: false, gating: true, summary: String::new(), findings };
+        let report = CheckReport {
+            commit: "abc123".to_string(),
+            passed: false,
+            checks: vec![
+                check("dead-code", vec![located]),
+                check("forbidden-deps", vec![forbidden]),
+                check("resolution", vec![coverage]),
+            ],
+        };
+
+        let sarif = to_sarif(&report);
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 3);
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "dead-code");
+        assert_eq!(results[0]["level"], "warning");
+        let location = &results[0]["locations"][0];
+        assert_eq!(location["physicalLocation"]["artifactLocation"]["uri"], "src/util.ts");
+        assert_eq!(location["physicalLocation"]["region"]["startLine"], 12);
+        assert_eq!(location["logicalLocations"][0]["fullyQualifiedName"], "util.helper");
+        assert_eq!(results[1]["ruleIndex"], 1);
+        assert!(results[1]["locations"][0]["physicalLocation"].get("region").is_none());
+
+        let notifications = run["invocations"][0]["toolExecutionNotifications"].as_array().unwrap();
+        assert_eq!(notifications[0]["descriptor"]["id"], "resolution-coverage");
+        assert_eq!(run["properties"]["commit"], "abc123");
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::Result;
+use std::path::Path;
+
+/// Decoded contents of a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceText {
+    /// UTF-8 text with any BOM removed and CRLF line endings folded to LF
+    pub text: String,
+    /// Encoding the bytes were decoded from
+    pub encoding: &'static str,
+    /// Whether undecodable bytes were replaced with U+FFFD
+    pub lossy: bool,
+}
+
+/// Read and decode a source file; see [`decode`]
+pub fn read_source(path: &Path) -> Result<SourceText> {
+    Ok(decode(&std::fs::read(path)?))
+}
+
+/// Decode raw file bytes into text the harnesses can parse.
+///
+/// Handles UTF-8 (with or without BOM) and BOM-marked UTF-16. Anything else
+/// goes through charset detection, so Latin-1 or Shift-JIS sources are
+/// transcoded instead of parsed as mojibake. CRLF is folded to
+/// LF: tree-sitter rows split on `\n`, so this keeps line numbers unchanged
+/// while keeping stray `\r` out of columns, names and doc comments.
+pub fn decode(bytes: &[u8]) -> SourceText {
+    let (text, encoding, lossy) = if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        let (text, lossy) = utf8_lossy(rest);
+        (text, "utf-8", lossy)
+    } else if let Some(rest) = bytes.strip_prefix(b"\xFF\xFE") {
+        let (text, lossy) = utf16(rest, u16::from_le_bytes);
+        (text, "utf-16le", lossy)
+    } else if let Some(rest) = bytes.strip_prefix(b"\xFE\xFF") {
+        let (text, lossy) = utf16(rest, u16::from_be_bytes);
+        (text, "utf-16be", lossy)
+    } else if let Ok(text) = std::str::from_utf8(bytes) {
+        (text.to_string(), "utf-8", false)
+    } else {
+        detect_and_decode(bytes)
+    };
+
+    let text = if text.contains("\r\n") {
+        text.replace("\r\n", "\n")
+    } else {
+        text
+    };
+
+    SourceText { text, encoding, lossy }
+}
+
+/// Guess the legacy encoding of non-UTF-8 bytes and transcode them
+fn detect_and_decode(bytes: &[u8]) -> (String, &'static str, bool) {
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, true);
+    let (text, _, had_errors) = encoding.decode(bytes);
+    (text.into_owned(), encoding.name(), had_errors)
+}
+
+fn utf8_lossy(bytes: &[u8]) -> (String, bool) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), false),
+        Err(_) => (String::from_utf8_lossy(bytes).into_owned(), true),
+    }
+}
+
+fn utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> (String, bool) {
+    let units: Vec<u16> = bytes
+        .chunks(2)
+        .map(|pair| to_unit([pair[0], *pair.get(1).unwrap_or(&0)]))
+        .collect();
+    let lossy = !bytes.len().is_multiple_of(2) || String::from_utf16(&units).is_err();
+    (String::from_utf16_lossy(&units), lossy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_bom_and_crlf() {
+        let source = decode(b"\xEF\xBB\xBFfn main() {}\r\nfn other() {}\r\n");
+        assert_eq!(source.text, "fn main() {}\nfn other() {}\n");
+        assert_eq!(source.encoding, "utf-8");
+        assert!(!source.lossy);
+    }
+
+    #[test]
+    fn test_utf16_with_bom() {
+        let mut le = vec![0xFF, 0xFE];
+        let mut be = vec![0xFE, 0xFF];
+        for unit in "class Ä {}\r\n".encode_utf16() {
+            le.extend_from_slice(&unit.to_le_bytes());
+            be.extend_from_slice(&unit.to_be_bytes());
+        }
+        assert_eq!(decode(&le).text, "class Ä {}\n");
+        assert_eq!(decode(&le).encoding, "utf-16le");
+        assert_eq!(decode(&be).text, "class Ä {}\n");
+        assert_eq!(decode(&be).encoding, "utf-16be");
+    }
+
+    #[test]
+    fn test_legacy_encodings_are_transcoded() {
+        let latin1 = decode(b"# R\xE9sum\xE9 du caf\xE9 pr\xE9f\xE9r\xE9\ndef caf\xE9(): pass\n");
+        assert_eq!(latin1.encoding, "windows-1252");
+        assert!(latin1.text.contains("Résumé"));
+        assert!(!latin1.lossy);
+
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode("// 日本語のコメントです。これはテストです。\nint main() { return 0; }\n");
+        let sjis = decode(&bytes);
+        assert_eq!(sjis.encoding, "Shift_JIS");
+        assert!(sjis.text.contains("日本語のコメント"));
+    }
+}
@@ -0,0 +1,669 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::time::Duration;
+use store::GraphStore;
+use tracing::{debug, info};
+
+/// Object holding the list of snapshots a remote has, in push order
+const INDEX_KEY: &str = "index.json";
+
+/// How many times a push re-reads the index after losing a race to update it
+const INDEX_ATTEMPTS: usize = 5;
+
+/// How long an http remote may take to accept a connection
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long an http remote may stall mid-request or mid-response
+const HTTP_IO_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Largest response body read from an http remote, whatever its headers claim
+const MAX_HTTP_BODY: u64 = 4 << 30;
+
+/// Exit code of aws CLI v2 when the service answered with an error, which
+/// for a `HEAD` of a single key means it isn't there
+const AWS_SERVICE_ERROR: i32 = 254;
+
+/// Where shared snapshots live. Keys are `/`-separated relative paths.
+pub trait Remote {
+    /// `None` when the key doesn't exist
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.get_tagged(key)?.map(|(data, _)| data))
+    }
+
+    /// The object along with a tag (e.g. its ETag) that changes whenever it
+    /// is rewritten
+    fn get_tagged(&self, key: &str) -> Result<Option<(Vec<u8>, String)>>;
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<()>;
+
+    /// Write `key` only if it is still at `tag`, or still absent when `tag`
+    /// is `None`. `Ok(false)` when another writer got there first.
+    fn put_if(&self, key: &str, data: &[u8], tag: Option<&str>) -> Result<bool>;
+}
+
+/// Open a remote from its URL:
+///
+/// - a directory path or `file://` URL (a shared mount)
+/// - `https://host[:port]/prefix` or `http://…`, using plain `GET`/`PUT`
+///   with `If-Match` for the index, e.g. a bucket behind an object-store
+///   gateway; `REVIEWBOT_REMOTE_TOKEN` is sent as a bearer token when set,
+///   which over plain http is only allowed to a loopback host
+/// - `s3://bucket/prefix`, through the `aws` CLI (v2) and its usual
+///   credentials
+pub fn open_remote(url: &str) -> Result<Box<dyn Remote>> {
+    if let Some(rest) = url.strip_prefix("s3://") {
+        return Ok(Box::new(S3Remote::parse(rest)?));
+    }
+    if url.starts_with("http://") || url.starts_with("https://") {
+        return Ok(Box::new(HttpRemote::parse(url, std::env::var("REVIEWBOT_REMOTE_TOKEN").ok())?));
+    }
+    let dir = url.strip_prefix("file://").unwrap_or(url);
+    Ok(Box::new(DirRemote { root: PathBuf::from(dir) }))
+}
+
+/// Key a snapshot of `commit` is uploaded under
+fn snapshot_key(commit: &str) -> Result<String> {
+    if commit.is_empty() || commit.len() > 64 || !commit.bytes().all(|b| b.is_ascii_hexdigit()) {
+        bail!("'{}' is not a commit sha", commit);
+    }
+    Ok(format!("snapshots/{}.bundle", commit))
+}
+
+/// Keys come from the remote's own index, so only the shapes a push writes
+/// are accepted before one is turned into a path or URL
+fn check_key(key: &str) -> Result<()> {
+    let commit = key.strip_prefix("snapshots/").and_then(|rest| rest.strip_suffix(".bundle"));
+    let valid = key == INDEX_KEY || commit.is_some_and(|commit| snapshot_key(commit).is_ok());
+    if !valid {
+        bail!("Refusing remote key {:?}; expected {} or snapshots/<sha>.bundle", key, INDEX_KEY);
+    }
+    Ok(())
+}
+
+/// The remote's `index.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RemoteIndex {
+    pub snapshots: Vec<RemoteSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteSnapshot {
+    pub commit: String,
+    /// Snapshot this one is a delta against; `None` for a full bundle
+    pub base: Option<String>,
+    pub key: String,
+}
+
+impl RemoteIndex {
+    pub fn load(remote: &dyn Remote) -> Result<Self> {
+        Ok(Self::load_tagged(remote)?.0)
+    }
+
+    /// The index and the tag to update it against; `None` while the remote
+    /// has none
+    fn load_tagged(remote: &dyn Remote) -> Result<(Self, Option<String>)> {
+        match remote.get_tagged(INDEX_KEY)? {
+            Some((data, tag)) => Ok((serde_json::from_slice(&data).context("Malformed remote index")?, Some(tag))),
+            None => Ok((Self::default(), None)),
+        }
+    }
+
+    pub fn get(&self, commit: &str) -> Option<&RemoteSnapshot> {
+        self.snapshots.iter().find(|s| s.commit == commit)
+    }
+
+    /// Bundles to import, oldest first, to rebuild `commit` from whatever
+    /// `have` already says is local
+    fn chain(&self, commit: &str, have: impl Fn(&str) -> bool) -> Result<Vec<&RemoteSnapshot>> {
+        let mut chain = Vec::new();
+        let mut current = Some(commit.to_string());
+        while let Some(sha) = current {
+            if have(&sha) {
+                break;
+            }
+            let snapshot = self.get(&sha).with_context(|| format!("Remote has no snapshot {}", sha))?;
+            if chain.len() > self.snapshots.len() {
+                bail!("Remote index has a delta cycle at {}", sha);
+            }
+            chain.push(snapshot);
+            current = snapshot.base.clone();
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+}
+
+/// What [`push`] uploaded
+#[derive(Debug, Clone, PartialEq)]
+pub enum PushOutcome {
+    AlreadyPresent,
+    Full { bytes: usize },
+    Delta { base: String, bytes: usize },
+}
+
+/// Upload `commit`'s snapshot. It goes up as a delta against the newest
+/// snapshot the remote already has that is also local, unless `full` is set.
+///
+/// The index is only written over the version that was read, so concurrent
+/// pushes re-read it and append again rather than drop each other's entries.
+pub fn push(store: &GraphStore, remote: &dyn Remote, commit: &str, full: bool) -> Result<PushOutcome> {
+    let key = snapshot_key(commit)?;
+    let index = RemoteIndex::load(remote)?;
+    if index.get(commit).is_some() {
+        return Ok(PushOutcome::AlreadyPresent);
+    }
+
+    let base = if full {
+        None
+    } else {
+        index
+            .snapshots
+            .iter()
+            .rev()
+            .map(|s| s.commit.clone())
+            .find(|sha| store.has_snapshot(sha).unwrap_or(false))
+    };
+
+    let scratch = tempfile::NamedTempFile::new()?;
+    match &base {
+        Some(base) => store.export_delta_bundle(commit, base, scratch.path())?,
+        None => store.export_bundle(commit, scratch.path(), false)?,
+    }
+    let data = std::fs::read(scratch.path())?;
+    remote.put(&key, &data)?;
+    info!("Pushed {} ({} bytes{})", commit, data.len(), base.as_ref().map(|b| format!(", delta against {}", b)).unwrap_or_default());
+
+    // Entries are only ever appended, so the base chosen above stays listed
+    let entry = RemoteSnapshot { commit: commit.to_string(), base: base.clone(), key };
+    let mut attempts = 0;
+    loop {
+        let (mut index, tag) = RemoteIndex::load_tagged(remote)?;
+        if index.get(commit).is_some() {
+            return Ok(PushOutcome::AlreadyPresent);
+        }
+        index.snapshots.push(entry.clone());
+        if remote.put_if(INDEX_KEY, &serde_json::to_vec_pretty(&index)?, tag.as_deref())? {
+            break;
+        }
+        attempts += 1;
+        if attempts == INDEX_ATTEMPTS {
+            bail!("Remote index kept changing while adding {}; push again", commit);
+        }
+        debug!("Remote index changed while adding {}, retrying", commit);
+    }
+    Ok(match base {
+        Some(base) => PushOutcome::Delta { base, bytes: data.len() },
+        None => PushOutcome::Full { bytes: data.len() },
+    })
+}
+
+/// Download and import `commit` (the newest pushed snapshot when `None`),
+/// along with any delta bases missing locally. Returns the commits imported.
+pub fn pull(store: &GraphStore, remote: &dyn Remote, commit: Option<&str>) -> Result<Vec<String>> {
+    let index = RemoteIndex::load(remote)?;
+    let commit = match commit {
+        Some(commit) => commit.to_string(),
+        None => index
+            .snapshots
+            .last()
+            .map(|s| s.commit.clone())
+            .context("Remote has no snapshots")?,
+    };
+
+    let chain = index.chain(&commit, |sha| store.has_snapshot(sha).unwrap_or(false))?;
+    let mut imported = Vec::new();
+    for snapshot in chain {
+        let data = remote
+            .get(&snapshot.key)?
+            .with_context(|| format!("Remote index lists {} but it is missing", snapshot.key))?;
+        let scratch = tempfile::NamedTempFile::new()?;
+        std::fs::write(scratch.path(), &data)?;
+        store.import_bundle(scratch.path())?;
+        info!("Pulled {} ({} bytes)", snapshot.commit, data.len());
+        imported.push(snapshot.commit.clone());
+    }
+    Ok(imported)
+}
+
+struct DirRemote {
+    root: PathBuf,
+}
+
+impl DirRemote {
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        check_key(key)?;
+        Ok(self.root.join(key))
+    }
+
+    fn tag(data: &[u8]) -> String {
+        use sha2::{Digest, Sha256};
+        format!("{:x}", Sha256::digest(data))
+    }
+
+    fn write(path: &std::path::Path, data: &[u8]) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write-then-rename so readers never see a partial object
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, data)?;
+        std::fs::rename(&partial, path)?;
+        Ok(())
+    }
+}
+
+impl Remote for DirRemote {
+    fn get_tagged(&self, key: &str) -> Result<Option<(Vec<u8>, String)>> {
+        match std::fs::read(self.path(key)?) {
+            Ok(data) => {
+                let tag = Self::tag(&data);
+                Ok(Some((data, tag)))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        Self::write(&self.path(key)?, data)
+    }
+
+    fn put_if(&self, key: &str, data: &[u8], tag: Option<&str>) -> Result<bool> {
+        let path = self.path(key)?;
+        std::fs::create_dir_all(&self.root)?;
+        // Held until dropped; an advisory lock, so a crashed pusher leaves
+        // nothing behind to clean up
+        let lock = File::create(path.with_extension("lock"))?;
+        lock.lock()?;
+        let current = match std::fs::read(&path) {
+            Ok(current) => Some(Self::tag(&current)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        if current.as_deref() != tag {
+            return Ok(false);
+        }
+        Self::write(&path, data)?;
+        Ok(true)
+    }
+}
+
+struct HttpRemote {
+    agent: ureq::Agent,
+    /// `scheme://authority[/prefix]`, without a trailing `/`
+    base: String,
+    token: Option<String>,
+}
+
+impl HttpRemote {
+    /// A token would cross the network in the clear over plain http, so
+    /// one is refused there unless the host is this machine
+    fn parse(url: &str, token: Option<String>) -> Result<Self> {
+        let (scheme, rest) = url.split_once("://").context("Remote URL has no scheme")?;
+        let authority = rest.split('/').next().unwrap_or("");
+        let host = match authority.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => {
+                port.parse::<u16>().context("Invalid port in remote URL")?;
+                host
+            }
+            _ => authority,
+        };
+        if host.is_empty() {
+            bail!("Remote URL has no host");
+        }
+        let loopback = host == "localhost"
+            || host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback());
+        if token.is_some() && scheme == "http" && !loopback {
+            bail!(
+                "Refusing to send REVIEWBOT_REMOTE_TOKEN over plain http to {}; use https://, s3:// or a gateway on localhost",
+                host
+            );
+        }
+        let agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_connect(Some(HTTP_CONNECT_TIMEOUT))
+            .timeout_send_body(Some(HTTP_IO_TIMEOUT))
+            .timeout_recv_response(Some(HTTP_IO_TIMEOUT))
+            .timeout_recv_body(Some(HTTP_IO_TIMEOUT))
+            .build()
+            .into();
+        Ok(Self { agent, base: url.trim_end_matches('/').to_string(), token })
+    }
+
+    fn url(&self, key: &str) -> Result<String> {
+        check_key(key)?;
+        Ok(format!("{}/{}", self.base, key))
+    }
+
+    fn authorization(&self) -> Option<String> {
+        self.token.as_ref().map(|token| format!("Bearer {}", token))
+    }
+
+    /// `PUT` with optional precondition headers; returns the status
+    fn send(&self, key: &str, data: &[u8], precondition: Option<(&str, &str)>) -> Result<u16> {
+        let url = self.url(key)?;
+        let mut request = self.agent.put(&url);
+        if let Some(authorization) = self.authorization() {
+            request = request.header("Authorization", authorization);
+        }
+        if let Some((name, value)) = precondition {
+            request = request.header(name, value);
+        }
+        let response = request.send(data).with_context(|| format!("PUT {} failed", url))?;
+        Ok(response.status().as_u16())
+    }
+}
+
+impl Remote for HttpRemote {
+    fn get_tagged(&self, key: &str) -> Result<Option<(Vec<u8>, String)>> {
+        let url = self.url(key)?;
+        let mut request = self.agent.get(&url);
+        if let Some(authorization) = self.authorization() {
+            request = request.header("Authorization", authorization);
+        }
+        let mut response = request.call().with_context(|| format!("GET {} failed", url))?;
+        match response.status().as_u16() {
+            200 => {}
+            404 => return Ok(None),
+            status => bail!("GET {} failed with HTTP {}", url, status),
+        }
+        let tag = response.headers().get("etag").and_then(|v| v.to_str().ok()).map(str::to_string);
+        let body = response
+            .body_mut()
+            .with_config()
+            .limit(MAX_HTTP_BODY)
+            .read_to_vec()
+            .with_context(|| format!("Failed to read {}", url))?;
+        // Without one the index can't be updated safely; bundles don't need it
+        let tag = match tag {
+            Some(tag) => tag,
+            None if key == INDEX_KEY => bail!("{} came back without an ETag, which index updates need", url),
+            None => String::new(),
+        };
+        Ok(Some((body, tag)))
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        match self.send(key, data, None)? {
+            status if (200..300).contains(&status) => Ok(()),
+            status => bail!("PUT {} failed with HTTP {}", self.url(key)?, status),
+        }
+    }
+
+    fn put_if(&self, key: &str, data: &[u8], tag: Option<&str>) -> Result<bool> {
+        let precondition = match tag {
+            Some(tag) => ("If-Match", tag),
+            None => ("If-None-Match", "*"),
+        };
+        match self.send(key, data, Some(precondition))? {
+            status if (200..300).contains(&status) => Ok(true),
+            412 => Ok(false),
+            status => bail!("PUT {} failed with HTTP {}", self.url(key)?, status),
+        }
+    }
+}
+
+struct S3Remote {
+    bucket: String,
+    /// Key prefix, without a trailing `/`
+    prefix: String,
+}
+
+/// What `head-object` and `get-object` print about the object
+#[derive(Deserialize)]
+struct S3Object {
+    #[serde(rename = "ETag")]
+    etag: String,
+}
+
+impl S3Remote {
+    /// `bucket[/prefix]`, the part of the URL after `s3://`
+    fn parse(rest: &str) -> Result<Self> {
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            bail!("Remote URL has no bucket");
+        }
+        Ok(Self { bucket: bucket.to_string(), prefix: prefix.trim_matches('/').to_string() })
+    }
+
+    fn object_key(&self, key: &str) -> Result<String> {
+        check_key(key)?;
+        Ok(if self.prefix.is_empty() { key.to_string() } else { format!("{}/{}", self.prefix, key) })
+    }
+
+    fn s3api(&self, operation: &str, key: &str) -> Result<Command> {
+        let mut command = Command::new("aws");
+        command.args(["s3api", operation, "--bucket", &self.bucket, "--key", &self.object_key(key)?]);
+        Ok(command)
+    }
+
+    fn run(command: &mut Command) -> Result<Output> {
+        command.output().context("Failed to run the aws CLI")
+    }
+
+    fn failure(&self, operation: &str, key: &str, output: &Output) -> anyhow::Error {
+        anyhow::anyhow!(
+            "aws s3api {} s3://{}/{} failed: {}",
+            operation,
+            self.bucket,
+            self.object_key(key).unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    }
+
+    /// The object's ETag, `None` when it doesn't exist
+    fn head(&self, key: &str) -> Result<Option<String>> {
+        let output = Self::run(&mut self.s3api("head-object", key)?)?;
+        match output.status.code() {
+            Some(0) => Ok(Some(serde_json::from_slice::<S3Object>(&output.stdout)?.etag)),
+            Some(AWS_SERVICE_ERROR) => Ok(None),
+            _ => Err(self.failure("head-object", key, &output)),
+        }
+    }
+
+    fn put_object(&self, key: &str, data: &[u8], extra: &[&str]) -> Result<Output> {
+        let mut body = tempfile::NamedTempFile::new()?;
+        body.write_all(data)?;
+        body.flush()?;
+        Self::run(self.s3api("put-object", key)?.arg("--body").arg(body.path()).args(extra))
+    }
+}
+
+impl Remote for S3Remote {
+    fn get_tagged(&self, key: &str) -> Result<Option<(Vec<u8>, String)>> {
+        if self.head(key)?.is_none() {
+            return Ok(None);
+        }
+        let scratch = tempfile::NamedTempFile::new()?;
+        let output = Self::run(self.s3api("get-object", key)?.arg(scratch.path()))?;
+        if !output.status.success() {
+            return Err(self.failure("get-object", key, &output));
+        }
+        let object: S3Object = serde_json::from_slice(&output.stdout)?;
+        Ok(Some((std::fs::read(scratch.path())?, object.etag)))
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        let output = self.put_object(key, data, &[])?;
+        if !output.status.success() {
+            return Err(self.failure("put-object", key, &output));
+        }
+        Ok(())
+    }
+
+    fn put_if(&self, key: &str, data: &[u8], tag: Option<&str>) -> Result<bool> {
+        let output = match tag {
+            Some(tag) => self.put_object(key, data, &["--if-match", tag])?,
+            None => self.put_object(key, data, &["--if-none-match", "*"])?,
+        };
+        if output.status.success() {
+            return Ok(true);
+        }
+        // A failed precondition exits like any other service error; it was
+        // the precondition if the object has moved on from `tag`
+        if self.head(key)?.as_deref() != tag {
+            return Ok(false);
+        }
+        Err(self.failure("put-object", key, &output))
+    }
+}
+
+/// Default remote when `--remote` isn't given
+pub fn default_remote() -> Option<String> {
+    std::env::var("REVIEWBOT_REMOTE").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Span, SymbolIR, test_util};
+    use tempfile::TempDir;
+
+    fn symbol(id: &str, file_path: &str) -> SymbolIR {
+        SymbolIR {
+            name_span: Span { start_line: 0, start_col: 0, end_line: 0, end_col: 1 },
+            full_span: Span { start_line: 0, start_col: 0, end_line: 1, end_col: 1 },
+            sig_hash: id.to_string(),
+            ..test_util::symbol(id, id, file_path)
+        }
+    }
+
+    #[test]
+    fn test_push_and_pull_through_directory_remote() -> Result<()> {
+        let remote_dir = TempDir::new()?;
+        let remote = open_remote(remote_dir.path().to_str().unwrap())?;
+
+        let alice_dir = TempDir::new()?;
+        let alice = GraphStore::new(alice_dir.path())?;
+        let first = alice.get_or_create_commit("c1")?;
+        alice.insert_file(first, "a.ts", "h1", 1)?;
+        alice.insert_symbol(first, &symbol("one", "a.ts"))?;
+        let second = alice.get_or_create_commit("c2")?;
+        alice.insert_file(second, "a.ts", "h1", 1)?;
+        alice.insert_symbol(second, &symbol("one", "a.ts"))?;
+        alice.insert_file(second, "b.ts", "h2", 1)?;
+        alice.insert_symbol(second, &symbol("two", "b.ts"))?;
+
+        assert!(matches!(push(&alice, remote.as_ref(), "c1", false)?, PushOutcome::Full { .. }));
+        assert!(matches!(push(&alice, remote.as_ref(), "c2", false)?, PushOutcome::Delta { ref base, .. } if base == "c1"));
+        assert_eq!(push(&alice, remote.as_ref(), "c2", false)?, PushOutcome::AlreadyPresent);
+
+        let bob_dir = TempDir::new()?;
+        let bob = GraphStore::new(bob_dir.path())?;
+        assert_eq!(pull(&bob, remote.as_ref(), None)?, vec!["c1".to_string(), "c2".to_string()]);
+        assert!(pull(&bob, remote.as_ref(), Some("c2"))?.is_empty());
+
+        let mut expected = Vec::new();
+        alice.export_jsonl("c2", &mut expected)?;
+        let mut pulled = Vec::new();
+        bob.export_jsonl("c2", &mut pulled)?;
+        assert_eq!(pulled, expected);
+        assert!(pull(&bob, remote.as_ref(), Some("c3")).is_err());
+        Ok(())
+    }
+
+    /// Lands a rival push's index entry just before the next conditional
+    /// index write
+    struct Racing<'a> {
+        remote: &'a dyn Remote,
+        rival: std::cell::Cell<Option<RemoteSnapshot>>,
+    }
+
+    impl Remote for Racing<'_> {
+        fn get_tagged(&self, key: &str) -> Result<Option<(Vec<u8>, String)>> {
+            self.remote.get_tagged(key)
+        }
+
+        fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+            self.remote.put(key, data)
+        }
+
+        fn put_if(&self, key: &str, data: &[u8], tag: Option<&str>) -> Result<bool> {
+            if let Some(rival) = self.rival.take() {
+                let (mut index, rival_tag) = RemoteIndex::load_tagged(self.remote)?;
+                index.snapshots.push(rival);
+                assert!(self.remote.put_if(key, &serde_json::to_vec(&index)?, rival_tag.as_deref())?);
+            }
+            self.remote.put_if(key, data, tag)
+        }
+    }
+
+    #[test]
+    fn test_concurrent_pushes_keep_both_entries() -> Result<()> {
+        let remote_dir = TempDir::new()?;
+        let remote = open_remote(remote_dir.path().to_str().unwrap())?;
+        let rival = RemoteSnapshot { commit: "d1".to_string(), base: None, key: "snapshots/d1.bundle".to_string() };
+        let racing = Racing { remote: remote.as_ref(), rival: std::cell::Cell::new(Some(rival)) };
+
+        let store_dir = TempDir::new()?;
+        let store = GraphStore::new(store_dir.path())?;
+        let commit = store.get_or_create_commit("c1")?;
+        store.insert_file(commit, "a.ts", "h1", 1)?;
+        store.insert_symbol(commit, &symbol("one", "a.ts"))?;
+        assert!(matches!(push(&store, &racing, "c1", false)?, PushOutcome::Full { .. }));
+
+        let commits: Vec<String> = RemoteIndex::load(remote.as_ref())?.snapshots.into_iter().map(|s| s.commit).collect();
+        assert_eq!(commits, ["d1", "c1"]);
+        // A write against a tag that has moved on is refused
+        assert!(!remote.put_if(INDEX_KEY, b"{}", Some("stale"))?);
+        assert!(!remote.put_if(INDEX_KEY, b"{}", None)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_remote_keys_are_checked() -> Result<()> {
+        let remote_dir = TempDir::new()?;
+        let root = remote_dir.path().join("remote");
+        let remote = open_remote(root.to_str().unwrap())?;
+        std::fs::write(remote_dir.path().join("secret.bundle"), "x")?;
+        assert!(remote.get("../secret.bundle").is_err());
+        assert!(remote.get("snapshots/../../secret.bundle").is_err());
+        assert!(remote.put("/tmp/elsewhere", b"x").is_err());
+        assert!(remote.get("snapshots/abc123.bundle")?.is_none());
+
+        // Entries of a tampered index are refused before anything is read
+        let index = RemoteIndex {
+            snapshots: vec![RemoteSnapshot { commit: "c1".to_string(), base: None, key: "../secret.bundle".to_string() }],
+        };
+        remote.put(INDEX_KEY, &serde_json::to_vec(&index)?)?;
+        let store_dir = TempDir::new()?;
+        let store = GraphStore::new(store_dir.path())?;
+        assert!(pull(&store, remote.as_ref(), None).is_err());
+        assert!(push(&store, remote.as_ref(), "../c1", false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_http_remote() -> Result<()> {
+        let remote = HttpRemote::parse("https://cache.internal:8080/graphs/shop/", None)?;
+        assert_eq!(remote.url("index.json")?, "https://cache.internal:8080/graphs/shop/index.json");
+        assert!(remote.url("../index.json").is_err());
+        assert_eq!(HttpRemote::parse("http://cache", None)?.url("index.json")?, "http://cache/index.json");
+        assert!(HttpRemote::parse("http://cache:port/graphs", None).is_err());
+
+        // Tokens go anywhere over https, and only to this machine over plain http
+        let token = || Some("secret".to_string());
+        assert!(HttpRemote::parse("https://cache.internal:8080/graphs", token())?.token.is_some());
+        assert!(HttpRemote::parse("http://cache.internal:8080/graphs", token()).is_err());
+        assert!(HttpRemote::parse("http://localhost:9000/graphs", token()).is_ok());
+        assert!(HttpRemote::parse("http://127.0.0.1:9000/graphs", token()).is_ok());
+        assert!(HttpRemote::parse("http://[::1]:9000/graphs", token()).is_ok());
+        assert!(HttpRemote::parse("http://[::1]/graphs", token()).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_s3_remote() -> Result<()> {
+        let remote = S3Remote::parse("team-bucket/graphs/shop/")?;
+        assert_eq!(remote.bucket, "team-bucket");
+        assert_eq!(remote.object_key("snapshots/c1.bundle")?, "graphs/shop/snapshots/c1.bundle");
+        assert_eq!(S3Remote::parse("team-bucket")?.object_key("index.json")?, "index.json");
+        assert!(S3Remote::parse("/graphs").is_err());
+        Ok(())
+    }
+}
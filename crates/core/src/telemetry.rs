@@ -0,0 +1,114 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// Log output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+/// Keeps the OTLP pipeline alive; spans still buffered are flushed on drop
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::TracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush OTLP spans: {}", e);
+            }
+        }
+    }
+}
+
+/// Install the global tracing subscriber.
+///
+/// Logs go to stdout in `log_format`, or to stderr with `log_to_stderr` so
+/// they stay out of machine-readable command output. When `otlp_endpoint` is
+/// set (and the binary was built with the `otel` feature) spans are also
+/// exported over OTLP/gRPC so indexing time can be profiled in a tracing backend.
+pub fn init(log_format: LogFormat, otlp_endpoint: Option<&str>, log_to_stderr: bool) -> Result<TelemetryGuard> {
+    let writer = if log_to_stderr {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+    let fmt_layer = match log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().with_writer(writer).boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer().with_writer(writer).json().flatten_event(true).boxed(),
+    };
+    let registry = tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(fmt_layer);
+
+    #[cfg(feature = "otel")]
+    {
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry_otlp::WithExportConfig;
+
+        let provider = match otlp_endpoint {
+            Some(endpoint) => {
+                let exporter = opentelemetry_otlp::SpanExporter::builder()
+                    .with_tonic()
+                    .with_endpoint(endpoint)
+                    .build()?;
+                Some(
+                    opentelemetry_sdk::trace::TracerProvider::builder()
+                        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+                        .with_resource(opentelemetry_sdk::Resource::new(vec![
+                            opentelemetry::KeyValue::new("service.name", "reviewbot"),
+                        ]))
+                        .build(),
+                )
+            }
+            None => None,
+        };
+
+        let otel_layer = provider
+            .as_ref()
+            .map(|p| tracing_opentelemetry::layer().with_tracer(p.tracer("reviewbot")));
+        registry.with(otel_layer).try_init()?;
+
+        Ok(TelemetryGuard { provider })
+    }
+
+    #[cfg(not(feature = "otel"))]
+    {
+        registry.try_init()?;
+        if let Some(endpoint) = otlp_endpoint {
+            tracing::warn!(
+                "OTLP endpoint {} ignored: reviewbot was built without the `otel` feature",
+                endpoint
+            );
+        }
+        Ok(TelemetryGuard {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The global subscriber can only be installed once per process, so every
+    // assertion about it lives in this one test
+    #[cfg(not(feature = "otel"))]
+    #[test]
+    fn test_init_json_ignores_otlp_endpoint_without_otel() {
+        let guard = init(LogFormat::Json, Some("http://localhost:4317"), true);
+        assert!(guard.is_ok());
+        assert!(tracing::dispatcher::has_been_set());
+        assert!(tracing::enabled!(tracing::Level::INFO));
+        assert!(!tracing::enabled!(tracing::Level::DEBUG));
+
+        assert!(init(LogFormat::Text, None, true).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use protocol::{EdgeIR, EdgeType, SymbolIR};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// A method that can raise the exception asked about
+#[derive(Debug, Clone, Serialize)]
+pub struct Thrower {
+    pub fqn: String,
+    pub file: String,
+    /// 1-based line of the method's name
+    pub line: u32,
+    /// The exception type as the method names it, which may be a subclass
+    pub exception: String,
+    /// `declared` in a `throws` clause or `thrown` by a `throw` statement
+    pub via: String,
+}
+
+/// The methods that can raise `exception` or one of its subclasses
+#[derive(Debug, Clone, Serialize)]
+pub struct ThrowersReport {
+    pub commit: String,
+    pub exception: String,
+    /// `exception` and the subclasses of it found in the snapshot
+    pub exceptions: Vec<String>,
+    pub throwers: Vec<Thrower>,
+}
+
+impl ThrowersReport {
+    pub fn to_text(&self, shown: usize) -> String {
+        let mut out = format!(
+            "{} methods raising {} or one of its {} subclasses at {}\n",
+            self.throwers.len(),
+            self.exception,
+            self.exceptions.len() - 1,
+            self.commit
+        );
+        for thrower in self.throwers.iter().take(shown) {
+            out.push_str(&format!(
+                "  {:<8} {:<24} {} ({}:{})\n",
+                thrower.via, thrower.exception, thrower.fqn, thrower.file, thrower.line
+            ));
+        }
+        out
+    }
+}
+
+/// `IOException` for `java.io.IOException`
+fn simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// The `Throws` edges of a snapshot raising `exception` or, following
+/// `Extends` edges, a subclass of it. Exception types are compared by
+/// simple name since the edges keep them as written, so `IOException`
+/// and `java.io.IOException` are the same type here.
+pub fn throwers(commit: &str, exception: &str, symbols: &[SymbolIR], edges: &[EdgeIR]) -> ThrowersReport {
+    let by_id: HashMap<&str, &SymbolIR> = symbols.iter().map(|s| (s.id.as_str(), s)).collect();
+
+    let mut subclasses: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in edges.iter().filter(|e| e.edge_type == EdgeType::Extends) {
+        if let (Some(class), Some(parent)) = (edge.src.as_deref().and_then(|id| by_id.get(id)), edge.dst.as_deref()) {
+            subclasses.entry(simple_name(parent)).or_default().push(class.name.as_str());
+        }
+    }
+    let mut exceptions = vec![simple_name(exception)];
+    let mut seen: HashSet<&str> = exceptions.iter().copied().collect();
+    let mut next = 0;
+    while let Some(name) = exceptions.get(next).copied() {
+        next += 1;
+        for subclass in subclasses.get(name).into_iter().flatten() {
+            if seen.insert(subclass) {
+                exceptions.push(subclass);
+            }
+        }
+    }
+
+    let mut throwers: Vec<Thrower> = edges
+        .iter()
+        .filter(|e| e.edge_type == EdgeType::Throws)
+        .filter_map(|edge| {
+            let thrown = edge.dst.as_deref()?;
+            if !seen.contains(simple_name(thrown)) {
+                return None;
+            }
+            let method = edge.src.as_deref().and_then(|id| by_id.get(id))?;
+            Some(Thrower {
+                fqn: method.fqn.clone(),
+                file: method.file_path.clone(),
+                line: method.name_span.start_line + 1,
+                exception: thrown.to_string(),
+                via: edge.meta.get("via").and_then(|v| v.as_str()).unwrap_or("declared").to_string(),
+            })
+        })
+        .collect();
+    throwers.sort_by(|a, b| {
+        (&a.file, a.line, &a.fqn, &a.via, &a.exception).cmp(&(&b.file, b.line, &b.fqn, &b.via, &b.exception))
+    });
+    throwers.dedup_by(|a, b| a.fqn == b.fqn && a.exception == b.exception && a.via == b.via);
+
+    ThrowersReport {
+        commit: commit.to_string(),
+        exception: exception.to_string(),
+        exceptions: exceptions.into_iter().map(str::to_string).collect(),
+        throwers,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Language, Resolution, Span, SymbolKind, test_util};
+
+    fn symbol(id: &str, line: u32) -> SymbolIR {
+        SymbolIR {
+            lang: Language::Java,
+            kind: SymbolKind::Method,
+            name_span: Span { start_line: line, start_col: 4, end_line: line, end_col: 8 },
+            full_span: test_util::lines(line, line + 2),
+            ..test_util::symbol(id, &format!("com.acme.{}", id), "Store.java")
+        }
+    }
+
+    fn edge(edge_type: EdgeType, src: &str, dst: &str, via: Option<&str>) -> EdgeIR {
+        EdgeIR {
+            edge_type,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: via.map(|v| ("via".to_string(), serde_json::json!(v))).into_iter().collect(),
+            provenance: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_throwers_include_subclasses() {
+        let symbols = vec![
+            symbol("StoreException", 1),
+            symbol("MissingKey", 3),
+            symbol("Store.get", 10),
+            symbol("Store.load", 20),
+            symbol("Store.close", 30),
+        ];
+        let edges = vec![
+            edge(EdgeType::Extends, "StoreException", "java.io.IOException", None),
+            edge(EdgeType::Extends, "MissingKey", "StoreException", None),
+            edge(EdgeType::Throws, "Store.get", "MissingKey", Some("thrown")),
+            edge(EdgeType::Throws, "Store.get", "MissingKey", Some("thrown")),
+            edge(EdgeType::Throws, "Store.load", "IOException", Some("declared")),
+            edge(EdgeType::Throws, "Store.close", "IllegalStateException", Some("thrown")),
+        ];
+
+        let report = throwers("abc123", "java.io.IOException", &symbols, &edges);
+        assert_eq!(report.exceptions, ["IOException", "StoreException", "MissingKey"]);
+        let found: Vec<(&str, &str, &str)> =
+            report.throwers.iter().map(|t| (t.fqn.as_str(), t.exception.as_str(), t.via.as_str())).collect();
+        assert_eq!(
+            found,
+            [("com.acme.Store.get", "MissingKey", "thrown"), ("com.acme.Store.load", "IOException", "declared")]
+        );
+        assert_eq!(report.throwers[0].line, 11);
+
+        let only_missing = throwers("abc123", "MissingKey", &symbols, &edges);
+        assert_eq!(only_missing.throwers.len(), 1);
+        assert!(only_missing.to_text(5).starts_with("1 methods raising MissingKey or one of its 0 subclasses at abc123\n"));
+    }
+}
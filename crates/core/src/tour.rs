@@ -0,0 +1,231 @@
+use anyhow::{bail, Result};
+use protocol::{EdgeIR, EdgeType, SymbolIR};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// A symbol to read, in the order the tour visits it
+#[derive(Debug, Clone, Serialize)]
+pub struct TourStop {
+    pub step: usize,
+    /// Calls away from the entry point
+    pub depth: usize,
+    pub fqn: String,
+    pub kind: String,
+    pub file: String,
+    /// 1-based line of the symbol's name
+    pub line: u32,
+    /// First sentence of the doc comment, else the signature
+    pub summary: Option<String>,
+    /// The stop whose call led here
+    pub called_from: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Tour {
+    pub commit: String,
+    pub entry: String,
+    pub stops: Vec<TourStop>,
+    /// Whether the depth or stop limit left reachable symbols out
+    pub truncated: bool,
+}
+
+impl Tour {
+    pub fn to_text(&self) -> String {
+        let mut out = format!("Tour of {} at {}, {} stops\n", self.entry, self.commit, self.stops.len());
+        for stop in &self.stops {
+            let indent = "  ".repeat(stop.depth);
+            out.push_str(&format!(
+                "{:>3}. {}{} ({}) {}:{}\n",
+                stop.step, indent, stop.fqn, stop.kind, stop.file, stop.line
+            ));
+            if let Some(summary) = &stop.summary {
+                out.push_str(&format!("     {}{}\n", indent, summary));
+            }
+        }
+        if self.truncated {
+            out.push_str("More is reachable; raise --depth or --max-stops to extend the tour\n");
+        }
+        out
+    }
+}
+
+/// The symbol `name` refers to: one with that FQN, else the only one whose
+/// FQN ends with it or whose name it is
+pub fn find_entry<'a>(symbols: &'a [SymbolIR], name: &str) -> Result<&'a SymbolIR> {
+    if let Some(symbol) = symbols.iter().find(|s| s.fqn == name) {
+        return Ok(symbol);
+    }
+    let qualified_suffix = |fqn: &str| {
+        fqn.strip_suffix(name).is_some_and(|prefix| prefix.ends_with(['.', ':', '/', '#']))
+    };
+    let mut candidates: Vec<&SymbolIR> = symbols.iter().filter(|s| qualified_suffix(&s.fqn)).collect();
+    if candidates.is_empty() {
+        candidates = symbols.iter().filter(|s| s.name == name).collect();
+    }
+    match candidates.as_slice() {
+        [] => bail!("No symbol named {}", name),
+        [symbol] => Ok(symbol),
+        many => {
+            let mut fqns: Vec<&str> = many.iter().map(|s| s.fqn.as_str()).collect();
+            fqns.sort();
+            bail!("{} is ambiguous, pass one of: {}", name, fqns.join(", "))
+        }
+    }
+}
+
+/// Walk the `Calls` edges from `entry` depth first, so each callee is read
+/// right after its caller, in the order the calls were found. Each symbol
+/// is visited once; calls out of the snapshot (libraries, unresolved
+/// names) are left out.
+pub fn build(
+    commit: &str,
+    entry: &SymbolIR,
+    symbols: &[SymbolIR],
+    edges: &[EdgeIR],
+    max_depth: usize,
+    max_stops: usize,
+) -> Tour {
+    let by_id: HashMap<&str, &SymbolIR> = symbols.iter().map(|s| (s.id.as_str(), s)).collect();
+    let mut callees: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in edges.iter().filter(|e| e.edge_type == EdgeType::Calls) {
+        let (Some(src), Some(dst)) = (edge.src.as_deref(), edge.dst.as_deref()) else {
+            continue;
+        };
+        if by_id.contains_key(dst) {
+            let calls = callees.entry(src).or_default();
+            if !calls.contains(&dst) {
+                calls.push(dst);
+            }
+        }
+    }
+
+    let mut stops = Vec::new();
+    let mut truncated = false;
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut pending: Vec<(&str, usize, Option<&str>)> = vec![(entry.id.as_str(), 0, None)];
+    while let Some((id, depth, caller)) = pending.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        if stops.len() == max_stops {
+            truncated = true;
+            break;
+        }
+        let symbol = by_id.get(id).copied().unwrap_or(entry);
+        stops.push(TourStop {
+            step: stops.len() + 1,
+            depth,
+            fqn: symbol.fqn.clone(),
+            kind: format!("{:?}", symbol.kind),
+            file: symbol.file_path.clone(),
+            line: symbol.name_span.start_line + 1,
+            summary: summary(symbol),
+            called_from: caller.and_then(|caller| by_id.get(caller)).map(|s| s.fqn.clone()),
+        });
+        let next: Vec<&str> = callees.get(id).into_iter().flatten().copied().filter(|c| !visited.contains(c)).collect();
+        if depth == max_depth {
+            truncated |= !next.is_empty();
+            continue;
+        }
+        pending.extend(next.into_iter().rev().map(|callee| (callee, depth + 1, Some(id))));
+    }
+    Tour { commit: commit.to_string(), entry: entry.fqn.clone(), stops, truncated }
+}
+
+/// The first sentence of the doc comment, else the signature
+fn summary(symbol: &SymbolIR) -> Option<String> {
+    let doc = symbol.doc.as_deref().map(|doc| doc.split("\n\n").next().unwrap_or(doc).split_whitespace().collect::<Vec<_>>().join(" "));
+    match doc.filter(|doc| !doc.is_empty()) {
+        Some(doc) => {
+            let end = doc.find(". ").map_or(doc.len(), |end| end + 1);
+            Some(doc[..end].to_string())
+        }
+        None => symbol.signature.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Language, Resolution, Span, SymbolKind, test_util};
+
+    fn symbol(id: &str, line: u32, doc: Option<&str>) -> SymbolIR {
+        SymbolIR {
+            lang: Language::Java,
+            kind: SymbolKind::Method,
+            signature: Some(format!("void {}()", id)),
+            name_span: Span { start_line: line, start_col: 4, end_line: line, end_col: 8 },
+            doc: doc.map(str::to_string),
+            ..test_util::symbol(id, &format!("app.{}", id), "src/App.java")
+        }
+    }
+
+    fn call(src: &str, dst: &str) -> EdgeIR {
+        EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_tour_reads_callees_after_callers() -> Result<()> {
+        let symbols = vec![
+            symbol("Main.run", 2, Some("Starts the app. Reads config first.\n\nMore detail.")),
+            symbol("Config.load", 10, None),
+            symbol("Config.parse", 20, Some("Parses\n  the file")),
+            symbol("Server.start", 30, None),
+        ];
+        let edges = vec![
+            call("Main.run", "Config.load"),
+            call("Main.run", "Server.start"),
+            call("Main.run", "Config.load"),
+            call("Config.load", "Config.parse"),
+            call("Config.parse", "Main.run"),
+            call("Server.start", "println"),
+        ];
+        let entry = find_entry(&symbols, "Main.run")?;
+        let tour = build("abc123", entry, &symbols, &edges, 5, 10);
+
+        let order: Vec<(usize, &str, usize, Option<&str>)> = tour
+            .stops
+            .iter()
+            .map(|s| (s.step, s.fqn.as_str(), s.depth, s.called_from.as_deref()))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                (1, "app.Main.run", 0, None),
+                (2, "app.Config.load", 1, Some("app.Main.run")),
+                (3, "app.Config.parse", 2, Some("app.Config.load")),
+                (4, "app.Server.start", 1, Some("app.Main.run")),
+            ]
+        );
+        assert!(!tour.truncated);
+        assert_eq!(tour.stops[0].summary.as_deref(), Some("Starts the app."));
+        assert_eq!(tour.stops[0].line, 3);
+        assert_eq!(tour.stops[2].summary.as_deref(), Some("Parses the file"));
+        assert_eq!(tour.stops[3].summary.as_deref(), Some("void Server.start()"));
+
+        let shallow = build("abc123", entry, &symbols, &edges, 1, 10);
+        assert_eq!(shallow.stops.len(), 3);
+        assert!(shallow.truncated);
+        assert!(shallow.to_text().contains("  3.   app.Server.start (Method) src/App.java:31"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_entry() {
+        let symbols = vec![symbol("Main.run", 1, None), symbol("Worker.run", 5, None)];
+        assert_eq!(find_entry(&symbols, "app.Worker.run").unwrap().id, "Worker.run");
+        assert_eq!(find_entry(&symbols, "Main.run").unwrap().id, "Main.run");
+        let ambiguous = find_entry(&symbols, "run").unwrap_err().to_string();
+        assert_eq!(ambiguous, "run is ambiguous, pass one of: app.Main.run, app.Worker.run");
+        assert!(find_entry(&symbols, "missing").is_err());
+    }
+}
@@ -2,11 +2,13 @@ use anyhow::Result;
 use ignore::WalkBuilder;
 use std::collections::HashSet;
 use std::path::PathBuf;
-use tracing::{debug, info};
+use std::path::Path;
+use tracing::{debug, info, warn};
 
 pub struct FileWalker {
     root: PathBuf,
     extensions: HashSet<String>,
+    follow_symlinks: bool,
 }
 
 impl FileWalker {
@@ -38,8 +40,17 @@ impl FileWalker {
         extensions.insert("hxx".to_string());
         // C#
         extensions.insert("cs".to_string());
+        // Languages without a harness, indexed by pattern
+        extensions.extend(crate::fallback::extensions().map(str::to_string));
         
-        Self { root, extensions }
+        Self { root, extensions, follow_symlinks: false }
+    }
+    
+    /// Follow symlinked files and directories. Symlink cycles are detected and
+    /// skipped, and a file reachable through several links is indexed once.
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
     }
     
     pub fn walk(&self) -> Result<Vec<PathBuf>> {
@@ -51,12 +62,26 @@ impl FileWalker {
             .git_global(true)  // Respect global gitignore
             .git_exclude(true)  // Respect .git/info/exclude
             .require_git(false)  // Work even if not a git repo
+            .follow_links(self.follow_symlinks)
             .build();
         
         for entry in walker {
-            let entry = entry?;
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) if is_loop(&e) => {
+                    warn!("Skipping symlink cycle: {}", e);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
             let path = entry.path();
             
+            // `is_file` follows links, so check the entry itself first
+            if !self.follow_symlinks && entry.path_is_symlink() {
+                debug!("Skipping symlink: {:?}", path);
+                continue;
+            }
+            
             if path.is_file() {
                 if let Some(ext) = path.extension() {
                     if self.extensions.contains(ext.to_str().unwrap_or("")) {
@@ -77,20 +102,95 @@ impl FileWalker {
             }
         }
         
+        // Sorted for reproducible indexes
+        files.sort();
+        
+        // Hardlinks and (when following) symlinks can reach one file by
+        // several paths; only the first path in sorted order is indexed
+        let mut seen = HashSet::new();
+        files.retain(|path| match file_identity(path) {
+            Some(id) if !seen.insert(id) => {
+                debug!("Skipping duplicate link: {:?}", path);
+                false
+            }
+            _ => true,
+        });
+        
         info!("Found {} files to index", files.len());
         Ok(files)
     }
     
     pub fn compute_file_hash(content: &str) -> String {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
+        // SHA-256 rather than `DefaultHasher`, whose output may change between
+        // Rust releases and would make stored hashes irreproducible
+        use sha2::{Digest, Sha256};
+        
+        format!("{:x}", Sha256::digest(content.as_bytes()))
+    }
+}
+
+/// One slice of a scan split across machines, written `K/N` (1-based).
+///
+/// Files are assigned by their directory, so a package's files land in the
+/// same shard and most references resolve without crossing shards. Every
+/// machine must use the same `N` on the same commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    pub index: u64,
+    pub count: u64,
+}
+
+impl std::str::FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, count) = s.split_once('/').ok_or_else(|| format!("expected K/N, got '{}'", s))?;
+        let index: u64 = index.trim().parse().map_err(|_| format!("invalid shard index '{}'", index))?;
+        let count: u64 = count.trim().parse().map_err(|_| format!("invalid shard count '{}'", count))?;
+        if count == 0 || index == 0 || index > count {
+            return Err(format!("shard {} is not within 1..={}", index, count));
+        }
+        Ok(Self { index, count })
+    }
+}
+
+impl Shard {
+    /// Whether the repo-relative `path` belongs to this shard
+    pub fn contains(&self, path: &str) -> bool {
+        use sha2::{Digest, Sha256};
         
-        let mut hasher = DefaultHasher::new();
-        content.hash(&mut hasher);
-        format!("{:x}", hasher.finish())
+        let dir = path.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let digest = Sha256::digest(dir.as_bytes());
+        let bucket = u64::from_be_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+        bucket % self.count == self.index - 1
+    }
+}
+
+fn is_loop(err: &ignore::Error) -> bool {
+    match err {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_loop(err),
+        _ => false,
     }
 }
 
+/// Identity of the file behind `path`, shared by all of its links
+/// (elsewhere only symlinks resolve to the same identity)
+#[cfg(unix)]
+fn file_identity(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(path: &Path) -> Option<PathBuf> {
+    std::fs::canonicalize(path).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,6 +274,27 @@ mod tests {
         Ok(())
     }
     
+    #[test]
+    fn test_shards_partition_by_directory() {
+        let paths: Vec<String> = (0..40)
+            .flat_map(|dir| [format!("pkg{}/a.ts", dir), format!("pkg{}/b.ts", dir)])
+            .chain(["main.rs".to_string()])
+            .collect();
+        let shards: Vec<Shard> = (1..=3).map(|k| format!("{}/3", k).parse().unwrap()).collect();
+        
+        for path in &paths {
+            let owners = shards.iter().filter(|shard| shard.contains(path)).count();
+            assert_eq!(owners, 1, "{} must be in exactly one shard", path);
+        }
+        assert!(shards.iter().all(|shard| paths.iter().any(|path| shard.contains(path))));
+        let first = shards.iter().position(|shard| shard.contains("pkg7/a.ts"));
+        assert_eq!(first, shards.iter().position(|shard| shard.contains("pkg7/b.ts")));
+        
+        assert!("0/3".parse::<Shard>().is_err());
+        assert!("4/3".parse::<Shard>().is_err());
+        assert!("2".parse::<Shard>().is_err());
+    }
+    
     #[test]
     fn test_file_hash_computation() {
         let content1 = "hello world";
@@ -187,4 +308,29 @@ mod tests {
         assert_eq!(hash1, hash2, "Same content should produce same hash");
         assert_ne!(hash1, hash3, "Different content should produce different hash");
     }
-}
\ No newline at end of file
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_walker_survives_symlink_cycles_and_duplicate_links() -> Result<()> {
+        use std::os::unix::fs::symlink;
+        
+        let dir = TempDir::new()?;
+        fs::create_dir_all(dir.path().join("src/nested"))?;
+        fs::write(dir.path().join("src/main.ts"), "export const x = 1")?;
+        fs::hard_link(dir.path().join("src/main.ts"), dir.path().join("src/copy.ts"))?;
+        symlink(dir.path().join("src"), dir.path().join("src/nested/loop"))?;
+        symlink(dir.path().join("src/main.ts"), dir.path().join("alias.ts"))?;
+        
+        // Not following: the hardlink pair is indexed once, symlinks not at all
+        let files = FileWalker::new(dir.path().to_path_buf()).walk()?;
+        assert_eq!(files, vec![dir.path().join("src/copy.ts")]);
+        
+        // Following: the cycle is skipped and every alias of main.ts collapses
+        let files = FileWalker::new(dir.path().to_path_buf())
+            .with_follow_symlinks(true)
+            .walk()?;
+        assert_eq!(files, vec![dir.path().join("alias.ts")]);
+        
+        Ok(())
+    }
+}
@@ -14,7 +14,7 @@ fn test_empty_repository() -> Result<()> {
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle empty repository");
@@ -53,7 +53,7 @@ fn test_files_with_no_symbols() -> Result<()> {
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle files with no symbols");
@@ -114,7 +114,7 @@ class КлассПример:
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle Unicode identifiers");
@@ -159,7 +159,7 @@ fn test_very_long_identifiers() -> Result<()> {
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle very long identifiers");
@@ -196,7 +196,7 @@ fn test_files_with_bom() -> Result<()> {
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle files with BOM");
@@ -232,7 +232,7 @@ fn test_mixed_line_endings() -> Result<()> {
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle mixed line endings");
@@ -277,7 +277,7 @@ export function b(): void { a(); }
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle circular dependencies");
@@ -327,7 +327,7 @@ public class Special {
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle special characters in strings");
@@ -362,7 +362,7 @@ fn test_non_ascii_file_paths() -> Result<()> {
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle non-ASCII file paths");
@@ -403,7 +403,7 @@ fn test_symlinks() -> Result<()> {
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     // Should at least not crash on symlinks
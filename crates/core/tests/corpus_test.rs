@@ -0,0 +1,172 @@
+//! Regression harness over pinned revisions of real open-source repositories.
+//!
+//! Needs network access and a few minutes, so it is ignored by default:
+//!
+//! ```text
+//! cargo test -p reviewbot --test corpus_test -- --ignored
+//! CORPUS_REPOS=gin,requests cargo test -p reviewbot --test corpus_test -- --ignored
+//! CORPUS_BLESS=1 cargo test -p reviewbot --test corpus_test -- --ignored
+//! ```
+//!
+//! Repositories and their golden symbols live in `tests/corpus/repos.json`.
+//! Symbol/edge counts are compared against `tests/corpus/baseline.json`;
+//! `CORPUS_BLESS=1` rewrites the baseline after an intentional change; a
+//! repository missing from it fails until blessed.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use store::GraphStore;
+
+/// Allowed drift in counts before the harness reports a regression
+const TOLERANCE: f64 = 0.02;
+
+#[derive(Deserialize)]
+struct CorpusRepo {
+    name: String,
+    url: String,
+    tag: String,
+    golden: Vec<GoldenSymbol>,
+}
+
+/// A symbol that must be found, by name, in the given file
+#[derive(Deserialize)]
+struct GoldenSymbol {
+    name: String,
+    file: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, Deserialize)]
+struct Counts {
+    symbols: usize,
+    edges: usize,
+}
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+/// Shallow clone of `repo` at its pinned tag, cached across runs
+fn checkout(repo: &CorpusRepo) -> Result<PathBuf> {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("corpus").join(format!("{}-{}", repo.name, repo.tag));
+    if !dir.exists() {
+        let status = Command::new("git")
+            .args(["clone", "-q", "--depth", "1", "--branch", &repo.tag, &repo.url])
+            .arg(&dir)
+            .status()?;
+        if !status.success() {
+            bail!("failed to clone {} at {}", repo.url, repo.tag);
+        }
+    }
+    // Always index from scratch
+    let _ = std::fs::remove_dir_all(dir.join(".reviewbot"));
+    Ok(dir)
+}
+
+fn scan(repo_dir: &Path) -> Result<()> {
+    let output = Command::new("cargo")
+        .args(["run", "-q", "--release", "-p", "reviewbot", "--", "--repo", repo_dir.to_str().unwrap(), "index", "scan"])
+        .output()?;
+    if !output.status.success() {
+        bail!("scan of {} failed: {}", repo_dir.display(), String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(())
+}
+
+fn within_tolerance(expected: usize, actual: usize) -> bool {
+    let allowed = (expected as f64 * TOLERANCE).ceil() as usize;
+    expected.abs_diff(actual) <= allowed
+}
+
+/// Index `repo` and check its golden symbols are found
+fn check_repo(repo: &CorpusRepo) -> Result<(Counts, Vec<String>)> {
+    let dir = checkout(repo)?;
+    scan(&dir)?;
+    let store = GraphStore::new(&dir)?;
+    let counts = Counts {
+        symbols: store.get_symbol_count()?,
+        edges: store.get_edge_count()?,
+    };
+
+    let mut failures = Vec::new();
+    for golden in &repo.golden {
+        let found = store
+            .get_symbols_in_file(&golden.file)?
+            .iter()
+            .any(|s| s.name == golden.name);
+        if !found {
+            failures.push(format!("{}: {} not found in {}", repo.name, golden.name, golden.file));
+        }
+    }
+
+    Ok((counts, failures))
+}
+
+/// Regressions of `counts` against the recorded `baseline` of repo `name`;
+/// a repo without one fails until it is blessed
+fn compare_to_baseline(name: &str, baseline: Option<&Counts>, counts: Counts) -> Vec<String> {
+    let Some(expected) = baseline else {
+        return vec![format!("{}: no baseline recorded, run with CORPUS_BLESS=1", name)];
+    };
+    let mut failures = Vec::new();
+    if !within_tolerance(expected.symbols, counts.symbols) {
+        failures.push(format!("{}: {} symbols, baseline {}", name, counts.symbols, expected.symbols));
+    }
+    if !within_tolerance(expected.edges, counts.edges) {
+        failures.push(format!("{}: {} edges, baseline {}", name, counts.edges, expected.edges));
+    }
+    failures
+}
+
+#[test]
+#[ignore = "clones real repositories; run with --ignored"]
+fn test_corpus_regressions() -> Result<()> {
+    let repos: Vec<CorpusRepo> = serde_json::from_str(&std::fs::read_to_string(corpus_dir().join("repos.json"))?)?;
+    let baseline_path = corpus_dir().join("baseline.json");
+    let mut baseline: BTreeMap<String, Counts> = serde_json::from_str(&std::fs::read_to_string(&baseline_path)?)?;
+    let only: Option<Vec<String>> = std::env::var("CORPUS_REPOS")
+        .ok()
+        .map(|v| v.split(',').map(|s| s.trim().to_string()).collect());
+    let bless = std::env::var_os("CORPUS_BLESS").is_some();
+
+    let mut failures = Vec::new();
+    for repo in &repos {
+        if only.as_ref().is_some_and(|only| !only.contains(&repo.name)) {
+            continue;
+        }
+        let (counts, repo_failures) = check_repo(repo).with_context(|| format!("indexing {}", repo.name))?;
+        eprintln!("{}: {} symbols, {} edges", repo.name, counts.symbols, counts.edges);
+        failures.extend(repo_failures);
+        if bless {
+            baseline.insert(repo.name.clone(), counts);
+        } else {
+            failures.extend(compare_to_baseline(&repo.name, baseline.get(&repo.name), counts));
+        }
+    }
+
+    if bless {
+        std::fs::write(&baseline_path, serde_json::to_string_pretty(&baseline)? + "\n")?;
+    }
+    assert!(failures.is_empty(), "corpus regressions:\n{}", failures.join("\n"));
+    Ok(())
+}
+
+#[test]
+fn test_tolerance() {
+    assert!(within_tolerance(1000, 1020));
+    assert!(!within_tolerance(1000, 1021));
+    assert!(within_tolerance(0, 0));
+}
+
+#[test]
+fn test_missing_baseline_fails() {
+    let counts = Counts { symbols: 100, edges: 50 };
+    assert_eq!(compare_to_baseline("gin", None, counts), ["gin: no baseline recorded, run with CORPUS_BLESS=1"]);
+    assert!(compare_to_baseline("gin", Some(&counts), counts).is_empty());
+    assert_eq!(
+        compare_to_baseline("gin", Some(&Counts { symbols: 100, edges: 60 }), counts),
+        ["gin: 50 edges, baseline 60"]
+    );
+}
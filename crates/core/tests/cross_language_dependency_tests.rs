@@ -1,5 +1,5 @@
 use anyhow::Result;
-use protocol::{EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Resolution, Span, SymbolIR, SymbolKind, Version};
+use protocol::{EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Resolution, Span, SymbolFlags, SymbolIR, SymbolKind, Version};
 use std::collections::HashMap;
 use serde_json;
 use std::fs;
@@ -1313,10 +1313,16 @@ fn test_cross_language_symbol_resolution() -> Result<()> {
             name: "DataProcessorClient".to_string(),
             fqn: "src.frontend.api_client.DataProcessorClient".to_string(),
             signature: Some("class DataProcessorClient".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/frontend/api_client.ts".to_string(),
-            span: Span { start_line: 12, start_col: 0, end_line: 50, end_col: 1 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 12, start_col: 0, end_line: 50, end_col: 1 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("Client for cross-language data processing services".to_string()),
+            doc_tags: None,
             sig_hash: "ts_client_hash".to_string(),
         },
         
@@ -1329,10 +1335,16 @@ fn test_cross_language_symbol_resolution() -> Result<()> {
             name: "DataProcessor".to_string(),
             fqn: "src.scripts.data_processor.DataProcessor".to_string(),
             signature: Some("class DataProcessor".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/scripts/data_processor.py".to_string(),
-            span: Span { start_line: 20, start_col: 0, end_line: 80, end_col: 1 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 20, start_col: 0, end_line: 80, end_col: 1 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("Python data processor with native library integration".to_string()),
+            doc_tags: None,
             sig_hash: "py_processor_hash".to_string(),
         },
         
@@ -1345,10 +1357,16 @@ fn test_cross_language_symbol_resolution() -> Result<()> {
             name: "processHandler".to_string(),
             fqn: "src.services.filter_service.processHandler".to_string(),
             signature: Some("func processHandler(w http.ResponseWriter, r *http.Request)".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/services/filter_service.go".to_string(),
-            span: Span { start_line: 65, start_col: 0, end_line: 80, end_col: 1 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 65, start_col: 0, end_line: 80, end_col: 1 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("HTTP handler for data processing requests".to_string()),
+            doc_tags: None,
             sig_hash: "go_handler_hash".to_string(),
         },
         
@@ -1361,10 +1379,16 @@ fn test_cross_language_symbol_resolution() -> Result<()> {
             name: "rust_sort_array".to_string(),
             fqn: "src.native.math_processor.rust_sort_array".to_string(),
             signature: Some("extern \"C\" fn rust_sort_array(arr: *mut c_double, len: c_int)".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/native/math_processor.rs".to_string(),
-            span: Span { start_line: 30, start_col: 0, end_line: 40, end_col: 1 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 30, start_col: 0, end_line: 40, end_col: 1 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("FFI-compatible sort function callable from C/Python/Java".to_string()),
+            doc_tags: None,
             sig_hash: "rust_ffi_hash".to_string(),
         },
         
@@ -1377,10 +1401,16 @@ fn test_cross_language_symbol_resolution() -> Result<()> {
             name: "sortArray".to_string(),
             fqn: "src.native.DataTransformer.sortArray".to_string(),
             signature: Some("private native double[] sortArray(double[] input)".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/native/DataTransformer.java".to_string(),
-            span: Span { start_line: 25, start_col: 4, end_line: 25, end_col: 50 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 25, start_col: 4, end_line: 25, end_col: 50 },
             visibility: Some("private".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("JNI method implemented in Rust".to_string()),
+            doc_tags: None,
             sig_hash: "java_jni_hash".to_string(),
         },
         
@@ -1393,10 +1423,16 @@ fn test_cross_language_symbol_resolution() -> Result<()> {
             name: "sort_and_analyze".to_string(),
             fqn: "src.native.analytics_lib.sort_and_analyze".to_string(),
             signature: Some("extern \"C\" double* sort_and_analyze(const double*, int, int*)".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/native/analytics_lib.cpp".to_string(),
-            span: Span { start_line: 10, start_col: 4, end_line: 20, end_col: 5 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 10, start_col: 4, end_line: 20, end_col: 5 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("C-compatible FFI interface for calling from other languages".to_string()),
+            doc_tags: None,
             sig_hash: "cpp_c_interface_hash".to_string(),
         },
     ];
@@ -1521,10 +1557,16 @@ fn test_cross_language_api_contracts() -> Result<()> {
             name: "DataProcessingRequest".to_string(),
             fqn: "src.frontend.api_client.DataProcessingRequest".to_string(),
             signature: Some("interface DataProcessingRequest".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/frontend/api_client.ts".to_string(),
-            span: Span { start_line: 5, start_col: 0, end_line: 9, end_col: 1 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 5, start_col: 0, end_line: 9, end_col: 1 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("Shared data structure used across all processing services".to_string()),
+            doc_tags: None,
             sig_hash: "data_request_interface".to_string(),
         },
         
@@ -1537,10 +1579,16 @@ fn test_cross_language_api_contracts() -> Result<()> {
             name: "ProcessingResult".to_string(),
             fqn: "src.frontend.api_client.ProcessingResult".to_string(),
             signature: Some("interface ProcessingResult".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/frontend/api_client.ts".to_string(),
-            span: Span { start_line: 11, start_col: 0, end_line: 15, end_col: 1 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 11, start_col: 0, end_line: 15, end_col: 1 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("Common result format returned by all processing services".to_string()),
+            doc_tags: None,
             sig_hash: "processing_result_interface".to_string(),
         },
     ];
@@ -1717,10 +1765,16 @@ fn test_cross_language_error_propagation() -> Result<()> {
             name: "ProcessingError".to_string(),
             fqn: "src.frontend.api_client.ProcessingError".to_string(),
             signature: Some("interface ProcessingError".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/frontend/api_client.ts".to_string(),
-            span: Span { start_line: 1, start_col: 0, end_line: 5, end_col: 1 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 1, start_col: 0, end_line: 5, end_col: 1 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("Error interface for cross-language error handling".to_string()),
+            doc_tags: None,
             sig_hash: "ts_error_interface".to_string(),
         },
         
@@ -1733,10 +1787,16 @@ fn test_cross_language_error_propagation() -> Result<()> {
             name: "ProcessingError".to_string(),
             fqn: "src.scripts.data_processor.ProcessingError".to_string(),
             signature: Some("class ProcessingError(Exception)".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/scripts/data_processor.py".to_string(),
-            span: Span { start_line: 1, start_col: 0, end_line: 5, end_col: 1 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 1, start_col: 0, end_line: 5, end_col: 1 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("Python exception for processing errors".to_string()),
+            doc_tags: None,
             sig_hash: "py_error_class".to_string(),
         },
     ];
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use protocol::{EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Resolution, Span, SymbolIR, SymbolKind, Version};
+use protocol::{EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Resolution, Span, SymbolFlags, SymbolIR, SymbolKind, Version};
 use rusqlite::Connection;
 use std::collections::HashMap;
 use store::GraphStore;
@@ -39,15 +39,21 @@ fn create_complex_symbol(id: &str, name: &str, lang: Language, kind: SymbolKind)
         name: name.to_string(),
         fqn: format!("{}.{}", lang_debug, name),
         signature: Some(format!("{}()", name)),
+        parameters: Vec::new(),
+        return_type: None,
         file_path: format!("{}.{}", name.to_lowercase(), file_ext),
-        span: Span {
+        name_span: Default::default(),
+        full_span: Span {
             start_line: 10 + id.len() as u32,
             start_col: 5,
             end_line: 10 + id.len() as u32,
             end_col: 5 + name.len() as u32,
         },
         visibility: Some(visibility),
+        flags: SymbolFlags::empty(),
+        configs: Vec::new(),
         doc: Some(format!("Documentation for {}", name)),
+        doc_tags: None,
         sig_hash: format!("hash_{}", id),
     }
 }
@@ -437,15 +443,15 @@ fn test_error_recovery_and_data_integrity() -> Result<()> {
     
     // Test insertion of malformed data
     let mut malformed_symbol = create_complex_symbol("malformed", "test", Language::TypeScript, SymbolKind::Function);
-    malformed_symbol.span.start_line = u32::MAX; // Extreme value
-    malformed_symbol.span.end_line = 0; // Invalid span (end < start)
+    malformed_symbol.full_span.start_line = u32::MAX; // Extreme value
+    malformed_symbol.full_span.end_line = 0; // Invalid span (end < start)
     
     // Should still insert successfully (no validation in current implementation)
     store.insert_symbol(commit_id, &malformed_symbol)?;
     
     let retrieved = store.get_symbol("malformed")?;
     assert!(retrieved.is_some());
-    assert_eq!(retrieved.unwrap().span.start_line, u32::MAX);
+    assert_eq!(retrieved.unwrap().full_span.start_line, u32::MAX);
     
     Ok(())
 }
@@ -518,7 +524,7 @@ fn test_query_result_ordering_and_pagination() -> Result<()> {
             SymbolKind::Function
         );
         // Set different line numbers for predictable ordering
-        symbol.span.start_line = (i as u32 + 1) * 10;
+        symbol.full_span.start_line = (i as u32 + 1) * 10;
         store.insert_symbol(commit_id, &symbol)?;
     }
     
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A small multi-language project; several files per language so that walk
+/// order and per-file HashMap iteration both have room to vary
+fn write_project(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir.join("src/models"))?;
+    fs::write(dir.join("src/index.ts"), "export * from './models/user';\nexport { helper as h } from './util';\n")?;
+    fs::write(dir.join("src/util.ts"), "export function helper(x: number) { return x * 2; }\n")?;
+    fs::write(dir.join("src/models/user.ts"), "import { helper } from '../util';\nexport class User { save() { helper(1); } }\n")?;
+    fs::write(dir.join("app.py"), "from models import User\n\ndef main():\n    User().save()\n")?;
+    fs::write(dir.join("models.py"), "class User:\n    def save(self):\n        pass\n")?;
+    fs::write(dir.join("lib.rs"), "pub use crate::inner::Thing as Alias;\nmod inner { pub struct Thing; }\nfn run() { println!(\"hi\"); }\n")?;
+    
+    for args in [&["init", "-q"][..], &["add", "."], &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "initial"]] {
+        std::process::Command::new("git").args(args).current_dir(dir).output()?;
+    }
+    Ok(())
+}
+
+fn run_reviewbot(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new("cargo")
+        .args(["run", "-q", "-p", "reviewbot", "--", "--repo", repo.to_str().unwrap()])
+        .args(args)
+        .output()?;
+    assert!(output.status.success(), "reviewbot {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn test_rescan_exports_are_byte_identical() -> Result<()> {
+    let dir = TempDir::new()?;
+    write_project(dir.path())?;
+    
+    run_reviewbot(dir.path(), &["index", "scan"])?;
+    let first = run_reviewbot(dir.path(), &["index", "export", "jsonl"])?;
+    
+    // Scan again from scratch into a fresh database
+    fs::remove_dir_all(dir.path().join(".reviewbot"))?;
+    run_reviewbot(dir.path(), &["index", "scan"])?;
+    let second = run_reviewbot(dir.path(), &["index", "export", "jsonl"])?;
+    
+    assert!(!first.is_empty(), "export should contain the indexed snapshot");
+    assert_eq!(first, second, "two scans of the same tree must export identically");
+    
+    Ok(())
+}
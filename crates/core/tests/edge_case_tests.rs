@@ -98,7 +98,7 @@ func main() {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -175,7 +175,7 @@ def test_function():
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -219,7 +219,7 @@ fn test_permission_denied_scenarios() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -304,7 +304,7 @@ fn test_very_large_files() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -346,7 +346,7 @@ fn test_binary_files_mixed_with_source() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -397,7 +397,7 @@ fn test_symlinks_and_circular_references() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -450,7 +450,7 @@ fn test_unusual_file_names() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -510,7 +510,7 @@ with no actual code
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -561,7 +561,7 @@ fn test_deeply_nested_directory_structure() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -33,7 +33,7 @@ fn test_typescript_parsing() -> Result<()> {
     
     // Run scan
     let output = std::process::Command::new(env!("CARGO_BIN_EXE_reviewbot"))
-        .arg("scan")
+        .args(["index", "scan"])
         .arg("--repo")
         .arg(&repo_path)
         .output()?;
@@ -65,7 +65,7 @@ fn test_python_parsing() -> Result<()> {
     
     // Run scan
     let output = std::process::Command::new(env!("CARGO_BIN_EXE_reviewbot"))
-        .arg("scan")
+        .args(["index", "scan"])
         .arg("--repo")
         .arg(&repo_path)
         .output()?;
@@ -100,7 +100,7 @@ fn test_go_parsing() -> Result<()> {
     
     // Run scan
     let output = std::process::Command::new(env!("CARGO_BIN_EXE_reviewbot"))
-        .arg("scan")
+        .args(["index", "scan"])
         .arg("--repo")
         .arg(&repo_path)
         .output()?;
@@ -135,7 +135,7 @@ fn test_graph_building() -> Result<()> {
     
     // Run scan
     let output = std::process::Command::new(env!("CARGO_BIN_EXE_reviewbot"))
-        .arg("scan")
+        .args(["index", "scan"])
         .arg("--repo")
         .arg(&repo_path)
         .output()?;
@@ -162,7 +162,7 @@ fn test_incremental_scan() -> Result<()> {
     
     // First scan
     let output = std::process::Command::new(env!("CARGO_BIN_EXE_reviewbot"))
-        .arg("scan")
+        .args(["index", "scan"])
         .arg("--repo")
         .arg(&repo_path)
         .output()?;
@@ -172,7 +172,7 @@ fn test_incremental_scan() -> Result<()> {
     
     // Second scan (should be incremental)
     let output = std::process::Command::new(env!("CARGO_BIN_EXE_reviewbot"))
-        .arg("scan")
+        .args(["index", "scan"])
         .arg("--repo")
         .arg(&repo_path)
         .output()?;
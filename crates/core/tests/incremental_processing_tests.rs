@@ -1,5 +1,5 @@
 use anyhow::Result;
-use protocol::{EdgeIR, EdgeType, Language, Resolution, Span, SymbolIR, SymbolKind, Version};
+use protocol::{EdgeIR, EdgeType, Language, Resolution, Span, SymbolFlags, SymbolIR, SymbolKind, Version};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
@@ -149,15 +149,21 @@ fn simulate_initial_scan(store: &GraphStore, repo_path: &PathBuf, commit_sha: &s
                 name: format!("function_{}", file_path.file_stem().unwrap_or_default().to_string_lossy()),
                 fqn: format!("{}.function_{}", relative_path, file_path.file_stem().unwrap_or_default().to_string_lossy()),
                 signature: Some("function()".to_string()),
+                parameters: Vec::new(),
+                return_type: None,
                 file_path: relative_path.clone(),
-                span: Span {
+                name_span: Default::default(),
+                full_span: Span {
                     start_line: 1,
                     start_col: 0,
                     end_line: 1,
                     end_col: 10,
                 },
                 visibility: Some("public".to_string()),
+                flags: SymbolFlags::empty(),
+                configs: Vec::new(),
                 doc: Some(format!("Function in {}", relative_path)),
+                doc_tags: None,
                 sig_hash: format!("hash_{}", relative_path.len()),
             };
             store.insert_symbol(commit_id, &symbol)?;
@@ -259,10 +265,16 @@ fn test_incremental_file_processing() -> Result<()> {
             name: "helper".to_string(),
             fqn: format!("{}.helper", file),
             signature: Some("function helper()".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file.clone(),
-            span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: 10 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: 10 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("Updated helper function".to_string()),
+            doc_tags: None,
             sig_hash: "hash_helper".to_string(),
         };
         
@@ -274,10 +286,16 @@ fn test_incremental_file_processing() -> Result<()> {
             name: "newHelper".to_string(),
             fqn: format!("{}.newHelper", file),
             signature: Some("function newHelper()".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file.clone(),
-            span: Span { start_line: 2, start_col: 0, end_line: 2, end_col: 15 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 2, start_col: 0, end_line: 2, end_col: 15 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: Some("New helper function".to_string()),
+            doc_tags: None,
             sig_hash: "hash_new_helper".to_string(),
         };
         
@@ -325,10 +343,16 @@ fn test_dependency_tracking_for_incremental_updates() -> Result<()> {
         name: "helper".to_string(),
         fqn: "src/utils.ts.helper".to_string(),
         signature: Some("function helper()".to_string()),
+        parameters: Vec::new(),
+        return_type: None,
         file_path: "src/utils.ts".to_string(),
-        span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: 10 },
+        name_span: Default::default(),
+        full_span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: 10 },
         visibility: Some("public".to_string()),
+        flags: SymbolFlags::empty(),
+        configs: Vec::new(),
         doc: Some("Helper function".to_string()),
+        doc_tags: None,
         sig_hash: "hash_helper".to_string(),
     };
     
@@ -488,10 +512,16 @@ fn test_incremental_symbol_deletion_and_recreation() -> Result<()> {
             name: "func1".to_string(),
             fqn: "src/module.ts.func1".to_string(),
             signature: Some("function func1()".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/module.ts".to_string(),
-            span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: 10 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: 10 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "hash_func1".to_string(),
         },
         SymbolIR {
@@ -502,10 +532,16 @@ fn test_incremental_symbol_deletion_and_recreation() -> Result<()> {
             name: "func2".to_string(),
             fqn: "src/module.ts.func2".to_string(),
             signature: Some("function func2()".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/module.ts".to_string(),
-            span: Span { start_line: 2, start_col: 0, end_line: 2, end_col: 10 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 2, start_col: 0, end_line: 2, end_col: 10 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "hash_func2".to_string(),
         },
         SymbolIR {
@@ -516,10 +552,16 @@ fn test_incremental_symbol_deletion_and_recreation() -> Result<()> {
             name: "MyClass".to_string(),
             fqn: "src/module.ts.MyClass".to_string(),
             signature: Some("class MyClass".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/module.ts".to_string(),
-            span: Span { start_line: 3, start_col: 0, end_line: 3, end_col: 15 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 3, start_col: 0, end_line: 3, end_col: 15 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "hash_class".to_string(),
         },
     ];
@@ -552,10 +594,16 @@ fn test_incremental_symbol_deletion_and_recreation() -> Result<()> {
             name: "func1".to_string(),
             fqn: "src/module.ts.func1".to_string(),
             signature: Some("function func1()".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/module.ts".to_string(),
-            span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: 10 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: 10 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "hash_func1_updated".to_string(),
         },
         SymbolIR {
@@ -566,10 +614,16 @@ fn test_incremental_symbol_deletion_and_recreation() -> Result<()> {
             name: "newFunc".to_string(),
             fqn: "src/module.ts.newFunc".to_string(),
             signature: Some("function newFunc()".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/module.ts".to_string(),
-            span: Span { start_line: 2, start_col: 0, end_line: 2, end_col: 12 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 2, start_col: 0, end_line: 2, end_col: 12 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "hash_new_func".to_string(),
         },
         SymbolIR {
@@ -580,10 +634,16 @@ fn test_incremental_symbol_deletion_and_recreation() -> Result<()> {
             name: "MyClass".to_string(),
             fqn: "src/module.ts.MyClass".to_string(),
             signature: Some("class MyClass".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "src/module.ts".to_string(),
-            span: Span { start_line: 3, start_col: 0, end_line: 3, end_col: 15 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 3, start_col: 0, end_line: 3, end_col: 15 },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "hash_class_updated".to_string(),
         },
     ];
@@ -47,7 +47,7 @@ fn test_incremental_cli_flag() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write",
             "--semantic", 
             "--incremental"
@@ -98,7 +98,7 @@ export class DatabaseService {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan",
+            "index", "scan",
             "--no-write",
             "--semantic"
         ])
@@ -112,7 +112,7 @@ export class DatabaseService {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan",
+            "index", "scan",
             "--no-write",
             "--semantic",
             "--incremental"
@@ -63,7 +63,7 @@ fn test_end_to_end_scan() -> Result<()> {
     
     // Run scan
     let output = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "scan"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Scan should succeed");
@@ -86,7 +86,7 @@ fn test_idempotent_scan() -> Result<()> {
     
     // First scan
     let output1 = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "scan"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     let stdout1 = String::from_utf8_lossy(&output1.stdout);
@@ -108,7 +108,7 @@ fn test_idempotent_scan() -> Result<()> {
     
     // Second scan
     let output2 = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "scan"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     let stdout2 = String::from_utf8_lossy(&output2.stdout);
@@ -106,7 +106,7 @@ int main() {
     
     // Run scan
     let output = Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "scan"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -170,7 +170,7 @@ void process();
     
     // Run scan
     let output = Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "scan"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -223,7 +223,7 @@ def valid_function():
     
     // Run scan - should not crash
     let output = Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "scan"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     // Should complete without crashing
@@ -268,7 +268,7 @@ fn test_large_file_handling() -> Result<()> {
     
     // Run scan
     let output = Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "scan"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle large files");
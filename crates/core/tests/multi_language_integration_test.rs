@@ -2081,7 +2081,7 @@ fn test_comprehensive_multi_language_project_scan() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -2232,7 +2232,7 @@ fn test_performance_with_large_multi_language_project() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -120,7 +120,7 @@ fn test_cli_performance_monitoring_integration() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -189,7 +189,7 @@ export class TestClass {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--semantic",
             "--no-write"
         ])
@@ -237,7 +237,7 @@ fn test_performance_output_format() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -411,7 +411,7 @@ fn measure_scan_performance(project_path: &Path, project_size: &str) -> Result<D
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &project_path.to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -538,7 +538,7 @@ fn test_memory_performance() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &small_temp.path().to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -552,7 +552,7 @@ fn test_memory_performance() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &large_temp.path().to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
@@ -587,7 +587,7 @@ fn test_semantic_scan_performance() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &temp_dir.path().to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--semantic",
             "--no-write"
         ])
@@ -647,7 +647,7 @@ fn test_performance_regression_baseline() -> Result<()> {
         .args(&[
             "run", "-p", "reviewbot", "--", 
             "--repo", &temp_dir.path().to_string_lossy(),
-            "scan", 
+            "index", "scan", 
             "--no-write"
         ])
         .output()?;
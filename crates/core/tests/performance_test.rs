@@ -83,7 +83,7 @@ private:
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--release", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     let elapsed = start.elapsed();
@@ -135,7 +135,7 @@ fn test_incremental_performance() -> Result<()> {
     // First scan
     let output1 = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output1.status.success());
@@ -161,7 +161,7 @@ fn test_incremental_performance() -> Result<()> {
     
     let output2 = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     let elapsed = start.elapsed();
@@ -228,7 +228,7 @@ fn test_concurrent_file_processing() -> Result<()> {
     
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     let elapsed = start.elapsed();
@@ -288,7 +288,7 @@ fn test_memory_efficiency() -> Result<()> {
     // Should handle deep nesting without stack overflow
     let output = std::process::Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Should handle deep nesting without crashing");
@@ -38,7 +38,7 @@ main();
     
     // Run the scanner with semantic analysis
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "reviewbot", "--", "--repo", &project_path.to_string_lossy(), "scan", "--semantic"])
+        .args(&["run", "--bin", "reviewbot", "--", "--repo", &project_path.to_string_lossy(), "index", "scan", "--semantic"])
         .output()?;
     
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -89,7 +89,7 @@ func main() {
     
     // Run the scanner with semantic analysis
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "reviewbot", "--", "--repo", &project_path.to_string_lossy(), "scan", "--semantic"])
+        .args(&["run", "--bin", "reviewbot", "--", "--repo", &project_path.to_string_lossy(), "index", "scan", "--semantic"])
         .output()?;
     
     let stdout = String::from_utf8_lossy(&output.stdout);
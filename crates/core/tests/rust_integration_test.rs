@@ -91,7 +91,7 @@ fn test_rust_parsing() -> Result<()> {
     
     // Run scan
     let output = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "scan"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Scan should succeed");
@@ -107,7 +107,7 @@ fn test_rust_parsing() -> Result<()> {
     
     // Test search for Rust symbols
     let search_output = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "search", "Calculator"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "query", "search", "Calculator"])
         .output()?;
     
     assert!(search_output.status.success(), "Search should succeed");
@@ -125,7 +125,7 @@ fn test_rust_symbols_extraction() -> Result<()> {
     
     // Run scan
     let output = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "scan"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Scan should succeed");
@@ -134,7 +134,7 @@ fn test_rust_symbols_extraction() -> Result<()> {
     
     // Search for trait
     let trait_search = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "search", "Compute"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "query", "search", "Compute"])
         .output()?;
     
     let trait_stdout = String::from_utf8_lossy(&trait_search.stdout);
@@ -143,7 +143,7 @@ fn test_rust_symbols_extraction() -> Result<()> {
     
     // Search for enum
     let enum_search = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "search", "Operation"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "query", "search", "Operation"])
         .output()?;
     
     let enum_stdout = String::from_utf8_lossy(&enum_search.stdout);
@@ -152,7 +152,7 @@ fn test_rust_symbols_extraction() -> Result<()> {
     
     // Search for function
     let func_search = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "search", "process_operation"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "query", "search", "process_operation"])
         .output()?;
     
     let func_stdout = String::from_utf8_lossy(&func_search.stdout);
@@ -161,7 +161,7 @@ fn test_rust_symbols_extraction() -> Result<()> {
     
     // Search for module
     let mod_search = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "search", "utils"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", test_dir.path().to_str().unwrap(), "query", "search", "utils"])
         .output()?;
     
     let mod_stdout = String::from_utf8_lossy(&mod_search.stdout);
@@ -224,7 +224,7 @@ export function calculate(x: number, y: number): number {
     
     // Run scan
     let output = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "scan"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Scan should succeed");
@@ -234,7 +234,7 @@ export function calculate(x: number, y: number): number {
     
     // Search for calculate function across all languages
     let search_output = std::process::Command::new("cargo")
-        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "search", "calculate"])
+        .args(&["run", "-p", "reviewbot", "--", "--repo", dir.path().to_str().unwrap(), "query", "search", "calculate"])
         .output()?;
     
     let search_stdout = String::from_utf8_lossy(&search_output.stdout);
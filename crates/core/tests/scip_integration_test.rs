@@ -393,10 +393,16 @@ fn create_sample_syntactic_symbols() -> Vec<protocol::SymbolIR> {
             name: "User".to_string(),
             fqn: "user/User".to_string(),
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "user.ts".to_string(),
-            span: Span { start_line: 1, start_col: 0, end_line: 5, end_col: 1 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 1, start_col: 0, end_line: 5, end_col: 1 },
             visibility: None,
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "4".to_string(),
         },
         SymbolIR {
@@ -407,10 +413,16 @@ fn create_sample_syntactic_symbols() -> Vec<protocol::SymbolIR> {
             name: "UserService".to_string(),
             fqn: "user/UserService".to_string(),
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "user.ts".to_string(),
-            span: Span { start_line: 7, start_col: 0, end_line: 21, end_col: 1 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 7, start_col: 0, end_line: 21, end_col: 1 },
             visibility: None,
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "11".to_string(),
         },
     ]
@@ -50,7 +50,7 @@ function helperFunction(): void {}
     // Run scan
     let output = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success(), "Scan should succeed");
@@ -137,7 +137,7 @@ instance.baseMethod();
     // Run scan
     let output = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success());
@@ -193,7 +193,7 @@ class OriginalClass:
     // First scan
     let output1 = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output1.status.success());
@@ -227,7 +227,7 @@ class OriginalClass:
     // Second scan - should detect exact change
     let output2 = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output2.status.success());
@@ -314,7 +314,7 @@ pub struct Data {
     // Run scan
     let output = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success());
@@ -380,7 +380,7 @@ function process(): void {}
     // Run scan
     let output = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success());
@@ -443,7 +443,7 @@ node_modules/
     // Run scan
     let output = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output.status.success());
@@ -492,7 +492,7 @@ fn test_exact_commit_tracking() -> Result<()> {
     // First scan
     let output1 = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output1.status.success());
@@ -522,7 +522,7 @@ fn test_exact_commit_tracking() -> Result<()> {
     // Second scan
     let output2 = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output2.status.success());
@@ -569,7 +569,7 @@ export class Broken {
     // Run scan - should not crash
     let output = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     // Should complete even with syntax errors
@@ -620,7 +620,7 @@ fn test_exact_database_integrity() -> Result<()> {
     // First scan
     let output1 = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output1.status.success());
@@ -635,7 +635,7 @@ fn test_exact_database_integrity() -> Result<()> {
     // Run scan again - should be idempotent
     let output2 = Command::new("cargo")
         .args(&["run", "-p", "reviewbot", "--", 
-                "--repo", dir.path().to_str().unwrap(), "scan"])
+                "--repo", dir.path().to_str().unwrap(), "index", "scan"])
         .output()?;
     
     assert!(output2.status.success());
@@ -1,10 +1,13 @@
 use anyhow::{Context, Result};
-use protocol::{EdgeIR, EdgeType, Language as ProtoLanguage, OccurrenceIR, OccurrenceRole, Resolution, Span, SymbolIR, SymbolKind, Version};
+use protocol::{Capabilities, EdgeIR, EdgeType, Language as ProtoLanguage, OccurrenceIR, OccurrenceRole, Parameter, Resolution, Span, SymbolFlags, SymbolIR, SymbolKind, Version};
 use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::{Node, Parser};
 
+pub mod preprocessor;
 mod version_detector;
+pub use preprocessor::CppConfig;
+use preprocessor::ConditionalRegions;
 use version_detector::CppVersionDetector;
 
 #[cfg(test)]
@@ -24,39 +27,61 @@ mod version_tests;
 #[cfg(test)]
 mod edge_case_extreme_tests;
 
+/// What this harness extracts, for `reviewbot query languages`
+pub const CAPABILITIES: Capabilities = Capabilities {
+    name: "C/C++",
+    extensions: &["cpp", "cc", "cxx", "hpp", "hh", "hxx", "c", "h"],
+    edges: &[EdgeType::Contains, EdgeType::Imports, EdgeType::Aliases, EdgeType::Calls, EdgeType::Extends],
+    occurrences: &[OccurrenceRole::Definition, OccurrenceRole::Reference],
+    docs: true,
+    resolution: Resolution::Syntactic,
+};
+
+/// Where a symbol is and what it is marked with, beyond its name and kind
+struct SymbolAttrs {
+    signature: Option<String>,
+    name_span: Span,
+    full_span: Span,
+    visibility: Option<String>,
+    flags: SymbolFlags,
+    doc: Option<String>,
+}
+
 pub struct CppHarness {
     parser: Parser,
     is_cpp: bool, // true for C++, false for C
     version: Option<Version>, // Detected or specified version
+    configs: Vec<CppConfig>,
 }
 
 impl CppHarness {
     fn create_symbol(
         &self,
-        id: String,
-        lang: ProtoLanguage,
         kind: SymbolKind,
         name: String,
         fqn: String,
-        signature: Option<String>,
-        file_path: String,
-        span: Span,
-        visibility: Option<String>,
-        doc: Option<String>,
+        file_path: &str,
+        attrs: SymbolAttrs,
         sig_hash: String,
     ) -> SymbolIR {
         SymbolIR {
-            id,
-            lang,
+            id: format!("{}#{}", file_path, fqn),
+            lang: if self.is_cpp { ProtoLanguage::Cpp } else { ProtoLanguage::C },
             lang_version: self.version.clone(),
             kind,
             name,
             fqn,
-            signature,
-            file_path,
-            span,
-            visibility,
-            doc,
+            signature: attrs.signature,
+            parameters: Vec::new(),
+            return_type: None,
+            file_path: file_path.to_string(),
+            name_span: attrs.name_span,
+            full_span: attrs.full_span,
+            visibility: attrs.visibility,
+            flags: attrs.flags,
+            configs: Vec::new(),
+            doc: attrs.doc,
+            doc_tags: None,
             sig_hash,
         }
     }
@@ -65,14 +90,14 @@ impl CppHarness {
         let mut parser = Parser::new();
         let lang = tree_sitter_cpp::language();
         parser.set_language(lang).context("Failed to set C++ language")?;
-        Ok(Self { parser, is_cpp: true, version: None })
+        Ok(Self { parser, is_cpp: true, version: None, configs: Vec::new() })
     }
 
     pub fn new_c() -> Result<Self> {
         let mut parser = Parser::new();
         let lang = tree_sitter_c::language();
         parser.set_language(lang).context("Failed to set C language")?;
-        Ok(Self { parser, is_cpp: false, version: None })
+        Ok(Self { parser, is_cpp: false, version: None, configs: Vec::new() })
     }
     
     pub fn new_with_version(is_cpp: bool, version: Version) -> Result<Self> {
@@ -84,7 +109,15 @@ impl CppHarness {
             let lang = tree_sitter_c::language();
             parser.set_language(lang).context("Failed to set C language")?;
         }
-        Ok(Self { parser, is_cpp, version: Some(version) })
+        Ok(Self { parser, is_cpp, version: Some(version), configs: Vec::new() })
+    }
+
+    /// Index every file under each preprocessor configuration: symbols are
+    /// tagged with the configs whose `#if` branches include them, and those
+    /// compiled under none are dropped
+    pub fn with_configs(mut self, configs: Vec<CppConfig>) -> Self {
+        self.configs = configs;
+        self
     }
 
     pub fn parse(
@@ -119,9 +152,43 @@ impl CppHarness {
             &mut context,
         )?;
         
+        if !self.configs.is_empty() {
+            let regions = ConditionalRegions::collect(root_node, content);
+            symbols = self.merge_configs(symbols, &regions);
+            occurrences.retain(|o| self.configs.iter().any(|c| regions.is_active(span_start(&o.span), c)));
+        }
+        
         Ok((symbols, edges, occurrences))
     }
 
+    /// Tag each symbol with the configs it is compiled in, folding together
+    /// definitions of the same symbol from different `#if` branches
+    fn merge_configs(&self, symbols: Vec<SymbolIR>, regions: &ConditionalRegions) -> Vec<SymbolIR> {
+        let mut merged: Vec<SymbolIR> = Vec::new();
+        for mut symbol in symbols {
+            symbol.configs = self
+                .configs
+                .iter()
+                .filter(|config| regions.is_active(span_start(&symbol.full_span), config))
+                .map(|config| config.name.clone())
+                .collect();
+            if symbol.configs.is_empty() {
+                continue;
+            }
+            match merged.iter_mut().find(|s| s.id == symbol.id) {
+                Some(existing) => {
+                    for config in symbol.configs {
+                        if !existing.configs.contains(&config) {
+                            existing.configs.push(config);
+                        }
+                    }
+                }
+                None => merged.push(symbol),
+            }
+        }
+        merged
+    }
+
     fn walk_node(
         &self,
         node: Node,
@@ -152,7 +219,10 @@ impl CppHarness {
                 self.handle_namespace(node, content, file_path, symbols, edges, occurrences, context)?;
             }
             "alias_declaration" if self.is_cpp => {
-                self.handle_alias_declaration(node, content, file_path, symbols, occurrences, context)?;
+                self.handle_alias_declaration(node, content, file_path, symbols, edges, occurrences, context)?;
+            }
+            "type_definition" => {
+                self.handle_type_definition(node, content, file_path, symbols, edges, occurrences, context)?;
             }
             "using_declaration" if self.is_cpp => {
                 self.handle_using_declaration(node, content, file_path, symbols, occurrences, context)?;
@@ -168,7 +238,7 @@ impl CppHarness {
                 self.handle_macro_definition(node, content, file_path, symbols, occurrences)?;
             }
             "lambda_expression" => {
-                self.handle_lambda(node, content, file_path, symbols, occurrences, context)?;
+                self.handle_lambda(node, content, file_path, symbols, edges, occurrences, context, None)?;
             }
             _ => {
                 // Recursively walk children
@@ -314,10 +384,16 @@ impl CppHarness {
             name: name.clone(),
             fqn: fqn.clone(),
             signature: Some(signature),
+            parameters: self.get_parameters(declarator, content),
+            return_type: self.get_return_type(node, declarator, content),
             file_path: file_path.to_string(),
-            span: self.node_to_span(declarator),
+            name_span: self.node_to_span(declarator),
+            full_span: self.node_to_span(node),
             visibility: context.current_access.clone(),
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: self.get_preceding_comment(node, content),
+            doc_tags: None,
             sig_hash,
         };
         
@@ -334,7 +410,7 @@ impl CppHarness {
         
         // Process function body for references
         if let Some(body) = node.child_by_field_name("body") {
-            self.process_function_body(body, content, file_path, edges, occurrences, &symbol.id)?;
+            self.process_function_body(body, content, file_path, symbols, edges, occurrences, context, &symbol.id)?;
         }
         
         Ok(())
@@ -375,10 +451,16 @@ impl CppHarness {
             name: name.clone(),
             fqn: fqn.clone(),
             signature: if signature.is_empty() { None } else { Some(signature) },
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: None,
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
         
@@ -497,10 +579,16 @@ impl CppHarness {
                 name: name.clone(),
                 fqn: fqn.clone(),
                 signature: None,
+                parameters: Vec::new(),
+                return_type: None,
                 file_path: file_path.to_string(),
-                span: self.node_to_span(name_node),
+                name_span: self.node_to_span(name_node),
+                full_span: self.node_to_span(node),
                 visibility: None,
+                flags: self.symbol_flags(node, file_path, content),
+                configs: Vec::new(),
                 doc: None,
+                doc_tags: None,
                 sig_hash,
             };
             
@@ -571,10 +659,16 @@ impl CppHarness {
                 name: name.clone(),
                 fqn: fqn.clone(),
                 signature: None,
+                parameters: Vec::new(),
+                return_type: None,
                 file_path: file_path.to_string(),
-                span: self.node_to_span(name_node),
+                name_span: self.node_to_span(name_node),
+                full_span: self.node_to_span(node),
                 visibility: None,
+                flags: self.symbol_flags(node, file_path, content),
+                configs: Vec::new(),
                 doc: None,
+                doc_tags: None,
                 sig_hash,
             };
             
@@ -624,6 +718,21 @@ impl CppHarness {
             let fqn = context.build_fqn(&name);
             let sig_hash = format!("{:x}", md5::compute(&fqn));
             
+            // `enum class Status : std::uint8_t`
+            let scoped = node.children(&mut node.walk()).any(|c| matches!(c.kind(), "class" | "struct"));
+            let underlying_type = node.child_by_field_name("base").map(|b| self.get_text(b, content)).or_else(|| {
+                // Builtin types like `int` aren't tagged as the base
+                let colon = node.children(&mut node.walk()).find(|c| c.kind() == ":")?;
+                colon.next_named_sibling().filter(|n| n.kind() != "enumerator_list").map(|n| self.get_text(n, content))
+            });
+            let mut signature = if scoped { format!("enum class {}", name) } else { format!("enum {}", name) };
+            if let Some(underlying) = &underlying_type {
+                signature.push_str(&format!(" : {}", underlying));
+            }
+            // Members of C++ enums are named through the enum with `::`,
+            // which scoped enums require; C has no such syntax
+            let member_separator = if self.is_cpp { "::" } else { "." };
+            
             let symbol = SymbolIR {
                 id: format!("{}#{}", file_path, fqn),
                 lang: if self.is_cpp { ProtoLanguage::Cpp } else { ProtoLanguage::C },
@@ -631,11 +740,17 @@ impl CppHarness {
                 kind: SymbolKind::Enum,
                 name: name.clone(),
                 fqn: fqn.clone(),
-                signature: None,
+                signature: Some(signature),
+                parameters: Vec::new(),
+                return_type: underlying_type,
                 file_path: file_path.to_string(),
-                span: self.node_to_span(name_node),
+                name_span: self.node_to_span(name_node),
+                full_span: self.node_to_span(node),
                 visibility: None,
+                flags: self.symbol_flags(node, file_path, content),
+                configs: Vec::new(),
                 doc: None,
+                doc_tags: None,
                 sig_hash,
             };
             
@@ -655,7 +770,7 @@ impl CppHarness {
                     if child.kind() == "enumerator" {
                         if let Some(enum_val_node) = child.child_by_field_name("name") {
                             let enum_val = self.get_text(enum_val_node, content);
-                            let enum_fqn = format!("{}.{}", fqn, enum_val);
+                            let enum_fqn = format!("{}{}{}", fqn, member_separator, enum_val);
                             let enum_sig_hash = format!("{:x}", md5::compute(&enum_fqn));
                             
                             let enum_symbol = SymbolIR {
@@ -665,11 +780,17 @@ impl CppHarness {
                                 kind: SymbolKind::EnumMember,
                                 name: enum_val.clone(),
                                 fqn: enum_fqn,
-                                signature: None,
+                                signature: Some(self.get_text(child, content)),
+                                parameters: Vec::new(),
+                                return_type: None,
                                 file_path: file_path.to_string(),
-                                span: self.node_to_span(enum_val_node),
+                                name_span: self.node_to_span(enum_val_node),
+                                full_span: self.node_to_span(child),
                                 visibility: None,
+                                flags: self.symbol_flags(child, file_path, content),
+                                configs: Vec::new(),
                                 doc: None,
+                                doc_tags: None,
                                 sig_hash: enum_sig_hash,
                             };
                             
@@ -697,6 +818,7 @@ impl CppHarness {
         content: &str,
         file_path: &str,
         symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
         context: &mut ParseContext,
     ) -> Result<()> {
@@ -721,15 +843,27 @@ impl CppHarness {
                 name: name.clone(),
                 fqn: fqn.clone(),
                 signature: Some(format!("using {} = {}", name, aliased_type)),
+                parameters: Vec::new(),
+                return_type: None,
                 file_path: file_path.to_string(),
-                span: self.node_to_span(name_node),
+                name_span: self.node_to_span(name_node),
+                full_span: self.node_to_span(node),
                 visibility: context.current_access.clone(),
+                flags: self.symbol_flags(node, file_path, content),
+                configs: Vec::new(),
                 doc: None,
+                doc_tags: None,
                 sig_hash,
             };
             
             symbols.push(symbol.clone());
             
+            if let Some(target) = node.child_by_field_name("type")
+                .and_then(|type_node| self.alias_target_name(type_node, content, context))
+            {
+                edges.push(self.create_alias_edge(file_path, &symbol, target, "using"));
+            }
+            
             occurrences.push(OccurrenceIR {
                 file_path: file_path.to_string(),
                 symbol_id: Some(symbol.id),
@@ -742,6 +876,107 @@ impl CppHarness {
         Ok(())
     }
     
+    fn handle_type_definition(
+        &self,
+        node: Node,
+        content: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        // typedef <type> <declarator>, ...;
+        let type_node = node.child_by_field_name("type");
+        
+        // Inline struct/union/enum bodies still define their own symbols
+        if let Some(type_node) = type_node {
+            self.walk_node(type_node, content, file_path, symbols, edges, occurrences, context)?;
+        }
+        
+        let aliased_type = match type_node {
+            Some(type_node) => match type_node.child_by_field_name("body") {
+                // Don't copy a whole struct body into the signature
+                Some(body) => content[type_node.start_byte()..body.start_byte()].trim().to_string(),
+                None => self.get_text(type_node, content),
+            },
+            None => "unknown".to_string(),
+        };
+        let target = type_node.and_then(|type_node| self.alias_target_name(type_node, content, context));
+        
+        for declarator in node.children_by_field_name("declarator", &mut node.walk()) {
+            let Some(name) = self.extract_declarator_name(declarator, content) else {
+                continue;
+            };
+            let fqn = context.build_fqn(&name);
+            let sig_hash = format!("{:x}", md5::compute(&fqn));
+            
+            let attrs = SymbolAttrs {
+                signature: Some(format!("typedef {} {}", aliased_type, self.get_text(declarator, content))),
+                name_span: self.node_to_span(declarator),
+                full_span: self.node_to_span(node),
+                visibility: context.current_access.clone(),
+                flags: self.symbol_flags(node, file_path, content),
+                doc: None,
+            };
+            let symbol = self.create_symbol(SymbolKind::Typedef, name.clone(), fqn, file_path, attrs, sig_hash);
+            
+            symbols.push(symbol.clone());
+            
+            occurrences.push(OccurrenceIR {
+                file_path: file_path.to_string(),
+                symbol_id: Some(symbol.id.clone()),
+                role: OccurrenceRole::Definition,
+                span: self.node_to_span(declarator),
+                token: name,
+            });
+            
+            if let Some(target) = &target {
+                edges.push(self.create_alias_edge(file_path, &symbol, target.clone(), "typedef"));
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Name of the user-defined type an alias refers to, if any (builtins have none)
+    fn alias_target_name(&self, type_node: Node, content: &str, context: &ParseContext) -> Option<String> {
+        match type_node.kind() {
+            "type_identifier" | "qualified_identifier" => Some(self.get_text(type_node, content)),
+            "template_type" => type_node.child_by_field_name("name")
+                .map(|n| self.get_text(n, content)),
+            "struct_specifier" | "union_specifier" | "enum_specifier" | "class_specifier" => {
+                let name_node = type_node.child_by_field_name("name")?;
+                let name = self.get_text(name_node, content);
+                if name.contains("::") {
+                    Some(name)
+                } else {
+                    Some(context.build_fqn(&name))
+                }
+            }
+            "type_descriptor" => type_node.child_by_field_name("type")
+                .and_then(|inner| self.alias_target_name(inner, content, context)),
+            _ => None,
+        }
+    }
+    
+    fn create_alias_edge(&self, file_path: &str, alias: &SymbolIR, target: String, alias_kind: &str) -> EdgeIR {
+        let mut meta = HashMap::new();
+        meta.insert("alias".to_string(), serde_json::json!(alias.name));
+        meta.insert("alias_kind".to_string(), serde_json::json!(alias_kind));
+        
+        EdgeIR {
+            edge_type: EdgeType::Aliases,
+            src: Some(alias.id.clone()),
+            dst: Some(target),
+            file_src: Some(file_path.to_string()),
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta,
+            provenance: HashMap::new(),
+        }
+    }
+    
     fn handle_using_declaration(
         &self,
         node: Node,
@@ -804,14 +1039,16 @@ impl CppHarness {
             name: name.clone(),
             fqn: fqn.clone(),
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: if let Some(n) = name_node {
-                self.node_to_span(n)
-            } else {
-                self.node_to_span(node)
-            },
+            name_span: self.node_to_span(name_node.unwrap_or(node)),
+            full_span: self.node_to_span(node),
             visibility: None,
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
         
@@ -888,10 +1125,16 @@ impl CppHarness {
                         name: name.clone(),
                         fqn: fqn.clone(),
                         signature: Some(format!("typedef {} {}", aliased_type, name)),
+                        parameters: Vec::new(),
+                        return_type: None,
                         file_path: file_path.to_string(),
-                        span: self.node_to_span(declarator),
+                        name_span: self.node_to_span(declarator),
+                        full_span: self.node_to_span(node),
                         visibility: context.current_access.clone(),
+                        flags: self.symbol_flags(node, file_path, content),
+                        configs: Vec::new(),
                         doc: None,
+                        doc_tags: None,
                         sig_hash,
                     };
                     
@@ -958,10 +1201,16 @@ impl CppHarness {
                     name: name.clone(),
                     fqn: fqn.clone(),
                     signature: Some(signature),
+                    parameters: self.get_parameters(declarator, content),
+                    return_type: self.get_return_type(node, declarator, content),
                     file_path: file_path.to_string(),
-                    span: self.node_to_span(declarator),
+                    name_span: self.node_to_span(declarator),
+                    full_span: self.node_to_span(node),
                     visibility: context.current_access.clone(),
+                    flags: self.symbol_flags(node, file_path, content),
+                    configs: Vec::new(),
                     doc: None,
+                    doc_tags: None,
                     sig_hash,
                 };
                 
@@ -995,10 +1244,16 @@ impl CppHarness {
                             name: name.clone(),
                             fqn: fqn.clone(),
                             signature: None,
+                            parameters: Vec::new(),
+                            return_type: None,
                             file_path: file_path.to_string(),
-                            span: self.node_to_span(declarator),
+                            name_span: self.node_to_span(declarator),
+                            full_span: self.node_to_span(node),
                             visibility: context.current_access.clone(),
+                            flags: self.symbol_flags(node, file_path, content),
+                            configs: Vec::new(),
                             doc: None,
+                            doc_tags: None,
                             sig_hash,
                         };
                         
@@ -1100,10 +1355,16 @@ impl CppHarness {
                     name: name.clone(),
                     fqn: fqn.clone(),
                     signature: Some(format!("{} {}", field_type, name)),
+                    parameters: Vec::new(),
+                    return_type: None,
                     file_path: file_path.to_string(),
-                    span: self.node_to_span(declarator),
+                    name_span: self.node_to_span(declarator),
+                    full_span: self.node_to_span(node),
                     visibility: context.current_access.clone(),
+                    flags: self.symbol_flags(node, file_path, content),
+                    configs: Vec::new(),
                     doc: None,
+                    doc_tags: None,
                     sig_hash,
                 };
                 
@@ -1198,10 +1459,16 @@ impl CppHarness {
                 name: name.clone(),
                 fqn,
                 signature: Some(signature),
+                parameters: Vec::new(),
+                return_type: None,
                 file_path: file_path.to_string(),
-                span: self.node_to_span(name_node),
+                name_span: self.node_to_span(name_node),
+                full_span: self.node_to_span(node),
                 visibility: None, // Macros don't have visibility modifiers
+                flags: self.symbol_flags(node, file_path, content),
+                configs: Vec::new(),
                 doc: self.get_preceding_comment(node, content),
+                doc_tags: None,
                 sig_hash,
             };
             
@@ -1224,13 +1491,20 @@ impl CppHarness {
         node: Node,
         content: &str,
         file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
         edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
         caller_id: &str,
     ) -> Result<()> {
         // Walk through the function body looking for function calls
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
+            // Lambdas get their own symbol, and the calls in their body
+            if child.kind() == "lambda_expression" {
+                self.handle_lambda(child, content, file_path, symbols, edges, occurrences, context, Some(caller_id))?;
+                continue;
+            }
             if child.kind() == "call_expression" {
                 if let Some(func_node) = child.child_by_field_name("function") {
                     if let Some(name) = self.extract_identifier(func_node, content) {
@@ -1259,7 +1533,7 @@ impl CppHarness {
             }
             
             // Recursively process nested blocks
-            self.process_function_body(child, content, file_path, edges, occurrences, caller_id)?;
+            self.process_function_body(child, content, file_path, symbols, edges, occurrences, context, caller_id)?;
         }
         
         Ok(())
@@ -1314,7 +1588,7 @@ impl CppHarness {
                         }
                     }
                 }
-                "identifier" => {
+                "identifier" | "type_identifier" => {
                     return Some(self.get_text(current, content));
                 }
                 "field_identifier" => {
@@ -1421,25 +1695,22 @@ impl CppHarness {
         Err(anyhow::anyhow!("Could not extract function name"))
     }
 
-    fn get_function_params(&self, declarator: Node, content: &str) -> Vec<String> {
-        let mut params = Vec::new();
-        
-        // Find the function_declarator node
-        let mut func_decl = None;
+    /// The function_declarator inside a (possibly pointer/reference) declarator
+    fn find_function_declarator<'a>(&self, declarator: Node<'a>) -> Option<Node<'a>> {
         let mut current = declarator;
         loop {
-            if current.kind() == "function_declarator" {
-                func_decl = Some(current);
-                break;
-            }
-            if let Some(child) = current.child_by_field_name("declarator") {
-                current = child;
-            } else {
-                break;
+            // Lambdas have an abstract one: `[](int x) {...}`
+            if matches!(current.kind(), "function_declarator" | "abstract_function_declarator") {
+                return Some(current);
             }
+            current = current.child_by_field_name("declarator")?;
         }
+    }
+    
+    fn get_function_params(&self, declarator: Node, content: &str) -> Vec<String> {
+        let mut params = Vec::new();
         
-        if let Some(func) = func_decl {
+        if let Some(func) = self.find_function_declarator(declarator) {
             if let Some(param_list) = func.child_by_field_name("parameters") {
                 for child in param_list.children(&mut param_list.walk()) {
                     if child.kind() == "parameter_declaration" {
@@ -1453,6 +1724,106 @@ impl CppHarness {
         params
     }
 
+    /// Flags from `[[deprecated]]`, `__attribute__((deprecated))` and
+    /// `__declspec(deprecated)`, plus gtest-style test file names
+    fn symbol_flags(&self, node: Node, file_path: &str, content: &str) -> SymbolFlags {
+        let mut flags = SymbolFlags::empty();
+        // Trailing GNU attributes hang off the function declarator instead
+        let declarator = node.child_by_field_name("declarator").into_iter();
+        for owner in std::iter::once(node).chain(declarator) {
+            for child in owner.children(&mut owner.walk()) {
+                if matches!(child.kind(), "attribute_declaration" | "attribute_specifier" | "ms_declspec_modifier") {
+                    flags.set_if(SymbolFlags::DEPRECATED, self.get_text(child, content).contains("deprecated"));
+                }
+            }
+        }
+        let file_name = Path::new(file_path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("");
+        flags.set_if(
+            SymbolFlags::TEST_ONLY,
+            file_name.ends_with("_test") || file_name.ends_with("_unittest") || file_name.starts_with("test_"),
+        );
+        flags
+    }
+
+    /// Declared return type including cv-qualifiers and the pointer/reference
+    /// markers that sit on the declarator (`const char*`). Constructors,
+    /// destructors and conversion operators have none.
+    fn get_return_type(&self, node: Node, declarator: Node, content: &str) -> Option<String> {
+        let type_node = node.child_by_field_name("type")?;
+        let start = node
+            .children(&mut node.walk())
+            .take_while(|child| child.id() != type_node.id())
+            .find(|child| child.kind() == "type_qualifier")
+            .map_or(type_node.start_byte(), |qualifier| qualifier.start_byte());
+        let indirection = self
+            .find_function_declarator(declarator)
+            .map(|func| content[declarator.start_byte()..func.start_byte()].trim())
+            .unwrap_or_default();
+        Some(format!("{}{}", &content[start..type_node.end_byte()], indirection))
+    }
+    
+    /// Structured parameters of a function declarator. The type keeps its
+    /// qualifiers and pointer/reference markers (`const std::string&`).
+    fn get_parameters(&self, declarator: Node, content: &str) -> Vec<Parameter> {
+        let Some(param_list) = self
+            .find_function_declarator(declarator)
+            .and_then(|func| func.child_by_field_name("parameters"))
+        else {
+            return Vec::new();
+        };
+        
+        let mut parameters = Vec::new();
+        for param in param_list.named_children(&mut param_list.walk()) {
+            let variadic = match param.kind() {
+                "parameter_declaration" | "optional_parameter_declaration" => false,
+                "variadic_parameter_declaration" => true,
+                // C `...`
+                "variadic_parameter" => {
+                    parameters.push(Parameter { variadic: true, ..Default::default() });
+                    continue;
+                }
+                _ => continue,
+            };
+            // `f(void)` takes no parameters
+            if self.get_text(param, content) == "void" {
+                continue;
+            }
+            
+            let name = param
+                .child_by_field_name("declarator")
+                .and_then(|decl| self.extract_identifier(decl, content))
+                .unwrap_or_default();
+            // Everything before the name, minus a trailing `...` of a parameter pack
+            let type_end = param
+                .child_by_field_name("declarator")
+                .filter(|_| !name.is_empty())
+                .and_then(|decl| {
+                    let decl_text = self.get_text(decl, content);
+                    decl_text.rfind(name.as_str()).map(|pos| decl.start_byte() + pos)
+                })
+                .or_else(|| param.child_by_field_name("default_value").map(|d| d.start_byte()))
+                .unwrap_or(param.end_byte());
+            let type_text = content[param.start_byte()..type_end]
+                .trim()
+                .trim_end_matches('=')
+                .trim_end()
+                .trim_end_matches("...")
+                .trim_end()
+                .to_string();
+            
+            parameters.push(Parameter {
+                name,
+                type_name: (!type_text.is_empty()).then_some(type_text),
+                default: param.child_by_field_name("default_value").map(|d| self.get_text(d, content)),
+                variadic,
+            });
+        }
+        parameters
+    }
+    
     fn extract_identifier(&self, node: Node, content: &str) -> Option<String> {
         match node.kind() {
             "identifier" | "field_identifier" => Some(self.get_text(node, content)),
@@ -1469,14 +1840,18 @@ impl CppHarness {
         }
     }
 
+    /// A lambda becomes a function symbol inside `parent_id`, the function
+    /// it is written in; the Contains edge carries its capture list
     fn handle_lambda(
         &self,
         node: Node,
         content: &str,
         file_path: &str,
         symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
         context: &mut ParseContext,
+        parent_id: Option<&str>,
     ) -> Result<()> {
         // Create a unique ID for the lambda
         let lambda_id = format!("lambda_{}_{}",
@@ -1489,9 +1864,17 @@ impl CppHarness {
         let mut signature = String::new();
         
         // Handle capture clause [&] or [=] or [this] etc
-        if let Some(captures) = node.child_by_field_name("captures") {
-            let capture_text = self.get_text(captures, content);
+        let mut captures = Vec::new();
+        if let Some(capture_node) = node.child_by_field_name("captures") {
+            let capture_text = self.get_text(capture_node, content);
             signature.push_str(&capture_text);
+            captures = capture_text
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split(',')
+                .map(|capture| capture.trim().to_string())
+                .filter(|capture| !capture.is_empty())
+                .collect();
         }
         
         // Handle parameters
@@ -1509,6 +1892,7 @@ impl CppHarness {
         }
         signature.push_str(") {...}");
         
+        let declarator = node.child_by_field_name("declarator");
         let symbol = SymbolIR {
             id: format!("{}#{}", file_path, fqn),
             lang: if self.is_cpp { ProtoLanguage::Cpp } else { ProtoLanguage::C },
@@ -1517,26 +1901,50 @@ impl CppHarness {
             name: lambda_id.clone(),
             fqn,
             signature: Some(signature),
+            parameters: declarator.map(|d| self.get_parameters(d, content)).unwrap_or_default(),
+            // `[](int x) -> int {...}`
+            return_type: declarator
+                .and_then(|d| d.children(&mut d.walk()).find(|c| c.kind() == "trailing_return_type"))
+                .map(|t| self.get_text(t, content).trim_start_matches("->").trim().to_string()),
             file_path: file_path.to_string(),
-            span: self.node_to_span(node),
+            name_span: self.node_to_span(node),
+            full_span: self.node_to_span(node),
             visibility: None,
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
         
         symbols.push(symbol.clone());
         
+        if let Some(parent_id) = parent_id {
+            let mut meta = HashMap::new();
+            meta.insert("captures".to_string(), serde_json::json!(captures));
+            edges.push(EdgeIR {
+                edge_type: EdgeType::Contains,
+                src: Some(parent_id.to_string()),
+                dst: Some(symbol.id.clone()),
+                file_src: Some(file_path.to_string()),
+                file_dst: Some(file_path.to_string()),
+                resolution: Resolution::Syntactic,
+                meta,
+                provenance: HashMap::new(),
+            });
+        }
+        
         occurrences.push(OccurrenceIR {
             file_path: file_path.to_string(),
-            symbol_id: Some(symbol.id),
+            symbol_id: Some(symbol.id.clone()),
             role: OccurrenceRole::Definition,
             span: self.node_to_span(node),
             token: lambda_id,
         });
         
-        // Walk the body to find any calls or references inside
+        // Calls in the body are made by the lambda
         if let Some(body) = node.child_by_field_name("body") {
-            self.walk_node(body, content, file_path, symbols, &mut Vec::new(), occurrences, context)?;
+            self.process_function_body(body, content, file_path, symbols, edges, occurrences, context, &symbol.id)?;
         }
         
         Ok(())
@@ -1717,6 +2125,10 @@ impl CppHarness {
     }
 }
 
+fn span_start(span: &Span) -> tree_sitter::Point {
+    tree_sitter::Point { row: span.start_line as usize, column: span.start_col as usize }
+}
+
 struct ParseContext {
     namespaces: Vec<String>,
     classes: Vec<String>,
@@ -1807,6 +2219,106 @@ int add(int a, int b) {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_parameters() -> Result<()> {
+        let mut harness = CppHarness::new_cpp()?;
+        let source = r#"
+void log(const std::string& fmt, int level = 2, char* buf);
+template<typename... Args> void emit(Args&&... args) {}
+"#;
+        
+        let (symbols, _, _) = harness.parse("test.cpp", source)?;
+        let summary = |name: &str| -> Vec<(String, Option<String>, Option<String>, bool)> {
+            symbols.iter().find(|s| s.name == name).unwrap().parameters.iter()
+                .map(|p| (p.name.clone(), p.type_name.clone(), p.default.clone(), p.variadic))
+                .collect()
+        };
+        
+        assert_eq!(summary("log"), vec![
+            ("fmt".to_string(), Some("const std::string&".to_string()), None, false),
+            ("level".to_string(), Some("int".to_string()), Some("2".to_string()), false),
+            ("buf".to_string(), Some("char*".to_string()), None, false),
+        ]);
+        assert_eq!(summary("emit"), vec![
+            ("args".to_string(), Some("Args&&".to_string()), None, true),
+        ]);
+        assert_eq!(symbols.iter().find(|s| s.name == "log").unwrap().return_type.as_deref(), Some("void"));
+        
+        let mut harness = CppHarness::new_c()?;
+        let (symbols, _, _) = harness.parse("test.c", "int printf(const char *fmt, ...);\nint rand(void);\nconst char *name(void) { return 0; }\n")?;
+        let name = symbols.iter().find(|s| s.name == "name").unwrap();
+        assert_eq!(name.return_type.as_deref(), Some("const char*"));
+        let printf = symbols.iter().find(|s| s.name == "printf").unwrap();
+        assert_eq!(printf.parameters.len(), 2);
+        assert!(printf.parameters[1].variadic);
+        let rand = symbols.iter().find(|s| s.name == "rand").unwrap();
+        assert!(rand.parameters.is_empty());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_config_indexing() -> Result<()> {
+        let configs = vec![
+            CppConfig::new("linux", &[("__linux__", ""), ("USE_EPOLL", "1")]),
+            CppConfig::new("windows", &[("_WIN32", "")]),
+        ];
+        let mut harness = CppHarness::new_c()?.with_configs(configs);
+        let content = r#"
+#ifdef _WIN32
+int poll_events(void) { return 0; }
+int iocp_handle = 0;
+#elif defined(__linux__) && USE_EPOLL
+int poll_events(void) { return 1; }
+int epoll_fd = 0;
+#endif
+
+#if 0
+int never_built(void);
+#endif
+
+int shared(void) { return 2; }
+"#;
+
+        let (symbols, _, _) = harness.parse("poll.c", content)?;
+        let configs = |name: &str| symbols.iter().find(|s| s.name == name).map(|s| s.configs.clone());
+        assert_eq!(configs("poll_events"), Some(vec!["windows".to_string(), "linux".to_string()]));
+        assert_eq!(symbols.iter().filter(|s| s.name == "poll_events").count(), 1);
+        assert_eq!(configs("iocp_handle"), Some(vec!["windows".to_string()]));
+        assert_eq!(configs("epoll_fd"), Some(vec!["linux".to_string()]));
+        assert_eq!(configs("never_built"), None);
+        assert_eq!(configs("shared"), Some(vec!["linux".to_string(), "windows".to_string()]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_flags() -> Result<()> {
+        let mut harness = CppHarness::new_cpp()?;
+        let content = r#"
+[[deprecated("use open_v2")]]
+int open(const char* path);
+
+void close(int fd) __attribute__((deprecated));
+
+int open_v2(const char* path) { return 0; }
+
+class [[deprecated]] LegacyFile {};
+"#;
+
+        let (symbols, _, _) = harness.parse("io.cpp", content)?;
+        let flags = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().flags;
+        assert_eq!(flags("open"), SymbolFlags::DEPRECATED);
+        assert_eq!(flags("close"), SymbolFlags::DEPRECATED);
+        assert!(flags("open_v2").is_empty());
+        assert_eq!(flags("LegacyFile"), SymbolFlags::DEPRECATED);
+
+        let (symbols, _, _) = harness.parse("io_test.cc", "void helper() {}")?;
+        assert_eq!(symbols[0].flags, SymbolFlags::TEST_ONLY);
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_cpp_class() -> Result<()> {
         let mut harness = CppHarness::new_cpp()?;
@@ -1914,6 +2426,36 @@ enum Color {
         Ok(())
     }
 
+    #[test]
+    fn test_scoped_enums() -> Result<()> {
+        let mut harness = CppHarness::new_cpp()?;
+        let source = r#"
+namespace net {
+enum class Status : std::uint8_t { OK = 0, ERROR };
+enum struct Mode : int { Fast };
+enum Color { RED };
+}
+"#;
+        
+        let (symbols, _edges, _occurrences) = harness.parse("test.cpp", source)?;
+        
+        let summary: Vec<_> = symbols.iter()
+            .filter(|s| matches!(s.kind, SymbolKind::Enum | SymbolKind::EnumMember))
+            .map(|s| (s.fqn.as_str(), s.signature.as_deref(), s.return_type.as_deref()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("net::Status", Some("enum class Status : std::uint8_t"), Some("std::uint8_t")),
+            ("net::Status::OK", Some("OK = 0"), None),
+            ("net::Status::ERROR", Some("ERROR"), None),
+            ("net::Mode", Some("enum class Mode : int"), Some("int")),
+            ("net::Mode::Fast", Some("Fast"), None),
+            ("net::Color", Some("enum Color"), None),
+            ("net::Color::RED", Some("RED"), None),
+        ]);
+        
+        Ok(())
+    }
+
     #[test]
     fn test_parse_namespace() -> Result<()> {
         let mut harness = CppHarness::new_cpp()?;
@@ -1993,6 +2535,51 @@ public:
         Ok(())
     }
 
+    #[test]
+    fn test_lambdas_in_functions() -> Result<()> {
+        let mut harness = CppHarness::new_cpp()?;
+        let source = r#"
+void process(std::vector<int>& items, int limit) {
+    auto keep = [&items, limit](int x) -> bool {
+        log(x);
+        return x < limit;
+    };
+    run([=] { flush(); });
+}
+"#;
+        
+        let (symbols, edges, _occurrences) = harness.parse("test.cpp", source)?;
+        
+        let keep = symbols.iter().find(|s| s.name == "lambda_2_16").unwrap();
+        assert_eq!(keep.kind, SymbolKind::Function);
+        assert_eq!(keep.signature.as_deref(), Some("[&items, limit](int x) {...}"));
+        assert_eq!(keep.parameters.len(), 1);
+        assert_eq!(keep.parameters[0].name, "x");
+        assert_eq!(keep.return_type.as_deref(), Some("bool"));
+        
+        let contains: Vec<_> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Contains)
+            .map(|e| (e.src.as_deref().unwrap(), e.dst.as_deref().unwrap(), e.meta["captures"].clone()))
+            .collect();
+        assert_eq!(contains, vec![
+            ("test.cpp#process", "test.cpp#lambda_2_16", serde_json::json!(["&items", "limit"])),
+            ("test.cpp#process", "test.cpp#lambda_6_8", serde_json::json!(["="])),
+        ]);
+        
+        // Calls inside a lambda are attributed to it, not the function
+        let callers = |callee: &str| -> Vec<String> {
+            edges.iter()
+                .filter(|e| e.edge_type == EdgeType::Calls && e.dst.as_deref() == Some(callee))
+                .filter_map(|e| e.src.clone())
+                .collect()
+        };
+        assert_eq!(callers("log"), ["test.cpp#lambda_2_16"]);
+        assert_eq!(callers("flush"), ["test.cpp#lambda_6_8"]);
+        assert_eq!(callers("run"), ["test.cpp#process"]);
+        
+        Ok(())
+    }
+
     #[test]
     fn test_parse_function_calls() -> Result<()> {
         let mut harness = CppHarness::new_c()?;
@@ -2075,4 +2662,35 @@ public:
         
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_typedef_alias_edges() -> Result<()> {
+        let mut harness = CppHarness::new_c()?;
+        let source = r#"
+struct node { int value; };
+typedef struct node Node, *NodePtr;
+typedef struct { int x; int y; } Point;
+typedef unsigned long size_type;
+"#;
+        
+        let (symbols, edges, _occurrences) = harness.parse("list.c", source)?;
+        
+        let typedefs: Vec<_> = symbols.iter()
+            .filter(|s| s.kind == SymbolKind::Typedef)
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(typedefs, vec!["Node", "NodePtr", "Point", "size_type"]);
+        
+        // Only typedefs of named user types alias another symbol
+        let aliases: Vec<_> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Aliases)
+            .map(|e| (e.src.clone().unwrap(), e.dst.clone().unwrap()))
+            .collect();
+        assert_eq!(aliases, vec![
+            ("list.c#Node".to_string(), "node".to_string()),
+            ("list.c#NodePtr".to_string(), "node".to_string()),
+        ]);
+        
+        Ok(())
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use tree_sitter::{Node, Point};
+
+/// A named preprocessor configuration, e.g. one target platform
+#[derive(Debug, Clone, PartialEq)]
+pub struct CppConfig {
+    pub name: String,
+    /// Macro name to replacement text; `-DFOO` is `FOO` -> ``
+    pub defines: HashMap<String, String>,
+}
+
+impl CppConfig {
+    pub fn new(name: &str, defines: &[(&str, &str)]) -> Self {
+        Self {
+            name: name.to_string(),
+            defines: defines.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+}
+
+impl FromStr for CppConfig {
+    type Err = String;
+
+    /// `name:DEF1,DEF2=value`, e.g. `linux:__linux__,HAVE_EPOLL=1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, defines) = s.split_once(':').unwrap_or((s, ""));
+        if name.is_empty() {
+            return Err(format!("config '{}' has no name", s));
+        }
+        let defines = defines
+            .split(',')
+            .filter(|define| !define.is_empty())
+            .map(|define| {
+                let (macro_name, value) = define.split_once('=').unwrap_or((define, ""));
+                (macro_name.to_string(), value.to_string())
+            })
+            .collect();
+        Ok(Self { name: name.to_string(), defines })
+    }
+}
+
+/// One branch of an `#if`/`#ifdef` chain: active when its own condition
+/// holds and none of the earlier branches' did
+#[derive(Debug, Clone)]
+struct Branch {
+    start: Point,
+    end: Point,
+    earlier: Vec<String>,
+    /// `None` for `#else`
+    condition: Option<String>,
+}
+
+/// The conditional-compilation branches of a file. tree-sitter keeps every
+/// branch in the tree, so a symbol's visibility under a configuration is
+/// decided by evaluating the branches around it.
+#[derive(Debug, Default)]
+pub struct ConditionalRegions {
+    branches: Vec<Branch>,
+}
+
+impl ConditionalRegions {
+    pub fn collect(root: Node, content: &str) -> Self {
+        let mut regions = Self::default();
+        regions.visit(root, content);
+        regions
+    }
+
+    fn visit(&mut self, node: Node, content: &str) {
+        if matches!(node.kind(), "preproc_if" | "preproc_ifdef") {
+            self.add_chain(node, content);
+        }
+        for child in node.children(&mut node.walk()) {
+            self.visit(child, content);
+        }
+    }
+
+    fn add_chain(&mut self, node: Node, content: &str) {
+        let mut earlier = Vec::new();
+        let mut current = Some(node);
+        while let Some(branch) = current {
+            let alternative = branch.child_by_field_name("alternative");
+            let condition = match branch.kind() {
+                "preproc_ifdef" => branch.child_by_field_name("name").map(|name| {
+                    let negate = branch.child(0).map(|c| c.kind() == "#ifndef").unwrap_or(false);
+                    let name = &content[name.byte_range()];
+                    format!("{}defined({})", if negate { "!" } else { "" }, name)
+                }),
+                "preproc_else" => None,
+                _ => branch
+                    .child_by_field_name("condition")
+                    .map(|condition| content[condition.byte_range()].to_string()),
+            };
+            self.branches.push(Branch {
+                start: branch.start_position(),
+                end: alternative.map(|a| a.start_position()).unwrap_or_else(|| branch.end_position()),
+                earlier: earlier.clone(),
+                condition: condition.clone(),
+            });
+            earlier.extend(condition);
+            current = alternative;
+        }
+    }
+
+    /// Whether code starting at `point` is compiled under `config`
+    pub fn is_active(&self, point: Point, config: &CppConfig) -> bool {
+        self.branches
+            .iter()
+            .filter(|branch| branch.start <= point && point < branch.end)
+            .all(|branch| {
+                branch.earlier.iter().all(|condition| evaluate(condition, &config.defines) == 0)
+                    && branch
+                        .condition
+                        .as_ref()
+                        .is_none_or(|condition| evaluate(condition, &config.defines) != 0)
+            })
+    }
+}
+
+/// Evaluate an `#if` expression. Undefined identifiers are 0, as in the
+/// preprocessor; anything unparseable counts as true so code isn't hidden.
+pub fn evaluate(expression: &str, defines: &HashMap<String, String>) -> i64 {
+    let tokens = tokenize(expression);
+    let mut parser = ExprParser { tokens: &tokens, pos: 0, defines, depth: 0 };
+    match parser.ternary() {
+        Some(value) if parser.pos == tokens.len() => value,
+        _ => 1,
+    }
+}
+
+fn tokenize(expression: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = expression.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() || c == '\\' {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            break;
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                i += 1;
+            }
+            i += 2;
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else {
+            let pair: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            if ["&&", "||", "==", "!=", "<=", ">=", "<<", ">>"].contains(&pair.as_str()) {
+                tokens.push(pair);
+                i += 2;
+            } else {
+                tokens.push(c.to_string());
+                i += 1;
+            }
+        }
+    }
+    tokens
+}
+
+struct ExprParser<'a> {
+    tokens: &'a [String],
+    pos: usize,
+    defines: &'a HashMap<String, String>,
+    /// Guards against self-referential macros
+    depth: usize,
+}
+
+/// Binary operators by precedence level, loosest first
+const BINARY_LEVELS: &[&[&str]] = &[
+    &["||"],
+    &["&&"],
+    &["|"],
+    &["^"],
+    &["&"],
+    &["==", "!="],
+    &["<", ">", "<=", ">="],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+impl ExprParser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ternary(&mut self) -> Option<i64> {
+        let condition = self.binary(0)?;
+        if self.eat("?") {
+            let then = self.ternary()?;
+            if !self.eat(":") {
+                return None;
+            }
+            let otherwise = self.ternary()?;
+            return Some(if condition != 0 { then } else { otherwise });
+        }
+        Some(condition)
+    }
+
+    fn binary(&mut self, level: usize) -> Option<i64> {
+        if level == BINARY_LEVELS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        while let Some(op) = self.peek().filter(|t| BINARY_LEVELS[level].contains(t)).map(str::to_string) {
+            self.pos += 1;
+            let right = self.binary(level + 1)?;
+            left = match op.as_str() {
+                "||" => ((left != 0) || (right != 0)) as i64,
+                "&&" => ((left != 0) && (right != 0)) as i64,
+                "|" => left | right,
+                "^" => left ^ right,
+                "&" => left & right,
+                "==" => (left == right) as i64,
+                "!=" => (left != right) as i64,
+                "<" => (left < right) as i64,
+                ">" => (left > right) as i64,
+                "<=" => (left <= right) as i64,
+                ">=" => (left >= right) as i64,
+                "<<" => left.checked_shl(right as u32).unwrap_or(0),
+                ">>" => left.checked_shr(right as u32).unwrap_or(0),
+                "+" => left.wrapping_add(right),
+                "-" => left.wrapping_sub(right),
+                "*" => left.wrapping_mul(right),
+                "/" => left.checked_div(right)?,
+                "%" => left.checked_rem(right)?,
+                _ => unreachable!(),
+            };
+        }
+        Some(left)
+    }
+
+    fn unary(&mut self) -> Option<i64> {
+        if self.eat("!") {
+            return Some((self.unary()? == 0) as i64);
+        }
+        if self.eat("-") {
+            return Some(self.unary()?.wrapping_neg());
+        }
+        if self.eat("+") {
+            return self.unary();
+        }
+        if self.eat("~") {
+            return Some(!self.unary()?);
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Option<i64> {
+        if self.eat("(") {
+            let value = self.ternary()?;
+            return self.eat(")").then_some(value);
+        }
+        let token = self.peek()?.to_string();
+        self.pos += 1;
+        if token == "defined" {
+            let parenthesized = self.eat("(");
+            let name = self.peek()?.to_string();
+            self.pos += 1;
+            if parenthesized && !self.eat(")") {
+                return None;
+            }
+            return Some(self.defines.contains_key(&name) as i64);
+        }
+        if token.starts_with(|c: char| c.is_ascii_digit()) {
+            return parse_number(&token);
+        }
+        // A macro evaluates to its replacement; function-like calls and
+        // unknown identifiers are 0
+        if self.eat("(") {
+            let mut depth = 1;
+            while depth > 0 {
+                match self.peek()? {
+                    "(" => depth += 1,
+                    ")" => depth -= 1,
+                    _ => {}
+                }
+                self.pos += 1;
+            }
+            return Some(0);
+        }
+        match self.defines.get(&token) {
+            Some(value) if value.trim().is_empty() => Some(1),
+            Some(value) if self.depth < 8 => {
+                let tokens = tokenize(value);
+                let mut nested = ExprParser { tokens: &tokens, pos: 0, defines: self.defines, depth: self.depth + 1 };
+                nested.ternary().filter(|_| nested.pos == tokens.len())
+            }
+            Some(_) => None,
+            None => Some(0),
+        }
+    }
+}
+
+fn parse_number(token: &str) -> Option<i64> {
+    let digits = token.trim_end_matches(['u', 'U', 'l', 'L']);
+    if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16).ok()
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()
+    } else {
+        digits.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_conditions() {
+        let defines: HashMap<String, String> = [("__linux__", ""), ("VERSION", "3"), ("LEVEL", "VERSION * 2")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(evaluate("defined(__linux__)", &defines), 1);
+        assert_eq!(evaluate("defined _WIN32 || !defined(__linux__)", &defines), 0);
+        assert_eq!(evaluate("VERSION >= 2 && LEVEL == 6", &defines), 1);
+        assert_eq!(evaluate("UNDEFINED_MACRO", &defines), 0);
+        assert_eq!(evaluate("0x10 > 010 ? 1 : 0", &defines), 1);
+        assert_eq!(evaluate("__has_include(<foo.h>)", &defines), 0);
+        // Can't parse it: leave the code visible
+        assert_eq!(evaluate("VERSION >", &defines), 1);
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config: CppConfig = "linux:__linux__,HAVE_EPOLL=1".parse().unwrap();
+        assert_eq!(config, CppConfig::new("linux", &[("__linux__", ""), ("HAVE_EPOLL", "1")]));
+        assert!(":FOO".parse::<CppConfig>().is_err());
+    }
+}
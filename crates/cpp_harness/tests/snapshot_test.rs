@@ -0,0 +1,38 @@
+//! Snapshots of the full IR emitted for the `testdata/cpp-basic` and
+//! `testdata/c-basic` fixtures.
+//!
+//! Review changes with `cargo insta review`, or rerun with
+//! `INSTA_UPDATE=always` to accept them.
+
+use cpp_harness::CppHarness;
+use std::path::Path;
+
+fn fixture(path: &str) -> String {
+    std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../testdata").join(path)).unwrap()
+}
+
+#[test]
+fn test_cpp_basic_snapshot() {
+    let mut harness = CppHarness::new_cpp().unwrap();
+    let content = fixture("cpp-basic/service.cpp");
+    let (symbols, edges, occurrences) = harness.parse("service.cpp", &content).unwrap();
+
+    insta::with_settings!({ sort_maps => true, snapshot_suffix => "cpp-basic" }, {
+        insta::assert_json_snapshot!("symbols", symbols);
+        insta::assert_json_snapshot!("edges", edges);
+        insta::assert_json_snapshot!("occurrences", occurrences);
+    });
+}
+
+#[test]
+fn test_c_basic_snapshot() {
+    let mut harness = CppHarness::new_c().unwrap();
+    let content = fixture("c-basic/list.c");
+    let (symbols, edges, occurrences) = harness.parse("list.c", &content).unwrap();
+
+    insta::with_settings!({ sort_maps => true, snapshot_suffix => "c-basic" }, {
+        insta::assert_json_snapshot!("symbols", symbols);
+        insta::assert_json_snapshot!("edges", edges);
+        insta::assert_json_snapshot!("occurrences", occurrences);
+    });
+}
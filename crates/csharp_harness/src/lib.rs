@@ -1,12 +1,27 @@
 //! C# Language Harness
-//! 
+//!
 //! Tree-sitter based parser for C# that extracts symbols and relationships
 //! for the Consilium Codegraph system.
 
 use anyhow::{anyhow, Result};
-use protocol::{EdgeIR, EdgeType, Language, OccurrenceIR, Resolution, Span, SymbolIR, SymbolKind, Version};
+use protocol::{
+    Capabilities, EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Parameter, Resolution, Span, SymbolFlags, SymbolIR,
+    SymbolKind, Version,
+};
 use std::collections::HashMap;
-use tree_sitter::{Parser, Tree};
+use tree_sitter::{Node, Parser};
+
+mod xml_doc;
+
+/// What this harness extracts, for `reviewbot query languages`
+pub const CAPABILITIES: Capabilities = Capabilities {
+    name: "C#",
+    extensions: &["cs"],
+    edges: &[EdgeType::Contains, EdgeType::Imports, EdgeType::Extends, EdgeType::Implements, EdgeType::AnnotatedBy],
+    occurrences: &[OccurrenceRole::Definition, OccurrenceRole::Reference, OccurrenceRole::Call],
+    docs: true,
+    resolution: Resolution::Syntactic,
+};
 
 pub struct CSharpHarness {
     parser: Parser,
@@ -25,26 +40,20 @@ impl CSharpHarness {
         let tree = self.parser.parse(source, None)
             .ok_or_else(|| anyhow!("Failed to parse C# file: {}", file_path))?;
 
-        let mut symbols = Vec::new();
+        let mut symbols = vec![self.file_symbol(file_path, source)];
         let mut edges = Vec::new();
-        let occurrences = Vec::new();
+        let mut occurrences = Vec::new();
+        let mut context = ParseContext::new();
 
-        // Simple extraction - look for basic patterns
-        self.extract_basic_symbols(&tree, source, file_path, &mut symbols, &mut edges)?;
+        self.walk_node(tree.root_node(), source, file_path, &mut symbols, &mut edges, &mut occurrences, &mut context)?;
+        classify_bases(&symbols, &mut edges);
 
         Ok((symbols, edges, occurrences))
     }
 
-    fn extract_basic_symbols(
-        &self,
-        tree: &Tree,
-        source: &str,
-        file_path: &str,
-        symbols: &mut Vec<SymbolIR>,
-        _edges: &mut Vec<EdgeIR>,
-    ) -> Result<()> {
-        // Create a simple placeholder symbol for now
-        let symbol = SymbolIR {
+    /// The file itself, as the module its declarations live in
+    fn file_symbol(&self, file_path: &str, source: &str) -> SymbolIR {
+        SymbolIR {
             id: format!("csharp_file_{}", file_path),
             lang: Language::CSharp,
             lang_version: Some(Version::DotNet6),
@@ -52,25 +61,820 @@ impl CSharpHarness {
             name: file_path.split('/').last().unwrap_or(file_path).to_string(),
             fqn: file_path.to_string(),
             signature: Some(format!("C# file: {}", file_path)),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: Span {
+            name_span: Default::default(),
+            full_span: Span {
                 start_line: 0,
                 start_col: 0,
                 end_line: source.lines().count() as u32,
                 end_col: 0,
             },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
-            sig_hash: self.calculate_hash(file_path),
+            doc_tags: None,
+            sig_hash: format!("{:x}", md5::compute(file_path)),
+        }
+    }
+
+    fn walk_node(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        match node.kind() {
+            "using_directive" => {
+                self.handle_using(node, source, file_path, edges);
+            }
+            "namespace_declaration" | "file_scoped_namespace_declaration" => {
+                self.handle_namespace(node, source, file_path, symbols, edges, occurrences, context)?;
+            }
+            "class_declaration" | "record_declaration" | "struct_declaration" | "record_struct_declaration"
+            | "interface_declaration" | "enum_declaration" => {
+                self.handle_type(node, source, file_path, symbols, edges, occurrences, context)?;
+            }
+            "method_declaration" | "constructor_declaration" | "local_function_statement" => {
+                self.handle_method(node, source, file_path, symbols, edges, occurrences, context)?;
+            }
+            "field_declaration" | "event_field_declaration" | "property_declaration" | "indexer_declaration"
+            | "event_declaration" | "enum_member_declaration" => {
+                let start = symbols.len();
+                match node.kind() {
+                    "field_declaration" | "event_field_declaration" => {
+                        self.handle_field(node, source, file_path, symbols, occurrences, context)
+                    }
+                    "property_declaration" | "indexer_declaration" | "event_declaration" => {
+                        self.handle_property(node, source, file_path, symbols, occurrences, context)?
+                    }
+                    _ => self.handle_enum_member(node, source, file_path, symbols, occurrences, context)?,
+                }
+                let owners: Vec<String> = symbols[start..].iter().map(|s| s.id.clone()).collect();
+                self.handle_attributes(node, &owners, source, file_path, edges, occurrences);
+                // Types, initializers and accessor bodies
+                for child in node.children(&mut node.walk()).filter(|c| c.kind() != "attribute_list") {
+                    self.walk_node(child, source, file_path, symbols, edges, occurrences, context)?;
+                }
+            }
+            // Lists on declarations are handled along with them; these are
+            // `[assembly: ...]` and those on parameters and accessors
+            "attribute_list" | "global_attribute_list" => {
+                let owners = match node.kind() {
+                    "global_attribute_list" => vec![format!("csharp_file_{}", file_path)],
+                    _ => Vec::new(),
+                };
+                self.handle_attribute_list(node, &owners, source, file_path, edges, occurrences);
+            }
+            "lambda_expression" => {
+                self.handle_lambda(node, source, file_path, symbols, edges, occurrences, context)?;
+            }
+            "invocation_expression" | "object_creation_expression" => {
+                self.handle_call(node, source, file_path, symbols, edges, occurrences, context)?;
+            }
+            "identifier" => {
+                if !is_declared_name(node) {
+                    self.push_occurrence(node, source, file_path, OccurrenceRole::Reference, occurrences);
+                }
+            }
+            _ => {
+                for child in node.children(&mut node.walk()) {
+                    self.walk_node(child, source, file_path, symbols, edges, occurrences, context)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `using A.B;` imports a namespace, `using static A.B.C;` a type's
+    /// static members and `using D = A.B.C;` an alias. Which of these it
+    /// is, and `global using`, are kept in the edge's metadata.
+    fn handle_using(&self, node: Node, source: &str, file_path: &str, edges: &mut Vec<EdgeIR>) {
+        let mut meta = HashMap::new();
+        let mut target = None;
+        for child in node.children(&mut node.walk()) {
+            match child.kind() {
+                // Before `using`; a `global::` prefix is part of the name
+                "global" if target.is_none() => {
+                    meta.insert("global".to_string(), serde_json::json!(true));
+                }
+                "static" => {
+                    meta.insert("static".to_string(), serde_json::json!(true));
+                }
+                "name_equals" => {
+                    if let Some(alias) = child.named_child(0) {
+                        meta.insert("alias".to_string(), serde_json::json!(self.get_text(alias, source)));
+                    }
+                }
+                "identifier" | "qualified_name" | "generic_name" | "alias_qualified_name" => {
+                    target = Some(self.get_text(child, source));
+                }
+                _ => {}
+            }
+        }
+        let Some(target) = target else {
+            return;
         };
+        edges.push(EdgeIR {
+            edge_type: EdgeType::Imports,
+            src: Some(format!("csharp_file_{}", file_path)),
+            dst: Some(target),
+            file_src: Some(file_path.to_string()),
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta,
+            provenance: HashMap::new(),
+        });
+    }
 
-        symbols.push(symbol);
+    /// `namespace A.B { ... }` and file-scoped `namespace A.B;`, whose
+    /// declarations tree-sitter nests under it all the same
+    fn handle_namespace(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        let name_node = node.child_by_field_name("name").ok_or_else(|| anyhow!("Namespace without name"))?;
+        let name = self.get_text(name_node, source);
+        let fqn = context.build_fqn(&name);
+        let symbol = self.make_symbol(SymbolKind::Namespace, node, name_node, &fqn, source, file_path);
+        self.define(symbol, symbols, occurrences);
 
+        context.namespaces.push(name);
+        for child in node.children(&mut node.walk()) {
+            if child.id() != name_node.id() {
+                self.walk_node(child, source, file_path, symbols, edges, occurrences, context)?;
+            }
+        }
+        context.namespaces.pop();
         Ok(())
     }
 
-    fn calculate_hash(&self, input: &str) -> String {
-        format!("{:x}", input.len() * 17 + input.chars().map(|c| c as usize).sum::<usize>())
+    /// Classes, records, structs, interfaces and enums, nested ones
+    /// qualified by their enclosing types
+    fn handle_type(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        let name_node = node.child_by_field_name("name").ok_or_else(|| anyhow!("{} without name", node.kind()))?;
+        let name = self.get_text(name_node, source);
+        let kind = match node.kind() {
+            "struct_declaration" | "record_struct_declaration" => SymbolKind::Struct,
+            "interface_declaration" => SymbolKind::Interface,
+            "enum_declaration" => SymbolKind::Enum,
+            _ => SymbolKind::Class,
+        };
+        let fqn = context.build_fqn(&name);
+        let mut symbol = self.make_symbol(kind.clone(), node, name_node, &fqn, source, file_path);
+        symbol.visibility = self.visibility(node, source, context);
+        // An enum's base is its underlying type
+        if kind != SymbolKind::Enum {
+            self.handle_bases(node, &symbol, source, edges);
+        }
+        self.handle_attributes(node, &[symbol.id.clone()], source, file_path, edges, occurrences);
+        self.define(symbol, symbols, occurrences);
+
+        let outer_kind = context.type_kind.replace(kind);
+        context.types.push(name);
+        // `record User(string Name)`: positional parameters are properties
+        if let Some(params) = node.child_by_field_name("parameters") {
+            for param_node in params.named_children(&mut params.walk()).filter(|p| p.kind() == "parameter") {
+                let Some(name_node) = param_node.child_by_field_name("name") else {
+                    continue;
+                };
+                let name = self.get_text(name_node, source);
+                let type_name = param_node.child_by_field_name("type").map(|t| self.get_text(t, source));
+                let fqn = context.build_fqn(&name);
+                let mut property = self.make_symbol(SymbolKind::Property, param_node, name_node, &fqn, source, file_path);
+                property.visibility = Some("public".to_string());
+                property.signature = Some(format!("{} {} {{ get; init; }}", type_name.as_deref().unwrap_or("var"), name));
+                property.return_type = type_name;
+                self.define(property, symbols, occurrences);
+            }
+        }
+        if let Some(body) = node.child_by_field_name("body") {
+            for child in body.children(&mut body.walk()) {
+                self.walk_node(child, source, file_path, symbols, edges, occurrences, context)?;
+            }
+        }
+        context.types.pop();
+        context.type_kind = outer_kind;
+        Ok(())
+    }
+
+    /// `: Base<T>, IDisposable`. The grammar can't tell a base class from
+    /// an interface, so a class's first base is taken as its superclass
+    /// unless named like an interface (`IName`); `classify_bases` corrects
+    /// this for types declared in the same file. Interfaces extend their
+    /// bases and structs only implement theirs.
+    fn handle_bases(&self, node: Node, symbol: &SymbolIR, source: &str, edges: &mut Vec<EdgeIR>) {
+        let Some(bases) = node.child_by_field_name("bases") else {
+            return;
+        };
+        for (i, base) in bases.named_children(&mut bases.walk()).enumerate() {
+            let (name, type_arguments) = match base.kind() {
+                // `record Admin(string Name) : User(Name)`
+                "primary_constructor_base_type" => match base.named_child(0) {
+                    Some(identifier) => (self.get_text(identifier, source), Vec::new()),
+                    None => continue,
+                },
+                _ => self.split_generic(base, source),
+            };
+            let edge_type = match symbol.kind {
+                SymbolKind::Interface => EdgeType::Extends,
+                SymbolKind::Struct => EdgeType::Implements,
+                _ if i == 0 && !looks_like_interface(&name) => EdgeType::Extends,
+                _ => EdgeType::Implements,
+            };
+            let mut meta = HashMap::new();
+            if !type_arguments.is_empty() {
+                meta.insert("type_arguments".to_string(), serde_json::json!(type_arguments));
+            }
+            edges.push(EdgeIR {
+                edge_type,
+                src: Some(symbol.id.clone()),
+                dst: Some(name),
+                file_src: Some(symbol.file_path.clone()),
+                file_dst: None,
+                resolution: Resolution::Syntactic,
+                meta,
+                provenance: HashMap::new(),
+            });
+        }
+    }
+
+    /// `System.Collections.Generic.IEnumerable<User>` into the name without
+    /// its type arguments and the arguments as written
+    fn split_generic(&self, type_node: Node, source: &str) -> (String, Vec<String>) {
+        let text = self.get_text(type_node, source);
+        let generic = match type_node.kind() {
+            "generic_name" => Some(type_node),
+            "qualified_name" => type_node
+                .named_child(type_node.named_child_count().saturating_sub(1))
+                .filter(|n| n.kind() == "generic_name"),
+            _ => None,
+        };
+        let Some(arguments) = generic.and_then(|g| g.named_children(&mut g.walk()).find(|c| c.kind() == "type_argument_list")) else {
+            return (text, Vec::new());
+        };
+        let name = source[type_node.start_byte()..arguments.start_byte()].trim().to_string();
+        let type_arguments = arguments.named_children(&mut arguments.walk()).map(|a| self.get_text(a, source)).collect();
+        (name, type_arguments)
+    }
+
+    /// Methods and constructors, and local functions qualified by the
+    /// method they are declared in
+    fn handle_method(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        let name_node = node.child_by_field_name("name").ok_or_else(|| anyhow!("{} without name", node.kind()))?;
+        let name = self.get_text(name_node, source);
+        let kind = match node.kind() {
+            "local_function_statement" => SymbolKind::Function,
+            _ => SymbolKind::Method,
+        };
+        let fqn = context.build_fqn(&name);
+        let mut symbol = self.make_symbol(kind, node, name_node, &fqn, source, file_path);
+        let params_node = node.child_by_field_name("parameters");
+        symbol.parameters = params_node.map(|p| self.get_parameters(p, source)).unwrap_or_default();
+        // Constructors have no return type
+        symbol.return_type = node.child_by_field_name("type").map(|t| self.get_text(t, source));
+        let params = params_node.map(|p| self.get_text(p, source)).unwrap_or_else(|| "()".to_string());
+        let prefix = if self.is_async(node, source) { "async " } else { "" };
+        symbol.signature = Some(match &symbol.return_type {
+            Some(return_type) => format!("{}{} {}{}", prefix, return_type, name, params),
+            None => format!("{}{}{}", prefix, name, params),
+        });
+        symbol.flags.set_if(SymbolFlags::ASYNC, self.is_async(node, source));
+        if symbol.kind == SymbolKind::Function {
+            self.add_contains_edge(&symbol, context, edges);
+        } else {
+            symbol.visibility = self.visibility(node, source, context);
+        }
+        self.handle_attributes(node, &[symbol.id.clone()], source, file_path, edges, occurrences);
+        self.define(symbol, symbols, occurrences);
+
+        context.members.push(name);
+        if let Some(body) = node.child_by_field_name("body") {
+            self.walk_node(body, source, file_path, symbols, edges, occurrences, context)?;
+        }
+        context.members.pop();
+        Ok(())
+    }
+
+    /// One symbol per declarator of `int a, b;`; `const` fields are
+    /// constants and `event EventHandler A, B;` declares events
+    fn handle_field(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &ParseContext,
+    ) {
+        let Some(declaration) = node.named_children(&mut node.walk()).find(|c| c.kind() == "variable_declaration") else {
+            return;
+        };
+        let kind = if node.kind() == "event_field_declaration" {
+            SymbolKind::Event
+        } else if self.modifiers(node, source).iter().any(|m| m == "const") {
+            SymbolKind::Constant
+        } else {
+            SymbolKind::Field
+        };
+        let prefix = if kind == SymbolKind::Event { "event " } else { "" };
+        let type_name = declaration.child_by_field_name("type").map(|t| self.get_text(t, source));
+        for declarator in declaration.named_children(&mut declaration.walk()).filter(|c| c.kind() == "variable_declarator") {
+            let Some(name_node) = declarator.named_children(&mut declarator.walk()).find(|c| c.kind() == "identifier") else {
+                continue;
+            };
+            let name = self.get_text(name_node, source);
+            let mut symbol = self.make_symbol(kind.clone(), node, name_node, &context.build_fqn(&name), source, file_path);
+            symbol.visibility = self.visibility(node, source, context);
+            symbol.signature = type_name.as_ref().map(|t| format!("{}{} {}", prefix, t, name));
+            symbol.return_type = type_name.clone();
+            self.define(symbol, symbols, occurrences);
+        }
+    }
+
+    /// Properties, indexers (named `this`) and events with `add`/`remove`
+    /// accessors. The accessors, with their own visibility, are kept in the
+    /// signature: `int Count { get; private set; }`, `string this[int i] { get; }`.
+    fn handle_property(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &ParseContext,
+    ) -> Result<()> {
+        let name_node = match node.kind() {
+            "indexer_declaration" => node.children(&mut node.walk()).find(|c| c.kind() == "this"),
+            _ => node.child_by_field_name("name"),
+        }
+        .ok_or_else(|| anyhow!("{} without name", node.kind()))?;
+        let name = self.get_text(name_node, source);
+        let kind = match node.kind() {
+            "event_declaration" => SymbolKind::Event,
+            _ => SymbolKind::Property,
+        };
+        let mut symbol = self.make_symbol(kind, node, name_node, &context.build_fqn(&name), source, file_path);
+        symbol.visibility = self.visibility(node, source, context);
+        symbol.return_type = node.child_by_field_name("type").map(|t| self.get_text(t, source));
+        let params_node = node.child_by_field_name("parameters");
+        symbol.parameters = params_node.map(|p| self.get_parameters(p, source)).unwrap_or_default();
+        let accessors = match node.child_by_field_name("accessors") {
+            Some(list) => {
+                let names: Vec<String> = list
+                    .named_children(&mut list.walk())
+                    .map(|accessor| {
+                        // `private set`, without any body
+                        let words: Vec<String> = accessor
+                            .children(&mut accessor.walk())
+                            .filter(|c| matches!(c.kind(), "modifier" | "get" | "set" | "init" | "add" | "remove"))
+                            .map(|c| self.get_text(c, source))
+                            .collect();
+                        format!("{};", words.join(" "))
+                    })
+                    .collect();
+                format!(" {{ {} }}", names.join(" "))
+            }
+            // `int Total => a + b;`
+            None => " { get; }".to_string(),
+        };
+        let prefix = if symbol.kind == SymbolKind::Event { "event " } else { "" };
+        let params = params_node.map(|p| self.get_text(p, source)).unwrap_or_default();
+        symbol.signature = Some(format!("{}{} {}{}{}", prefix, symbol.return_type.as_deref().unwrap_or("var"), name, params, accessors));
+        self.define(symbol, symbols, occurrences);
+        Ok(())
+    }
+
+    fn handle_enum_member(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &ParseContext,
+    ) -> Result<()> {
+        let name_node = node.child_by_field_name("name").ok_or_else(|| anyhow!("Enum member without name"))?;
+        let name = self.get_text(name_node, source);
+        let mut symbol = self.make_symbol(SymbolKind::EnumMember, node, name_node, &context.build_fqn(&name), source, file_path);
+        symbol.visibility = Some("public".to_string());
+        symbol.signature = Some(self.get_text(node, source));
+        self.define(symbol, symbols, occurrences);
+        Ok(())
+    }
+
+    /// Lambdas as anonymous functions named after where they start,
+    /// contained by the method, local function or lambda around them
+    fn handle_lambda(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        let position = node.start_position();
+        let name = format!("lambda_{}_{}", position.row, position.column);
+        let fqn = context.build_fqn(&name);
+
+        // `(a, b) => ...` or the bare `x => ...`
+        let (params, parameters) = match node.child_by_field_name("parameters") {
+            Some(list) => (
+                list.named_children(&mut list.walk())
+                    .filter(|p| p.kind() == "parameter")
+                    .map(|p| self.get_text(p, source))
+                    .collect::<Vec<_>>(),
+                self.get_parameters(list, source),
+            ),
+            None => node
+                .named_children(&mut node.walk())
+                .filter(|c| c.kind() == "identifier")
+                .take(1)
+                .map(|c| {
+                    let name = self.get_text(c, source);
+                    (name.clone(), Parameter { name, type_name: None, default: None, variadic: false })
+                })
+                .unzip(),
+        };
+        let is_async = self.is_async(node, source);
+
+        let mut symbol = self.make_symbol(SymbolKind::Function, node, node, &fqn, source, file_path);
+        symbol.name = name.clone();
+        symbol.parameters = parameters;
+        symbol.signature = Some(format!("{}({}) => {{...}}", if is_async { "async " } else { "" }, params.join(", ")));
+        symbol.flags.set_if(SymbolFlags::ASYNC, is_async);
+        self.add_contains_edge(&symbol, context, edges);
+        self.handle_attributes(node, &[symbol.id.clone()], source, file_path, edges, occurrences);
+        self.define(symbol, symbols, occurrences);
+
+        context.members.push(name);
+        if let Some(body) = node.child_by_field_name("body") {
+            self.walk_node(body, source, file_path, symbols, edges, occurrences, context)?;
+        }
+        context.members.pop();
+        Ok(())
+    }
+
+    /// The attribute lists of a declaration, applied to every symbol it
+    /// introduced (each declarator of `int a, b;`)
+    fn handle_attributes(
+        &self,
+        node: Node,
+        owners: &[String],
+        source: &str,
+        file_path: &str,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+    ) {
+        for list in node.children(&mut node.walk()).filter(|c| c.kind() == "attribute_list") {
+            self.handle_attribute_list(list, owners, source, file_path, edges, occurrences);
+        }
+    }
+
+    /// `[HttpGet("{id}"), Authorize]`: a Reference occurrence on each
+    /// attribute name and an `AnnotatedBy` edge to it, by the name as
+    /// written, from each owner. Arguments and an explicit target
+    /// (`return:`, `assembly:`) go in the edge's metadata.
+    fn handle_attribute_list(
+        &self,
+        list: Node,
+        owners: &[String],
+        source: &str,
+        file_path: &str,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+    ) {
+        let target = match list.kind() {
+            "global_attribute_list" => list.child(1).map(|t| self.get_text(t, source)),
+            _ => list
+                .named_children(&mut list.walk())
+                .find(|c| c.kind() == "attribute_target_specifier")
+                .map(|t| self.get_text(t, source).trim_end_matches(':').trim().to_string()),
+        };
+        for attribute in list.named_children(&mut list.walk()).filter(|c| c.kind() == "attribute") {
+            let Some(name_node) = attribute.child_by_field_name("name") else {
+                continue;
+            };
+            self.push_occurrence(name_node, source, file_path, OccurrenceRole::Reference, occurrences);
+
+            let mut meta = HashMap::new();
+            if let Some(arguments) = attribute.named_children(&mut attribute.walk()).find(|c| c.kind() == "attribute_argument_list") {
+                let arguments: Vec<String> = arguments
+                    .named_children(&mut arguments.walk())
+                    .map(|argument| self.get_text(argument, source))
+                    .collect();
+                meta.insert("arguments".to_string(), serde_json::json!(arguments));
+            }
+            if let Some(target) = &target {
+                meta.insert("target".to_string(), serde_json::json!(target));
+            }
+            for owner in owners {
+                edges.push(EdgeIR {
+                    edge_type: EdgeType::AnnotatedBy,
+                    src: Some(owner.clone()),
+                    dst: Some(self.get_text(name_node, source)),
+                    file_src: Some(file_path.to_string()),
+                    file_dst: None,
+                    resolution: Resolution::Syntactic,
+                    meta: meta.clone(),
+                    provenance: HashMap::new(),
+                });
+            }
+        }
+    }
+
+    /// `Run(id)`, `repo.Load<T>(id)` and `new Service(repo)`: a Call
+    /// occurrence on the method or type name, then the receiver and
+    /// arguments are walked for their own references
+    fn handle_call(
+        &self,
+        node: Node,
+        source: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        let target = if node.kind() == "object_creation_expression" {
+            node.child_by_field_name("type")
+        } else {
+            node.child_by_field_name("function")
+        };
+        let (name, rest) = match target {
+            Some(target) if target.kind() == "member_access_expression" => {
+                (target.child_by_field_name("name"), target.child_by_field_name("expression"))
+            }
+            Some(target) if matches!(target.kind(), "identifier" | "generic_name" | "qualified_name") => (Some(target), None),
+            // A delegate returned by another expression
+            other => (None, other),
+        };
+        if let Some(name) = name {
+            // `Load<T>` is called as `Load`
+            let (text, _) = self.split_generic(name, source);
+            let token = text.rsplit('.').next().unwrap_or(&text).to_string();
+            occurrences.push(OccurrenceIR {
+                file_path: file_path.to_string(),
+                symbol_id: None,
+                role: OccurrenceRole::Call,
+                span: self.node_to_span(name),
+                token,
+            });
+        }
+        for child in node.children(&mut node.walk()) {
+            if Some(child) == target {
+                if let Some(rest) = rest {
+                    self.walk_node(rest, source, file_path, symbols, edges, occurrences, context)?;
+                }
+            } else {
+                self.walk_node(child, source, file_path, symbols, edges, occurrences, context)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn push_occurrence(&self, node: Node, source: &str, file_path: &str, role: OccurrenceRole, occurrences: &mut Vec<OccurrenceIR>) {
+        occurrences.push(OccurrenceIR {
+            file_path: file_path.to_string(),
+            symbol_id: None,
+            role,
+            span: self.node_to_span(node),
+            token: self.get_text(node, source),
+        });
+    }
+
+    /// `Contains` from the enclosing method, local function or lambda, if any
+    fn add_contains_edge(&self, symbol: &SymbolIR, context: &ParseContext, edges: &mut Vec<EdgeIR>) {
+        let Some(enclosing) = context.enclosing_member() else {
+            return;
+        };
+        edges.push(EdgeIR {
+            edge_type: EdgeType::Contains,
+            src: Some(format!("{}#{}", symbol.file_path, enclosing)),
+            dst: Some(symbol.id.clone()),
+            file_src: Some(symbol.file_path.clone()),
+            file_dst: Some(symbol.file_path.clone()),
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        });
+    }
+
+    /// Record a symbol along with the occurrence of its name
+    fn define(&self, mut symbol: SymbolIR, symbols: &mut Vec<SymbolIR>, occurrences: &mut Vec<OccurrenceIR>) {
+        symbol.sig_hash = signature_hash(&symbol);
+        occurrences.push(OccurrenceIR {
+            file_path: symbol.file_path.clone(),
+            symbol_id: Some(symbol.id.clone()),
+            role: OccurrenceRole::Definition,
+            span: symbol.name_span.clone(),
+            token: symbol.name.clone(),
+        });
+        symbols.push(symbol);
+    }
+
+    fn modifiers(&self, node: Node, source: &str) -> Vec<String> {
+        node.children(&mut node.walk())
+            .filter(|c| c.kind() == "modifier")
+            .map(|c| self.get_text(c, source))
+            .collect()
+    }
+
+    /// `async` methods, local functions and lambdas; a lambda's
+    /// `async static` comes as a single modifier
+    fn is_async(&self, node: Node, source: &str) -> bool {
+        self.modifiers(node, source).iter().any(|m| m.split_whitespace().any(|word| word == "async"))
+    }
+
+    /// Declared accessibility, or the default for where the declaration
+    /// sits: `internal` for top-level types, `public` in interfaces,
+    /// `private` for other members
+    fn visibility(&self, node: Node, source: &str, context: &ParseContext) -> Option<String> {
+        let modifiers = self.modifiers(node, source);
+        let has = |m: &str| modifiers.iter().any(|modifier| modifier == m);
+        let visibility = if has("protected") && has("internal") {
+            "protected internal"
+        } else if has("private") && has("protected") {
+            "private protected"
+        } else if let Some(m) = ["public", "protected", "internal", "private"].into_iter().find(|m| has(m)) {
+            m
+        } else if context.type_kind == Some(SymbolKind::Interface) {
+            "public"
+        } else if context.types.is_empty() {
+            "internal"
+        } else {
+            "private"
+        };
+        Some(visibility.to_string())
+    }
+
+    /// `(int a, string b = "x", params object[] rest)`
+    fn get_parameters(&self, list: Node, source: &str) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        let mut cursor = list.walk();
+        let children: Vec<Node> = list.named_children(&mut cursor).collect();
+        for (i, param) in children.iter().enumerate() {
+            match param.kind() {
+                "parameter" => parameters.push(Parameter {
+                    name: param.child_by_field_name("name").map(|n| self.get_text(n, source)).unwrap_or_default(),
+                    type_name: param.child_by_field_name("type").map(|t| self.get_text(t, source)),
+                    default: param
+                        .named_children(&mut param.walk())
+                        .find(|c| c.kind() == "equals_value_clause")
+                        .map(|d| self.get_text(d, source).trim_start_matches('=').trim().to_string()),
+                    variadic: false,
+                }),
+                // `params T[] name` is a type followed by the list's own name
+                "array_type" | "nullable_type" => parameters.push(Parameter {
+                    name: children
+                        .get(i + 1)
+                        .filter(|n| n.kind() == "identifier")
+                        .map(|n| self.get_text(*n, source))
+                        .unwrap_or_default(),
+                    type_name: Some(self.get_text(*param, source)),
+                    default: None,
+                    variadic: true,
+                }),
+                _ => {}
+            }
+        }
+        parameters
+    }
+
+    fn make_symbol(&self, kind: SymbolKind, node: Node, name_node: Node, fqn: &str, source: &str, file_path: &str) -> SymbolIR {
+        SymbolIR {
+            id: format!("{}#{}", file_path, fqn),
+            lang: Language::CSharp,
+            lang_version: Some(Version::DotNet6),
+            kind,
+            name: self.get_text(name_node, source),
+            fqn: fqn.to_string(),
+            signature: None,
+            parameters: Vec::new(),
+            return_type: None,
+            file_path: file_path.to_string(),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
+            visibility: None,
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
+            doc: self.doc_comment(node, source),
+            // Set once the signature is known, in `define`
+            doc_tags: None,
+            sig_hash: String::new(),
+        }
+    }
+
+    /// The `///` lines directly above a declaration, as plain text
+    fn doc_comment(&self, node: Node, source: &str) -> Option<String> {
+        let mut lines = Vec::new();
+        let mut current = node.prev_sibling();
+        while let Some(comment) = current.filter(|c| c.kind() == "comment") {
+            let Some(line) = self.get_text(comment, source).strip_prefix("///").map(str::to_string) else {
+                break;
+            };
+            lines.push(line);
+            current = comment.prev_sibling();
+        }
+        lines.reverse();
+        Some(xml_doc::to_text(&lines.join("\n"))).filter(|doc| !doc.is_empty())
+    }
+
+    fn get_text(&self, node: Node, source: &str) -> String {
+        source[node.byte_range()].to_string()
+    }
+
+    fn node_to_span(&self, node: Node) -> Span {
+        let start = node.start_position();
+        let end = node.end_position();
+        Span {
+            start_line: start.row as u32,
+            start_col: start.column as u32,
+            end_line: end.row as u32,
+            end_col: end.column as u32,
+        }
+    }
+}
+
+/// Hash of a symbol's FQN and signature with whitespace collapsed, so
+/// overloads hash apart and reformatting doesn't change the hash
+fn signature_hash(symbol: &SymbolIR) -> String {
+    let signature = symbol.signature.as_deref().unwrap_or_default().split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("{:x}", md5::compute(format!("{}{}", symbol.fqn, signature)))
+}
+
+/// Whether an identifier names what is being declared (a local, a
+/// parameter, a pattern variable, a named argument) rather than using it
+fn is_declared_name(node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    match parent.kind() {
+        "variable_declarator" | "name_colon" | "name_equals" => true,
+        // `a.Name` uses `Name`
+        "member_access_expression" | "member_binding_expression" => false,
+        "for_each_statement" if parent.child_by_field_name("left") == Some(node) => true,
+        _ => parent.child_by_field_name("name") == Some(node),
+    }
+}
+
+/// `IDisposable`, `System.IComparable`: the .NET naming convention for interfaces
+fn looks_like_interface(name: &str) -> bool {
+    let simple = name.rsplit('.').next().unwrap_or(name);
+    let mut chars = simple.chars();
+    chars.next() == Some('I') && chars.next().is_some_and(|c| c.is_ascii_uppercase())
+}
+
+/// Fix up the base class or interface guess of classes whose bases are
+/// declared in the same file
+fn classify_bases(symbols: &[SymbolIR], edges: &mut [EdgeIR]) {
+    let kind_of = |name: &str| {
+        let simple = name.rsplit('.').next().unwrap_or(name);
+        symbols
+            .iter()
+            .find(|s| s.name == simple && matches!(s.kind, SymbolKind::Class | SymbolKind::Interface))
+            .map(|s| &s.kind)
+    };
+    for edge in edges.iter_mut().filter(|e| matches!(e.edge_type, EdgeType::Extends | EdgeType::Implements)) {
+        let from_class = symbols.iter().any(|s| Some(&s.id) == edge.src.as_ref() && s.kind == SymbolKind::Class);
+        match (from_class, edge.dst.as_deref().and_then(kind_of)) {
+            (true, Some(SymbolKind::Interface)) => edge.edge_type = EdgeType::Implements,
+            (true, Some(SymbolKind::Class)) => edge.edge_type = EdgeType::Extends,
+            _ => {}
+        }
     }
 }
 
@@ -80,22 +884,66 @@ impl Default for CSharpHarness {
     }
 }
 
+/// Declarations enclosing the node being walked, outermost first
+struct ParseContext {
+    /// Namespace names as written, possibly dotted (`App.Services`)
+    namespaces: Vec<String>,
+    types: Vec<String>,
+    /// Kind of the innermost type, which decides default accessibility
+    type_kind: Option<SymbolKind>,
+    /// Methods and local functions
+    members: Vec<String>,
+}
+
+impl ParseContext {
+    fn new() -> Self {
+        Self {
+            namespaces: Vec::new(),
+            types: Vec::new(),
+            type_kind: None,
+            members: Vec::new(),
+        }
+    }
+
+    fn scope(&self) -> Vec<&str> {
+        let mut parts: Vec<&str> = Vec::new();
+        parts.extend(self.namespaces.iter().map(String::as_str));
+        parts.extend(self.types.iter().map(String::as_str));
+        parts.extend(self.members.iter().map(String::as_str));
+        parts
+    }
+
+    fn build_fqn(&self, name: &str) -> String {
+        let mut parts = self.scope();
+        parts.push(name);
+        parts.join(".")
+    }
+
+    /// FQN of the innermost method, local function or lambda
+    fn enclosing_member(&self) -> Option<String> {
+        (!self.members.is_empty()).then(|| self.scope().join("."))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// An import edge as `(src, dst, sorted meta)`
+    type Import<'a> = (&'a str, &'a str, Vec<(String, serde_json::Value)>);
+
     #[test]
     fn test_basic_parsing() -> Result<()> {
         let mut harness = CSharpHarness::new()?;
-        
+
         let source = r#"
 using System;
 
-namespace MyNamespace 
+namespace MyNamespace
 {
-    public class Calculator 
+    public class Calculator
     {
-        public int Add(int a, int b) 
+        public int Add(int a, int b)
         {
             return a + b;
         }
@@ -104,11 +952,520 @@ namespace MyNamespace
 "#;
 
         let (symbols, _edges, _) = harness.parse_file("Calculator.cs", source)?;
-        
+
         // Should find at least one symbol
         assert!(symbols.len() >= 1);
         assert_eq!(symbols[0].lang, Language::CSharp);
-        
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_nested_fqns() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let source = r#"
+namespace Company.Product
+{
+    namespace Core
+    {
+        public struct Point { }
+
+        public class Outer
+        {
+            public class Inner
+            {
+                public void Run()
+                {
+                    int Twice(int x) => x * 2;
+                }
+            }
+
+            private enum Mode { On, Off }
+        }
+    }
+
+    public interface IService { void Start(); }
+}
+"#;
+        let (symbols, _, _) = harness.parse_file("src/Core.cs", source)?;
+        let fqns: Vec<(SymbolKind, &str)> = symbols[1..].iter().map(|s| (s.kind.clone(), s.fqn.as_str())).collect();
+        assert_eq!(
+            fqns,
+            [
+                (SymbolKind::Namespace, "Company.Product"),
+                (SymbolKind::Namespace, "Company.Product.Core"),
+                (SymbolKind::Struct, "Company.Product.Core.Point"),
+                (SymbolKind::Class, "Company.Product.Core.Outer"),
+                (SymbolKind::Class, "Company.Product.Core.Outer.Inner"),
+                (SymbolKind::Method, "Company.Product.Core.Outer.Inner.Run"),
+                (SymbolKind::Function, "Company.Product.Core.Outer.Inner.Run.Twice"),
+                (SymbolKind::Enum, "Company.Product.Core.Outer.Mode"),
+                (SymbolKind::EnumMember, "Company.Product.Core.Outer.Mode.On"),
+                (SymbolKind::EnumMember, "Company.Product.Core.Outer.Mode.Off"),
+                (SymbolKind::Interface, "Company.Product.IService"),
+                (SymbolKind::Method, "Company.Product.IService.Start"),
+            ]
+        );
+        assert_eq!(symbols[5].id, "src/Core.cs#Company.Product.Core.Outer.Inner");
+        assert_eq!(symbols[5].name, "Inner");
+
+        let (symbols, _, _) = harness.parse_file("Models.cs", "namespace App.Models;\n\npublic record User(string Name);\n")?;
+        let fqns: Vec<&str> = symbols[1..].iter().map(|s| s.fqn.as_str()).collect();
+        assert_eq!(fqns, ["App.Models", "App.Models.User", "App.Models.User.Name"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_functions_and_lambdas() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let source = r#"namespace App
+{
+    class Jobs
+    {
+        void Schedule(List<int> ids)
+        {
+            ids.ForEach(id => Run(id));
+            void Log(string message)
+            {
+                Action<int, int> both = (int a, int b) => { };
+            }
+        }
+    }
+}
+"#;
+        let (symbols, edges, _) = harness.parse_file("Jobs.cs", source)?;
+        let functions: Vec<(&str, Option<&str>)> = symbols
+            .iter()
+            .filter(|s| s.kind == SymbolKind::Function)
+            .map(|s| (s.fqn.as_str(), s.signature.as_deref()))
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                ("App.Jobs.Schedule.lambda_6_24", Some("(id) => {...}")),
+                ("App.Jobs.Schedule.Log", Some("void Log(string message)")),
+                ("App.Jobs.Schedule.Log.lambda_9_40", Some("(int a, int b) => {...}")),
+            ]
+        );
+
+        let contains: Vec<(&str, &str)> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Contains)
+            .map(|e| (e.src.as_deref().unwrap(), e.dst.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            contains,
+            [
+                ("Jobs.cs#App.Jobs.Schedule", "Jobs.cs#App.Jobs.Schedule.lambda_6_24"),
+                ("Jobs.cs#App.Jobs.Schedule", "Jobs.cs#App.Jobs.Schedule.Log"),
+                ("Jobs.cs#App.Jobs.Schedule.Log", "Jobs.cs#App.Jobs.Schedule.Log.lambda_9_40"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_signature_hashes() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let hashes = |harness: &mut CSharpHarness, source: &str| -> Result<Vec<String>> {
+            let (symbols, _, _) = harness.parse_file("Calc.cs", source)?;
+            Ok(symbols.into_iter().filter(|s| s.name == "Add").map(|s| s.sig_hash).collect())
+        };
+        let original = hashes(&mut harness, "class Calc { int Add(int a, int b) { return a + b; } long Add(long a) => a; }")?;
+        assert_eq!(original.len(), 2);
+        assert_ne!(original[0], original[1]);
+        assert_eq!(original[0].len(), 32);
+
+        // Formatting and bodies don't matter, signatures do
+        let reformatted = hashes(&mut harness, "class Calc {\n    int Add(int a,\n            int b) { return b + a; }\n    long Add(long a) => a;\n}")?;
+        assert_eq!(reformatted, original);
+        let changed = hashes(&mut harness, "class Calc { int Add(int a, int c) { return a + c; } long Add(long a) => a; }")?;
+        assert_ne!(changed[0], original[0]);
+        assert_eq!(changed[1], original[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_async_methods_and_lambdas() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let source = r#"class Loader
+{
+    public async Task<string> Load(int id)
+    {
+        async Task<string> Fetch(string url) => await client.GetStringAsync(url);
+        Func<int, Task> save = async (int key) => await Save(key);
+        items.Select(x => x * 2);
+        return await Fetch("/items/" + id);
+    }
+}
+"#;
+        let (symbols, _, _) = harness.parse_file("Loader.cs", source)?;
+        let callables: Vec<(&str, Option<&str>, bool)> = symbols
+            .iter()
+            .filter(|s| matches!(s.kind, SymbolKind::Method | SymbolKind::Function))
+            .map(|s| (s.name.as_str(), s.signature.as_deref(), s.flags.contains(SymbolFlags::ASYNC)))
+            .collect();
+        assert_eq!(
+            callables,
+            [
+                ("Load", Some("async Task<string> Load(int id)"), true),
+                ("Fetch", Some("async Task<string> Fetch(string url)"), true),
+                ("lambda_5_31", Some("async (int key) => {...}"), true),
+                ("lambda_6_21", Some("(x) => {...}"), false),
+            ]
+        );
+
+        let lambda = symbols.iter().find(|s| s.name == "lambda_5_31").unwrap();
+        assert_eq!(lambda.parameters[0].name, "key");
+        assert_eq!(lambda.parameters[0].type_name.as_deref(), Some("int"));
+        let bare = symbols.iter().find(|s| s.name == "lambda_6_21").unwrap();
+        assert_eq!(bare.parameters[0].name, "x");
+        assert_eq!(bare.parameters[0].type_name, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_members_visibility_and_parameters() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let source = r#"namespace App
+{
+    class Account
+    {
+        private const int Limit = 10;
+        protected internal decimal balance, overdraft;
+        public Account(string owner) { }
+        public string Owner { get; private set; }
+        public decimal Total => balance + overdraft;
+        internal bool Withdraw(decimal amount, string note = "", params string[] tags) => true;
+    }
+
+    public interface IAudited { void Audit(); }
+
+    public record User(string Name, int Age);
+}
+"#;
+        let (symbols, _, occurrences) = harness.parse_file("Account.cs", source)?;
+        let summary: Vec<(&str, SymbolKind, Option<&str>, Option<&str>)> = symbols[1..]
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind.clone(), s.visibility.as_deref(), s.signature.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("App", SymbolKind::Namespace, None, None),
+                ("Account", SymbolKind::Class, Some("internal"), None),
+                ("Limit", SymbolKind::Constant, Some("private"), Some("int Limit")),
+                ("balance", SymbolKind::Field, Some("protected internal"), Some("decimal balance")),
+                ("overdraft", SymbolKind::Field, Some("protected internal"), Some("decimal overdraft")),
+                ("Account", SymbolKind::Method, Some("public"), Some("Account(string owner)")),
+                ("Owner", SymbolKind::Property, Some("public"), Some("string Owner { get; private set; }")),
+                ("Total", SymbolKind::Property, Some("public"), Some("decimal Total { get; }")),
+                (
+                    "Withdraw",
+                    SymbolKind::Method,
+                    Some("internal"),
+                    Some("bool Withdraw(decimal amount, string note = \"\", params string[] tags)")
+                ),
+                ("IAudited", SymbolKind::Interface, Some("public"), None),
+                ("Audit", SymbolKind::Method, Some("public"), Some("void Audit()")),
+                ("User", SymbolKind::Class, Some("public"), None),
+                ("Name", SymbolKind::Property, Some("public"), Some("string Name { get; init; }")),
+                ("Age", SymbolKind::Property, Some("public"), Some("int Age { get; init; }")),
+            ]
+        );
+
+        let withdraw = symbols.iter().find(|s| s.name == "Withdraw").unwrap();
+        assert_eq!(withdraw.fqn, "App.Account.Withdraw");
+        assert_eq!(withdraw.return_type.as_deref(), Some("bool"));
+        let params: Vec<(&str, Option<&str>, Option<&str>, bool)> = withdraw
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.type_name.as_deref(), p.default.as_deref(), p.variadic))
+            .collect();
+        assert_eq!(
+            params,
+            [
+                ("amount", Some("decimal"), None, false),
+                ("note", Some("string"), Some("\"\""), false),
+                ("tags", Some("string[]"), None, true),
+            ]
+        );
+
+        // Every declaration but the file has a definition occurrence on its name
+        let definitions: Vec<&OccurrenceIR> =
+            occurrences.iter().filter(|o| o.role == OccurrenceRole::Definition).collect();
+        assert_eq!(definitions.len(), symbols.len() - 1);
+        let limit = occurrences.iter().find(|o| o.token == "Limit").unwrap();
+        assert_eq!((limit.span.start_line, limit.span.start_col), (4, 26));
+        Ok(())
+    }
+
+    #[test]
+    fn test_base_list_edges() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let source = r#"namespace App
+{
+    public class Repository<T> : BaseRepository<T>, IDisposable, System.Collections.Generic.IEnumerable<T> { }
+    public class Cache : Store { }
+    public class Store : IStore { }
+    public class Logger : Sink, IStore { }
+    interface Sink { }
+    public interface IStore : IDisposable { }
+    public struct Point : IEquatable<Point> { }
+    public record Admin(string Name) : User(Name);
+    public enum Level : byte { Low }
+}
+"#;
+        let (_, edges, _) = harness.parse_file("Repo.cs", source)?;
+        let bases: Vec<(&str, &str, &str, Option<&serde_json::Value>)> = edges
+            .iter()
+            .filter(|e| matches!(e.edge_type, EdgeType::Extends | EdgeType::Implements))
+            .map(|e| {
+                let kind = if e.edge_type == EdgeType::Extends { "extends" } else { "implements" };
+                let src = e.src.as_deref().unwrap().trim_start_matches("Repo.cs#App.");
+                (src, kind, e.dst.as_deref().unwrap(), e.meta.get("type_arguments"))
+            })
+            .collect();
+        let args = serde_json::json!(["T"]);
+        let point = serde_json::json!(["Point"]);
+        assert_eq!(
+            bases,
+            [
+                ("Repository", "extends", "BaseRepository", Some(&args)),
+                ("Repository", "implements", "IDisposable", None),
+                ("Repository", "implements", "System.Collections.Generic.IEnumerable", Some(&args)),
+                ("Cache", "extends", "Store", None),
+                ("Store", "implements", "IStore", None),
+                // `Sink` is declared as an interface below
+                ("Logger", "implements", "Sink", None),
+                ("Logger", "implements", "IStore", None),
+                ("IStore", "extends", "IDisposable", None),
+                ("Point", "implements", "IEquatable", Some(&point)),
+                ("Admin", "extends", "User", None),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_attribute_edges() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let source = r#"[assembly: InternalsVisibleTo("Shop.Tests")]
+namespace Shop
+{
+    [ApiController, Route("api/[controller]")]
+    public class OrdersController
+    {
+        [JsonIgnore] private int a, b;
+
+        [HttpGet("{id}")]
+        [return: NotNull]
+        public Order Get([FromRoute] int id) => null;
+
+        [System.Obsolete("Use Get", true)]
+        public string Legacy { get; set; }
+    }
+}
+"#;
+        let (_, edges, occurrences) = harness.parse_file("OrdersController.cs", source)?;
+        let annotated: Vec<(&str, &str)> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::AnnotatedBy)
+            .map(|e| (e.src.as_deref().unwrap(), e.dst.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            annotated,
+            [
+                ("csharp_file_OrdersController.cs", "InternalsVisibleTo"),
+                ("OrdersController.cs#Shop.OrdersController", "ApiController"),
+                ("OrdersController.cs#Shop.OrdersController", "Route"),
+                ("OrdersController.cs#Shop.OrdersController.a", "JsonIgnore"),
+                ("OrdersController.cs#Shop.OrdersController.b", "JsonIgnore"),
+                ("OrdersController.cs#Shop.OrdersController.Get", "HttpGet"),
+                ("OrdersController.cs#Shop.OrdersController.Get", "NotNull"),
+                ("OrdersController.cs#Shop.OrdersController.Legacy", "System.Obsolete"),
+            ]
+        );
+
+        let meta = |dst: &str| &edges.iter().find(|e| e.dst.as_deref() == Some(dst)).unwrap().meta;
+        assert_eq!(meta("InternalsVisibleTo")["target"], "assembly");
+        assert_eq!(meta("Route")["arguments"], serde_json::json!(["\"api/[controller]\""]));
+        assert_eq!(meta("NotNull")["target"], "return");
+        assert_eq!(meta("System.Obsolete")["arguments"], serde_json::json!(["\"Use Get\"", "true"]));
+        assert!(!meta("ApiController").contains_key("arguments"));
+
+        // One occurrence per attribute, however many symbols it applies to
+        let references: Vec<&str> = occurrences
+            .iter()
+            .filter(|o| o.role == OccurrenceRole::Reference)
+            .map(|o| o.token.as_str())
+            .collect();
+        for attribute in ["InternalsVisibleTo", "ApiController", "Route", "JsonIgnore", "HttpGet", "NotNull", "System.Obsolete"] {
+            assert_eq!(references.iter().filter(|t| **t == attribute).count(), 1, "{}", attribute);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_xml_doc_comments() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let source = r#"namespace Shop
+{
+    /// <summary>Keeps track of orders.</summary>
+    public class Orders
+    {
+        // Not documentation
+        private int count;
+
+        /// <summary>
+        /// Looks up an order by <paramref name="id"/>.
+        /// </summary>
+        /// <param name="id">Order number.</param>
+        /// <returns>The <see cref="T:Shop.Order"/>, if any.</returns>
+        [HttpGet]
+        public Order Find(int id) => null;
+    }
+}
+"#;
+        let (symbols, _, _) = harness.parse_file("Orders.cs", source)?;
+        let doc = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().doc.clone();
+        assert_eq!(doc("Orders").as_deref(), Some("Keeps track of orders."));
+        assert_eq!(doc("count"), None);
+        assert_eq!(
+            doc("Find").as_deref(),
+            Some("Looks up an order by id.\nid: Order number.\nReturns: The Shop.Order, if any.")
+        );
+        assert_eq!(doc("Shop"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_events_and_indexers() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let source = r#"namespace Shop
+{
+    public class Cart
+    {
+        private readonly List<Item> items = new();
+        public event EventHandler Changed, Cleared;
+        protected event EventHandler<Item> Added
+        {
+            add { handlers.Add(value); }
+            remove { handlers.Remove(value); }
+        }
+        public Item this[int index]
+        {
+            get => items[index];
+            internal set { items[index] = value; Validate(value); }
+        }
+        public Item this[string sku] => items.Find(i => i.Sku == sku);
+    }
+}
+"#;
+        let (symbols, _, occurrences) = harness.parse_file("Cart.cs", source)?;
+        let find = |fqn: &str| symbols.iter().find(|s| s.fqn == fqn).unwrap_or_else(|| panic!("{} not found", fqn));
+
+        for (fqn, kind, visibility, signature) in [
+            ("Shop.Cart.Changed", SymbolKind::Event, "public", "event EventHandler Changed"),
+            ("Shop.Cart.Cleared", SymbolKind::Event, "public", "event EventHandler Cleared"),
+            ("Shop.Cart.Added", SymbolKind::Event, "protected", "event EventHandler<Item> Added { add; remove; }"),
+            ("Shop.Cart.this", SymbolKind::Property, "public", "Item this[int index] { get; internal set; }"),
+        ] {
+            let symbol = find(fqn);
+            assert_eq!(symbol.kind, kind, "{}", fqn);
+            assert_eq!(symbol.visibility.as_deref(), Some(visibility), "{}", fqn);
+            assert_eq!(symbol.signature.as_deref(), Some(signature), "{}", fqn);
+        }
+        assert_eq!(find("Shop.Cart.Added").return_type.as_deref(), Some("EventHandler<Item>"));
+        let indexers: Vec<_> = symbols.iter().filter(|s| s.name == "this").collect();
+        assert_eq!(indexers.len(), 2);
+        assert_eq!(indexers[0].parameters[0].type_name.as_deref(), Some("int"));
+        assert_eq!(indexers[1].signature.as_deref(), Some("Item this[string sku] { get; }"));
+
+        // Accessor bodies are walked
+        assert!(occurrences.iter().any(|o| o.role == OccurrenceRole::Call && o.token == "Validate"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_using_directives() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let source = r#"global using System;
+using static System.Math;
+using Json = Newtonsoft.Json.JsonConvert;
+using global::App.Models;
+
+namespace App
+{
+    using Map = System.Collections.Generic.Dictionary<string, int>;
+}
+"#;
+        let (_, edges, _) = harness.parse_file("Program.cs", source)?;
+        let imports: Vec<Import> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Imports)
+            .map(|e| {
+                let mut meta: Vec<(String, serde_json::Value)> = e.meta.clone().into_iter().collect();
+                meta.sort_by(|a, b| a.0.cmp(&b.0));
+                (e.src.as_deref().unwrap(), e.dst.as_deref().unwrap(), meta)
+            })
+            .collect();
+        let flag = |key: &str| (key.to_string(), serde_json::json!(true));
+        let alias = |name: &str| ("alias".to_string(), serde_json::json!(name));
+        assert_eq!(
+            imports,
+            [
+                ("csharp_file_Program.cs", "System", vec![flag("global")]),
+                ("csharp_file_Program.cs", "System.Math", vec![flag("static")]),
+                ("csharp_file_Program.cs", "Newtonsoft.Json.JsonConvert", vec![alias("Json")]),
+                ("csharp_file_Program.cs", "global::App.Models", vec![]),
+                ("csharp_file_Program.cs", "System.Collections.Generic.Dictionary<string, int>", vec![alias("Map")]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_call_and_reference_occurrences() -> Result<()> {
+        let mut harness = CSharpHarness::new()?;
+        let source = r#"class Orders
+{
+    private readonly Repo repo = new Repo();
+
+    void Place(Order order)
+    {
+        var total = order.Total;
+        repo.Save<Order>(order);
+        Log(new Receipt(total));
+        foreach (var line in order.Lines) { Notify(message: line); }
+    }
+}
+"#;
+        let (_, _, occurrences) = harness.parse_file("Orders.cs", source)?;
+        let uses: Vec<(OccurrenceRole, &str, u32)> = occurrences
+            .iter()
+            .filter(|o| o.role != OccurrenceRole::Definition)
+            .map(|o| (o.role.clone(), o.token.as_str(), o.span.start_line))
+            .collect();
+        assert_eq!(
+            uses,
+            [
+                (OccurrenceRole::Reference, "Repo", 2),
+                (OccurrenceRole::Call, "Repo", 2),
+                (OccurrenceRole::Reference, "order", 6),
+                (OccurrenceRole::Reference, "Total", 6),
+                (OccurrenceRole::Call, "Save", 7),
+                (OccurrenceRole::Reference, "repo", 7),
+                (OccurrenceRole::Reference, "order", 7),
+                (OccurrenceRole::Call, "Log", 8),
+                (OccurrenceRole::Call, "Receipt", 8),
+                (OccurrenceRole::Reference, "total", 8),
+                (OccurrenceRole::Reference, "order", 9),
+                (OccurrenceRole::Reference, "Lines", 9),
+                (OccurrenceRole::Call, "Notify", 9),
+                (OccurrenceRole::Reference, "line", 9),
+            ]
+        );
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,124 @@
+//! `///` XML documentation comments as plain text
+
+/// Readable text of an XML doc comment: the summary first, then one line
+/// per other section (`Returns: ...`, `id: ...` for a parameter,
+/// `Throws InvalidOperationException: ...`), with references such as
+/// `<see cref="T:Shop.Order"/>` reduced to the name they point at
+pub(crate) fn to_text(xml: &str) -> String {
+    let mut sections = Vec::new();
+    let mut current = String::new();
+    let mut current_label = String::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        current.push_str(&rest[..start]);
+        let Some(length) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + length];
+        rest = &rest[start + length + 1..];
+
+        let closing = tag.starts_with('/');
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_start_matches('/').trim_end_matches('/').trim();
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let label = match name {
+            "summary" => Some(String::new()),
+            "remarks" => Some("Remarks: ".to_string()),
+            "returns" => Some("Returns: ".to_string()),
+            "value" => Some("Value: ".to_string()),
+            "example" => Some("Example: ".to_string()),
+            "param" | "typeparam" => Some(format!("{}: ", attribute(attributes, "name").unwrap_or_default())),
+            "exception" => Some(format!("Throws {}: ", attribute(attributes, "cref").map(cref_name).unwrap_or_default())),
+            _ => None,
+        };
+        match label {
+            // Sections
+            Some(label) => {
+                flush(&mut current, &current_label, &mut sections);
+                current_label.clear();
+                if !closing && !self_closing {
+                    current.push_str(&label);
+                    current_label = label;
+                }
+            }
+            None => match name {
+                // `<see cref="..."/>` stands for the name; `<see cref="...">text</see>` for its text
+                "see" | "seealso" if self_closing => {
+                    let target = attribute(attributes, "cref")
+                        .map(cref_name)
+                        .or_else(|| attribute(attributes, "langword"))
+                        .or_else(|| attribute(attributes, "href"));
+                    current.push_str(target.unwrap_or_default());
+                }
+                "paramref" | "typeparamref" => current.push_str(attribute(attributes, "name").unwrap_or_default()),
+                "para" | "br" => current.push(' '),
+                // `<c>`, `<code>`, `<b>`, list markup: keep just the text
+                _ => {}
+            },
+        }
+    }
+    current.push_str(rest);
+    flush(&mut current, &current_label, &mut sections);
+    sections.join("\n")
+}
+
+/// End the section being collected, with whitespace collapsed; one with
+/// nothing but its label is dropped
+fn flush(current: &mut String, label: &str, sections: &mut Vec<String>) {
+    let text = decode_entities(&current.split_whitespace().collect::<Vec<_>>().join(" "));
+    if !text.is_empty() && text != label.trim_end() {
+        sections.push(text);
+    }
+    current.clear();
+}
+
+/// Value of `key="..."` (or single-quoted) among a tag's attributes
+fn attribute<'a>(attributes: &'a str, key: &str) -> Option<&'a str> {
+    let start = attributes.find(&format!("{}=", key))? + key.len() + 1;
+    let quote = attributes[start..].chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &attributes[start + 1..];
+    value.find(quote).map(|end| &value[..end])
+}
+
+/// `T:Shop.Order` -> `Shop.Order`; compiler-resolved crefs carry a kind prefix
+fn cref_name(cref: &str) -> &str {
+    match cref.split_once(':') {
+        Some((kind, name)) if kind.len() == 1 && kind.chars().all(|c| c.is_ascii_uppercase()) => name,
+        _ => cref,
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xml_doc_to_text() {
+        let xml = r#"<summary>
+Places an <see cref="T:Shop.Order"/> for
+<paramref name="customer"/>, or returns <see langword="null"/>.
+<para>Orders &lt; 1 item are rejected.</para>
+</summary>
+<param name="customer">Who is buying.</param>
+<param name="express"></param>
+<returns>The <c>Order</c>, once saved.</returns>
+<exception cref="InvalidOperationException">When the cart is empty.</exception>"#;
+        assert_eq!(
+            to_text(xml),
+            "Places an Shop.Order for customer, or returns null. Orders < 1 item are rejected.\n\
+             customer: Who is buying.\n\
+             Returns: The Order, once saved.\n\
+             Throws InvalidOperationException: When the cart is empty."
+        );
+        assert_eq!(to_text("Plain text, no tags"), "Plain text, no tags");
+        assert_eq!(to_text("<inheritdoc/>"), "");
+    }
+}
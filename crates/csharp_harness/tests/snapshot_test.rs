@@ -0,0 +1,24 @@
+//! Snapshot of the full IR emitted for the `testdata/csharp-basic` fixture.
+//!
+//! Review changes with `cargo insta review`, or rerun with
+//! `INSTA_UPDATE=always` to accept them.
+
+use csharp_harness::CSharpHarness;
+use std::path::Path;
+
+fn fixture(path: &str) -> String {
+    std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../testdata").join(path)).unwrap()
+}
+
+#[test]
+fn test_csharp_basic_snapshot() {
+    let mut harness = CSharpHarness::new().unwrap();
+    let content = fixture("csharp-basic/Service.cs");
+    let (symbols, edges, occurrences) = harness.parse_file("Service.cs", &content).unwrap();
+
+    insta::with_settings!({ sort_maps => true, snapshot_suffix => "csharp-basic" }, {
+        insta::assert_json_snapshot!("symbols", symbols);
+        insta::assert_json_snapshot!("edges", edges);
+        insta::assert_json_snapshot!("occurrences", occurrences);
+    });
+}
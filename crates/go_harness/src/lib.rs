@@ -1,8 +1,18 @@
 use anyhow::Result;
-use protocol::{EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Resolution, Span, SymbolIR, SymbolKind};
+use protocol::{Capabilities, EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Parameter, Resolution, Span, SymbolFlags, SymbolIR, SymbolKind};
 use std::collections::HashMap;
 use tree_sitter::{Node, Parser};
 
+/// What this harness extracts, for `reviewbot query languages`
+pub const CAPABILITIES: Capabilities = Capabilities {
+    name: "Go",
+    extensions: &["go"],
+    edges: &[EdgeType::Contains, EdgeType::Imports, EdgeType::Calls],
+    occurrences: &[OccurrenceRole::Definition, OccurrenceRole::Call],
+    docs: false,
+    resolution: Resolution::Syntactic,
+};
+
 pub struct GoHarness {
     parser: Parser,
 }
@@ -67,8 +77,10 @@ impl GoHarness {
                         &name,
                         SymbolKind::Function,
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
+                        source,
                     );
                     
                     if let Some(parent) = parent_symbol {
@@ -92,7 +104,20 @@ impl GoHarness {
                         token: name.clone(),
                     });
                     
+                    let symbol_id = symbol.id.clone();
                     symbols.push(symbol);
+                    if let Some(body) = node.child_by_field_name("body") {
+                        self.extract_symbols_recursive(
+                            body,
+                            source,
+                            file_path,
+                            commit_sha,
+                            Some(&symbol_id),
+                            symbols,
+                            edges,
+                            occurrences,
+                        )?;
+                    }
                     return Ok(());
                 }
             }
@@ -111,8 +136,10 @@ impl GoHarness {
                         &name,
                         SymbolKind::Method,
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
+                        source,
                     );
                     
                     // Add edge from receiver type if we can determine it
@@ -138,7 +165,20 @@ impl GoHarness {
                         token: name.clone(),
                     });
                     
+                    let symbol_id = symbol.id.clone();
                     symbols.push(symbol);
+                    if let Some(body) = node.child_by_field_name("body") {
+                        self.extract_symbols_recursive(
+                            body,
+                            source,
+                            file_path,
+                            commit_sha,
+                            Some(&symbol_id),
+                            symbols,
+                            edges,
+                            occurrences,
+                        )?;
+                    }
                     return Ok(());
                 }
             }
@@ -166,8 +206,10 @@ impl GoHarness {
                             &name,
                             kind,
                             node,
+                            name_node,
                             file_path,
                             commit_sha,
+                            source,
                         );
                         
                         if let Some(parent) = parent_symbol {
@@ -215,49 +257,138 @@ impl GoHarness {
                 }
             }
             "var_declaration" | "const_declaration" => {
-                // Handle variable and constant declarations
+                let is_const = node_kind == "const_declaration";
+                // In a const group a spec without values repeats the type and
+                // expressions of the previous one, with iota counting the specs
+                let mut implicit: Option<(Option<String>, Vec<String>)> = None;
+                let mut iota = 0;
                 for child in node.children(&mut node.walk()) {
-                    if child.kind() == "var_spec" || child.kind() == "const_spec" {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = self.node_text(name_node, source);
-                            let kind = if node_kind == "const_declaration" {
-                                SymbolKind::Constant
-                            } else {
-                                SymbolKind::Variable
-                            };
-                            
-                            let symbol = self.create_symbol(
-                                &name,
-                                kind,
-                                child,
-                                file_path,
-                                commit_sha,
-                            );
-                            
-                            occurrences.push(OccurrenceIR {
-                                file_path: file_path.to_string(),
-                                symbol_id: Some(symbol.id.clone()),
-                                role: OccurrenceRole::Definition,
-                                span: self.node_to_span(name_node),
-                                token: name.clone(),
-                            });
-                            
-                            symbols.push(symbol);
+                    if child.kind() != "var_spec" && child.kind() != "const_spec" {
+                        continue;
+                    }
+                    let values: Vec<String> = child
+                        .child_by_field_name("value")
+                        .map(|list| list.named_children(&mut list.walk()).map(|v| self.node_text(v, source)).collect())
+                        .unwrap_or_default();
+                    let type_name = child.child_by_field_name("type").map(|t| self.node_text(t, source));
+                    if is_const && !values.is_empty() {
+                        implicit = Some((type_name.clone(), values.clone()));
+                    }
+                    let (type_name, values) = match &implicit {
+                        Some(previous) if is_const && values.is_empty() => previous.clone(),
+                        _ => (type_name, values),
+                    };
+
+                    // The grammar tags the separating commas as names too
+                    let names: Vec<Node> =
+                        child.children_by_field_name("name", &mut child.walk()).filter(|n| n.is_named()).collect();
+                    for (i, name_node) in names.into_iter().enumerate() {
+                        let name = self.node_text(name_node, source);
+                        let kind = if is_const { SymbolKind::Constant } else { SymbolKind::Variable };
+
+                        let mut symbol = self.create_symbol(
+                            &name,
+                            kind,
+                            child,
+                            name_node,
+                            file_path,
+                            commit_sha,
+                            source,
+                        );
+                        if is_const {
+                            symbol.signature = Some(const_signature(&name, type_name.as_deref(), values.get(i), iota));
+                            symbol.return_type = type_name.clone();
                         }
+
+                        occurrences.push(OccurrenceIR {
+                            file_path: file_path.to_string(),
+                            symbol_id: Some(symbol.id.clone()),
+                            role: OccurrenceRole::Definition,
+                            span: self.node_to_span(name_node),
+                            token: name.clone(),
+                        });
+
+                        symbols.push(symbol);
                     }
+                    iota += 1;
                 }
             }
+            "func_literal" => {
+                let symbol = self.func_literal_symbol(node, file_path, commit_sha, source);
+                if let Some(parent) = parent_symbol {
+                    edges.push(EdgeIR {
+                        edge_type: EdgeType::Contains,
+                        src: Some(parent.to_string()),
+                        dst: Some(symbol.id.clone()),
+                        file_src: None,
+                        file_dst: None,
+                        resolution: Resolution::Syntactic,
+                        meta: HashMap::new(),
+                        provenance: HashMap::new(),
+                    });
+                }
+                
+                occurrences.push(OccurrenceIR {
+                    file_path: file_path.to_string(),
+                    symbol_id: Some(symbol.id.clone()),
+                    role: OccurrenceRole::Definition,
+                    span: self.node_to_span(node),
+                    token: symbol.name.clone(),
+                });
+                
+                // Calls in the literal's body belong to the literal
+                let symbol_id = symbol.id.clone();
+                symbols.push(symbol);
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.extract_symbols_recursive(
+                        body,
+                        source,
+                        file_path,
+                        commit_sha,
+                        Some(&symbol_id),
+                        symbols,
+                        edges,
+                        occurrences,
+                    )?;
+                }
+                return Ok(());
+            }
             "call_expression" => {
                 if let Some(func) = node.child_by_field_name("function") {
                     let callee_name = self.node_text(func, source);
                     
-                    occurrences.push(OccurrenceIR {
-                        file_path: file_path.to_string(),
-                        symbol_id: None,
-                        role: OccurrenceRole::Call,
-                        span: self.node_to_span(func),
-                        token: callee_name,
-                    });
+                    if let Some(caller) = parent_symbol {
+                        // `go func() {...}()` calls the literal itself
+                        let callee = if func.kind() == "func_literal" {
+                            self.func_literal_symbol(func, file_path, commit_sha, source).id
+                        } else {
+                            callee_name.clone()
+                        };
+                        let mut meta = HashMap::new();
+                        if node.parent().is_some_and(|p| p.kind() == "go_statement") {
+                            meta.insert("goroutine".to_string(), serde_json::json!(true));
+                        }
+                        edges.push(EdgeIR {
+                            edge_type: EdgeType::Calls,
+                            src: Some(caller.to_string()),
+                            dst: Some(callee),
+                            file_src: Some(file_path.to_string()),
+                            file_dst: None,
+                            resolution: Resolution::Syntactic,
+                            meta,
+                            provenance: HashMap::new(),
+                        });
+                    }
+                    
+                    if func.kind() != "func_literal" {
+                        occurrences.push(OccurrenceIR {
+                            file_path: file_path.to_string(),
+                            symbol_id: None,
+                            role: OccurrenceRole::Call,
+                            span: self.node_to_span(func),
+                            token: callee_name,
+                        });
+                    }
                 }
             }
             _ => {}
@@ -304,8 +435,10 @@ impl GoHarness {
                                     &name,
                                     SymbolKind::Field,
                                     field_decl,
+                                    field_child,
                                     file_path,
                                     commit_sha,
+                                    source,
                                 );
                                 
                                 edges.push(EdgeIR {
@@ -406,8 +539,10 @@ impl GoHarness {
         name: &str,
         kind: SymbolKind,
         node: Node,
+        name_node: Node,
         file_path: &str,
         commit_sha: &str,
+        source: &[u8],
     ) -> SymbolIR {
         let package_name = self.extract_package_name(file_path);
         let fqn = format!("{}.{}", package_name, name);
@@ -423,14 +558,93 @@ impl GoHarness {
             name: name.to_string(),
             fqn,
             signature: None,
+            parameters: self.extract_parameters(node, source),
+            // A single type or a parenthesized result list: `(int, error)`
+            return_type: node.child_by_field_name("result").map(|r| self.node_text(r, source)),
             file_path: file_path.to_string(),
-            span: self.node_to_span(node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: Some("public".to_string()),
+            flags: self.symbol_flags(node, file_path, source),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         }
     }
     
+    /// Function literals are named after their position, `func_12_8`
+    fn func_literal_symbol(&self, node: Node, file_path: &str, commit_sha: &str, source: &[u8]) -> SymbolIR {
+        let position = node.start_position();
+        let name = format!("func_{}_{}", position.row, position.column);
+        let mut symbol = self.create_symbol(&name, SymbolKind::Function, node, node, file_path, commit_sha, source);
+        symbol.visibility = None;
+        symbol
+    }
+    
+    /// `Deprecated:` paragraphs in the doc comment (the Go convention) and
+    /// declarations in `_test.go` files
+    fn symbol_flags(&self, node: Node, file_path: &str, source: &[u8]) -> SymbolFlags {
+        let mut flags = SymbolFlags::empty();
+        flags.set_if(
+            SymbolFlags::DEPRECATED,
+            self.doc_comment(node, source)
+                .lines()
+                .any(|line| line.trim_start_matches("//").trim_start().starts_with("Deprecated:")),
+        );
+        flags.set_if(SymbolFlags::TEST_ONLY, file_path.ends_with("_test.go"));
+        flags
+    }
+    
+    /// The run of comments directly above a declaration (for specs inside a
+    /// `type`/`var`/`const` declaration, above the whole declaration)
+    fn doc_comment(&self, node: Node, source: &[u8]) -> String {
+        let mut anchor = node;
+        if let Some(parent) = node.parent() {
+            if matches!(parent.kind(), "type_declaration" | "var_declaration" | "const_declaration")
+                && parent.named_child(0).map(|first| first.id()) == Some(node.id())
+            {
+                anchor = parent;
+            }
+        }
+        let mut lines = Vec::new();
+        let mut current = anchor.prev_named_sibling();
+        while let Some(comment) = current.filter(|c| c.kind() == "comment") {
+            lines.push(self.node_text(comment, source));
+            current = comment.prev_named_sibling();
+        }
+        lines.reverse();
+        lines.join("\n")
+    }
+    
+    /// Parameters of a func or method (not its receiver); `a, b int` yields
+    /// one entry per name, unnamed parameters have an empty name
+    fn extract_parameters(&self, node: Node, source: &[u8]) -> Vec<Parameter> {
+        let Some(params) = node.child_by_field_name("parameters") else {
+            return Vec::new();
+        };
+        let mut parameters = Vec::new();
+        for param in params.named_children(&mut params.walk()) {
+            let variadic = match param.kind() {
+                "parameter_declaration" => false,
+                "variadic_parameter_declaration" => true,
+                _ => continue,
+            };
+            let type_name = param.child_by_field_name("type").map(|t| self.node_text(t, source));
+            let mut names: Vec<String> = param
+                .children_by_field_name("name", &mut param.walk())
+                .map(|name| self.node_text(name, source))
+                .collect();
+            if names.is_empty() {
+                names.push(String::new());
+            }
+            for name in names {
+                parameters.push(Parameter { name, type_name: type_name.clone(), default: None, variadic });
+            }
+        }
+        parameters
+    }
+    
     fn extract_package_name(&self, file_path: &str) -> String {
         // Extract package name from file path
         // In real implementation, would parse the package declaration
@@ -464,6 +678,22 @@ impl GoHarness {
     }
 }
 
+/// `const Tuesday Weekday = iota // iota = 1`: the declared or implicitly
+/// repeated expression, with the spec's ordinal when it uses iota
+fn const_signature(name: &str, type_name: Option<&str>, value: Option<&String>, iota: usize) -> String {
+    let mut signature = format!("const {}", name);
+    if let Some(type_name) = type_name {
+        signature.push_str(&format!(" {}", type_name));
+    }
+    if let Some(value) = value {
+        signature.push_str(&format!(" = {}", value));
+        if value.split(|c: char| !c.is_alphanumeric() && c != '_').any(|word| word == "iota") {
+            signature.push_str(&format!(" // iota = {}", iota));
+        }
+    }
+    signature
+}
+
 mod test_fixtures;
 
 #[cfg(test)]
@@ -496,7 +726,108 @@ func add(a int, b int) int {
         assert_eq!(symbols[0].kind, SymbolKind::Function);
         assert_eq!(symbols[1].name, "add");
         
-        assert_eq!(occurrences.len(), 2);
+        // Two definitions and the call in hello's body
+        assert_eq!(occurrences.len(), 3);
+        assert_eq!(occurrences[1].role, OccurrenceRole::Call);
+        assert_eq!(occurrences[1].token, "fmt.Println");
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_parse_go_parameters() -> Result<()> {
+        let mut harness = GoHarness::new()?;
+        
+        let code = r#"
+package main
+
+func printf(w io.Writer, a, b int, args ...any) {}
+"#;
+        
+        let (symbols, _, _) = harness.parse_file(code, "test.go", "abc123")?;
+        let summary: Vec<_> = symbols[0].parameters.iter()
+            .map(|p| (p.name.as_str(), p.type_name.as_deref(), p.variadic))
+            .collect();
+        assert_eq!(summary, vec![
+            ("w", Some("io.Writer"), false),
+            ("a", Some("int"), false),
+            ("b", Some("int"), false),
+            ("args", Some("any"), true),
+        ]);
+        assert_eq!(symbols[0].return_type, None);
+        
+        let code = "package main\n\nfunc open(name string) (*File, error) {}\n";
+        let (symbols, _, _) = harness.parse_file(code, "test.go", "abc123")?;
+        assert_eq!(symbols[0].return_type.as_deref(), Some("(*File, error)"));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_parse_go_iota_consts() -> Result<()> {
+        let mut harness = GoHarness::new()?;
+        
+        let code = r#"
+package main
+
+const (
+    Sunday Weekday = iota
+    Monday
+    _
+    Wednesday
+)
+
+const (
+    KB, KiB = 1 << (10 * (iota + 1)), iota
+    MB, MiB
+)
+
+const Pi = 3.14
+"#;
+        
+        let (symbols, _, _) = harness.parse_file(code, "test.go", "abc123")?;
+        let summary: Vec<_> = symbols.iter()
+            .map(|s| (s.name.as_str(), s.kind.clone(), s.signature.as_deref().unwrap_or("")))
+            .collect();
+        assert_eq!(summary, vec![
+            ("Sunday", SymbolKind::Constant, "const Sunday Weekday = iota // iota = 0"),
+            ("Monday", SymbolKind::Constant, "const Monday Weekday = iota // iota = 1"),
+            ("_", SymbolKind::Constant, "const _ Weekday = iota // iota = 2"),
+            ("Wednesday", SymbolKind::Constant, "const Wednesday Weekday = iota // iota = 3"),
+            ("KB", SymbolKind::Constant, "const KB = 1 << (10 * (iota + 1)) // iota = 0"),
+            ("KiB", SymbolKind::Constant, "const KiB = iota // iota = 0"),
+            ("MB", SymbolKind::Constant, "const MB = 1 << (10 * (iota + 1)) // iota = 1"),
+            ("MiB", SymbolKind::Constant, "const MiB = iota // iota = 1"),
+            ("Pi", SymbolKind::Constant, "const Pi = 3.14"),
+        ]);
+        assert_eq!(symbols[3].return_type.as_deref(), Some("Weekday"));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_parse_go_flags() -> Result<()> {
+        let mut harness = GoHarness::new()?;
+        
+        let code = r#"
+package main
+
+// Open opens a file.
+//
+// Deprecated: use OpenFile.
+func Open(name string) {}
+
+// OpenFile opens a file.
+func OpenFile(name string) {}
+"#;
+        
+        let (symbols, _, _) = harness.parse_file(code, "pkg/file.go", "abc123")?;
+        let flags = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().flags;
+        assert_eq!(flags("Open"), SymbolFlags::DEPRECATED);
+        assert!(flags("OpenFile").is_empty());
+        
+        let (symbols, _, _) = harness.parse_file(code, "pkg/file_test.go", "abc123")?;
+        assert!(symbols.iter().all(|s| s.flags.contains(SymbolFlags::TEST_ONLY)));
         
         Ok(())
     }
@@ -673,6 +1004,48 @@ type Reader interface {
         Ok(())
     }
     
+    #[test]
+    fn test_func_literals_and_goroutine_calls() -> Result<()> {
+        let mut harness = GoHarness::new()?;
+        
+        let code = r#"
+package main
+
+func serve(jobs []Job) {
+    go func() {
+        handle(jobs)
+    }()
+    go worker(jobs)
+    log("started")
+}
+"#;
+        
+        let (symbols, edges, _) = harness.parse_file(code, "server/serve.go", "abc123")?;
+        let serve = symbols.iter().find(|s| s.name == "serve").unwrap();
+        let literal = symbols.iter().find(|s| s.name == "func_4_7").unwrap();
+        assert_eq!(literal.kind, SymbolKind::Function);
+        assert!(edges.iter().any(|e| e.edge_type == EdgeType::Contains
+            && e.src.as_ref() == Some(&serve.id)
+            && e.dst.as_ref() == Some(&literal.id)));
+        
+        let calls: Vec<_> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Calls)
+            .map(|e| {
+                let src = if e.src.as_ref() == Some(&serve.id) { "serve" } else { "literal" };
+                let dst = if e.dst.as_ref() == Some(&literal.id) { "literal" } else { e.dst.as_deref().unwrap() };
+                (src, dst, e.meta.contains_key("goroutine"))
+            })
+            .collect();
+        assert_eq!(calls, vec![
+            ("serve", "literal", true),
+            ("literal", "handle", false),
+            ("serve", "worker", true),
+            ("serve", "log", false),
+        ]);
+        
+        Ok(())
+    }
+    
     #[test]
     fn test_error_handling() -> Result<()> {
         let mut harness = GoHarness::new()?;
@@ -919,10 +1292,10 @@ type Reader interface {
         
         for symbol in &symbols {
             // Spans should have valid line/column numbers
-            assert!(symbol.span.start_line <= symbol.span.end_line,
+            assert!(symbol.full_span.start_line <= symbol.full_span.end_line,
                 "Start line should be <= end line");
-            if symbol.span.start_line == symbol.span.end_line {
-                assert!(symbol.span.start_col <= symbol.span.end_col,
+            if symbol.full_span.start_line == symbol.full_span.end_line {
+                assert!(symbol.full_span.start_col <= symbol.full_span.end_col,
                     "Start col should be <= end col on same line");
             }
         }
@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use crate::modules::{BuildModule, ModuleDependency};
+use crate::source_roots::{normalize, quoted_strings};
+
+const SETTINGS_FILES: &[&str] = &["settings.gradle", "settings.gradle.kts"];
+const BUILD_FILES: &[&str] = &["build.gradle", "build.gradle.kts"];
+
+/// Modules of the Gradle build rooted at `repo_root`: the projects listed in
+/// `settings.gradle(.kts)` plus the root project when it has a build script,
+/// each with the `project(...)` dependencies of its build script
+pub fn detect_modules(repo_root: &Path) -> Vec<BuildModule> {
+    let settings = SETTINGS_FILES
+        .iter()
+        .find_map(|name| std::fs::read_to_string(repo_root.join(name)).ok());
+
+    let mut projects = vec![(":".to_string(), String::new())];
+    if let Some(settings) = &settings {
+        projects.extend(parse_settings(settings));
+    }
+
+    let names: Vec<String> = projects.iter().map(|(name, _)| name.clone()).collect();
+    let mut modules = Vec::new();
+    for (name, dir) in projects {
+        let Some((build_file, content)) = BUILD_FILES.iter().find_map(|file| {
+            let path = if dir.is_empty() { file.to_string() } else { format!("{}/{}", dir, file) };
+            std::fs::read_to_string(repo_root.join(&path)).ok().map(|content| (path, content))
+        }) else {
+            continue;
+        };
+        modules.push(BuildModule {
+            name,
+            dir,
+            build_file,
+            dependencies: parse_dependencies(&content, &names),
+        });
+    }
+    modules
+}
+
+/// `(project path, directory)` pairs from a settings script, applying
+/// `project(':x').projectDir = file('dir')` overrides
+fn parse_settings(content: &str) -> Vec<(String, String)> {
+    let mut projects: Vec<(String, String)> = Vec::new();
+    let mut in_include = false;
+    for line in code_lines(content) {
+        if in_include || line.starts_with("include") {
+            let open_paren = line.starts_with("include(") || line.starts_with("include (");
+            in_include = (in_include || open_paren) && !line.contains(')');
+            for path in quoted_strings(line) {
+                let name = format!(":{}", path.trim_start_matches(':'));
+                let dir = name.trim_start_matches(':').replace(':', "/");
+                if !projects.iter().any(|(n, _)| *n == name) {
+                    projects.push((name, dir));
+                }
+            }
+        } else if line.contains("projectDir") {
+            if let [project, dir, ..] = quoted_strings(line).as_slice() {
+                let name = format!(":{}", project.trim_start_matches(':'));
+                if let Some(entry) = projects.iter_mut().find(|(n, _)| *n == name) {
+                    entry.1 = normalize(dir);
+                }
+            }
+        }
+    }
+    projects
+}
+
+/// Inter-project dependencies of a build script: `implementation project(':core')`,
+/// `api(project(path: ":core"))` and type-safe `testImplementation(projects.core)`
+fn parse_dependencies(content: &str, projects: &[String]) -> Vec<ModuleDependency> {
+    let mut dependencies = Vec::new();
+    for line in code_lines(content) {
+        let configuration: String = line
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        if configuration.is_empty() {
+            continue;
+        }
+
+        let module = if let Some(idx) = line.find("project(") {
+            quoted_strings(&line[idx..])
+                .into_iter()
+                .next()
+                .map(|path| format!(":{}", path.trim_start_matches(':')))
+        } else if let Some(idx) = line.find("projects.") {
+            let accessor: String = line[idx + "projects.".len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '.')
+                .collect();
+            resolve_accessor(&accessor, projects)
+        } else {
+            None
+        };
+
+        if let Some(module) = module {
+            dependencies.push(ModuleDependency { module, configuration });
+        }
+    }
+    dependencies
+}
+
+/// Map a type-safe accessor (`projects.lib.coreUtils`) back to the project
+/// path it was generated from (`:lib:core-utils`)
+fn resolve_accessor(accessor: &str, projects: &[String]) -> Option<String> {
+    let simplify = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect::<String>()
+    };
+    let wanted: Vec<String> = accessor.split('.').map(simplify).collect();
+    projects
+        .iter()
+        .find(|project| {
+            let segments: Vec<String> = project.trim_start_matches(':').split(':').map(simplify).collect();
+            segments == wanted
+        })
+        .cloned()
+}
+
+/// Trimmed lines with `//` comments dropped
+fn code_lines(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings() {
+        let groovy = "rootProject.name = 'shop'\ninclude ':app', ':lib:core'\nproject(':app').projectDir = file('applications/app')\n";
+        assert_eq!(
+            parse_settings(groovy),
+            vec![
+                (":app".to_string(), "applications/app".to_string()),
+                (":lib:core".to_string(), "lib/core".to_string()),
+            ]
+        );
+
+        let kts = "include(\n    \"api\",\n    \":core-utils\",\n)\n";
+        assert_eq!(
+            parse_settings(kts),
+            vec![
+                (":api".to_string(), "api".to_string()),
+                (":core-utils".to_string(), "core-utils".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_dependencies() {
+        let projects = vec![":api".to_string(), ":core-utils".to_string(), ":lib:model".to_string()];
+        let build = r#"
+dependencies {
+    implementation project(':api')
+    api(project(path: ":lib:model"))
+    // implementation project(':old')
+    testImplementation(projects.coreUtils)
+    implementation("com.google.guava:guava:33.0.0-jre")
+}
+"#;
+        let dependencies: Vec<(String, String)> = parse_dependencies(build, &projects)
+            .into_iter()
+            .map(|d| (d.module, d.configuration))
+            .collect();
+        assert_eq!(
+            dependencies,
+            vec![
+                (":api".to_string(), "implementation".to_string()),
+                (":lib:model".to_string(), "api".to_string()),
+                (":core-utils".to_string(), "testImplementation".to_string()),
+            ]
+        );
+    }
+}
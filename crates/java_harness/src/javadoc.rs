@@ -0,0 +1,112 @@
+//! Javadoc block tags (`@param`, `@return`, `@throws`, `@deprecated`),
+//! split off the description so tools can render them without
+//! re-parsing the comment
+
+use protocol::{DocTag, DocTags};
+
+/// The description of a doc comment, already stripped of its `/**` and
+/// leading `*`s, and its block tags; `None` tags when it has none of the
+/// ones kept. Tags other than those are dropped from the description.
+pub(crate) fn split(comment: &str) -> (String, Option<DocTags>) {
+    // Description lines, then one entry per tag with its continuation lines
+    let mut description = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    for line in comment.lines() {
+        if line.trim_start().starts_with('@') {
+            tags.push(line.trim().to_string());
+        } else if let Some(tag) = tags.last_mut() {
+            tag.push(' ');
+            tag.push_str(line.trim());
+        } else {
+            description.push(line);
+        }
+    }
+    if tags.is_empty() {
+        return (inline_text(comment.trim()), None);
+    }
+
+    let mut doc_tags = DocTags::default();
+    for tag in &tags {
+        let (name, rest) = tag[1..].split_once(char::is_whitespace).unwrap_or((&tag[1..], ""));
+        let rest = rest.trim();
+        match name {
+            "param" => doc_tags.params.extend(named(rest)),
+            "return" | "returns" => doc_tags.returns = Some(text(rest)),
+            "throws" | "exception" => doc_tags.throws.extend(named(rest)),
+            "deprecated" => doc_tags.deprecated = Some(text(rest)),
+            _ => {}
+        }
+    }
+    let description = inline_text(description.join("\n").trim());
+    let found = doc_tags != DocTags::default();
+    (description, found.then_some(doc_tags))
+}
+
+/// `name text` of a `@param` or `@throws` tag
+fn named(rest: &str) -> Option<DocTag> {
+    let (name, text_part) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    (!name.is_empty()).then(|| DocTag { name: name.to_string(), text: text(text_part) })
+}
+
+/// Tag text on one line, inline tags reduced to what they show
+fn text(raw: &str) -> String {
+    inline_text(&raw.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// `{@link Foo#bar label}` as `label` (or `Foo#bar`), `{@code x}` and
+/// `{@literal x}` as `x`; other inline tags such as `{@inheritDoc}` stay
+fn inline_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{@") {
+        out.push_str(&rest[..start]);
+        let Some(length) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let inner = &rest[start + 2..start + length];
+        let (tag, body) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+        let body = body.trim();
+        match tag {
+            "link" | "linkplain" => {
+                let (target, label) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+                out.push_str(if label.trim().is_empty() { target } else { label.trim() });
+            }
+            "code" | "literal" | "value" if !body.is_empty() => out.push_str(body),
+            _ => out.push_str(&rest[start..start + length + 1]),
+        }
+        rest = &rest[start + length + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_block_and_inline_tags() {
+        let comment = "Loads the {@code Order} with the given id.\n\nSee {@link Store#save(Order) saving}.\n\
+                       @param <T> the order type\n@param id the order's key,\n  never null\n\
+                       @return the order, or {@code null}\n@throws IOException if the disk fails\n\
+                       @exception IllegalStateException when closed\n@since 2.1\n\
+                       @deprecated use {@link #find(long)}";
+        let (description, tags) = split(comment);
+        assert_eq!(description, "Loads the Order with the given id.\n\nSee saving.");
+        let tags = tags.unwrap();
+        let params: Vec<(&str, &str)> = tags.params.iter().map(|p| (p.name.as_str(), p.text.as_str())).collect();
+        assert_eq!(params, [("<T>", "the order type"), ("id", "the order's key, never null")]);
+        assert_eq!(tags.returns.as_deref(), Some("the order, or null"));
+        let throws: Vec<&str> = tags.throws.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(throws, ["IOException", "IllegalStateException"]);
+        assert_eq!(tags.deprecated.as_deref(), Some("use #find(long)"));
+
+        // Without tags only inline ones are reduced
+        assert_eq!(split("Plain {@inheritDoc} text"), ("Plain {@inheritDoc} text".to_string(), None));
+        let (description, tags) = split("@deprecated");
+        assert_eq!(description, "");
+        assert_eq!(tags.unwrap().deprecated.as_deref(), Some(""));
+        assert_eq!(split("@author someone").1, None);
+    }
+}
@@ -1,8 +1,17 @@
 use anyhow::{Context, Result};
-use protocol::{EdgeIR, EdgeType, OccurrenceIR, OccurrenceRole, SymbolIR, SymbolKind, Language as ProtoLanguage, Span};
+use protocol::{Capabilities, DocTags, EdgeIR, EdgeType, OccurrenceIR, OccurrenceRole, Parameter, SymbolFlags, SymbolIR, SymbolKind, Language as ProtoLanguage, Resolution, Span};
 use std::collections::HashMap;
 use tree_sitter::{Language, Node, Parser};
 
+mod gradle;
+mod javadoc;
+mod maven;
+pub mod modules;
+pub mod source_roots;
+
+pub use modules::{BuildModule, ModuleDependency, ModuleGraph};
+pub use source_roots::SourceRoots;
+
 extern "C" {
     fn tree_sitter_java() -> Language;
 }
@@ -11,8 +20,36 @@ pub fn get_language() -> Language {
     unsafe { tree_sitter_java() }
 }
 
+/// What this harness extracts, for `reviewbot query languages`
+pub const CAPABILITIES: Capabilities = Capabilities {
+    name: "Java",
+    extensions: &["java"],
+    edges: &[
+        EdgeType::Imports,
+        EdgeType::Calls,
+        EdgeType::Extends,
+        EdgeType::Implements,
+        EdgeType::References,
+        EdgeType::DependsOn,
+        EdgeType::Throws,
+        EdgeType::Permits,
+    ],
+    occurrences: &[
+        OccurrenceRole::Definition,
+        OccurrenceRole::Reference,
+        OccurrenceRole::Call,
+        OccurrenceRole::Read,
+        OccurrenceRole::Write,
+    ],
+    docs: true,
+    resolution: Resolution::Syntactic,
+};
+
 pub struct JavaHarness {
     parser: Parser,
+    source_roots: SourceRoots,
+    modules: ModuleGraph,
+    local_symbols: bool,
 }
 
 impl JavaHarness {
@@ -22,7 +59,34 @@ impl JavaHarness {
         parser
             .set_language(&language)
             .context("Failed to set Java language")?;
-        Ok(Self { parser })
+        Ok(Self {
+            parser,
+            source_roots: SourceRoots::new(),
+            modules: ModuleGraph::new(),
+            local_symbols: false,
+        })
+    }
+
+    /// Use the repository's source roots for file FQNs and import resolution
+    pub fn with_source_roots(mut self, source_roots: SourceRoots) -> Self {
+        self.source_roots = source_roots;
+        self
+    }
+
+    /// Tag imports that cross build modules, flagging those the importing
+    /// module does not declare a dependency on
+    pub fn with_modules(mut self, modules: ModuleGraph) -> Self {
+        self.modules = modules;
+        self
+    }
+
+    /// Also index parameters and local variables, as symbols scoped to
+    /// their method or lambda (`app.Totals.sum.start`) with Read and Write
+    /// occurrences for each use. Off by default: it multiplies the symbols
+    /// of a typical file several times over.
+    pub fn with_local_symbols(mut self, enabled: bool) -> Self {
+        self.local_symbols = enabled;
+        self
     }
 
     pub fn parse(
@@ -39,7 +103,7 @@ impl JavaHarness {
         let mut symbols = Vec::new();
         let mut edges = Vec::new();
         let mut occurrences = Vec::new();
-        let mut context = ParseContext::new();
+        let mut context = ParseContext::new(self.local_symbols);
 
         self.walk_node(
             root_node,
@@ -50,6 +114,8 @@ impl JavaHarness {
             &mut occurrences,
             &mut context,
         )?;
+        mark_varargs_calls(&symbols, &mut edges);
+        permit_same_file_subtypes(&context.implicitly_sealed, &mut edges);
 
         Ok((symbols, edges, occurrences))
     }
@@ -69,7 +135,7 @@ impl JavaHarness {
                 self.handle_package(node, content, context)?;
             }
             "import_declaration" => {
-                self.handle_import(node, content, file_path, edges, occurrences)?;
+                self.handle_import(node, content, file_path, edges, occurrences, context)?;
             }
             "class_declaration" => {
                 self.handle_class(node, content, file_path, symbols, edges, occurrences, context)?;
@@ -84,38 +150,104 @@ impl JavaHarness {
                 self.handle_record(node, content, file_path, symbols, edges, occurrences, context)?;
             }
             "method_declaration" | "constructor_declaration" => {
-                self.handle_method(node, content, file_path, symbols, occurrences, context)?;
+                self.handle_method(node, content, file_path, symbols, edges, occurrences, context)?;
             }
             "field_declaration" => {
-                self.handle_field(node, content, file_path, symbols, occurrences, context)?;
+                self.handle_field(node, content, file_path, symbols, edges, occurrences, context)?;
             }
             "annotation_type_declaration" => {
-                self.handle_annotation(node, content, file_path, symbols, occurrences, context)?;
+                self.handle_annotation(node, content, file_path, symbols, edges, occurrences, context)?;
             }
             "annotation" | "marker_annotation" => {
-                self.handle_annotation_usage(node, content, file_path, occurrences)?;
+                self.handle_annotation_usage(node, content, file_path, occurrences, context);
             }
             "method_invocation" => {
-                self.handle_method_call(node, content, file_path, edges, occurrences)?;
+                self.handle_method_call(node, content, file_path, edges, occurrences, context)?;
+                // Receivers and arguments: `a.b(c.d(), () -> e())`
+                for child in node.children(&mut node.walk()) {
+                    if Some(child) != node.child_by_field_name("name") {
+                        self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
+                    }
+                }
+            }
+            "object_creation_expression" => {
+                self.handle_object_creation(node, content, file_path, symbols, edges, occurrences, context)?;
+            }
+            "throw_statement" => {
+                if let Some(exception) = self.thrown_type(node, content) {
+                    let thrower = self.caller_id(file_path, context);
+                    self.push_throws_edge(&thrower, exception, "thrown", file_path, edges);
+                }
+                for child in node.children(&mut node.walk()) {
+                    self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
+                }
+            }
+            "switch_expression" => {
+                self.handle_switch(node, content, file_path, symbols, edges, occurrences, context)?;
+            }
+            "instanceof_expression" => {
+                self.handle_instanceof(node, content, file_path, symbols, edges, occurrences, context)?;
             }
             "lambda_expression" => {
-                self.handle_lambda(node, content, file_path, symbols, occurrences, context)?;
+                self.handle_lambda(node, content, file_path, symbols, edges, occurrences, context)?;
             }
             "method_reference" => {
-                self.handle_method_reference(node, content, file_path, edges, occurrences)?;
+                self.handle_method_reference(node, content, file_path, edges, occurrences, context)?;
             }
             "static_initializer" => {
-                self.handle_static_initializer(node, content, file_path, symbols, occurrences, context)?;
+                self.handle_static_initializer(node, content, file_path, symbols, edges, occurrences, context)?;
             }
             "instance_initializer" | "block" => {
-                // Check if this is an instance initializer (block at class level)
-                if context.current_class().is_some() {
-                    self.handle_instance_initializer(node, content, file_path, symbols, occurrences, context)?;
+                // Check if this is an instance initializer (block directly in a class body)
+                if node.parent().is_some_and(|p| matches!(p.kind(), "class_body" | "enum_body_declarations")) {
+                    self.handle_instance_initializer(node, content, file_path, symbols, edges, occurrences, context)?;
                 } else {
                     // Regular block, walk children
+                    context.push_scope(None);
                     for child in node.children(&mut node.walk()) {
                         self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
                     }
+                    context.pop_scope();
+                }
+            }
+            // Local variables and their uses, with local symbols on
+            "local_variable_declaration" | "resource" | "enhanced_for_statement" | "catch_formal_parameter"
+                if context.tracks_locals() =>
+            {
+                self.handle_local_declaration(node, content, file_path, symbols, edges, occurrences, context)?;
+            }
+            "for_statement" | "try_with_resources_statement" | "catch_clause" if context.tracks_locals() => {
+                // What these declare is visible only inside them
+                context.push_scope(None);
+                for child in node.children(&mut node.walk()) {
+                    self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
+                }
+                context.pop_scope();
+            }
+            "identifier" => {
+                if self.is_variable_use(node) {
+                    self.push_variable_use(node, content, file_path, OccurrenceRole::Read, occurrences, context);
+                }
+            }
+            "assignment_expression" | "update_expression" => {
+                // `total += v`, `i++`, `this.count = count`
+                let target = node.child_by_field_name("left").or_else(|| node.named_child(0));
+                for child in node.children(&mut node.walk()) {
+                    if Some(child) == target && matches!(child.kind(), "identifier" | "field_access") {
+                        self.push_variable_use(child, content, file_path, OccurrenceRole::Write, occurrences, context);
+                        if let Some(object) = child.child_by_field_name("object") {
+                            self.walk_node(object, content, file_path, symbols, edges, occurrences, context)?;
+                        }
+                    } else {
+                        self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
+                    }
+                }
+            }
+            // `p.x` reads the field `x`
+            "field_access" => {
+                self.push_variable_use(node, content, file_path, OccurrenceRole::Read, occurrences, context);
+                if let Some(object) = node.child_by_field_name("object") {
+                    self.walk_node(object, content, file_path, symbols, edges, occurrences, context)?;
                 }
             }
             _ => {
@@ -147,19 +279,42 @@ impl JavaHarness {
         file_path: &str,
         edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
     ) -> Result<()> {
         let import_path = self.extract_import_path(node, content);
         if !import_path.is_empty() {
+            // Single-type imports name what a simple name in this file means
+            if !node.children(&mut node.walk()).any(|c| c.kind() == "asterisk") {
+                let simple_name = import_path.rsplit('.').next().unwrap_or(&import_path);
+                context.imports.insert(simple_name.to_string(), import_path.clone());
+            }
             let from_id = format!("{}#{}", file_path, self.get_file_fqn(file_path));
+            let file_dst = self.source_roots.resolve_import(&import_path);
+
+            let mut meta = HashMap::new();
+            if let Some(root) = file_dst.as_deref().and_then(|f| self.source_roots.root_for(f)) {
+                meta.insert("source_root".to_string(), serde_json::json!(root));
+            }
+            if let (Some(from), Some(to)) = (
+                self.modules.module_for(file_path),
+                file_dst.as_deref().and_then(|f| self.modules.module_for(f)),
+            ) {
+                if from.name != to.name {
+                    meta.insert("module".to_string(), serde_json::json!(to.name));
+                    if !self.modules.depends_on(&from.name, &to.name) {
+                        meta.insert("undeclared_dependency".to_string(), serde_json::json!(true));
+                    }
+                }
+            }
             
             edges.push(EdgeIR {
                 edge_type: EdgeType::Imports,
                 src: Some(from_id),
                 dst: Some(import_path.clone()),
                 file_src: Some(file_path.to_string()),
-                file_dst: None,
+                file_dst,
                 resolution: protocol::Resolution::Syntactic,
-                meta: HashMap::new(),
+                meta,
                 provenance: HashMap::new(),
             });
 
@@ -227,6 +382,7 @@ impl JavaHarness {
             signature.push('>');
         }
 
+        let (doc, doc_tags) = self.doc_comment(node, content);
         let symbol = SymbolIR {
             id: format!("{}#{}", file_path, fqn),
             lang: ProtoLanguage::Java,
@@ -234,14 +390,22 @@ impl JavaHarness {
             kind: SymbolKind::Class,
             name: name.clone(),
             fqn: fqn.clone(),
-            signature: if signature.is_empty() { None } else { Some(signature) },
+            signature: with_sealing(&modifiers, signature),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if is_public { Some("public".to_string()) } else { None },
-            doc: self.get_preceding_comment(node, content),
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
+            doc,
+            doc_tags,
             sig_hash,
         };
 
+        self.annotation_references(node, &symbol.id, content, file_path, edges, occurrences, context);
+        self.permits_edges(node, &symbol, &modifiers, content, file_path, edges, context);
         symbols.push(symbol.clone());
 
         occurrences.push(OccurrenceIR {
@@ -278,7 +442,7 @@ impl JavaHarness {
                     if child.kind() == "type_list" {
                         // Iterate through all interface types in the type_list
                         for type_child in child.children(&mut child.walk()) {
-                            if type_child.kind() == "type_identifier" || type_child.kind() == "scoped_type_identifier" {
+                            if matches!(type_child.kind(), "type_identifier" | "generic_type" | "scoped_type_identifier") {
                                 let interface_type = self.get_text(type_child, content);
                                 edges.push(EdgeIR {
                                     edge_type: EdgeType::Implements,
@@ -298,6 +462,14 @@ impl JavaHarness {
             }
         }
 
+        let supertypes = [node.child_by_field_name("superclass"), node.child_by_field_name("interfaces")];
+        self.type_argument_edges(&symbol.id, node, &supertypes, content, file_path, edges);
+
+        // Dependency injection points (fields, constructors, setters)
+        if let Some(body) = node.child_by_field_name("body") {
+            edges.extend(self.injection_edges(&modifiers, body, content, file_path, &symbol.id, context));
+        }
+
         // Process class body
         context.push_class(name.clone());
         if let Some(body) = node.child_by_field_name("body") {
@@ -366,14 +538,22 @@ impl JavaHarness {
             kind: SymbolKind::Interface,
             name: name.clone(),
             fqn: fqn.clone(),
-            signature: if signature.is_empty() { None } else { Some(signature) },
+            signature: with_sealing(&modifiers, signature),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if is_public { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
+        self.annotation_references(node, &symbol.id, content, file_path, edges, occurrences, context);
+        self.permits_edges(node, &symbol, &modifiers, content, file_path, edges, context);
         symbols.push(symbol.clone());
 
         occurrences.push(OccurrenceIR {
@@ -406,7 +586,7 @@ impl JavaHarness {
                         } else if type_child.kind() == "type_list" {
                             // Sometimes the interfaces are in a type_list
                             for interface_node in type_child.children(&mut type_child.walk()) {
-                                if interface_node.kind() == "type_identifier" || interface_node.kind() == "scoped_type_identifier" {
+                                if matches!(interface_node.kind(), "type_identifier" | "generic_type" | "scoped_type_identifier") {
                                     let extended_interface = self.get_text(interface_node, content);
                                     edges.push(EdgeIR {
                                         edge_type: EdgeType::Extends,
@@ -422,6 +602,7 @@ impl JavaHarness {
                             }
                         }
                     }
+                    self.type_argument_edges(&symbol.id, node, &[Some(child)], content, file_path, edges);
                     break;
                 }
             }
@@ -466,13 +647,20 @@ impl JavaHarness {
             name: name.clone(),
             fqn: fqn.clone(),
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if is_public { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
+        self.annotation_references(node, &symbol.id, content, file_path, edges, occurrences, context);
         symbols.push(symbol.clone());
 
         occurrences.push(OccurrenceIR {
@@ -521,10 +709,16 @@ impl JavaHarness {
                 name: name.clone(),
                 fqn,
                 signature: None,
+                parameters: Vec::new(),
+                return_type: None,
                 file_path: file_path.to_string(),
-                span: self.node_to_span(name_node),
+                name_span: self.node_to_span(name_node),
+                full_span: self.node_to_span(node),
                 visibility: Some("public".to_string()), // Enum constants are implicitly public
+                flags: self.symbol_flags(node, file_path, content),
+                configs: Vec::new(),
                 doc: None,
+                doc_tags: None,
                 sig_hash,
             };
 
@@ -547,6 +741,7 @@ impl JavaHarness {
         content: &str,
         file_path: &str,
         symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
         context: &mut ParseContext,
     ) -> Result<()> {
@@ -595,6 +790,7 @@ impl JavaHarness {
             properties.insert("is_final".to_string(), "true".to_string());
         }
 
+        let (doc, doc_tags) = self.doc_comment(node, content);
         let symbol = SymbolIR {
             id: format!("{}#{}", file_path, fqn),
             lang: ProtoLanguage::Java,
@@ -607,30 +803,60 @@ impl JavaHarness {
             name: name.clone(),
             fqn,
             signature: Some(signature),
+            parameters: self.get_parameters(node, content),
+            // Constructors have no `type` field
+            return_type: node.child_by_field_name("type").map(|t| self.get_text(t, content)),
             file_path: file_path.to_string(),
-            span: self.node_to_span(node),
+            name_span: self.node_to_span(node.child_by_field_name("name").unwrap_or(node)),
+            full_span: self.node_to_span(node),
             visibility,
-            doc: self.get_preceding_comment(node, content),
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
+            doc,
+            doc_tags,
             sig_hash,
         };
 
+        let declared_types = [node.child_by_field_name("type"), node.child_by_field_name("parameters")];
+        self.type_argument_edges(&symbol.id, node, &declared_types, content, file_path, edges);
+        self.annotation_references(node, &symbol.id, content, file_path, edges, occurrences, context);
+        if let Some(throws) = node.named_children(&mut node.walk()).find(|c| c.kind() == "throws") {
+            for exception in throws.named_children(&mut throws.walk()) {
+                if let Some(exception) = self.named_type(exception, content) {
+                    self.push_throws_edge(&symbol.id, exception, "declared", file_path, edges);
+                }
+            }
+        }
         symbols.push(symbol.clone());
 
-        let name_span = if node.kind() == "constructor_declaration" {
-            self.node_to_span(node)
-        } else {
-            let name_node = node.child_by_field_name("name").unwrap();
-            self.node_to_span(name_node)
+        let name_span = match node.child_by_field_name("name") {
+            Some(name_node) if node.kind() != "constructor_declaration" => self.node_to_span(name_node),
+            _ => self.node_to_span(node),
         };
 
         occurrences.push(OccurrenceIR {
             file_path: file_path.to_string(),
-            symbol_id: Some(symbol.id),
+            symbol_id: Some(symbol.id.clone()),
             role: OccurrenceRole::Definition,
             span: name_span,
             token: name,
         });
 
+        // Walk the statements of the body; the block itself would be
+        // mistaken for an instance initializer while inside a class
+        context.push_scope(Some(symbol.fqn.clone()));
+        if let Some(params_node) = node.child_by_field_name("parameters") {
+            self.declare_parameters(params_node, content, file_path, symbols, occurrences, context);
+        }
+        if let Some(body) = node.child_by_field_name("body") {
+            context.method_stack.push(symbol.id.clone());
+            for child in body.children(&mut body.walk()) {
+                self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
+            }
+            context.method_stack.pop();
+        }
+        context.pop_scope();
+
         Ok(())
     }
 
@@ -640,6 +866,7 @@ impl JavaHarness {
         content: &str,
         file_path: &str,
         symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
         context: &mut ParseContext,
     ) -> Result<()> {
@@ -691,13 +918,21 @@ impl JavaHarness {
                         name: name.clone(),
                         fqn,
                         signature: None,
+                        parameters: Vec::new(),
+                        return_type: None,
                         file_path: file_path.to_string(),
-                        span: self.node_to_span(name_node),
+                        name_span: self.node_to_span(name_node),
+                        full_span: self.node_to_span(node),
                         visibility,
+                        flags: self.symbol_flags(node, file_path, content),
+                        configs: Vec::new(),
                         doc: None,
+                        doc_tags: None,
                         sig_hash,
                     };
 
+                    self.type_argument_edges(&symbol.id, node, &[node.child_by_field_name("type")], content, file_path, edges);
+                    self.annotation_references(node, &symbol.id, content, file_path, edges, occurrences, context);
                     symbols.push(symbol.clone());
 
                     occurrences.push(OccurrenceIR {
@@ -708,6 +943,10 @@ impl JavaHarness {
                         token: name,
                     });
                 }
+                // Initializers run as part of constructing the class
+                if let Some(value) = child.child_by_field_name("value") {
+                    self.walk_node(value, content, file_path, symbols, edges, occurrences, context)?;
+                }
             }
         }
         Ok(())
@@ -753,13 +992,20 @@ impl JavaHarness {
             name: name.clone(),
             fqn: fqn.clone(),
             signature: Some(signature),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if is_public { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
+        self.annotation_references(node, &symbol.id, content, file_path, edges, occurrences, context);
         symbols.push(symbol.clone());
 
         occurrences.push(OccurrenceIR {
@@ -784,10 +1030,16 @@ impl JavaHarness {
             name: name.clone(),
             fqn: constructor_fqn,
             signature: Some(format!("{}({})", name, params.join(", "))),
+            parameters: self.get_parameters(node, content),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if is_public { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: constructor_sig_hash,
         };
         
@@ -822,6 +1074,9 @@ impl JavaHarness {
             }
         }
 
+        let declared_types = [node.child_by_field_name("parameters"), node.child_by_field_name("interfaces")];
+        self.type_argument_edges(&symbol.id, node, &declared_types, content, file_path, edges);
+
         // Process record body (methods, compact constructor, etc)
         if let Some(body) = node.child_by_field_name("body") {
             for child in body.children(&mut body.walk()) {
@@ -845,45 +1100,73 @@ impl JavaHarness {
         Ok(())
     }
 
+    /// A Reference occurrence on an annotation, under the qualified name it
+    /// most likely refers to (see [`Self::annotation_reference`]); the
+    /// store links it to the `@interface` once the whole repo is indexed
     fn handle_annotation_usage(
         &self,
         node: Node,
         content: &str,
         file_path: &str,
         occurrences: &mut Vec<OccurrenceIR>,
-    ) -> Result<()> {
-        // Extract annotation name
-        let annotation_name = if let Some(name_node) = node.child_by_field_name("name") {
-            self.get_text(name_node, content)
-        } else {
-            // For marker annotations, get the identifier directly
-            for child in node.children(&mut node.walk()) {
-                if child.kind() == "identifier" {
-                    return Ok({
-                        let name = self.get_text(child, content);
-                        occurrences.push(OccurrenceIR {
-                            file_path: file_path.to_string(),
-                            symbol_id: Some(format!("@{}", name)),
-                            role: OccurrenceRole::Reference,
-                            span: self.node_to_span(child),
-                            token: format!("@{}", name),
-                        });
-                    });
-                }
-            }
-            return Ok(());
-        };
-        
-        // Create reference occurrence for the annotation
+        context: &ParseContext,
+    ) -> Option<String> {
+        let name_node = node.child_by_field_name("name")?;
+        let name = self.get_text(name_node, content);
         occurrences.push(OccurrenceIR {
             file_path: file_path.to_string(),
-            symbol_id: Some(format!("@{}", annotation_name)),
+            symbol_id: Some(self.annotation_reference(&name, context)),
             role: OccurrenceRole::Reference,
             span: self.node_to_span(node),
-            token: format!("@{}", annotation_name),
+            token: format!("@{}", name),
         });
-        
-        Ok(())
+        Some(name)
+    }
+
+    /// The annotations on a declaration: an occurrence for each and a
+    /// `References` edge to it from `owner_id`, tagged with the annotation
+    /// name as written
+    fn annotation_references(
+        &self,
+        declaration: Node,
+        owner_id: &str,
+        content: &str,
+        file_path: &str,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &ParseContext,
+    ) {
+        let Some(modifiers) = declaration.children(&mut declaration.walk()).find(|c| c.kind() == "modifiers") else {
+            return;
+        };
+        for annotation in modifiers.children(&mut modifiers.walk()) {
+            if !matches!(annotation.kind(), "annotation" | "marker_annotation") {
+                continue;
+            }
+            let Some(name) = self.handle_annotation_usage(annotation, content, file_path, occurrences, context) else {
+                continue;
+            };
+            edges.push(EdgeIR {
+                edge_type: EdgeType::References,
+                src: Some(owner_id.to_string()),
+                dst: Some(self.annotation_reference(&name, context)),
+                file_src: Some(file_path.to_string()),
+                file_dst: None,
+                resolution: protocol::Resolution::Syntactic,
+                meta: HashMap::from([("annotation".to_string(), serde_json::json!(name))]),
+                provenance: HashMap::new(),
+            });
+        }
+    }
+
+    /// The qualified name an annotation written as `name` most likely
+    /// refers to: as written when qualified, else through this file's
+    /// imports, `java.lang` or its package
+    fn annotation_reference(&self, name: &str, context: &ParseContext) -> String {
+        if JAVA_LANG_ANNOTATIONS.contains(&name) && !context.imports.contains_key(name) {
+            return format!("java.lang.{}", name);
+        }
+        qualified_type_name(name, context)
     }
     
     fn handle_annotation(
@@ -892,6 +1175,7 @@ impl JavaHarness {
         content: &str,
         file_path: &str,
         symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
         context: &mut ParseContext,
     ) -> Result<()> {
@@ -912,13 +1196,20 @@ impl JavaHarness {
             name: format!("@{}", name),
             fqn,
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if is_public { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
+        self.annotation_references(node, &symbol.id, content, file_path, edges, occurrences, context);
         symbols.push(symbol.clone());
 
         occurrences.push(OccurrenceIR {
@@ -939,7 +1230,7 @@ impl JavaHarness {
                     // Handle annotation method/element
                     self.handle_annotation_method(child, content, file_path, symbols, occurrences, context)?;
                 } else {
-                    self.walk_node(child, content, file_path, symbols, &mut Vec::new(), occurrences, context)?;
+                    self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
                 }
             }
         }
@@ -987,10 +1278,16 @@ impl JavaHarness {
                 name: name.clone(),
                 fqn,
                 signature: Some(signature),
+                parameters: Vec::new(),
+                return_type: None,
                 file_path: file_path.to_string(),
-                span: self.node_to_span(name_node),
+                name_span: self.node_to_span(name_node),
+                full_span: self.node_to_span(node),
                 visibility: Some("public".to_string()), // Annotation methods are implicitly public
+                flags: self.symbol_flags(node, file_path, content),
+                configs: Vec::new(),
                 doc: None,
+                doc_tags: None,
                 sig_hash,
             };
             
@@ -1015,10 +1312,24 @@ impl JavaHarness {
         file_path: &str,
         edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
+        context: &ParseContext,
     ) -> Result<()> {
         if let Some(name_node) = node.child_by_field_name("name") {
             let method_name = self.get_text(name_node, content);
-            let from_id = format!("{}#{}", file_path, self.get_file_fqn(file_path));
+            let from_id = self.caller_id(file_path, context);
+
+            // Inputs for picking among overloads: how many arguments were
+            // passed and any explicit type arguments, `this.<T>call()`
+            let mut meta = HashMap::new();
+            let arg_count = node
+                .child_by_field_name("arguments")
+                .map_or(0, |args| args.named_children(&mut args.walk()).filter(|a| !a.is_extra()).count());
+            meta.insert("arg_count".to_string(), serde_json::json!(arg_count));
+            if let Some(type_args) = node.child_by_field_name("type_arguments") {
+                let types: Vec<String> =
+                    type_args.named_children(&mut type_args.walk()).map(|t| self.get_text(t, content)).collect();
+                meta.insert("type_arguments".to_string(), serde_json::json!(types));
+            }
 
             edges.push(EdgeIR {
                 edge_type: EdgeType::Calls,
@@ -1027,7 +1338,7 @@ impl JavaHarness {
                 file_src: Some(file_path.to_string()),
                 file_dst: None,
                 resolution: protocol::Resolution::Syntactic,
-                meta: HashMap::new(),
+                meta,
                 provenance: HashMap::new(),
             });
 
@@ -1042,12 +1353,76 @@ impl JavaHarness {
         Ok(())
     }
 
+    /// `new Type(...)`, and anonymous classes `new Type() { ... }`: named
+    /// after the line they start on, like lambdas, so that their methods
+    /// are callers in their own right rather than the enclosing method
+    fn handle_object_creation(
+        &self,
+        node: Node,
+        content: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        let body = node.children(&mut node.walk()).find(|c| c.kind() == "class_body");
+        for child in node.children(&mut node.walk()) {
+            if Some(child) != body {
+                self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
+            }
+        }
+        let Some(body) = body else {
+            return Ok(());
+        };
+
+        let name = format!("anonymous_{}", node.start_position().row);
+        let fqn = context.build_fqn(&name);
+        let type_node = node.child_by_field_name("type").unwrap_or(node);
+        let symbol = SymbolIR {
+            id: format!("{}#{}", file_path, fqn),
+            lang: ProtoLanguage::Java,
+            lang_version: None,
+            kind: SymbolKind::Class,
+            name: name.clone(),
+            doc_tags: None,
+            sig_hash: format!("{:x}", md5::compute(&fqn)),
+            fqn,
+            signature: Some(format!("new {}() {{...}}", self.get_text(type_node, content))),
+            parameters: Vec::new(),
+            return_type: None,
+            file_path: file_path.to_string(),
+            name_span: self.node_to_span(type_node),
+            full_span: self.node_to_span(node),
+            visibility: None,
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
+            doc: None,
+        };
+        occurrences.push(OccurrenceIR {
+            file_path: file_path.to_string(),
+            symbol_id: Some(symbol.id.clone()),
+            role: OccurrenceRole::Definition,
+            span: symbol.name_span.clone(),
+            token: name.clone(),
+        });
+        symbols.push(symbol);
+
+        context.push_class(name);
+        for child in body.children(&mut body.walk()) {
+            self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
+        }
+        context.pop_class();
+        Ok(())
+    }
+
     fn handle_lambda(
         &self,
         node: Node,
         content: &str,
         file_path: &str,
         symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
         context: &mut ParseContext,
     ) -> Result<()> {
@@ -1077,10 +1452,16 @@ impl JavaHarness {
             name: lambda_id.clone(),
             fqn,
             signature: Some(signature),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(node),
+            name_span: self.node_to_span(node),
+            full_span: self.node_to_span(node),
             visibility: None,
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
         
@@ -1094,117 +1475,679 @@ impl JavaHarness {
             token: lambda_id,
         });
         
-        // Walk the body to find any calls or references inside
+        context.push_scope(Some(symbol.fqn));
+        if let Some(params_node) = node.child_by_field_name("parameters") {
+            self.declare_parameters(params_node, content, file_path, symbols, occurrences, context);
+        }
+        // Walk the body to find any calls or references inside; its
+        // calls count as the enclosing method's
         if let Some(body) = node.child_by_field_name("body") {
-            self.walk_node(body, content, file_path, symbols, &mut Vec::new(), occurrences, context)?;
+            self.walk_node(body, content, file_path, symbols, edges, occurrences, context)?;
         }
+        context.pop_scope();
         
         Ok(())
     }
     
-    fn handle_method_reference(
+    /// `switch` statements and expressions: the selector, constant and
+    /// pattern labels, and uses of pattern variables in guards and arms
+    fn handle_switch(
         &self,
         node: Node,
         content: &str,
         file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
         edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
     ) -> Result<()> {
-        // Method references like String::toUpperCase or System.out::println
-        let full_text = self.get_text(node, content);
-        let from_id = format!("{}#{}", file_path, self.get_file_fqn(file_path));
-        
-        // Split on :: to get the method name
-        let parts: Vec<&str> = full_text.split("::").collect();
-        if parts.len() == 2 {
-            let method_name = parts[1];
-            
-            edges.push(EdgeIR {
-                edge_type: EdgeType::Calls,
-                src: Some(from_id),
-                dst: Some(method_name.to_string()),
-                file_src: Some(file_path.to_string()),
-                file_dst: None,
-                resolution: protocol::Resolution::Syntactic,
-                meta: HashMap::new(),
-                provenance: HashMap::new(),
-            });
-            
-            occurrences.push(OccurrenceIR {
-                file_path: file_path.to_string(),
-                symbol_id: Some(full_text.clone()),
-                role: OccurrenceRole::Reference,
-                span: self.node_to_span(node),
-                token: full_text,
-            });
+        if let Some(condition) = node.child_by_field_name("condition") {
+            // A local's or field's read is recorded, resolved, by walking the condition
+            if let Some(selector) = condition.named_child(0).filter(|n| n.kind() == "identifier") {
+                if self.variable_id(selector, content, file_path, context).is_none() {
+                    self.push_occurrence(selector, content, file_path, None, OccurrenceRole::Read, occurrences);
+                }
+            }
+            self.walk_node(condition, content, file_path, symbols, edges, occurrences, context)?;
+        }
+        let Some(body) = node.child_by_field_name("body") else {
+            return Ok(());
+        };
+        let src_id = self.caller_id(file_path, context);
+        // Arrow rules and `case X:` statement groups alike
+        for arm in body.named_children(&mut body.walk()) {
+            let mut bound = Vec::new();
+            for child in arm.named_children(&mut arm.walk()) {
+                if child.kind() != "switch_label" {
+                    continue;
+                }
+                for label in child.named_children(&mut child.walk()) {
+                    match label.kind() {
+                        "pattern" | "record_pattern" | "type_pattern" => {
+                            self.handle_pattern(label, &src_id, content, file_path, &mut bound, edges, occurrences);
+                        }
+                        // `case RED ->`: an enum constant or constant variable
+                        "identifier" => {
+                            let name = self.get_text(label, content);
+                            self.push_occurrence(label, content, file_path, Some(name), OccurrenceRole::Reference, occurrences);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            for child in arm.named_children(&mut arm.walk()) {
+                let guard = child.kind() == "switch_label";
+                let scope = if guard {
+                    child.named_children(&mut child.walk()).find(|n| n.kind() == "guard")
+                } else {
+                    Some(child)
+                };
+                if let Some(scope) = scope {
+                    self.push_uses(scope, content, file_path, &bound, occurrences);
+                    self.walk_node(scope, content, file_path, symbols, edges, occurrences, context)?;
+                }
+            }
         }
-        
         Ok(())
     }
-    
-    fn handle_static_initializer(
+
+    /// `x instanceof Shape s` and `x instanceof Point(int a, int b)`
+    fn handle_instanceof(
         &self,
         node: Node,
         content: &str,
         file_path: &str,
         symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
         context: &mut ParseContext,
     ) -> Result<()> {
-        // Create a symbol for the static initializer block
-        let class_name = context.current_class().unwrap_or_else(|| "Unknown".to_string());
-        let block_id = format!("static_init_{}", node.start_position().row);
-        let fqn = context.build_fqn(&block_id);
-        let sig_hash = format!("{:x}", md5::compute(&fqn));
-        
-        let symbol = SymbolIR {
-            id: format!("{}#{}", file_path, fqn),
-            lang: ProtoLanguage::Java,
-            lang_version: None,
-            kind: SymbolKind::Method, // Static initializers are like special methods
-            name: "<clinit>".to_string(), // Java bytecode name for static initializer
-            fqn,
-            signature: Some("static {}".to_string()),
-            file_path: file_path.to_string(),
-            span: self.node_to_span(node),
-            visibility: None, // Static initializers have no visibility modifier
-            doc: None,
-            sig_hash,
-        };
-        
-        symbols.push(symbol.clone());
-        
-        occurrences.push(OccurrenceIR {
-            file_path: file_path.to_string(),
-            symbol_id: Some(symbol.id),
-            role: OccurrenceRole::Definition,
-            span: self.node_to_span(node),
-            token: "static".to_string(),
-        });
-        
-        // Walk the body to find any method calls or references
-        for child in node.children(&mut node.walk()) {
-            if child.kind() != "static" && child.kind() != "{" && child.kind() != "}" {
-                self.walk_node(child, content, file_path, symbols, &mut Vec::new(), occurrences, context)?;
+        if let Some(left) = node.child_by_field_name("left") {
+            if left.kind() == "identifier" && self.variable_id(left, content, file_path, context).is_none() {
+                self.push_occurrence(left, content, file_path, None, OccurrenceRole::Read, occurrences);
             }
+            self.walk_node(left, content, file_path, symbols, edges, occurrences, context)?;
+        }
+        let src_id = self.caller_id(file_path, context);
+        let name = node.child_by_field_name("name");
+        if let Some(right) = node.child_by_field_name("right") {
+            // A plain `x instanceof Shape` tests the type without a pattern
+            let via = if name.is_some() { "type" } else { "type_test" };
+            self.pattern_type(right, via, &src_id, content, file_path, edges, occurrences);
+        }
+        if let Some(name) = name {
+            self.push_occurrence(name, content, file_path, None, OccurrenceRole::Write, occurrences);
+        }
+        if let Some(pattern) = node.child_by_field_name("pattern") {
+            self.handle_pattern(pattern, &src_id, content, file_path, &mut Vec::new(), edges, occurrences);
         }
-        
         Ok(())
     }
-    
-    fn handle_instance_initializer(
+
+    /// The types a pattern tests against, referenced from `src_id`, and
+    /// the variables it binds, whose names are added to `bound`
+    fn handle_pattern(
         &self,
         node: Node,
+        src_id: &str,
         content: &str,
         file_path: &str,
-        symbols: &mut Vec<SymbolIR>,
+        bound: &mut Vec<String>,
+        edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
-        context: &mut ParseContext,
-    ) -> Result<()> {
-        // Create a symbol for the instance initializer block
-        let class_name = context.current_class().unwrap_or_else(|| "Unknown".to_string());
-        let block_id = format!("instance_init_{}", node.start_position().row);
-        let fqn = context.build_fqn(&block_id);
+    ) {
+        match node.kind() {
+            // `Shape s`, or a record component `int a`
+            "type_pattern" | "record_pattern_component" => {
+                for child in node.named_children(&mut node.walk()) {
+                    match child.kind() {
+                        "identifier" => {
+                            bound.push(self.get_text(child, content));
+                            self.push_occurrence(child, content, file_path, None, OccurrenceRole::Write, occurrences);
+                        }
+                        "record_pattern" => {
+                            self.handle_pattern(child, src_id, content, file_path, bound, edges, occurrences)
+                        }
+                        "underscore_pattern" => {}
+                        _ => self.pattern_type(child, "type", src_id, content, file_path, edges, occurrences),
+                    }
+                }
+            }
+            // `Point(int a, int b)`
+            "record_pattern" => {
+                for child in node.named_children(&mut node.walk()) {
+                    match child.kind() {
+                        "identifier" | "scoped_identifier" | "generic_type" => {
+                            self.pattern_type(child, "record", src_id, content, file_path, edges, occurrences);
+                        }
+                        "record_pattern_body" => {
+                            for component in child.named_children(&mut child.walk()) {
+                                self.handle_pattern(component, src_id, content, file_path, bound, edges, occurrences);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {
+                for child in node.named_children(&mut node.walk()) {
+                    self.handle_pattern(child, src_id, content, file_path, bound, edges, occurrences);
+                }
+            }
+        }
+    }
+
+    /// A Reference occurrence and `References` edge for the type a pattern
+    /// tests against, `via` a `type` or `record` pattern. `var` and
+    /// primitive types aren't types to reference.
+    fn pattern_type(
+        &self,
+        type_node: Node,
+        via: &str,
+        src_id: &str,
+        content: &str,
+        file_path: &str,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+    ) {
+        // Record patterns name their type with a plain identifier
+        let name = match type_node.kind() {
+            "identifier" | "scoped_identifier" => Some(self.get_text(type_node, content)),
+            _ => self.named_type(type_node, content),
+        };
+        let Some(name) = name.filter(|name| name != "var") else {
+            return;
+        };
+        self.push_occurrence(type_node, content, file_path, Some(name.clone()), OccurrenceRole::Reference, occurrences);
+        let mut meta = HashMap::new();
+        meta.insert("pattern".to_string(), serde_json::json!(via));
+        edges.push(EdgeIR {
+            edge_type: EdgeType::References,
+            src: Some(src_id.to_string()),
+            dst: Some(name),
+            file_src: Some(file_path.to_string()),
+            file_dst: None,
+            resolution: protocol::Resolution::Syntactic,
+            meta,
+            provenance: HashMap::new(),
+        });
+    }
+
+    /// Read occurrences of the `names` a pattern bound, within `node`
+    fn push_uses(&self, node: Node, content: &str, file_path: &str, names: &[String], occurrences: &mut Vec<OccurrenceIR>) {
+        if names.is_empty() {
+            return;
+        }
+        if node.kind() == "identifier" {
+            if names.contains(&self.get_text(node, content)) {
+                self.push_occurrence(node, content, file_path, None, OccurrenceRole::Read, occurrences);
+            }
+            return;
+        }
+        for child in node.named_children(&mut node.walk()) {
+            // A field access `p.x` names the field, not a variable
+            if node.kind() == "field_access" && node.child_by_field_name("field") == Some(child) {
+                continue;
+            }
+            self.push_uses(child, content, file_path, names, occurrences);
+        }
+    }
+
+    /// Whether an `identifier` reached by the walk uses a variable, rather
+    /// than declaring one or naming a label or lambda parameter
+    fn is_variable_use(&self, node: Node) -> bool {
+        let Some(parent) = node.parent() else {
+            return false;
+        };
+        parent.child_by_field_name("name") != Some(node)
+            && !matches!(
+                parent.kind(),
+                "labeled_statement" | "break_statement" | "continue_statement" | "inferred_parameters" | "lambda_expression"
+            )
+    }
+
+    /// Record a use of the variable or field `node` names: a parameter or
+    /// local, a field of an enclosing class (`count`, `this.count`), or,
+    /// unresolved, the field of some other object (`p.x`). A bare name that
+    /// is neither, such as a class name, is skipped.
+    fn push_variable_use(
+        &self,
+        node: Node,
+        content: &str,
+        file_path: &str,
+        role: OccurrenceRole,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &ParseContext,
+    ) {
+        match node.kind() {
+            "identifier" => {
+                if let Some(symbol_id) = self.variable_id(node, content, file_path, context) {
+                    self.push_occurrence(node, content, file_path, Some(symbol_id), role, occurrences);
+                }
+            }
+            "field_access" => {
+                let Some(field) = node.child_by_field_name("field") else {
+                    return;
+                };
+                let symbol_id = node
+                    .child_by_field_name("object")
+                    .filter(|object| object.kind() == "this")
+                    .and_then(|_| self.field_id(field, &self.get_text(field, content), false, content, file_path, context));
+                self.push_occurrence(field, content, file_path, symbol_id, role, occurrences);
+            }
+            _ => {}
+        }
+    }
+
+    /// Symbol id of the parameter, local or field the bare name `identifier`
+    /// refers to
+    fn variable_id(&self, identifier: Node, content: &str, file_path: &str, context: &ParseContext) -> Option<String> {
+        let name = self.get_text(identifier, content);
+        if let Some(symbol_id) = context.local(&name) {
+            return Some(symbol_id.to_string());
+        }
+        self.field_id(identifier, &name, true, content, file_path, context)
+    }
+
+    /// Symbol id of the field `name` declared by the innermost class around
+    /// `from` that has one, unless (with `shadowing`) a parameter, local or
+    /// pattern variable of that name is in scope at `from` first
+    fn field_id(
+        &self,
+        from: Node,
+        name: &str,
+        shadowing: bool,
+        content: &str,
+        file_path: &str,
+        context: &ParseContext,
+    ) -> Option<String> {
+        // Each class body walked pushed its class, innermost last
+        let mut depth = context.class_stack.len();
+        let mut previous = from;
+        let mut scope = from.parent();
+        while let Some(node) = scope {
+            match node.kind() {
+                "class_body" | "interface_body" | "enum_body" | "annotation_type_body" => {
+                    if self.declares_field(node, name, content) {
+                        let classes = context.class_stack.get(..depth)?;
+                        let fqn = context
+                            .package
+                            .iter()
+                            .chain(classes)
+                            .map(String::as_str)
+                            .chain([name])
+                            .collect::<Vec<_>>()
+                            .join(".");
+                        return Some(format!("{}#{}", file_path, fqn));
+                    }
+                    depth = depth.checked_sub(1)?;
+                }
+                _ if shadowing && self.declares_before(node, previous, name, content) => return None,
+                _ => {}
+            }
+            previous = node;
+            scope = node.parent();
+        }
+        None
+    }
+
+    fn declares_field(&self, body: Node, name: &str, content: &str) -> bool {
+        body.named_children(&mut body.walk()).any(|member| match member.kind() {
+            "field_declaration" => member
+                .named_children(&mut member.walk())
+                .filter(|c| c.kind() == "variable_declarator")
+                .any(|d| self.is_named(d, name, content)),
+            // An enum's fields follow its constants
+            "enum_body_declarations" => self.declares_field(member, name, content),
+            _ => false,
+        })
+    }
+
+    /// Whether a child of `scope` before `child` declares a variable `name`
+    /// visible in `child`
+    fn declares_before(&self, scope: Node, child: Node, name: &str, content: &str) -> bool {
+        scope
+            .named_children(&mut scope.walk())
+            .take_while(|sibling| *sibling != child)
+            .any(|sibling| self.binds(scope, sibling, name, content))
+    }
+
+    fn binds(&self, scope: Node, node: Node, name: &str, content: &str) -> bool {
+        match node.kind() {
+            "local_variable_declaration" => node
+                .named_children(&mut node.walk())
+                .filter(|c| c.kind() == "variable_declarator")
+                .any(|d| self.is_named(d, name, content)),
+            "formal_parameters" => node.named_children(&mut node.walk()).any(|parameter| match parameter.kind() {
+                "formal_parameter" => self.is_named(parameter, name, content),
+                // `String... args`
+                "spread_parameter" => parameter
+                    .named_children(&mut parameter.walk())
+                    .any(|c| c.kind() == "variable_declarator" && self.is_named(c, name, content)),
+                _ => false,
+            }),
+            "inferred_parameters" => {
+                node.named_children(&mut node.walk()).any(|p| self.get_text(p, content) == name)
+            }
+            "catch_formal_parameter" | "resource" => self.is_named(node, name, content),
+            "resource_specification" => node.named_children(&mut node.walk()).any(|r| self.binds(node, r, name, content)),
+            // `x -> ...`, `for (Item x : items)`
+            "identifier" => {
+                let declared = scope.child_by_field_name("parameters").or_else(|| {
+                    Some(scope).filter(|s| s.kind() == "enhanced_for_statement").and_then(|s| s.child_by_field_name("name"))
+                });
+                declared == Some(node) && self.get_text(node, content) == name
+            }
+            // `o instanceof Shape s && s.area() > 0`, `case Circle c -> c.r`
+            _ if matches!(
+                scope.kind(),
+                "binary_expression" | "if_statement" | "while_statement" | "ternary_expression" | "switch_rule" | "switch_block_statement_group"
+            ) =>
+            {
+                self.binds_pattern(node, name, content)
+            }
+            _ => false,
+        }
+    }
+
+    fn binds_pattern(&self, node: Node, name: &str, content: &str) -> bool {
+        let bound = match node.kind() {
+            "instanceof_expression" => node.child_by_field_name("name"),
+            "type_pattern" | "record_pattern_component" => {
+                node.named_children(&mut node.walk()).find(|c| c.kind() == "identifier")
+            }
+            _ => None,
+        };
+        bound.is_some_and(|b| self.get_text(b, content) == name)
+            || node.named_children(&mut node.walk()).any(|c| self.binds_pattern(c, name, content))
+    }
+
+    fn is_named(&self, node: Node, name: &str, content: &str) -> bool {
+        node.child_by_field_name("name").is_some_and(|n| self.get_text(n, content) == name)
+    }
+
+    fn push_occurrence(
+        &self,
+        node: Node,
+        content: &str,
+        file_path: &str,
+        symbol_id: Option<String>,
+        role: OccurrenceRole,
+        occurrences: &mut Vec<OccurrenceIR>,
+    ) {
+        occurrences.push(OccurrenceIR {
+            file_path: file_path.to_string(),
+            symbol_id,
+            role,
+            span: self.node_to_span(node),
+            token: self.get_text(node, content),
+        });
+    }
+
+    /// A local variable declaration, enhanced `for` variable, resource or
+    /// caught exception: its initializer is walked before the variable is
+    /// in scope, the rest of an enhanced `for` after
+    fn handle_local_declaration(
+        &self,
+        node: Node,
+        content: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        let type_name = node
+            .child_by_field_name("type")
+            .or_else(|| node.named_children(&mut node.walk()).find(|c| c.kind() == "catch_type"))
+            .map(|t| self.get_text(t, content));
+        let declarators: Vec<Node> = if node.kind() == "local_variable_declaration" {
+            node.children_by_field_name("declarator", &mut node.walk()).collect()
+        } else {
+            vec![node]
+        };
+        if let Some(modifiers) = node.children(&mut node.walk()).find(|c| c.kind() == "modifiers") {
+            self.walk_node(modifiers, content, file_path, symbols, edges, occurrences, context)?;
+        }
+        for declarator in declarators {
+            if let Some(value) = declarator.child_by_field_name("value") {
+                self.walk_node(value, content, file_path, symbols, edges, occurrences, context)?;
+            }
+            if let Some(name_node) = declarator.child_by_field_name("name") {
+                self.declare_local(node, name_node, type_name.clone(), SymbolKind::Variable, content, file_path, symbols, occurrences, context);
+            }
+        }
+        if node.kind() == "enhanced_for_statement" {
+            if let Some(body) = node.child_by_field_name("body") {
+                self.walk_node(body, content, file_path, symbols, edges, occurrences, context)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parameter symbols for a method's or lambda's `params_node`: formal
+    /// parameters, `String... rest`, or a lambda's bare `(a, b)` / `x`
+    fn declare_parameters(
+        &self,
+        params_node: Node,
+        content: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) {
+        if !context.tracks_locals() {
+            return;
+        }
+        let params: Vec<Node> = if params_node.kind() == "identifier" {
+            vec![params_node]
+        } else {
+            params_node.named_children(&mut params_node.walk()).collect()
+        };
+        for param in params {
+            match param.kind() {
+                "identifier" => {
+                    self.declare_local(param, param, None, SymbolKind::Parameter, content, file_path, symbols, occurrences, context);
+                }
+                "formal_parameter" => {
+                    if let Some(name_node) = param.child_by_field_name("name") {
+                        let type_name = param.child_by_field_name("type").map(|t| self.get_text(t, content));
+                        self.declare_local(param, name_node, type_name, SymbolKind::Parameter, content, file_path, symbols, occurrences, context);
+                    }
+                }
+                "spread_parameter" => {
+                    let children: Vec<Node> = param.named_children(&mut param.walk()).collect();
+                    let type_name = children
+                        .iter()
+                        .find(|c| !matches!(c.kind(), "modifiers" | "variable_declarator"))
+                        .map(|t| format!("{}...", self.get_text(*t, content)));
+                    let name_node = children
+                        .iter()
+                        .find(|c| c.kind() == "variable_declarator")
+                        .and_then(|d| d.child_by_field_name("name"));
+                    if let Some(name_node) = name_node {
+                        self.declare_local(param, name_node, type_name, SymbolKind::Parameter, content, file_path, symbols, occurrences, context);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// A `Parameter` or `Variable` symbol named by `name_node`, scoped to
+    /// the enclosing method, initializer or lambda. A name declared twice
+    /// there (in sibling blocks) gets the line it's declared on, `total@12`.
+    fn declare_local(
+        &self,
+        declaration: Node,
+        name_node: Node,
+        type_name: Option<String>,
+        kind: SymbolKind,
+        content: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) {
+        let Some(scope) = context.scope_fqn() else {
+            return;
+        };
+        let name = self.get_text(name_node, content);
+        let mut fqn = format!("{}.{}", scope, name);
+        if symbols.iter().any(|s| s.fqn == fqn && s.file_path == file_path) {
+            fqn = format!("{}@{}", fqn, name_node.start_position().row);
+        }
+        let signature = match &type_name {
+            Some(type_name) => format!("{} {}", type_name, name),
+            None => name.clone(),
+        };
+        let symbol = SymbolIR {
+            id: format!("{}#{}", file_path, fqn),
+            lang: ProtoLanguage::Java,
+            lang_version: None,
+            kind,
+            name: name.clone(),
+            doc_tags: None,
+            sig_hash: format!("{:x}", md5::compute(format!("{}{}", fqn, signature))),
+            fqn,
+            signature: Some(signature),
+            parameters: Vec::new(),
+            return_type: None,
+            file_path: file_path.to_string(),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(declaration),
+            visibility: None,
+            flags: self.symbol_flags(declaration, file_path, content),
+            configs: Vec::new(),
+            doc: None,
+        };
+        occurrences.push(OccurrenceIR {
+            file_path: file_path.to_string(),
+            symbol_id: Some(symbol.id.clone()),
+            role: OccurrenceRole::Definition,
+            span: symbol.name_span.clone(),
+            token: name.clone(),
+        });
+        context.declare(name, symbol.id.clone());
+        symbols.push(symbol);
+    }
+
+    fn handle_method_reference(
+        &self,
+        node: Node,
+        content: &str,
+        file_path: &str,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &ParseContext,
+    ) -> Result<()> {
+        // Method references like String::toUpperCase or System.out::println
+        let full_text = self.get_text(node, content);
+        let from_id = self.caller_id(file_path, context);
+        
+        // Split on :: to get the method name
+        let parts: Vec<&str> = full_text.split("::").collect();
+        if parts.len() == 2 {
+            let method_name = parts[1];
+            
+            edges.push(EdgeIR {
+                edge_type: EdgeType::Calls,
+                src: Some(from_id),
+                dst: Some(method_name.to_string()),
+                file_src: Some(file_path.to_string()),
+                file_dst: None,
+                resolution: protocol::Resolution::Syntactic,
+                meta: HashMap::new(),
+                provenance: HashMap::new(),
+            });
+            
+            occurrences.push(OccurrenceIR {
+                file_path: file_path.to_string(),
+                symbol_id: Some(full_text.clone()),
+                role: OccurrenceRole::Reference,
+                span: self.node_to_span(node),
+                token: full_text,
+            });
+        }
+        
+        Ok(())
+    }
+    
+    fn handle_static_initializer(
+        &self,
+        node: Node,
+        content: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        // Create a symbol for the static initializer block
+        let class_name = context.current_class().unwrap_or_else(|| "Unknown".to_string());
+        let block_id = format!("static_init_{}", node.start_position().row);
+        let fqn = context.build_fqn(&block_id);
+        let sig_hash = format!("{:x}", md5::compute(&fqn));
+        
+        let symbol = SymbolIR {
+            id: format!("{}#{}", file_path, fqn),
+            lang: ProtoLanguage::Java,
+            lang_version: None,
+            kind: SymbolKind::Method, // Static initializers are like special methods
+            name: "<clinit>".to_string(), // Java bytecode name for static initializer
+            fqn,
+            signature: Some("static {}".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
+            file_path: file_path.to_string(),
+            name_span: self.node_to_span(node),
+            full_span: self.node_to_span(node),
+            visibility: None, // Static initializers have no visibility modifier
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
+            doc: None,
+            doc_tags: None,
+            sig_hash,
+        };
+        
+        symbols.push(symbol.clone());
+        
+        occurrences.push(OccurrenceIR {
+            file_path: file_path.to_string(),
+            symbol_id: Some(symbol.id.clone()),
+            role: OccurrenceRole::Definition,
+            span: self.node_to_span(node),
+            token: "static".to_string(),
+        });
+        
+        // Walk the body to find any method calls or references
+        context.method_stack.push(symbol.id.clone());
+        context.push_scope(Some(symbol.fqn.clone()));
+        for child in node.children(&mut node.walk()) {
+            if child.kind() != "static" && child.kind() != "{" && child.kind() != "}" {
+                self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
+            }
+        }
+        context.pop_scope();
+        context.method_stack.pop();
+        
+        Ok(())
+    }
+    
+    fn handle_instance_initializer(
+        &self,
+        node: Node,
+        content: &str,
+        file_path: &str,
+        symbols: &mut Vec<SymbolIR>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+        context: &mut ParseContext,
+    ) -> Result<()> {
+        // Create a symbol for the instance initializer block
+        let class_name = context.current_class().unwrap_or_else(|| "Unknown".to_string());
+        let block_id = format!("instance_init_{}", node.start_position().row);
+        let fqn = context.build_fqn(&block_id);
         let sig_hash = format!("{:x}", md5::compute(&fqn));
         
         let symbol = SymbolIR {
@@ -1215,10 +2158,16 @@ impl JavaHarness {
             name: "<init>".to_string(), // Java bytecode name for instance initializer
             fqn,
             signature: Some("{}".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(node),
+            name_span: self.node_to_span(node),
+            full_span: self.node_to_span(node),
             visibility: None, // Instance initializers have no visibility modifier
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
         
@@ -1226,24 +2175,318 @@ impl JavaHarness {
         
         occurrences.push(OccurrenceIR {
             file_path: file_path.to_string(),
-            symbol_id: Some(symbol.id),
+            symbol_id: Some(symbol.id.clone()),
             role: OccurrenceRole::Definition,
             span: self.node_to_span(node),
             token: "{".to_string(),
         });
         
         // Walk the body
+        context.method_stack.push(symbol.id.clone());
+        context.push_scope(Some(symbol.fqn.clone()));
         for child in node.children(&mut node.walk()) {
             if child.kind() != "{" && child.kind() != "}" {
-                self.walk_node(child, content, file_path, symbols, &mut Vec::new(), occurrences, context)?;
+                self.walk_node(child, content, file_path, symbols, edges, occurrences, context)?;
             }
         }
+        context.pop_scope();
+        context.method_stack.pop();
         
         Ok(())
     }
 
     // Helper methods
 
+    /// `References` edges from `src_id` to the types used as type arguments
+    /// anywhere in `types`, e.g. `Foo` in `extends Base<Foo>` or a
+    /// `Map<String, List<Foo>>` field, with the generic type each is an
+    /// argument of. Type variables of `declaration` and its enclosing
+    /// declarations (`List<T>`) aren't types to reference.
+    fn type_argument_edges(
+        &self,
+        src_id: &str,
+        declaration: Node,
+        types: &[Option<Node>],
+        content: &str,
+        file_path: &str,
+        edges: &mut Vec<EdgeIR>,
+    ) {
+        let mut type_variables = Vec::new();
+        let mut scope = Some(declaration);
+        while let Some(node) = scope {
+            if let Some(params) = node.child_by_field_name("type_parameters") {
+                for param in params.named_children(&mut params.walk()) {
+                    if let Some(name) = param.named_children(&mut param.walk()).find(|c| c.kind() == "type_identifier") {
+                        type_variables.push(self.get_text(name, content));
+                    }
+                }
+            }
+            scope = node.parent();
+        }
+
+        let mut pending: Vec<Node> = types.iter().flatten().copied().collect();
+        pending.reverse();
+        while let Some(node) = pending.pop() {
+            let children: Vec<Node> = node.named_children(&mut node.walk()).collect();
+            pending.extend(children.iter().rev());
+            if node.kind() != "generic_type" {
+                continue;
+            }
+            let Some(generic) = children.first().map(|raw| self.get_text(*raw, content)) else {
+                continue;
+            };
+            let Some(arguments) = children.iter().find(|c| c.kind() == "type_arguments") else {
+                continue;
+            };
+            for argument in arguments.named_children(&mut arguments.walk()) {
+                let Some(name) = self.named_type(argument, content) else {
+                    continue;
+                };
+                if type_variables.contains(&name) {
+                    continue;
+                }
+                let mut meta = HashMap::new();
+                meta.insert("type_argument_of".to_string(), serde_json::json!(generic));
+                edges.push(EdgeIR {
+                    edge_type: EdgeType::References,
+                    src: Some(src_id.to_string()),
+                    dst: Some(name),
+                    file_src: Some(file_path.to_string()),
+                    file_dst: None,
+                    resolution: protocol::Resolution::Syntactic,
+                    meta,
+                    provenance: HashMap::new(),
+                });
+            }
+        }
+    }
+
+    /// `Permits` edges from a sealed class or interface to the subtypes its
+    /// `permits` clause lists. Without one, the subtypes are those in the
+    /// same file, linked once the whole file is walked.
+    fn permits_edges(
+        &self,
+        declaration: Node,
+        symbol: &SymbolIR,
+        modifiers: &[String],
+        content: &str,
+        file_path: &str,
+        edges: &mut Vec<EdgeIR>,
+        context: &mut ParseContext,
+    ) {
+        if !modifiers.iter().any(|m| m == "sealed") {
+            return;
+        }
+        let Some(permits) = declaration.child_by_field_name("permits") else {
+            context.implicitly_sealed.push((symbol.id.clone(), symbol.name.clone()));
+            return;
+        };
+        let Some(types) = permits.named_child(0) else {
+            return;
+        };
+        for subtype in types.named_children(&mut types.walk()) {
+            if let Some(subtype) = self.named_type(subtype, content) {
+                edges.push(EdgeIR {
+                    edge_type: EdgeType::Permits,
+                    src: Some(symbol.id.clone()),
+                    dst: Some(subtype),
+                    file_src: Some(file_path.to_string()),
+                    file_dst: None,
+                    resolution: protocol::Resolution::Syntactic,
+                    meta: HashMap::new(),
+                    provenance: HashMap::new(),
+                });
+            }
+        }
+    }
+
+    /// The exception type a `throw` raises: `X` for `throw new X(...)`, and
+    /// for a rethrown `catch (X e)` parameter its declared type (the first
+    /// alternative of a multi-catch)
+    fn thrown_type(&self, throw: Node, content: &str) -> Option<String> {
+        let thrown = throw.named_child(0)?;
+        match thrown.kind() {
+            "object_creation_expression" => self.named_type(thrown.child_by_field_name("type")?, content),
+            "identifier" => {
+                let name = self.get_text(thrown, content);
+                let mut scope = throw.parent();
+                while let Some(node) = scope {
+                    if node.kind() == "catch_clause" {
+                        let parameter = node.named_children(&mut node.walk()).find(|c| c.kind() == "catch_formal_parameter")?;
+                        let declared = parameter.child_by_field_name("name").map(|n| self.get_text(n, content));
+                        if declared.as_deref() == Some(name.as_str()) {
+                            let catch_type = parameter.named_children(&mut parameter.walk()).find(|c| c.kind() == "catch_type")?;
+                            return self.named_type(catch_type.named_child(0)?, content);
+                        }
+                    }
+                    if matches!(node.kind(), "method_declaration" | "constructor_declaration" | "lambda_expression") {
+                        break;
+                    }
+                    scope = node.parent();
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// `Throws` edge to an exception type as written; `via` is `declared`
+    /// for a `throws` clause and `thrown` for a `throw` statement
+    fn push_throws_edge(&self, src_id: &str, exception: String, via: &str, file_path: &str, edges: &mut Vec<EdgeIR>) {
+        let mut meta = HashMap::new();
+        meta.insert("via".to_string(), serde_json::json!(via));
+        edges.push(EdgeIR {
+            edge_type: EdgeType::Throws,
+            src: Some(src_id.to_string()),
+            dst: Some(exception),
+            file_src: Some(file_path.to_string()),
+            file_dst: None,
+            resolution: protocol::Resolution::Syntactic,
+            meta,
+            provenance: HashMap::new(),
+        });
+    }
+
+    /// The class or interface a type names: `List` for `List<Foo>`, `Foo`
+    /// for `Foo[]` or `? extends Foo`; `None` for primitives
+    fn named_type(&self, type_node: Node, content: &str) -> Option<String> {
+        match type_node.kind() {
+            "type_identifier" | "scoped_type_identifier" => Some(self.get_text(type_node, content)),
+            "generic_type" => self.named_type(type_node.named_child(0)?, content),
+            "array_type" => self.named_type(type_node.child_by_field_name("element")?, content),
+            "wildcard" | "annotated_type" => {
+                let last = type_node.named_child(type_node.named_child_count().checked_sub(1)?)?;
+                self.named_type(last, content)
+            }
+            _ => None,
+        }
+    }
+
+    /// `DependsOn` edges from a bean class to the types injected into it.
+    ///
+    /// Covers `@Autowired`/`@Inject`/`@Resource` fields and setters, annotated
+    /// constructors, and the implicit constructor injection Spring applies to a
+    /// stereotype class (`@Component`, `@Service`, ...) with a single constructor.
+    fn injection_edges(
+        &self,
+        class_modifiers: &[String],
+        body: Node,
+        content: &str,
+        file_path: &str,
+        class_id: &str,
+        context: &ParseContext,
+    ) -> Vec<EdgeIR> {
+        let mut edges = Vec::new();
+        let is_bean = class_modifiers
+            .iter()
+            .any(|m| SPRING_STEREOTYPES.contains(&annotation_name(m)));
+        let constructors: Vec<Node> = body
+            .children(&mut body.walk())
+            .filter(|c| c.kind() == "constructor_declaration")
+            .collect();
+
+        for member in body.children(&mut body.walk()) {
+            let modifiers = self.get_modifiers(member, content);
+            let annotation = modifiers
+                .iter()
+                .map(|m| annotation_name(m))
+                .find(|m| INJECT_ANNOTATIONS.contains(m));
+
+            match member.kind() {
+                "field_declaration" => {
+                    let Some(annotation) = annotation else { continue };
+                    let Some(type_node) = member.child_by_field_name("type") else { continue };
+                    let bean = self.bean_reference(&self.get_text(type_node, content), context);
+                    for declarator in member.children(&mut member.walk()) {
+                        if declarator.kind() != "variable_declarator" {
+                            continue;
+                        }
+                        if let Some(name_node) = declarator.child_by_field_name("name") {
+                            let member_name = self.get_text(name_node, content);
+                            edges.push(self.injection_edge(
+                                class_id, bean.clone(), file_path, "field", &member_name, annotation,
+                            ));
+                        }
+                    }
+                }
+                "constructor_declaration" | "method_declaration" => {
+                    let injection = if member.kind() == "method_declaration" {
+                        "setter"
+                    } else {
+                        "constructor"
+                    };
+                    let annotation = match annotation {
+                        Some(annotation) => annotation,
+                        // Spring autowires the sole constructor of a bean implicitly
+                        None if injection == "constructor" && is_bean && constructors.len() == 1 => "implicit",
+                        None => continue,
+                    };
+                    let member_name = member
+                        .child_by_field_name("name")
+                        .map(|n| self.get_text(n, content))
+                        .unwrap_or_default();
+                    let Some(params) = member.child_by_field_name("parameters") else { continue };
+                    for param in params.children(&mut params.walk()) {
+                        if param.kind() != "formal_parameter" {
+                            continue;
+                        }
+                        if let Some(type_node) = param.child_by_field_name("type") {
+                            let bean = self.bean_reference(&self.get_text(type_node, content), context);
+                            edges.push(self.injection_edge(
+                                class_id, bean, file_path, injection, &member_name, annotation,
+                            ));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        edges
+    }
+
+    /// The bean an injected type names, qualified through the file's
+    /// imports and package, and the file declaring it: a bean declared in
+    /// the repository is referred to by its symbol id, others by their
+    /// qualified name
+    fn bean_reference(&self, injected_type: &str, context: &ParseContext) -> (String, Option<String>) {
+        let bean_type = injected_bean_type(injected_type);
+        // `Repository<User>` is a `Repository` bean
+        let bean_name = bean_type.split('<').next().unwrap_or(&bean_type).trim();
+        let bean_fqn = qualified_type_name(bean_name, context);
+        match self.source_roots.resolve_import(&bean_fqn) {
+            Some(file) => (format!("{}#{}", file, bean_fqn), Some(file)),
+            None => (bean_fqn, None),
+        }
+    }
+
+    fn injection_edge(
+        &self,
+        class_id: &str,
+        (dst, file_dst): (String, Option<String>),
+        file_path: &str,
+        injection: &str,
+        member: &str,
+        annotation: &str,
+    ) -> EdgeIR {
+        let mut meta = HashMap::new();
+        meta.insert("injection".to_string(), serde_json::json!(injection));
+        meta.insert("annotation".to_string(), serde_json::json!(annotation));
+        if !member.is_empty() {
+            meta.insert("member".to_string(), serde_json::json!(member));
+        }
+
+        EdgeIR {
+            edge_type: EdgeType::DependsOn,
+            src: Some(class_id.to_string()),
+            dst: Some(dst),
+            file_src: Some(file_path.to_string()),
+            file_dst,
+            resolution: protocol::Resolution::Syntactic,
+            meta,
+            provenance: HashMap::new(),
+        }
+    }
+
     fn get_text(&self, node: Node, content: &str) -> String {
         content[node.byte_range()].to_string()
     }
@@ -1277,6 +2520,16 @@ impl JavaHarness {
         None
     }
     
+    /// The description of the preceding doc comment and its structured
+    /// block tags
+    fn doc_comment(&self, node: Node, content: &str) -> (Option<String>, Option<DocTags>) {
+        let Some(comment) = self.get_preceding_comment(node, content) else {
+            return (None, None);
+        };
+        let (description, tags) = javadoc::split(&comment);
+        (Some(description).filter(|d| !d.is_empty()), tags)
+    }
+    
     fn clean_comment(&self, comment: String) -> String {
         let comment = comment.trim();
         
@@ -1333,6 +2586,71 @@ impl JavaHarness {
         modifiers
     }
 
+    /// Flags from stability annotations, a Javadoc `@deprecated` tag, and
+    /// Maven/Gradle test source sets
+    fn symbol_flags(&self, node: Node, file_path: &str, content: &str) -> SymbolFlags {
+        let mut flags = SymbolFlags::empty();
+        for modifier in self.get_modifiers(node, content) {
+            if !modifier.starts_with('@') {
+                continue;
+            }
+            match annotation_name(&modifier) {
+                "Deprecated" => flags.insert(SymbolFlags::DEPRECATED),
+                "Beta" | "Experimental" | "Incubating" | "ApiStatus.Experimental" => {
+                    flags.insert(SymbolFlags::EXPERIMENTAL)
+                }
+                "Generated" => flags.insert(SymbolFlags::GENERATED),
+                "VisibleForTesting" | "Test" | "ParameterizedTest" | "BeforeEach" | "AfterEach" => {
+                    flags.insert(SymbolFlags::TEST_ONLY)
+                }
+                _ => {}
+            }
+        }
+        if let Some(doc) = self.get_preceding_comment(node, content) {
+            flags.set_if(SymbolFlags::DEPRECATED, doc.contains("@deprecated"));
+        }
+        flags.set_if(SymbolFlags::TEST_ONLY, file_path.contains("src/test/"));
+        flags
+    }
+
+    /// Formal parameters of a method or constructor, or the components of a record
+    fn get_parameters(&self, node: Node, content: &str) -> Vec<Parameter> {
+        let Some(params_node) = node.child_by_field_name("parameters") else {
+            return Vec::new();
+        };
+        let mut parameters = Vec::new();
+        for param in params_node.named_children(&mut params_node.walk()) {
+            match param.kind() {
+                "formal_parameter" | "record_component" => parameters.push(Parameter {
+                    name: param
+                        .child_by_field_name("name")
+                        .map(|n| self.get_text(n, content))
+                        .unwrap_or_default(),
+                    type_name: param.child_by_field_name("type").map(|t| self.get_text(t, content)),
+                    ..Default::default()
+                }),
+                // `String... args`: the type and declarator are unnamed children
+                "spread_parameter" => {
+                    let mut parameter = Parameter { variadic: true, ..Default::default() };
+                    for child in param.named_children(&mut param.walk()) {
+                        match child.kind() {
+                            "modifiers" => {}
+                            "variable_declarator" => {
+                                if let Some(name_node) = child.child_by_field_name("name") {
+                                    parameter.name = self.get_text(name_node, content);
+                                }
+                            }
+                            _ => parameter.type_name = Some(self.get_text(child, content)),
+                        }
+                    }
+                    parameters.push(parameter);
+                }
+                _ => {}
+            }
+        }
+        parameters
+    }
+
     fn get_method_signature(&self, node: Node, content: &str) -> String {
         let mut sig = String::new();
 
@@ -1414,36 +2732,243 @@ impl JavaHarness {
         sig
     }
 
-    fn extract_import_path(&self, node: Node, content: &str) -> String {
-        for child in node.children(&mut node.walk()) {
-            match child.kind() {
-                "scoped_identifier" => return self.get_text(child, content),
-                "identifier" => return self.get_text(child, content),
-                "asterisk" => {} // Handle wildcard imports
-                _ => {}
-            }
-        }
-        String::new()
+    fn extract_import_path(&self, node: Node, content: &str) -> String {
+        for child in node.children(&mut node.walk()) {
+            match child.kind() {
+                "scoped_identifier" => return self.get_text(child, content),
+                "identifier" => return self.get_text(child, content),
+                "asterisk" => {} // Handle wildcard imports
+                _ => {}
+            }
+        }
+        String::new()
+    }
+
+    /// The symbol a call or method reference is made from: the enclosing
+    /// method, else the class whose field initializer it is in, else the file
+    fn caller_id(&self, file_path: &str, context: &ParseContext) -> String {
+        if let Some(method_id) = context.method_stack.last() {
+            return method_id.clone();
+        }
+        if context.class_stack.is_empty() {
+            return format!("{}#{}", file_path, self.get_file_fqn(file_path));
+        }
+        let class_fqn = context.package.iter().chain(&context.class_stack).cloned().collect::<Vec<_>>().join(".");
+        format!("{}#{}", file_path, class_fqn)
+    }
+
+    fn get_file_fqn(&self, file_path: &str) -> String {
+        // Relative to the source root the path is the package path, so
+        // `src/main/java/com/acme/Foo.java` becomes `com.acme.Foo`
+        self.source_roots
+            .strip_root(file_path)
+            .replace('/', ".")
+            .replace(".java", "")
+    }
+}
+
+/// Annotations usable without an import
+const JAVA_LANG_ANNOTATIONS: &[&str] = &["Override", "Deprecated", "SuppressWarnings", "SafeVarargs", "FunctionalInterface"];
+
+/// Annotations that mark a field, constructor or setter as an injection point
+const INJECT_ANNOTATIONS: &[&str] = &["Autowired", "Inject", "Resource"];
+
+/// Spring stereotypes whose single constructor is autowired without annotation
+const SPRING_STEREOTYPES: &[&str] = &[
+    "Component",
+    "Service",
+    "Repository",
+    "Controller",
+    "RestController",
+    "Configuration",
+];
+
+/// Wrappers whose type argument is the bean actually being injected
+const INJECTION_WRAPPERS: &[&str] = &["Provider", "ObjectProvider", "Optional", "Lazy"];
+
+/// Simple name of an annotation modifier: `@org.x.Autowired(required = false)` -> `Autowired`
+/// A class or interface signature led by its `sealed` or `non-sealed`
+/// modifier, if it has one
+fn with_sealing(modifiers: &[String], signature: String) -> Option<String> {
+    let sealing = modifiers.iter().find(|m| *m == "sealed" || *m == "non-sealed");
+    let signature = match sealing {
+        Some(sealing) => format!("{} {}", sealing, signature).trim_end().to_string(),
+        None => signature,
+    };
+    (!signature.is_empty()).then_some(signature)
+}
+
+/// `Permits` edges from each sealed type without a `permits` clause to
+/// the types of its file that extend or implement it, which is what Java
+/// permits in that case
+fn permit_same_file_subtypes(sealed: &[(String, String)], edges: &mut Vec<EdgeIR>) {
+    let mut permitted = Vec::new();
+    for (sealed_id, sealed_name) in sealed {
+        for edge in edges.iter().filter(|e| matches!(e.edge_type, EdgeType::Extends | EdgeType::Implements)) {
+            let (Some(subtype), Some(supertype)) = (&edge.src, &edge.dst) else {
+                continue;
+            };
+            // `Shape<T>` and `com.acme.Shape` name `Shape`
+            let supertype = supertype.split('<').next().unwrap_or(supertype);
+            if supertype.rsplit('.').next() != Some(sealed_name.as_str()) {
+                continue;
+            }
+            let subtype_name = subtype.rsplit(['.', '#']).next().unwrap_or(subtype);
+            let mut meta = HashMap::new();
+            meta.insert("implicit".to_string(), serde_json::json!(true));
+            permitted.push(EdgeIR {
+                edge_type: EdgeType::Permits,
+                src: Some(sealed_id.clone()),
+                dst: Some(subtype_name.to_string()),
+                file_src: edge.file_src.clone(),
+                file_dst: None,
+                resolution: protocol::Resolution::Syntactic,
+                meta,
+                provenance: HashMap::new(),
+            });
+        }
+    }
+    edges.extend(permitted);
+}
+
+/// Flag calls that can only reach a method of this file through its
+/// varargs parameter: no fixed-arity overload takes that many arguments,
+/// but a variadic one does
+fn mark_varargs_calls(symbols: &[SymbolIR], edges: &mut [EdgeIR]) {
+    for edge in edges.iter_mut().filter(|e| e.edge_type == EdgeType::Calls) {
+        let (Some(name), Some(arg_count)) = (&edge.dst, edge.meta.get("arg_count").and_then(|n| n.as_u64())) else {
+            continue;
+        };
+        let arg_count = arg_count as usize;
+        let overloads: Vec<&SymbolIR> =
+            symbols.iter().filter(|s| s.kind == SymbolKind::Method && &s.name == name).collect();
+        let variadic = |s: &SymbolIR| s.parameters.last().is_some_and(|p| p.variadic);
+        let fixed_match = overloads.iter().any(|s| !variadic(s) && s.parameters.len() == arg_count);
+        let varargs_match = overloads.iter().any(|s| variadic(s) && arg_count + 1 >= s.parameters.len());
+        if varargs_match && !fixed_match {
+            edge.meta.insert("varargs".to_string(), serde_json::json!(true));
+        }
+    }
+}
+
+fn annotation_name(modifier: &str) -> &str {
+    let name = modifier.trim_start_matches('@');
+    let name = name.split('(').next().unwrap_or(name).trim();
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// The qualified name a type written as `name` most likely refers to:
+/// through this file's imports (also for the outer type of `Outer.Inner`),
+/// as written when otherwise qualified, else in the file's package
+fn qualified_type_name(name: &str, context: &ParseContext) -> String {
+    let (head, rest) = match name.split_once('.') {
+        Some((head, rest)) => (head, Some(rest)),
+        None => (name, None),
+    };
+    if let Some(import) = context.imports.get(head) {
+        return match rest {
+            Some(rest) => format!("{}.{}", import, rest),
+            None => import.clone(),
+        };
+    }
+    if rest.is_some() {
+        return name.to_string();
+    }
+    match &context.package {
+        Some(package) => format!("{}.{}", package, name),
+        None => name.to_string(),
     }
+}
 
-    fn get_file_fqn(&self, file_path: &str) -> String {
-        file_path.replace('/', ".").replace(".java", "")
+/// The bean type behind an injected type, unwrapping `Provider<T>`-style wrappers
+fn injected_bean_type(type_text: &str) -> String {
+    let type_text = type_text.trim();
+    if let Some((outer, rest)) = type_text.split_once('<') {
+        let outer = outer.rsplit('.').next().unwrap_or(outer).trim();
+        if INJECTION_WRAPPERS.contains(&outer) {
+            return injected_bean_type(rest.strip_suffix('>').unwrap_or(rest));
+        }
     }
+    type_text.to_string()
 }
 
 struct ParseContext {
     package: Option<String>,
     class_stack: Vec<String>,
+    /// Ids of the methods, constructors and initializers being walked,
+    /// innermost last: the callers of the calls found in them
+    method_stack: Vec<String>,
+    /// Simple name to qualified name, from single-type imports
+    imports: HashMap<String, String>,
+    /// Parameters and local variables visible at this point, innermost
+    /// block last; `None` unless local symbols are on
+    scopes: Option<Vec<LocalScope>>,
+    /// Id and name of each sealed type without a `permits` clause
+    implicitly_sealed: Vec<(String, String)>,
+}
+
+/// A method, lambda or block and the variables declared directly in it
+struct LocalScope {
+    /// FQN the variables are scoped to: the innermost method, initializer
+    /// or lambda
+    fqn: String,
+    /// Name and symbol id
+    variables: Vec<(String, String)>,
 }
 
 impl ParseContext {
-    fn new() -> Self {
+    fn new(track_locals: bool) -> Self {
         Self {
             package: None,
             class_stack: Vec::new(),
+            method_stack: Vec::new(),
+            imports: HashMap::new(),
+            scopes: track_locals.then(Vec::new),
+            implicitly_sealed: Vec::new(),
+        }
+    }
+
+    fn tracks_locals(&self) -> bool {
+        self.scopes.is_some()
+    }
+
+    /// Open a scope; blocks pass `None` and keep the enclosing FQN
+    fn push_scope(&mut self, fqn: Option<String>) {
+        if let Some(scopes) = &mut self.scopes {
+            // A block outside any method (never valid Java) gets an empty
+            // FQN and declares nothing
+            let fqn = fqn.or_else(|| scopes.last().map(|s| s.fqn.clone())).unwrap_or_default();
+            scopes.push(LocalScope { fqn, variables: Vec::new() });
+        }
+    }
+
+    fn pop_scope(&mut self) {
+        if let Some(scopes) = &mut self.scopes {
+            scopes.pop();
+        }
+    }
+
+    fn scope_fqn(&self) -> Option<String> {
+        self.scopes.as_ref()?.last().map(|s| s.fqn.clone()).filter(|fqn| !fqn.is_empty())
+    }
+
+    fn declare(&mut self, name: String, symbol_id: String) {
+        if let Some(scope) = self.scopes.as_mut().and_then(|scopes| scopes.last_mut()) {
+            scope.variables.push((name, symbol_id));
         }
     }
 
+    /// Symbol id of the parameter or local `name` refers to here
+    fn local(&self, name: &str) -> Option<&str> {
+        self.scopes
+            .as_ref()?
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.variables.iter().rev())
+            .find(|(variable, _)| variable == name)
+            .map(|(_, symbol_id)| symbol_id.as_str())
+    }
+
     fn push_class(&mut self, name: String) {
         self.class_stack.push(name);
     }
@@ -1490,6 +3015,7 @@ mod edge_case_extreme_tests;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use protocol::DocTag;
 
     #[test]
     fn test_parse_class() -> Result<()> {
@@ -1679,6 +3205,11 @@ public class TestClass {
         let method_doc = test_method.unwrap().doc.as_ref();
         assert!(method_doc.is_some());
         assert!(method_doc.unwrap().contains("test method"));
+        assert!(!method_doc.unwrap().contains("@param"));
+        let tags = test_method.unwrap().doc_tags.as_ref().unwrap();
+        assert_eq!(tags.params, [DocTag { name: "x".to_string(), text: "the input value".to_string() }]);
+        assert_eq!(tags.returns.as_deref(), Some("the result"));
+        assert!(test_class.unwrap().doc_tags.is_none());
         
         Ok(())
     }
@@ -1741,6 +3272,74 @@ public class Box<T> {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_parameters() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+public class Log {
+    public void write(final String format, Object... args) {}
+}
+
+record Point(int x, int y) {}
+"#;
+
+        let (symbols, _, _) = harness.parse("Log.java", content)?;
+
+        let write = symbols.iter().find(|s| s.name == "write").expect("Should find write");
+        let summary: Vec<_> = write.parameters.iter()
+            .map(|p| (p.name.as_str(), p.type_name.as_deref(), p.variadic))
+            .collect();
+        assert_eq!(summary, vec![("format", Some("String"), false), ("args", Some("Object"), true)]);
+        assert_eq!(write.return_type.as_deref(), Some("void"));
+
+        let constructor = symbols.iter()
+            .find(|s| s.name == "Point" && s.kind == SymbolKind::Method)
+            .expect("Should find the record constructor");
+        let names: Vec<_> = constructor.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["x", "y"]);
+        assert_eq!(constructor.return_type, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_flags() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+package com.example;
+
+public class Api {
+    /** @deprecated use {@link #fetchAll()} */
+    public void fetch() {}
+
+    @Deprecated(since = "2.0")
+    public void load() {}
+
+    @Beta
+    public void preview() {}
+
+    @VisibleForTesting
+    void reset() {}
+
+    public void fetchAll() {}
+}
+"#;
+
+        let (symbols, _, _) = harness.parse("src/main/java/com/example/Api.java", content)?;
+        let flags = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().flags;
+        assert_eq!(flags("fetch"), SymbolFlags::DEPRECATED);
+        assert_eq!(flags("load"), SymbolFlags::DEPRECATED);
+        assert_eq!(flags("preview"), SymbolFlags::EXPERIMENTAL);
+        assert_eq!(flags("reset"), SymbolFlags::TEST_ONLY);
+        assert!(flags("fetchAll").is_empty());
+        assert!(flags("Api").is_empty());
+
+        let (symbols, _, _) = harness.parse("src/test/java/com/example/ApiTest.java", "class ApiTest {}")?;
+        assert_eq!(symbols[0].flags, SymbolFlags::TEST_ONLY);
+
+        Ok(())
+    }
+
     #[test]
     fn test_empty_file() -> Result<()> {
         let mut harness = JavaHarness::new()?;
@@ -1770,4 +3369,706 @@ public class {
 
         Ok(())
     }
+
+    #[test]
+    fn test_imports_checked_against_gradle_modules() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("java_gradle_modules_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for module in ["app", "model", "db"] {
+            std::fs::create_dir_all(dir.join(module).join("src/main/java/com/acme").join(module))?;
+        }
+        std::fs::write(dir.join("settings.gradle.kts"), "include(\":app\", \":model\", \":db\")\n")?;
+        std::fs::write(dir.join("app/build.gradle.kts"), "dependencies {\n    implementation(project(\":model\"))\n}\n")?;
+        std::fs::write(dir.join("model/build.gradle.kts"), "")?;
+        std::fs::write(dir.join("db/build.gradle.kts"), "")?;
+        std::fs::write(dir.join("model/src/main/java/com/acme/model/User.java"), "package com.acme.model;")?;
+        std::fs::write(dir.join("db/src/main/java/com/acme/db/Pool.java"), "package com.acme.db;")?;
+
+        let modules = ModuleGraph::detect(&dir);
+        let (module_symbols, module_edges) = modules.to_ir();
+        assert_eq!(module_symbols.len(), 3);
+        assert_eq!(module_edges.len(), 1);
+
+        let mut harness = JavaHarness::new()?
+            .with_source_roots(SourceRoots::detect(&dir))
+            .with_modules(modules);
+        let content = r#"
+package com.acme.app;
+
+import com.acme.model.User;
+import com.acme.db.Pool;
+
+public class Main {}
+"#;
+        let (_, edges, _) = harness.parse("app/src/main/java/com/acme/app/Main.java", content)?;
+        let import = |dst: &str| edges.iter().find(|e| e.dst.as_deref() == Some(dst)).unwrap();
+        assert_eq!(import("com.acme.model.User").meta.get("module"), Some(&serde_json::json!(":model")));
+        assert!(!import("com.acme.model.User").meta.contains_key("undeclared_dependency"));
+        assert_eq!(import("com.acme.db.Pool").meta.get("undeclared_dependency"), Some(&serde_json::json!(true)));
+
+        let _ = std::fs::remove_dir_all(&dir);
+        Ok(())
+    }
+
+    #[test]
+    fn test_imports_resolve_through_source_roots() -> Result<()> {
+        let mut roots = SourceRoots::new();
+        roots.add_root("core/src/main/java");
+        roots.add_file("core/src/main/java/com/acme/model/User.java");
+
+        let mut harness = JavaHarness::new()?.with_source_roots(roots);
+        let content = r#"
+package com.acme.service;
+
+import com.acme.model.User;
+import java.util.List;
+
+public class UserService {}
+"#;
+
+        let (_, edges, _) = harness.parse("core/src/main/java/com/acme/service/UserService.java", content)?;
+
+        let user_import = edges
+            .iter()
+            .find(|e| e.edge_type == EdgeType::Imports && e.dst.as_deref() == Some("com.acme.model.User"))
+            .expect("import of User");
+        assert_eq!(
+            user_import.src.as_deref(),
+            Some("core/src/main/java/com/acme/service/UserService.java#com.acme.service.UserService")
+        );
+        assert_eq!(user_import.file_dst.as_deref(), Some("core/src/main/java/com/acme/model/User.java"));
+        assert_eq!(user_import.meta.get("source_root"), Some(&serde_json::json!("core/src/main/java")));
+
+        let list_import = edges
+            .iter()
+            .find(|e| e.dst.as_deref() == Some("java.util.List"))
+            .expect("import of List");
+        assert!(list_import.file_dst.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_spring_injection_depends_on() -> Result<()> {
+        let mut roots = SourceRoots::new();
+        roots.add_root("src/main/java");
+        roots.add_file("src/main/java/com/acme/payments/PaymentGateway.java");
+        roots.add_file("src/main/java/com/acme/orders/OrderRepository.java");
+        let mut harness = JavaHarness::new()?.with_source_roots(roots);
+        let content = r#"
+package com.acme.orders;
+
+import com.acme.payments.PaymentGateway;
+import java.time.Clock;
+
+@Service
+public class OrderService {
+    @Autowired
+    private PaymentGateway gateway;
+
+    @Inject
+    private Provider<AuditLog> audit;
+
+    private final Clock clock;
+
+    private final OrderRepository repository;
+
+    public OrderService(OrderRepository repository, Clock clock) {
+        this.repository = repository;
+        this.clock = clock;
+    }
+
+    @Autowired
+    public void setNotifier(Notifier notifier) {}
+}
+
+public class PlainPojo {
+    public PlainPojo(Helper helper) {}
+}
+"#;
+
+        let (symbols, edges, _) = harness.parse("src/main/java/com/acme/orders/OrderService.java", content)?;
+        let deps: Vec<_> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::DependsOn)
+            .collect();
+
+        let dep = |dst: &str| deps.iter().find(|e| e.dst.as_deref() == Some(dst)).copied();
+
+        // Imported and declared in the repository: the bean's symbol id
+        let gateway_id = "src/main/java/com/acme/payments/PaymentGateway.java#com.acme.payments.PaymentGateway";
+        let gateway = dep(gateway_id).expect("field injection");
+        let service = symbols.iter().find(|s| s.name == "OrderService").unwrap();
+        assert_eq!(gateway.src.as_deref(), Some(service.id.as_str()));
+        assert_eq!(gateway.file_dst.as_deref(), Some("src/main/java/com/acme/payments/PaymentGateway.java"));
+        assert_eq!(gateway.meta["injection"], "field");
+        assert_eq!(gateway.meta["member"], "gateway");
+
+        assert!(dep("com.acme.orders.AuditLog").is_some(), "Provider<T> should unwrap to T");
+        // Same package, no import needed
+        let repository_id = "src/main/java/com/acme/orders/OrderRepository.java#com.acme.orders.OrderRepository";
+        assert_eq!(dep(repository_id).unwrap().meta["injection"], "constructor");
+        // Imported from outside the repository
+        assert_eq!(dep("java.time.Clock").unwrap().meta["annotation"], "implicit");
+        assert_eq!(dep("com.acme.orders.Notifier").unwrap().meta["injection"], "setter");
+
+        // Not a bean and not annotated: no injection
+        assert!(dep("com.acme.orders.Helper").is_none());
+        assert_eq!(deps.len(), 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_switch_and_pattern_occurrences() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+class Shapes {
+    double area(Object shape, Color color) {
+        if (shape instanceof Circle c) {
+            return c.radius();
+        }
+        String name = switch (color) {
+            case RED -> "red";
+            default -> "other";
+        };
+        return switch (shape) {
+            case Square s when s.side() > 0 -> s.side() * s.side();
+            case Rect(Point(var x, var y), int h) -> x * h;
+            default -> 0;
+        };
+    }
+}
+"#;
+        let (_, edges, occurrences) = harness.parse("Shapes.java", content)?;
+        let found: Vec<(u32, &str, OccurrenceRole, bool)> = occurrences
+            .iter()
+            .filter(|o| o.role != OccurrenceRole::Definition && o.role != OccurrenceRole::Call)
+            .filter(|o| !o.token.contains('('))
+            .map(|o| (o.span.start_line, o.token.as_str(), o.role.clone(), o.symbol_id.is_some()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (3, "shape", OccurrenceRole::Read, false),
+                (3, "Circle", OccurrenceRole::Reference, true),
+                (3, "c", OccurrenceRole::Write, false),
+                (6, "color", OccurrenceRole::Read, false),
+                (7, "RED", OccurrenceRole::Reference, true),
+                (10, "shape", OccurrenceRole::Read, false),
+                (11, "Square", OccurrenceRole::Reference, true),
+                (11, "s", OccurrenceRole::Write, false),
+                (11, "s", OccurrenceRole::Read, false),
+                (11, "s", OccurrenceRole::Read, false),
+                (11, "s", OccurrenceRole::Read, false),
+                (12, "Rect", OccurrenceRole::Reference, true),
+                (12, "Point", OccurrenceRole::Reference, true),
+                (12, "x", OccurrenceRole::Write, false),
+                (12, "y", OccurrenceRole::Write, false),
+                (12, "h", OccurrenceRole::Write, false),
+                (12, "x", OccurrenceRole::Read, false),
+                (12, "h", OccurrenceRole::Read, false),
+            ]
+        );
+        // Calls inside arms and guards are still walked
+        assert_eq!(edges.iter().filter(|e| e.dst.as_deref() == Some("side")).count(), 3);
+        let references: Vec<(&str, &str)> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::References)
+            .map(|e| (e.dst.as_deref().unwrap(), e.meta["pattern"].as_str().unwrap()))
+            .collect();
+        assert_eq!(references, [("Circle", "type"), ("Square", "type"), ("Rect", "record"), ("Point", "record")]);
+        assert!(edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::References)
+            .all(|e| e.src.as_deref() == Some("Shapes.java#Shapes.area")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_java21_pattern_references() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+package geo;
+
+sealed interface Shape permits Circle, Boxed {}
+record Circle(double r) implements Shape {}
+record Boxed<T>(T value, Point corner) implements Shape {}
+record Point(int x, int y) {}
+
+class Render {
+    String describe(Object o) {
+        if (o instanceof Boxed<?>(geo.Circle c, Point(var x, _))) {
+            return "boxed circle at " + x;
+        }
+        if (o instanceof String) {
+            return "text";
+        }
+        return switch (o) {
+            case Circle(double r) when r > 10 -> "big";
+            case Boxed<?> b -> "box";
+            case int[] values -> "ints";
+            default -> "other";
+        };
+    }
+}
+"#;
+        let (_, edges, occurrences) = harness.parse("geo/Render.java", content)?;
+        let references: Vec<(&str, &str)> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::References && e.meta.contains_key("pattern"))
+            .map(|e| (e.dst.as_deref().unwrap(), e.meta["pattern"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            references,
+            [
+                ("Boxed", "record"),
+                ("geo.Circle", "type"),
+                ("Point", "record"),
+                ("String", "type_test"),
+                ("Circle", "record"),
+                ("Boxed", "type"),
+            ]
+        );
+        let types: Vec<&str> = occurrences
+            .iter()
+            .filter(|o| o.role == OccurrenceRole::Reference && o.span.start_line > 9)
+            .filter_map(|o| o.symbol_id.as_deref())
+            .collect();
+        assert_eq!(types, ["Boxed", "geo.Circle", "Point", "String", "Circle", "Boxed"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_calls_attributed_to_enclosing_method() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+package app;
+
+class Worker {
+    private final Queue queue = Queues.create();
+
+    static {
+        Registry.register();
+    }
+
+    Worker() {
+        if (ready()) {
+            start();
+        }
+    }
+
+    void run() {
+        submit(new Runnable() {
+            public void run() {
+                process(load());
+            }
+        });
+        queue.forEach(item -> handle(item));
+    }
+
+    class Inner {
+        void poke() { wake(); }
+    }
+}
+"#;
+        let (symbols, edges, _) = harness.parse("app/Worker.java", content)?;
+        let callers = |callee: &str| -> Vec<String> {
+            edges
+                .iter()
+                .filter(|e| e.edge_type == EdgeType::Calls && e.dst.as_deref() == Some(callee))
+                .filter_map(|e| e.src.as_deref()?.strip_prefix("app/Worker.java#").map(str::to_string))
+                .collect()
+        };
+        assert_eq!(callers("create"), ["app.Worker"]);
+        assert_eq!(callers("register"), ["app.Worker.static_init_6"]);
+        assert_eq!(callers("ready"), ["app.Worker.Worker"]);
+        assert_eq!(callers("start"), ["app.Worker.Worker"]);
+        assert_eq!(callers("submit"), ["app.Worker.run"]);
+        assert_eq!(callers("process"), ["app.Worker.anonymous_17.run"]);
+        assert_eq!(callers("load"), ["app.Worker.anonymous_17.run"]);
+        assert_eq!(callers("handle"), ["app.Worker.run"]);
+        assert_eq!(callers("wake"), ["app.Worker.Inner.poke"]);
+
+        // Nested blocks inside methods aren't instance initializers
+        assert!(!symbols.iter().any(|s| s.name == "<init>"));
+        let anonymous = symbols.iter().find(|s| s.name == "anonymous_17").unwrap();
+        assert_eq!(anonymous.kind, SymbolKind::Class);
+        assert_eq!(anonymous.signature.as_deref(), Some("new Runnable() {...}"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_type_argument_references() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+package app;
+
+class OrderRepo<T> extends Repository<Order> implements Comparable<OrderRepo<T>> {
+    private Map<String, List<LineItem>> items;
+    private List<T> pending;
+    private int count;
+
+    <R> Optional<Customer> find(Set<? extends Region> regions, List<R> extra) { return null; }
+}
+
+interface Source extends Supplier<Invoice[]> {}
+"#;
+        let (_, edges, _) = harness.parse("app/OrderRepo.java", content)?;
+        let references: Vec<(&str, &str, String)> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::References)
+            .map(|e| {
+                (
+                    e.src.as_deref().unwrap().trim_start_matches("app/OrderRepo.java#"),
+                    e.dst.as_deref().unwrap(),
+                    e.meta["type_argument_of"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            references,
+            [
+                ("app.OrderRepo", "Order", "Repository".to_string()),
+                ("app.OrderRepo", "OrderRepo", "Comparable".to_string()),
+                ("app.OrderRepo.items", "String", "Map".to_string()),
+                ("app.OrderRepo.items", "List", "Map".to_string()),
+                ("app.OrderRepo.items", "LineItem", "List".to_string()),
+                ("app.OrderRepo.find", "Customer", "Optional".to_string()),
+                ("app.OrderRepo.find", "Region", "Set".to_string()),
+                ("app.Source", "Invoice", "Supplier".to_string()),
+            ]
+        );
+
+        // Generic supertypes are kept as written, like superclasses
+        let implements: Vec<_> = edges.iter().filter(|e| e.edge_type == EdgeType::Implements).filter_map(|e| e.dst.as_deref()).collect();
+        assert_eq!(implements, ["Comparable<OrderRepo<T>>"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_call_argument_metadata() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+class Log {
+    void log(String format, Object... args) {}
+    void log(String message) {}
+
+    void run() {
+        log("a");
+        log("a %s %s", 1, 2);
+        log("none" /* nothing else */);
+        Collections.<String>emptyList();
+    }
+}
+"#;
+        let (_, edges, _) = harness.parse("Log.java", content)?;
+        let calls: Vec<&EdgeIR> = edges.iter().filter(|e| e.edge_type == EdgeType::Calls).collect();
+        let meta = |i: usize, key: &str| calls[i].meta.get(key).cloned();
+
+        assert_eq!(calls.len(), 4);
+        assert_eq!(meta(0, "arg_count"), Some(serde_json::json!(1)));
+        assert_eq!(meta(0, "varargs"), None);
+        assert_eq!(meta(1, "arg_count"), Some(serde_json::json!(3)));
+        assert_eq!(meta(1, "varargs"), Some(serde_json::json!(true)));
+        assert_eq!(meta(2, "arg_count"), Some(serde_json::json!(1)));
+        assert_eq!(meta(3, "arg_count"), Some(serde_json::json!(0)));
+        assert_eq!(meta(3, "type_arguments"), Some(serde_json::json!(["String"])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_symbols() -> Result<()> {
+        let content = r#"
+package app;
+
+class Totals {
+    int sum(int[] values, int start) {
+        int total = start;
+        for (int v : values) {
+            total += v;
+        }
+        items.forEach(item -> log(item, total));
+        try (Reader reader = open()) {
+            reader.read();
+        } catch (IOException e) {
+            log(e);
+        }
+        for (int v : values) {}
+        this.start = start;
+        return total;
+    }
+}
+"#;
+        // Off by default
+        let (symbols, _, _) = JavaHarness::new()?.parse("Totals.java", content)?;
+        assert!(!symbols.iter().any(|s| matches!(s.kind, SymbolKind::Parameter | SymbolKind::Variable)));
+
+        let mut harness = JavaHarness::new()?.with_local_symbols(true);
+        let (symbols, _, occurrences) = harness.parse("Totals.java", content)?;
+        let locals: Vec<(SymbolKind, &str, &str)> = symbols
+            .iter()
+            .filter(|s| matches!(s.kind, SymbolKind::Parameter | SymbolKind::Variable))
+            .map(|s| (s.kind.clone(), s.fqn.as_str(), s.signature.as_deref().unwrap_or_default()))
+            .collect();
+        assert_eq!(
+            locals,
+            [
+                (SymbolKind::Parameter, "app.Totals.sum.values", "int[] values"),
+                (SymbolKind::Parameter, "app.Totals.sum.start", "int start"),
+                (SymbolKind::Variable, "app.Totals.sum.total", "int total"),
+                (SymbolKind::Variable, "app.Totals.sum.v", "int v"),
+                (SymbolKind::Parameter, "app.Totals.lambda_9.item", "item"),
+                (SymbolKind::Variable, "app.Totals.sum.reader", "Reader reader"),
+                (SymbolKind::Variable, "app.Totals.sum.e", "IOException e"),
+                (SymbolKind::Variable, "app.Totals.sum.v@15", "int v"),
+            ]
+        );
+
+        let uses = |fqn: &str| -> Vec<(u32, OccurrenceRole)> {
+            let id = format!("Totals.java#{}", fqn);
+            occurrences
+                .iter()
+                .filter(|o| o.symbol_id.as_deref() == Some(id.as_str()))
+                .map(|o| (o.span.start_line, o.role.clone()))
+                .collect()
+        };
+        // `this.start` is the field
+        assert_eq!(uses("app.Totals.sum.start"), [(4, OccurrenceRole::Definition), (5, OccurrenceRole::Read), (16, OccurrenceRole::Read)]);
+        assert_eq!(
+            uses("app.Totals.sum.total"),
+            [(5, OccurrenceRole::Definition), (7, OccurrenceRole::Write), (9, OccurrenceRole::Read), (17, OccurrenceRole::Read)]
+        );
+        assert_eq!(uses("app.Totals.sum.v"), [(6, OccurrenceRole::Definition), (7, OccurrenceRole::Read)]);
+        assert_eq!(uses("app.Totals.lambda_9.item"), [(9, OccurrenceRole::Definition), (9, OccurrenceRole::Read)]);
+        assert_eq!(uses("app.Totals.sum.reader"), [(10, OccurrenceRole::Definition), (11, OccurrenceRole::Read)]);
+        assert_eq!(uses("app.Totals.sum.e"), [(12, OccurrenceRole::Definition), (13, OccurrenceRole::Read)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotation_references() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+package com.acme.service;
+
+import com.acme.audit.Audited;
+import com.acme.cache.*;
+
+@Audited
+public class Service {
+    @Inject @Cached(ttl = 60) private Repo repo;
+
+    @Override
+    @com.acme.Timed
+    public String toString() {
+        @SuppressWarnings("unused") int unused = 0;
+        return "service";
+    }
+}
+"#;
+        let (_, edges, occurrences) = harness.parse("Service.java", content)?;
+        let annotations: Vec<(&str, &str, &str)> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::References && e.meta.contains_key("annotation"))
+            .map(|e| (e.src.as_deref().unwrap(), e.dst.as_deref().unwrap(), e.meta["annotation"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            annotations,
+            [
+                ("Service.java#com.acme.service.Service", "com.acme.audit.Audited", "Audited"),
+                ("Service.java#com.acme.service.Service.repo", "com.acme.service.Inject", "Inject"),
+                // A wildcard import leaves the package as the best guess
+                ("Service.java#com.acme.service.Service.repo", "com.acme.service.Cached", "Cached"),
+                ("Service.java#com.acme.service.Service.toString", "java.lang.Override", "Override"),
+                ("Service.java#com.acme.service.Service.toString", "com.acme.Timed", "com.acme.Timed"),
+            ]
+        );
+        let referenced: Vec<(&str, &str)> = occurrences
+            .iter()
+            .filter(|o| o.token.starts_with('@'))
+            .map(|o| (o.token.as_str(), o.symbol_id.as_deref().unwrap()))
+            .collect();
+        assert_eq!(referenced.len(), 6);
+        assert_eq!(referenced[5], ("@SuppressWarnings", "java.lang.SuppressWarnings"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_throws_edges() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+package com.acme;
+
+public class Store {
+    public Store(String path) throws java.io.IOException {
+        if (path == null) throw new IllegalArgumentException("path");
+    }
+
+    public String get(String key) throws MissingKey, StoreException {
+        try {
+            return read(key);
+        } catch (IOException | RuntimeException e) {
+            throw e;
+        } catch (MissingKey missing) {
+            throw new StoreException<String>(missing);
+        }
+    }
+
+    private final Runnable check = () -> { throw new UnsupportedOperationException(); };
+}
+"#;
+        let (_, edges, _) = harness.parse("Store.java", content)?;
+        let throws: Vec<(&str, &str, &str)> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Throws)
+            .map(|e| (e.src.as_deref().unwrap(), e.dst.as_deref().unwrap(), e.meta["via"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            throws,
+            [
+                ("Store.java#com.acme.Store.Store", "java.io.IOException", "declared"),
+                ("Store.java#com.acme.Store.Store", "IllegalArgumentException", "thrown"),
+                ("Store.java#com.acme.Store.get", "MissingKey", "declared"),
+                ("Store.java#com.acme.Store.get", "StoreException", "declared"),
+                // A rethrown catch parameter raises what it was declared to catch
+                ("Store.java#com.acme.Store.get", "IOException", "thrown"),
+                ("Store.java#com.acme.Store.get", "StoreException", "thrown"),
+                ("Store.java#com.acme.Store", "UnsupportedOperationException", "thrown"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_field_reads_and_writes() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+package app;
+
+public class Counter {
+    private int count;
+    private Point origin;
+
+    public Counter(int count) {
+        this.count = count;
+    }
+
+    void tick(Object o) {
+        count++;
+        count += origin.x;
+        origin.y = 0;
+        if (o instanceof Counter count && count.count > 0) {
+            reset();
+        }
+    }
+
+    class View {
+        int shown() { return count; }
+    }
+}
+"#;
+        let (_, _, occurrences) = harness.parse("Counter.java", content)?;
+        let uses: Vec<(u32, &str, &OccurrenceRole, Option<&str>)> = occurrences
+            .iter()
+            .filter(|o| matches!(o.role, OccurrenceRole::Read | OccurrenceRole::Write))
+            .map(|o| (o.span.start_line, o.token.as_str(), &o.role, o.symbol_id.as_deref()))
+            .collect();
+        let count = Some("Counter.java#app.Counter.count");
+        let origin = Some("Counter.java#app.Counter.origin");
+        assert_eq!(
+            uses,
+            [
+                // The parameter shadows the field; `this.count` doesn't
+                (8, "count", &OccurrenceRole::Write, count),
+                (12, "count", &OccurrenceRole::Write, count),
+                (13, "count", &OccurrenceRole::Write, count),
+                (13, "x", &OccurrenceRole::Read, None),
+                (13, "origin", &OccurrenceRole::Read, origin),
+                (14, "y", &OccurrenceRole::Write, None),
+                (14, "origin", &OccurrenceRole::Read, origin),
+                (15, "o", &OccurrenceRole::Read, None),
+                (15, "count", &OccurrenceRole::Write, None),
+                // Past the pattern `count` is the bound variable, whose
+                // own `count` field is unresolved
+                (15, "count", &OccurrenceRole::Read, None),
+                // An inner class reads its outer instance's field
+                (21, "count", &OccurrenceRole::Read, count),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sealed_hierarchies() -> Result<()> {
+        let mut harness = JavaHarness::new()?;
+        let content = r#"
+package geo;
+
+public sealed interface Shape permits Circle, geo.poly.Square {}
+
+public abstract sealed class Node {
+    static final class Leaf extends Node {}
+    non-sealed static class Branch extends geo.Node {}
+}
+
+sealed interface Expr {
+    record Const(int value) implements Expr {}
+    record Neg(Expr operand) implements Expr {}
+}
+"#;
+        let (symbols, edges, _) = harness.parse("Shapes.java", content)?;
+        let permits: Vec<(&str, &str, bool)> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Permits)
+            .map(|e| (e.src.as_deref().unwrap(), e.dst.as_deref().unwrap(), e.meta.contains_key("implicit")))
+            .collect();
+        assert_eq!(
+            permits,
+            [
+                ("Shapes.java#geo.Shape", "Circle", false),
+                ("Shapes.java#geo.Shape", "geo.poly.Square", false),
+                // No `permits`: the subtypes in the same file
+                ("Shapes.java#geo.Node", "Leaf", true),
+                ("Shapes.java#geo.Node", "Branch", true),
+                ("Shapes.java#geo.Expr", "Const", true),
+                ("Shapes.java#geo.Expr", "Neg", true),
+            ]
+        );
+        let signature = |fqn: &str| symbols.iter().find(|s| s.fqn == fqn).and_then(|s| s.signature.clone());
+        assert_eq!(signature("geo.Shape").as_deref(), Some("sealed"));
+        assert_eq!(signature("geo.Node.Branch").as_deref(), Some("non-sealed"));
+        assert_eq!(signature("geo.Node.Leaf"), None);
+        Ok(())
+    }
+    #[test]
+    fn test_capabilities_cover_emitted_edges() {
+        let mut harness = JavaHarness::new().unwrap();
+        let source = r#"
+package com.acme;
+
+import java.io.IOException;
+import java.util.List;
+
+/** Keeps items */
+public sealed class Store extends Base implements AutoCloseable permits Cache {
+    @Inject private Loader loader;
+    private List<Item> items;
+
+    public void load() throws IOException {
+        items = loader.fetch();
+        if (items == null) throw new IOException("none");
+    }
+}
+"#;
+        let (_, edges, occurrences) = harness.parse("Store.java", source).unwrap();
+        for edge in &edges {
+            assert!(CAPABILITIES.emits(&edge.edge_type), "undeclared {:?} edge", edge.edge_type);
+        }
+        for occurrence in &occurrences {
+            assert!(CAPABILITIES.occurrences.contains(&occurrence.role), "undeclared {:?} occurrence", occurrence.role);
+        }
+        assert!(CAPABILITIES.call_edges() && CAPABILITIES.type_edges());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::modules::{BuildModule, ModuleDependency};
+use crate::source_roots::normalize;
+
+/// Sections whose `<groupId>`/`<artifactId>` belong to something other than
+/// the project itself
+const FOREIGN_SECTIONS: &[&str] = &[
+    "parent",
+    "dependencies",
+    "dependencyManagement",
+    "build",
+    "profiles",
+    "reporting",
+    "modules",
+    "pluginRepositories",
+    "repositories",
+    "distributionManagement",
+];
+
+const MAX_PARENT_DEPTH: usize = 16;
+
+/// The parts of a `pom.xml` the module graph needs
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Pom {
+    group_id: Option<String>,
+    artifact_id: String,
+    parent: Option<ParentRef>,
+    modules: Vec<String>,
+    dependencies: Vec<PomDependency>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ParentRef {
+    group_id: String,
+    artifact_id: String,
+    relative_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PomDependency {
+    group_id: String,
+    artifact_id: String,
+    scope: String,
+}
+
+/// Modules of the Maven reactor rooted at `repo_root/pom.xml`, following
+/// `<modules>` recursively. A module's name is `groupId:artifactId`; it
+/// depends on its parent POM and inherits the parent's `<dependencies>`.
+pub fn detect_modules(repo_root: &Path) -> Vec<BuildModule> {
+    let mut poms: Vec<(String, Pom)> = Vec::new();
+    collect_poms(repo_root, "", &mut poms);
+    if poms.is_empty() {
+        return Vec::new();
+    }
+
+    let by_dir: HashMap<&str, &Pom> = poms.iter().map(|(dir, pom)| (dir.as_str(), pom)).collect();
+    poms.iter()
+        .map(|(dir, pom)| BuildModule {
+            name: format!("{}:{}", group_id(pom), pom.artifact_id),
+            dir: dir.clone(),
+            build_file: if dir.is_empty() { "pom.xml".to_string() } else { format!("{}/pom.xml", dir) },
+            dependencies: module_dependencies(dir, pom, &by_dir),
+        })
+        .collect()
+}
+
+/// Declared dependencies, the parent itself, then everything inherited up
+/// the parent chain
+fn module_dependencies(dir: &str, pom: &Pom, by_dir: &HashMap<&str, &Pom>) -> Vec<ModuleDependency> {
+    let mut dependencies: Vec<ModuleDependency> = Vec::new();
+    let mut current = Some((dir.to_string(), pom));
+    // Bounded so a parent cycle can't loop forever
+    for depth in 0..MAX_PARENT_DEPTH {
+        let Some((pom_dir, current_pom)) = current.take() else {
+            break;
+        };
+        let group = group_id(current_pom);
+        for dependency in &current_pom.dependencies {
+            let dependency_group = dependency
+                .group_id
+                .replace("${project.groupId}", &group)
+                .replace("${project.parent.groupId}", &group);
+            push_unique(&mut dependencies, format!("{}:{}", dependency_group, dependency.artifact_id), &dependency.scope);
+        }
+        if let Some(parent) = &current_pom.parent {
+            if depth == 0 {
+                push_unique(&mut dependencies, format!("{}:{}", parent.group_id, parent.artifact_id), "parent");
+            }
+            let parent_dir = parent_dir(&pom_dir, parent);
+            current = by_dir.get(parent_dir.as_str()).map(|p| (parent_dir, *p));
+        }
+    }
+    dependencies
+}
+
+/// Nearer declarations win over inherited ones, as in Maven
+fn push_unique(dependencies: &mut Vec<ModuleDependency>, module: String, configuration: &str) {
+    if !dependencies.iter().any(|d| d.module == module) {
+        dependencies.push(ModuleDependency { module, configuration: configuration.to_string() });
+    }
+}
+
+fn collect_poms(repo_root: &Path, dir: &str, poms: &mut Vec<(String, Pom)>) {
+    if poms.iter().any(|(d, _)| d == dir) {
+        return;
+    }
+    let Ok(content) = std::fs::read_to_string(repo_root.join(dir).join("pom.xml")) else {
+        return;
+    };
+    let pom = parse_pom(&content);
+    let modules = pom.modules.clone();
+    poms.push((dir.to_string(), pom));
+    for module in modules {
+        let child = if dir.is_empty() { normalize(&module) } else { normalize(&format!("{}/{}", dir, module)) };
+        collect_poms(repo_root, &child, poms);
+    }
+}
+
+/// The project's groupId, inherited from the parent when omitted
+fn group_id(pom: &Pom) -> String {
+    pom.group_id
+        .clone()
+        .or_else(|| pom.parent.as_ref().map(|p| p.group_id.clone()))
+        .unwrap_or_default()
+}
+
+/// Repo-relative directory of the parent POM (`relativePath` defaults to `..`)
+fn parent_dir(dir: &str, parent: &ParentRef) -> String {
+    let relative = parent.relative_path.trim_end_matches("pom.xml").trim_end_matches('/');
+    let mut segments: Vec<&str> = dir.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in relative.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            other => segments.push(other),
+        }
+    }
+    segments.join("/")
+}
+
+fn parse_pom(content: &str) -> Pom {
+    let content = strip_comments(content);
+    let own = FOREIGN_SECTIONS
+        .iter()
+        .fold(content.clone(), |text, section| remove_sections(&text, section));
+
+    let parent = sections(&content, "parent").first().map(|parent| ParentRef {
+        group_id: text_of(parent, "groupId").unwrap_or_default(),
+        artifact_id: text_of(parent, "artifactId").unwrap_or_default(),
+        relative_path: text_of(parent, "relativePath").unwrap_or_else(|| "..".to_string()),
+    });
+
+    // Managed dependencies only pin versions, they are not dependencies
+    let unmanaged = remove_sections(&remove_sections(&content, "dependencyManagement"), "build");
+    let dependencies = sections(&unmanaged, "dependency")
+        .iter()
+        .filter_map(|dependency| {
+            Some(PomDependency {
+                group_id: text_of(dependency, "groupId")?,
+                artifact_id: text_of(dependency, "artifactId")?,
+                scope: text_of(dependency, "scope").unwrap_or_else(|| "compile".to_string()),
+            })
+        })
+        .collect();
+
+    Pom {
+        group_id: text_of(&own, "groupId"),
+        artifact_id: text_of(&own, "artifactId").unwrap_or_default(),
+        parent,
+        modules: sections(&content, "module"),
+        dependencies,
+    }
+}
+
+/// Bodies of every `<tag>...</tag>` element (not descending into matches)
+fn sections(content: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut bodies = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else {
+            break;
+        };
+        bodies.push(rest[..end].trim().to_string());
+        rest = &rest[end + close.len()..];
+    }
+    bodies
+}
+
+fn text_of(content: &str, tag: &str) -> Option<String> {
+    sections(content, tag).into_iter().next().filter(|text| !text.is_empty())
+}
+
+fn remove_sections(content: &str, tag: &str) -> String {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find(&open) {
+        out.push_str(&rest[..start]);
+        match rest[start..].find(&close) {
+            Some(end) => rest = &rest[start + end + close.len()..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn strip_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("<!--") {
+        out.push_str(&rest[..start]);
+        rest = rest[start..].find("-->").map_or("", |end| &rest[start + end + 3..]);
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pom() {
+        let pom = parse_pom(
+            r#"
+<project>
+  <parent>
+    <groupId>com.acme</groupId>
+    <artifactId>shop-parent</artifactId>
+    <version>1.0</version>
+  </parent>
+  <artifactId>orders</artifactId>
+  <!-- <dependency><groupId>x</groupId><artifactId>gone</artifactId></dependency> -->
+  <dependencyManagement>
+    <dependencies>
+      <dependency><groupId>com.acme</groupId><artifactId>managed</artifactId></dependency>
+    </dependencies>
+  </dependencyManagement>
+  <dependencies>
+    <dependency>
+      <groupId>${project.groupId}</groupId>
+      <artifactId>model</artifactId>
+    </dependency>
+    <dependency>
+      <groupId>junit</groupId>
+      <artifactId>junit</artifactId>
+      <scope>test</scope>
+    </dependency>
+  </dependencies>
+</project>
+"#,
+        );
+        assert_eq!(pom.group_id, None);
+        assert_eq!(pom.artifact_id, "orders");
+        assert_eq!(pom.parent.as_ref().map(|p| p.artifact_id.as_str()), Some("shop-parent"));
+        let dependencies: Vec<(&str, &str)> = pom
+            .dependencies
+            .iter()
+            .map(|d| (d.artifact_id.as_str(), d.scope.as_str()))
+            .collect();
+        assert_eq!(dependencies, vec![("model", "compile"), ("junit", "test")]);
+    }
+
+    #[test]
+    fn test_detect_reactor_with_parent_inheritance() {
+        let dir = std::env::temp_dir().join(format!("java_maven_modules_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("model")).unwrap();
+        std::fs::create_dir_all(dir.join("orders")).unwrap();
+        std::fs::write(
+            dir.join("pom.xml"),
+            r#"<project>
+  <groupId>com.acme</groupId>
+  <artifactId>shop-parent</artifactId>
+  <packaging>pom</packaging>
+  <modules><module>model</module><module>orders</module></modules>
+  <dependencies>
+    <dependency><groupId>com.acme</groupId><artifactId>model</artifactId><scope>provided</scope></dependency>
+  </dependencies>
+</project>"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("model/pom.xml"),
+            "<project><parent><groupId>com.acme</groupId><artifactId>shop-parent</artifactId></parent><artifactId>model</artifactId></project>",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("orders/pom.xml"),
+            "<project><parent><groupId>com.acme</groupId><artifactId>shop-parent</artifactId></parent><artifactId>orders</artifactId></project>",
+        )
+        .unwrap();
+
+        let modules = detect_modules(&dir);
+        let names: Vec<&str> = modules.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["com.acme:shop-parent", "com.acme:model", "com.acme:orders"]);
+
+        let orders = &modules[2];
+        assert_eq!(orders.dir, "orders");
+        assert_eq!(orders.build_file, "orders/pom.xml");
+        let dependencies: Vec<(&str, &str)> = orders
+            .dependencies
+            .iter()
+            .map(|d| (d.module.as_str(), d.configuration.as_str()))
+            .collect();
+        assert_eq!(
+            dependencies,
+            vec![("com.acme:shop-parent", "parent"), ("com.acme:model", "provided")]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
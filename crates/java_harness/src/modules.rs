@@ -0,0 +1,206 @@
+use protocol::{EdgeIR, EdgeType, Language as ProtoLanguage, Resolution, Span, SymbolFlags, SymbolIR, SymbolKind};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+use crate::{gradle, maven};
+
+/// Dependency configurations whose targets are visible to the module's own
+/// consumers, so an import may reach through them
+const TRANSITIVE_CONFIGURATIONS: &[&str] = &["api", "compile"];
+
+/// A module of a multi-module JVM build and the modules it depends on
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildModule {
+    /// Build-tool name: the Gradle project path `:lib:core`, or the Maven
+    /// `groupId:artifactId`
+    pub name: String,
+    /// Repo-relative module directory, empty for the root project
+    pub dir: String,
+    /// Repo-relative build script declaring the module
+    pub build_file: String,
+    pub dependencies: Vec<ModuleDependency>,
+}
+
+/// A dependency of one module on another module of the same build
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleDependency {
+    pub module: String,
+    /// `implementation`, `api`, `testImplementation`, ...
+    pub configuration: String,
+}
+
+impl ModuleDependency {
+    pub fn is_transitive(&self) -> bool {
+        TRANSITIVE_CONFIGURATIONS.contains(&self.configuration.as_str())
+    }
+}
+
+/// Module structure of a JVM build, used to emit module-level `DependsOn`
+/// edges and to check that file-level imports only cross into modules the
+/// importing module actually declares.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleGraph {
+    modules: Vec<BuildModule>,
+}
+
+impl ModuleGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the Gradle settings and build scripts, and the Maven reactor,
+    /// under `repo_root`
+    pub fn detect(repo_root: &Path) -> Self {
+        let mut graph = Self::new();
+        for module in gradle::detect_modules(repo_root).into_iter().chain(maven::detect_modules(repo_root)) {
+            graph.add_module(module);
+        }
+        graph
+    }
+
+    pub fn add_module(&mut self, module: BuildModule) {
+        self.modules.retain(|m| m.name != module.name);
+        self.modules.push(module);
+    }
+
+    pub fn modules(&self) -> &[BuildModule] {
+        &self.modules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    pub fn module(&self, name: &str) -> Option<&BuildModule> {
+        self.modules.iter().find(|m| m.name == name)
+    }
+
+    /// The innermost module whose directory contains `file_path`
+    pub fn module_for(&self, file_path: &str) -> Option<&BuildModule> {
+        self.modules
+            .iter()
+            .filter(|m| m.dir.is_empty() || file_path.starts_with(&format!("{}/", m.dir)))
+            .max_by_key(|m| m.dir.len())
+    }
+
+    /// Whether `from` can see `to`: declared directly, or exported through a
+    /// chain of `api` dependencies
+    pub fn depends_on(&self, from: &str, to: &str) -> bool {
+        let Some(start) = self.module(from) else {
+            return false;
+        };
+        let mut seen = HashSet::new();
+        let mut queue: VecDeque<&ModuleDependency> = start.dependencies.iter().collect();
+        while let Some(dependency) = queue.pop_front() {
+            if dependency.module == to {
+                return true;
+            }
+            if !seen.insert(dependency.module.as_str()) {
+                continue;
+            }
+            if let Some(module) = self.module(&dependency.module) {
+                queue.extend(module.dependencies.iter().filter(|d| d.is_transitive()));
+            }
+        }
+        false
+    }
+
+    /// Module symbols and the `DependsOn` edges between them. Dependencies on
+    /// modules outside the build are left out.
+    pub fn to_ir(&self) -> (Vec<SymbolIR>, Vec<EdgeIR>) {
+        let symbols = self.modules.iter().map(module_symbol).collect();
+        let mut edges = Vec::new();
+        for module in &self.modules {
+            for dependency in &module.dependencies {
+                let Some(target) = self.module(&dependency.module) else {
+                    continue;
+                };
+                edges.push(EdgeIR {
+                    edge_type: EdgeType::DependsOn,
+                    src: Some(module_id(module)),
+                    dst: Some(module_id(target)),
+                    file_src: Some(module.build_file.clone()),
+                    file_dst: Some(target.build_file.clone()),
+                    resolution: Resolution::Syntactic,
+                    meta: HashMap::from([(
+                        "configuration".to_string(),
+                        serde_json::json!(dependency.configuration),
+                    )]),
+                    provenance: HashMap::from([("source".to_string(), "build_file".to_string())]),
+                });
+            }
+        }
+        (symbols, edges)
+    }
+}
+
+fn module_id(module: &BuildModule) -> String {
+    format!("{}#{}", module.build_file, module.name)
+}
+
+fn module_symbol(module: &BuildModule) -> SymbolIR {
+    let span = Span { start_line: 0, start_col: 0, end_line: 0, end_col: 0 };
+    SymbolIR {
+        id: module_id(module),
+        lang: ProtoLanguage::Java,
+        lang_version: None,
+        kind: SymbolKind::Module,
+        name: module.name.clone(),
+        fqn: module.name.clone(),
+        signature: None,
+        parameters: Vec::new(),
+        return_type: None,
+        file_path: module.build_file.clone(),
+        name_span: span.clone(),
+        full_span: span,
+        visibility: Some("public".to_string()),
+        flags: SymbolFlags::empty(),
+        configs: Vec::new(),
+        doc: None,
+        doc_tags: None,
+        sig_hash: format!("{:x}", md5::compute(&module.name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(name: &str, dir: &str, dependencies: &[(&str, &str)]) -> BuildModule {
+        BuildModule {
+            name: name.to_string(),
+            dir: dir.to_string(),
+            build_file: format!("{}/build.gradle", dir),
+            dependencies: dependencies
+                .iter()
+                .map(|(module, configuration)| ModuleDependency {
+                    module: module.to_string(),
+                    configuration: configuration.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_depends_on_follows_api_chains() {
+        let mut graph = ModuleGraph::new();
+        graph.add_module(module(":app", "app", &[(":service", "implementation")]));
+        graph.add_module(module(":service", "service", &[(":model", "api"), (":db", "implementation")]));
+        graph.add_module(module(":model", "model", &[]));
+        graph.add_module(module(":db", "db", &[]));
+
+        assert!(graph.depends_on(":app", ":service"));
+        assert!(graph.depends_on(":app", ":model"));
+        assert!(!graph.depends_on(":app", ":db"));
+        assert!(!graph.depends_on(":model", ":app"));
+
+        assert_eq!(graph.module_for("service/src/main/java/Foo.java").map(|m| m.name.as_str()), Some(":service"));
+        assert!(graph.module_for("tools/Bar.java").is_none());
+
+        let (symbols, edges) = graph.to_ir();
+        assert_eq!(symbols.len(), 4);
+        assert_eq!(edges.len(), 3);
+        assert_eq!(edges[0].src.as_deref(), Some("app/build.gradle#:app"));
+        assert_eq!(edges[0].dst.as_deref(), Some("service/build.gradle#:service"));
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Conventional Maven/Gradle source directories, relative to a module root
+const CONVENTIONAL_ROOTS: &[&str] = &[
+    "src/main/java",
+    "src/test/java",
+    "src/main/kotlin",
+    "src/test/kotlin",
+    "src/testFixtures/java",
+    "src/integrationTest/java",
+];
+
+/// Directories that never contain first-party sources
+const SKIPPED_DIRS: &[&str] = &["build", "target", "out", "node_modules", "bin"];
+
+/// Java/Kotlin source roots of a repository.
+///
+/// A file's package is its path relative to the source root it lives in, so
+/// knowing the roots lets us turn `src/main/java/com/acme/Foo.java` into
+/// `com.acme.Foo` and map an import of `com.acme.Foo` back onto that file.
+#[derive(Debug, Clone, Default)]
+pub struct SourceRoots {
+    /// Repo-relative roots, longest first so nested roots win
+    roots: Vec<String>,
+    /// Repo-relative paths of all source files under the roots
+    files: HashSet<String>,
+}
+
+impl SourceRoots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Detect source roots under `repo_root` from directory conventions,
+    /// Gradle `sourceSets` and Maven `<sourceDirectory>` declarations
+    pub fn detect(repo_root: &Path) -> Self {
+        let mut roots = Self::new();
+        roots.scan_dir(repo_root, repo_root);
+        roots.collect_files(repo_root);
+        roots
+    }
+
+    /// Register a repo-relative source root
+    pub fn add_root(&mut self, root: &str) {
+        let root = normalize(root);
+        if root.is_empty() || self.roots.contains(&root) {
+            return;
+        }
+        self.roots.push(root);
+        self.roots.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    }
+
+    /// Register a repo-relative source file so imports can resolve to it
+    pub fn add_file(&mut self, file_path: &str) {
+        self.files.insert(normalize(file_path));
+    }
+
+    pub fn roots(&self) -> &[String] {
+        &self.roots
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// The source root containing `file_path`, if any
+    pub fn root_for(&self, file_path: &str) -> Option<&str> {
+        let file_path = normalize(file_path);
+        self.roots
+            .iter()
+            .find(|root| file_path.starts_with(&format!("{}/", root)))
+            .map(|root| root.as_str())
+    }
+
+    /// Path of `file_path` relative to its source root, or the path itself
+    pub fn strip_root<'a>(&self, file_path: &'a str) -> &'a str {
+        match self.root_for(file_path) {
+            Some(root) => file_path
+                .trim_start_matches("./")
+                .get(root.len() + 1..)
+                .unwrap_or(file_path),
+            None => file_path,
+        }
+    }
+
+    /// Resolve a (possibly nested or static member) import such as
+    /// `com.acme.Outer.Inner` or `com.acme.Util.helper` to the in-repo file
+    /// declaring its top-level class
+    pub fn resolve_import(&self, import_path: &str) -> Option<String> {
+        let segments: Vec<&str> = import_path.split('.').filter(|s| !s.is_empty()).collect();
+        for len in (1..=segments.len()).rev() {
+            let relative = segments[..len].join("/");
+            for root in &self.roots {
+                for ext in ["java", "kt"] {
+                    let candidate = format!("{}/{}.{}", root, relative, ext);
+                    if self.files.contains(&candidate) {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn scan_dir(&mut self, repo_root: &Path, dir: &Path) {
+        let relative = relative_to(repo_root, dir);
+
+        for convention in CONVENTIONAL_ROOTS {
+            if dir.join(convention).is_dir() {
+                self.add_root(&join(&relative, convention));
+            }
+        }
+
+        for build_file in ["build.gradle", "build.gradle.kts"] {
+            if let Ok(content) = std::fs::read_to_string(dir.join(build_file)) {
+                for src_dir in parse_gradle_src_dirs(&content) {
+                    self.add_root(&join(&relative, &src_dir));
+                }
+            }
+        }
+
+        if let Ok(content) = std::fs::read_to_string(dir.join("pom.xml")) {
+            for src_dir in parse_maven_src_dirs(&content) {
+                self.add_root(&join(&relative, &src_dir));
+            }
+        }
+
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                // Symlinked directories are not followed, so link cycles
+                // can't recurse forever; don't descend into the sources
+                // themselves either
+                if is_real_dir(&entry)
+                    && !name.starts_with('.')
+                    && !SKIPPED_DIRS.contains(&name.as_str())
+                    && name != "src"
+                {
+                    self.scan_dir(repo_root, &path);
+                }
+            }
+        }
+    }
+
+    fn collect_files(&mut self, repo_root: &Path) {
+        let roots = self.roots.clone();
+        for root in roots {
+            self.collect_files_in(repo_root, &repo_root.join(&root));
+        }
+    }
+
+    fn collect_files_in(&mut self, repo_root: &Path, dir: &Path) {
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if is_real_dir(&entry) {
+                    self.collect_files_in(repo_root, &path);
+                } else if matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("java") | Some("kt")
+                ) {
+                    self.add_file(&relative_to(repo_root, &path));
+                }
+            }
+        }
+    }
+}
+
+/// Extract `srcDir`/`srcDirs` entries from a Gradle build script. Handles
+/// both the Groovy (`srcDirs = ['a', 'b']`, `srcDir 'a'`) and Kotlin DSL
+/// (`srcDirs("a")`, `setSrcDirs(listOf("a"))`) spellings.
+fn parse_gradle_src_dirs(content: &str) -> Vec<String> {
+    let mut dirs = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with("//") {
+            continue;
+        }
+        let Some(idx) = line.find("srcDir").or_else(|| line.find("SrcDirs")) else {
+            continue;
+        };
+        dirs.extend(quoted_strings(&line[idx..]));
+    }
+    dirs
+}
+
+/// Extract `<sourceDirectory>`/`<testSourceDirectory>` from a Maven pom
+fn parse_maven_src_dirs(content: &str) -> Vec<String> {
+    let mut dirs = Vec::new();
+    for tag in ["sourceDirectory", "testSourceDirectory"] {
+        let open = format!("<{}>", tag);
+        let close = format!("</{}>", tag);
+        let mut rest = content;
+        while let Some(start) = rest.find(&open) {
+            rest = &rest[start + open.len()..];
+            if let Some(end) = rest.find(&close) {
+                let dir = rest[..end].trim().trim_start_matches("${project.basedir}/");
+                if !dir.is_empty() && !dir.contains("${") {
+                    dirs.push(dir.to_string());
+                }
+                rest = &rest[end..];
+            }
+        }
+    }
+    dirs
+}
+
+pub(crate) fn quoted_strings(text: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = text.char_indices();
+    while let Some((start, c)) = chars.next() {
+        if c == '"' || c == '\'' {
+            if let Some((end, _)) = chars.by_ref().find(|(_, d)| *d == c) {
+                strings.push(text[start + 1..end].to_string());
+            }
+        }
+    }
+    strings
+}
+
+pub(crate) fn normalize(path: &str) -> String {
+    path.replace('\\', "/")
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn join(base: &str, path: &str) -> String {
+    if base.is_empty() {
+        normalize(path)
+    } else {
+        normalize(&format!("{}/{}", base, path))
+    }
+}
+
+/// A directory that is not a symlink to one
+fn is_real_dir(entry: &std::fs::DirEntry) -> bool {
+    entry.file_type().is_ok_and(|t| t.is_dir())
+}
+
+fn relative_to(repo_root: &Path, path: &Path) -> String {
+    normalize(&path.strip_prefix(repo_root).unwrap_or(path).to_string_lossy())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_conventional_and_gradle_roots() {
+        let dir = std::env::temp_dir().join(format!("java_roots_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("app/src/main/java/com/acme")).unwrap();
+        std::fs::create_dir_all(dir.join("legacy/sources/org/old")).unwrap();
+        std::fs::write(dir.join("app/src/main/java/com/acme/Foo.java"), "package com.acme;").unwrap();
+        std::fs::write(dir.join("legacy/sources/org/old/Bar.java"), "package org.old;").unwrap();
+        std::fs::write(
+            dir.join("legacy/build.gradle"),
+            "sourceSets {\n  main {\n    java {\n      srcDirs = ['sources']\n    }\n  }\n}\n",
+        )
+        .unwrap();
+
+        let roots = SourceRoots::detect(&dir);
+        assert!(roots.roots().contains(&"app/src/main/java".to_string()));
+        assert!(roots.roots().contains(&"legacy/sources".to_string()));
+        assert_eq!(
+            roots.resolve_import("com.acme.Foo"),
+            Some("app/src/main/java/com/acme/Foo.java".to_string())
+        );
+        assert_eq!(
+            roots.resolve_import("org.old.Bar.Inner"),
+            Some("legacy/sources/org/old/Bar.java".to_string())
+        );
+        assert_eq!(roots.resolve_import("java.util.List"), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_strip_root() {
+        let mut roots = SourceRoots::new();
+        roots.add_root("src/main/java");
+        assert_eq!(roots.strip_root("src/main/java/com/acme/Foo.java"), "com/acme/Foo.java");
+        assert_eq!(roots.strip_root("scripts/Tool.java"), "scripts/Tool.java");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_skips_symlink_cycles() {
+        let dir = std::env::temp_dir().join(format!("java_roots_cycle_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("app/src/main/java/com/acme")).unwrap();
+        std::fs::write(dir.join("app/src/main/java/com/acme/Foo.java"), "package com.acme;").unwrap();
+        // Both walks would loop forever following these
+        std::os::unix::fs::symlink(&dir, dir.join("app/loop")).unwrap();
+        std::os::unix::fs::symlink(dir.join("app/src/main/java"), dir.join("app/src/main/java/com/acme/back")).unwrap();
+
+        let roots = SourceRoots::detect(&dir);
+        assert_eq!(roots.roots(), ["app/src/main/java"]);
+        assert_eq!(
+            roots.resolve_import("com.acme.Foo"),
+            Some("app/src/main/java/com/acme/Foo.java".to_string())
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_build_files() {
+        let kts = r#"sourceSets { main { java.srcDirs("src/gen/java", "src/core") } }"#;
+        assert_eq!(parse_gradle_src_dirs(kts), vec!["src/gen/java", "src/core"]);
+
+        let pom = "<build><sourceDirectory>src/java</sourceDirectory></build>";
+        assert_eq!(parse_maven_src_dirs(pom), vec!["src/java"]);
+    }
+}
@@ -94,15 +94,22 @@ public class TestClass {
             .collect::<Vec<_>>();
         assert_eq!(import_edges.len(), 2, "Should find exactly 2 imports");
         
-        // Verify occurrences
-        assert_eq!(occurrences.len(), symbols.len(),
-            "Each symbol should have exactly one occurrence");
+        // Verify occurrences: one definition per symbol, plus the
+        // constructors' writes to `privateField`
+        let (definitions, uses): (Vec<_>, Vec<_>) = occurrences.iter()
+            .partition(|o| o.role == OccurrenceRole::Definition);
+        assert_eq!(definitions.len(), symbols.len(),
+            "Each symbol should have exactly one definition");
         
         for occ in &occurrences {
-            assert_eq!(occ.role, OccurrenceRole::Definition);
             assert!(occ.symbol_id.is_some());
             assert_eq!(occ.file_path, "Test.java");
         }
+        assert_eq!(uses.len(), 2);
+        for occ in uses {
+            assert_eq!(occ.role, OccurrenceRole::Write);
+            assert_eq!(occ.symbol_id.as_deref(), Some("Test.java#com.example.test.TestClass.privateField"));
+        }
         
         Ok(())
     }
@@ -0,0 +1,24 @@
+//! Snapshot of the full IR emitted for the `testdata/java-basic` fixture.
+//!
+//! Review changes with `cargo insta review`, or rerun with
+//! `INSTA_UPDATE=always` to accept them.
+
+use java_harness::JavaHarness;
+use std::path::Path;
+
+fn fixture(path: &str) -> String {
+    std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../testdata").join(path)).unwrap()
+}
+
+#[test]
+fn test_java_basic_snapshot() {
+    let mut harness = JavaHarness::new().unwrap();
+    let content = fixture("java-basic/Service.java");
+    let (symbols, edges, occurrences) = harness.parse("com/acme/service/Service.java", &content).unwrap();
+
+    insta::with_settings!({ sort_maps => true, snapshot_suffix => "java-basic" }, {
+        insta::assert_json_snapshot!("symbols", symbols);
+        insta::assert_json_snapshot!("edges", edges);
+        insta::assert_json_snapshot!("occurrences", occurrences);
+    });
+}
@@ -0,0 +1,63 @@
+use serde::Serialize;
+
+use crate::{EdgeType, OccurrenceRole, Resolution};
+
+/// What a harness extracts, declared next to it so tools can tell users
+/// the fidelity to expect from a language without reading the parser
+#[derive(Debug, Clone, Serialize)]
+pub struct Capabilities {
+    pub name: &'static str,
+    /// File extensions the scan hands to the harness
+    pub extensions: &'static [&'static str],
+    /// Edge types the harness emits
+    pub edges: &'static [EdgeType],
+    /// Occurrence roles it records
+    pub occurrences: &'static [OccurrenceRole],
+    /// Whether doc comments end up in `SymbolIR::doc`
+    pub docs: bool,
+    /// How its edges find their targets
+    pub resolution: Resolution,
+}
+
+impl Capabilities {
+    pub fn emits(&self, edge_type: &EdgeType) -> bool {
+        self.edges.contains(edge_type)
+    }
+
+    pub fn call_edges(&self) -> bool {
+        self.emits(&EdgeType::Calls)
+    }
+
+    /// Inheritance, sealing and other edges between types
+    pub fn type_edges(&self) -> bool {
+        self.edges.iter().any(|edge_type| {
+            matches!(
+                edge_type,
+                EdgeType::Extends | EdgeType::Implements | EdgeType::References | EdgeType::Returns | EdgeType::Permits
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capability_summary() {
+        let capabilities = Capabilities {
+            name: "Toy",
+            extensions: &["toy"],
+            edges: &[EdgeType::Contains, EdgeType::Calls],
+            occurrences: &[OccurrenceRole::Definition],
+            docs: false,
+            resolution: Resolution::Syntactic,
+        };
+        assert!(capabilities.call_edges());
+        assert!(!capabilities.type_edges());
+
+        let json = serde_json::to_value(&capabilities).unwrap();
+        assert_eq!(json["edges"], serde_json::json!(["Contains", "Calls"]));
+        assert_eq!(json["resolution"], "Syntactic");
+    }
+}
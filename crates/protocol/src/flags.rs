@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+
+/// Stability, provenance and qualifier markers on a symbol, stored as a bitset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SymbolFlags(u32);
+
+impl SymbolFlags {
+    /// `@deprecated`, `#[deprecated]`, `[[deprecated]]`, `// Deprecated:`
+    pub const DEPRECATED: Self = Self(1);
+    /// `@experimental`, `@beta`, `#[unstable]` and similar
+    pub const EXPERIMENTAL: Self = Self(1 << 1);
+    /// Produced by a code generator rather than written by hand
+    pub const GENERATED: Self = Self(1 << 2);
+    /// Tests, fixtures and helpers only reachable from tests
+    pub const TEST_ONLY: Self = Self(1 << 3);
+    /// `async fn`
+    pub const ASYNC: Self = Self(1 << 4);
+    /// `unsafe fn`, unsafe to call
+    pub const UNSAFE: Self = Self(1 << 5);
+    /// `const fn`, usable in constant expressions
+    pub const CONST: Self = Self(1 << 6);
+    /// Foreign ABI: `extern "C" fn`, or declared in an `extern` block
+    pub const EXTERN: Self = Self(1 << 7);
+    /// The body contains `unsafe { ... }` blocks
+    pub const UNSAFE_BLOCKS: Self = Self(1 << 8);
+    /// Ambient declaration without a body, e.g. in a `.d.ts` file
+    pub const DECLARATION: Self = Self(1 << 9);
+
+    const NAMES: [(Self, &'static str); 10] = [
+        (Self::DEPRECATED, "deprecated"),
+        (Self::EXPERIMENTAL, "experimental"),
+        (Self::GENERATED, "generated"),
+        (Self::TEST_ONLY, "test-only"),
+        (Self::ASYNC, "async"),
+        (Self::UNSAFE, "unsafe"),
+        (Self::CONST, "const"),
+        (Self::EXTERN, "extern"),
+        (Self::UNSAFE_BLOCKS, "unsafe-blocks"),
+        (Self::DECLARATION, "declaration"),
+    ];
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Unknown bits are dropped so newer databases still load
+    pub fn from_bits_truncate(bits: u32) -> Self {
+        let known = Self::NAMES.iter().fold(0, |acc, (flag, _)| acc | flag.0);
+        Self(bits & known)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Set `flag` when `condition` holds
+    pub fn set_if(&mut self, flag: Self, condition: bool) {
+        if condition {
+            self.insert(flag);
+        }
+    }
+
+    pub fn names(self) -> Vec<&'static str> {
+        Self::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+impl BitOr for SymbolFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for SymbolFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl fmt::Display for SymbolFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.names().join(","))
+    }
+}
+
+impl std::str::FromStr for SymbolFlags {
+    type Err = String;
+
+    /// A single flag name, e.g. `deprecated` or `test-only`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.to_ascii_lowercase().replace('_', "-");
+        Self::NAMES
+            .iter()
+            .find(|(_, name)| *name == normalized || (normalized == "test" && *name == "test-only"))
+            .map(|(flag, _)| *flag)
+            .ok_or_else(|| format!("unknown symbol flag '{}'", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_set_operations() {
+        let mut flags = SymbolFlags::DEPRECATED;
+        flags |= SymbolFlags::TEST_ONLY;
+        assert!(flags.contains(SymbolFlags::DEPRECATED));
+        assert!(!flags.contains(SymbolFlags::DEPRECATED | SymbolFlags::GENERATED));
+        assert!(flags.intersects(SymbolFlags::DEPRECATED | SymbolFlags::GENERATED));
+        assert_eq!(flags.to_string(), "deprecated,test-only");
+        assert_eq!(SymbolFlags::from_bits_truncate(flags.bits() | 1 << 31), flags);
+        assert_eq!("Test_Only".parse::<SymbolFlags>(), Ok(SymbolFlags::TEST_ONLY));
+        assert!("stable".parse::<SymbolFlags>().is_err());
+    }
+}
@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::Language;
+
+/// Separator used between segments of a canonical FQN
+pub const SEPARATOR: &str = "::";
+
+/// Placeholder segment for anonymous scopes (lambdas, anonymous namespaces, ...)
+pub const ANONYMOUS_SEGMENT: &str = "{anon}";
+
+/// Language-neutral fully qualified name.
+///
+/// Every harness builds FQNs its own way (`a::b::c` in Rust and C++, `pkg.Name`
+/// in Go/Java/Python, `path/to/file/name` in TypeScript). A canonical FQN is the
+/// same name split into segments with generic arguments erased and anonymous
+/// scopes collapsed, so FQNs from different languages and harnesses compare equal
+/// when they name the same logical entity.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CanonicalFqn {
+    segments: Vec<String>,
+}
+
+impl CanonicalFqn {
+    /// Build a canonical FQN from raw segments, normalizing each one
+    pub fn from_segments<I, S>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let segments = segments
+            .into_iter()
+            .filter_map(|s| normalize_segment(s.as_ref()))
+            .collect();
+        Self { segments }
+    }
+
+    /// Parse a string that is already in canonical form
+    pub fn parse(canonical: &str) -> Self {
+        Self::from_segments(split_top_level(canonical, &[SEPARATOR]))
+    }
+
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// The last segment, i.e. the entity's own name
+    pub fn name(&self) -> Option<&str> {
+        self.segments.last().map(|s| s.as_str())
+    }
+
+    /// The enclosing scope, if any
+    pub fn parent(&self) -> Option<CanonicalFqn> {
+        if self.segments.len() <= 1 {
+            return None;
+        }
+        Some(Self {
+            segments: self.segments[..self.segments.len() - 1].to_vec(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Whether this FQN contains an anonymous scope anywhere along its path
+    pub fn is_anonymous(&self) -> bool {
+        self.segments.iter().any(|s| s == ANONYMOUS_SEGMENT)
+    }
+
+    /// Whether `suffix` matches the trailing segments of this FQN.
+    ///
+    /// Used for cross-language joins where one side only knows a partial
+    /// qualification (e.g. `Foo::bar` against `com::acme::Foo::bar`).
+    pub fn ends_with(&self, suffix: &CanonicalFqn) -> bool {
+        !suffix.is_empty() && self.segments.ends_with(&suffix.segments)
+    }
+}
+
+impl fmt::Display for CanonicalFqn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.segments.join(SEPARATOR))
+    }
+}
+
+/// Converts a language's native FQN spelling into a canonical FQN
+pub trait FqnAdapter: Send + Sync {
+    /// Split a native FQN into its scope segments
+    fn split<'a>(&self, fqn: &'a str) -> Vec<&'a str>;
+
+    fn canonicalize(&self, fqn: &str) -> CanonicalFqn {
+        CanonicalFqn::from_segments(self.split(fqn))
+    }
+}
+
+/// Rust, C and C++ use `::` between scopes
+pub struct ColonColonAdapter;
+
+impl FqnAdapter for ColonColonAdapter {
+    fn split<'a>(&self, fqn: &'a str) -> Vec<&'a str> {
+        split_top_level(fqn, &["::"])
+    }
+}
+
+/// Java, Go, Python and C# use `.` between scopes
+pub struct DotAdapter;
+
+impl FqnAdapter for DotAdapter {
+    fn split<'a>(&self, fqn: &'a str) -> Vec<&'a str> {
+        split_top_level(fqn, &["."])
+    }
+}
+
+/// TypeScript/JavaScript FQNs are `path/to/module/name`, with `.` only
+/// separating members inside the final segment (path segments may contain dots)
+pub struct ModulePathAdapter;
+
+impl FqnAdapter for ModulePathAdapter {
+    fn split<'a>(&self, fqn: &'a str) -> Vec<&'a str> {
+        let fqn = fqn.trim_start_matches("./");
+        let mut parts = split_top_level(fqn, &["/"]);
+        match parts.pop() {
+            Some(last) => {
+                parts.extend(split_top_level(last, &["."]));
+                parts
+            }
+            None => parts,
+        }
+    }
+}
+
+/// Fallback for unknown languages: accept any common separator
+pub struct AnySeparatorAdapter;
+
+impl FqnAdapter for AnySeparatorAdapter {
+    fn split<'a>(&self, fqn: &'a str) -> Vec<&'a str> {
+        split_top_level(fqn, &["::", ".", "/", "#"])
+    }
+}
+
+/// Get the FQN adapter for a language
+pub fn adapter_for(lang: &Language) -> &'static dyn FqnAdapter {
+    match lang {
+        Language::Rust | Language::C | Language::Cpp => &ColonColonAdapter,
+        Language::Java | Language::Go | Language::Python | Language::CSharp => &DotAdapter,
+        Language::TypeScript | Language::JavaScript => &ModulePathAdapter,
+        Language::Unknown => &AnySeparatorAdapter,
+    }
+}
+
+/// Canonicalize a native FQN for the given language
+pub fn canonicalize(lang: &Language, fqn: &str) -> CanonicalFqn {
+    adapter_for(lang).canonicalize(fqn)
+}
+
+/// Split on any of `separators`, ignoring separators nested inside
+/// `<...>`, `(...)` or `[...]` so generic arguments stay intact.
+fn split_top_level<'a>(input: &'a str, separators: &[&str]) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut depth: i32 = 0;
+    let mut start = 0;
+    let mut i = 0;
+    let bytes = input.as_bytes();
+
+    while i < bytes.len() {
+        // C++ operator names (`operator<`, `operator()`) must not open a nesting level
+        let in_operator = is_operator_name(&bytes[start..=i]);
+        match bytes[i] {
+            b'<' | b'(' | b'[' if !in_operator => depth += 1,
+            b'>' | b')' | b']' if !in_operator && depth > 0 => depth -= 1,
+            _ => {}
+        }
+
+        if depth == 0 {
+            if let Some(sep) = separators.iter().find(|sep| bytes[i..].starts_with(sep.as_bytes())) {
+                parts.push(&input[start..i]);
+                i += sep.len();
+                start = i;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
+/// Normalize one segment: collapse anonymous scopes, erase generic arguments,
+/// and drop empty segments (e.g. from a leading `::`).
+fn normalize_segment(segment: &str) -> Option<String> {
+    let segment = segment.trim();
+    if segment.is_empty() {
+        return None;
+    }
+    if is_anonymous_segment(segment) {
+        return Some(ANONYMOUS_SEGMENT.to_string());
+    }
+    if is_operator_name(segment.as_bytes()) {
+        return Some(segment.to_string());
+    }
+
+    let erased = erase_generics(segment);
+    let erased = erased.trim();
+    if erased.is_empty() {
+        None
+    } else {
+        Some(erased.to_string())
+    }
+}
+
+/// Whether `name` is a C++ operator: the `operator` keyword followed by
+/// what it overloads, so `operator<` and `operator bool` but not
+/// `operators` or `operatorName`
+fn is_operator_name(name: &[u8]) -> bool {
+    name.trim_ascii_start()
+        .strip_prefix(b"operator")
+        .and_then(|rest| rest.first())
+        .is_some_and(|c| c.is_ascii() && !c.is_ascii_alphanumeric() && *c != b'_')
+}
+
+/// Anonymous scopes as the harnesses and compilers name them. Names
+/// merely looking numbered are left alone.
+fn is_anonymous_segment(segment: &str) -> bool {
+    if matches!(
+        segment,
+        "<anonymous>" | "(anonymous namespace)" | "(anonymous)" | "<lambda>" | ANONYMOUS_SEGMENT
+    ) {
+        return true;
+    }
+
+    // Named by position: Java `lambda_12` and `anonymous_12`, C++/C#
+    // `lambda_12_4` and Go `func_12_4`
+    let numbers = |rest: &str, counts: &[usize]| {
+        let parts: Vec<&str> = rest.split('_').collect();
+        counts.contains(&parts.len()) && parts.iter().all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+    };
+    match segment.split_once('_') {
+        Some(("lambda", rest)) => numbers(rest, &[1, 2]),
+        Some(("anonymous", rest)) => numbers(rest, &[1]),
+        Some(("func", rest)) => numbers(rest, &[2]),
+        _ => false,
+    }
+}
+
+/// Remove generic argument lists (`<...>` and Go-style `[...]`) from a segment.
+///
+/// Brackets only open type parameters right after an identifier (`List[T]`);
+/// ones that don't are part of the name, like the Next.js route segments
+/// `[id]` and `[[...slug]]`.
+fn erase_generics(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    let mut depth = 0;
+    for c in segment.chars() {
+        let after_identifier = out.ends_with(|p: char| p.is_alphanumeric() || p == '_');
+        match c {
+            '<' => depth += 1,
+            '[' if depth > 0 || after_identifier => depth += 1,
+            '>' | ']' if depth > 0 => depth -= 1,
+            _ if depth == 0 => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_entity_across_languages() {
+        let rust = canonicalize(&Language::Rust, "geometry::Point::new");
+        let java = canonicalize(&Language::Java, "geometry.Point.new");
+        let cpp = canonicalize(&Language::Cpp, "::geometry::Point::new");
+        assert_eq!(rust, java);
+        assert_eq!(rust, cpp);
+        assert_eq!(rust.to_string(), "geometry::Point::new");
+        assert_eq!(rust.name(), Some("new"));
+        assert_eq!(rust.parent().unwrap().to_string(), "geometry::Point");
+    }
+
+    #[test]
+    fn test_generics_erasure() {
+        let rust = canonicalize(&Language::Rust, "collections::HashMap<K, V>::insert");
+        assert_eq!(rust.to_string(), "collections::HashMap::insert");
+
+        let java = canonicalize(&Language::Java, "java.util.Map<String, List<Integer>>.put");
+        assert_eq!(java.to_string(), "java::util::Map::put");
+
+        let go = canonicalize(&Language::Go, "lists.List[T].Push");
+        assert_eq!(go.to_string(), "lists::List::Push");
+    }
+
+    #[test]
+    fn test_anonymous_scopes() {
+        let cpp = canonicalize(&Language::Cpp, "(anonymous namespace)::helper");
+        assert_eq!(cpp.to_string(), "{anon}::helper");
+        assert!(cpp.is_anonymous());
+
+        let java = canonicalize(&Language::Java, "com.acme.Service.lambda_42");
+        assert_eq!(java.to_string(), "com::acme::Service::{anon}");
+
+        let cpp_lambda = canonicalize(&Language::Cpp, "ns::lambda_3_14");
+        assert_eq!(cpp_lambda.to_string(), "ns::{anon}");
+
+        let go = canonicalize(&Language::Go, "main.run.func_4_9");
+        assert_eq!(go.to_string(), "main::run::{anon}");
+        let java = canonicalize(&Language::Java, "app.Worker.anonymous_17.run");
+        assert_eq!(java.to_string(), "app::Worker::{anon}::run");
+    }
+
+    #[test]
+    fn test_numbered_names_are_kept() {
+        let ts = canonicalize(&Language::TypeScript, "api/2024/handler");
+        assert_eq!(ts.to_string(), "api::2024::handler");
+        assert!(!ts.is_anonymous());
+
+        let python = canonicalize(&Language::Python, "app.lambda_handler.func_1");
+        assert_eq!(python.to_string(), "app::lambda_handler::func_1");
+    }
+
+    #[test]
+    fn test_typescript_module_paths() {
+        let ts = canonicalize(&Language::TypeScript, "src/utils/date.helpers/format");
+        assert_eq!(ts.segments(), &["src", "utils", "date.helpers", "format"]);
+
+        let member = canonicalize(&Language::TypeScript, "./src/models/User.save");
+        assert_eq!(member.to_string(), "src::models::User::save");
+    }
+
+    #[test]
+    fn test_bracketed_route_segments_are_kept() {
+        let page = canonicalize(&Language::TypeScript, "app/[id]/page");
+        assert_eq!(page.segments(), &["app", "[id]", "page"]);
+        assert_ne!(page, canonicalize(&Language::TypeScript, "app/page"));
+        assert_eq!(CanonicalFqn::parse(&page.to_string()), page);
+
+        let catch_all = canonicalize(&Language::TypeScript, "app/blog/[[...slug]]/page");
+        assert_eq!(catch_all.to_string(), "app::blog::[[...slug]]::page");
+    }
+
+    #[test]
+    fn test_cpp_operators_are_kept() {
+        let cpp = canonicalize(&Language::Cpp, "math::Vec3::operator<");
+        assert_eq!(cpp.name(), Some("operator<"));
+
+        let call = canonicalize(&Language::Cpp, "Functor::operator()");
+        assert_eq!(call.to_string(), "Functor::operator()");
+
+        let conversion = canonicalize(&Language::Cpp, "Flag::operator bool");
+        assert_eq!(conversion.name(), Some("operator bool"));
+
+        // Identifiers merely starting with the keyword
+        let named = canonicalize(&Language::Cpp, "ui::operators<T>::operatorName");
+        assert_eq!(named.to_string(), "ui::operators::operatorName");
+    }
+
+    #[test]
+    fn test_parse_round_trip_and_suffix() {
+        let full = canonicalize(&Language::Python, "app.models.User.save");
+        let parsed = CanonicalFqn::parse(&full.to_string());
+        assert_eq!(full, parsed);
+
+        let suffix = canonicalize(&Language::Rust, "User::save");
+        assert!(full.ends_with(&suffix));
+        assert!(!full.ends_with(&CanonicalFqn::parse("")));
+    }
+}
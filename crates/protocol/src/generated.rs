@@ -0,0 +1,80 @@
+/// File name endings produced by common code generators: protoc and its
+/// plugins, build_runner/freezed, Qt's moc/uic, and .NET designers.
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    ".pb.h",
+    ".pb.cc",
+    ".pb.c",
+    "_pb2.py",
+    "_pb2.pyi",
+    "_pb2_grpc.py",
+    "_pb.js",
+    "_pb.d.ts",
+    ".g.dart",
+    ".freezed.dart",
+    ".pb.dart",
+    ".g.cs",
+    ".designer.cs",
+    ".generated.cs",
+    ".generated.ts",
+    ".generated.h",
+];
+
+/// File name prefixes used by generators that keep the source extension
+const GENERATED_PREFIXES: &[&str] = &["moc_", "ui_", "zz_generated."];
+
+/// Markers generators write into the file header, including Go's
+/// `// Code generated ... DO NOT EDIT.` convention
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "<auto-generated",
+    "Generated by the protocol buffer compiler",
+    "DO NOT EDIT",
+];
+
+/// How far into a file header markers are looked for
+const HEADER_LINES: usize = 10;
+
+/// Whether `path` looks like generator output, by name or by a header marker
+/// in the first few lines of `content`
+pub fn is_generated_file(path: &str, content: &str) -> bool {
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    if GENERATED_SUFFIXES.iter().any(|suffix| file_name.ends_with(suffix))
+        || GENERATED_PREFIXES.iter().any(|prefix| file_name.starts_with(prefix))
+    {
+        return true;
+    }
+
+    content
+        .lines()
+        .take(HEADER_LINES)
+        .any(|line| GENERATED_MARKERS.iter().any(|marker| line.contains(marker)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_by_name() {
+        assert!(is_generated_file("api/v1/user.pb.go", ""));
+        assert!(is_generated_file("proto/user_pb2.py", ""));
+        assert!(is_generated_file("include/user.pb.h", ""));
+        assert!(is_generated_file("lib/models/user.g.dart", ""));
+        assert!(is_generated_file("src/moc_window.cpp", ""));
+        assert!(!is_generated_file("src/user.go", ""));
+        assert!(!is_generated_file("src/pb2.py", ""));
+    }
+
+    #[test]
+    fn test_generated_by_header() {
+        let go = "// Code generated by mockgen. DO NOT EDIT.\n\npackage mocks\n";
+        assert!(is_generated_file("mocks/store.go", go));
+
+        let ts = "/**\n * @generated SignedSource<<abc>>\n */\nexport const x = 1;\n";
+        assert!(is_generated_file("src/relay/query.ts", ts));
+
+        let late = format!("{}// DO NOT EDIT\n", "\n".repeat(HEADER_LINES));
+        assert!(!is_generated_file("src/notes.go", &late));
+    }
+}
@@ -1,7 +1,17 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub mod capabilities;
+pub mod flags;
+pub mod fqn;
+pub mod generated;
+pub mod path;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 pub mod version;
+pub use capabilities::Capabilities;
+pub use flags::SymbolFlags;
+pub use fqn::CanonicalFqn;
 pub use version::{LanguageVersion, Version, VersionDetection};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -25,6 +35,8 @@ pub enum SymbolKind {
     Class,
     Interface,
     Variable,
+    /// A function or method parameter
+    Parameter,
     Type,
     Module,
     Package,
@@ -36,14 +48,19 @@ pub enum SymbolKind {
     Constant,
     Field,
     Property,
+    /// A C# `event`
+    Event,
     TypeAlias,
     Typedef,
     Union,
+    /// A Cargo package (workspace member or external dependency)
+    Crate,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum EdgeType {
     Contains,
+    /// `src` is a bodiless declaration (`.d.ts`) of the implementation `dst`
     Declares,
     Calls,
     Imports,
@@ -53,12 +70,35 @@ pub enum EdgeType {
     Returns,
     Reads,
     Writes,
+    /// `src` is an alternative name (re-export, import rename, typedef) for `dst`
+    Aliases,
+    /// `src` needs an instance of `dst` at runtime, e.g. an injected dependency
+    DependsOn,
+    /// Module `src` makes `dst` part of its public surface
+    Exports,
+    /// `src` carries the attribute or annotation `dst`, e.g. `[HttpGet]`
+    AnnotatedBy,
+    /// `src` names the type `dst` in its declared types, e.g. as the type
+    /// argument `Foo` of a `List<Foo>` field
+    References,
+    /// `src` can raise the exception type `dst`, declared in a `throws`
+    /// clause or thrown with `throw new X(...)`
+    Throws,
+    /// `src`, a sealed class or interface, permits the subtype `dst`
+    Permits,
+    /// `src` publishes or emits the event or message topic `dst`, named
+    /// `event:<topic>`
+    PublishesTo,
+    /// `src` handles the event or message topic `dst`, named `event:<topic>`
+    SubscribesTo,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Resolution {
     Syntactic,
     Semantic,
+    /// Pattern-matched from source text, without a parser for the language
+    Heuristic,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -70,9 +110,11 @@ pub enum OccurrenceRole {
     Extend,
     Implement,
     Definition,
+    /// Name of a declaration without a body, e.g. in a `.d.ts` file
+    Declaration,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Span {
     pub start_line: u32,
     pub start_col: u32,
@@ -80,6 +122,44 @@ pub struct Span {
     pub end_col: u32,
 }
 
+/// One formal parameter of a function or method
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: String,
+    /// Declared type as written in source, if any
+    #[serde(rename = "type")]
+    pub type_name: Option<String>,
+    /// Default value expression as written in source, if any
+    pub default: Option<String>,
+    /// Rest/variadic parameter (`...args`, `*args`, `T... args`)
+    pub variadic: bool,
+}
+
+/// Block tags of a doc comment (`@param`, `@return`, `@throws`,
+/// `@deprecated`), kept apart from the prose in `SymbolIR::doc` so tools
+/// can render parameter docs
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DocTags {
+    /// In source order; type parameters keep their brackets, e.g. `<T>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<DocTag>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub returns: Option<String>,
+    /// One per exception type, named as written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub throws: Vec<DocTag>,
+    /// Empty when the tag gives no reason or replacement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+}
+
+/// A tag naming a parameter or exception type, and what it says about it
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DocTag {
+    pub name: String,
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolIR {
     pub id: String,
@@ -89,10 +169,31 @@ pub struct SymbolIR {
     pub name: String,
     pub fqn: String,
     pub signature: Option<String>,
+    /// Structured parameters of functions and methods (signature help, API diffs)
+    #[serde(default)]
+    pub parameters: Vec<Parameter>,
+    /// Declared return type as written in source, for functions and methods
+    #[serde(default)]
+    pub return_type: Option<String>,
     pub file_path: String,
-    pub span: Span,
+    /// Span of the symbol's name (go-to-definition, rename, highlights)
+    #[serde(default)]
+    pub name_span: Span,
+    /// Span of the whole declaration including its body (outline, folding)
+    #[serde(alias = "span")]
+    pub full_span: Span,
     pub visibility: Option<String>,
+    /// Deprecated / experimental / generated / test-only markers
+    #[serde(default)]
+    pub flags: SymbolFlags,
+    /// Build configurations (e.g. per-platform define sets) the symbol is
+    /// compiled in; empty when the file was indexed under a single config
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub configs: Vec<String>,
     pub doc: Option<String>,
+    /// Structured tags of the doc comment, for harnesses that parse them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc_tags: Option<DocTags>,
     pub sig_hash: String,
 }
 
@@ -117,6 +218,91 @@ pub struct OccurrenceIR {
     pub token: String,
 }
 
+impl std::str::FromStr for Language {
+    type Err = String;
+
+    /// Case-insensitive language name or common short form (`ts`, `py`, `c++`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "typescript" | "ts" | "tsx" => Ok(Language::TypeScript),
+            "javascript" | "js" | "jsx" => Ok(Language::JavaScript),
+            "python" | "py" => Ok(Language::Python),
+            "go" | "golang" => Ok(Language::Go),
+            "rust" | "rs" => Ok(Language::Rust),
+            "java" => Ok(Language::Java),
+            "c" => Ok(Language::C),
+            "cpp" | "c++" | "cxx" => Ok(Language::Cpp),
+            "csharp" | "c#" | "cs" => Ok(Language::CSharp),
+            _ => Err(format!("unknown language '{}'", s)),
+        }
+    }
+}
+
+impl std::str::FromStr for SymbolKind {
+    type Err = String;
+
+    /// Case-insensitive kind name, e.g. `function` or `TypeAlias`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let kind = match s.to_ascii_lowercase().replace(['_', '-'], "").as_str() {
+            "function" => SymbolKind::Function,
+            "method" => SymbolKind::Method,
+            "class" => SymbolKind::Class,
+            "interface" => SymbolKind::Interface,
+            "variable" => SymbolKind::Variable,
+            "parameter" => SymbolKind::Parameter,
+            "type" => SymbolKind::Type,
+            "module" => SymbolKind::Module,
+            "package" => SymbolKind::Package,
+            "namespace" => SymbolKind::Namespace,
+            "enum" => SymbolKind::Enum,
+            "enummember" => SymbolKind::EnumMember,
+            "struct" => SymbolKind::Struct,
+            "trait" => SymbolKind::Trait,
+            "constant" => SymbolKind::Constant,
+            "field" => SymbolKind::Field,
+            "property" => SymbolKind::Property,
+            "event" => SymbolKind::Event,
+            "typealias" => SymbolKind::TypeAlias,
+            "typedef" => SymbolKind::Typedef,
+            "union" => SymbolKind::Union,
+            "crate" => SymbolKind::Crate,
+            _ => return Err(format!("unknown symbol kind '{}'", s)),
+        };
+        Ok(kind)
+    }
+}
+
+impl std::str::FromStr for EdgeType {
+    type Err = String;
+
+    /// Case-insensitive edge type name, e.g. `calls` or `depends_on`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let edge_type = match s.to_ascii_lowercase().replace(['_', '-'], "").as_str() {
+            "contains" => EdgeType::Contains,
+            "declares" => EdgeType::Declares,
+            "calls" => EdgeType::Calls,
+            "imports" => EdgeType::Imports,
+            "extends" => EdgeType::Extends,
+            "implements" => EdgeType::Implements,
+            "overrides" => EdgeType::Overrides,
+            "returns" => EdgeType::Returns,
+            "reads" => EdgeType::Reads,
+            "writes" => EdgeType::Writes,
+            "aliases" => EdgeType::Aliases,
+            "dependson" => EdgeType::DependsOn,
+            "exports" => EdgeType::Exports,
+            "annotatedby" => EdgeType::AnnotatedBy,
+            "references" => EdgeType::References,
+            "throws" => EdgeType::Throws,
+            "permits" => EdgeType::Permits,
+            "publishesto" => EdgeType::PublishesTo,
+            "subscribesto" => EdgeType::SubscribesTo,
+            _ => return Err(format!("unknown edge type '{}'", s)),
+        };
+        Ok(edge_type)
+    }
+}
+
 impl SymbolIR {
     pub fn generate_id(commit_sha: &str, file_path: &str, lang: &Language, fqn: &str, sig_hash: &str) -> String {
         format!("repo://{}/{}/{}#sym({}:{}:{})", 
@@ -128,6 +314,11 @@ impl SymbolIR {
             sig_hash
         )
     }
+
+    /// Language-neutral form of this symbol's FQN, comparable across harnesses
+    pub fn canonical_fqn(&self) -> CanonicalFqn {
+        fqn::canonicalize(&self.lang, &self.fqn)
+    }
 }
 
 #[cfg(test)]
@@ -147,6 +338,17 @@ mod tests {
         assert!(id.contains("rust:mymod::MyStruct::new:hash123"));
     }
 
+    #[test]
+    fn test_parse_language_and_kind() {
+        assert_eq!("ts".parse::<Language>(), Ok(Language::TypeScript));
+        assert_eq!("C++".parse::<Language>(), Ok(Language::Cpp));
+        assert!("cobol".parse::<Language>().is_err());
+        assert_eq!("type_alias".parse::<SymbolKind>(), Ok(SymbolKind::TypeAlias));
+        assert_eq!("Class".parse::<SymbolKind>(), Ok(SymbolKind::Class));
+        assert_eq!("depends-on".parse::<EdgeType>(), Ok(EdgeType::DependsOn));
+        assert!("uses".parse::<EdgeType>().is_err());
+    }
+
     #[test]
     fn test_serialize_deserialize() {
         let symbol = SymbolIR {
@@ -157,15 +359,21 @@ mod tests {
             name: "test".to_string(),
             fqn: "module.test".to_string(),
             signature: Some("(x: number) => number".to_string()),
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "test.ts".to_string(),
-            span: Span {
+            name_span: Default::default(),
+            full_span: Span {
                 start_line: 1,
                 start_col: 0,
                 end_line: 1,
                 end_col: 10,
             },
             visibility: Some("public".to_string()),
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "abc".to_string(),
         };
         
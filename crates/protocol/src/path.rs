@@ -0,0 +1,50 @@
+use std::path::Path;
+
+/// Normalize a repository-relative path to the form stored in the index:
+/// forward slashes, no `./` prefix, no duplicate separators, and no Windows
+/// verbatim (`\\?\`) prefix.
+///
+/// Every path that ends up in a symbol id, file row or edge goes through this,
+/// so a scan on Windows produces the same keys as one on Linux or macOS.
+pub fn normalize_path(path: &str) -> String {
+    let path = path.strip_prefix(r"\\?\").unwrap_or(path);
+    let mut out = String::with_capacity(path.len());
+    for c in path.chars() {
+        let c = if c == '\\' { '/' } else { c };
+        if c == '/' && out.ends_with('/') {
+            continue;
+        }
+        out.push(c);
+    }
+    while let Some(rest) = out.strip_prefix("./") {
+        out = rest.to_string();
+    }
+    out
+}
+
+/// `path` relative to `root`, normalized. Falls back to the full path when
+/// `path` is not under `root`.
+pub fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    normalize_path(&relative.to_string_lossy())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_windows_paths() {
+        assert_eq!(normalize_path(r"src\models\user.ts"), "src/models/user.ts");
+        assert_eq!(normalize_path(r".\src\\lib.rs"), "src/lib.rs");
+        assert_eq!(normalize_path(r"\\?\C:\repo\main.go"), "C:/repo/main.go");
+        assert_eq!(normalize_path("src/unchanged.py"), "src/unchanged.py");
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let root = Path::new("/repo");
+        assert_eq!(relative_path(root, Path::new("/repo/a/b.ts")), "a/b.ts");
+        assert_eq!(relative_path(root, Path::new("/elsewhere/c.ts")), "/elsewhere/c.ts");
+    }
+}
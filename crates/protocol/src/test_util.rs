@@ -0,0 +1,33 @@
+use crate::{Language, Span, SymbolFlags, SymbolIR, SymbolKind};
+
+/// A TypeScript function `fqn` in `file`, named by the last segment of its
+/// FQN, at line 0 with every optional field empty. Tests override what they
+/// care about with struct update syntax:
+/// `SymbolIR { kind: SymbolKind::Method, ..symbol("id", "app.Cart.add", "cart.ts") }`
+pub fn symbol(id: &str, fqn: &str, file: &str) -> SymbolIR {
+    SymbolIR {
+        id: id.to_string(),
+        lang: Language::TypeScript,
+        lang_version: None,
+        kind: SymbolKind::Function,
+        name: fqn.rsplit(['.', ':', '/', '#']).next().unwrap_or(fqn).to_string(),
+        fqn: fqn.to_string(),
+        signature: None,
+        parameters: Vec::new(),
+        return_type: None,
+        file_path: file.to_string(),
+        name_span: Span::default(),
+        full_span: Span::default(),
+        visibility: None,
+        flags: SymbolFlags::empty(),
+        configs: Vec::new(),
+        doc: None,
+        doc_tags: None,
+        sig_hash: String::new(),
+    }
+}
+
+/// Lines `start..=end` from column 0, the shape most fixtures give spans
+pub fn lines(start: u32, end: u32) -> Span {
+    Span { start_line: start, start_col: 0, end_line: end, end_col: 0 }
+}
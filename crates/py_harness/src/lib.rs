@@ -1,8 +1,18 @@
 use anyhow::Result;
-use protocol::{EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Resolution, Span, SymbolIR, SymbolKind};
+use protocol::{Capabilities, EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Parameter, Resolution, Span, SymbolFlags, SymbolIR, SymbolKind};
 use std::collections::HashMap;
 use tree_sitter::{Node, Parser};
 
+/// What this harness extracts, for `reviewbot query languages`
+pub const CAPABILITIES: Capabilities = Capabilities {
+    name: "Python",
+    extensions: &["py"],
+    edges: &[EdgeType::Contains, EdgeType::Imports, EdgeType::Aliases],
+    occurrences: &[OccurrenceRole::Definition, OccurrenceRole::Call],
+    docs: false,
+    resolution: Resolution::Syntactic,
+};
+
 pub struct PythonHarness {
     parser: Parser,
 }
@@ -67,8 +77,10 @@ impl PythonHarness {
                         &name,
                         SymbolKind::Function,
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
+                        source,
                     );
                     
                     if let Some(parent) = parent_symbol {
@@ -118,8 +130,10 @@ impl PythonHarness {
                         &name,
                         SymbolKind::Class,
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
+                        source,
                     );
                     
                     if let Some(parent) = parent_symbol {
@@ -181,8 +195,10 @@ impl PythonHarness {
                                     &name,
                                     SymbolKind::Variable,
                                     node,
+                                    left,
                                     file_path,
                                     commit_sha,
+                                    source,
                                 );
                                 
                                 occurrences.push(OccurrenceIR {
@@ -249,8 +265,10 @@ impl PythonHarness {
                 &name,
                 SymbolKind::Method,
                 node,
+                name_node,
                 file_path,
                 commit_sha,
+                source,
             );
             
             edges.push(EdgeIR {
@@ -305,6 +323,10 @@ impl PythonHarness {
                     if let Some(module) = module_name {
                         let resolved_path = self.resolve_import_path(file_path, &module);
                         
+                        // `import x as y` / `from m import x as y` make `y` an alias
+                        let from_module = (child.kind() == "import_from_statement").then_some(module.as_str());
+                        self.extract_aliases(child, source, file_path, from_module, edges);
+                        
                         edges.push(EdgeIR {
                             edge_type: EdgeType::Imports,
                             src: None,
@@ -327,6 +349,95 @@ impl PythonHarness {
         Ok(())
     }
     
+    /// Emit Aliases edges for `import x as y` / `from m import x as y`.
+    ///
+    /// In a package `__init__.py`, every name pulled in with `from` is a
+    /// re-export: `pkg/__init__.py` doing `from .models import User` makes
+    /// `pkg.User` an alias of `pkg.models.User`.
+    fn extract_aliases(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &str,
+        from_module: Option<&str>,
+        edges: &mut Vec<EdgeIR>,
+    ) {
+        let is_package_init = file_path.ends_with("__init__.py");
+        let scope = self.module_name(file_path)
+            .trim_end_matches("__init__")
+            .trim_end_matches('.')
+            .to_string();
+        let from_module = from_module.map(|module| self.absolute_module(file_path, module));
+        
+        for child in node.children_by_field_name("name", &mut node.walk()) {
+            let (name, alias, alias_kind) = match child.kind() {
+                "aliased_import" => {
+                    let (Some(name_node), Some(alias_node)) = (
+                        child.child_by_field_name("name"),
+                        child.child_by_field_name("alias"),
+                    ) else {
+                        continue;
+                    };
+                    let kind = if is_package_init && from_module.is_some() { "reexport" } else { "import_as" };
+                    (self.node_text(name_node, source), self.node_text(alias_node, source), kind)
+                }
+                "dotted_name" if is_package_init && from_module.is_some() => {
+                    let name = self.node_text(child, source);
+                    (name.clone(), name, "reexport")
+                }
+                _ => continue,
+            };
+            
+            let target = match &from_module {
+                Some(module) if module.is_empty() => name,
+                Some(module) => format!("{}.{}", module, name),
+                None => name,
+            };
+            
+            let mut meta = HashMap::new();
+            meta.insert("alias".to_string(), serde_json::json!(alias));
+            meta.insert("alias_kind".to_string(), serde_json::json!(alias_kind));
+            
+            edges.push(EdgeIR {
+                edge_type: EdgeType::Aliases,
+                src: Some(if scope.is_empty() { alias } else { format!("{}.{}", scope, alias) }),
+                dst: Some(target),
+                file_src: Some(file_path.to_string()),
+                file_dst: None,
+                resolution: Resolution::Syntactic,
+                meta,
+                provenance: HashMap::new(),
+            });
+        }
+    }
+    
+    fn module_name(&self, file_path: &str) -> String {
+        file_path
+            .trim_end_matches(".py")
+            .replace('/', ".")
+    }
+    
+    /// Turn a relative module (`.models`, `..core`) into a dotted absolute module
+    fn absolute_module(&self, current_file: &str, module: &str) -> String {
+        let level = module.chars().take_while(|c| *c == '.').count();
+        if level == 0 {
+            return module.to_string();
+        }
+        
+        // One dot is the current file's package; each extra dot goes up a level
+        let mut package: Vec<&str> = current_file.split('/').collect();
+        package.pop();
+        for _ in 1..level {
+            package.pop();
+        }
+        
+        let rest = &module[level..];
+        if !rest.is_empty() {
+            package.push(rest);
+        }
+        package.join(".")
+    }
+    
     fn resolve_import_path(&self, current_file: &str, import_module: &str) -> String {
         // Simple resolution - convert dots to slashes and add .py
         // In real implementation, would need to handle relative imports, packages, etc.
@@ -350,13 +461,12 @@ impl PythonHarness {
         name: &str,
         kind: SymbolKind,
         node: Node,
+        name_node: Node,
         file_path: &str,
         commit_sha: &str,
+        source: &[u8],
     ) -> SymbolIR {
-        let module_name = file_path
-            .trim_end_matches(".py")
-            .replace('/', ".");
-        let fqn = format!("{}.{}", module_name, name);
+        let fqn = format!("{}.{}", self.module_name(file_path), name);
         let sig_hash = format!("{:x}", name.len());
         
         let id = SymbolIR::generate_id(commit_sha, file_path, &Language::Python, &fqn, &sig_hash);
@@ -369,14 +479,97 @@ impl PythonHarness {
             name: name.to_string(),
             fqn,
             signature: None,
+            parameters: self.extract_parameters(node, source),
+            // `def f() -> T`
+            return_type: node.child_by_field_name("return_type").map(|t| self.node_text(t, source)),
             file_path: file_path.to_string(),
-            span: self.node_to_span(node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: Some("public".to_string()),
+            flags: self.symbol_flags(node, file_path, source),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         }
     }
     
+    /// Flags from decorators (`@deprecated`, `@pytest.fixture`), a
+    /// `.. deprecated::` docstring note and pytest file naming
+    fn symbol_flags(&self, node: Node, file_path: &str, source: &[u8]) -> SymbolFlags {
+        let mut flags = SymbolFlags::empty();
+        
+        if let Some(parent) = node.parent().filter(|p| p.kind() == "decorated_definition") {
+            for decorator in parent.named_children(&mut parent.walk()) {
+                if decorator.kind() != "decorator" {
+                    continue;
+                }
+                let text = self.node_text(decorator, source);
+                let name = text.trim_start_matches('@').split('(').next().unwrap_or("").trim();
+                flags.set_if(SymbolFlags::DEPRECATED, name == "deprecated" || name.ends_with(".deprecated"));
+                flags.set_if(SymbolFlags::TEST_ONLY, name.starts_with("pytest.fixture"));
+            }
+        }
+        
+        let docstring = node
+            .child_by_field_name("body")
+            .and_then(|body| body.named_child(0))
+            .filter(|stmt| stmt.kind() == "expression_statement")
+            .and_then(|stmt| stmt.named_child(0))
+            .filter(|expr| expr.kind() == "string")
+            .map(|doc| self.node_text(doc, source));
+        flags.set_if(
+            SymbolFlags::DEPRECATED,
+            docstring.is_some_and(|doc| doc.contains(".. deprecated::")),
+        );
+        
+        let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+        flags.set_if(
+            SymbolFlags::TEST_ONLY,
+            file_name.starts_with("test_") || file_name.ends_with("_test.py") || file_name == "conftest.py",
+        );
+        flags
+    }
+    
+    /// Parameters of a `def`; `self`/`cls` are kept as written
+    fn extract_parameters(&self, node: Node, source: &[u8]) -> Vec<Parameter> {
+        let Some(params) = node.child_by_field_name("parameters") else {
+            return Vec::new();
+        };
+        params
+            .named_children(&mut params.walk())
+            .filter_map(|param| self.parameter(param, source))
+            .collect()
+    }
+    
+    fn parameter(&self, param: Node, source: &[u8]) -> Option<Parameter> {
+        let text = |node: Node| self.node_text(node, source);
+        match param.kind() {
+            "identifier" => Some(Parameter { name: text(param), ..Default::default() }),
+            // `*args`, `**kwargs`
+            "list_splat_pattern" | "dictionary_splat_pattern" => Some(Parameter {
+                name: text(param.named_child(0)?),
+                variadic: true,
+                ..Default::default()
+            }),
+            // `x: int`, `*args: str`
+            "typed_parameter" => {
+                let mut parameter = self.parameter(param.named_child(0)?, source)?;
+                parameter.type_name = param.child_by_field_name("type").map(text);
+                Some(parameter)
+            }
+            // `x=1`, `x: int = 1`
+            "default_parameter" | "typed_default_parameter" => Some(Parameter {
+                name: text(param.child_by_field_name("name")?),
+                type_name: param.child_by_field_name("type").map(text),
+                default: param.child_by_field_name("value").map(text),
+                variadic: false,
+            }),
+            // `*` and `/` separators carry no name
+            _ => None,
+        }
+    }
+    
     fn node_text(&self, node: Node, source: &[u8]) -> String {
         std::str::from_utf8(&source[node.byte_range()])
             .unwrap_or("")
@@ -432,6 +625,68 @@ def add(a, b):
         Ok(())
     }
     
+    #[test]
+    fn test_parse_python_parameters() -> Result<()> {
+        let mut harness = PythonHarness::new()?;
+        
+        let code = r#"
+def fetch(url: str, retries=3, *args, timeout: float = 1.0, **kwargs):
+    pass
+"#;
+        
+        let (symbols, _, _) = harness.parse_file(code, "test.py", "abc123")?;
+        let fetch = symbols.iter().find(|s| s.name == "fetch").expect("Should find fetch");
+        
+        let summary: Vec<_> = fetch.parameters.iter()
+            .map(|p| (p.name.as_str(), p.type_name.as_deref(), p.default.as_deref(), p.variadic))
+            .collect();
+        assert_eq!(summary, vec![
+            ("url", Some("str"), None, false),
+            ("retries", None, Some("3"), false),
+            ("args", None, None, true),
+            ("timeout", Some("float"), Some("1.0"), false),
+            ("kwargs", None, None, true),
+        ]);
+        assert_eq!(fetch.return_type, None);
+        
+        let (symbols, _, _) = harness.parse_file("def load() -> dict[str, int]:\n    pass\n", "test.py", "abc123")?;
+        assert_eq!(symbols[0].return_type.as_deref(), Some("dict[str, int]"));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_parse_python_flags() -> Result<()> {
+        let mut harness = PythonHarness::new()?;
+        
+        let code = r#"
+@deprecated("use load_v2")
+def load():
+    pass
+
+def load_v2():
+    """Load things.
+
+    .. deprecated:: 2.0
+    """
+
+@pytest.fixture
+def client():
+    pass
+"#;
+        
+        let (symbols, _, _) = harness.parse_file(code, "app/load.py", "abc123")?;
+        let flags = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().flags;
+        assert_eq!(flags("load"), SymbolFlags::DEPRECATED);
+        assert_eq!(flags("load_v2"), SymbolFlags::DEPRECATED);
+        assert_eq!(flags("client"), SymbolFlags::TEST_ONLY);
+        
+        let (symbols, _, _) = harness.parse_file(code, "tests/test_load.py", "abc123")?;
+        assert!(symbols.iter().all(|s| s.flags.contains(SymbolFlags::TEST_ONLY)));
+        
+        Ok(())
+    }
+    
     #[test]
     fn test_parse_python_class() -> Result<()> {
         let mut harness = PythonHarness::new()?;
@@ -492,6 +747,61 @@ from ..parent import something
         Ok(())
     }
     
+    #[test]
+    fn test_import_aliases() -> Result<()> {
+        let mut harness = PythonHarness::new()?;
+        
+        let code = r#"
+import numpy as np
+import os
+from app.models import User as Account, Order
+"#;
+        
+        let (_, edges, _) = harness.parse_file(code, "pkg/views.py", "abc123")?;
+        
+        let aliases: Vec<_> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Aliases)
+            .collect();
+        assert_eq!(aliases.len(), 2);
+        
+        assert_eq!(aliases[0].src.as_deref(), Some("pkg.views.np"));
+        assert_eq!(aliases[0].dst.as_deref(), Some("numpy"));
+        assert_eq!(aliases[1].src.as_deref(), Some("pkg.views.Account"));
+        assert_eq!(aliases[1].dst.as_deref(), Some("app.models.User"));
+        assert_eq!(aliases[1].meta["alias"], "Account");
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_package_init_reexports() -> Result<()> {
+        let mut harness = PythonHarness::new()?;
+        
+        let code = r#"
+from .models import User, Order as PurchaseOrder
+from ..shared.util import slugify
+import logging
+"#;
+        
+        let (_, edges, _) = harness.parse_file(code, "app/accounts/__init__.py", "abc123")?;
+        
+        let aliases: Vec<_> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Aliases)
+            .map(|e| (e.src.clone().unwrap(), e.dst.clone().unwrap()))
+            .collect();
+        
+        assert_eq!(aliases, vec![
+            ("app.accounts.User".to_string(), "app.accounts.models.User".to_string()),
+            ("app.accounts.PurchaseOrder".to_string(), "app.accounts.models.Order".to_string()),
+            ("app.accounts.slugify".to_string(), "app.shared.util.slugify".to_string()),
+        ]);
+        assert!(edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Aliases)
+            .all(|e| e.meta["alias_kind"] == "reexport"));
+        
+        Ok(())
+    }
+    
     #[test]
     fn test_decorators_and_properties() -> Result<()> {
         let mut harness = PythonHarness::new()?;
@@ -845,10 +1155,10 @@ from ..parent import something
         
         for symbol in &symbols {
             // Spans should have valid line/column numbers
-            assert!(symbol.span.start_line <= symbol.span.end_line,
+            assert!(symbol.full_span.start_line <= symbol.full_span.end_line,
                 "Start line should be <= end line");
-            if symbol.span.start_line == symbol.span.end_line {
-                assert!(symbol.span.start_col <= symbol.span.end_col,
+            if symbol.full_span.start_line == symbol.full_span.end_line {
+                assert!(symbol.full_span.start_col <= symbol.full_span.end_col,
                     "Start col should be <= end col on same line");
             }
         }
@@ -0,0 +1,24 @@
+//! Snapshot of the full IR emitted for the `testdata/python-basic` fixture.
+//!
+//! Review changes with `cargo insta review`, or rerun with
+//! `INSTA_UPDATE=always` to accept them.
+
+use py_harness::PythonHarness;
+use std::path::Path;
+
+fn fixture(path: &str) -> String {
+    std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../testdata").join(path)).unwrap()
+}
+
+#[test]
+fn test_python_basic_snapshot() {
+    let mut harness = PythonHarness::new().unwrap();
+    let content = fixture("python-basic/service.py");
+    let (symbols, edges, occurrences) = harness.parse_file(&content, "service.py", "snapshot").unwrap();
+
+    insta::with_settings!({ sort_maps => true, snapshot_suffix => "python-basic" }, {
+        insta::assert_json_snapshot!("symbols", symbols);
+        insta::assert_json_snapshot!("edges", edges);
+        insta::assert_json_snapshot!("occurrences", occurrences);
+    });
+}
@@ -1,8 +1,12 @@
 use anyhow::{Context, Result};
-use protocol::{EdgeIR, EdgeType, OccurrenceIR, OccurrenceRole, SymbolIR, SymbolKind, Language as ProtoLanguage, Span};
+use protocol::{Capabilities, EdgeIR, EdgeType, OccurrenceIR, OccurrenceRole, Parameter, SymbolFlags, SymbolIR, SymbolKind, Language as ProtoLanguage, Resolution, Span};
 use std::collections::HashMap;
 use tree_sitter::{Language, Node, Parser};
 
+pub mod workspace;
+
+pub use workspace::CargoWorkspace;
+
 extern "C" {
     fn tree_sitter_rust() -> Language;
 }
@@ -11,8 +15,19 @@ pub fn get_language() -> Language {
     unsafe { tree_sitter_rust() }
 }
 
+/// What this harness extracts, for `reviewbot query languages`
+pub const CAPABILITIES: Capabilities = Capabilities {
+    name: "Rust",
+    extensions: &["rs"],
+    edges: &[EdgeType::Contains, EdgeType::Imports, EdgeType::Aliases, EdgeType::Calls, EdgeType::Implements],
+    occurrences: &[OccurrenceRole::Definition, OccurrenceRole::Reference, OccurrenceRole::Call],
+    docs: false,
+    resolution: Resolution::Syntactic,
+};
+
 pub struct RustHarness {
     parser: Parser,
+    workspace: CargoWorkspace,
 }
 
 impl RustHarness {
@@ -22,7 +37,13 @@ impl RustHarness {
         parser
             .set_language(&language)
             .context("Failed to set Rust language")?;
-        Ok(Self { parser })
+        Ok(Self { parser, workspace: CargoWorkspace::new() })
+    }
+
+    /// Point `use` paths of declared dependencies at their crate nodes
+    pub fn with_workspace(mut self, workspace: CargoWorkspace) -> Self {
+        self.workspace = workspace;
+        self
     }
 
     pub fn parse(
@@ -136,7 +157,7 @@ impl RustHarness {
                 return Ok(()); // mod_item handles its own children
             }
             "use_declaration" => {
-                self.handle_use(node, content, file_path, edges, occurrences)?;
+                self.handle_use(node, content, file_path, edges, occurrences, module_stack)?;
             }
             "const_item" | "static_item" => {
                 self.handle_const_or_static(
@@ -220,10 +241,16 @@ impl RustHarness {
             name: name.clone(),
             fqn: fqn.clone(),
             signature: Some(signature),
+            parameters: self.get_parameters(node, content),
+            return_type: self.get_return_type(node, content),
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if self.is_public(node) { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content) | self.function_qualifiers(node),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
@@ -265,10 +292,16 @@ impl RustHarness {
             name: name.clone(),
             fqn: fqn.clone(),
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if self.is_public(node) { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
@@ -323,10 +356,16 @@ impl RustHarness {
                 name: name.clone(),
                 fqn,
                 signature: None,
+                parameters: Vec::new(),
+                return_type: None,
                 file_path: file_path.to_string(),
-                span: self.node_to_span(name_node),
+                name_span: self.node_to_span(name_node),
+                full_span: self.node_to_span(node),
                 visibility: if self.is_public(node) { Some("public".to_string()) } else { None },
+                flags: self.symbol_flags(node, file_path, content),
+                configs: Vec::new(),
                 doc: None,
+                doc_tags: None,
                 sig_hash,
             };
 
@@ -368,10 +407,16 @@ impl RustHarness {
             name: name.clone(),
             fqn: fqn.clone(),
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if self.is_public(node) { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
@@ -426,10 +471,16 @@ impl RustHarness {
                 name: name.clone(),
                 fqn,
                 signature: None,
+                parameters: Vec::new(),
+                return_type: None,
                 file_path: file_path.to_string(),
-                span: self.node_to_span(name_node),
+                name_span: self.node_to_span(name_node),
+                full_span: self.node_to_span(node),
                 visibility: None, // Enum variants inherit visibility from the enum
+                flags: self.symbol_flags(node, file_path, content),
+                configs: Vec::new(),
                 doc: None,
+                doc_tags: None,
                 sig_hash,
             };
 
@@ -468,14 +519,17 @@ impl RustHarness {
         // Set impl context for nested functions
         *impl_context = impl_type.clone();
 
-        // If implementing a trait, create an edge
-        if let (Some(impl_type), Some(trait_name)) = (&impl_type, &trait_name) {
-            let from_fqn = self.build_fqn(module_stack, None, impl_type);
-            let from_id = format!("{}#{}", file_path, from_fqn);
+        // The struct or enum's own symbol: `impl<T> Stack<T>` is for `Stack`
+        let type_id = impl_type.as_deref().map(|impl_type| {
+            let base = impl_type.split('<').next().unwrap_or(impl_type).trim();
+            format!("{}#{}", file_path, self.build_fqn(module_stack, None, base))
+        });
 
+        // If implementing a trait, create an edge
+        if let (Some(type_id), Some(trait_name)) = (&type_id, &trait_name) {
             edges.push(EdgeIR {
                 edge_type: EdgeType::Implements,
-                src: Some(from_id),
+                src: Some(type_id.clone()),
                 dst: Some(trait_name.clone()),
                 file_src: Some(file_path.to_string()),
                 file_dst: None,
@@ -486,6 +540,7 @@ impl RustHarness {
         }
 
         // Process impl body
+        let first_new = symbols.len();
         if let Some(body) = node.child_by_field_name("body") {
             for child in body.children(&mut body.walk()) {
                 self.walk_node(
@@ -501,6 +556,27 @@ impl RustHarness {
             }
         }
 
+        // The type contains its methods, so listing them doesn't depend on
+        // matching FQN prefixes
+        if let Some(type_id) = &type_id {
+            for method in symbols[first_new..].iter().filter(|s| s.kind == SymbolKind::Method) {
+                let mut meta = HashMap::new();
+                if let Some(trait_name) = &trait_name {
+                    meta.insert("trait".to_string(), serde_json::json!(trait_name));
+                }
+                edges.push(EdgeIR {
+                    edge_type: EdgeType::Contains,
+                    src: Some(type_id.clone()),
+                    dst: Some(method.id.clone()),
+                    file_src: Some(file_path.to_string()),
+                    file_dst: Some(file_path.to_string()),
+                    resolution: protocol::Resolution::Syntactic,
+                    meta,
+                    provenance: HashMap::new(),
+                });
+            }
+        }
+
         // Clear impl context
         *impl_context = None;
 
@@ -532,10 +608,16 @@ impl RustHarness {
             name: name.clone(),
             fqn,
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if self.is_public(node) { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
@@ -579,10 +661,16 @@ impl RustHarness {
             name: name.clone(),
             fqn: fqn.clone(),
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if self.is_public(node) { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
@@ -624,11 +712,42 @@ impl RustHarness {
         file_path: &str,
         edges: &mut Vec<EdgeIR>,
         occurrences: &mut Vec<OccurrenceIR>,
+        module_stack: &[String],
     ) -> Result<()> {
         // Extract the import path
         if let Some(tree_node) = node.child_by_field_name("argument") {
+            // `pub use` re-exports every imported name; plain `use` only aliases on `as`
+            let is_reexport = self.is_public(node);
+            let mut aliases = Vec::new();
+            self.collect_use_aliases(tree_node, content, &[], !is_reexport, &mut aliases);
+            
+            for (alias, target) in aliases {
+                let mut meta = HashMap::new();
+                meta.insert("alias".to_string(), serde_json::json!(alias));
+                meta.insert(
+                    "alias_kind".to_string(),
+                    serde_json::json!(if is_reexport { "reexport" } else { "import_as" }),
+                );
+                
+                edges.push(EdgeIR {
+                    edge_type: EdgeType::Aliases,
+                    src: Some(self.build_fqn(module_stack, None, &alias)),
+                    dst: Some(target),
+                    file_src: Some(file_path.to_string()),
+                    file_dst: None,
+                    resolution: protocol::Resolution::Syntactic,
+                    meta,
+                    provenance: HashMap::new(),
+                });
+            }
+            
             let import_path = self.get_import_path(tree_node, content);
             if !import_path.is_empty() {
+                let mut meta = HashMap::new();
+                let first_segment = import_path.split("::").next().unwrap_or_default();
+                if let Some(crate_id) = self.workspace.resolve_use(file_path, first_segment) {
+                    meta.insert("crate".to_string(), serde_json::json!(crate_id));
+                }
                 edges.push(EdgeIR {
                     edge_type: EdgeType::Imports,
                     src: Some(format!("{}#root", file_path)),
@@ -636,7 +755,7 @@ impl RustHarness {
                     file_src: Some(file_path.to_string()),
                     file_dst: None,
                     resolution: protocol::Resolution::Syntactic,
-                    meta: HashMap::new(),
+                    meta,
                     provenance: HashMap::new(),
                 });
 
@@ -679,10 +798,16 @@ impl RustHarness {
             name: name.clone(),
             fqn,
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if self.is_public(node) { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
@@ -724,10 +849,16 @@ impl RustHarness {
             name: name.clone(),
             fqn,
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: file_path.to_string(),
-            span: self.node_to_span(name_node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: if self.is_public(node) { Some("public".to_string()) } else { None },
+            flags: self.symbol_flags(node, file_path, content),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash,
         };
 
@@ -845,7 +976,86 @@ impl RustHarness {
         }
     }
 
+    /// Collect `(alias, target path)` pairs from a use tree.
+    ///
+    /// With `only_renames`, plain imports are skipped and only `x as y` clauses
+    /// are reported. A leading `crate` segment is dropped from targets since
+    /// symbol FQNs are crate-relative.
+    fn collect_use_aliases(
+        &self,
+        node: Node,
+        content: &str,
+        prefix: &[String],
+        only_renames: bool,
+        aliases: &mut Vec<(String, String)>,
+    ) {
+        let join_target = |parts: Vec<String>| {
+            parts
+                .into_iter()
+                .skip_while(|p| p == "crate")
+                .collect::<Vec<_>>()
+                .join("::")
+        };
+
+        match node.kind() {
+            "identifier" | "scoped_identifier" => {
+                if only_renames {
+                    return;
+                }
+                let mut parts = prefix.to_vec();
+                if node.kind() == "identifier" {
+                    parts.push(self.get_text(node, content));
+                } else {
+                    self.collect_scoped_parts(node, content, &mut parts);
+                }
+                if let Some(alias) = parts.last().cloned() {
+                    aliases.push((alias, join_target(parts)));
+                }
+            }
+            "use_as_clause" => {
+                let (Some(path), Some(alias)) = (
+                    node.child_by_field_name("path"),
+                    node.child_by_field_name("alias"),
+                ) else {
+                    return;
+                };
+                let mut parts = prefix.to_vec();
+                if path.kind() == "scoped_identifier" {
+                    self.collect_scoped_parts(path, content, &mut parts);
+                } else {
+                    parts.push(self.get_text(path, content));
+                }
+                aliases.push((self.get_text(alias, content), join_target(parts)));
+            }
+            "scoped_use_list" => {
+                let mut parts = prefix.to_vec();
+                if let Some(path) = node.child_by_field_name("path") {
+                    if path.kind() == "scoped_identifier" {
+                        self.collect_scoped_parts(path, content, &mut parts);
+                    } else {
+                        parts.push(self.get_text(path, content));
+                    }
+                }
+                if let Some(list) = node.child_by_field_name("list") {
+                    self.collect_use_aliases(list, content, &parts, only_renames, aliases);
+                }
+            }
+            "use_list" => {
+                for child in node.named_children(&mut node.walk()) {
+                    self.collect_use_aliases(child, content, prefix, only_renames, aliases);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn collect_scoped_parts(&self, node: Node, content: &str, parts: &mut Vec<String>) {
+        // The innermost path segment is a bare identifier (or `crate`/`super`/`self`)
+        if node.kind() != "scoped_identifier" {
+            parts.push(self.get_text(node, content));
+            return;
+        }
+
         if let Some(path) = node.child_by_field_name("path") {
             self.collect_scoped_parts(path, content, parts);
         }
@@ -867,6 +1077,90 @@ impl RustHarness {
         None
     }
 
+    /// Outer attributes (`#[...]`) written above an item; tree-sitter keeps
+    /// them as preceding siblings rather than children
+    fn outer_attributes(&self, node: Node, content: &str) -> Vec<String> {
+        let mut attributes = Vec::new();
+        let mut current = node.prev_sibling();
+        while let Some(sibling) = current {
+            match sibling.kind() {
+                "attribute_item" => attributes.push(self.get_text(sibling, content)),
+                "line_comment" | "block_comment" => {}
+                _ => break,
+            }
+            current = sibling.prev_sibling();
+        }
+        attributes
+    }
+
+    /// Flags from `#[deprecated]`, `#[unstable]`, `#[test]` / `#[cfg(test)]`
+    /// on the item or an enclosing module, and integration test directories
+    fn symbol_flags(&self, node: Node, file_path: &str, content: &str) -> SymbolFlags {
+        let mut flags = SymbolFlags::empty();
+        let attribute_name = |attr: &str| {
+            attr.trim_start_matches("#[")
+                .split(['(', ']', ' ', '='])
+                .next()
+                .unwrap_or("")
+                .to_string()
+        };
+        for attr in self.outer_attributes(node, content) {
+            let name = attribute_name(&attr);
+            flags.set_if(SymbolFlags::DEPRECATED, name == "deprecated");
+            flags.set_if(SymbolFlags::EXPERIMENTAL, name == "unstable");
+            flags.set_if(SymbolFlags::TEST_ONLY, name == "test" || attr.replace(' ', "") == "#[cfg(test)]");
+        }
+
+        let mut ancestor = node.parent();
+        while let Some(item) = ancestor {
+            if item.kind() == "mod_item"
+                && self.outer_attributes(item, content).iter().any(|attr| attr.replace(' ', "") == "#[cfg(test)]")
+            {
+                flags.insert(SymbolFlags::TEST_ONLY);
+            }
+            ancestor = item.parent();
+        }
+
+        let in_test_dir = file_path
+            .split('/')
+            .rev()
+            .skip(1)
+            .any(|dir| dir == "tests" || dir == "benches");
+        flags.set_if(SymbolFlags::TEST_ONLY, in_test_dir);
+        flags
+    }
+
+    /// `async`, `unsafe`, `const` and `extern` on a fn, and whether its own
+    /// body (not nested fns) has unsafe blocks
+    fn function_qualifiers(&self, node: Node) -> SymbolFlags {
+        let mut flags = SymbolFlags::empty();
+        if let Some(modifiers) = node.children(&mut node.walk()).find(|c| c.kind() == "function_modifiers") {
+            for modifier in modifiers.children(&mut modifiers.walk()) {
+                match modifier.kind() {
+                    "async" => flags.insert(SymbolFlags::ASYNC),
+                    "unsafe" => flags.insert(SymbolFlags::UNSAFE),
+                    "const" => flags.insert(SymbolFlags::CONST),
+                    "extern_modifier" => flags.insert(SymbolFlags::EXTERN),
+                    _ => {}
+                }
+            }
+        }
+        // Declarations in `extern "C" { ... }`
+        flags.set_if(
+            SymbolFlags::EXTERN,
+            node.parent().and_then(|list| list.parent()).is_some_and(|item| item.kind() == "foreign_mod_item"),
+        );
+
+        fn has_unsafe_block(node: Node) -> bool {
+            node.children(&mut node.walk())
+                .any(|child| child.kind() == "unsafe_block" || (child.kind() != "function_item" && has_unsafe_block(child)))
+        }
+        if let Some(body) = node.child_by_field_name("body") {
+            flags.set_if(SymbolFlags::UNSAFE_BLOCKS, has_unsafe_block(body));
+        }
+        flags
+    }
+
     fn is_public(&self, node: Node) -> bool {
         node.children(&mut node.walk())
             .any(|child| child.kind() == "visibility_modifier")
@@ -886,15 +1180,55 @@ impl RustHarness {
         }
         
         // Get return type
-        if let Some(return_type_node) = node.child_by_field_name("return_type") {
+        if let Some(return_type) = self.get_return_type(node, content) {
             sig.push_str(" -> ");
-            if let Some(type_node) = return_type_node.child(1) {
-                sig.push_str(&self.get_text(type_node, content));
-            }
+            sig.push_str(&return_type);
         }
         
         sig
     }
+
+    /// Declared return type of a fn (the `return_type` field is the type itself)
+    fn get_return_type(&self, node: Node, content: &str) -> Option<String> {
+        node.child_by_field_name("return_type").map(|t| self.get_text(t, content))
+    }
+
+    /// Parameters of a fn; the receiver is reported as `self` with its
+    /// written form (`&mut self`) as the type
+    fn get_parameters(&self, node: Node, content: &str) -> Vec<Parameter> {
+        let Some(params_node) = node.child_by_field_name("parameters") else {
+            return Vec::new();
+        };
+        let mut parameters = Vec::new();
+        for param in params_node.named_children(&mut params_node.walk()) {
+            match param.kind() {
+                "self_parameter" => parameters.push(Parameter {
+                    name: "self".to_string(),
+                    type_name: Some(self.get_text(param, content)),
+                    ..Default::default()
+                }),
+                "parameter" => parameters.push(Parameter {
+                    name: param
+                        .child_by_field_name("pattern")
+                        .map(|pattern| self.get_text(pattern, content))
+                        .unwrap_or_default(),
+                    type_name: param.child_by_field_name("type").map(|t| self.get_text(t, content)),
+                    ..Default::default()
+                }),
+                // C-variadic `...` in extern fns
+                "variadic_parameter" => parameters.push(Parameter {
+                    name: param
+                        .child_by_field_name("pattern")
+                        .map(|pattern| self.get_text(pattern, content))
+                        .unwrap_or_default(),
+                    variadic: true,
+                    ..Default::default()
+                }),
+                _ => {}
+            }
+        }
+        parameters
+    }
 }
 
 #[cfg(test)]
@@ -916,6 +1250,8 @@ pub fn calculate(x: i32, y: i32) -> i32 {
         assert_eq!(symbols[0].name, "calculate");
         assert_eq!(symbols[0].kind, SymbolKind::Function);
         assert_eq!(symbols[0].visibility, Some("public".to_string()));
+        assert_eq!(symbols[0].return_type.as_deref(), Some("i32"));
+        assert_eq!(symbols[0].signature.as_deref(), Some("calculate(x: i32, y: i32) -> i32"));
 
         assert_eq!(occurrences.len(), 1);
         assert_eq!(occurrences[0].role, OccurrenceRole::Definition);
@@ -923,6 +1259,116 @@ pub fn calculate(x: i32, y: i32) -> i32 {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_parameters() -> Result<()> {
+        let mut harness = RustHarness::new()?;
+        let content = r#"
+struct Counter;
+
+impl Counter {
+    fn add(&mut self, (a, b): (u32, u32), label: &str) {}
+}
+"#;
+
+        let (symbols, _, _) = harness.parse("test.rs", content)?;
+        let add = symbols.iter().find(|s| s.name == "add").expect("Should find add");
+        let summary: Vec<_> = add.parameters.iter()
+            .map(|p| (p.name.as_str(), p.type_name.as_deref()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("self", Some("&mut self")),
+            ("(a, b)", Some("(u32, u32)")),
+            ("label", Some("&str")),
+        ]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_use_edges_point_at_crates() -> Result<()> {
+        let mut workspace = CargoWorkspace::new();
+        workspace.add_crate(workspace::WorkspaceCrate {
+            name: "app".to_string(),
+            dir: "app".to_string(),
+            manifest: "app/Cargo.toml".to_string(),
+            dependencies: vec![workspace::CrateDependency {
+                name: "serde".to_string(),
+                package: "serde".to_string(),
+                kind: "normal".to_string(),
+            }],
+        });
+        let mut harness = RustHarness::new()?.with_workspace(workspace);
+        let content = "use serde::Deserialize;\nuse std::fmt;\n";
+
+        let (_, edges, _) = harness.parse("app/src/main.rs", content)?;
+        let import = |dst: &str| edges.iter().find(|e| e.edge_type == EdgeType::Imports && e.dst.as_deref() == Some(dst)).unwrap();
+        assert_eq!(import("serde::Deserialize").meta.get("crate"), Some(&serde_json::json!("crate:serde")));
+        assert!(!import("std::fmt").meta.contains_key("crate"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_flags() -> Result<()> {
+        let mut harness = RustHarness::new()?;
+        let content = r#"
+#[deprecated(since = "1.2.0", note = "use `load_v2`")]
+pub fn load() {}
+
+pub fn load_v2() {}
+
+#[cfg(test)]
+mod tests {
+    fn helper() {}
+}
+"#;
+
+        let (symbols, _, _) = harness.parse("src/lib.rs", content)?;
+        let flags = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().flags;
+        assert_eq!(flags("load"), SymbolFlags::DEPRECATED);
+        assert!(flags("load_v2").is_empty());
+        assert_eq!(flags("tests"), SymbolFlags::TEST_ONLY);
+        assert_eq!(flags("helper"), SymbolFlags::TEST_ONLY);
+
+        let (symbols, _, _) = harness.parse("tests/api.rs", "fn check() {}")?;
+        assert_eq!(symbols[0].flags, SymbolFlags::TEST_ONLY);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_function_qualifiers() -> Result<()> {
+        let mut harness = RustHarness::new()?;
+        let content = r#"
+pub async fn fetch() {}
+pub unsafe fn raw(ptr: *const u8) {}
+const fn size() -> usize { 4 }
+pub extern "C" fn callback() {}
+fn read(ptr: *const u8) -> u8 {
+    unsafe { *ptr }
+}
+fn outer() {
+    fn inner() { unsafe {} }
+}
+extern "C" {
+    fn abs(x: i32) -> i32;
+}
+"#;
+
+        let (symbols, _, _) = harness.parse("src/ffi.rs", content)?;
+        let flags = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().flags.to_string();
+        assert_eq!(flags("fetch"), "async");
+        assert_eq!(flags("raw"), "unsafe");
+        assert_eq!(flags("size"), "const");
+        assert_eq!(flags("callback"), "extern");
+        assert_eq!(flags("read"), "unsafe-blocks");
+        assert_eq!(flags("outer"), "");
+        assert_eq!(flags("inner"), "unsafe-blocks");
+        assert_eq!(flags("abs"), "extern");
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_struct() -> Result<()> {
         let mut harness = RustHarness::new()?;
@@ -1007,6 +1453,46 @@ impl Circle {
         Ok(())
     }
 
+    #[test]
+    fn test_impl_contains_methods() -> Result<()> {
+        let mut harness = RustHarness::new()?;
+        let content = r#"
+pub struct Stack<T> {
+    items: Vec<T>,
+}
+
+impl<T> Stack<T> {
+    pub fn push(&mut self, item: T) {}
+}
+
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Stack { items: Vec::new() }
+    }
+}
+"#;
+
+        let (symbols, edges, _) = harness.parse("src/stack.rs", content)?;
+        let stack = symbols.iter().find(|s| s.name == "Stack").unwrap();
+        let contains: Vec<(&str, &str, Option<&serde_json::Value>)> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Contains)
+            .map(|e| (e.src.as_deref().unwrap(), e.dst.as_deref().unwrap(), e.meta.get("trait")))
+            .collect();
+        let default_trait = serde_json::json!("Default");
+        assert_eq!(
+            contains,
+            [
+                (stack.id.as_str(), "src/stack.rs#Stack<T>::push", None),
+                (stack.id.as_str(), "src/stack.rs#Stack<T>::default", Some(&default_trait)),
+            ]
+        );
+        let implements = edges.iter().find(|e| e.edge_type == EdgeType::Implements).unwrap();
+        assert_eq!(implements.src.as_ref(), Some(&stack.id));
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_trait() -> Result<()> {
         let mut harness = RustHarness::new()?;
@@ -1040,6 +1526,30 @@ use std::io::{Read, Write};
         Ok(())
     }
 
+    #[test]
+    fn test_use_aliases() -> Result<()> {
+        let mut harness = RustHarness::new()?;
+        let content = r#"
+use std::collections::HashMap as Map;
+use std::io::Read;
+pub use crate::shapes::{Circle, square::Square as Quad};
+"#;
+
+        let (_, edges, _) = harness.parse("lib.rs", content)?;
+
+        let aliases: Vec<_> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Aliases)
+            .map(|e| (e.src.clone().unwrap(), e.dst.clone().unwrap(), e.meta["alias_kind"].clone()))
+            .collect();
+
+        assert_eq!(aliases.len(), 3, "plain `use` without `as` is not an alias: {:?}", aliases);
+        assert!(aliases.contains(&("Map".to_string(), "std::collections::HashMap".to_string(), serde_json::json!("import_as"))));
+        assert!(aliases.contains(&("Circle".to_string(), "shapes::Circle".to_string(), serde_json::json!("reexport"))));
+        assert!(aliases.contains(&("Quad".to_string(), "shapes::square::Square".to_string(), serde_json::json!("reexport"))));
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_module() -> Result<()> {
         let mut harness = RustHarness::new()?;
@@ -0,0 +1,536 @@
+use protocol::{EdgeIR, EdgeType, Language as ProtoLanguage, Resolution, Span, SymbolFlags, SymbolIR, SymbolKind};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A crate of the Cargo workspace
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceCrate {
+    /// Package name from `[package] name`
+    pub name: String,
+    /// Repo-relative crate directory, empty for a root package
+    pub dir: String,
+    /// Repo-relative path of the crate's Cargo.toml
+    pub manifest: String,
+    pub dependencies: Vec<CrateDependency>,
+}
+
+/// An entry of `[dependencies]`, `[dev-dependencies]` or `[build-dependencies]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrateDependency {
+    /// Name the dependency is referred to by in code (`use serde_json::...`)
+    pub name: String,
+    /// Package actually depended on; differs from `name` when renamed
+    pub package: String,
+    /// `normal`, `dev` or `build`
+    pub kind: String,
+}
+
+/// Crates of a Cargo workspace and what they depend on.
+///
+/// Workspace members and their external dependencies become `Crate`
+/// symbols, so `use serde::Deserialize` can point at the `serde` node
+/// instead of ending at a bare path.
+#[derive(Debug, Clone, Default)]
+pub struct CargoWorkspace {
+    crates: Vec<WorkspaceCrate>,
+}
+
+impl CargoWorkspace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the root Cargo.toml, its `[workspace] members` and each member's
+    /// manifest. A root `[package]` counts as a crate too.
+    pub fn detect(repo_root: &Path) -> Self {
+        let mut workspace = Self::new();
+        let Some(root) = read_manifest(&repo_root.join("Cargo.toml")) else {
+            return workspace;
+        };
+
+        let inherited = workspace_dependencies(&root);
+        if let Some(name) = root.value("package", "name") {
+            workspace.add_crate(crate_from_manifest(name, "", &root, &inherited));
+        }
+
+        let excluded = root.array("workspace", "exclude");
+        for pattern in root.array("workspace", "members") {
+            for dir in expand_member(repo_root, &pattern) {
+                if excluded.contains(&dir) {
+                    continue;
+                }
+                let Some(manifest) = read_manifest(&repo_root.join(&dir).join("Cargo.toml")) else {
+                    continue;
+                };
+                if let Some(name) = manifest.value("package", "name") {
+                    workspace.add_crate(crate_from_manifest(name, &dir, &manifest, &inherited));
+                }
+            }
+        }
+        workspace
+    }
+
+    pub fn add_crate(&mut self, krate: WorkspaceCrate) {
+        self.crates.retain(|c| c.name != krate.name);
+        self.crates.push(krate);
+    }
+
+    pub fn crates(&self) -> &[WorkspaceCrate] {
+        &self.crates
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.crates.is_empty()
+    }
+
+    /// The innermost crate whose directory contains `file_path`
+    pub fn crate_for(&self, file_path: &str) -> Option<&WorkspaceCrate> {
+        self.crates
+            .iter()
+            .filter(|c| c.dir.is_empty() || file_path.starts_with(&format!("{}/", c.dir)))
+            .max_by_key(|c| c.dir.len())
+    }
+
+    /// Id of the crate node a `use` path starting with `first_segment`
+    /// refers to from `file_path`: a declared dependency, or the file's own
+    /// crate when named explicitly
+    pub fn resolve_use(&self, file_path: &str, first_segment: &str) -> Option<String> {
+        let krate = self.crate_for(file_path)?;
+        if let Some(dependency) = krate.dependencies.iter().find(|d| d.name == first_segment) {
+            return Some(crate_id(&dependency.package));
+        }
+        (code_name(&krate.name) == first_segment).then(|| crate_id(&krate.name))
+    }
+
+    /// Crate symbols for members and external dependencies, and `DependsOn`
+    /// edges from each member to what its manifest declares
+    pub fn to_ir(&self) -> (Vec<SymbolIR>, Vec<EdgeIR>) {
+        let mut symbols: Vec<SymbolIR> = Vec::new();
+        let mut edges = Vec::new();
+        let manifests: HashMap<&str, &str> =
+            self.crates.iter().map(|c| (c.name.as_str(), c.manifest.as_str())).collect();
+
+        for krate in &self.crates {
+            symbols.push(crate_symbol(&krate.name, &krate.manifest));
+        }
+        for krate in &self.crates {
+            for dependency in &krate.dependencies {
+                let target_manifest = manifests.get(dependency.package.as_str()).copied();
+                // External crates live with the first manifest that names them
+                if target_manifest.is_none() && !symbols.iter().any(|s| s.id == crate_id(&dependency.package)) {
+                    symbols.push(crate_symbol(&dependency.package, &krate.manifest));
+                }
+                edges.push(EdgeIR {
+                    edge_type: EdgeType::DependsOn,
+                    src: Some(crate_id(&krate.name)),
+                    dst: Some(crate_id(&dependency.package)),
+                    file_src: Some(krate.manifest.clone()),
+                    file_dst: target_manifest.map(str::to_string),
+                    resolution: Resolution::Syntactic,
+                    meta: HashMap::from([("kind".to_string(), serde_json::json!(dependency.kind))]),
+                    provenance: HashMap::from([("source".to_string(), "build_file".to_string())]),
+                });
+            }
+        }
+        (symbols, edges)
+    }
+}
+
+/// Crates are keyed by package name, which is unique within a build
+pub fn crate_id(package: &str) -> String {
+    format!("crate:{}", package)
+}
+
+fn code_name(package: &str) -> String {
+    package.replace('-', "_")
+}
+
+fn crate_symbol(package: &str, manifest: &str) -> SymbolIR {
+    let span = Span { start_line: 0, start_col: 0, end_line: 0, end_col: 0 };
+    SymbolIR {
+        id: crate_id(package),
+        lang: ProtoLanguage::Rust,
+        lang_version: None,
+        kind: SymbolKind::Crate,
+        name: package.to_string(),
+        fqn: code_name(package),
+        signature: None,
+        parameters: Vec::new(),
+        return_type: None,
+        file_path: manifest.to_string(),
+        name_span: span.clone(),
+        full_span: span,
+        visibility: Some("public".to_string()),
+        flags: SymbolFlags::empty(),
+        configs: Vec::new(),
+        doc: None,
+        doc_tags: None,
+        sig_hash: format!("{:x}", md5::compute(package)),
+    }
+}
+
+fn crate_from_manifest(
+    name: &str,
+    dir: &str,
+    manifest: &Manifest,
+    inherited: &HashMap<String, String>,
+) -> WorkspaceCrate {
+    let mut dependencies = Vec::new();
+    for (table, kind) in dependency_tables(manifest) {
+        for (key, fields) in manifest.entries(&table) {
+            let inherits = fields.get("workspace").map(String::as_str) == Some("true");
+            let package = match fields.get("package") {
+                Some(package) => package.clone(),
+                None if inherits => inherited.get(&key).cloned().unwrap_or_else(|| key.clone()),
+                None => key.clone(),
+            };
+            let dependency = CrateDependency { name: code_name(&key), package, kind: kind.to_string() };
+            if !dependencies.contains(&dependency) {
+                dependencies.push(dependency);
+            }
+        }
+    }
+    WorkspaceCrate {
+        name: name.to_string(),
+        dir: dir.to_string(),
+        manifest: if dir.is_empty() { "Cargo.toml".to_string() } else { format!("{}/Cargo.toml", dir) },
+        dependencies,
+    }
+}
+
+/// Dependency tables of a manifest, including `[target.'cfg(..)'.dependencies]`
+fn dependency_tables(manifest: &Manifest) -> Vec<(String, &'static str)> {
+    let mut tables = Vec::new();
+    for table in manifest.tables() {
+        let kind = if table == "dependencies" || (table.starts_with("target.") && table.ends_with(".dependencies")) {
+            "normal"
+        } else if table == "dev-dependencies" || table.ends_with(".dev-dependencies") {
+            "dev"
+        } else if table == "build-dependencies" || table.ends_with(".build-dependencies") {
+            "build"
+        } else {
+            continue;
+        };
+        if !table.starts_with("workspace.") {
+            tables.push((table.to_string(), kind));
+        }
+    }
+    tables
+}
+
+/// `[workspace.dependencies]` renames, so `foo = { workspace = true }` in a
+/// member resolves to the package the workspace actually pins
+fn workspace_dependencies(root: &Manifest) -> HashMap<String, String> {
+    root.entries("workspace.dependencies")
+        .into_iter()
+        .filter_map(|(key, fields)| fields.get("package").map(|package| (key, package.clone())))
+        .collect()
+}
+
+fn expand_member(repo_root: &Path, pattern: &str) -> Vec<String> {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    let Some(base) = pattern.strip_suffix("/*") else {
+        return vec![pattern.to_string()];
+    };
+    let Ok(entries) = std::fs::read_dir(repo_root.join(base)) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().join("Cargo.toml").is_file())
+        .map(|entry| format!("{}/{}", base, entry.file_name().to_string_lossy()))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Just enough TOML for Cargo manifests: `[table]` headers, `key = value`
+/// lines (values may be strings, inline tables or multi-line arrays), and
+/// dotted keys such as `serde.workspace = true`
+#[derive(Debug, Default)]
+struct Manifest {
+    /// `(table, key, raw value)` in file order
+    entries: Vec<(String, String, String)>,
+}
+
+fn read_manifest(path: &Path) -> Option<Manifest> {
+    std::fs::read_to_string(path).ok().map(|content| parse_manifest(&content))
+}
+
+fn parse_manifest(content: &str) -> Manifest {
+    let mut manifest = Manifest::default();
+    let mut table = String::new();
+    let mut pending: Option<(String, String)> = None;
+
+    for line in content.lines() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some((key, mut value)) = pending.take() {
+            value.push(' ');
+            value.push_str(line);
+            if balanced(&value) {
+                manifest.entries.push((table.clone(), key, value));
+            } else {
+                pending = Some((key, value));
+            }
+            continue;
+        }
+        if line.starts_with('[') {
+            table = line.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = unquote(key.trim());
+        let value = value.trim().to_string();
+        if balanced(&value) {
+            manifest.entries.push((table.clone(), key, value));
+        } else {
+            pending = Some((key, value));
+        }
+    }
+    manifest
+}
+
+impl Manifest {
+    fn tables(&self) -> Vec<&str> {
+        let mut tables: Vec<&str> = Vec::new();
+        for (table, _, _) in &self.entries {
+            // `[dependencies.serde]` belongs to `dependencies`
+            let table = dependency_table_of(table).unwrap_or(table);
+            if !tables.contains(&table) {
+                tables.push(table);
+            }
+        }
+        tables
+    }
+
+    fn value(&self, table: &str, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(t, k, _)| t == table && k == key)
+            .map(|(_, _, value)| value.trim_matches('"'))
+    }
+
+    fn array(&self, table: &str, key: &str) -> Vec<String> {
+        self.value(table, key).map(quoted_strings).unwrap_or_default()
+    }
+
+    /// Entries of a dependency table as `(key, fields)`, merging the inline
+    /// table, dotted-key and `[table.key]` spellings
+    fn entries(&self, table: &str) -> Vec<(String, HashMap<String, String>)> {
+        let mut entries: Vec<(String, HashMap<String, String>)> = Vec::new();
+        for (entry_table, key, value) in &self.entries {
+            if entry_table == table {
+                match key.split_once('.') {
+                    Some((name, field)) => {
+                        let index = entry_index(&mut entries, unquote(name));
+                        entries[index].1.insert(field.to_string(), unquote(value));
+                    }
+                    None => {
+                        let index = entry_index(&mut entries, key.clone());
+                        entries[index].1.extend(inline_table(value));
+                    }
+                }
+            } else if let Some(name) = entry_table.strip_prefix(table).and_then(|rest| rest.strip_prefix('.')) {
+                let index = entry_index(&mut entries, unquote(name));
+                entries[index].1.insert(key.clone(), unquote(value));
+            }
+        }
+        entries
+    }
+}
+
+fn entry_index(entries: &mut Vec<(String, HashMap<String, String>)>, name: String) -> usize {
+    match entries.iter().position(|(n, _)| *n == name) {
+        Some(index) => index,
+        None => {
+            entries.push((name, HashMap::new()));
+            entries.len() - 1
+        }
+    }
+}
+
+/// The dependency table a `[dependencies.serde]`-style header belongs to
+fn dependency_table_of(table: &str) -> Option<&str> {
+    ["dependencies", "dev-dependencies", "build-dependencies"]
+        .iter()
+        .find_map(|suffix| {
+            let index = table.find(&format!("{}.", suffix))?;
+            let end = index + suffix.len();
+            (index == 0 || table[..index].ends_with('.')).then(|| &table[..end])
+        })
+}
+
+/// Fields of `{ path = "../a", package = "b" }`; plain values yield nothing
+fn inline_table(value: &str) -> HashMap<String, String> {
+    let Some(body) = value.trim().strip_prefix('{').and_then(|v| v.strip_suffix('}')) else {
+        return HashMap::new();
+    };
+    split_top_level(body)
+        .into_iter()
+        .filter_map(|field| {
+            let (key, value) = field.split_once('=')?;
+            Some((unquote(key.trim()), unquote(value.trim())))
+        })
+        .collect()
+}
+
+/// Split on commas that are not inside quotes or brackets
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts.into_iter().filter(|p| !p.trim().is_empty()).collect()
+}
+
+fn balanced(value: &str) -> bool {
+    let mut depth = 0i32;
+    let mut quote = None;
+    for c in value.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            _ => {}
+        }
+    }
+    depth <= 0
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote(text: &str) -> String {
+    text.trim().trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+fn quoted_strings(text: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = text.char_indices();
+    while let Some((start, c)) = chars.next() {
+        if c == '"' || c == '\'' {
+            if let Some((end, _)) = chars.by_ref().find(|(_, d)| *d == c) {
+                strings.push(text[start + 1..end].to_string());
+            }
+        }
+    }
+    strings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_detect_workspace_crates() {
+        let root = std::env::temp_dir().join(format!("rust_workspace_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        write(
+            &root,
+            "Cargo.toml",
+            r#"
+[workspace]
+members = [
+    "crates/*",   # every crate
+]
+exclude = ["crates/scratch"]
+
+[workspace.dependencies]
+json = { package = "serde_json", version = "1" }
+"#,
+        );
+        write(
+            &root,
+            "crates/app/Cargo.toml",
+            r#"
+[package]
+name = "my-app"
+
+[dependencies]
+core-lib = { path = "../core" }
+json.workspace = true
+anyhow = "1"
+
+[dev-dependencies.tempfile]
+version = "3"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+"#,
+        );
+        write(&root, "crates/core/Cargo.toml", "[package]\nname = \"core-lib\"\n");
+        write(&root, "crates/scratch/Cargo.toml", "[package]\nname = \"scratch\"\n");
+
+        let workspace = CargoWorkspace::detect(&root);
+        let names: Vec<&str> = workspace.crates().iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["my-app", "core-lib"]);
+
+        let app = &workspace.crates()[0];
+        assert_eq!(app.manifest, "crates/app/Cargo.toml");
+        let dependencies: Vec<(&str, &str, &str)> = app
+            .dependencies
+            .iter()
+            .map(|d| (d.name.as_str(), d.package.as_str(), d.kind.as_str()))
+            .collect();
+        assert_eq!(
+            dependencies,
+            vec![
+                ("core_lib", "core-lib", "normal"),
+                ("json", "serde_json", "normal"),
+                ("anyhow", "anyhow", "normal"),
+                ("tempfile", "tempfile", "dev"),
+                ("libc", "libc", "normal"),
+            ]
+        );
+
+        assert_eq!(workspace.resolve_use("crates/app/src/main.rs", "json"), Some("crate:serde_json".to_string()));
+        assert_eq!(workspace.resolve_use("crates/app/src/main.rs", "my_app"), Some("crate:my-app".to_string()));
+        assert_eq!(workspace.resolve_use("crates/app/src/main.rs", "std"), None);
+
+        let (symbols, edges) = workspace.to_ir();
+        assert_eq!(symbols.len(), 6);
+        let core_edge = edges.iter().find(|e| e.dst.as_deref() == Some("crate:core-lib")).unwrap();
+        assert_eq!(core_edge.file_dst.as_deref(), Some("crates/core/Cargo.toml"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
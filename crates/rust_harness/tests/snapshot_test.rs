@@ -0,0 +1,24 @@
+//! Snapshot of the full IR emitted for the `testdata/rust-basic` fixture.
+//!
+//! Review changes with `cargo insta review`, or rerun with
+//! `INSTA_UPDATE=always` to accept them.
+
+use rust_harness::RustHarness;
+use std::path::Path;
+
+fn fixture(path: &str) -> String {
+    std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../testdata").join(path)).unwrap()
+}
+
+#[test]
+fn test_rust_basic_snapshot() {
+    let mut harness = RustHarness::new().unwrap();
+    let content = fixture("rust-basic/service.rs");
+    let (symbols, edges, occurrences) = harness.parse("service.rs", &content).unwrap();
+
+    insta::with_settings!({ sort_maps => true, snapshot_suffix => "rust-basic" }, {
+        insta::assert_json_snapshot!("symbols", symbols);
+        insta::assert_json_snapshot!("edges", edges);
+        insta::assert_json_snapshot!("occurrences", occurrences);
+    });
+}
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use protocol::{EdgeIR, EdgeType, OccurrenceIR, OccurrenceRole, Resolution, SymbolIR, SymbolKind, Language, Span};
+use protocol::{EdgeIR, EdgeType, OccurrenceIR, OccurrenceRole, Resolution, SymbolFlags, SymbolIR, SymbolKind, Language, Span};
 use std::collections::HashMap;
 use std::process::Command;
 use serde::{Deserialize, Serialize};
@@ -199,7 +199,18 @@ impl ScipMapper {
         for doc in &scip_index.documents {
             // Process symbols
             for scip_sym in &doc.symbols {
-                if let Some(symbol) = self.convert_symbol(scip_sym, &doc.relative_path, commit_sha) {
+                if let Some(mut symbol) = self.convert_symbol(scip_sym, &doc.relative_path, commit_sha) {
+                    // Definition occurrence gives the name range; its enclosing range covers the body
+                    let definition = doc.occurrences.iter().find(|occ| {
+                        occ.symbol == scip_sym.symbol && occ.symbol_roles.unwrap_or(0) & 1 != 0
+                    });
+                    if let Some(name_span) = definition.and_then(|occ| scip_range_to_span(&occ.range)) {
+                        symbol.full_span = definition
+                            .and_then(|occ| occ.enclosing_range.as_deref())
+                            .and_then(scip_range_to_span)
+                            .unwrap_or_else(|| name_span.clone());
+                        symbol.name_span = name_span;
+                    }
                     symbols.push(symbol);
                     
                     // Process relationships as edges
@@ -265,9 +276,16 @@ impl ScipMapper {
             fqn,
             signature: None,
             file_path: file_path.to_string(),
-            span: Span { start_line: 0, start_col: 0, end_line: 0, end_col: 0 }, // Will be filled from occurrences
+            parameters: Vec::new(),
+            return_type: None,
+            // Filled from the definition occurrence in map_scip_to_ir
+            name_span: Span::default(),
+            full_span: Span::default(),
             visibility: None,
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: scip_sym.documentation.as_ref().map(|d| d.join("\n")),
+            doc_tags: None,
             sig_hash,
         })
     }
@@ -292,12 +310,7 @@ impl ScipMapper {
     }
     
     fn convert_occurrence(&self, scip_occ: &ScipOccurrence, file_path: &str) -> Option<OccurrenceIR> {
-        // SCIP range format: [startLine, startCol, endCol] or [startLine, startCol, endLine, endCol]
-        let (start_line, start_col, end_line, end_col) = match scip_occ.range.len() {
-            3 => (scip_occ.range[0], scip_occ.range[1], scip_occ.range[0], scip_occ.range[2]), // Same line
-            4 => (scip_occ.range[0], scip_occ.range[1], scip_occ.range[2], scip_occ.range[3]), // Multi-line
-            _ => return None, // Invalid range format
-        };
+        let span = scip_range_to_span(&scip_occ.range)?;
         
         let role = match scip_occ.symbol_roles.unwrap_or(2) {
             1 => OccurrenceRole::Definition,
@@ -310,17 +323,27 @@ impl ScipMapper {
             file_path: file_path.to_string(),
             symbol_id: Some(scip_occ.symbol.clone()),
             role,
-            span: Span {
-                start_line: start_line as u32,
-                start_col: start_col as u32,
-                end_line: end_line as u32,
-                end_col: end_col as u32,
-            },
+            span,
             token: String::new(), // Would need to extract from source
         })
     }
 }
 
+/// SCIP range format: [startLine, startCol, endCol] or [startLine, startCol, endLine, endCol]
+fn scip_range_to_span(range: &[i32]) -> Option<Span> {
+    let (start_line, start_col, end_line, end_col) = match range.len() {
+        3 => (range[0], range[1], range[0], range[2]), // Same line
+        4 => (range[0], range[1], range[2], range[3]), // Multi-line
+        _ => return None, // Invalid range format
+    };
+    Some(Span {
+        start_line: start_line as u32,
+        start_col: start_col as u32,
+        end_line: end_line as u32,
+        end_col: end_col as u32,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,4 +353,35 @@ mod tests {
         let mapper = ScipMapper::new("scip-typescript", "1.0.0");
         assert!(mapper.provenance.contains_key("source"));
     }
+
+    #[test]
+    fn test_symbol_spans_from_definition() {
+        let index: ScipIndex = serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "tool_info": {"name": "scip-typescript", "version": "1.0.0"},
+                "project_root": "file:///repo",
+                "text_document_encoding": null
+            },
+            "documents": [{
+                "relative_path": "main.ts",
+                "symbols": [{
+                    "symbol": "scip-typescript npm . . `main.ts`/greet().",
+                    "documentation": null,
+                    "relationships": null
+                }],
+                "occurrences": [{
+                    "range": [2, 9, 14],
+                    "symbol": "scip-typescript npm . . `main.ts`/greet().",
+                    "symbol_roles": 1,
+                    "enclosing_range": [2, 0, 4, 1]
+                }]
+            }]
+        }))
+        .unwrap();
+
+        let mapper = ScipMapper::new("scip-typescript", "1.0.0");
+        let (symbols, _, _) = mapper.map_scip_to_ir(&index, "abc").unwrap();
+        assert_eq!(symbols[0].name_span, Span { start_line: 2, start_col: 9, end_line: 2, end_col: 14 });
+        assert_eq!(symbols[0].full_span, Span { start_line: 2, start_col: 0, end_line: 4, end_col: 1 });
+    }
 }
\ No newline at end of file
@@ -0,0 +1,97 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use protocol::{test_util, EdgeIR, EdgeType, Resolution, SymbolIR};
+use store::GraphStore;
+use tempfile::TempDir;
+
+const FILES: usize = 50;
+const SYMBOLS_PER_FILE: usize = 40;
+
+fn file_path(file: usize) -> String {
+    format!("src/module_{}.ts", file)
+}
+
+fn symbols() -> Vec<SymbolIR> {
+    (0..FILES)
+        .flat_map(|file| {
+            (0..SYMBOLS_PER_FILE).map(move |n| {
+                let path = file_path(file);
+                let fqn = format!("module_{}.fn_{}", file, n);
+                test_util::symbol(&format!("{}#{}", path, fqn), &fqn, &path)
+            })
+        })
+        .collect()
+}
+
+/// Each symbol calls the next, as a chain through every file
+fn calls(symbols: &[SymbolIR]) -> Vec<EdgeIR> {
+    symbols
+        .windows(2)
+        .map(|pair| EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some(pair[0].id.clone()),
+            dst: Some(pair[1].id.clone()),
+            file_src: Some(pair[0].file_path.clone()),
+            file_dst: Some(pair[1].file_path.clone()),
+            resolution: Resolution::Syntactic,
+            meta: Default::default(),
+            provenance: Default::default(),
+        })
+        .collect()
+}
+
+/// What a scan writes: one snapshot of files, symbols and edges
+fn write_snapshot(store: &GraphStore, symbols: &[SymbolIR], edges: &[EdgeIR]) {
+    let snapshot = store.begin_snapshot("c1").unwrap();
+    let commit_id = snapshot.commit_id();
+    for file in 0..FILES {
+        store.insert_file(commit_id, &file_path(file), "hash", 1024).unwrap();
+    }
+    for symbol in symbols {
+        store.insert_symbol(commit_id, symbol).unwrap();
+    }
+    for edge in edges {
+        store.insert_edge(commit_id, edge).unwrap();
+    }
+    snapshot.commit().unwrap();
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let symbols = symbols();
+    let edges = calls(&symbols);
+    c.bench_function("insert_snapshot", |b| {
+        b.iter_batched(
+            || {
+                let dir = TempDir::new().unwrap();
+                let store = GraphStore::new(dir.path()).unwrap();
+                (dir, store)
+            },
+            |(_dir, store)| write_snapshot(&store, &symbols, &edges),
+            BatchSize::PerIteration,
+        )
+    });
+}
+
+fn bench_lookups(c: &mut Criterion) {
+    let symbols = symbols();
+    let dir = TempDir::new().unwrap();
+    let store = GraphStore::new(dir.path()).unwrap().with_cache_capacity(0);
+    write_snapshot(&store, &symbols, &calls(&symbols));
+
+    c.bench_function("get_symbol", |b| {
+        b.iter(|| {
+            for symbol in symbols.iter().step_by(SYMBOLS_PER_FILE / 4) {
+                store.get_symbol(&symbol.id).unwrap();
+            }
+        })
+    });
+    c.bench_function("get_symbols_in_file", |b| {
+        b.iter(|| {
+            for file in 0..FILES {
+                store.get_symbols_in_file(&file_path(file)).unwrap();
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_insert, bench_lookups);
+criterion_main!(benches);
@@ -0,0 +1,369 @@
+use anyhow::{bail, Context, Result};
+use memmap2::Mmap;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use protocol::EdgeType;
+use std::collections::HashSet;
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::CodeGraph;
+
+const MAGIC: &[u8; 8] = b"CGCSR\0\0\x02";
+
+/// The graph as compressed sparse rows, kept on disk next to the database
+/// and mapped into memory so later runs traverse it in place instead of
+/// rebuilding a graph from SQL rows.
+///
+/// After a header (the key describing the rows it was built from, the
+/// counts, the edge types and the ids) come little-endian `u32` arrays:
+/// where each id ends, the nodes in id order for lookups, then node `i`'s
+/// outgoing edges as `targets[rows[i]..rows[i + 1]]` and its incoming ones
+/// as `sources[in_rows[i]..in_rows[i + 1]]`, each in the order petgraph
+/// lists them, and finally one byte per outgoing edge indexing its type.
+/// A file whose key doesn't match is stale and ignored.
+pub(crate) struct Adjacency {
+    map: Mmap,
+    node_count: usize,
+    types: Vec<EdgeType>,
+    ids: Range<usize>,
+    id_ends: Range<usize>,
+    by_id: Range<usize>,
+    rows: Range<usize>,
+    targets: Range<usize>,
+    in_rows: Range<usize>,
+    sources: Range<usize>,
+    edge_types: Range<usize>,
+}
+
+/// One of the file's `u32` arrays, read in place
+#[derive(Clone, Copy)]
+struct U32s<'a>(&'a [u8]);
+
+impl<'a> U32s<'a> {
+    fn len(self) -> usize {
+        self.0.len() / 4
+    }
+
+    fn get(self, index: usize) -> usize {
+        let start = index * 4;
+        u32::from_le_bytes(self.0[start..start + 4].try_into().unwrap()) as usize
+    }
+
+    fn iter(self) -> impl Iterator<Item = usize> + 'a {
+        (0..self.len()).map(move |index| self.get(index))
+    }
+
+    /// `self[index]..self[index + 1]`, a node's span of a row array
+    fn span(self, index: usize) -> Range<usize> {
+        self.get(index)..self.get(index + 1)
+    }
+
+    /// Offsets that never go backwards, from 0 up to `last`
+    fn is_monotonic(self, last: usize) -> bool {
+        self.get(0) == 0 && self.get(self.len() - 1) == last && (1..self.len()).all(|i| self.get(i - 1) <= self.get(i))
+    }
+}
+
+impl Adjacency {
+    /// Write `graph` to `path` under `key`, replacing any earlier file in
+    /// one step
+    pub fn write(graph: &CodeGraph, path: &Path, key: &str) -> Result<()> {
+        let graph = graph.inner();
+        let mut types: Vec<EdgeType> = Vec::new();
+        let (mut rows, mut targets, mut edge_types) = (vec![0], Vec::with_capacity(graph.edge_count()), Vec::new());
+        let (mut in_rows, mut sources) = (vec![0], Vec::with_capacity(graph.edge_count()));
+        for node in graph.node_indices() {
+            for edge in graph.edges(node) {
+                let type_index = match types.iter().position(|t| t == edge.weight()) {
+                    Some(i) => i,
+                    None => {
+                        types.push(edge.weight().clone());
+                        types.len() - 1
+                    }
+                };
+                targets.push(edge.target().index() as u32);
+                edge_types.push(u8::try_from(type_index).context("Too many edge types for an adjacency file")?);
+            }
+            rows.push(targets.len() as u32);
+            sources.extend(graph.neighbors_directed(node, Direction::Incoming).map(|n| n.index() as u32));
+            in_rows.push(sources.len() as u32);
+        }
+        let ids: Vec<&str> = graph.node_weights().map(String::as_str).collect();
+        let mut by_id: Vec<u32> = (0..ids.len() as u32).collect();
+        by_id.sort_by_key(|&node| ids[node as usize]);
+        let id_ends = ids.iter().scan(0, |end, id| {
+            *end += id.len() as u32;
+            Some(*end)
+        });
+
+        let mut bytes = MAGIC.to_vec();
+        put_bytes(&mut bytes, key.as_bytes());
+        put_u32(&mut bytes, ids.len() as u32);
+        put_u32(&mut bytes, targets.len() as u32);
+        put_bytes(&mut bytes, &serde_json::to_vec(&types)?);
+        put_bytes(&mut bytes, ids.concat().as_bytes());
+        for value in id_ends.chain(by_id).chain(rows).chain(targets).chain(in_rows).chain(sources) {
+            put_u32(&mut bytes, value);
+        }
+        bytes.extend_from_slice(&edge_types);
+
+        let partial = path.with_extension("csr.tmp");
+        std::fs::write(&partial, bytes)?;
+        std::fs::rename(&partial, path)?;
+        Ok(())
+    }
+
+    /// The graph in `path`, or `None` when there is no file or it was
+    /// written under a different key
+    pub fn read(path: &Path, key: &str) -> Result<Option<Self>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        // SAFETY: files are only ever replaced by renaming a new one over
+        // them, which leaves this mapping on the old contents
+        let map = unsafe { Mmap::map(&file)? };
+        let mut reader = Reader { bytes: &map, position: 0 };
+        if reader.take(MAGIC.len())? != MAGIC || reader.take_bytes()? != key.as_bytes() {
+            return Ok(None);
+        }
+        let node_count = reader.u32()? as usize;
+        let edge_count = reader.u32()? as usize;
+        let types: Vec<EdgeType> = serde_json::from_slice(reader.take_bytes()?)?;
+        let ids_length = reader.u32()? as usize;
+        let ids = reader.section(ids_length)?;
+        let id_ends = reader.section(node_count * 4)?;
+        let by_id = reader.section(node_count * 4)?;
+        let rows = reader.section((node_count + 1) * 4)?;
+        let targets = reader.section(edge_count * 4)?;
+        let in_rows = reader.section((node_count + 1) * 4)?;
+        let sources = reader.section(edge_count * 4)?;
+        let edge_types = reader.section(edge_count)?;
+        if reader.position != map.len() {
+            bail!("Corrupt adjacency file {}", path.display());
+        }
+
+        let adjacency = Adjacency { map, node_count, types, ids, id_ends, by_id, rows, targets, in_rows, sources, edge_types };
+        if !adjacency.is_valid() {
+            bail!("Corrupt adjacency file {}", path.display());
+        }
+        Ok(Some(adjacency))
+    }
+
+    /// Whether every offset and index stays in bounds, so lookups can't
+    /// panic on a damaged file
+    fn is_valid(&self) -> bool {
+        let id_ends = self.u32s(&self.id_ends);
+        let ids_valid = (0..self.node_count).all(|node| {
+            let start = if node == 0 { 0 } else { id_ends.get(node - 1) };
+            let end = id_ends.get(node);
+            start <= end && end <= self.ids.len() && std::str::from_utf8(&self.map[self.ids.start + start..self.ids.start + end]).is_ok()
+        });
+        let by_id = self.u32s(&self.by_id);
+        ids_valid
+            && by_id.iter().all(|node| node < self.node_count)
+            && (1..self.node_count).all(|i| self.id(by_id.get(i - 1)) <= self.id(by_id.get(i)))
+            && self.u32s(&self.rows).is_monotonic(self.targets.len() / 4)
+            && self.u32s(&self.in_rows).is_monotonic(self.sources.len() / 4)
+            && self.u32s(&self.targets).iter().all(|node| node < self.node_count)
+            && self.u32s(&self.sources).iter().all(|node| node < self.node_count)
+            && self.map[self.edge_types.clone()].iter().all(|&t| (t as usize) < self.types.len())
+    }
+
+    fn u32s(&self, section: &Range<usize>) -> U32s<'_> {
+        U32s(&self.map[section.clone()])
+    }
+
+    fn id(&self, node: usize) -> &str {
+        let id_ends = self.u32s(&self.id_ends);
+        let start = if node == 0 { 0 } else { id_ends.get(node - 1) };
+        let bytes = &self.map[self.ids.start + start..self.ids.start + id_ends.get(node)];
+        // Checked when the file was read
+        std::str::from_utf8(bytes).unwrap_or_default()
+    }
+
+    fn node(&self, symbol_id: &str) -> Option<usize> {
+        let by_id = self.u32s(&self.by_id);
+        let (mut low, mut high) = (0, by_id.len());
+        while low < high {
+            let middle = (low + high) / 2;
+            match self.id(by_id.get(middle)).cmp(symbol_id) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Some(by_id.get(middle)),
+            }
+        }
+        None
+    }
+
+    /// Symbols with an edge into `symbol_id`, up to `max_depth` hops away,
+    /// as [`CodeGraph::find_callers`] finds them
+    pub fn find_callers(&self, symbol_id: &str, max_depth: usize) -> Vec<String> {
+        self.reachable(symbol_id, max_depth, &self.in_rows, &self.sources)
+    }
+
+    /// Symbols `symbol_id` has an edge to, up to `max_depth` hops away, as
+    /// [`CodeGraph::find_callees`] finds them
+    pub fn find_callees(&self, symbol_id: &str, max_depth: usize) -> Vec<String> {
+        self.reachable(symbol_id, max_depth, &self.rows, &self.targets)
+    }
+
+    fn reachable(&self, symbol_id: &str, max_depth: usize, rows: &Range<usize>, neighbors: &Range<usize>) -> Vec<String> {
+        let Some(start) = self.node(symbol_id) else {
+            return Vec::new();
+        };
+        let (rows, neighbors) = (self.u32s(rows), self.u32s(neighbors));
+        let mut results = Vec::new();
+        let mut visited = HashSet::from([start]);
+        let mut stack = vec![(start, 0)];
+        while let Some((current, depth)) = stack.pop() {
+            if depth > 0 {
+                results.push(self.id(current).to_string());
+            }
+            if depth < max_depth {
+                for edge in rows.span(current) {
+                    let neighbor = neighbors.get(edge);
+                    if visited.insert(neighbor) {
+                        stack.push((neighbor, depth + 1));
+                    }
+                }
+            }
+        }
+        results
+    }
+
+    pub fn to_graph(&self) -> CodeGraph {
+        let (rows, targets) = (self.u32s(&self.rows), self.u32s(&self.targets));
+        let mut graph = DiGraph::with_capacity(self.node_count, targets.len());
+        for node in 0..self.node_count {
+            graph.add_node(self.id(node).to_string());
+        }
+        for node in 0..self.node_count {
+            // petgraph lists a node's edges newest first
+            for edge in rows.span(node).rev() {
+                let edge_type = self.types[self.map[self.edge_types.start + edge] as usize].clone();
+                graph.add_edge(NodeIndex::new(node), NodeIndex::new(targets.get(edge)), edge_type);
+            }
+        }
+        CodeGraph::from_inner(graph)
+    }
+}
+
+/// The graph store queries run against
+pub(crate) enum LoadedGraph {
+    /// The adjacency file, mapped
+    Mapped(Adjacency),
+    /// Built from the rows, when there is no adjacency file to map
+    Built(CodeGraph),
+}
+
+impl LoadedGraph {
+    pub fn find_callers(&self, symbol_id: &str, max_depth: usize) -> Vec<String> {
+        match self {
+            LoadedGraph::Mapped(adjacency) => adjacency.find_callers(symbol_id, max_depth),
+            LoadedGraph::Built(graph) => graph.find_callers(symbol_id, max_depth),
+        }
+    }
+
+    pub fn find_callees(&self, symbol_id: &str, max_depth: usize) -> Vec<String> {
+        match self {
+            LoadedGraph::Mapped(adjacency) => adjacency.find_callees(symbol_id, max_depth),
+            LoadedGraph::Built(graph) => graph.find_callees(symbol_id, max_depth),
+        }
+    }
+
+    pub fn to_graph(&self) -> CodeGraph {
+        match self {
+            LoadedGraph::Mapped(adjacency) => adjacency.to_graph(),
+            LoadedGraph::Built(graph) => graph.clone(),
+        }
+    }
+}
+
+fn put_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Length-prefixed
+fn put_bytes(bytes: &mut Vec<u8>, value: &[u8]) {
+    put_u32(bytes, value.len() as u32);
+    bytes.extend_from_slice(value);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        let section = self.section(length)?;
+        Ok(&self.bytes[section])
+    }
+
+    /// Where the next `length` bytes are, to be read in place later
+    fn section(&mut self, length: usize) -> Result<Range<usize>> {
+        let end = self.position.checked_add(length).filter(|&end| end <= self.bytes.len()).context("Truncated adjacency file")?;
+        let section = self.position..end;
+        self.position = end;
+        Ok(section)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn take_bytes(&mut self) -> Result<&'a [u8]> {
+        let length = self.u32()? as usize;
+        self.take(length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjacency_roundtrip() -> Result<()> {
+        let mut graph = CodeGraph::new();
+        graph.add_edge("a", "b", EdgeType::Calls);
+        graph.add_edge("a", "c", EdgeType::Calls);
+        graph.add_edge("c", "a", EdgeType::Imports);
+        graph.add_edge("b", "d", EdgeType::Calls);
+        graph.add_symbol("lonely");
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("graph.csr");
+        Adjacency::write(&graph, &path, "commit-1")?;
+        assert!(Adjacency::read(&path, "commit-2")?.is_none());
+        assert!(Adjacency::read(&dir.path().join("missing.csr"), "commit-1")?.is_none());
+
+        // Traversals run on the mapped file and agree with the graph
+        let adjacency = Adjacency::read(&path, "commit-1")?.unwrap();
+        for (id, depth) in [("a", 1), ("a", 2), ("b", 3), ("d", 3), ("lonely", 1), ("missing", 1)] {
+            assert_eq!(adjacency.find_callees(id, depth), graph.find_callees(id, depth), "callees of {}", id);
+            assert_eq!(adjacency.find_callers(id, depth), graph.find_callers(id, depth), "callers of {}", id);
+        }
+        assert_eq!(adjacency.find_callers("a", 1), ["c"]);
+
+        let restored = adjacency.to_graph();
+        assert_eq!(restored.stats().node_count, 5);
+        assert_eq!(restored.find_callees("a", 1), graph.find_callees("a", 1));
+        assert_eq!(restored.find_path("c", "b"), Some(vec!["c".to_string(), "a".to_string(), "b".to_string()]));
+
+        // Damaged files are errors, not empty graphs
+        let bytes = std::fs::read(&path)?;
+        std::fs::write(&path, &bytes[..bytes.len() - 3])?;
+        assert!(Adjacency::read(&path, "commit-1").is_err());
+        let mut out_of_bounds = bytes.clone();
+        let edge_type = out_of_bounds.len() - 1;
+        out_of_bounds[edge_type] = 9;
+        std::fs::write(&path, &out_of_bounds)?;
+        assert!(Adjacency::read(&path, "commit-1").is_err());
+        Ok(())
+    }
+}
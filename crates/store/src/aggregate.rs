@@ -0,0 +1,228 @@
+use protocol::{EdgeIR, EdgeType, SymbolIR, SymbolKind};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// What symbols are grouped by when rolling the graph up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    File,
+    /// Directory of the file, cut to its first `depth` components when set
+    Directory { depth: Option<usize> },
+    /// Innermost package, namespace or module whose FQN prefixes the
+    /// symbol's, falling back to the directory for symbols outside one
+    Package,
+}
+
+/// A group of symbols
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AggregateNode {
+    pub id: String,
+    pub symbols: usize,
+    /// References between symbols of this group
+    pub internal_references: usize,
+}
+
+/// References from symbols of one group to symbols of another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AggregateEdge {
+    pub from: String,
+    pub to: String,
+    /// Number of underlying references
+    pub weight: usize,
+    /// The weight split by edge type, e.g. `Calls: 12, Imports: 1`
+    pub by_type: BTreeMap<String, usize>,
+}
+
+/// Symbol-level graph rolled up to files, directories or packages
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AggregateGraph {
+    /// Sorted by id
+    pub nodes: Vec<AggregateNode>,
+    /// Heaviest first
+    pub edges: Vec<AggregateEdge>,
+}
+
+impl AggregateGraph {
+    /// Roll up `edges` between `symbols`. Containment is left out as it is
+    /// structure rather than a reference; references to names that aren't
+    /// symbols count towards the file they point at, if known.
+    pub fn build(symbols: &[SymbolIR], edges: &[EdgeIR], granularity: Granularity) -> Self {
+        let is_package = |s: &SymbolIR| matches!(s.kind, SymbolKind::Package | SymbolKind::Namespace | SymbolKind::Module);
+        let packages: HashSet<&str> = symbols.iter().filter(|s| is_package(s)).map(|s| s.fqn.as_str()).collect();
+        let depth = match granularity {
+            Granularity::Directory { depth } => depth,
+            _ => None,
+        };
+        let group_of_file = |file: &str| match granularity {
+            Granularity::File => file.to_string(),
+            _ => directory(file, depth),
+        };
+        let group_of_symbol = |symbol: &SymbolIR| match granularity {
+            Granularity::Package if is_package(symbol) => symbol.fqn.clone(),
+            Granularity::Package => {
+                package_of(&symbol.fqn, &packages).unwrap_or_else(|| directory(&symbol.file_path, None))
+            }
+            _ => group_of_file(&symbol.file_path),
+        };
+
+        let mut nodes: BTreeMap<String, AggregateNode> = BTreeMap::new();
+        let mut group_by_id: HashMap<&str, String> = HashMap::new();
+        for symbol in symbols {
+            let group = group_of_symbol(symbol);
+            nodes
+                .entry(group.clone())
+                .or_insert_with(|| AggregateNode { id: group.clone(), symbols: 0, internal_references: 0 })
+                .symbols += 1;
+            group_by_id.insert(symbol.id.as_str(), group);
+        }
+
+        let mut weights: HashMap<(String, String), AggregateEdge> = HashMap::new();
+        for edge in edges.iter().filter(|e| e.edge_type != EdgeType::Contains) {
+            let from = match (edge.src.as_deref().and_then(|id| group_by_id.get(id)), &edge.file_src) {
+                (Some(group), _) => group.clone(),
+                (None, Some(file)) => group_of_file(file),
+                (None, None) => continue,
+            };
+            let to = match (edge.dst.as_deref().and_then(|id| group_by_id.get(id)), &edge.file_dst) {
+                (Some(group), _) => group.clone(),
+                (None, Some(file)) => group_of_file(file),
+                (None, None) => continue,
+            };
+            if from == to {
+                if let Some(node) = nodes.get_mut(&from) {
+                    node.internal_references += 1;
+                }
+                continue;
+            }
+            for group in [&from, &to] {
+                nodes
+                    .entry(group.clone())
+                    .or_insert_with(|| AggregateNode { id: group.clone(), symbols: 0, internal_references: 0 });
+            }
+            let aggregate = weights.entry((from.clone(), to.clone())).or_insert_with(|| AggregateEdge {
+                from,
+                to,
+                weight: 0,
+                by_type: BTreeMap::new(),
+            });
+            aggregate.weight += 1;
+            *aggregate.by_type.entry(format!("{:?}", edge.edge_type)).or_default() += 1;
+        }
+
+        let mut edges: Vec<AggregateEdge> = weights.into_values().collect();
+        edges.sort_by(|a, b| b.weight.cmp(&a.weight).then_with(|| (&a.from, &a.to).cmp(&(&b.from, &b.to))));
+        Self { nodes: nodes.into_values().collect(), edges }
+    }
+
+    /// Drop edges lighter than `min_weight`, and nodes left without edges
+    pub fn prune(mut self, min_weight: usize) -> Self {
+        self.edges.retain(|edge| edge.weight >= min_weight);
+        let connected: HashSet<&str> =
+            self.edges.iter().flat_map(|edge| [edge.from.as_str(), edge.to.as_str()]).collect();
+        let nodes = self.nodes.iter().filter(|node| connected.contains(node.id.as_str())).cloned().collect();
+        self.nodes = nodes;
+        self
+    }
+}
+
+/// Directory of a repo-relative file, `.` for the root, cut to `depth` components
+pub(crate) fn directory(file: &str, depth: Option<usize>) -> String {
+    let components: Vec<&str> = file.split('/').collect();
+    let dirs = &components[..components.len().saturating_sub(1)];
+    let dirs = match depth {
+        Some(depth) => &dirs[..dirs.len().min(depth)],
+        None => dirs,
+    };
+    if dirs.is_empty() {
+        ".".to_string()
+    } else {
+        dirs.join("/")
+    }
+}
+
+/// Longest proper prefix of `fqn`, cut at a scope separator, that names a package
+fn package_of(fqn: &str, packages: &HashSet<&str>) -> Option<String> {
+    let mut end = fqn.len();
+    while let Some(cut) = fqn[..end].rfind(['.', ':', '/', '#']) {
+        let prefix = fqn[..cut].trim_end_matches(':');
+        if packages.contains(prefix) {
+            return Some(prefix.to_string());
+        }
+        end = prefix.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Language, Resolution, test_util};
+
+    fn symbol(id: &str, fqn: &str, kind: SymbolKind, file: &str) -> SymbolIR {
+        SymbolIR { lang: Language::Java, kind, name: id.to_string(), ..test_util::symbol(id, fqn, file) }
+    }
+
+    fn edge(edge_type: EdgeType, src: &str, dst: &str, file_dst: Option<&str>) -> EdgeIR {
+        EdgeIR {
+            edge_type,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: None,
+            file_dst: file_dst.map(str::to_string),
+            resolution: Resolution::Syntactic,
+            meta: Default::default(),
+            provenance: Default::default(),
+        }
+    }
+
+    fn fixture() -> (Vec<SymbolIR>, Vec<EdgeIR>) {
+        let symbols = vec![
+            symbol("pkg_web", "com.shop.web", SymbolKind::Package, "src/com/shop/web/Api.java"),
+            symbol("pkg_core", "com.shop.core", SymbolKind::Package, "src/com/shop/core/Cart.java"),
+            symbol("api", "com.shop.web.Api.list", SymbolKind::Method, "src/com/shop/web/Api.java"),
+            symbol("cart", "com.shop.core.Cart.total", SymbolKind::Method, "src/com/shop/core/Cart.java"),
+            symbol("price", "com.shop.core.Price.of", SymbolKind::Method, "src/com/shop/core/Price.java"),
+        ];
+        let edges = vec![
+            edge(EdgeType::Calls, "api", "cart", None),
+            edge(EdgeType::Calls, "api", "price", None),
+            edge(EdgeType::Imports, "api", "cart", None),
+            edge(EdgeType::Calls, "cart", "price", None),
+            edge(EdgeType::Contains, "pkg_core", "cart", None),
+            edge(EdgeType::Calls, "api", "java.util.List.of", Some("lib/java/util/List.java")),
+        ];
+        (symbols, edges)
+    }
+
+    #[test]
+    fn test_rolls_up_to_packages() {
+        let (symbols, edges) = fixture();
+        let graph = AggregateGraph::build(&symbols, &edges, Granularity::Package);
+
+        let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["com.shop.core", "com.shop.web", "lib/java/util"]);
+        let core = &graph.nodes[0];
+        assert_eq!((core.symbols, core.internal_references), (3, 1));
+
+        let top = &graph.edges[0];
+        assert_eq!((top.from.as_str(), top.to.as_str(), top.weight), ("com.shop.web", "com.shop.core", 3));
+        assert_eq!(top.by_type, BTreeMap::from([("Calls".to_string(), 2), ("Imports".to_string(), 1)]));
+        assert_eq!(graph.edges[1].to, "lib/java/util");
+    }
+
+    #[test]
+    fn test_rolls_up_to_directories() {
+        let (symbols, edges) = fixture();
+        let graph = AggregateGraph::build(&symbols, &edges, Granularity::Directory { depth: Some(2) });
+        let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["lib/java", "src/com"]);
+        assert_eq!(graph.nodes[1].internal_references, 4);
+
+        let files = AggregateGraph::build(&symbols, &edges, Granularity::File);
+        assert_eq!(files.edges.len(), 4);
+        assert_eq!(files.prune(2).edges.len(), 1);
+
+        assert_eq!(directory("main.go", None), ".");
+        assert_eq!(directory("a/b/c/d.go", Some(1)), "a");
+    }
+}
@@ -0,0 +1,596 @@
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+use crate::{GraphStore, EXPORT_TABLES};
+
+/// Bumped whenever the bundle layout changes incompatibly
+const BUNDLE_FORMAT: i64 = 2;
+
+/// zstd level bundles are compressed at: the default, which already
+/// shrinks the vacuumed database several times over
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// First bytes of a zstd frame, telling a compressed bundle from a plain
+/// database written before bundles were compressed
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// Full-text indexes a bundle may leave out. Importing re-indexes the rows
+/// through the target database's own triggers either way.
+const FTS_TABLES: &[&str] = &["symbol_fts", "occurrence_fts"];
+
+/// The column tying each exported table's rows to a source file. A delta
+/// bundle ships a file's rows only when any of them changed.
+const FILE_KEYS: &[(&str, &str)] = &[
+    ("file", "path"),
+    ("symbol", "file_path"),
+    ("edge", "file_src"),
+    ("occurrence", "file_path"),
+    ("symbol_alias", "file_path"),
+    ("file_summary", "path"),
+];
+
+/// Result of [`GraphStore::merge_bundles`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeSummary {
+    pub commit: String,
+    pub shards: usize,
+    /// Rows dropped because another shard had already written them
+    pub duplicates: usize,
+}
+
+impl GraphStore {
+    /// Pack one snapshot into a single zstd-compressed file.
+    ///
+    /// The bundle is a vacuumed database holding that commit's rows only,
+    /// plus a `bundle_info` record, so it can be shipped to CI or another
+    /// machine and loaded there with [`import_bundle`](Self::import_bundle).
+    /// Full-text indexes are dropped unless `with_fts` is set, which is only
+    /// worth it when the bundle will be queried on its own after
+    /// [`unpack_bundle`].
+    #[tracing::instrument(skip(self))]
+    pub fn export_bundle(&self, commit_sha: &str, path: &Path, with_fts: bool) -> Result<()> {
+        let commit_id = self.snapshot_id(commit_sha)?;
+        self.write_bundle(path, with_fts, |tx| {
+            tx.execute(
+                "INSERT INTO bundle.bundle_info (format, commit_sha, exported_at) VALUES (?1, ?2, ?3)",
+                params![BUNDLE_FORMAT, commit_sha, unix_now()?],
+            )?;
+            copy_commit_row(tx, commit_id)?;
+            for (table, columns, _) in EXPORT_TABLES {
+                tx.execute(
+                    &format!(
+                        "INSERT INTO bundle.{table} (commit_id, {columns})
+                         SELECT 1, {columns} FROM main.{table} WHERE commit_id = ?1 ORDER BY id"
+                    ),
+                    params![commit_id],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Like [`export_bundle`](Self::export_bundle), but only ships the rows
+    /// of files that differ from the `base_sha` snapshot. The rows of every
+    /// other file are listed by path and copied from the base on import, so
+    /// the importing store must already have `base_sha`.
+    #[tracing::instrument(skip(self))]
+    pub fn export_delta_bundle(&self, commit_sha: &str, base_sha: &str, path: &Path) -> Result<()> {
+        let commit_id = self.snapshot_id(commit_sha)?;
+        let base_id = self.snapshot_id(base_sha)?;
+        let current = self.file_fingerprints(commit_id)?;
+        let base = self.file_fingerprints(base_id)?;
+        let unchanged: HashSet<&String> = current
+            .iter()
+            .filter(|(path, fingerprint)| base.get(*path) == Some(fingerprint))
+            .map(|(path, _)| path)
+            .collect();
+
+        self.write_bundle(path, false, |tx| {
+            tx.execute(
+                "INSERT INTO bundle.bundle_info (format, commit_sha, base_commit, exported_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![BUNDLE_FORMAT, commit_sha, base_sha, unix_now()?],
+            )?;
+            copy_commit_row(tx, commit_id)?;
+            for path in &unchanged {
+                tx.execute("INSERT INTO bundle.bundle_unchanged (path) VALUES (?1)", params![path])?;
+            }
+            for (table, columns, _) in EXPORT_TABLES {
+                let key = file_key(table);
+                tx.execute(
+                    &format!(
+                        "INSERT INTO bundle.{table} (commit_id, {columns})
+                         SELECT 1, {columns} FROM main.{table}
+                         WHERE commit_id = ?1
+                           AND ({key} IS NULL OR {key} NOT IN (SELECT path FROM bundle.bundle_unchanged))
+                         ORDER BY id"
+                    ),
+                    params![commit_id],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Hash of every row belonging to each file of a snapshot
+    fn file_fingerprints(&self, commit_id: i64) -> Result<HashMap<String, u64>> {
+        let mut hashers: HashMap<String, DefaultHasher> = HashMap::new();
+        for (table, columns, _) in EXPORT_TABLES {
+            let key = file_key(table);
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {key}, json_array({columns}) FROM {table}
+                 WHERE commit_id = ?1 AND {key} IS NOT NULL
+                 ORDER BY {columns}"
+            ))?;
+            let mut rows = stmt.query(params![commit_id])?;
+            while let Some(row) = rows.next()? {
+                let hasher = hashers.entry(row.get(0)?).or_default();
+                table.hash(hasher);
+                row.get::<_, String>(1)?.hash(hasher);
+            }
+        }
+        Ok(hashers.into_iter().map(|(path, hasher)| (path, hasher.finish())).collect())
+    }
+
+    /// Create a bundle database, fill it through `fill` inside one
+    /// transaction with it attached as `bundle`, compact it and write it
+    /// compressed to `path`
+    fn write_bundle(
+        &self,
+        path: &Path,
+        with_fts: bool,
+        fill: impl FnOnce(&rusqlite::Transaction) -> Result<()>,
+    ) -> Result<()> {
+        let scratch = tempfile::Builder::new().prefix("bundle").suffix(".db").tempfile()?;
+        let database = scratch.path();
+        {
+            let bundle = GraphStore::open(database)?;
+            // A single self-contained file, no WAL alongside it
+            bundle.conn.pragma_update(None, "journal_mode", "DELETE")?;
+            bundle.conn.execute_batch(
+                "CREATE TABLE bundle_info (
+                    format INTEGER NOT NULL,
+                    commit_sha TEXT NOT NULL,
+                    base_commit TEXT,
+                    exported_at INTEGER NOT NULL
+                );
+                CREATE TABLE bundle_unchanged (path TEXT PRIMARY KEY);",
+            )?;
+        }
+
+        self.conn.execute("ATTACH DATABASE ?1 AS bundle", params![database.to_string_lossy()])?;
+        let filled = self.conn.unchecked_transaction().map_err(anyhow::Error::from).and_then(|tx| {
+            fill(&tx)?;
+            tx.commit()?;
+            Ok(())
+        });
+        self.conn.execute_batch("DETACH DATABASE bundle")?;
+        filled?;
+
+        let bundle = Connection::open(database)?;
+        if !with_fts {
+            for table in FTS_TABLES {
+                bundle.execute_batch(&format!(
+                    "DROP TRIGGER {table}_insert;
+                     DROP TRIGGER {table}_delete;
+                     DROP TRIGGER {table}_update;
+                     DROP TABLE {table};"
+                ))?;
+            }
+        }
+        bundle.execute_batch("VACUUM")?;
+        bundle.close().map_err(|(_, e)| e)?;
+
+        let mut output = BufWriter::new(File::create(path)?);
+        zstd::stream::copy_encode(BufReader::new(File::open(database)?), &mut output, COMPRESSION_LEVEL)?;
+        output.flush()?;
+        Ok(())
+    }
+
+    /// Run `f` with the bundle at `path` attached as `bundle`, unpacked
+    /// into a scratch database first when compressed
+    fn with_bundle<T>(&self, path: &Path, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        // ATTACH would silently create a missing file
+        if !path.is_file() {
+            bail!("Bundle {} not found", path.display());
+        }
+        let scratch = if is_compressed(path)? {
+            let scratch = tempfile::Builder::new().prefix("bundle").suffix(".db").tempfile()?;
+            unpack_bundle(path, scratch.path())?;
+            Some(scratch)
+        } else {
+            None
+        };
+        let database = scratch.as_ref().map_or(path, NamedTempFile::path);
+        self.conn.execute("ATTACH DATABASE ?1 AS bundle", params![database.to_string_lossy()])?;
+        let result = f();
+        self.conn.execute_batch("DETACH DATABASE bundle")?;
+        result
+    }
+
+    /// Load a bundle written by [`export_bundle`](Self::export_bundle),
+    /// replacing any snapshot of the same commit. Returns the commit SHA.
+    #[tracing::instrument(skip(self))]
+    pub fn import_bundle(&self, path: &Path) -> Result<String> {
+        self.with_bundle(path, || self.copy_from_bundle())
+    }
+
+    /// Union several full bundles of the same commit, e.g. the shards of a
+    /// scan split across machines, into one snapshot (replacing any existing
+    /// one). Rows every shard emitted alike, such as build-module symbols and
+    /// the edges between them, are kept once, and re-export attribution is
+    /// rerun so references resolve across shard boundaries.
+    #[tracing::instrument(skip(self))]
+    pub fn merge_bundles(&self, paths: &[PathBuf]) -> Result<MergeSummary> {
+        let mut commit: Option<(String, i64)> = None;
+        for path in paths {
+            let merged = self.with_bundle(path, || {
+                let (commit_sha, base) = self.bundle_header()?;
+                if base.is_some() {
+                    bail!("{} is a delta bundle; shards must be exported in full", path.display());
+                }
+                match &commit {
+                    Some((expected, _)) if *expected != commit_sha => {
+                        bail!("{} holds commit {}, not {}", path.display(), commit_sha, expected)
+                    }
+                    Some((_, commit_id)) => self.append_bundle_rows(*commit_id),
+                    None => {
+                        self.copy_from_bundle()?;
+                        commit = Some((commit_sha.clone(), self.snapshot_id(&commit_sha)?));
+                        Ok(())
+                    }
+                }
+            });
+            merged.with_context(|| format!("Failed to merge {}", path.display()))?;
+        }
+        let Some((commit, commit_id)) = commit else {
+            bail!("No shard bundles to merge");
+        };
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut duplicates = 0;
+        for (table, columns, _) in EXPORT_TABLES {
+            duplicates += tx.execute(
+                &format!(
+                    "DELETE FROM {table} WHERE commit_id = ?1 AND id NOT IN (
+                         SELECT MIN(id) FROM {table} WHERE commit_id = ?1 GROUP BY {columns}
+                     )"
+                ),
+                params![commit_id],
+            )?;
+        }
+        self.bump_graph_version()?;
+        tx.commit()?;
+        self.attribute_reexports(commit_id)?;
+
+        Ok(MergeSummary { commit, shards: paths.len(), duplicates })
+    }
+
+    /// Add the attached bundle's rows to an existing snapshot. Rows clashing
+    /// with a unique key (a file or symbol another shard already wrote) are
+    /// skipped.
+    fn append_bundle_rows(&self, commit_id: i64) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (table, columns, _) in EXPORT_TABLES {
+            tx.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO main.{table} (commit_id, {columns})
+                     SELECT ?1, {columns} FROM bundle.{table} ORDER BY id"
+                ),
+                params![commit_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Commit and delta base of the attached bundle
+    fn bundle_header(&self) -> Result<(String, Option<String>)> {
+        let (format, commit_sha, base_commit): (i64, String, Option<String>) = self.conn
+            .query_row("SELECT format, commit_sha, base_commit FROM bundle.bundle_info", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .context("Not a snapshot bundle")?;
+        if format != BUNDLE_FORMAT {
+            bail!("Unsupported bundle format {} (expected {})", format, BUNDLE_FORMAT);
+        }
+        Ok((commit_sha, base_commit))
+    }
+
+    fn copy_from_bundle(&self) -> Result<String> {
+        let (commit_sha, base_commit) = self.bundle_header()?;
+        let base_id = match &base_commit {
+            Some(base) => Some(
+                self.snapshot_id(base)
+                    .with_context(|| format!("Delta bundle for {} needs snapshot {} first", commit_sha, base))?,
+            ),
+            None => None,
+        };
+        let timestamp: i64 = self.conn.query_row(
+            "SELECT timestamp FROM bundle.commit_snapshot WHERE commit_sha = ?1",
+            params![commit_sha],
+            |row| row.get(0),
+        )?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        let existing = tx.query_row(
+            "SELECT id FROM main.commit_snapshot WHERE commit_sha = ?1",
+            params![commit_sha],
+            |row| row.get::<_, i64>(0),
+        ).optional()?;
+        let commit_id = match existing {
+            Some(id) => {
+                for (table, _, _) in EXPORT_TABLES {
+                    tx.execute(&format!("DELETE FROM main.{table} WHERE commit_id = ?1"), params![id])?;
+                }
+                tx.execute("DELETE FROM main.scan_checkpoint WHERE commit_id = ?1", params![id])?;
+                id
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO main.commit_snapshot (commit_sha, timestamp) VALUES (?1, ?2)",
+                    params![commit_sha, timestamp],
+                )?;
+                tx.last_insert_rowid()
+            }
+        };
+        for (table, columns, _) in EXPORT_TABLES {
+            let key = file_key(table);
+            if let Some(base_id) = base_id {
+                tx.execute(
+                    &format!(
+                        "INSERT INTO main.{table} (commit_id, {columns})
+                         SELECT ?1, {columns} FROM main.{table}
+                         WHERE commit_id = ?2 AND {key} IN (SELECT path FROM bundle.bundle_unchanged)
+                         ORDER BY id"
+                    ),
+                    params![commit_id, base_id],
+                )?;
+            }
+            tx.execute(
+                &format!(
+                    "INSERT INTO main.{table} (commit_id, {columns})
+                     SELECT ?1, {columns} FROM bundle.{table} ORDER BY id"
+                ),
+                params![commit_id],
+            )?;
+        }
+        self.bump_graph_version()?;
+        tx.commit()?;
+        Ok(commit_sha)
+    }
+}
+
+/// Decompress the bundle at `bundle` into the database file `database`,
+/// e.g. to query a bundle exported with its full-text indexes on its own
+pub fn unpack_bundle(bundle: &Path, database: &Path) -> Result<()> {
+    let mut output = BufWriter::new(File::create(database)?);
+    zstd::stream::copy_decode(BufReader::new(File::open(bundle)?), &mut output)
+        .with_context(|| format!("{} is not a compressed bundle", bundle.display()))?;
+    output.flush()?;
+    Ok(())
+}
+
+/// Whether the file at `path` starts with a zstd frame
+fn is_compressed(path: &Path) -> Result<bool> {
+    let mut magic = [0; 4];
+    let mut file = File::open(path)?;
+    Ok(file.read_exact(&mut magic).is_ok() && magic == ZSTD_MAGIC)
+}
+
+fn copy_commit_row(tx: &rusqlite::Transaction, commit_id: i64) -> Result<()> {
+    tx.execute(
+        "INSERT INTO bundle.commit_snapshot (id, commit_sha, timestamp)
+         SELECT 1, commit_sha, timestamp FROM main.commit_snapshot WHERE id = ?1",
+        params![commit_id],
+    )?;
+    Ok(())
+}
+
+fn file_key(table: &str) -> &'static str {
+    FILE_KEYS
+        .iter()
+        .find(|(t, _)| *t == table)
+        .map(|(_, key)| *key)
+        .expect("every exported table has a file key")
+}
+
+fn unix_now() -> Result<i64> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{EdgeIR, EdgeType, OccurrenceIR, OccurrenceRole, Resolution, Span, SymbolIR, test_util};
+    use tempfile::TempDir;
+
+    fn symbol(id: &str, name: &str) -> SymbolIR {
+        SymbolIR {
+            name_span: Span { start_line: 0, start_col: 9, end_line: 0, end_col: 9 + name.len() as u32 },
+            full_span: Span { start_line: 0, start_col: 0, end_line: 2, end_col: 1 },
+            sig_hash: "hash".to_string(),
+            ..test_util::symbol(id, &format!("src/app/{}", name), "src/app.ts")
+        }
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() -> Result<()> {
+        let source_dir = TempDir::new()?;
+        let source = GraphStore::new(source_dir.path())?;
+        let old = source.get_or_create_commit("old")?;
+        source.insert_symbol(old, &symbol("s_old", "stale"))?;
+        let commit_id = source.get_or_create_commit("abc123")?;
+        source.insert_file(commit_id, "src/app.ts", "hash", 42)?;
+        source.insert_symbol(commit_id, &symbol("s_load", "loadConfig"))?;
+        source.insert_occurrence(commit_id, &OccurrenceIR {
+            file_path: "src/app.ts".to_string(),
+            symbol_id: Some("s_load".to_string()),
+            role: OccurrenceRole::Definition,
+            span: Span { start_line: 0, start_col: 9, end_line: 0, end_col: 19 },
+            token: "loadConfig".to_string(),
+        })?;
+
+        let bundle_path = source_dir.path().join("abc123.bundle");
+        source.export_bundle("abc123", &bundle_path, false)?;
+        assert!(source.export_bundle("missing", &bundle_path, false).is_err());
+        assert!(is_compressed(&bundle_path)?);
+
+        let target_dir = TempDir::new()?;
+        let target = GraphStore::new(target_dir.path())?;
+        assert_eq!(target.import_bundle(&bundle_path)?, "abc123");
+        // Importing again replaces the snapshot instead of duplicating it
+        target.import_bundle(&bundle_path)?;
+
+        let mut expected = Vec::new();
+        source.export_jsonl("abc123", &mut expected)?;
+        let mut imported = Vec::new();
+        target.export_jsonl("abc123", &mut imported)?;
+        assert_eq!(String::from_utf8(imported)?, String::from_utf8(expected)?);
+        assert!(target.get_symbol("s_old")?.is_none());
+
+        // The target's own indexes cover the imported rows
+        assert_eq!(target.search_symbols_fts("loadCon", 10)?.len(), 1);
+        assert_eq!(target.search_occurrences("loadConfig", 10)?.len(), 1);
+
+        assert!(target.import_bundle(&target_dir.path().join("nope.bundle")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_delta_bundle_ships_changed_files_only() -> Result<()> {
+        let dir = TempDir::new()?;
+        let source = GraphStore::new(dir.path())?;
+        let file_symbol = |id: &str, name: &str, file: &str| SymbolIR { file_path: file.to_string(), ..symbol(id, name) };
+
+        let base = source.get_or_create_commit("base")?;
+        for (file, hash) in [("src/a.ts", "a1"), ("src/b.ts", "b1"), ("src/gone.ts", "g1")] {
+            source.insert_file(base, file, hash, 1)?;
+        }
+        source.insert_symbol(base, &file_symbol("s_a", "keep", "src/a.ts"))?;
+        source.insert_symbol(base, &file_symbol("s_b", "before", "src/b.ts"))?;
+        source.insert_symbol(base, &file_symbol("s_gone", "gone", "src/gone.ts"))?;
+
+        let head = source.get_or_create_commit("head")?;
+        for (file, hash) in [("src/a.ts", "a1"), ("src/b.ts", "b2"), ("src/new.ts", "n1")] {
+            source.insert_file(head, file, hash, 1)?;
+        }
+        source.insert_symbol(head, &file_symbol("s_a", "keep", "src/a.ts"))?;
+        source.insert_symbol(head, &file_symbol("s_b", "after", "src/b.ts"))?;
+        source.insert_symbol(head, &file_symbol("s_new", "fresh", "src/new.ts"))?;
+
+        let full_path = dir.path().join("base.bundle");
+        let delta_path = dir.path().join("head.delta");
+        source.export_bundle("base", &full_path, false)?;
+        source.export_delta_bundle("head", "base", &delta_path)?;
+        let unpacked = dir.path().join("head.db");
+        unpack_bundle(&delta_path, &unpacked)?;
+        let delta = Connection::open(&unpacked)?;
+        let shipped: i64 = delta.query_row("SELECT COUNT(*) FROM symbol", [], |row| row.get(0))?;
+        assert_eq!(shipped, 2, "src/a.ts is unchanged and stays out of the delta");
+
+        let target_dir = TempDir::new()?;
+        let target = GraphStore::new(target_dir.path())?;
+        assert!(target.import_bundle(&delta_path).is_err(), "the base has to be imported first");
+        target.import_bundle(&full_path)?;
+        target.import_bundle(&delta_path)?;
+
+        let mut expected = Vec::new();
+        source.export_jsonl("head", &mut expected)?;
+        let mut imported = Vec::new();
+        target.export_jsonl("head", &mut imported)?;
+        assert_eq!(String::from_utf8(imported)?, String::from_utf8(expected)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_shard_bundles() -> Result<()> {
+        let dir = TempDir::new()?;
+        let file_symbol = |id: &str, file: &str| SymbolIR { file_path: file.to_string(), ..symbol(id, id) };
+        let shared_edge = EdgeIR {
+            edge_type: EdgeType::DependsOn,
+            src: Some("app/build.gradle#:app".to_string()),
+            dst: Some("core/build.gradle#:core".to_string()),
+            file_src: Some("app/build.gradle".to_string()),
+            file_dst: Some("core/build.gradle".to_string()),
+            resolution: Resolution::Syntactic,
+            meta: Default::default(),
+            provenance: Default::default(),
+        };
+
+        let mut shard_paths = Vec::new();
+        let whole = GraphStore::open(&dir.path().join("whole.db"))?;
+        let whole_id = whole.get_or_create_commit("abc123")?;
+        whole.insert_symbol(whole_id, &file_symbol("module_app", "app/build.gradle"))?;
+        whole.insert_edge(whole_id, &shared_edge)?;
+        for (index, files) in [["src/a.ts", "src/b.ts"], ["lib/c.ts", "lib/d.ts"]].iter().enumerate() {
+            let shard = GraphStore::open(&dir.path().join(format!("shard{}.db", index)))?;
+            let commit_id = shard.get_or_create_commit("abc123")?;
+            // Every shard derives the build-module graph on its own
+            shard.insert_symbol(commit_id, &file_symbol("module_app", "app/build.gradle"))?;
+            shard.insert_edge(commit_id, &shared_edge)?;
+            for file in files {
+                for (store, id) in [(&shard, commit_id), (&whole, whole_id)] {
+                    store.insert_file(id, file, "hash", 1)?;
+                    store.insert_symbol(id, &file_symbol(&format!("sym_{}", file), file))?;
+                }
+            }
+            let path = dir.path().join(format!("shard{}.bundle", index));
+            shard.export_bundle("abc123", &path, false)?;
+            shard_paths.push(path);
+        }
+
+        let target_dir = TempDir::new()?;
+        let target = GraphStore::new(target_dir.path())?;
+        let summary = target.merge_bundles(&shard_paths)?;
+        assert_eq!(summary, MergeSummary { commit: "abc123".to_string(), shards: 2, duplicates: 1 });
+
+        // Identical to one scan of the whole tree, module rows included once
+        let mut expected = Vec::new();
+        whole.export_jsonl("abc123", &mut expected)?;
+        let mut merged = Vec::new();
+        target.export_jsonl("abc123", &mut merged)?;
+        assert_eq!(String::from_utf8(merged)?, String::from_utf8(expected)?);
+
+        let other = GraphStore::open(&dir.path().join("other.db"))?;
+        other.get_or_create_commit("def456")?;
+        let other_path = dir.path().join("other.bundle");
+        other.export_bundle("def456", &other_path, false)?;
+        assert!(target.merge_bundles(&[shard_paths[0].clone(), other_path]).is_err());
+        assert!(target.merge_bundles(&[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bundle_with_fts_is_queryable_unpacked() -> Result<()> {
+        let dir = TempDir::new()?;
+        let source = GraphStore::new(dir.path())?;
+        let commit_id = source.get_or_create_commit("abc123")?;
+        source.insert_symbol(commit_id, &symbol("s_load", "loadConfig"))?;
+
+        let bundle_path = dir.path().join("abc123.bundle");
+        source.export_bundle("abc123", &bundle_path, true)?;
+        let unpacked = dir.path().join("abc123.db");
+        unpack_bundle(&bundle_path, &unpacked)?;
+        let bundle = GraphStore::open(&unpacked)?;
+        assert_eq!(bundle.search_symbols_fts("loadCon", 10)?.len(), 1);
+
+        // Plain databases from before bundles were compressed still import
+        let target_dir = TempDir::new()?;
+        let target = GraphStore::new(target_dir.path())?;
+        drop(bundle);
+        assert_eq!(target.import_bundle(&unpacked)?, "abc123");
+        assert_eq!(target.search_symbols_fts("loadCon", 10)?.len(), 1);
+        assert!(unpack_bundle(&unpacked, &dir.path().join("again.db")).is_err());
+        Ok(())
+    }
+}
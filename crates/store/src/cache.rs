@@ -0,0 +1,85 @@
+use lru::LruCache;
+use protocol::SymbolIR;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use crate::adjacency::LoadedGraph;
+
+/// Entries kept by a store unless configured otherwise
+pub const DEFAULT_CACHE_CAPACITY: usize = 64;
+
+#[derive(Clone)]
+pub(crate) enum Cached {
+    Graph(Arc<LoadedGraph>),
+    Symbols(Arc<Vec<SymbolIR>>),
+}
+
+/// Hit/miss counters of a store's query cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// Results of expensive reads, keyed by the snapshot they ran against and
+/// the query. Everything is dropped as soon as the database changes, by this
+/// connection or another process, so entries never outlive their snapshot.
+pub(crate) struct QueryCache {
+    /// `None` when caching is disabled (capacity 0)
+    entries: Option<LruCache<(String, String), Cached>>,
+    /// Database state the entries were computed from
+    stamp: Option<(u64, i64)>,
+    stats: CacheStats,
+}
+
+impl QueryCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: NonZeroUsize::new(capacity).map(LruCache::new),
+            stamp: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Forget every entry unless the database is still at `stamp`
+    pub fn validate(&mut self, stamp: (u64, i64)) {
+        if self.stamp != Some(stamp) {
+            if let Some(entries) = &mut self.entries {
+                entries.clear();
+            }
+            self.stamp = Some(stamp);
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.entries.is_some()
+    }
+
+    pub fn get(&mut self, commit: &str, query: &str) -> Option<Cached> {
+        let hit = self
+            .entries
+            .as_mut()?
+            .get(&(commit.to_string(), query.to_string()))
+            .cloned();
+        if hit.is_some() {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+        hit
+    }
+
+    pub fn put(&mut self, commit: &str, query: &str, value: Cached) {
+        if let Some(entries) = &mut self.entries {
+            entries.put((commit.to_string(), query.to_string()), value);
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.as_ref().map_or(0, |entries| entries.len()),
+            ..self.stats
+        }
+    }
+}
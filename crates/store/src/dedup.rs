@@ -0,0 +1,153 @@
+use protocol::{CanonicalFqn, Language, Span, SymbolFlags, SymbolIR, SymbolKind};
+use serde::Serialize;
+
+/// One logical symbol among search results: the definition to show, and
+/// every place it was found, declarations and other snapshots' copies
+/// folded in
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchCard {
+    #[serde(flatten)]
+    pub symbol: SymbolIR,
+    pub locations: Vec<SymbolLocation>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SymbolLocation {
+    pub id: String,
+    pub file_path: String,
+    pub name_span: Span,
+    /// A `.d.ts`, header prototype or overload signature rather than the body
+    pub declaration: bool,
+}
+
+/// What makes two search results the same symbol: languages sharing a
+/// declaration space (TypeScript and JavaScript, C and C++) count as one,
+/// FQNs are compared in canonical form (so a C++ `Box<T>::get` defined out
+/// of line is the `Box::get` its header declares), and where overloads
+/// share an FQN their parameter types tell them apart
+#[derive(PartialEq)]
+struct CardKey {
+    family: Language,
+    kind: SymbolKind,
+    fqn: CanonicalFqn,
+    overload: Option<Vec<String>>,
+}
+
+impl CardKey {
+    fn of(symbol: &SymbolIR) -> Self {
+        let family = match symbol.lang {
+            Language::JavaScript => Language::TypeScript,
+            Language::C => Language::Cpp,
+            ref lang => lang.clone(),
+        };
+        let overloads = matches!(family, Language::Java | Language::CSharp | Language::Cpp)
+            && matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method);
+        CardKey {
+            family,
+            kind: symbol.kind.clone(),
+            fqn: symbol.canonical_fqn(),
+            overload: overloads.then(|| {
+                symbol.parameters.iter().map(|p| p.type_name.as_deref().unwrap_or("").trim().to_string()).collect()
+            }),
+        }
+    }
+}
+
+/// Fold `symbols`, best match first, into one card per logical symbol in
+/// the order each was first matched
+pub fn group(symbols: Vec<SymbolIR>) -> Vec<SearchCard> {
+    let mut cards: Vec<SearchCard> = Vec::new();
+    let mut keys: Vec<CardKey> = Vec::new();
+    for symbol in symbols {
+        let declaration = symbol.flags.contains(SymbolFlags::DECLARATION);
+        let location = SymbolLocation {
+            id: symbol.id.clone(),
+            file_path: symbol.file_path.clone(),
+            name_span: symbol.name_span.clone(),
+            declaration,
+        };
+        let key = CardKey::of(&symbol);
+        match keys.iter().position(|k| *k == key) {
+            Some(index) => {
+                let card = &mut cards[index];
+                if card.locations.iter().any(|l| l.id == location.id) {
+                    continue;
+                }
+                // The card shows the implementation once there is one
+                if card.symbol.flags.contains(SymbolFlags::DECLARATION) && !declaration {
+                    card.symbol = symbol;
+                }
+                card.locations.push(location);
+            }
+            None => {
+                cards.push(SearchCard { symbol, locations: vec![location] });
+                keys.push(key);
+            }
+        }
+    }
+    for card in &mut cards {
+        card.locations.sort_by_key(|l| l.declaration);
+    }
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::{Parameter, test_util};
+
+    fn symbol(lang: Language, file_path: &str, fqn: &str, declaration: bool, params: &[&str]) -> SymbolIR {
+        SymbolIR {
+            lang,
+            name: fqn.rsplit('.').next().unwrap().to_string(),
+            parameters: params
+                .iter()
+                .map(|t| Parameter { name: "x".to_string(), type_name: Some(t.to_string()), default: None, variadic: false })
+                .collect(),
+            name_span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: 4 },
+            full_span: test_util::lines(1, 3),
+            flags: if declaration { SymbolFlags::DECLARATION } else { SymbolFlags::empty() },
+            ..test_util::symbol(&format!("{}#{}", file_path, fqn), fqn, file_path)
+        }
+    }
+
+    #[test]
+    fn test_group_folds_copies_and_declarations() {
+        let results = vec![
+            symbol(Language::TypeScript, "types/api.d.ts", "api.fetch", true, &[]),
+            symbol(Language::JavaScript, "src/api.js", "api.fetch", false, &[]),
+            // The same row from an older snapshot
+            symbol(Language::JavaScript, "src/api.js", "api.fetch", false, &[]),
+            symbol(Language::Cpp, "net.h", "net::send", true, &["int"]),
+            symbol(Language::Cpp, "net.cpp", "net::send", false, &["int "]),
+            // An overload is its own card
+            symbol(Language::Cpp, "net.cpp", "net::send", false, &["const char*"]),
+            // Defined out of line against a template declaration
+            symbol(Language::Cpp, "box.h", "Box::get", true, &[]),
+            symbol(Language::Cpp, "box.cpp", "Box<T>::get", false, &[]),
+            // As is the same name in another language
+            symbol(Language::Python, "api.py", "api.fetch", false, &[]),
+        ];
+
+        let cards = group(results);
+        let shown: Vec<(&str, Vec<&str>)> = cards
+            .iter()
+            .map(|c| (c.symbol.id.as_str(), c.locations.iter().map(|l| l.file_path.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            shown,
+            [
+                ("src/api.js#api.fetch", vec!["src/api.js", "types/api.d.ts"]),
+                ("net.cpp#net::send", vec!["net.cpp", "net.h"]),
+                ("net.cpp#net::send", vec!["net.cpp"]),
+                ("box.cpp#Box<T>::get", vec!["box.cpp", "box.h"]),
+                ("api.py#api.fetch", vec!["api.py"]),
+            ]
+        );
+        assert!(cards[0].locations[1].declaration);
+
+        let json = serde_json::to_value(&cards[0]).unwrap();
+        assert_eq!(json["fqn"], "api.fetch");
+        assert_eq!(json["locations"].as_array().unwrap().len(), 2);
+    }
+}
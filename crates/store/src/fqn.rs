@@ -0,0 +1,68 @@
+/// Scope separators that symbol lookups treat as one, so `Foo::bar`,
+/// `Foo.bar`, `Foo#bar` and `Foo/bar` all find the same symbol whatever
+/// separator its language put in the stored FQN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FqnSeparators {
+    /// Longest first, so `::` is replaced before a `:` could be
+    separators: Vec<String>,
+}
+
+impl Default for FqnSeparators {
+    fn default() -> Self {
+        Self::new(["::", ".", "#", "/"].map(String::from))
+    }
+}
+
+impl FqnSeparators {
+    /// An empty list turns normalization off
+    pub fn new(separators: impl IntoIterator<Item = String>) -> Self {
+        let mut separators: Vec<String> = separators.into_iter().filter(|s| !s.is_empty()).collect();
+        separators.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        separators.dedup();
+        Self { separators }
+    }
+
+    fn replaced(&self) -> impl Iterator<Item = &str> {
+        self.separators.iter().map(String::as_str).filter(|separator| *separator != ".")
+    }
+
+    /// Whether `text` has any separator to normalize
+    pub fn applies_to(&self, text: &str) -> bool {
+        self.separators.iter().any(|separator| text.contains(separator.as_str()))
+    }
+
+    /// `text` with every separator replaced by `.`
+    pub fn normalize(&self, text: &str) -> String {
+        self.replaced().fold(text.to_string(), |text, separator| text.replace(separator, "."))
+    }
+
+    /// SQL expression for [`normalize`](Self::normalize) applied to `column`
+    pub(crate) fn normalize_sql(&self, column: &str) -> String {
+        self.replaced().fold(column.to_string(), |sql, separator| {
+            format!("REPLACE({}, '{}', '.')", sql, separator.replace('\'', "''"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_separators() {
+        let separators = FqnSeparators::default();
+        for query in ["app::models::User", "app.models.User", "app/models#User"] {
+            assert_eq!(separators.normalize(query), "app.models.User");
+        }
+        assert!(!separators.applies_to("User"));
+        assert_eq!(
+            separators.normalize_sql("s.fqn"),
+            "REPLACE(REPLACE(REPLACE(s.fqn, '::', '.'), '#', '.'), '/', '.')"
+        );
+
+        let custom = FqnSeparators::new([":".to_string(), "::".to_string(), "'".to_string()]);
+        assert_eq!(custom.normalize("a::b:c'd"), "a.b.c.d");
+        assert_eq!(custom.normalize_sql("fqn"), "REPLACE(REPLACE(REPLACE(fqn, '::', '.'), '''', '.'), ':', '.')");
+        assert!(!FqnSeparators::new([]).applies_to("a::b"));
+    }
+}
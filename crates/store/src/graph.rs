@@ -1,9 +1,11 @@
 use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
 use protocol::{EdgeIR, EdgeType, SymbolIR};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use tracing::info;
 
 /// In-memory graph for fast traversals
+#[derive(Clone)]
 pub struct CodeGraph {
     graph: DiGraph<String, EdgeType>,
     symbol_to_node: HashMap<String, NodeIndex>,
@@ -48,6 +50,18 @@ impl CodeGraph {
         graph
     }
     
+    /// Wrap a graph whose nodes are symbol ids
+    pub(crate) fn from_inner(graph: DiGraph<String, EdgeType>) -> Self {
+        let node_to_symbol: HashMap<NodeIndex, String> =
+            graph.node_indices().map(|node| (node, graph[node].clone())).collect();
+        let symbol_to_node = node_to_symbol.iter().map(|(node, id)| (id.clone(), *node)).collect();
+        Self { graph, symbol_to_node, node_to_symbol }
+    }
+    
+    pub(crate) fn inner(&self) -> &DiGraph<String, EdgeType> {
+        &self.graph
+    }
+    
     /// Add a symbol node to the graph
     pub fn add_symbol(&mut self, symbol_id: &str) -> NodeIndex {
         if let Some(&node) = self.symbol_to_node.get(symbol_id) {
@@ -152,32 +166,157 @@ impl CodeGraph {
         cycles
     }
     
+    /// Every cycle in the graph: strongly connected components of more
+    /// than one symbol (self-recursion alone doesn't count)
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        petgraph::algo::kosaraju_scc(&self.graph)
+            .into_iter()
+            .filter(|scc| scc.len() > 1)
+            .map(|scc| scc.iter().filter_map(|n| self.node_to_symbol.get(n).cloned()).collect())
+            .collect()
+    }
+    
+    /// PageRank of every symbol: how much of the graph depends on it,
+    /// directly or through other heavily used symbols. Ranks sum to 1.
+    /// Symbols without outgoing edges spread their rank evenly, as usual.
+    pub fn page_rank(&self, damping: f64, iterations: usize) -> HashMap<String, f64> {
+        let n = self.graph.node_count();
+        if n == 0 {
+            return HashMap::new();
+        }
+        let out_degree: Vec<usize> = self.graph.node_indices().map(|node| self.graph.edges(node).count()).collect();
+        let mut ranks = vec![1.0 / n as f64; n];
+        for _ in 0..iterations {
+            let dangling: f64 = (0..n).filter(|&i| out_degree[i] == 0).map(|i| ranks[i]).sum();
+            let base = (1.0 - damping) / n as f64 + damping * dangling / n as f64;
+            let mut next = vec![base; n];
+            for edge in self.graph.raw_edges() {
+                let (src, dst) = (edge.source().index(), edge.target().index());
+                next[dst] += damping * ranks[src] / out_degree[src] as f64;
+            }
+            ranks = next;
+        }
+        self.graph
+            .node_indices()
+            .filter_map(|node| Some((self.node_to_symbol.get(&node)?.clone(), ranks[node.index()])))
+            .collect()
+    }
+    
     /// Find shortest path between two symbols
+    ///
+    /// Searches forward from `from_id` and backward from `to_id` at once,
+    /// always growing the smaller frontier, so distant symbols meet in the
+    /// middle instead of one side flooding the whole graph.
     pub fn find_path(&self, from_id: &str, to_id: &str) -> Option<Vec<String>> {
-        use petgraph::algo::astar;
-        
-        let from_node = self.symbol_to_node.get(from_id)?;
-        let to_node = self.symbol_to_node.get(to_id)?;
-        
-        // Use A* to find the shortest path and get the actual path
-        let result = astar(
-            &self.graph,
-            *from_node,
-            |node| node == *to_node,
-            |_| 1,  // Edge weight
-            |_| 0,  // Heuristic (0 makes it equivalent to Dijkstra)
-        );
-        
-        if let Some((_, path)) = result {
-            // Convert node indices to symbol IDs
-            let symbol_path: Vec<String> = path
-                .iter()
-                .filter_map(|&node| self.node_to_symbol.get(&node).cloned())
-                .collect();
-            Some(symbol_path)
-        } else {
-            None
+        let from_node = *self.symbol_to_node.get(from_id)?;
+        let to_node = *self.symbol_to_node.get(to_id)?;
+        
+        // Each side maps a node it reached to the node it came from
+        let mut forward = HashMap::from([(from_node, None)]);
+        let mut backward = HashMap::from([(to_node, None)]);
+        let mut forward_frontier = vec![from_node];
+        let mut backward_frontier = vec![to_node];
+        let mut meeting = forward.contains_key(&to_node).then_some(to_node);
+        
+        while meeting.is_none() && !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+            let (frontier, reached, other, direction) = if forward_frontier.len() <= backward_frontier.len() {
+                (&mut forward_frontier, &mut forward, &backward, petgraph::Direction::Outgoing)
+            } else {
+                (&mut backward_frontier, &mut backward, &forward, petgraph::Direction::Incoming)
+            };
+            // One whole level, so the first meeting found is on a shortest path
+            let mut next = Vec::new();
+            for &node in frontier.iter() {
+                for neighbor in self.graph.neighbors_directed(node, direction) {
+                    if reached.contains_key(&neighbor) {
+                        continue;
+                    }
+                    reached.insert(neighbor, Some(node));
+                    next.push(neighbor);
+                    if meeting.is_none() && other.contains_key(&neighbor) {
+                        meeting = Some(neighbor);
+                    }
+                }
+            }
+            *frontier = next;
+        }
+        
+        let meeting = meeting?;
+        let mut path = vec![meeting];
+        while let Some(&Some(previous)) = forward.get(path.last()?) {
+            path.push(previous);
+        }
+        path.reverse();
+        while let Some(&Some(next)) = backward.get(path.last()?) {
+            path.push(next);
+        }
+        Some(path.iter().filter_map(|node| self.node_to_symbol.get(node).cloned()).collect())
+    }
+    
+    /// Symbols within `radius` edges of `symbol_id`, in either direction,
+    /// and the edges between them: the subgraph to draw around a symbol or
+    /// to pack as context for it. Only `edge_filter` types are followed
+    /// (all when empty). Nearer symbols are kept first when `limits` cut
+    /// the result short.
+    pub fn neighborhood(
+        &self,
+        symbol_id: &str,
+        radius: usize,
+        edge_filter: &[EdgeType],
+        limits: NeighborhoodLimits,
+    ) -> Neighborhood {
+        let mut neighborhood = Neighborhood::default();
+        let Some(&start) = self.symbol_to_node.get(symbol_id) else {
+            return neighborhood;
+        };
+        let followed = |edge_type: &EdgeType| edge_filter.is_empty() || edge_filter.contains(edge_type);
+        
+        let mut distances = HashMap::from([(start, 0)]);
+        let mut order = vec![start];
+        let mut queue = VecDeque::from([(start, 0)]);
+        'walk: while let Some((node, distance)) = queue.pop_front() {
+            if distance == radius {
+                continue;
+            }
+            let edges = self.graph
+                .edges_directed(node, petgraph::Direction::Outgoing)
+                .map(|e| (e.target(), e.weight()))
+                .chain(self.graph.edges_directed(node, petgraph::Direction::Incoming).map(|e| (e.source(), e.weight())));
+            for (neighbor, edge_type) in edges {
+                if !followed(edge_type) || distances.contains_key(&neighbor) {
+                    continue;
+                }
+                if order.len() == limits.max_nodes {
+                    neighborhood.truncated = true;
+                    break 'walk;
+                }
+                distances.insert(neighbor, distance + 1);
+                order.push(neighbor);
+                queue.push_back((neighbor, distance + 1));
+            }
+        }
+        
+        for &node in &order {
+            for edge in self.graph.edges_directed(node, petgraph::Direction::Outgoing) {
+                if !followed(edge.weight()) || !distances.contains_key(&edge.target()) {
+                    continue;
+                }
+                if neighborhood.edges.len() == limits.max_edges {
+                    neighborhood.truncated = true;
+                    break;
+                }
+                neighborhood.edges.push(NeighborhoodEdge {
+                    src: self.graph[node].clone(),
+                    dst: self.graph[edge.target()].clone(),
+                    edge_type: edge.weight().clone(),
+                });
+            }
         }
+        neighborhood.nodes = order
+            .into_iter()
+            .map(|node| NeighborhoodNode { id: self.graph[node].clone(), distance: distances[&node] })
+            .collect();
+        neighborhood
     }
     
     /// Get graph statistics
@@ -196,10 +335,47 @@ pub struct GraphStats {
     pub is_cyclic: bool,
 }
 
+/// Caps on the size of a [`CodeGraph::neighborhood`]
+#[derive(Debug, Clone, Copy)]
+pub struct NeighborhoodLimits {
+    pub max_nodes: usize,
+    pub max_edges: usize,
+}
+
+impl Default for NeighborhoodLimits {
+    fn default() -> Self {
+        Self { max_nodes: 200, max_edges: 1000 }
+    }
+}
+
+/// Induced subgraph around a symbol
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct Neighborhood {
+    /// The symbol itself first, then outward by distance
+    pub nodes: Vec<NeighborhoodNode>,
+    pub edges: Vec<NeighborhoodEdge>,
+    /// Whether the limits left out symbols or edges within the radius
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct NeighborhoodNode {
+    pub id: String,
+    /// Edges away from the symbol the neighborhood is around
+    pub distance: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct NeighborhoodEdge {
+    pub src: String,
+    pub dst: String,
+    pub edge_type: EdgeType,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use protocol::{Language, Span};
+    use protocol::{Language, Span, SymbolFlags};
     
     fn create_test_symbol(id: &str, name: &str) -> SymbolIR {
         SymbolIR {
@@ -210,10 +386,16 @@ mod tests {
             name: name.to_string(),
             fqn: format!("test.{}", name),
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "test.ts".to_string(),
-            span: Span { start_line: 0, start_col: 0, end_line: 0, end_col: 0 },
+            name_span: Default::default(),
+            full_span: Span { start_line: 0, start_col: 0, end_line: 0, end_col: 0 },
             visibility: None,
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "test".to_string(),
         }
     }
@@ -403,6 +585,84 @@ mod tests {
         assert_eq!(graph.find_callees("a", 1), vec!["b".to_string()]);
     }
 
+    #[test]
+    fn test_page_rank() {
+        let mut graph = CodeGraph::new();
+        graph.add_edge("a", "core", EdgeType::Calls);
+        graph.add_edge("b", "core", EdgeType::Calls);
+        graph.add_edge("c", "core", EdgeType::Calls);
+        graph.add_edge("core", "leaf", EdgeType::Calls);
+        
+        let ranks = graph.page_rank(0.85, 30);
+        assert_eq!(ranks.len(), 5);
+        assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        // Depended on by three callers, and passing it all on to its callee
+        assert!(ranks["core"] > ranks["a"]);
+        assert!(ranks["leaf"] > ranks["core"]);
+        assert!((ranks["a"] - ranks["b"]).abs() < 1e-12);
+        assert!(CodeGraph::new().page_rank(0.85, 30).is_empty());
+    }
+
+    #[test]
+    fn test_neighborhood() {
+        let mut graph = CodeGraph::new();
+        graph.add_edge("caller", "center", EdgeType::Calls);
+        graph.add_edge("center", "callee", EdgeType::Calls);
+        graph.add_edge("callee", "far", EdgeType::Calls);
+        graph.add_edge("caller", "callee", EdgeType::Calls);
+        graph.add_edge("module", "center", EdgeType::Contains);
+        graph.add_edge("module", "far", EdgeType::Contains);
+        
+        fn ids(neighborhood: &Neighborhood) -> HashMap<&str, usize> {
+            neighborhood.nodes.iter().map(|n| (n.id.as_str(), n.distance)).collect()
+        }
+        let limits = NeighborhoodLimits::default();
+        
+        let around = graph.neighborhood("center", 1, &[], limits);
+        assert_eq!(around.nodes[0], NeighborhoodNode { id: "center".to_string(), distance: 0 });
+        assert_eq!(ids(&around), HashMap::from([("center", 0), ("caller", 1), ("callee", 1), ("module", 1)]));
+        // Induced: includes caller -> callee, but nothing to `far`
+        assert_eq!(around.edges.len(), 4);
+        assert!(around.edges.iter().all(|e| e.dst != "far"));
+        assert!(!around.truncated);
+        
+        // Only calls: `far` is two calls away, not one containment
+        let calls = graph.neighborhood("center", 1, &[EdgeType::Calls], limits);
+        assert_eq!(ids(&calls), HashMap::from([("center", 0), ("caller", 1), ("callee", 1)]));
+        let calls = graph.neighborhood("center", 2, &[EdgeType::Calls], limits);
+        assert_eq!(ids(&calls)["far"], 2);
+        
+        let capped = graph.neighborhood("center", 2, &[], NeighborhoodLimits { max_nodes: 3, max_edges: 1 });
+        assert_eq!(capped.nodes.len(), 3);
+        assert!(capped.nodes.iter().all(|n| n.distance <= 1));
+        assert_eq!(capped.edges.len(), 1);
+        assert!(capped.truncated);
+        
+        assert!(graph.neighborhood("nonexistent", 2, &[], limits).nodes.is_empty());
+        assert_eq!(graph.neighborhood("center", 0, &[], limits).nodes.len(), 1);
+    }
+
+    #[test]
+    fn test_find_path_takes_shortest_route() {
+        let mut graph = CodeGraph::new();
+        // A long chain with a shortcut through `hub`, and a dead end fanning out
+        for i in 1..50 {
+            graph.add_edge(&format!("n{}", i - 1), &format!("n{}", i), EdgeType::Calls);
+        }
+        graph.add_edge("n3", "hub", EdgeType::Calls);
+        graph.add_edge("hub", "n40", EdgeType::Calls);
+        for i in 0..500 {
+            graph.add_edge("n1", &format!("leaf{}", i), EdgeType::Calls);
+        }
+        
+        let path = graph.find_path("n0", "n49").unwrap();
+        assert_eq!(path[..6], ["n0", "n1", "n2", "n3", "hub", "n40"]);
+        assert_eq!(path.len(), 15);
+        assert_eq!(graph.find_path("n49", "n0"), None);
+        assert_eq!(graph.find_path("leaf7", "n2"), None);
+        assert_eq!(graph.find_path("n1", "leaf7").unwrap(), ["n1", "leaf7"]);
+    }
+
     #[test]
     fn test_nonexistent_symbol_queries() {
         let mut graph = CodeGraph::new();
@@ -518,6 +778,10 @@ mod tests {
         let cycles_x = graph.find_cycles_containing("x");
         assert_eq!(cycles_x.len(), 1);
         assert_eq!(cycles_x[0].len(), 3);
+
+        // Self-recursion is not a cycle between symbols
+        graph.add_edge("r", "r", EdgeType::Calls);
+        assert_eq!(graph.cycles().len(), 2);
     }
 
     #[test]
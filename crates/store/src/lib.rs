@@ -1,38 +1,509 @@
 use anyhow::Result;
-use protocol::{EdgeIR, EdgeType, Language, OccurrenceIR, Span, SymbolIR, SymbolKind};
+use protocol::path::normalize_path;
+use protocol::{EdgeIR, EdgeType, Language, OccurrenceIR, Parameter, Span, SymbolFlags, SymbolIR, SymbolKind};
 use rusqlite::{params, Connection, OptionalExtension};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use tracing::info;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
 
+mod adjacency;
+mod aggregate;
+mod bundle;
+mod cache;
+mod dedup;
+mod fqn;
 mod graph;
-pub use graph::{CodeGraph, GraphStats};
+mod noise;
+mod summary;
+pub use aggregate::{AggregateEdge, AggregateGraph, AggregateNode, Granularity};
+pub use bundle::{unpack_bundle, MergeSummary};
+pub use cache::{CacheStats, DEFAULT_CACHE_CAPACITY};
+pub use dedup::{SearchCard, SymbolLocation};
+pub use fqn::FqnSeparators;
+use adjacency::{Adjacency, LoadedGraph};
+use cache::{Cached, QueryCache};
+pub use graph::{CodeGraph, GraphStats, Neighborhood, NeighborhoodEdge, NeighborhoodLimits, NeighborhoodNode};
+pub use noise::{NoiseFilter, NoiseMode};
+pub use summary::{DirectorySummary, FileSummary, ParseQuality};
+
+/// Upper bound on alias hops followed by `resolve_alias`
+const MAX_ALIAS_DEPTH: usize = 16;
+
+/// Rows fetched per search card asked for, to fill the cards after copies fold
+const SEARCH_CARD_OVERFETCH: usize = 4;
+
+/// Restrictions applied to symbol search, evaluated in SQL
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFilter {
+    /// Match any of these kinds (empty = all)
+    pub kinds: Vec<SymbolKind>,
+    /// Match any of these languages (empty = all)
+    pub langs: Vec<Language>,
+    /// SQLite GLOB over the file path, e.g. `src/**.ts` or `*_test.go`
+    pub file_glob: Option<String>,
+    pub visibility: Option<String>,
+    /// SQLite GLOB over the declared return type, e.g. `Result<*, io::Error>`
+    pub return_type: Option<String>,
+    /// Symbols must carry all of these flags
+    pub with_flags: SymbolFlags,
+    /// Symbols must carry none of these flags
+    pub without_flags: SymbolFlags,
+    /// Architecture layer assigned at scan time
+    pub layer: Option<String>,
+    /// Kind of entry point detected at scan time, e.g. `http`
+    pub entry_point: Option<String>,
+}
+
+impl SearchFilter {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// `AND ...` predicates over the symbol table aliased as `s`, numbering
+    /// parameters from `?{first_param}`
+    fn to_sql(&self, first_param: usize) -> Result<(String, Vec<rusqlite::types::Value>)> {
+        let mut sql = String::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        let placeholder = |value: rusqlite::types::Value, values: &mut Vec<rusqlite::types::Value>| {
+            values.push(value);
+            format!("?{}", first_param + values.len() - 1)
+        };
+        
+        if !self.kinds.is_empty() {
+            let kinds = self.kinds.iter()
+                .map(|k| Ok(placeholder(enum_to_text(k)?.into(), &mut values)))
+                .collect::<Result<Vec<_>>>()?;
+            sql.push_str(&format!(" AND s.kind IN ({})", kinds.join(", ")));
+        }
+        if !self.langs.is_empty() {
+            let langs = self.langs.iter()
+                .map(|l| Ok(placeholder(enum_to_text(l)?.into(), &mut values)))
+                .collect::<Result<Vec<_>>>()?;
+            sql.push_str(&format!(" AND s.lang IN ({})", langs.join(", ")));
+        }
+        if let Some(glob) = &self.file_glob {
+            sql.push_str(&format!(" AND s.file_path GLOB {}", placeholder(normalize_path(glob).into(), &mut values)));
+        }
+        if let Some(visibility) = &self.visibility {
+            sql.push_str(&format!(" AND s.visibility = {}", placeholder(visibility.clone().into(), &mut values)));
+        }
+        if let Some(glob) = &self.return_type {
+            sql.push_str(&format!(" AND s.return_type GLOB {}", placeholder(glob.clone().into(), &mut values)));
+        }
+        if !self.with_flags.is_empty() {
+            let bits = placeholder(i64::from(self.with_flags.bits()).into(), &mut values);
+            sql.push_str(&format!(" AND (s.flags & {bits}) = {bits}"));
+        }
+        if !self.without_flags.is_empty() {
+            let bits = placeholder(i64::from(self.without_flags.bits()).into(), &mut values);
+            sql.push_str(&format!(" AND (s.flags & {bits}) = 0"));
+        }
+        if let Some(layer) = &self.layer {
+            sql.push_str(&format!(" AND s.layer = {}", placeholder(layer.clone().into(), &mut values)));
+        }
+        if let Some(entry_point) = &self.entry_point {
+            sql.push_str(&format!(" AND s.entry_point = {}", placeholder(entry_point.clone().into(), &mut values)));
+        }
+        
+        Ok((sql, values))
+    }
+}
+
+/// A symbol found by [`GraphStore::find_symbol_by_fqn`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FqnMatch {
+    pub symbol: SymbolIR,
+    /// Alias ids followed to reach it, empty when the FQN named it directly
+    pub alias_chain: Vec<String>,
+}
+
+/// A file importing a symbol by name, from [`GraphStore::get_importers`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct SymbolImporter {
+    pub file: String,
+    /// The export imported: the symbol's name, a name it is exported under,
+    /// or `*` for a namespace import of its module
+    pub imported: String,
+    /// The binding in the importing file
+    pub local: String,
+}
+
+/// An alternative name under which a symbol can be reached
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolAlias {
+    /// Short name as written at the alias site
+    pub alias: String,
+    /// FQN or symbol id of the alias itself
+    pub alias_id: String,
+    /// What the alias refers to: a symbol id, an FQN, or another alias
+    pub target: String,
+    /// Language-specific alias flavor (e.g. "reexport", "import_as", "typedef")
+    pub kind: Option<String>,
+    pub file_path: Option<String>,
+}
+
+impl SymbolAlias {
+    /// Build an alias record from an `Aliases` edge
+    pub fn from_edge(edge: &EdgeIR) -> Option<Self> {
+        let alias_id = edge.src.clone()?;
+        let target = edge.dst.clone()?;
+        let alias = edge.meta.get("alias")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .or_else(|| protocol::fqn::canonicalize(&Language::Unknown, &alias_id).name().map(|s| s.to_string()))
+            .unwrap_or_else(|| alias_id.clone());
+        let kind = edge.meta.get("alias_kind")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        
+        Some(Self {
+            alias,
+            alias_id,
+            target,
+            kind,
+            file_path: edge.file_src.clone(),
+        })
+    }
+}
+
+/// An occurrence matched by [`GraphStore::search_occurrences`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OccurrenceHit {
+    pub occurrence: OccurrenceIR,
+    /// What the token refers to, when the harness resolved it
+    pub symbol: Option<SymbolIR>,
+    /// The function, class, ... the token appears in
+    pub enclosing: Option<SymbolIR>,
+}
+
+/// Use of one line of a file, from [`GraphStore::file_heatmap`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct LineHeat {
+    /// 0-based
+    pub line: u32,
+    /// Symbols whose name is on this line
+    pub definitions: usize,
+    /// Occurrences in other files referring to those symbols
+    pub references: usize,
+}
 
 pub struct GraphStore {
     db_path: PathBuf,
     conn: Connection,
+    /// Graph builds, caller/callee walks and searches; see [`QueryCache`]
+    cache: RefCell<QueryCache>,
+    /// Applied to edges read back for graph walks and metrics
+    noise: NoiseFilter,
+    /// Scope separators that FQN lookups and searches treat as equal
+    separators: FqnSeparators,
+    /// Whether graph builds are saved to and loaded from an adjacency file
+    /// beside the database
+    adjacency_cache: bool,
+}
+
+/// SQL condition that `column` is the path `prefix` (a parameter) or lies
+/// under it
+fn under_sql(column: &str, prefix: &str) -> String {
+    format!("({column} = {prefix} OR substr({column}, 1, length({prefix}) + 1) = {prefix} || '/')")
+}
+
+/// Last segment of a call token: `this.repo.load` / `pkg::load` -> `load`
+fn token_name(token: &str) -> &str {
+    token.rsplit(['.', ':', '>', '/']).next().unwrap_or(token)
+}
+
+/// Serialize a map with sorted keys so equal maps always produce equal bytes
+fn to_sorted_json<V: serde::Serialize>(map: &HashMap<String, V>) -> serde_json::Result<String> {
+    serde_json::to_string(&map.iter().collect::<BTreeMap<_, _>>())
+}
+
+/// Unit enum variant as its bare name (`Function`, not `"Function"`), so
+/// columns like `symbol.kind` can be filtered with plain SQL
+fn enum_to_text<T: serde::Serialize>(value: &T) -> serde_json::Result<String> {
+    match serde_json::to_value(value)? {
+        serde_json::Value::String(text) => Ok(text),
+        other => Ok(other.to_string()),
+    }
+}
+
+/// Columns read by [`symbol_from_row`], for queries over `symbol s`
+const SYMBOL_COLUMNS: &str = "s.symbol_id, s.lang, s.kind, s.name, s.fqn, s.signature, s.file_path, \
+     s.span_start_line, s.span_start_col, s.span_end_line, s.span_end_col, s.visibility, s.doc, \
+     s.sig_hash, s.lang_version, s.name_start_line, s.name_start_col, s.name_end_line, s.name_end_col, \
+     s.parameters, s.return_type, s.flags, s.configs, s.doc_tags";
+
+/// Prepared statements kept per connection; enough for every fixed query
+/// plus the common search filter shapes
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+/// A symbol from a row selecting [`SYMBOL_COLUMNS`]
+fn symbol_from_row(row: &rusqlite::Row) -> rusqlite::Result<SymbolIR> {
+    Ok(SymbolIR {
+        id: row.get(0)?,
+        lang: enum_from_text(&row.get::<_, String>(1)?).unwrap_or(Language::Unknown),
+        lang_version: row.get::<_, Option<String>>(14)?.and_then(|v| enum_from_text(&v)),
+        kind: enum_from_text(&row.get::<_, String>(2)?).unwrap_or(SymbolKind::Variable),
+        name: row.get(3)?,
+        fqn: row.get(4)?,
+        signature: row.get(5)?,
+        parameters: parameters_from_row(row)?,
+        return_type: row.get(20)?,
+        file_path: row.get(6)?,
+        name_span: name_span_from_row(row)?,
+        full_span: Span {
+            start_line: row.get(7)?,
+            start_col: row.get(8)?,
+            end_line: row.get(9)?,
+            end_col: row.get(10)?,
+        },
+        visibility: row.get(11)?,
+        flags: SymbolFlags::from_bits_truncate(row.get(21)?),
+        configs: configs_from_row(row)?,
+        doc: row.get(12)?,
+        doc_tags: row.get::<_, Option<String>>(23)?.and_then(|json| serde_json::from_str(&json).ok()),
+        sig_hash: row.get(13)?,
+    })
+}
+
+/// An edge from a row selecting `edge_type, src_symbol, dst_symbol, file_src,
+/// file_dst, resolution, meta, provenance`
+fn edge_from_row(row: &rusqlite::Row) -> rusqlite::Result<EdgeIR> {
+    Ok(EdgeIR {
+        edge_type: serde_json::from_str(&row.get::<_, String>(0)?).unwrap_or(EdgeType::Contains),
+        src: row.get(1)?,
+        dst: row.get(2)?,
+        file_src: row.get(3)?,
+        file_dst: row.get(4)?,
+        resolution: serde_json::from_str(&row.get::<_, String>(5)?).unwrap_or(protocol::Resolution::Syntactic),
+        meta: row.get::<_, Option<String>>(6)?
+            .and_then(|m| serde_json::from_str(&m).ok())
+            .unwrap_or_default(),
+        provenance: row.get::<_, Option<String>>(7)?
+            .and_then(|p| serde_json::from_str(&p).ok())
+            .unwrap_or_default(),
+    })
+}
+
+/// An occurrence from a row selecting `file_path, symbol_id, role,
+/// span_start_line, span_start_col, span_end_line, span_end_col, token`
+fn occurrence_from_row(row: &rusqlite::Row) -> rusqlite::Result<OccurrenceIR> {
+    Ok(OccurrenceIR {
+        file_path: row.get(0)?,
+        symbol_id: row.get(1)?,
+        role: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or(protocol::OccurrenceRole::Reference),
+        span: Span {
+            start_line: row.get(3)?,
+            start_col: row.get(4)?,
+            end_line: row.get(5)?,
+            end_col: row.get(6)?,
+        },
+        token: row.get(7)?,
+    })
+}
+
+/// Name span from columns 15-18 of a symbol row; rows written before name
+/// spans were stored fall back to the full span in columns 7-10
+fn name_span_from_row(row: &rusqlite::Row) -> rusqlite::Result<Span> {
+    let base = match row.get::<_, Option<u32>>(15)? {
+        Some(_) => 15,
+        None => 7,
+    };
+    Ok(Span {
+        start_line: row.get(base)?,
+        start_col: row.get(base + 1)?,
+        end_line: row.get(base + 2)?,
+        end_col: row.get(base + 3)?,
+    })
+}
+
+/// Structured parameters from column 19 of a symbol row (NULL when there are none)
+fn parameters_from_row(row: &rusqlite::Row) -> rusqlite::Result<Vec<Parameter>> {
+    Ok(row
+        .get::<_, Option<String>>(19)?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+/// Build configurations from column 22 of a symbol row (NULL when there are none)
+fn configs_from_row(row: &rusqlite::Row) -> rusqlite::Result<Vec<String>> {
+    Ok(row
+        .get::<_, Option<String>>(22)?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+fn enum_from_text<T: serde::de::DeserializeOwned>(text: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(text.to_string())).ok()
+}
+
+/// Tables written by [`GraphStore::export_jsonl`], with the column order that
+/// makes each row's position stable and the columns holding encoded JSON
+const EXPORT_TABLES: &[(&str, &str, &[&str])] = &[
+    ("file", "path, content_hash, size_bytes, encoding", &[]),
+    (
+        "symbol",
+        "symbol_id, lang, kind, name, fqn, signature, file_path, span_start_line, span_start_col, \
+         span_end_line, span_end_col, visibility, doc, sig_hash, lang_version, name_start_line, \
+         name_start_col, name_end_line, name_end_col, parameters, return_type, flags, configs, doc_tags",
+        &["parameters", "configs", "doc_tags"],
+    ),
+    (
+        "edge",
+        "edge_type, src_symbol, dst_symbol, file_src, file_dst, resolution, meta, provenance",
+        &["edge_type", "resolution", "meta", "provenance"],
+    ),
+    (
+        "occurrence",
+        "file_path, span_start_line, span_start_col, span_end_line, span_end_col, symbol_id, role, token",
+        &["role"],
+    ),
+    ("symbol_alias", "alias_id, target, alias, kind, file_path", &[]),
+    (
+        "file_summary",
+        "path, lang, symbols, symbols_by_kind, imports, quality, complexity",
+        &["symbols_by_kind"],
+    ),
+];
+
+/// An in-progress snapshot ingestion.
+///
+/// Everything written through the store while the writer is open lands in a
+/// single transaction, so the `commit_snapshot` row and all of its files only
+/// become visible to readers together. Dropping the writer without calling
+/// [`SnapshotWriter::commit`] (an error, a panic, a killed scan) rolls the
+/// whole snapshot back. An interrupted scan commits its finished files
+/// together with a scan checkpoint, which keeps the snapshot out of
+/// `get_latest_commit` until a later scan completes it.
+pub struct SnapshotWriter<'a> {
+    store: &'a GraphStore,
+    commit_id: i64,
+    finished: bool,
+}
+
+impl SnapshotWriter<'_> {
+    pub fn commit_id(&self) -> i64 {
+        self.commit_id
+    }
+
+    /// Publish the snapshot to readers
+    pub fn commit(mut self) -> Result<()> {
+        self.finished = true;
+        self.store.bump_graph_version()?;
+        self.store.conn.execute_batch("COMMIT")?;
+        Ok(())
+    }
+}
+
+impl Drop for SnapshotWriter<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            if let Err(e) = self.store.conn.execute_batch("ROLLBACK") {
+                tracing::warn!("Failed to roll back snapshot {}: {}", self.commit_id, e);
+            }
+        }
+    }
 }
 
 impl GraphStore {
     pub fn new(repo_path: &Path) -> Result<Self> {
         let db_dir = repo_path.join(".reviewbot");
         std::fs::create_dir_all(&db_dir)?;
-        let db_path = db_dir.join("graph.db");
-        
-        let conn = Connection::open(&db_path)?;
+        Self::open(&db_dir.join("graph.db"))
+    }
+
+    /// Open (creating if needed) the database at `db_path`
+    pub fn open(db_path: &Path) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
         
         // Enable WAL mode for better concurrency
         conn.pragma_update(None, "journal_mode", "WAL")?;
         conn.pragma_update(None, "foreign_keys", "ON")?;
         
-        let store = Self { db_path, conn };
+        let store = Self {
+            db_path: db_path.to_path_buf(),
+            conn,
+            cache: RefCell::new(QueryCache::new(DEFAULT_CACHE_CAPACITY)),
+            noise: NoiseFilter::default(),
+            separators: FqnSeparators::default(),
+            adjacency_cache: true,
+        };
         store.init_schema()?;
         Ok(store)
     }
-    
+
+    /// Keep at most `capacity` cached query results; 0 disables the cache
+    pub fn with_cache_capacity(self, capacity: usize) -> Self {
+        self.cache.replace(QueryCache::new(capacity));
+        self
+    }
+
+    /// Drop or collapse standard library calls in the graph and in
+    /// [`Self::get_snapshot_edges`]
+    pub fn with_noise_filter(mut self, noise: NoiseFilter) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    /// Separators [`Self::find_symbol_by_fqn`] and searches normalize, by
+    /// default `::`, `.`, `#` and `/`
+    pub fn with_fqn_separators(mut self, separators: FqnSeparators) -> Self {
+        self.separators = separators;
+        self
+    }
+
+    /// Turn the on-disk adjacency file the graph is loaded from off or on
+    pub fn with_adjacency_cache(mut self, enabled: bool) -> Self {
+        self.adjacency_cache = enabled;
+        self
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.borrow().stats()
+    }
+
+    /// Result of `query` against the latest snapshot, computed at most once
+    /// until the database changes
+    fn cached(&self, query: &str, compute: impl FnOnce() -> Result<Cached>) -> Result<Cached> {
+        if !self.cache.borrow().is_enabled() {
+            return compute();
+        }
+        // data_version moves when another connection commits, total_changes
+        // when this one writes
+        let data_version: i64 = self.conn.query_row("PRAGMA data_version", [], |row| row.get(0))?;
+        let commit = self.get_latest_commit()?.unwrap_or_default();
+        {
+            let mut cache = self.cache.borrow_mut();
+            cache.validate((self.conn.total_changes(), data_version));
+            if let Some(hit) = cache.get(&commit, query) {
+                return Ok(hit);
+            }
+        }
+        let value = compute()?;
+        self.cache.borrow_mut().put(&commit, query, value.clone());
+        Ok(value)
+    }
+
+    fn cached_symbols(&self, query: &str, compute: impl FnOnce() -> Result<Vec<SymbolIR>>) -> Result<Vec<SymbolIR>> {
+        match self.cached(query, || Ok(Cached::Symbols(Arc::new(compute()?))))? {
+            Cached::Symbols(symbols) => Ok(symbols.as_ref().clone()),
+            Cached::Graph(_) => unreachable!("symbol queries never cache a graph"),
+        }
+    }
+
+    fn graph(&self) -> Result<Arc<LoadedGraph>> {
+        match self.cached("graph", || Ok(Cached::Graph(Arc::new(self.load_graph()?))))? {
+            Cached::Graph(graph) => Ok(graph),
+            Cached::Symbols(_) => unreachable!("the graph query never caches symbols"),
+        }
+    }
+
     fn get_connection(&self) -> Result<&Connection> {
         Ok(&self.conn)
     }
-    
+
     fn init_schema(&self) -> Result<()> {
         self.conn.execute_batch(
             r#"
@@ -49,6 +520,7 @@ impl GraphStore {
                 path TEXT NOT NULL,
                 content_hash TEXT NOT NULL,
                 size_bytes INTEGER,
+                encoding TEXT,
                 FOREIGN KEY (commit_id) REFERENCES commit_snapshot(id),
                 UNIQUE(commit_id, path)
             );
@@ -70,6 +542,18 @@ impl GraphStore {
                 visibility TEXT,
                 doc TEXT,
                 sig_hash TEXT NOT NULL,
+                lang_version TEXT,
+                name_start_line INTEGER,
+                name_start_col INTEGER,
+                name_end_line INTEGER,
+                name_end_col INTEGER,
+                parameters TEXT,
+                return_type TEXT,
+                flags INTEGER NOT NULL DEFAULT 0,
+                configs TEXT,
+                doc_tags TEXT,
+                layer TEXT,
+                entry_point TEXT,
                 FOREIGN KEY (commit_id) REFERENCES commit_snapshot(id),
                 UNIQUE(commit_id, symbol_id)
             );
@@ -83,6 +567,8 @@ impl GraphStore {
                 file_src TEXT,
                 file_dst TEXT,
                 resolution TEXT NOT NULL,
+                meta TEXT,
+                provenance TEXT,
                 FOREIGN KEY (commit_id) REFERENCES commit_snapshot(id)
             );
             
@@ -100,14 +586,54 @@ impl GraphStore {
                 FOREIGN KEY (commit_id) REFERENCES commit_snapshot(id)
             );
             
+            CREATE TABLE IF NOT EXISTS symbol_alias (
+                id INTEGER PRIMARY KEY,
+                commit_id INTEGER NOT NULL,
+                alias TEXT NOT NULL,
+                alias_id TEXT NOT NULL,
+                target TEXT NOT NULL,
+                kind TEXT,
+                file_path TEXT,
+                FOREIGN KEY (commit_id) REFERENCES commit_snapshot(id),
+                UNIQUE(commit_id, alias_id, target)
+            );
+            
+            -- Per-file figures written at scan time, for roll-ups that
+            -- shouldn't aggregate the symbol table
+            CREATE TABLE IF NOT EXISTS file_summary (
+                id INTEGER PRIMARY KEY,
+                commit_id INTEGER NOT NULL,
+                path TEXT NOT NULL,
+                lang TEXT NOT NULL,
+                symbols INTEGER NOT NULL,
+                symbols_by_kind TEXT NOT NULL,
+                imports INTEGER NOT NULL,
+                quality TEXT NOT NULL,
+                complexity INTEGER NOT NULL,
+                FOREIGN KEY (commit_id) REFERENCES commit_snapshot(id),
+                UNIQUE(commit_id, path)
+            );
+            
+            -- Present while a scan of the commit is incomplete (interrupted)
+            CREATE TABLE IF NOT EXISTS scan_checkpoint (
+                commit_id INTEGER PRIMARY KEY,
+                completed_files TEXT NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (commit_id) REFERENCES commit_snapshot(id)
+            );
+            
             CREATE INDEX IF NOT EXISTS idx_symbol_fqn ON symbol(fqn);
             CREATE INDEX IF NOT EXISTS idx_symbol_commit_fqn ON symbol(commit_id, fqn);
+            CREATE INDEX IF NOT EXISTS idx_symbol_kind ON symbol(kind);
+            CREATE INDEX IF NOT EXISTS idx_symbol_lang ON symbol(lang);
             CREATE INDEX IF NOT EXISTS idx_edge_src ON edge(src_symbol);
             CREATE INDEX IF NOT EXISTS idx_edge_dst ON edge(dst_symbol);
             CREATE INDEX IF NOT EXISTS idx_edge_type ON edge(edge_type);
             CREATE INDEX IF NOT EXISTS idx_edge_resolution ON edge(resolution);
             CREATE INDEX IF NOT EXISTS idx_occurrence_file ON occurrence(file_path);
             CREATE INDEX IF NOT EXISTS idx_occurrence_symbol ON occurrence(symbol_id);
+            CREATE INDEX IF NOT EXISTS idx_alias_name ON symbol_alias(alias);
+            CREATE INDEX IF NOT EXISTS idx_alias_id ON symbol_alias(alias_id);
             
             -- FTS5 virtual table for full-text search on symbols
             CREATE VIRTUAL TABLE IF NOT EXISTS symbol_fts USING fts5(
@@ -137,6 +663,37 @@ impl GraphStore {
                 VALUES (new.id, new.symbol_id, new.name, new.fqn, new.doc, new.file_path);
             END;
             
+            -- Identifier index over occurrence tokens for grep-like searches.
+            -- `_` stays inside a token; `.`, `::` and `->` split member paths.
+            CREATE VIRTUAL TABLE IF NOT EXISTS occurrence_fts USING fts5(
+                token,
+                content=occurrence,
+                content_rowid=id,
+                tokenize="unicode61 tokenchars '_'"
+            );
+            
+            CREATE TRIGGER IF NOT EXISTS occurrence_fts_insert AFTER INSERT ON occurrence BEGIN
+                INSERT INTO occurrence_fts(rowid, token) VALUES (new.id, new.token);
+            END;
+            
+            CREATE TRIGGER IF NOT EXISTS occurrence_fts_delete AFTER DELETE ON occurrence BEGIN
+                INSERT INTO occurrence_fts(occurrence_fts, rowid, token) VALUES ('delete', old.id, old.token);
+            END;
+            
+            CREATE TRIGGER IF NOT EXISTS occurrence_fts_update AFTER UPDATE OF token ON occurrence BEGIN
+                INSERT INTO occurrence_fts(occurrence_fts, rowid, token) VALUES ('delete', old.id, old.token);
+                INSERT INTO occurrence_fts(rowid, token) VALUES (new.id, new.token);
+            END;
+            
+            -- Bumped once per batch of writes to the graph's nodes and edges
+            -- (see `bump_graph_version`), so saved adjacency files can tell
+            -- they are stale
+            CREATE TABLE IF NOT EXISTS graph_version (
+                id INTEGER PRIMARY KEY CHECK (id = 0),
+                version INTEGER NOT NULL
+            );
+            INSERT OR IGNORE INTO graph_version (id, version) VALUES (0, 0);
+            
             COMMIT;
             "#,
         )?;
@@ -152,10 +709,214 @@ impl GraphStore {
              CREATE INDEX IF NOT EXISTS idx_file_commit ON file(commit_id, path);"
         )?;
         
+        self.migrate_schema()?;
+        
         info!("Database schema initialized at {:?}", self.db_path);
         Ok(())
     }
-    
+
+    /// Bring databases created by older versions up to the current schema
+    fn migrate_schema(&self) -> Result<()> {
+        for column in ["meta", "provenance"] {
+            if !self.has_column("edge", column)? {
+                self.conn.execute(&format!("ALTER TABLE edge ADD COLUMN {} TEXT", column), [])?;
+            }
+        }
+        if !self.has_column("symbol", "lang_version")? {
+            self.conn.execute("ALTER TABLE symbol ADD COLUMN lang_version TEXT", [])?;
+        }
+        for column in ["name_start_line", "name_start_col", "name_end_line", "name_end_col"] {
+            if !self.has_column("symbol", column)? {
+                self.conn.execute(&format!("ALTER TABLE symbol ADD COLUMN {} INTEGER", column), [])?;
+            }
+        }
+        for column in ["parameters", "return_type", "configs", "doc_tags", "layer", "entry_point"] {
+            if !self.has_column("symbol", column)? {
+                self.conn.execute(&format!("ALTER TABLE symbol ADD COLUMN {} TEXT", column), [])?;
+            }
+        }
+        if !self.has_column("symbol", "flags")? {
+            self.conn.execute("ALTER TABLE symbol ADD COLUMN flags INTEGER NOT NULL DEFAULT 0", [])?;
+        }
+        if !self.has_column("file", "encoding")? {
+            self.conn.execute("ALTER TABLE file ADD COLUMN encoding TEXT", [])?;
+        }
+        // Per-row version triggers doubled the writes of every scan
+        for trigger in ["edge_version_insert", "edge_version_update", "edge_version_delete", "symbol_version_insert", "symbol_version_delete"] {
+            self.conn.execute(&format!("DROP TRIGGER IF EXISTS {trigger}"), [])?;
+        }
+        
+        let version: i64 = self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version < 1 {
+            // Symbol lang/kind/visibility used to be stored JSON-quoted
+            // ("\"public\""); unquote them so plain SQL predicates work
+            for column in ["lang", "kind", "visibility", "lang_version"] {
+                self.conn.execute(
+                    &format!(
+                        "UPDATE symbol SET {column} = json_extract({column}, '$')
+                         WHERE {column} LIKE '\"%' AND json_valid({column})"
+                    ),
+                    [],
+                )?;
+            }
+            self.conn.execute("PRAGMA user_version = 1", [])?;
+        }
+        if version < 2 {
+            // Occurrences written before the token index existed
+            self.conn.execute("INSERT INTO occurrence_fts(occurrence_fts) VALUES ('rebuild')", [])?;
+            self.conn.execute("PRAGMA user_version = 2", [])?;
+        }
+        Ok(())
+    }
+
+    /// Mark saved adjacency files stale. Called once by each batch of writes
+    /// to symbols or edges rather than per row, which would double the
+    /// writes of a scan; single inserts outside a batch are caught by the
+    /// row counts in the adjacency key.
+    fn bump_graph_version(&self) -> Result<()> {
+        self.conn.execute("UPDATE graph_version SET version = version + 1", [])?;
+        Ok(())
+    }
+
+    fn has_column(&self, table: &str, column: &str) -> Result<bool> {
+        let mut stmt = self.conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let columns = stmt.query_map([], |row| row.get::<_, String>(1))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(columns.iter().any(|c| c == column))
+    }
+
+    /// Write one snapshot as canonical JSON lines.
+    ///
+    /// Rows are sorted on all of their columns and carry no row ids or
+    /// timestamps, so two scans of the same tree export byte-identical output.
+    pub fn export_jsonl<W: std::io::Write>(&self, commit_sha: &str, out: &mut W) -> Result<()> {
+        let commit_id = self.snapshot_id(commit_sha)?;
+        
+        for (table, columns, json_columns) in EXPORT_TABLES {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {columns} FROM {table} WHERE commit_id = ?1 ORDER BY {columns}"
+            ))?;
+            let names: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+            let mut rows = stmt.query(params![commit_id])?;
+            
+            while let Some(row) = rows.next()? {
+                let mut record = serde_json::Map::new();
+                record.insert("table".to_string(), serde_json::json!(table));
+                for (i, name) in names.iter().enumerate() {
+                    let value = match row.get_ref(i)? {
+                        rusqlite::types::ValueRef::Null => serde_json::Value::Null,
+                        rusqlite::types::ValueRef::Integer(n) => serde_json::json!(n),
+                        rusqlite::types::ValueRef::Real(f) => serde_json::json!(f),
+                        rusqlite::types::ValueRef::Text(t) => {
+                            let text = String::from_utf8_lossy(t);
+                            if json_columns.contains(&name.as_str()) {
+                                serde_json::from_str(&text)?
+                            } else {
+                                serde_json::json!(text)
+                            }
+                        }
+                        rusqlite::types::ValueRef::Blob(_) => continue,
+                    };
+                    record.insert(name.clone(), value);
+                }
+                writeln!(out, "{}", serde_json::Value::Object(record))?;
+            }
+        }
+        
+        Ok(())
+    }
+
+    pub fn has_snapshot(&self, commit_sha: &str) -> Result<bool> {
+        Ok(self.snapshot_id(commit_sha).is_ok())
+    }
+
+    /// Row id of an existing snapshot
+    fn snapshot_id(&self, commit_sha: &str) -> Result<i64> {
+        self.conn.query_row(
+            "SELECT id FROM commit_snapshot WHERE commit_sha = ?1",
+            params![commit_sha],
+            |row| row.get(0),
+        ).optional()?
+        .ok_or_else(|| anyhow::anyhow!("No snapshot for commit {}", commit_sha))
+    }
+
+    /// Every symbol recorded for `commit_sha`
+    pub fn get_snapshot_symbols(&self, commit_sha: &str) -> Result<Vec<SymbolIR>> {
+        let commit_id = self.snapshot_id(commit_sha)?;
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {SYMBOL_COLUMNS} FROM symbol s WHERE s.commit_id = ?1 ORDER BY s.id"
+        ))?;
+        let symbols = stmt.query_map(params![commit_id], symbol_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(symbols)
+    }
+
+    /// Every edge recorded for `commit_sha`
+    pub fn get_snapshot_edges(&self, commit_sha: &str) -> Result<Vec<EdgeIR>> {
+        let commit_id = self.snapshot_id(commit_sha)?;
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT edge_type, src_symbol, dst_symbol, file_src, file_dst, resolution, meta, provenance
+               FROM edge
+               WHERE commit_id = ?1
+               ORDER BY id"#
+        )?;
+        let edges = stmt.query_map(params![commit_id], edge_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(self.noise.filter_edges(edges))
+    }
+
+    /// Every complete snapshot, oldest scan first
+    pub fn snapshot_commits(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT commit_sha FROM commit_snapshot
+               WHERE id NOT IN (SELECT commit_id FROM scan_checkpoint)
+               ORDER BY timestamp, id"#
+        )?;
+        let commits = stmt.query_map([], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(commits)
+    }
+
+    /// How many times each symbol's `sig_hash` changed over `commits`, given
+    /// in history order; commits without a snapshot are skipped. A symbol
+    /// missing from a snapshot (an incremental scan that didn't touch its
+    /// file) is compared at its next appearance, so only real changes count.
+    pub fn symbol_churn(&self, commits: &[String]) -> Result<HashMap<String, usize>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT symbol_id, sig_hash FROM symbol WHERE commit_id = ?1"
+        )?;
+        let mut last_hash: HashMap<String, String> = HashMap::new();
+        let mut changes: HashMap<String, usize> = HashMap::new();
+        for commit in commits {
+            let Ok(commit_id) = self.snapshot_id(commit) else {
+                continue;
+            };
+            let rows = stmt.query_map(params![commit_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+            for row in rows {
+                let (symbol_id, sig_hash) = row?;
+                match last_hash.get_mut(&symbol_id) {
+                    Some(last) if *last != sig_hash => {
+                        *changes.entry(symbol_id).or_default() += 1;
+                        *last = sig_hash;
+                    }
+                    Some(_) => {}
+                    None => {
+                        changes.entry(symbol_id.clone()).or_default();
+                        last_hash.insert(symbol_id, sig_hash);
+                    }
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    /// The graph of `commit_sha` rolled up to files, directories or packages
+    pub fn aggregate_graph(&self, commit_sha: &str, granularity: Granularity) -> Result<AggregateGraph> {
+        let symbols = self.get_snapshot_symbols(commit_sha)?;
+        let edges = self.get_snapshot_edges(commit_sha)?;
+        Ok(AggregateGraph::build(&symbols, &edges, granularity))
+    }
+
     pub fn get_or_create_commit(&self, commit_sha: &str) -> Result<i64> {
         // First, try to get existing commit
         if let Some(id) = self.conn.query_row(
@@ -178,118 +939,312 @@ impl GraphStore {
         
         Ok(self.conn.last_insert_rowid())
     }
-    
+
     pub fn insert_file(&self, commit_id: i64, path: &str, content_hash: &str, size: usize) -> Result<()> {
-        self.conn.execute(
+        let path = normalize_path(path);
+        self.conn.prepare_cached(
             "INSERT OR REPLACE INTO file (commit_id, path, content_hash, size_bytes) 
              VALUES (?1, ?2, ?3, ?4)",
-            params![commit_id, path, content_hash, size as i64],
+        )?
+        .execute(params![commit_id, path, content_hash, size as i64])?;
+        Ok(())
+    }
+
+    /// Record the encoding a file was decoded from (e.g. `Shift_JIS`)
+    pub fn set_file_encoding(&self, commit_id: i64, path: &str, encoding: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE file SET encoding = ?3 WHERE commit_id = ?1 AND path = ?2",
+            params![commit_id, normalize_path(path), encoding],
         )?;
         Ok(())
     }
-    
-    pub fn insert_symbol(&self, commit_id: i64, symbol: &SymbolIR) -> Result<()> {
-        let lang_str = serde_json::to_string(&symbol.lang)?;
-        let kind_str = serde_json::to_string(&symbol.kind)?;
-        let visibility_str = symbol.visibility.as_ref().map(serde_json::to_string).transpose()?;
+
+    /// Record the summary of a file, replacing the one of an earlier scan
+    pub fn insert_file_summary(&self, commit_id: i64, summary: &FileSummary) -> Result<()> {
+        self.conn.prepare_cached(
+            "INSERT OR REPLACE INTO file_summary
+             (commit_id, path, lang, symbols, symbols_by_kind, imports, quality, complexity)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?
+        .execute(params![
+            commit_id,
+            normalize_path(&summary.path),
+            summary.language,
+            summary.symbols as i64,
+            serde_json::to_string(&summary.symbols_by_kind)?,
+            summary.imports as i64,
+            enum_to_text(&summary.quality)?,
+            summary.complexity as i64,
+        ])?;
+        Ok(())
+    }
+
+    /// Summaries of the files of `commit_sha` under the directory `prefix`
+    /// (all of them without one), by path
+    pub fn get_file_summaries(&self, commit_sha: &str, prefix: Option<&str>) -> Result<Vec<FileSummary>> {
+        let commit_id = self.snapshot_id(commit_sha)?;
+        let prefix = prefix.map(|p| normalize_path(p.trim_end_matches('/')));
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT path, lang, symbols, symbols_by_kind, imports, quality, complexity
+             FROM file_summary
+             WHERE commit_id = ?1 AND (?2 IS NULL OR {})
+             ORDER BY path",
+            under_sql("path", "?2")
+        ))?;
+        let summaries = stmt
+            .query_map(params![commit_id, prefix], |row| {
+                Ok(FileSummary {
+                    path: row.get(0)?,
+                    language: row.get(1)?,
+                    symbols: row.get::<_, i64>(2)? as usize,
+                    symbols_by_kind: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                    imports: row.get::<_, i64>(4)? as usize,
+                    quality: enum_from_text(&row.get::<_, String>(5)?).unwrap_or(ParseQuality::Syntactic),
+                    complexity: row.get::<_, i64>(6)? as usize,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(summaries)
+    }
+
+    /// File summaries of `commit_sha` under `prefix` added up per directory,
+    /// cut to `depth` components when set
+    pub fn directory_summaries(
+        &self,
+        commit_sha: &str,
+        prefix: Option<&str>,
+        depth: Option<usize>,
+    ) -> Result<Vec<DirectorySummary>> {
+        Ok(summary::by_directory(&self.get_file_summaries(commit_sha, prefix)?, depth))
+    }
+
+    /// Record the architecture layer of symbols as `(symbol id, layer)`;
+    /// symbols of the snapshot not listed are left without one
+    pub fn set_symbol_layers(&self, commit_id: i64, layers: &[(String, String)]) -> Result<()> {
+        self.conn.execute("UPDATE symbol SET layer = NULL WHERE commit_id = ?1", params![commit_id])?;
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE symbol SET layer = ?3 WHERE commit_id = ?1 AND symbol_id = ?2"
+        )?;
+        for (symbol_id, layer) in layers {
+            stmt.execute(params![commit_id, symbol_id, layer])?;
+        }
+        Ok(())
+    }
+
+    /// Record symbols the program is entered through as `(symbol id, kind)`;
+    /// symbols of the snapshot not listed are left untagged
+    pub fn set_symbol_entry_points(&self, commit_id: i64, entry_points: &[(String, String)]) -> Result<()> {
+        self.conn.execute("UPDATE symbol SET entry_point = NULL WHERE commit_id = ?1", params![commit_id])?;
+        let mut stmt = self.conn.prepare_cached(
+            "UPDATE symbol SET entry_point = ?3 WHERE commit_id = ?1 AND symbol_id = ?2"
+        )?;
+        for (symbol_id, kind) in entry_points {
+            stmt.execute(params![commit_id, symbol_id, kind])?;
+        }
+        Ok(())
+    }
+
+    /// Replace the `PublishesTo`/`SubscribesTo` edges made from `files`.
+    /// They are derived once the files are written, since topics may be
+    /// constants from elsewhere; other files' event edges are kept, so a
+    /// partial scan only recomputes the files it indexed.
+    pub fn set_event_edges(&self, commit_id: i64, files: &[String], edges: &[EdgeIR]) -> Result<()> {
+        let mut delete = self.conn.prepare_cached(
+            r#"DELETE FROM edge
+               WHERE commit_id = ?1 AND file_src = ?2 AND edge_type IN ('"PublishesTo"', '"SubscribesTo"')"#,
+        )?;
+        for file in files {
+            delete.execute(params![commit_id, normalize_path(file)])?;
+        }
+        for edge in edges {
+            self.insert_edge(commit_id, edge)?;
+        }
+        self.bump_graph_version()
+    }
+
+    /// Kind of every entry point of `commit_sha` by symbol id
+    pub fn get_entry_points(&self, commit_sha: &str) -> Result<HashMap<String, String>> {
+        let commit_id = self.snapshot_id(commit_sha)?;
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT symbol_id, entry_point FROM symbol WHERE commit_id = ?1 AND entry_point IS NOT NULL"
+        )?;
+        let entry_points = stmt.query_map(params![commit_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+        Ok(entry_points)
+    }
+
+    pub fn get_file_encoding(&self, commit_sha: &str, file_path: &str) -> Result<Option<String>> {
+        let encoding = self.conn.query_row(
+            r#"SELECT f.encoding 
+               FROM file f
+               JOIN commit_snapshot c ON f.commit_id = c.id
+               WHERE c.commit_sha = ?1 AND f.path = ?2"#,
+            params![commit_sha, normalize_path(file_path)],
+            |row| row.get::<_, Option<String>>(0),
+        ).optional()?;
         
-        self.conn.execute(
+        Ok(encoding.flatten())
+    }
+
+    pub fn insert_symbol(&self, commit_id: i64, symbol: &SymbolIR) -> Result<()> {
+        let lang_str = enum_to_text(&symbol.lang)?;
+        let kind_str = enum_to_text(&symbol.kind)?;
+        let lang_version_str = symbol.lang_version.as_ref().map(enum_to_text).transpose()?;
+        let parameters_str = (!symbol.parameters.is_empty())
+            .then(|| serde_json::to_string(&symbol.parameters))
+            .transpose()?;
+        let configs_str = (!symbol.configs.is_empty())
+            .then(|| serde_json::to_string(&symbol.configs))
+            .transpose()?;
+        let doc_tags_str = symbol.doc_tags.as_ref().map(serde_json::to_string).transpose()?;
+        
+        self.conn.prepare_cached(
             r#"INSERT OR REPLACE INTO symbol 
             (commit_id, symbol_id, lang, kind, name, fqn, signature, 
              file_path, span_start_line, span_start_col, span_end_line, 
-             span_end_col, visibility, doc, sig_hash)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)"#,
-            params![
-                commit_id,
-                symbol.id,
-                lang_str,
-                kind_str,
-                symbol.name,
-                symbol.fqn,
-                symbol.signature,
-                symbol.file_path,
-                symbol.span.start_line,
-                symbol.span.start_col,
-                symbol.span.end_line,
-                symbol.span.end_col,
-                visibility_str,
-                symbol.doc,
-                symbol.sig_hash,
-            ],
-        )?;
+             span_end_col, visibility, doc, sig_hash, lang_version,
+             name_start_line, name_start_col, name_end_line, name_end_col, parameters, return_type,
+             flags, configs, doc_tags)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20,
+                    ?21, ?22, ?23, ?24, ?25)"#,
+        )?
+        .execute(params![
+            commit_id,
+            symbol.id,
+            lang_str,
+            kind_str,
+            symbol.name,
+            symbol.fqn,
+            symbol.signature,
+            normalize_path(&symbol.file_path),
+            symbol.full_span.start_line,
+            symbol.full_span.start_col,
+            symbol.full_span.end_line,
+            symbol.full_span.end_col,
+            symbol.visibility,
+            symbol.doc,
+            symbol.sig_hash,
+            lang_version_str,
+            symbol.name_span.start_line,
+            symbol.name_span.start_col,
+            symbol.name_span.end_line,
+            symbol.name_span.end_col,
+            parameters_str,
+            symbol.return_type,
+            symbol.flags.bits(),
+            configs_str,
+            doc_tags_str,
+        ])?;
         
         Ok(())
     }
-    
+
     pub fn insert_edge(&self, commit_id: i64, edge: &EdgeIR) -> Result<()> {
         let edge_type_str = serde_json::to_string(&edge.edge_type)?;
         let resolution_str = serde_json::to_string(&edge.resolution)?;
+        let meta_str = (!edge.meta.is_empty()).then(|| to_sorted_json(&edge.meta)).transpose()?;
+        let provenance_str = (!edge.provenance.is_empty()).then(|| to_sorted_json(&edge.provenance)).transpose()?;
         
-        self.conn.execute(
+        self.conn.prepare_cached(
             r#"INSERT INTO edge 
-            (commit_id, edge_type, src_symbol, dst_symbol, file_src, file_dst, resolution)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
-            params![
-                commit_id,
-                edge_type_str,
-                edge.src,
-                edge.dst,
-                edge.file_src,
-                edge.file_dst,
-                resolution_str,
-            ],
-        )?;
+            (commit_id, edge_type, src_symbol, dst_symbol, file_src, file_dst, resolution, meta, provenance)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+        )?
+        .execute(params![
+            commit_id,
+            edge_type_str,
+            edge.src,
+            edge.dst,
+            edge.file_src.as_deref().map(normalize_path),
+            edge.file_dst.as_deref().map(normalize_path),
+            resolution_str,
+            meta_str,
+            provenance_str,
+        ])?;
+        
+        // Alias edges are also indexed by name so lookups can follow them
+        if edge.edge_type == EdgeType::Aliases {
+            if let Some(alias) = SymbolAlias::from_edge(edge) {
+                self.insert_alias(commit_id, &alias)?;
+            }
+        }
+        
+        Ok(())
+    }
+
+    pub fn insert_alias(&self, commit_id: i64, alias: &SymbolAlias) -> Result<()> {
+        self.conn.prepare_cached(
+            r#"INSERT OR REPLACE INTO symbol_alias
+            (commit_id, alias, alias_id, target, kind, file_path)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+        )?
+        .execute(params![
+            commit_id,
+            alias.alias,
+            alias.alias_id,
+            alias.target,
+            alias.kind,
+            alias.file_path,
+        ])?;
         
         Ok(())
     }
-    
+
     pub fn insert_occurrence(&self, commit_id: i64, occurrence: &OccurrenceIR) -> Result<()> {
         let role_str = serde_json::to_string(&occurrence.role)?;
         
-        self.conn.execute(
+        self.conn.prepare_cached(
             r#"INSERT INTO occurrence 
             (commit_id, file_path, symbol_id, role, span_start_line, 
              span_start_col, span_end_line, span_end_col, token)
             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
-            params![
-                commit_id,
-                occurrence.file_path,
-                occurrence.symbol_id,
-                role_str,
-                occurrence.span.start_line,
-                occurrence.span.start_col,
-                occurrence.span.end_line,
-                occurrence.span.end_col,
-                occurrence.token,
-            ],
-        )?;
+        )?
+        .execute(params![
+            commit_id,
+            normalize_path(&occurrence.file_path),
+            occurrence.symbol_id,
+            role_str,
+            occurrence.span.start_line,
+            occurrence.span.start_col,
+            occurrence.span.end_line,
+            occurrence.span.end_col,
+            occurrence.token,
+        ])?;
         
         Ok(())
     }
-    
+
     pub fn get_latest_commit(&self) -> Result<Option<String>> {
         let commit = self.conn.query_row(
-            "SELECT commit_sha FROM commit_snapshot ORDER BY timestamp DESC LIMIT 1",
+            r#"SELECT commit_sha FROM commit_snapshot
+               WHERE id NOT IN (SELECT commit_id FROM scan_checkpoint)
+               ORDER BY timestamp DESC LIMIT 1"#,
             [],
             |row| row.get::<_, String>(0),
         ).optional()?;
         
         Ok(commit)
     }
-    
+
+    /// Row id of the snapshot [`get_latest_commit`](Self::get_latest_commit) names
+    fn latest_snapshot_id(&self) -> Result<Option<i64>> {
+        self.get_latest_commit()?.map(|commit| self.snapshot_id(&commit)).transpose()
+    }
+
     pub fn get_file_hash(&self, commit_sha: &str, file_path: &str) -> Result<Option<String>> {
-        let hash = self.conn.query_row(
+        let file_path = normalize_path(file_path);
+        let hash = self.conn.prepare_cached(
             r#"SELECT f.content_hash 
                FROM file f
                JOIN commit_snapshot c ON f.commit_id = c.id
-               WHERE c.commit_sha = ?1 AND f.path = ?2"#,
-            params![commit_sha, file_path],
-            |row| row.get::<_, String>(0),
-        ).optional()?;
+               WHERE c.commit_sha = ?1 AND f.path = ?2"#
+        )?
+        .query_row(params![commit_sha, file_path], |row| row.get::<_, String>(0))
+        .optional()?;
         
         Ok(hash)
     }
-    
+
     pub fn get_files_in_commit(&self, commit_sha: &str) -> Result<Vec<(String, String)>> {
         let mut stmt = self.conn.prepare(
             r#"SELECT f.path, f.content_hash
@@ -305,8 +1260,10 @@ impl GraphStore {
         
         Ok(files)
     }
-    
+
+    #[tracing::instrument(skip(self))]
     pub fn clear_file_data(&self, commit_id: i64, file_path: &str) -> Result<()> {
+        let file_path = normalize_path(file_path);
         // Delete symbols
         self.conn.execute(
             "DELETE FROM symbol WHERE commit_id = ?1 AND file_path = ?2",
@@ -325,10 +1282,155 @@ impl GraphStore {
             params![commit_id, file_path],
         )?;
         
-        Ok(())
+        // Delete aliases declared in this file
+        self.conn.execute(
+            "DELETE FROM symbol_alias WHERE commit_id = ?1 AND file_path = ?2",
+            params![commit_id, file_path],
+        )?;
+        
+        self.conn.execute(
+            "DELETE FROM file_summary WHERE commit_id = ?1 AND path = ?2",
+            params![commit_id, file_path],
+        )?;
+        
+        self.bump_graph_version()
+    }
+
+    /// Remove what snapshot `commit_id` holds for the files under the
+    /// directory `prefix`, ahead of rescanning just that subtree, and return
+    /// how many files it had there. Unlike
+    /// [`clear_file_data`](Self::clear_file_data), edges into the subtree
+    /// from files outside it stay, since those files aren't rescanned;
+    /// [`mark_dangling_edges`](Self::mark_dangling_edges) checks them after.
+    #[tracing::instrument(skip(self))]
+    pub fn clear_subtree_data(&self, commit_id: i64, prefix: &str) -> Result<usize> {
+        let prefix = normalize_path(prefix.trim_end_matches('/'));
+        for (table, column) in [("symbol", "file_path"), ("occurrence", "file_path"), ("edge", "file_src"), ("symbol_alias", "file_path"), ("file_summary", "path")] {
+            self.conn.execute(
+                &format!("DELETE FROM {table} WHERE commit_id = ?1 AND {}", under_sql(column, "?2")),
+                params![commit_id, prefix],
+            )?;
+        }
+        let files = self.conn.execute(
+            &format!("DELETE FROM file WHERE commit_id = ?1 AND {}", under_sql("path", "?2")),
+            params![commit_id, prefix],
+        )?;
+        self.bump_graph_version()?;
+        Ok(files)
     }
-    
+
+    /// After a rescan of the subtree `prefix`, flag the edges crossing its
+    /// boundary that lead nowhere with `"dangling": true` in their meta: their
+    /// target file isn't in the snapshot (never scanned, or deleted), or the
+    /// symbol they name is gone from it. Crossing edges that resolve again
+    /// lose the flag. Returns how many are dangling.
+    #[tracing::instrument(skip(self))]
+    pub fn mark_dangling_edges(&self, commit_id: i64, prefix: &str) -> Result<usize> {
+        let prefix = normalize_path(prefix.trim_end_matches('/'));
+        let crossing = format!(
+            "commit_id = ?1 AND file_src IS NOT NULL AND file_dst IS NOT NULL AND {} != {}",
+            under_sql("file_src", "?2"),
+            under_sql("file_dst", "?2")
+        );
+        self.conn.execute(
+            &format!(
+                "UPDATE edge SET meta = json_remove(meta, '$.dangling')
+                 WHERE {crossing} AND json_extract(meta, '$.dangling') IS NOT NULL"
+            ),
+            params![commit_id, prefix],
+        )?;
+        let dangling = self.conn.execute(
+            &format!(
+                r#"UPDATE edge SET meta = json_set(COALESCE(meta, '{{}}'), '$.dangling', json('true'))
+                   WHERE {crossing}
+                     AND (
+                         NOT EXISTS (SELECT 1 FROM file f WHERE f.commit_id = edge.commit_id AND f.path = edge.file_dst)
+                         -- A symbol id, `file#name`, in the target file
+                         OR (substr(dst_symbol, 1, length(file_dst) + 1) = file_dst || '#'
+                             AND NOT EXISTS (
+                                 SELECT 1 FROM symbol s
+                                 WHERE s.commit_id = edge.commit_id AND s.symbol_id = edge.dst_symbol
+                             ))
+                     )"#
+            ),
+            params![commit_id, prefix],
+        )?;
+        Ok(dangling)
+    }
+
+    #[tracing::instrument(skip(self))]
     pub fn build_graph(&self) -> Result<CodeGraph> {
+        Ok(self.graph()?.to_graph())
+    }
+
+    /// The adjacency file, mapped, when it is current; else the graph is
+    /// built from the database and saved for the next run
+    fn load_graph(&self) -> Result<LoadedGraph> {
+        let Some((path, key)) = self.adjacency_file()? else {
+            return Ok(LoadedGraph::Built(self.graph_from_rows()?));
+        };
+        match Adjacency::read(&path, &key) {
+            Ok(Some(adjacency)) => return Ok(LoadedGraph::Mapped(adjacency)),
+            Ok(None) => {}
+            Err(e) => warn!("Rebuilding the graph: {:#}", e),
+        }
+        let graph = self.graph_from_rows()?;
+        if let Err(e) = Adjacency::write(&graph, &path, &key) {
+            warn!("Could not save adjacency file {}: {:#}", path.display(), e);
+            return Ok(LoadedGraph::Built(graph));
+        }
+        Self::remove_stale_adjacency_files(&path);
+        Ok(LoadedGraph::Built(graph))
+    }
+
+    /// Other commits' adjacency files. Writing one means a write batch has
+    /// moved the graph version since they were saved, so none of them can
+    /// match again.
+    fn remove_stale_adjacency_files(current: &Path) {
+        let Some(Ok(entries)) = current.parent().map(std::fs::read_dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if path != current && name.starts_with("graph-") && name.ends_with(".csr") {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    debug!("Could not remove stale adjacency file {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    /// Where the latest commit's adjacency file goes, and the key telling
+    /// whether one found there still matches the database. The graph spans
+    /// every snapshot (an incremental scan's snapshot only holds the files
+    /// it reprocessed), so the file is rewritten whenever the rows or the
+    /// noise filter change. Rows written one at a time outside a write batch
+    /// (`insert_symbol` after a snapshot is published) don't bump the graph
+    /// version, so the key also carries each table's row count and last
+    /// rowid, which every insert moves.
+    fn adjacency_file(&self) -> Result<Option<(PathBuf, String)>> {
+        let Some(dir) = self.db_path.parent().filter(|dir| self.adjacency_cache && dir.is_dir()) else {
+            return Ok(None);
+        };
+        let Some(commit) = self.get_latest_commit()? else {
+            return Ok(None);
+        };
+        let rows: [i64; 5] = self.conn.query_row(
+            r#"SELECT (SELECT version FROM graph_version),
+                      (SELECT COUNT(*) FROM symbol), (SELECT COALESCE(MAX(id), 0) FROM symbol),
+                      (SELECT COUNT(*) FROM edge), (SELECT COALESCE(MAX(id), 0) FROM edge)"#,
+            [],
+            |row| Ok([row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?]),
+        )?;
+        let rows = rows.map(|n| n.to_string()).join(":");
+        let key = format!("{}:{}:{}", commit, rows, self.noise.fingerprint());
+        let file_name: String = commit.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+        Ok(Some((dir.join(format!("graph-{}.csr", file_name)), key)))
+    }
+
+    fn graph_from_rows(&self) -> Result<CodeGraph> {
         // Get all symbols
         let mut stmt = self.conn.prepare(
             "SELECT symbol_id, name, kind FROM symbol"
@@ -345,17 +1447,14 @@ impl GraphStore {
         
         // Get all edges
         let mut stmt = self.conn.prepare(
-            "SELECT edge_type, src_symbol, dst_symbol FROM edge WHERE src_symbol IS NOT NULL AND dst_symbol IS NOT NULL"
+            r#"SELECT edge_type, src_symbol, dst_symbol, file_src, file_dst, resolution, meta, provenance
+               FROM edge
+               WHERE src_symbol IS NOT NULL AND dst_symbol IS NOT NULL"#
         )?;
         
-        let edges: Vec<(String, String, String)> = stmt.query_map([], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-            ))
-        })?
-        .collect::<std::result::Result<Vec<_>, _>>()?;
+        let edges = stmt.query_map([], edge_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let edges = self.noise.filter_edges(edges);
         
         // Build the graph
         let mut graph = CodeGraph::new();
@@ -366,96 +1465,52 @@ impl GraphStore {
         }
         
         // Add edges
-        for (edge_type_str, src, dst) in edges {
-            let edge_type: EdgeType = serde_json::from_str(&edge_type_str)?;
-            graph.add_edge(&src, &dst, edge_type);
+        for edge in edges {
+            if let (Some(src), Some(dst)) = (&edge.src, &edge.dst) {
+                graph.add_edge(src, dst, edge.edge_type);
+            }
         }
         
         Ok(graph)
     }
-    
+
     pub fn get_symbol(&self, symbol_id: &str) -> Result<Option<SymbolIR>> {
-        let symbol = self.conn.query_row(
-            r#"SELECT symbol_id, lang, kind, name, fqn, signature, file_path,
-                     span_start_line, span_start_col, span_end_line, span_end_col,
-                     visibility, doc, sig_hash
-               FROM symbol 
-               WHERE symbol_id = ?1
-               LIMIT 1"#,
-            params![symbol_id],
-            |row| {
-                Ok(SymbolIR {
-                    id: row.get(0)?,
-                    lang: serde_json::from_str(&row.get::<_, String>(1)?).unwrap_or(Language::Unknown),
-                    lang_version: None,
-                    kind: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or(SymbolKind::Variable),
-                    name: row.get(3)?,
-                    fqn: row.get(4)?,
-                    signature: row.get(5)?,
-                    file_path: row.get(6)?,
-                    span: Span {
-                        start_line: row.get(7)?,
-                        start_col: row.get(8)?,
-                        end_line: row.get(9)?,
-                        end_col: row.get(10)?,
-                    },
-                    visibility: row.get::<_, Option<String>>(11)?
-                        .and_then(|v| serde_json::from_str(&v).ok()),
-                    doc: row.get(12)?,
-                    sig_hash: row.get(13)?,
-                })
-            }
-        ).optional()?;
+        let symbol = self.conn.prepare_cached(&format!(
+            r#"SELECT {SYMBOL_COLUMNS}
+               FROM symbol s
+               WHERE s.symbol_id = ?1
+               LIMIT 1"#
+        ))?
+        .query_row(params![symbol_id], symbol_from_row)
+        .optional()?;
         
         Ok(symbol)
     }
-    
+
     pub fn get_edges(&self, symbol_id: &str) -> Result<Vec<EdgeIR>> {
         let mut edges = Vec::new();
         
         // Get outgoing edges
-        let mut stmt = self.conn.prepare(
-            r#"SELECT edge_type, src_symbol, dst_symbol, file_src, file_dst, resolution
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT edge_type, src_symbol, dst_symbol, file_src, file_dst, resolution, meta, provenance
                FROM edge 
                WHERE src_symbol = ?1"#
         )?;
         
-        let edge_iter = stmt.query_map(params![symbol_id], |row| {
-            Ok(EdgeIR {
-                edge_type: serde_json::from_str(&row.get::<_, String>(0)?).unwrap_or(EdgeType::Contains),
-                src: row.get(1)?,
-                dst: row.get(2)?,
-                file_src: row.get(3)?,
-                file_dst: row.get(4)?,
-                resolution: serde_json::from_str(&row.get::<_, String>(5)?).unwrap_or(protocol::Resolution::Syntactic),
-                meta: std::collections::HashMap::new(),
-                provenance: std::collections::HashMap::new(),
-            })
-        })?;
+        let edge_iter = stmt.query_map(params![symbol_id], edge_from_row)?;
         
         for edge in edge_iter {
             edges.push(edge?);
         }
         
         // Get incoming edges
-        let mut stmt = self.conn.prepare(
-            r#"SELECT edge_type, src_symbol, dst_symbol, file_src, file_dst, resolution
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT edge_type, src_symbol, dst_symbol, file_src, file_dst, resolution, meta, provenance
                FROM edge 
                WHERE dst_symbol = ?1"#
         )?;
         
-        let edge_iter = stmt.query_map(params![symbol_id], |row| {
-            Ok(EdgeIR {
-                edge_type: serde_json::from_str(&row.get::<_, String>(0)?).unwrap_or(EdgeType::Contains),
-                src: row.get(1)?,
-                dst: row.get(2)?,
-                file_src: row.get(3)?,
-                file_dst: row.get(4)?,
-                resolution: serde_json::from_str(&row.get::<_, String>(5)?).unwrap_or(protocol::Resolution::Syntactic),
-                meta: std::collections::HashMap::new(),
-                provenance: std::collections::HashMap::new(),
-            })
-        })?;
+        let edge_iter = stmt.query_map(params![symbol_id], edge_from_row)?;
         
         for edge in edge_iter {
             edges.push(edge?);
@@ -463,86 +1518,52 @@ impl GraphStore {
         
         Ok(edges)
     }
-    
+
+    /// The symbol with this FQN, preferring an implementation over a
+    /// `.d.ts` declaration that shares it
     pub fn get_symbol_by_fqn(&self, fqn: &str) -> Result<Option<SymbolIR>> {
-        let symbol = self.conn.query_row(
-            r#"SELECT symbol_id, lang, kind, name, fqn, signature, file_path,
-                     span_start_line, span_start_col, span_end_line, span_end_col,
-                     visibility, doc, sig_hash
-               FROM symbol 
-               WHERE fqn = ?1
-               ORDER BY id DESC
-               LIMIT 1"#,
-            params![fqn],
-            |row| {
-                Ok(SymbolIR {
-                    id: row.get(0)?,
-                    lang: serde_json::from_str(&row.get::<_, String>(1)?).unwrap_or(Language::Unknown),
-                    lang_version: None,
-                    kind: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or(SymbolKind::Variable),
-                    name: row.get(3)?,
-                    fqn: row.get(4)?,
-                    signature: row.get(5)?,
-                    file_path: row.get(6)?,
-                    span: Span {
-                        start_line: row.get(7)?,
-                        start_col: row.get(8)?,
-                        end_line: row.get(9)?,
-                        end_col: row.get(10)?,
-                    },
-                    visibility: row.get::<_, Option<String>>(11)?
-                        .and_then(|v| serde_json::from_str(&v).ok()),
-                    doc: row.get(12)?,
-                    sig_hash: row.get(13)?,
-                })
-            }
-        ).optional()?;
+        let declaration = SymbolFlags::DECLARATION.bits();
+        let symbol = self.conn.prepare_cached(&format!(
+            r#"SELECT {SYMBOL_COLUMNS}
+               FROM symbol s
+               WHERE s.fqn = ?1
+               ORDER BY (s.flags & {declaration}) != 0, s.id DESC
+               LIMIT 1"#
+        ))?
+        .query_row(params![fqn], symbol_from_row)
+        .optional()?;
         
         Ok(symbol)
     }
 
     /// Search symbols using FTS5 full-text search for fast fuzzy matching
     pub fn search_symbols_fts(&self, query: &str, limit: usize) -> Result<Vec<SymbolIR>> {
+        self.search_fts(query, limit, &SearchFilter::default())
+    }
+
+    fn search_fts(&self, query: &str, limit: usize, filter: &SearchFilter) -> Result<Vec<SymbolIR>> {
         let mut symbols = Vec::new();
+        let (filter_sql, filter_values) = filter.to_sql(3)?;
+        // Generated code sorts after hand-written matches
+        let generated = SymbolFlags::GENERATED.bits();
         
         // Use FTS5 MATCH for fast full-text searching with ranking
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(&format!(
             r#"
-            SELECT s.symbol_id, s.lang, s.kind, s.name, s.fqn, s.signature, s.file_path,
-                   s.span_start_line, s.span_start_col, s.span_end_line, s.span_end_col,
-                   s.visibility, s.doc, s.sig_hash
+            SELECT {SYMBOL_COLUMNS}
             FROM symbol_fts
             JOIN symbol s ON symbol_fts.rowid = s.id
-            WHERE symbol_fts MATCH ?1
-            ORDER BY rank
+            WHERE symbol_fts MATCH ?1{filter_sql}
+            ORDER BY (s.flags & {generated}) != 0, rank
             LIMIT ?2
             "#,
-        )?;
+        ))?;
         
         // For FTS5, append * for prefix matching to find partial matches
         let fts_query = format!("{}*", query);
-        let symbol_iter = stmt.query_map(params![fts_query, limit], |row| {
-            Ok(SymbolIR {
-                id: row.get(0)?,
-                lang: serde_json::from_str(&row.get::<_, String>(1)?).unwrap_or(Language::Unknown),
-                lang_version: None,
-                kind: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or(SymbolKind::Variable),
-                name: row.get(3)?,
-                fqn: row.get(4)?,
-                signature: row.get(5)?,
-                file_path: row.get(6)?,
-                span: Span {
-                    start_line: row.get(7)?,
-                    start_col: row.get(8)?,
-                    end_line: row.get(9)?,
-                    end_col: row.get(10)?,
-                },
-                visibility: row.get::<_, Option<String>>(11)?
-                    .and_then(|v| serde_json::from_str(&v).ok()),
-                doc: row.get(12)?,
-                sig_hash: row.get(13)?,
-            })
-        })?;
+        let mut values: Vec<rusqlite::types::Value> = vec![fts_query.into(), (limit as i64).into()];
+        values.extend(filter_values);
+        let symbol_iter = stmt.query_map(rusqlite::params_from_iter(values), symbol_from_row)?;
         
         for symbol in symbol_iter {
             symbols.push(symbol?);
@@ -550,61 +1571,72 @@ impl GraphStore {
         
         Ok(symbols)
     }
-    
+
+    #[tracing::instrument(skip(self))]
     pub fn search_symbols(&self, query: &str, limit: usize) -> Result<Vec<SymbolIR>> {
+        self.search_symbols_filtered(query, limit, &SearchFilter::default())
+    }
+
+    /// [`search_symbols`](Self::search_symbols) restricted by `filter`
+    #[tracing::instrument(skip(self))]
+    pub fn search_symbols_filtered(&self, query: &str, limit: usize, filter: &SearchFilter) -> Result<Vec<SymbolIR>> {
+        self.cached_symbols(&format!("search:{}:{}:{:?}", query, limit, filter), || {
+            let symbols = self.search_symbols_direct(query, limit, filter)?;
+            self.append_alias_matches(query, limit, filter, symbols)
+        })
+    }
+
+    /// [`search_symbols_filtered`](Self::search_symbols_filtered) with each
+    /// logical symbol on one card, however many snapshots, declarations and
+    /// definitions it was found in
+    pub fn search_symbol_cards(&self, query: &str, limit: usize, filter: &SearchFilter) -> Result<Vec<SearchCard>> {
+        // Copies crowd out distinct symbols, so look past `limit` for them
+        let symbols = self.search_symbols_filtered(query, limit.saturating_mul(SEARCH_CARD_OVERFETCH), filter)?;
+        let mut cards = dedup::group(symbols);
+        cards.truncate(limit);
+        Ok(cards)
+    }
+
+    fn search_symbols_direct(&self, query: &str, limit: usize, filter: &SearchFilter) -> Result<Vec<SymbolIR>> {
         // Try FTS5 first for better performance
-        if let Ok(results) = self.search_symbols_fts(query, limit) {
+        if let Ok(results) = self.search_fts(query, limit, filter) {
             if !results.is_empty() {
                 return Ok(results);
             }
         }
         
         let mut symbols = Vec::new();
+        let (filter_sql, filter_values) = filter.to_sql(6)?;
+        let generated = SymbolFlags::GENERATED.bits();
         
-        // Fall back to LIKE search
+        // Fall back to LIKE search, on the FQN also with separators
+        // normalized so `Foo::bar` finds `Foo.bar`
         let pattern = format!("%{}%", query);
+        let normalized_pattern = format!("%{}%", self.separators.normalize(query));
+        let normalized_fqn = self.separators.normalize_sql("fqn");
         
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(&format!(
             r#"
-            SELECT symbol_id, lang, kind, name, fqn, signature, file_path,
-                   span_start_line, span_start_col, span_end_line, span_end_col,
-                   visibility, doc, sig_hash
-            FROM symbol 
-            WHERE name LIKE ?1 OR fqn LIKE ?1
+            SELECT {SYMBOL_COLUMNS}
+            FROM symbol s
+            WHERE (name LIKE ?1 OR fqn LIKE ?1 OR {normalized_fqn} LIKE ?5){filter_sql}
             ORDER BY 
+                (flags & {generated}) != 0,
                 CASE WHEN name = ?2 THEN 0
                      WHEN name LIKE ?3 THEN 1
                      ELSE 2 END,
                 length(name)
             LIMIT ?4
             "#,
-        )?;
+        ))?;
         
         let exact = query;
         let prefix = format!("{}%", query);
+        let mut values: Vec<rusqlite::types::Value> =
+            vec![pattern.into(), exact.to_string().into(), prefix.into(), (limit as i64).into(), normalized_pattern.into()];
+        values.extend(filter_values);
         
-        let symbol_iter = stmt.query_map(params![pattern, exact, prefix, limit], |row| {
-            Ok(SymbolIR {
-                id: row.get(0)?,
-                lang: serde_json::from_str(&row.get::<_, String>(1)?).unwrap_or(Language::Unknown),
-                lang_version: None,
-                kind: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or(SymbolKind::Variable),
-                name: row.get(3)?,
-                fqn: row.get(4)?,
-                signature: row.get(5)?,
-                file_path: row.get(6)?,
-                span: Span {
-                    start_line: row.get(7)?,
-                    start_col: row.get(8)?,
-                    end_line: row.get(9)?,
-                    end_col: row.get(10)?,
-                },
-                visibility: row.get::<_, Option<String>>(11)?
-                    .and_then(|v| serde_json::from_str(&v).ok()),
-                doc: row.get(12)?,
-                sig_hash: row.get(13)?,
-            })
-        })?;
+        let symbol_iter = stmt.query_map(rusqlite::params_from_iter(values), symbol_from_row)?;
         
         for symbol in symbol_iter {
             symbols.push(symbol?);
@@ -612,51 +1644,600 @@ impl GraphStore {
         
         Ok(symbols)
     }
-    
+
+    /// Add symbols reachable through an alias whose name matches the query
+    fn append_alias_matches(&self, query: &str, limit: usize, filter: &SearchFilter, mut symbols: Vec<SymbolIR>) -> Result<Vec<SymbolIR>> {
+        if symbols.len() >= limit {
+            return Ok(symbols);
+        }
+        let Some(commit_id) = self.latest_snapshot_id()? else {
+            return Ok(symbols);
+        };
+        
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT alias_id FROM symbol_alias WHERE commit_id = ?1 AND alias LIKE ?2 LIMIT ?3"
+        )?;
+        let alias_ids = stmt.query_map(params![commit_id, format!("%{}%", query), limit], |row| {
+            row.get::<_, String>(0)
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+        
+        for alias_id in alias_ids {
+            if symbols.len() >= limit {
+                break;
+            }
+            if let Some((symbol, _)) = self.resolve_alias_chain(commit_id, &alias_id)? {
+                if !symbols.iter().any(|s| s.id == symbol.id) && self.symbol_matches(&symbol.id, filter)? {
+                    symbols.push(symbol);
+                }
+            }
+        }
+        
+        Ok(symbols)
+    }
+
+    /// Whether the stored symbol `symbol_id` passes `filter`
+    fn symbol_matches(&self, symbol_id: &str, filter: &SearchFilter) -> Result<bool> {
+        if filter.is_empty() {
+            return Ok(true);
+        }
+        let (filter_sql, filter_values) = filter.to_sql(2)?;
+        let mut values: Vec<rusqlite::types::Value> = vec![symbol_id.to_string().into()];
+        values.extend(filter_values);
+        let found = self.conn.query_row(
+            &format!("SELECT 1 FROM symbol s WHERE s.symbol_id = ?1{filter_sql} LIMIT 1"),
+            rusqlite::params_from_iter(values),
+            |_| Ok(()),
+        ).optional()?;
+        Ok(found.is_some())
+    }
+
+    /// Get aliases a snapshot declares under the given name or alias id
+    pub fn get_aliases(&self, commit_id: i64, name: &str) -> Result<Vec<SymbolAlias>> {
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT alias, alias_id, target, kind, file_path
+               FROM symbol_alias
+               WHERE commit_id = ?1 AND (alias = ?2 OR alias_id = ?2)
+               ORDER BY id"#
+        )?;
+        
+        let aliases = stmt.query_map(params![commit_id, name], |row| {
+            Ok(SymbolAlias {
+                alias: row.get(0)?,
+                alias_id: row.get(1)?,
+                target: row.get(2)?,
+                kind: row.get(3)?,
+                file_path: row.get(4)?,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+        
+        Ok(aliases)
+    }
+
+    /// Follow aliases of the latest snapshot from `name` until a real symbol
+    /// definition is found
+    pub fn resolve_alias(&self, name: &str) -> Result<Option<SymbolIR>> {
+        let Some(commit_id) = self.latest_snapshot_id()? else {
+            return Ok(None);
+        };
+        Ok(self.resolve_alias_chain(commit_id, name)?.map(|(symbol, _)| symbol))
+    }
+
+    /// Follow a snapshot's aliases (renames, re-exports, barrel `export *`)
+    /// from `name`, returning the snapshot's defining symbol and the alias
+    /// ids traversed to reach it
+    #[tracing::instrument(skip(self))]
+    pub fn resolve_alias_chain(&self, commit_id: i64, name: &str) -> Result<Option<(SymbolIR, Vec<String>)>> {
+        let mut visited = HashSet::new();
+        let mut pending = vec![(name.to_string(), Vec::new())];
+        
+        while let Some((current, chain)) = pending.pop() {
+            if chain.len() >= MAX_ALIAS_DEPTH || !visited.insert(current.clone()) {
+                continue;
+            }
+            
+            let mut aliases = self.get_aliases(commit_id, &current)?;
+            // Exact alias ids win over short-name matches from unrelated files
+            if aliases.iter().any(|a| a.alias_id == current) {
+                aliases.retain(|a| a.alias_id == current);
+            }
+            if aliases.is_empty() {
+                aliases = self.wildcard_reexports(commit_id, &current)?;
+            }
+            
+            for alias in aliases {
+                let mut next_chain = chain.clone();
+                next_chain.push(alias.alias_id.clone());
+                
+                if let Some(symbol) = self.snapshot_symbol(commit_id, &alias.target)? {
+                    return Ok(Some((symbol, next_chain)));
+                }
+                pending.push((alias.target, next_chain));
+            }
+        }
+        
+        Ok(None)
+    }
+
+    /// Expand wildcard re-exports: `scope/name` is reachable through an alias
+    /// `scope/*` pointing at `other/*`, which makes it `other/name`
+    fn wildcard_reexports(&self, commit_id: i64, name: &str) -> Result<Vec<SymbolAlias>> {
+        let Some((idx, sep)) = ["::", "/", "."].iter()
+            .filter_map(|sep| name.rfind(sep).map(|idx| (idx, *sep)))
+            .max_by_key(|(idx, _)| *idx)
+        else {
+            return Ok(Vec::new());
+        };
+        let member = &name[idx + sep.len()..];
+        if member.is_empty() || member == "*" {
+            return Ok(Vec::new());
+        }
+        
+        let wildcard = format!("{}{}*", &name[..idx], sep);
+        let aliases = self.get_aliases(commit_id, &wildcard)?
+            .into_iter()
+            .filter(|a| a.alias_id == wildcard && a.target.ends_with('*'))
+            .map(|mut a| {
+                a.target = format!("{}{}", a.target.trim_end_matches('*'), member);
+                a
+            })
+            .collect();
+        
+        Ok(aliases)
+    }
+
+    /// The snapshot's symbol with id `target`, else the one with FQN
+    /// `target`, preferring an implementation over a declaration
+    fn snapshot_symbol(&self, commit_id: i64, target: &str) -> Result<Option<SymbolIR>> {
+        let declaration = SymbolFlags::DECLARATION.bits();
+        let symbol = self.conn.prepare_cached(&format!(
+            r#"SELECT {SYMBOL_COLUMNS}
+               FROM symbol s
+               WHERE s.commit_id = ?1 AND (s.symbol_id = ?2 OR s.fqn = ?2)
+               ORDER BY s.symbol_id = ?2 DESC, (s.flags & {declaration}) != 0, s.id DESC
+               LIMIT 1"#
+        ))?
+        .query_row(params![commit_id, target], symbol_from_row)
+        .optional()?;
+        
+        Ok(symbol)
+    }
+
+    /// Link each declaration (`.d.ts`, `declare`, overload signature) to
+    /// the implementations sharing its FQN with a `Declares` edge. Derived
+    /// from the whole snapshot, so earlier links are replaced.
+    #[tracing::instrument(skip(self))]
+    pub fn link_declarations(&self, commit_id: i64) -> Result<usize> {
+        let declaration = SymbolFlags::DECLARATION.bits();
+        let declares = serde_json::to_string(&EdgeType::Declares)?;
+        self.conn.execute("DELETE FROM edge WHERE commit_id = ?1 AND edge_type = ?2", params![commit_id, declares])?;
+        let linked = self.conn.execute(
+            &format!(
+                r#"INSERT INTO edge (commit_id, edge_type, src_symbol, dst_symbol, file_src, file_dst, resolution)
+                   SELECT d.commit_id, ?2, d.symbol_id, i.symbol_id, d.file_path, i.file_path, ?3
+                   FROM symbol d
+                   JOIN symbol i ON i.commit_id = d.commit_id AND i.fqn = d.fqn AND i.kind = d.kind
+                   WHERE d.commit_id = ?1
+                     AND (d.flags & {declaration}) != 0
+                     AND (i.flags & {declaration}) = 0"#
+            ),
+            params![commit_id, declares, serde_json::to_string(&protocol::Resolution::Syntactic)?],
+        )?;
+        self.bump_graph_version()?;
+        Ok(linked)
+    }
+
+    /// Point Java annotation usages at the `@interface` declaring them when
+    /// it is in the snapshot: `References` edges tagged `annotation` and
+    /// `@Name` occurrences, which harnesses name by the qualified name the
+    /// annotation most likely has. Linked edges become semantic.
+    #[tracing::instrument(skip(self))]
+    pub fn link_annotations(&self, commit_id: i64) -> Result<usize> {
+        let references = serde_json::to_string(&EdgeType::References)?;
+        let semantic = serde_json::to_string(&protocol::Resolution::Semantic)?;
+        let mut stmt = self.conn.prepare(
+            r#"SELECT e.id, e.dst_symbol
+               FROM edge e
+               WHERE e.commit_id = ?1
+                 AND e.edge_type = ?2
+                 AND json_extract(e.meta, '$.annotation') IS NOT NULL
+                 AND e.dst_symbol IS NOT NULL
+                 AND NOT EXISTS (
+                     SELECT 1 FROM symbol s
+                     WHERE s.commit_id = e.commit_id AND s.symbol_id = e.dst_symbol
+                 )"#
+        )?;
+        let unresolved = stmt.query_map(params![commit_id, references], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+        
+        let mut linked = 0;
+        for (edge_id, reference) in unresolved {
+            if let Some((symbol_id, file_path)) = self.annotation_declaration(commit_id, &reference)? {
+                linked += self.conn.execute(
+                    "UPDATE edge SET dst_symbol = ?1, file_dst = ?2, resolution = ?3 WHERE id = ?4",
+                    params![symbol_id, file_path, semantic, edge_id],
+                )?;
+            }
+        }
+        
+        let mut stmt = self.conn.prepare(
+            r#"SELECT DISTINCT o.symbol_id
+               FROM occurrence o
+               WHERE o.commit_id = ?1
+                 AND o.token LIKE '@%'
+                 AND o.symbol_id IS NOT NULL
+                 AND NOT EXISTS (
+                     SELECT 1 FROM symbol s
+                     WHERE s.commit_id = o.commit_id AND s.symbol_id = o.symbol_id
+                 )"#
+        )?;
+        let unresolved = stmt.query_map(params![commit_id], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        for reference in unresolved {
+            if let Some((symbol_id, _)) = self.annotation_declaration(commit_id, &reference)? {
+                linked += self.conn.execute(
+                    "UPDATE occurrence SET symbol_id = ?1 WHERE commit_id = ?2 AND symbol_id = ?3 AND token LIKE '@%'",
+                    params![symbol_id, commit_id, reference],
+                )?;
+            }
+        }
+        self.bump_graph_version()?;
+        Ok(linked)
+    }
+
+    /// Id and file of the annotation type `reference` (a qualified name)
+    /// names: the one with that FQN, else the only one with its simple name
+    fn annotation_declaration(&self, commit_id: i64, reference: &str) -> Result<Option<(String, String)>> {
+        let simple_name = reference.rsplit('.').next().unwrap_or(reference);
+        let mut stmt = self.conn.prepare_cached(
+            r#"SELECT symbol_id, file_path, fqn FROM symbol
+               WHERE commit_id = ?1 AND name = ?2
+               ORDER BY fqn = ?3 DESC"#
+        )?;
+        let candidates = stmt.query_map(params![commit_id, format!("@{}", simple_name), reference], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(match candidates.as_slice() {
+            [(id, file, fqn), ..] if fqn == reference => Some((id.clone(), file.clone())),
+            [(id, file, _)] => Some((id.clone(), file.clone())),
+            _ => None,
+        })
+    }
+
+    /// The implementation a declaration was linked to in the snapshot, or
+    /// the symbol itself
+    fn implementation_of(&self, commit_id: i64, symbol: SymbolIR) -> Result<SymbolIR> {
+        if !symbol.flags.contains(SymbolFlags::DECLARATION) {
+            return Ok(symbol);
+        }
+        let declares = serde_json::to_string(&EdgeType::Declares)?;
+        let implementation = self.conn.prepare_cached(
+            "SELECT dst_symbol FROM edge WHERE commit_id = ?1 AND src_symbol = ?2 AND edge_type = ?3 ORDER BY id LIMIT 1",
+        )?
+        .query_row(params![commit_id, symbol.id, declares], |row| row.get::<_, String>(0))
+        .optional()?;
+        match implementation {
+            Some(id) => Ok(self.snapshot_symbol(commit_id, &id)?.unwrap_or(symbol)),
+            None => Ok(symbol),
+        }
+    }
+
+    /// Re-point edges and occurrences that name a re-exported alias at the
+    /// original definition. The alias chain is recorded in edge provenance.
+    #[tracing::instrument(skip(self))]
+    pub fn attribute_reexports(&self, commit_id: i64) -> Result<usize> {
+        let alias_count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM symbol_alias WHERE commit_id = ?1",
+            params![commit_id],
+            |row| row.get(0),
+        )?;
+        if alias_count == 0 {
+            return Ok(0);
+        }
+        
+        let mut stmt = self.conn.prepare(
+            r#"SELECT e.id, e.dst_symbol, e.provenance
+               FROM edge e
+               WHERE e.commit_id = ?1
+                 AND e.edge_type != '"Aliases"'
+                 AND e.dst_symbol IS NOT NULL
+                 AND NOT EXISTS (
+                     SELECT 1 FROM symbol s
+                     WHERE s.commit_id = e.commit_id AND s.symbol_id = e.dst_symbol
+                 )"#
+        )?;
+        let unresolved = stmt.query_map(params![commit_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+        
+        let mut attributed = 0;
+        for (edge_id, dst, provenance) in unresolved {
+            let Some((symbol, chain)) = self.resolve_alias_chain(commit_id, &dst)? else {
+                continue;
+            };
+            
+            let mut provenance: BTreeMap<String, String> = provenance
+                .and_then(|p| serde_json::from_str(&p).ok())
+                .unwrap_or_default();
+            provenance.insert("alias_of".to_string(), dst);
+            provenance.insert("reexport_chain".to_string(), chain.join(" -> "));
+            
+            self.conn.execute(
+                "UPDATE edge SET dst_symbol = ?1, file_dst = ?2, provenance = ?3 WHERE id = ?4",
+                params![symbol.id, symbol.file_path, serde_json::to_string(&provenance)?, edge_id],
+            )?;
+            attributed += 1;
+        }
+        
+        let mut stmt = self.conn.prepare(
+            r#"SELECT DISTINCT o.symbol_id
+               FROM occurrence o
+               WHERE o.commit_id = ?1
+                 AND o.symbol_id IS NOT NULL
+                 AND NOT EXISTS (
+                     SELECT 1 FROM symbol s
+                     WHERE s.commit_id = o.commit_id AND s.symbol_id = o.symbol_id
+                 )"#
+        )?;
+        let unresolved = stmt.query_map(params![commit_id], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        
+        for symbol_ref in unresolved {
+            if let Some((symbol, _)) = self.resolve_alias_chain(commit_id, &symbol_ref)? {
+                attributed += self.conn.execute(
+                    "UPDATE occurrence SET symbol_id = ?1 WHERE commit_id = ?2 AND symbol_id = ?3",
+                    params![symbol.id, commit_id, symbol_ref],
+                )?;
+            }
+        }
+        
+        self.bump_graph_version()?;
+        Ok(attributed)
+    }
+
     pub fn get_symbols_in_file(&self, file_path: &str) -> Result<Vec<SymbolIR>> {
+        let file_path = normalize_path(file_path);
         let mut symbols = Vec::new();
         
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(&format!(
+            r#"
+            SELECT {SYMBOL_COLUMNS}
+            FROM symbol s
+            WHERE s.file_path = ?1
+            ORDER BY s.span_start_line, s.span_start_col
+            "#,
+        ))?;
+        
+        let symbol_iter = stmt.query_map(params![file_path], symbol_from_row)?;
+        
+        for symbol in symbol_iter {
+            symbols.push(symbol?);
+        }
+        
+        Ok(symbols)
+    }
+
+    /// The symbol defined or referenced at a 0-based `line`/`col` of a file
+    /// in the latest snapshot, resolved to its definition (go-to-definition).
+    ///
+    /// Uses the innermost occurrence covering the position. Occurrences with a
+    /// symbol id are looked up directly or through aliases; unresolved ones
+    /// (e.g. calls) fall back to the token's last segment by name, preferring
+    /// a definition in the same file.
+    #[tracing::instrument(skip(self))]
+    pub fn definition_at(&self, file_path: &str, line: u32, col: u32) -> Result<Option<SymbolIR>> {
+        let Some(commit_id) = self.latest_snapshot_id()? else {
+            return Ok(None);
+        };
+        let file_path = normalize_path(file_path);
+        let occurrence = self.conn.prepare_cached(
+            r#"SELECT symbol_id, token FROM occurrence
+               WHERE commit_id = ?4 AND file_path = ?1
+                 AND (span_start_line < ?2 OR (span_start_line = ?2 AND span_start_col <= ?3))
+                 AND (span_end_line > ?2 OR (span_end_line = ?2 AND span_end_col >= ?3))
+               ORDER BY span_end_line - span_start_line, span_end_col - span_start_col
+               LIMIT 1"#
+        )?
+        .query_row(params![file_path, line, col, commit_id], |row| {
+            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?))
+        })
+        .optional()?;
+        let Some((symbol_id, token)) = occurrence else {
+            return Ok(None);
+        };
+        
+        for name in symbol_id.iter().chain(std::iter::once(&token)) {
+            if let Some(symbol) = self.snapshot_symbol(commit_id, name)? {
+                return self.implementation_of(commit_id, symbol).map(Some);
+            }
+            if let Some((symbol, _)) = self.resolve_alias_chain(commit_id, name)? {
+                return Ok(Some(symbol));
+            }
+        }
+        
+        let name = token_name(&token);
+        let declaration = SymbolFlags::DECLARATION.bits();
+        let symbol_id = self.conn.prepare_cached(&format!(
+            r#"SELECT symbol_id FROM symbol
+               WHERE commit_id = ?3 AND name = ?1
+               ORDER BY file_path = ?2 DESC, (flags & {declaration}) != 0, file_path, span_start_line
+               LIMIT 1"#
+        ))?
+        .query_row(params![name, file_path, commit_id], |row| row.get::<_, String>(0))
+        .optional()?;
+        
+        match symbol_id {
+            Some(id) => self.snapshot_symbol(commit_id, &id),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_occurrences_in_file(&self, file_path: &str) -> Result<Vec<OccurrenceIR>> {
+        let file_path = normalize_path(file_path);
+        
+        let mut stmt = self.conn.prepare_cached(
             r#"
-            SELECT symbol_id, lang, kind, name, fqn, signature, file_path,
-                   span_start_line, span_start_col, span_end_line, span_end_col,
-                   visibility, doc, sig_hash
-            FROM symbol 
+            SELECT file_path, symbol_id, role, span_start_line, span_start_col,
+                   span_end_line, span_end_col, token
+            FROM occurrence
             WHERE file_path = ?1
             ORDER BY span_start_line, span_start_col
             "#,
         )?;
         
-        let symbol_iter = stmt.query_map(params![file_path], |row| {
-            Ok(SymbolIR {
-                id: row.get(0)?,
-                lang: serde_json::from_str(&row.get::<_, String>(1)?).unwrap_or(Language::Unknown),
-                lang_version: None,
-                kind: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or(SymbolKind::Variable),
-                name: row.get(3)?,
-                fqn: row.get(4)?,
-                signature: row.get(5)?,
-                file_path: row.get(6)?,
-                span: Span {
-                    start_line: row.get(7)?,
-                    start_col: row.get(8)?,
-                    end_line: row.get(9)?,
-                    end_col: row.get(10)?,
+        let occurrences = stmt.query_map(params![file_path], occurrence_from_row)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+        
+        Ok(occurrences)
+    }
+
+    /// Grep-like identifier search over the latest snapshot's occurrence
+    /// tokens. Every hit carries
+    /// the symbol it refers to (when resolvable) and the innermost symbol it
+    /// sits in, so a text match lands in graph context. `query` matches whole
+    /// identifiers or member-path segments by prefix: `load` finds
+    /// `this.repo.loadAll`, `repo.load` finds the two segments in order.
+    #[tracing::instrument(skip(self))]
+    pub fn search_occurrences(&self, query: &str, limit: usize) -> Result<Vec<OccurrenceHit>> {
+        let Some(commit_id) = self.latest_snapshot_id()? else {
+            return Ok(Vec::new());
+        };
+        let phrase = format!("\"{}\"*", query.replace('"', "\"\""));
+        let mut stmt = self.conn.prepare_cached(
+            r#"
+            SELECT o.file_path, o.symbol_id, o.role, o.span_start_line, o.span_start_col,
+                   o.span_end_line, o.span_end_col, o.token
+            FROM occurrence_fts
+            JOIN occurrence o ON occurrence_fts.rowid = o.id
+            WHERE occurrence_fts MATCH ?1 AND o.commit_id = ?3
+            ORDER BY rank, o.file_path, o.span_start_line, o.span_start_col
+            LIMIT ?2
+            "#,
+        )?;
+        let occurrences = stmt.query_map(params![phrase, limit as i64, commit_id], occurrence_from_row)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+        
+        let mut hits = Vec::with_capacity(occurrences.len());
+        for occurrence in occurrences {
+            let symbol = match &occurrence.symbol_id {
+                Some(id) => match self.snapshot_symbol(commit_id, id)? {
+                    Some(symbol) => Some(symbol),
+                    None => self.resolve_alias_chain(commit_id, id)?.map(|(symbol, _)| symbol),
                 },
-                visibility: row.get::<_, Option<String>>(11)?
-                    .and_then(|v| serde_json::from_str(&v).ok()),
-                doc: row.get(12)?,
-                sig_hash: row.get(13)?,
-            })
-        })?;
+                None => None,
+            };
+            let enclosing = self.enclosing_symbol(commit_id, &occurrence)?;
+            hits.push(OccurrenceHit { occurrence, symbol, enclosing });
+        }
+        Ok(hits)
+    }
+
+    /// Per-line definition and reference counts for a file in the latest
+    /// snapshot, for editors to
+    /// shade the gutter by how heavily each region is used elsewhere. Only
+    /// lines defining a symbol appear, in order.
+    ///
+    /// References are occurrences in other files, counted on the line of
+    /// the symbol they resolve to. Unresolved ones (e.g. calls) count when
+    /// the token's last segment is the symbol's name, against the first
+    /// symbol of that name in the file.
+    #[tracing::instrument(skip(self))]
+    pub fn file_heatmap(&self, file_path: &str) -> Result<Vec<LineHeat>> {
+        let Some(commit_id) = self.latest_snapshot_id()? else {
+            return Ok(Vec::new());
+        };
+        let file_path = normalize_path(file_path);
+        let symbols = self.conn.prepare_cached(&format!(
+            r#"SELECT {SYMBOL_COLUMNS}
+               FROM symbol s
+               WHERE s.commit_id = ?1 AND s.file_path = ?2
+               ORDER BY s.span_start_line, s.span_start_col"#
+        ))?
+        .query_map(params![commit_id, file_path], symbol_from_row)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+        let definition = serde_json::to_string(&protocol::OccurrenceRole::Definition)?;
+        let declaration = serde_json::to_string(&protocol::OccurrenceRole::Declaration)?;
+        
+        let mut lines: BTreeMap<u32, LineHeat> = BTreeMap::new();
+        let mut by_name: HashMap<&str, u32> = HashMap::new();
+        let mut resolved = self.conn.prepare_cached(
+            r#"SELECT COUNT(*) FROM occurrence
+               WHERE commit_id = ?5 AND symbol_id = ?1 AND file_path != ?2 AND role NOT IN (?3, ?4)"#
+        )?;
+        for symbol in &symbols {
+            let line = symbol.name_span.start_line;
+            let references: i64 = resolved.query_row(
+                params![symbol.id, file_path, definition, declaration, commit_id],
+                |row| row.get(0),
+            )?;
+            let heat = lines.entry(line).or_insert(LineHeat { line, definitions: 0, references: 0 });
+            heat.definitions += 1;
+            heat.references += references as usize;
+            by_name.entry(symbol.name.as_str()).or_insert(line);
+        }
         
-        for symbol in symbol_iter {
-            symbols.push(symbol?);
+        let mut unresolved = self.conn.prepare_cached(
+            r#"SELECT o.token FROM occurrence_fts
+               JOIN occurrence o ON occurrence_fts.rowid = o.id
+               WHERE occurrence_fts MATCH ?1 AND o.commit_id = ?5
+                 AND o.symbol_id IS NULL AND o.file_path != ?2 AND o.role NOT IN (?3, ?4)"#
+        )?;
+        for (name, line) in by_name {
+            let phrase = format!("\"{}\"", name.replace('"', "\"\""));
+            let tokens = unresolved
+                .query_map(params![phrase, file_path, definition, declaration, commit_id], |row| row.get::<_, String>(0))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let references = tokens
+                .iter()
+                .filter(|token| token_name(token) == name)
+                .count();
+            if let Some(heat) = lines.get_mut(&line) {
+                heat.references += references;
+            }
         }
         
-        Ok(symbols)
+        Ok(lines.into_values().collect())
+    }
+
+    /// Innermost symbol of the snapshot whose span contains `occurrence`,
+    /// other than the symbol the occurrence itself defines
+    fn enclosing_symbol(&self, commit_id: i64, occurrence: &OccurrenceIR) -> Result<Option<SymbolIR>> {
+        let symbol_id = self.conn.prepare_cached(
+            r#"SELECT symbol_id FROM symbol
+               WHERE commit_id = ?5 AND file_path = ?1
+                 AND symbol_id IS NOT ?4
+                 AND (span_start_line < ?2 OR (span_start_line = ?2 AND span_start_col <= ?3))
+                 AND (span_end_line > ?2 OR (span_end_line = ?2 AND span_end_col >= ?3))
+               ORDER BY span_end_line - span_start_line, span_end_col - span_start_col
+               LIMIT 1"#
+        )?
+        .query_row(
+            params![
+                occurrence.file_path,
+                occurrence.span.start_line,
+                occurrence.span.start_col,
+                occurrence.symbol_id,
+                commit_id,
+            ],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()?;
+        
+        match symbol_id {
+            Some(id) => self.snapshot_symbol(commit_id, &id),
+            None => Ok(None),
+        }
     }
-    
+
     pub fn get_symbol_count(&self) -> Result<usize> {
         let count = self.conn.query_row(
             "SELECT COUNT(*) FROM symbol",
@@ -666,7 +2247,7 @@ impl GraphStore {
         
         Ok(count as usize)
     }
-    
+
     pub fn get_edge_count(&self) -> Result<usize> {
         let count = self.conn.query_row(
             "SELECT COUNT(*) FROM edge",
@@ -676,7 +2257,7 @@ impl GraphStore {
         
         Ok(count as usize)
     }
-    
+
     pub fn get_file_count(&self) -> Result<usize> {
         let count = self.conn.query_row(
             "SELECT COUNT(DISTINCT path) FROM file",
@@ -686,63 +2267,200 @@ impl GraphStore {
         
         Ok(count as usize)
     }
-    
+
     // Additional methods needed by the main binary
-    
+
     pub fn get_last_scanned_commit(&self) -> Result<Option<String>> {
         // Same as get_latest_commit
         self.get_latest_commit()
     }
-    
+
+    #[tracing::instrument(skip(self))]
     pub fn create_commit_snapshot(&self, commit_sha: &str) -> Result<i64> {
         // Same as get_or_create_commit
         self.get_or_create_commit(commit_sha)
     }
-    
-    pub fn delete_file_data(&self, commit_id: i64, file_path: &str) -> Result<()> {
-        // Same as clear_file_data
-        self.clear_file_data(commit_id, file_path)
-    }
-    
-    pub fn find_symbol_by_fqn(&self, fqn: &str) -> Result<Option<SymbolIR>> {
-        // Same as get_symbol_by_fqn
-        self.get_symbol_by_fqn(fqn)
-    }
-    
-    pub fn find_symbol_by_id(&self, symbol_id: &str) -> Result<Option<SymbolIR>> {
+
+    /// Record the files an interrupted scan finished, marking the commit incomplete
+    pub fn save_checkpoint(&self, commit_id: i64, completed_files: &[String]) -> Result<()> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs() as i64;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO scan_checkpoint (commit_id, completed_files, updated_at) VALUES (?1, ?2, ?3)",
+            params![commit_id, serde_json::to_string(completed_files)?, timestamp],
+        )?;
+        Ok(())
+    }
+
+    /// Files already indexed by an interrupted scan of `commit_sha`, if any
+    pub fn get_checkpoint(&self, commit_sha: &str) -> Result<Option<Vec<String>>> {
+        let completed = self.conn.query_row(
+            r#"SELECT k.completed_files
+               FROM scan_checkpoint k
+               JOIN commit_snapshot c ON k.commit_id = c.id
+               WHERE c.commit_sha = ?1"#,
+            params![commit_sha],
+            |row| row.get::<_, String>(0),
+        ).optional()?;
+        
+        match completed {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Mark the commit's scan as complete
+    pub fn clear_checkpoint(&self, commit_id: i64) -> Result<()> {
+        self.conn.execute("DELETE FROM scan_checkpoint WHERE commit_id = ?1", params![commit_id])?;
+        Ok(())
+    }
+
+    /// Start ingesting `commit_sha` atomically; see [`SnapshotWriter`]
+    #[tracing::instrument(skip(self))]
+    pub fn begin_snapshot(&self, commit_sha: &str) -> Result<SnapshotWriter<'_>> {
+        // IMMEDIATE takes the write lock up front so a concurrent scan fails
+        // fast instead of deadlocking halfway through
+        self.conn.execute_batch("BEGIN IMMEDIATE")?;
+        let commit_id = match self.get_or_create_commit(commit_sha) {
+            Ok(id) => id,
+            Err(e) => {
+                self.conn.execute_batch("ROLLBACK")?;
+                return Err(e);
+            }
+        };
+        Ok(SnapshotWriter {
+            store: self,
+            commit_id,
+            finished: false,
+        })
+    }
+
+    pub fn delete_file_data(&self, commit_id: i64, file_path: &str) -> Result<()> {
+        // Same as clear_file_data
+        self.clear_file_data(commit_id, file_path)
+    }
+
+    /// The symbol `fqn` names, optionally only in `lang`: by exact FQN,
+    /// then with scope separators normalized (`Foo::bar` finds `Foo.bar`),
+    /// then through the latest snapshot's aliases and re-exports, whose ids
+    /// are returned as the chain that led to it
+    pub fn find_symbol_by_fqn(&self, fqn: &str, lang: Option<&Language>) -> Result<Option<FqnMatch>> {
+        if let Some(symbol) = self.symbol_by_fqn_in(fqn, lang)? {
+            return Ok(Some(FqnMatch { symbol, alias_chain: Vec::new() }));
+        }
+        let Some(commit_id) = self.latest_snapshot_id()? else {
+            return Ok(None);
+        };
+        Ok(self
+            .resolve_alias_chain(commit_id, fqn)?
+            .filter(|(symbol, _)| lang.is_none_or(|lang| &symbol.lang == lang))
+            .map(|(symbol, alias_chain)| FqnMatch { symbol, alias_chain }))
+    }
+
+    fn symbol_by_fqn_in(&self, fqn: &str, lang: Option<&Language>) -> Result<Option<SymbolIR>> {
+        let declaration = SymbolFlags::DECLARATION.bits();
+        let lang = lang.map(enum_to_text).transpose()?;
+        let mut candidates = vec![("s.fqn".to_string(), fqn.to_string())];
+        if self.separators.applies_to(fqn) {
+            candidates.push((self.separators.normalize_sql("s.fqn"), self.separators.normalize(fqn)));
+        }
+        for (column, value) in candidates {
+            let symbol = self.conn.prepare_cached(&format!(
+                r#"SELECT {SYMBOL_COLUMNS}
+                   FROM symbol s
+                   WHERE {column} = ?1 AND (?2 IS NULL OR s.lang = ?2)
+                   ORDER BY (s.flags & {declaration}) != 0, s.id DESC
+                   LIMIT 1"#
+            ))?
+            .query_row(params![value, lang], symbol_from_row)
+            .optional()?;
+            if symbol.is_some() {
+                return Ok(symbol);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn find_symbol_by_id(&self, symbol_id: &str) -> Result<Option<SymbolIR>> {
         // Same as get_symbol
         self.get_symbol(symbol_id)
     }
-    
+
+    #[tracing::instrument(skip(self))]
     pub fn get_callers(&self, symbol_id: &str, max_depth: usize) -> Result<Vec<SymbolIR>> {
-        // Build graph and find callers
-        let graph = self.build_graph()?;
-        let caller_ids = graph.find_callers(symbol_id, max_depth);
-        
-        let mut callers = Vec::new();
-        for id in caller_ids {
-            if let Some(symbol) = self.get_symbol(&id)? {
-                callers.push(symbol);
+        self.cached_symbols(&format!("callers:{}:{}", symbol_id, max_depth), || {
+            let caller_ids = self.graph()?.find_callers(symbol_id, max_depth);
+            
+            let mut callers = Vec::new();
+            for id in caller_ids {
+                if let Some(symbol) = self.get_symbol(&id)? {
+                    callers.push(symbol);
+                }
             }
-        }
-        Ok(callers)
+            Ok(callers)
+        })
     }
-    
+
+    #[tracing::instrument(skip(self))]
     pub fn get_callees(&self, symbol_id: &str, max_depth: usize) -> Result<Vec<SymbolIR>> {
-        // Build graph and find callees
-        let graph = self.build_graph()?;
-        let callee_ids = graph.find_callees(symbol_id, max_depth);
-        
-        let mut callees = Vec::new();
-        for id in callee_ids {
-            if let Some(symbol) = self.get_symbol(&id)? {
-                callees.push(symbol);
+        self.cached_symbols(&format!("callees:{}:{}", symbol_id, max_depth), || {
+            let callee_ids = self.graph()?.find_callees(symbol_id, max_depth);
+            
+            let mut callees = Vec::new();
+            for id in callee_ids {
+                if let Some(symbol) = self.get_symbol(&id)? {
+                    callees.push(symbol);
+                }
             }
+            Ok(callees)
+        })
+    }
+
+    /// Files importing the symbol `symbol_id` by name, from the Imports
+    /// edges harnesses emit per import specifier (`meta.imported`). Matches
+    /// the symbol's own name, the names its module exports it under, and
+    /// namespace imports of its file.
+    #[tracing::instrument(skip(self))]
+    pub fn get_importers(&self, symbol_id: &str) -> Result<Vec<SymbolImporter>> {
+        let Some((commit_id, name, fqn, file_path)) = self.conn.query_row(
+            "SELECT commit_id, name, fqn, file_path FROM symbol WHERE symbol_id = ?1",
+            params![symbol_id],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?)),
+        ).optional()? else {
+            return Ok(Vec::new());
+        };
+        
+        let mut names = vec![name, "*".to_string()];
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT json_extract(meta, '$.export_name') FROM edge
+             WHERE commit_id = ?1 AND edge_type = ?2 AND file_src = ?3 AND dst_symbol IN (?4, ?5)",
+        )?;
+        let exported = stmt.query_map(
+            params![commit_id, serde_json::to_string(&EdgeType::Exports)?, file_path, fqn, symbol_id],
+            |row| row.get::<_, Option<String>>(0),
+        )?;
+        for export_name in exported {
+            names.extend(export_name?);
         }
-        Ok(callees)
+        
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT DISTINCT file_src, json_extract(meta, '$.imported'), json_extract(meta, '$.local') FROM edge
+             WHERE commit_id = ?1 AND edge_type = ?2 AND file_dst = ?3
+               AND json_extract(meta, '$.imported') IS NOT NULL
+             ORDER BY file_src",
+        )?;
+        let importers = stmt
+            .query_map(params![commit_id, serde_json::to_string(&EdgeType::Imports)?, file_path], |row| {
+                Ok(SymbolImporter { file: row.get(0)?, imported: row.get(1)?, local: row.get(2)? })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(importers.into_iter().filter(|importer| names.contains(&importer.imported)).collect())
     }
-    
+
+    #[tracing::instrument(skip(self))]
     pub fn get_file_dependents(&self, file_path: &str) -> Result<Vec<String>> {
+        let file_path = normalize_path(file_path);
         // Find files that import/depend on this file
         let mut stmt = self.conn.prepare(
             "SELECT DISTINCT file_src FROM edge 
@@ -764,43 +2482,33 @@ impl GraphStore {
 mod tests {
     use super::*;
     use tempfile::TempDir;
-    use protocol::{EdgeType, Language, OccurrenceRole, Resolution, SymbolKind};
+    use protocol::{EdgeType, Language, OccurrenceRole, Resolution, SymbolKind, test_util};
     use std::collections::HashMap;
-    
+
     fn create_test_store() -> Result<(GraphStore, TempDir)> {
         let temp_dir = TempDir::new()?;
         let store = GraphStore::new(temp_dir.path())?;
         Ok((store, temp_dir))
     }
-    
+
     fn create_test_symbol(id: &str, name: &str) -> SymbolIR {
         SymbolIR {
-            id: id.to_string(),
-            lang: Language::TypeScript,
-            lang_version: None,
-            kind: SymbolKind::Function,
             name: name.to_string(),
-            fqn: format!("test.{}", name),
             signature: Some(format!("function {}()", name)),
-            file_path: "test.ts".to_string(),
-            span: Span {
-                start_line: 1,
-                start_col: 0,
-                end_line: 1,
-                end_col: 10,
-            },
+            full_span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: 10 },
             visibility: Some("public".to_string()),
             doc: Some("Test function".to_string()),
             sig_hash: format!("hash_{}", id),
+            ..test_util::symbol(id, &format!("test.{}", name), "test.ts")
         }
     }
-    
+
     #[test]
     fn test_store_creation() -> Result<()> {
         let (_store, _temp_dir) = create_test_store()?;
         Ok(())
     }
-    
+
     #[test]
     fn test_commit_operations() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -826,7 +2534,149 @@ mod tests {
         
         Ok(())
     }
-    
+
+    #[test]
+    fn test_scan_checkpoint() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let previous = store.get_or_create_commit("abc123")?;
+        let commit_id = store.get_or_create_commit("def456")?;
+        // Scanned earlier, so "def456" is latest once its scan completes
+        store.conn.execute("UPDATE commit_snapshot SET timestamp = timestamp - 10 WHERE id = ?1", params![previous])?;
+        
+        store.save_checkpoint(commit_id, &["a.ts".to_string(), "b.ts".to_string()])?;
+        assert_eq!(store.get_checkpoint("def456")?, Some(vec!["a.ts".to_string(), "b.ts".to_string()]));
+        assert_eq!(store.get_checkpoint("abc123")?, None);
+        // An interrupted scan is not the last scanned commit
+        assert_eq!(store.get_latest_commit()?, Some("abc123".to_string()));
+        
+        store.clear_checkpoint(commit_id)?;
+        assert_eq!(store.get_checkpoint("def456")?, None);
+        assert_eq!(store.get_latest_commit()?, Some("def456".to_string()));
+        assert!(previous < commit_id);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_windows_paths_are_normalized() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        
+        let mut symbol = create_test_symbol("s1", "foo");
+        symbol.file_path = r"src\models\user.ts".to_string();
+        store.insert_file(commit_id, r"src\models\user.ts", "hash", 10)?;
+        store.insert_symbol(commit_id, &symbol)?;
+        
+        assert_eq!(store.get_file_hash("abc123", "src/models/user.ts")?, Some("hash".to_string()));
+        let symbols = store.get_symbols_in_file(r".\src\models\user.ts")?;
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].file_path, "src/models/user.ts");
+        
+        store.clear_file_data(commit_id, r"src\models\user.ts")?;
+        assert!(store.get_symbols_in_file("src/models/user.ts")?.is_empty());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_encoding() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        store.insert_file(commit_id, "legacy.c", "hash", 10)?;
+        assert_eq!(store.get_file_encoding("abc123", "legacy.c")?, None);
+        
+        store.set_file_encoding(commit_id, "legacy.c", "Shift_JIS")?;
+        assert_eq!(store.get_file_encoding("abc123", "legacy.c")?, Some("Shift_JIS".to_string()));
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_invisible_until_committed() -> Result<()> {
+        let (store, temp_dir) = create_test_store()?;
+        let reader = GraphStore::new(temp_dir.path())?;
+        
+        let snapshot = store.begin_snapshot("abc123")?;
+        store.insert_file(snapshot.commit_id(), "test.ts", "hash", 10)?;
+        store.insert_symbol(snapshot.commit_id(), &create_test_symbol("s1", "foo"))?;
+        
+        // A second connection (e.g. a query while a scan runs) sees nothing yet
+        assert_eq!(reader.get_latest_commit()?, None);
+        assert_eq!(reader.get_symbol_count()?, 0);
+        
+        snapshot.commit()?;
+        assert_eq!(reader.get_latest_commit()?, Some("abc123".to_string()));
+        assert_eq!(reader.get_symbol_count()?, 1);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_dropped_snapshot_rolls_back() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        
+        {
+            let snapshot = store.begin_snapshot("abc123")?;
+            store.insert_symbol(snapshot.commit_id(), &create_test_symbol("s1", "foo"))?;
+            // Simulate a scan failing halfway: the writer is dropped uncommitted
+        }
+        
+        assert_eq!(store.get_latest_commit()?, None);
+        assert_eq!(store.get_symbol_count()?, 0);
+        
+        // The store is usable for a fresh attempt afterwards
+        let snapshot = store.begin_snapshot("abc123")?;
+        snapshot.commit()?;
+        assert_eq!(store.get_latest_commit()?, Some("abc123".to_string()));
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_is_independent_of_insertion_order() -> Result<()> {
+        let mut edge = EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some("a".to_string()),
+            dst: Some("b".to_string()),
+            file_src: Some("test.ts".to_string()),
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        };
+        for key in ["line", "arity", "receiver", "callee", "kind"] {
+            edge.meta.insert(key.to_string(), serde_json::json!(key.len()));
+        }
+        let symbols = [create_test_symbol("a", "foo"), create_test_symbol("b", "bar")];
+        
+        let mut exports = Vec::new();
+        for reversed in [false, true] {
+            let (store, _temp_dir) = create_test_store()?;
+            let commit_id = store.get_or_create_commit("abc123")?;
+            let mut ordered: Vec<&SymbolIR> = symbols.iter().collect();
+            if reversed {
+                ordered.reverse();
+                // Simulate a later scan: an extra commit shifts every row id
+                store.get_or_create_commit("zzz")?;
+            }
+            store.insert_file(commit_id, "test.ts", "hash", 10)?;
+            for symbol in ordered {
+                store.insert_symbol(commit_id, symbol)?;
+            }
+            store.insert_edge(commit_id, &edge)?;
+            
+            let mut out = Vec::new();
+            store.export_jsonl("abc123", &mut out)?;
+            exports.push(String::from_utf8(out)?);
+        }
+        
+        assert_eq!(exports[0], exports[1]);
+        assert_eq!(exports[0].lines().count(), 4);
+        assert!(exports[0].contains(r#""meta":{"arity":5,"callee":6,"kind":4,"line":4,"receiver":8}"#));
+        
+        Ok(())
+    }
+
     #[test]
     fn test_file_operations() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -851,7 +2701,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_symbol_operations() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -887,7 +2737,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_edge_operations() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -924,7 +2774,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_occurrence_operations() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -948,7 +2798,7 @@ mod tests {
         // Verify insertion (would need to add a getter method)
         Ok(())
     }
-    
+
     #[test]
     fn test_search_operations() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -986,7 +2836,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_fts5_search() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1016,7 +2866,186 @@ mod tests {
         
         Ok(())
     }
-    
+
+    #[test]
+    fn test_file_summaries_roll_up_and_clear() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        let mut method = create_test_symbol("src/api/a.ts#get", "get");
+        method.kind = SymbolKind::Method;
+        let import = EdgeIR {
+            edge_type: EdgeType::Imports,
+            src: None,
+            dst: Some("./b".to_string()),
+            file_src: Some("src/api/a.ts".to_string()),
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        };
+        let content = "function get(x) { if (x && x.ok) { return 1; } }";
+        let summary = FileSummary::new("src/api/a.ts", "typescript", content, &[method], &[import], ParseQuality::Syntactic);
+        assert_eq!((summary.symbols, summary.imports, summary.complexity), (1, 1, 3));
+        store.insert_file_summary(commit_id, &summary)?;
+        store.insert_file_summary(commit_id, &FileSummary::new("src/api/v1/b.ts", "typescript", "", &[], &[], ParseQuality::Lossy))?;
+        store.insert_file_summary(commit_id, &FileSummary::new("tools/c.rb", "ruby", "", &[], &[], ParseQuality::Heuristic))?;
+        // A rescan replaces the row
+        store.insert_file_summary(commit_id, &summary)?;
+
+        assert_eq!(store.get_file_summaries("abc123", None)?[0], summary);
+        let directories = store.directory_summaries("abc123", None, Some(1))?;
+        let totals: Vec<(&str, usize, usize, usize)> =
+            directories.iter().map(|d| (d.directory.as_str(), d.files, d.complexity, d.degraded_files)).collect();
+        assert_eq!(totals, [("src", 2, 3, 1), ("tools", 1, 0, 1)]);
+        assert_eq!(directories[0].symbols_by_kind["Method"], 1);
+        let api = store.directory_summaries("abc123", Some("src/api/"), None)?;
+        assert_eq!(api.iter().map(|d| d.directory.as_str()).collect::<Vec<_>>(), ["src/api", "src/api/v1"]);
+
+        store.clear_file_data(commit_id, "tools/c.rb")?;
+        store.clear_subtree_data(commit_id, "src/api/v1")?;
+        let paths: Vec<String> = store.get_file_summaries("abc123", None)?.into_iter().map(|f| f.path).collect();
+        assert_eq!(paths, ["src/api/a.ts"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_event_edges_are_replaced() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        let edge = |edge_type: EdgeType, src: &str, dst: &str, file: &str| EdgeIR {
+            edge_type,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: Some(file.to_string()),
+            file_dst: None,
+            resolution: Resolution::Heuristic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        };
+        let files = |files: &[&str]| files.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+        store.insert_edge(commit_id, &edge(EdgeType::Calls, "a", "b", "src/a.ts"))?;
+        store.set_event_edges(
+            commit_id,
+            &files(&["src/a.ts", "src/b.ts"]),
+            &[edge(EdgeType::PublishesTo, "a", "event:x", "src/a.ts"), edge(EdgeType::SubscribesTo, "b", "event:w", "src/b.ts")],
+        )?;
+        // Rescanning src/a.ts leaves src/b.ts's edges alone
+        store.set_event_edges(commit_id, &files(&["src/a.ts"]), &[edge(EdgeType::PublishesTo, "a", "event:y", "src/a.ts")])?;
+
+        let mut edges: Vec<(EdgeType, String)> = store
+            .get_snapshot_edges("abc123")?
+            .into_iter()
+            .map(|e| (e.edge_type, e.dst.unwrap()))
+            .collect();
+        edges.sort_by_key(|(_, dst)| dst.clone());
+        assert_eq!(
+            edges,
+            [
+                (EdgeType::Calls, "b".to_string()),
+                (EdgeType::SubscribesTo, "event:w".to_string()),
+                (EdgeType::PublishesTo, "event:y".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_importers_by_export_name() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        let mut symbol = create_test_symbol("sym_parse", "parse");
+        symbol.fqn = "src/lib/parse".to_string();
+        symbol.file_path = "src/lib.ts".to_string();
+        store.insert_symbol(commit_id, &symbol)?;
+        let edge = |edge_type: EdgeType, src: &str, dst: &str, file_src: &str, file_dst: &str, meta: &[(&str, &str)]| EdgeIR {
+            edge_type,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: Some(file_src.to_string()),
+            file_dst: Some(file_dst.to_string()),
+            resolution: Resolution::Syntactic,
+            meta: meta.iter().map(|(k, v)| (k.to_string(), serde_json::json!(v))).collect(),
+            provenance: HashMap::new(),
+        };
+        // export default parse;
+        store.insert_edge(commit_id, &edge(EdgeType::Exports, "src/lib", "src/lib/parse", "src/lib.ts", "src/lib.ts", &[("export_name", "default")]))?;
+        for (file, imported, local) in [
+            ("src/a.ts", "parse", "parse"),
+            ("src/b.ts", "default", "P"),
+            ("src/c.ts", "*", "lib"),
+            ("src/d.ts", "other", "other"),
+        ] {
+            let dst = format!("src/lib/{}", imported);
+            let meta = [("imported", imported), ("local", local)];
+            store.insert_edge(commit_id, &edge(EdgeType::Imports, file, &dst, file, "src/lib.ts", &meta))?;
+        }
+        // The file-to-file edge names nothing
+        store.insert_edge(commit_id, &EdgeIR { src: None, dst: None, ..edge(EdgeType::Imports, "", "", "src/e.ts", "src/lib.ts", &[]) })?;
+
+        let importers: Vec<(String, String, String)> =
+            store.get_importers("sym_parse")?.into_iter().map(|i| (i.file, i.imported, i.local)).collect();
+        let expected = [("src/a.ts", "parse", "parse"), ("src/b.ts", "default", "P"), ("src/c.ts", "*", "lib")];
+        assert_eq!(importers, expected.map(|(f, i, l)| (f.to_string(), i.to_string(), l.to_string())));
+        assert!(store.get_importers("missing")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_subtree_rescan_marks_dangling_edges() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        let edge = |src: &str, file_src: &str, dst: &str, file_dst: &str| EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: Some(file_src.to_string()),
+            file_dst: Some(file_dst.to_string()),
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        };
+        for (id, file) in [("a/x.ts#run", "a/x.ts"), ("a/y.ts#gone", "a/y.ts"), ("ab/z.ts#keep", "ab/z.ts"), ("b/w.ts#use", "b/w.ts")] {
+            let mut symbol = create_test_symbol(id, id.rsplit('#').next().unwrap());
+            symbol.file_path = file.to_string();
+            store.insert_symbol(commit_id, &symbol)?;
+            store.insert_file(commit_id, file, "hash", 1)?;
+        }
+        store.insert_edge(commit_id, &edge("b/w.ts#use", "b/w.ts", "a/x.ts#run", "a/x.ts"))?;
+        store.insert_edge(commit_id, &edge("b/w.ts#use", "b/w.ts", "a/y.ts#gone", "a/y.ts"))?;
+        store.insert_edge(commit_id, &edge("a/x.ts#run", "a/x.ts", "a/y.ts#gone", "a/y.ts"))?;
+        
+        // `ab/` is a sibling of `a/`, not inside it
+        assert_eq!(store.clear_subtree_data(commit_id, "a/")?, 2);
+        assert_eq!(store.get_snapshot_symbols("abc123")?.len(), 2);
+        let edges = store.get_snapshot_edges("abc123")?;
+        assert_eq!(edges.len(), 2, "edges from outside the subtree stay");
+        
+        // The rescan finds `run` again, and a call out to a file never scanned
+        let mut run = create_test_symbol("a/x.ts#run", "run");
+        run.file_path = "a/x.ts".to_string();
+        store.insert_symbol(commit_id, &run)?;
+        store.insert_file(commit_id, "a/x.ts", "hash2", 1)?;
+        store.insert_edge(commit_id, &edge("a/x.ts#run", "a/x.ts", "lib", "vendor/lib.ts"))?;
+        
+        assert_eq!(store.mark_dangling_edges(commit_id, "a")?, 2);
+        let dangling: Vec<(String, bool)> = store
+            .get_snapshot_edges("abc123")?
+            .into_iter()
+            .map(|e| (e.dst.unwrap(), e.meta.get("dangling") == Some(&serde_json::json!(true))))
+            .collect();
+        assert_eq!(
+            dangling,
+            [("a/x.ts#run".to_string(), false), ("a/y.ts#gone".to_string(), true), ("lib".to_string(), true)]
+        );
+        
+        // Once the file is scanned the call resolves again
+        store.insert_file(commit_id, "vendor/lib.ts", "hash", 1)?;
+        assert_eq!(store.mark_dangling_edges(commit_id, "a")?, 1);
+        
+        Ok(())
+    }
+
     #[test]
     fn test_clear_file_data() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1049,7 +3078,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_graph_building() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1097,7 +3126,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_idempotency() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1115,7 +3144,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_unicode_symbols() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1137,7 +3166,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_very_long_names() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1156,7 +3185,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_empty_values() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1171,15 +3200,21 @@ mod tests {
             name: "".to_string(), // Empty name
             fqn: "".to_string(),  // Empty FQN
             signature: None,
+            parameters: Vec::new(),
+            return_type: None,
             file_path: "".to_string(), // Empty path
-            span: Span {
+            name_span: Default::default(),
+            full_span: Span {
                 start_line: 0,
                 start_col: 0,
                 end_line: 0,
                 end_col: 0,
             },
             visibility: None,
+            flags: SymbolFlags::empty(),
+            configs: Vec::new(),
             doc: None,
+            doc_tags: None,
             sig_hash: "".to_string(),
         };
         
@@ -1190,7 +3225,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_special_characters_in_paths() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1207,7 +3242,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_sql_injection_protection() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1229,10 +3264,10 @@ mod tests {
         
         Ok(())
     }
-    
+
     // Note: Concurrent test removed because SQLite connections are not thread-safe (not Send)
     // In production, you'd use a connection pool or separate connections per thread
-    
+
     #[test]
     fn test_cycle_detection_in_graph() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1275,7 +3310,7 @@ mod tests {
         
         Ok(())
     }
-    
+
     #[test]
     fn test_file_count_distinct() -> Result<()> {
         let (store, _temp_dir) = create_test_store()?;
@@ -1294,4 +3329,887 @@ mod tests {
         
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_alias_chain_resolution() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("test_commit")?;
+        
+        let symbol = create_test_symbol("sym1", "formatDate");
+        store.insert_symbol(commit_id, &symbol)?;
+        
+        // index.ts re-exports `fmt` which is itself a rename of test.formatDate
+        let alias_edge = |src: &str, dst: &str, alias: &str, file: &str| EdgeIR {
+            edge_type: EdgeType::Aliases,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: Some(file.to_string()),
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::from([
+                ("alias".to_string(), serde_json::json!(alias)),
+                ("alias_kind".to_string(), serde_json::json!("reexport")),
+            ]),
+            provenance: HashMap::new(),
+        };
+        store.insert_edge(commit_id, &alias_edge("utils/fmt", "test.formatDate", "fmt", "utils.ts"))?;
+        store.insert_edge(commit_id, &alias_edge("index/toDate", "utils/fmt", "toDate", "index.ts"))?;
+        
+        let resolved = store.resolve_alias("toDate")?.expect("alias chain should resolve");
+        assert_eq!(resolved.id, "sym1");
+        
+        let aliases = store.get_aliases(commit_id, "fmt")?;
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].kind.as_deref(), Some("reexport"));
+        assert_eq!(aliases[0].file_path.as_deref(), Some("utils.ts"));
+        
+        // Searching by the alias name finds the real definition
+        let results = store.search_symbols("toDate", 10)?;
+        assert!(results.iter().any(|s| s.id == "sym1"));
+        
+        // Clearing the re-exporting file drops its alias
+        store.clear_file_data(commit_id, "index.ts")?;
+        assert!(store.resolve_alias("toDate")?.is_none());
+        assert!(store.resolve_alias("fmt")?.is_some());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_alias_cycle_terminates() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("test_commit")?;
+        
+        for (src, dst) in [("a", "b"), ("b", "a")] {
+            store.insert_edge(commit_id, &EdgeIR {
+                edge_type: EdgeType::Aliases,
+                src: Some(src.to_string()),
+                dst: Some(dst.to_string()),
+                file_src: Some("cycle.ts".to_string()),
+                file_dst: None,
+                resolution: Resolution::Syntactic,
+                meta: HashMap::new(),
+                provenance: HashMap::new(),
+            })?;
+        }
+        
+        assert!(store.resolve_alias("a")?.is_none());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_edge_meta_and_provenance_round_trip() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("test_commit")?;
+        
+        let edge = EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some("sym1".to_string()),
+            dst: Some("sym2".to_string()),
+            file_src: Some("test.ts".to_string()),
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::from([("arg_count".to_string(), serde_json::json!(2))]),
+            provenance: HashMap::from([("source".to_string(), "ts_harness".to_string())]),
+        };
+        store.insert_edge(commit_id, &edge)?;
+        
+        let edges = store.get_edges("sym1")?;
+        assert_eq!(edges[0].meta["arg_count"], 2);
+        assert_eq!(edges[0].provenance["source"], "ts_harness");
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrates_edge_table_without_meta_columns() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db_dir = temp_dir.path().join(".reviewbot");
+        std::fs::create_dir_all(&db_dir)?;
+        {
+            let conn = Connection::open(db_dir.join("graph.db"))?;
+            conn.execute_batch(
+                "CREATE TABLE edge (
+                    id INTEGER PRIMARY KEY,
+                    commit_id INTEGER NOT NULL,
+                    edge_type TEXT NOT NULL,
+                    src_symbol TEXT,
+                    dst_symbol TEXT,
+                    file_src TEXT,
+                    file_dst TEXT,
+                    resolution TEXT NOT NULL
+                );"
+            )?;
+        }
+        
+        let store = GraphStore::new(temp_dir.path())?;
+        assert!(store.has_column("edge", "meta")?);
+        assert!(store.has_column("edge", "provenance")?);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_definition_at() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        
+        let mut helper = create_test_symbol("sym_helper", "helper");
+        helper.file_path = "src/util.ts".to_string();
+        store.insert_symbol(commit_id, &helper)?;
+        let occurrence = |symbol_id: Option<&str>, role, line, start_col, end_col, token: &str| OccurrenceIR {
+            file_path: "src/app.ts".to_string(),
+            symbol_id: symbol_id.map(str::to_string),
+            role,
+            span: Span { start_line: line, start_col, end_line: line, end_col },
+            token: token.to_string(),
+        };
+        // `import { helper }` on line 0, `utils.helper(1)` on line 3
+        store.insert_occurrence(commit_id, &occurrence(Some("sym_helper"), OccurrenceRole::Reference, 0, 9, 15, "helper"))?;
+        store.insert_occurrence(commit_id, &occurrence(None, OccurrenceRole::Call, 3, 4, 16, "utils.helper"))?;
+        
+        assert_eq!(store.definition_at("src/app.ts", 0, 12)?.map(|s| s.id), Some("sym_helper".to_string()));
+        assert_eq!(store.definition_at("src/app.ts", 3, 16)?.map(|s| s.id), Some("sym_helper".to_string()));
+        assert!(store.definition_at("src/app.ts", 1, 0)?.is_none());
+        assert!(store.definition_at("src/other.ts", 0, 12)?.is_none());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotations_link_to_their_declaration() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        
+        let annotation = |id: &str, fqn: &str| {
+            let name = format!("@{}", fqn.rsplit('.').next().unwrap());
+            SymbolIR { fqn: fqn.to_string(), kind: SymbolKind::Interface, ..create_test_symbol(id, &name) }
+        };
+        store.insert_symbol(commit_id, &annotation("audited", "com.acme.Audited"))?;
+        store.insert_symbol(commit_id, &annotation("cached", "com.acme.Cache.Cached"))?;
+        store.insert_symbol(commit_id, &annotation("timed_a", "com.acme.a.Timed"))?;
+        store.insert_symbol(commit_id, &annotation("timed_b", "com.acme.b.Timed"))?;
+        store.insert_symbol(commit_id, &create_test_symbol("service", "Service"))?;
+        let annotated_by = |reference: &str| EdgeIR {
+            edge_type: EdgeType::References,
+            src: Some("service".to_string()),
+            dst: Some(reference.to_string()),
+            file_src: Some("Service.java".to_string()),
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::from([("annotation".to_string(), serde_json::json!(reference))]),
+            provenance: HashMap::new(),
+        };
+        for reference in ["com.acme.Audited", "com.acme.Cached", "com.acme.Timed", "java.lang.Override"] {
+            store.insert_edge(commit_id, &annotated_by(reference))?;
+        }
+        store.insert_occurrence(commit_id, &OccurrenceIR {
+            file_path: "Service.java".to_string(),
+            symbol_id: Some("com.acme.Audited".to_string()),
+            role: OccurrenceRole::Reference,
+            span: Span { start_line: 3, start_col: 0, end_line: 3, end_col: 8 },
+            token: "@Audited".to_string(),
+        })?;
+        
+        assert_eq!(store.link_annotations(commit_id)?, 3);
+        let edges = store.get_edges("service")?;
+        let targets: Vec<(&str, &Resolution)> =
+            edges.iter().map(|e| (e.dst.as_deref().unwrap(), &e.resolution)).collect();
+        assert_eq!(
+            targets,
+            [
+                // By FQN, then by the only annotation with that simple name
+                ("audited", &Resolution::Semantic),
+                ("cached", &Resolution::Semantic),
+                // Ambiguous, or not in the repo
+                ("com.acme.Timed", &Resolution::Syntactic),
+                ("java.lang.Override", &Resolution::Syntactic),
+            ]
+        );
+        assert_eq!(edges[0].file_dst.as_deref(), Some("test.ts"));
+        assert_eq!(store.get_occurrences_in_file("Service.java")?[0].symbol_id.as_deref(), Some("audited"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_declarations_link_to_implementations() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        
+        let mut declared = create_test_symbol("sym_format_dts", "format");
+        declared.fqn = "src/format/format".to_string();
+        declared.file_path = "src/format.d.ts".to_string();
+        declared.flags = SymbolFlags::DECLARATION;
+        let mut implemented = create_test_symbol("sym_format", "format");
+        implemented.fqn = "src/format/format".to_string();
+        implemented.file_path = "src/format.ts".to_string();
+        let mut typed_only = create_test_symbol("sym_env", "env");
+        typed_only.flags = SymbolFlags::DECLARATION;
+        store.insert_symbol(commit_id, &implemented)?;
+        store.insert_symbol(commit_id, &declared)?;
+        store.insert_symbol(commit_id, &typed_only)?;
+        
+        assert_eq!(store.get_symbol_by_fqn("src/format/format")?.map(|s| s.id), Some("sym_format".to_string()));
+        
+        assert_eq!(store.link_declarations(commit_id)?, 1);
+        // Relinking replaces rather than duplicates
+        assert_eq!(store.link_declarations(commit_id)?, 1);
+        let edges = store.get_edges("sym_format_dts")?;
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].edge_type, EdgeType::Declares);
+        assert_eq!(edges[0].dst.as_deref(), Some("sym_format"));
+        
+        // Go to definition on a usage bound to the declaration lands on real code
+        for (symbol_id, line) in [("sym_format_dts", 0), ("sym_env", 1)] {
+            store.insert_occurrence(commit_id, &OccurrenceIR {
+                file_path: "src/app.ts".to_string(),
+                symbol_id: Some(symbol_id.to_string()),
+                role: OccurrenceRole::Reference,
+                span: Span { start_line: line, start_col: 0, end_line: line, end_col: 6 },
+                token: symbol_id.to_string(),
+            })?;
+        }
+        assert_eq!(store.definition_at("src/app.ts", 0, 2)?.map(|s| s.id), Some("sym_format".to_string()));
+        assert_eq!(store.definition_at("src/app.ts", 1, 2)?.map(|s| s.id), Some("sym_env".to_string()));
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_fqn_lookup_normalizes_separators() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        let mut rust = create_test_symbol("sym_parse", "parse");
+        rust.fqn = "codec::json::parse".to_string();
+        let mut java = create_test_symbol("sym_save", "save");
+        java.fqn = "com.acme.Repo.save".to_string();
+        store.insert_symbol(commit_id, &rust)?;
+        store.insert_symbol(commit_id, &java)?;
+        
+        let found = |store: &GraphStore, fqn: &str| store.find_symbol_by_fqn(fqn, None).unwrap().map(|m| m.symbol.id);
+        assert_eq!(found(&store, "codec.json.parse"), Some("sym_parse".to_string()));
+        assert_eq!(found(&store, "com::acme::Repo#save"), Some("sym_save".to_string()));
+        assert_eq!(found(&store, "com/acme/Repo/save"), Some("sym_save".to_string()));
+        assert_eq!(found(&store, "codec.json.other"), None);
+        
+        let ids: Vec<String> = store.search_symbols("json.parse", 10)?.into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, ["sym_parse"]);
+        let ids: Vec<String> = store.search_symbols("Repo::save", 10)?.into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, ["sym_save"]);
+        
+        // Only the configured separators are normalized
+        let store = store.with_fqn_separators(FqnSeparators::new(["::".to_string()]));
+        assert_eq!(found(&store, "codec.json.parse"), None);
+        assert_eq!(found(&store, "com::acme::Repo::save"), Some("sym_save".to_string()));
+        assert_eq!(found(&store, "com::acme::Repo#save"), None);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_fqn_lookup_language_hint_and_aliases() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        let ts = create_test_symbol("sym_ts_user", "User");
+        let mut py = create_test_symbol("sym_py_user", "User");
+        py.lang = Language::Python;
+        py.fqn = ts.fqn.clone();
+        store.insert_symbol(commit_id, &ts)?;
+        store.insert_symbol(commit_id, &py)?;
+        for (alias_id, target) in [("models.index.User", "models.barrel.User"), ("models.barrel.User", "sym_ts_user")] {
+            store.insert_alias(commit_id, &SymbolAlias {
+                alias: "User".to_string(),
+                alias_id: alias_id.to_string(),
+                target: target.to_string(),
+                kind: Some("reexport".to_string()),
+                file_path: None,
+            })?;
+        }
+        
+        let found = store.find_symbol_by_fqn(&ts.fqn, Some(&Language::Python))?.unwrap();
+        assert_eq!(found.symbol.id, "sym_py_user");
+        assert!(found.alias_chain.is_empty());
+        let found = store.find_symbol_by_fqn(&ts.fqn, Some(&Language::TypeScript))?.unwrap();
+        assert_eq!(found.symbol.id, "sym_ts_user");
+        assert!(store.find_symbol_by_fqn(&ts.fqn, Some(&Language::Go))?.is_none());
+        
+        // Re-exports lead to the canonical symbol, reporting the way there
+        let found = store.find_symbol_by_fqn("models.index.User", None)?.unwrap();
+        assert_eq!(found.symbol.id, "sym_ts_user");
+        assert_eq!(found.alias_chain, ["models.index.User", "models.barrel.User"]);
+        assert!(store.find_symbol_by_fqn("models.index.User", Some(&Language::Python))?.is_none());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_occurrences() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        
+        let mut load = create_test_symbol("sym_load", "load_all");
+        load.file_path = "src/repo.ts".to_string();
+        let mut main = create_test_symbol("sym_main", "main");
+        main.file_path = "src/app.ts".to_string();
+        main.full_span = Span { start_line: 0, start_col: 0, end_line: 10, end_col: 1 };
+        store.insert_symbol(commit_id, &load)?;
+        store.insert_symbol(commit_id, &main)?;
+        
+        let occurrence = |symbol_id: Option<&str>, line, token: &str| OccurrenceIR {
+            file_path: "src/app.ts".to_string(),
+            symbol_id: symbol_id.map(str::to_string),
+            role: OccurrenceRole::Reference,
+            span: Span { start_line: line, start_col: 4, end_line: line, end_col: 4 + token.len() as u32 },
+            token: token.to_string(),
+        };
+        store.insert_occurrence(commit_id, &occurrence(Some("sym_load"), 2, "this.repo.load_all"))?;
+        store.insert_occurrence(commit_id, &occurrence(None, 3, "loader"))?;
+        store.insert_occurrence(commit_id, &occurrence(None, 20, "unload"))?;
+        
+        let hits = store.search_occurrences("load", 10)?;
+        let tokens: Vec<&str> = hits.iter().map(|h| h.occurrence.token.as_str()).collect();
+        assert_eq!(tokens.len(), 2, "prefix matches identifiers, not substrings: {:?}", tokens);
+        let resolved = hits.iter().find(|h| h.occurrence.token == "this.repo.load_all").unwrap();
+        assert_eq!(resolved.symbol.as_ref().map(|s| s.id.as_str()), Some("sym_load"));
+        assert_eq!(resolved.enclosing.as_ref().map(|s| s.id.as_str()), Some("sym_main"));
+        
+        assert_eq!(store.search_occurrences("repo.load_all", 10)?.len(), 1);
+        assert!(store.search_occurrences("load_al\"l", 10)?.is_empty());
+        
+        // Deleting a file's data drops its tokens from the index
+        store.delete_file_data(commit_id, "src/app.ts")?;
+        assert!(store.search_occurrences("unload", 10)?.is_empty());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_heatmap() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        
+        let symbol = |id: &str, name: &str, line| {
+            let mut symbol = create_test_symbol(id, name);
+            symbol.file_path = "src/repo.ts".to_string();
+            symbol.name_span = Span { start_line: line, start_col: 4, end_line: line, end_col: 4 + name.len() as u32 };
+            symbol
+        };
+        store.insert_symbol(commit_id, &symbol("sym_repo", "Repo", 0))?;
+        store.insert_symbol(commit_id, &symbol("sym_load", "load", 2))?;
+        store.insert_symbol(commit_id, &symbol("sym_save", "save", 2))?;
+        store.insert_symbol(commit_id, &symbol("sym_unused", "unused", 5))?;
+        
+        let occurrence = |file: &str, symbol_id: Option<&str>, role, token: &str| OccurrenceIR {
+            file_path: file.to_string(),
+            symbol_id: symbol_id.map(str::to_string),
+            role,
+            span: Span { start_line: 1, start_col: 0, end_line: 1, end_col: token.len() as u32 },
+            token: token.to_string(),
+        };
+        store.insert_occurrence(commit_id, &occurrence("src/app.ts", Some("sym_repo"), OccurrenceRole::Reference, "Repo"))?;
+        store.insert_occurrence(commit_id, &occurrence("src/cli.ts", Some("sym_repo"), OccurrenceRole::Reference, "Repo"))?;
+        store.insert_occurrence(commit_id, &occurrence("src/app.ts", None, OccurrenceRole::Call, "this.repo.load"))?;
+        store.insert_occurrence(commit_id, &occurrence("src/app.ts", None, OccurrenceRole::Call, "save"))?;
+        // Not counted: other names, uses inside the file itself, definitions
+        store.insert_occurrence(commit_id, &occurrence("src/app.ts", None, OccurrenceRole::Call, "load.later"))?;
+        store.insert_occurrence(commit_id, &occurrence("src/repo.ts", Some("sym_load"), OccurrenceRole::Call, "load"))?;
+        store.insert_occurrence(commit_id, &occurrence("src/repo.d.ts", Some("sym_repo"), OccurrenceRole::Declaration, "Repo"))?;
+        
+        let heat = |line, definitions, references| LineHeat { line, definitions, references };
+        assert_eq!(store.file_heatmap("src/repo.ts")?, [heat(0, 1, 2), heat(2, 2, 2), heat(5, 1, 0)]);
+        assert!(store.file_heatmap("src/missing.ts")?.is_empty());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_cache_invalidated_by_writes() -> Result<()> {
+        let (store, temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        store.insert_symbol(commit_id, &create_test_symbol("s1", "parseConfig"))?;
+        
+        assert_eq!(store.search_symbols("parse", 10)?.len(), 1);
+        assert_eq!(store.search_symbols("parse", 10)?.len(), 1);
+        assert_eq!(store.cache_stats().hits, 1);
+        
+        // A write through this connection drops the cached result
+        store.insert_symbol(commit_id, &create_test_symbol("s2", "parseArgs"))?;
+        assert_eq!(store.search_symbols("parse", 10)?.len(), 2);
+        
+        // So does a snapshot written by another process
+        let other = GraphStore::new(temp_dir.path())?;
+        other.insert_symbol(commit_id, &create_test_symbol("s3", "parseFlags"))?;
+        assert_eq!(store.search_symbols("parse", 10)?.len(), 3);
+        assert_eq!(store.cache_stats().hits, 1);
+        
+        let uncached = GraphStore::new(temp_dir.path())?.with_cache_capacity(0);
+        uncached.search_symbols("parse", 10)?;
+        uncached.search_symbols("parse", 10)?;
+        assert_eq!(uncached.cache_stats(), CacheStats::default());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_saved_as_adjacency_file() -> Result<()> {
+        let (store, temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        let call = |src: &str, dst: &str| EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        };
+        store.insert_symbol(commit_id, &create_test_symbol("a", "a"))?;
+        store.insert_symbol(commit_id, &create_test_symbol("b", "b"))?;
+        store.insert_edge(commit_id, &call("a", "b"))?;
+        
+        assert_eq!(store.get_callees("a", 1)?.len(), 1);
+        let path = temp_dir.path().join(".reviewbot").join("graph-abc123.csr");
+        assert!(path.exists());
+        
+        // Another process traverses the mapped file rather than the rows...
+        let key = |store: &GraphStore| store.adjacency_file().unwrap().unwrap().1;
+        let other = GraphStore::new(temp_dir.path())?;
+        assert_eq!(Adjacency::read(&path, &key(&other))?.unwrap().find_callees("a", 1), ["b"]);
+        assert!(matches!(*other.graph()?, LoadedGraph::Mapped(_)));
+        assert_eq!(other.get_callees("a", 1)?.len(), 1);
+        
+        // ...until a write batch changes an edge
+        let writer = other.begin_snapshot("abc123")?;
+        other.conn.execute("UPDATE edge SET dst_symbol = 'c'", [])?;
+        writer.commit()?;
+        assert!(Adjacency::read(&path, &key(&other))?.is_none());
+        assert_eq!(other.build_graph()?.find_callees("a", 1), ["c"]);
+        assert_eq!(Adjacency::read(&path, &key(&other))?.unwrap().find_callees("a", 1), ["c"]);
+        
+        // A different noise filter keeps a different graph
+        let filtered = GraphStore::new(temp_dir.path())?.with_noise_filter(NoiseFilter::new(NoiseMode::Drop, &["c".to_string()]));
+        assert_ne!(key(&filtered), key(&other));
+        assert!(filtered.build_graph()?.find_callees("a", 1).is_empty());
+        
+        // A later snapshot gets its own file, and the earlier one, which
+        // can't match again, is removed
+        other.conn.execute("UPDATE commit_snapshot SET timestamp = timestamp - 10 WHERE commit_sha = 'abc123'", [])?;
+        let writer = other.begin_snapshot("def456")?;
+        writer.commit()?;
+        assert_eq!(other.build_graph()?.find_callees("a", 1), ["c"]);
+        let later = other.adjacency_file()?.unwrap().0;
+        assert_eq!(later, temp_dir.path().join(".reviewbot").join("graph-def456.csr"));
+        assert!(later.exists() && !path.exists());
+        let saved = std::fs::read_dir(path.parent().unwrap())?
+            .filter(|entry| entry.as_ref().is_ok_and(|entry| entry.path().extension().is_some_and(|ext| ext == "csr")))
+            .count();
+        assert_eq!(saved, 1);
+        
+        let disabled = GraphStore::new(temp_dir.path())?.with_adjacency_cache(false);
+        assert!(disabled.adjacency_file()?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_sees_inserts_after_a_saved_build() -> Result<()> {
+        let (store, temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        store.insert_symbol(commit_id, &create_test_symbol("a", "a"))?;
+        assert_eq!(store.build_graph()?.stats().node_count, 1);
+
+        // Semantic indexing writes rows one at a time after the snapshot is
+        // published, with no write batch to bump the graph version
+        store.insert_symbol(commit_id, &create_test_symbol("b", "b"))?;
+        assert_eq!(store.build_graph()?.stats().node_count, 2);
+        let reopened = GraphStore::new(temp_dir.path())?;
+        assert_eq!(reopened.build_graph()?.stats().node_count, 2);
+
+        reopened.insert_edge(commit_id, &EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some("a".to_string()),
+            dst: Some("b".to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Semantic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        })?;
+        assert_eq!(GraphStore::new(temp_dir.path())?.build_graph()?.find_callees("a", 1), ["b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_churn() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let version = |id: &str, hash: &str| SymbolIR { sig_hash: hash.to_string(), ..create_test_symbol(id, id) };
+        let snapshots = [
+            ("c1", vec![version("a", "a1"), version("b", "b1"), version("c", "c1")]),
+            // An incremental scan that only saw `a`'s file
+            ("c2", vec![version("a", "a2")]),
+            ("c3", vec![version("a", "a3"), version("b", "b2"), version("c", "c1")]),
+        ];
+        for (commit, symbols) in &snapshots {
+            let commit_id = store.get_or_create_commit(commit)?;
+            for symbol in symbols {
+                store.insert_symbol(commit_id, symbol)?;
+            }
+        }
+        assert_eq!(store.snapshot_commits()?, ["c1", "c2", "c3"]);
+        
+        let commits = ["c1", "c2", "unscanned", "c3"].map(String::from);
+        let churn = store.symbol_churn(&commits)?;
+        assert_eq!(churn.get("a"), Some(&2));
+        assert_eq!(churn.get("b"), Some(&1));
+        assert_eq!(churn.get("c"), Some(&0));
+        // Only the history given counts
+        assert_eq!(store.symbol_churn(&commits[..2])?.get("b"), Some(&0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_reads_stay_in_their_commit() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let base = store.get_or_create_commit("base")?;
+        let head = store.get_or_create_commit("head")?;
+        store.insert_symbol(base, &create_test_symbol("s1", "parseConfig"))?;
+        store.insert_symbol(head, &create_test_symbol("s2", "parseArgs"))?;
+        store.insert_edge(head, &EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some("s2".to_string()),
+            dst: Some("s1".to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        })?;
+
+        let names = |commit| -> Result<Vec<String>> {
+            Ok(store.get_snapshot_symbols(commit)?.into_iter().map(|s| s.name).collect())
+        };
+        assert_eq!(names("base")?, ["parseConfig"]);
+        assert_eq!(names("head")?, ["parseArgs"]);
+        assert!(store.get_snapshot_edges("base")?.is_empty());
+        assert_eq!(store.get_snapshot_edges("head")?[0].dst.as_deref(), Some("s1"));
+        assert!(store.get_snapshot_symbols("missing").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_filters() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        
+        let mut function = create_test_symbol("s1", "parseConfig");
+        function.file_path = "src/config.ts".to_string();
+        let mut class = create_test_symbol("s2", "ConfigParser");
+        class.kind = SymbolKind::Class;
+        class.file_path = "src/parser.ts".to_string();
+        let mut private = create_test_symbol("s3", "parse_config");
+        private.lang = Language::Python;
+        private.file_path = "tools/config.py".to_string();
+        private.visibility = Some("private".to_string());
+        private.return_type = Some("Result<Config, io::Error>".to_string());
+        private.flags = SymbolFlags::TEST_ONLY;
+        class.flags = SymbolFlags::DEPRECATED | SymbolFlags::EXPERIMENTAL;
+        for symbol in [&function, &class, &private] {
+            store.insert_symbol(commit_id, symbol)?;
+        }
+        
+        let names = |filter: SearchFilter| -> Result<Vec<String>> {
+            let mut names: Vec<String> = store.search_symbols_filtered("onfig", 10, &filter)?
+                .into_iter()
+                .map(|s| s.name)
+                .collect();
+            names.sort();
+            Ok(names)
+        };
+        
+        assert_eq!(names(SearchFilter::default())?.len(), 3);
+        assert_eq!(names(SearchFilter { kinds: vec![SymbolKind::Class], ..Default::default() })?, vec!["ConfigParser"]);
+        assert_eq!(names(SearchFilter { langs: vec![Language::Python], ..Default::default() })?, vec!["parse_config"]);
+        assert_eq!(
+            names(SearchFilter { file_glob: Some("src/*".to_string()), ..Default::default() })?,
+            vec!["ConfigParser", "parseConfig"]
+        );
+        assert_eq!(
+            names(SearchFilter { visibility: Some("public".to_string()), kinds: vec![SymbolKind::Function], ..Default::default() })?,
+            vec!["parseConfig"]
+        );
+        assert_eq!(
+            names(SearchFilter { return_type: Some("Result<*, io::Error>".to_string()), ..Default::default() })?,
+            vec!["parse_config"]
+        );
+        assert_eq!(
+            names(SearchFilter { with_flags: SymbolFlags::DEPRECATED, ..Default::default() })?,
+            vec!["ConfigParser"]
+        );
+        assert_eq!(
+            names(SearchFilter { without_flags: SymbolFlags::DEPRECATED | SymbolFlags::TEST_ONLY, ..Default::default() })?,
+            vec!["parseConfig"]
+        );
+        // An empty query lists everything the filter admits
+        let returns_io_error = SearchFilter { return_type: Some("Result<*, io::Error>".to_string()), ..Default::default() };
+        assert_eq!(store.search_symbols_filtered("", 10, &returns_io_error)?.len(), 1);
+        
+        store.set_symbol_layers(commit_id, &[("s1".to_string(), "app".to_string()), ("s2".to_string(), "app".to_string())])?;
+        store.set_symbol_layers(commit_id, &[("s2".to_string(), "domain".to_string())])?;
+        assert_eq!(names(SearchFilter { layer: Some("domain".to_string()), ..Default::default() })?, vec!["ConfigParser"]);
+        assert!(names(SearchFilter { layer: Some("app".to_string()), ..Default::default() })?.is_empty());
+        
+        store.set_symbol_entry_points(commit_id, &[("s1".to_string(), "export".to_string())])?;
+        assert_eq!(names(SearchFilter { entry_point: Some("export".to_string()), ..Default::default() })?, vec!["parseConfig"]);
+        let entry_points = store.get_entry_points("abc123")?;
+        assert_eq!(entry_points.get("s1").map(String::as_str), Some("export"));
+        assert_eq!(entry_points.len(), 1);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_ranks_generated_last() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        
+        let mut generated = create_test_symbol("s1", "User");
+        generated.file_path = "api/user.pb.go".to_string();
+        generated.flags = SymbolFlags::GENERATED;
+        let mut written = create_test_symbol("s2", "User");
+        written.file_path = "models/user.go".to_string();
+        store.insert_symbol(commit_id, &generated)?;
+        store.insert_symbol(commit_id, &written)?;
+        
+        let ids: Vec<String> = store.search_symbols("User", 10)?.into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec!["s2", "s1"]);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_cards_fold_snapshots_and_declarations() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let mut declared = create_test_symbol("sym_format_dts", "format");
+        declared.file_path = "src/format.d.ts".to_string();
+        declared.flags = SymbolFlags::DECLARATION;
+        let implemented = create_test_symbol("sym_format", "format");
+        for commit in ["abc123", "def456"] {
+            let commit_id = store.get_or_create_commit(commit)?;
+            store.insert_symbol(commit_id, &declared)?;
+            store.insert_symbol(commit_id, &implemented)?;
+        }
+        let commit_id = store.get_or_create_commit("def456")?;
+        store.insert_symbol(commit_id, &create_test_symbol("sym_formatter", "formatter"))?;
+        
+        assert_eq!(store.search_symbols("format", 10)?.len(), 5);
+        let cards = store.search_symbol_cards("format", 2, &SearchFilter::default())?;
+        let shown: Vec<(&str, usize)> = cards.iter().map(|c| (c.symbol.id.as_str(), c.locations.len())).collect();
+        assert_eq!(shown, [("sym_format", 2), ("sym_formatter", 1)]);
+        assert_eq!(cards[0].locations[1].file_path, "src/format.d.ts");
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrates_json_quoted_symbol_columns() -> Result<()> {
+        let (store, temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        store.insert_symbol(commit_id, &create_test_symbol("s1", "foo"))?;
+        // Rewrite the row the way older versions stored it
+        store.conn.execute_batch(
+            r#"UPDATE symbol SET lang = '"TypeScript"', kind = '"Function"', visibility = '"public"';
+               PRAGMA user_version = 0;"#,
+        )?;
+        drop(store);
+        
+        let store = GraphStore::new(temp_dir.path())?;
+        let row: (String, String, String) = store.conn.query_row(
+            "SELECT lang, kind, visibility FROM symbol WHERE symbol_id = 's1'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        assert_eq!(row, ("TypeScript".to_string(), "Function".to_string(), "public".to_string()));
+        
+        let symbol = store.get_symbol("s1")?.unwrap();
+        assert_eq!(symbol.kind, SymbolKind::Function);
+        assert_eq!(symbol.visibility.as_deref(), Some("public"));
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_name_span() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("abc123")?;
+        
+        let mut symbol = create_test_symbol("s1", "foo");
+        symbol.name_span = Span { start_line: 1, start_col: 9, end_line: 1, end_col: 12 };
+        store.insert_symbol(commit_id, &symbol)?;
+        let stored = store.get_symbol("s1")?.unwrap();
+        assert_eq!(stored.name_span, symbol.name_span);
+        assert_eq!(stored.full_span, symbol.full_span);
+        
+        // Rows written before name spans were stored fall back to the full span
+        store.conn.execute(
+            "UPDATE symbol SET name_start_line = NULL, name_start_col = NULL, name_end_line = NULL, name_end_col = NULL",
+            [],
+        )?;
+        let stored = store.get_symbol("s1")?.unwrap();
+        assert_eq!(stored.name_span, symbol.full_span);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_attribute_reexports_through_barrel() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let commit_id = store.get_or_create_commit("test_commit")?;
+        
+        let mut symbol = create_test_symbol("sym_parse", "parse");
+        symbol.fqn = "src/lib/parser/parse".to_string();
+        symbol.file_path = "src/lib/parser.ts".to_string();
+        store.insert_symbol(commit_id, &symbol)?;
+        
+        let alias_edge = |src: &str, dst: &str, alias: &str, kind: &str| EdgeIR {
+            edge_type: EdgeType::Aliases,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: Some("src/index.ts".to_string()),
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::from([
+                ("alias".to_string(), serde_json::json!(alias)),
+                ("alias_kind".to_string(), serde_json::json!(kind)),
+            ]),
+            provenance: HashMap::new(),
+        };
+        // src/index.ts: export * from './lib';  src/lib.ts: export { parse } from './lib/parser'
+        store.insert_edge(commit_id, &alias_edge("src/index/*", "src/lib/*", "*", "reexport_all"))?;
+        store.insert_edge(commit_id, &alias_edge("src/lib/parse", "src/lib/parser/parse", "parse", "reexport"))?;
+        
+        // A usage that only knows the barrel name
+        store.insert_edge(commit_id, &EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some("caller".to_string()),
+            dst: Some("src/index/parse".to_string()),
+            file_src: Some("src/app.ts".to_string()),
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: HashMap::new(),
+            provenance: HashMap::new(),
+        })?;
+        store.insert_occurrence(commit_id, &OccurrenceIR {
+            file_path: "src/app.ts".to_string(),
+            symbol_id: Some("src/index/parse".to_string()),
+            role: OccurrenceRole::Call,
+            span: Span { start_line: 3, start_col: 4, end_line: 3, end_col: 9 },
+            token: "parse".to_string(),
+        })?;
+        
+        let (resolved, chain) = store.resolve_alias_chain(commit_id, "src/index/parse")?.expect("chain should resolve");
+        assert_eq!(resolved.id, "sym_parse");
+        assert_eq!(chain, vec!["src/index/*", "src/lib/parse"]);
+        
+        assert_eq!(store.attribute_reexports(commit_id)?, 2);
+        
+        let edges = store.get_edges("caller")?;
+        assert_eq!(edges[0].dst.as_deref(), Some("sym_parse"));
+        assert_eq!(edges[0].file_dst.as_deref(), Some("src/lib/parser.ts"));
+        assert_eq!(edges[0].provenance["alias_of"], "src/index/parse");
+        assert_eq!(edges[0].provenance["reexport_chain"], "src/index/* -> src/lib/parse");
+        
+        let hits: i64 = store.conn.query_row(
+            "SELECT COUNT(*) FROM occurrence WHERE symbol_id = 'sym_parse'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(hits, 1);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_occurrence_queries_read_the_latest_snapshot() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        // The same files scanned twice; only load's body changed
+        for (sha, hash) in [("c1", "hash_v1"), ("c2", "hash_v2")] {
+            let commit_id = store.get_or_create_commit(sha)?;
+            store.conn.execute("UPDATE commit_snapshot SET timestamp = ?2 WHERE id = ?1", params![commit_id, commit_id])?;
+            let mut load = SymbolIR { sig_hash: hash.to_string(), ..create_test_symbol("sym_load", "load") };
+            load.file_path = "src/repo.ts".to_string();
+            load.name_span = Span { start_line: 2, start_col: 4, end_line: 2, end_col: 8 };
+            let mut main = create_test_symbol("sym_main", "main");
+            main.file_path = "src/app.ts".to_string();
+            main.full_span = Span { start_line: 0, start_col: 0, end_line: 10, end_col: 1 };
+            store.insert_symbol(commit_id, &load)?;
+            store.insert_symbol(commit_id, &main)?;
+            store.insert_occurrence(commit_id, &OccurrenceIR {
+                file_path: "src/app.ts".to_string(),
+                symbol_id: Some("sym_load".to_string()),
+                role: OccurrenceRole::Reference,
+                span: Span { start_line: 3, start_col: 4, end_line: 3, end_col: 8 },
+                token: "load".to_string(),
+            })?;
+        }
+        
+        let heat = store.file_heatmap("src/repo.ts")?;
+        assert_eq!(heat, [LineHeat { line: 2, definitions: 1, references: 1 }]);
+        let definition = store.definition_at("src/app.ts", 3, 6)?.unwrap();
+        assert_eq!(definition.sig_hash, "hash_v2");
+        let hits = store.search_occurrences("load", 10)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].symbol.as_ref().map(|s| s.sig_hash.as_str()), Some("hash_v2"));
+        assert_eq!(hits[0].enclosing.as_ref().map(|s| s.id.as_str()), Some("sym_main"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_aliases_are_scoped_to_their_snapshot() -> Result<()> {
+        let (store, _temp_dir) = create_test_store()?;
+        let old = store.get_or_create_commit("old")?;
+        let new = store.get_or_create_commit("new")?;
+        store.conn.execute("UPDATE commit_snapshot SET timestamp = timestamp - 10 WHERE id = ?1", params![old])?;
+        
+        let mut symbol = create_test_symbol("sym_parse", "parse");
+        symbol.fqn = "src/lib/parse".to_string();
+        let alias = |alias: &str, alias_id: &str, target: &str| SymbolAlias {
+            alias: alias.to_string(),
+            alias_id: alias_id.to_string(),
+            target: target.to_string(),
+            kind: Some("reexport".to_string()),
+            file_path: Some("src/index.ts".to_string()),
+        };
+        for commit_id in [old, new] {
+            store.insert_symbol(commit_id, &symbol)?;
+            store.insert_edge(commit_id, &EdgeIR {
+                edge_type: EdgeType::Calls,
+                src: Some("caller".to_string()),
+                dst: Some("src/index/parse".to_string()),
+                file_src: Some("src/app.ts".to_string()),
+                file_dst: None,
+                resolution: Resolution::Syntactic,
+                meta: HashMap::new(),
+                provenance: HashMap::new(),
+            })?;
+        }
+        // index.ts stopped re-exporting `parse` and `legacyParse`; only `format` is left
+        store.insert_alias(old, &alias("parse", "src/index/parse", "src/lib/parse"))?;
+        store.insert_alias(old, &alias("legacyParse", "src/index/legacyParse", "src/lib/parse"))?;
+        store.insert_alias(new, &alias("format", "src/index/format", "src/lib/format"))?;
+        
+        assert!(store.resolve_alias_chain(new, "src/index/parse")?.is_none());
+        assert!(store.resolve_alias_chain(old, "src/index/parse")?.is_some());
+        assert_eq!(store.attribute_reexports(new)?, 0);
+        assert_eq!(store.attribute_reexports(old)?, 1);
+        assert!(store.get_aliases(new, "parse")?.is_empty());
+        
+        // Queries follow the latest snapshot's aliases
+        assert!(store.resolve_alias("src/index/parse")?.is_none());
+        assert!(store.find_symbol_by_fqn("src/index/parse", None)?.is_none());
+        assert!(store.search_symbols("legacyParse", 10)?.is_empty());
+        
+        Ok(())
+    }
+}
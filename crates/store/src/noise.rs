@@ -0,0 +1,194 @@
+use protocol::{EdgeIR, EdgeType, Resolution};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// What happens to calls into the standard library and language builtins
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoiseMode {
+    /// Leave them in the graph
+    #[default]
+    Keep,
+    /// Leave them out
+    Drop,
+    /// Point them all at one node per library, e.g. `stdlib:fmt`
+    Collapse,
+}
+
+impl FromStr for NoiseMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep" => Ok(NoiseMode::Keep),
+            "drop" => Ok(NoiseMode::Drop),
+            "collapse" => Ok(NoiseMode::Collapse),
+            _ => Err(format!("'{}' is not one of keep, drop, collapse", s)),
+        }
+    }
+}
+
+/// Well-known standard library and builtin callees, as written at the call
+/// site. A trailing `*` matches any rest of the name.
+const STDLIB_CALLS: &[&str] = &[
+    // Go
+    "fmt.*", "strings.*", "strconv.*", "errors.*", "log.*", "os.*", "sort.*", "math.*", "time.*", "bytes.*",
+    "len", "append", "make", "panic", "cap", "copy", "delete",
+    // TypeScript / JavaScript
+    "console.*", "Math.*", "JSON.*", "Object.*", "Array.isArray", "Array.from", "Promise.*", "parseInt",
+    "parseFloat", "setTimeout", "setInterval", "clearTimeout", "require",
+    // Python
+    "print", "range", "str", "int", "float", "bool", "list", "dict", "set", "tuple", "isinstance", "hasattr",
+    "getattr", "setattr", "super", "enumerate", "zip", "map", "filter", "sorted", "open", "repr", "type", "min",
+    "max", "sum", "any", "all", "logging.*", "os.path.*", "json.*",
+    // Java, whose call sites record the bare method name
+    "System.*", "println", "String.format", "String.valueOf", "Objects.*", "Arrays.*",
+    "Collections.*", "List.of", "Map.of", "Set.of",
+    // Rust, and C++ below
+    "std::*", "String::from", "String::new", "Vec::new", "Vec::with_capacity", "Box::new", "Rc::new", "Arc::new",
+    "Some", "Ok", "Err", "Default::default",
+    // C
+    "printf", "fprintf", "sprintf", "snprintf", "malloc", "calloc", "free", "memcpy", "memset", "strlen", "strcmp",
+    // C#
+    "Console.*", "string.Format", "String.Format",
+];
+
+/// Drops or collapses calls to the standard library so call graph views
+/// and metrics are about the repository's own code
+#[derive(Debug, Clone, Default)]
+pub struct NoiseFilter {
+    mode: NoiseMode,
+    exact: HashSet<String>,
+    prefixes: Vec<String>,
+}
+
+impl NoiseFilter {
+    /// The built-in list plus `extra` patterns, e.g. `mylog.*`
+    pub fn new(mode: NoiseMode, extra: &[String]) -> Self {
+        let mut filter = Self { mode, ..Default::default() };
+        for pattern in STDLIB_CALLS.iter().copied().chain(extra.iter().map(String::as_str)) {
+            match pattern.strip_suffix('*') {
+                Some(prefix) => filter.prefixes.push(prefix.to_string()),
+                None => {
+                    filter.exact.insert(pattern.to_string());
+                }
+            }
+        }
+        filter
+    }
+
+    /// Changes whenever the filter would treat some edge differently
+    pub(crate) fn fingerprint(&self) -> String {
+        let mut patterns: Vec<String> =
+            self.exact.iter().cloned().chain(self.prefixes.iter().map(|prefix| format!("{}*", prefix))).collect();
+        patterns.sort_unstable();
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        std::hash::Hash::hash(&patterns, &mut hasher);
+        format!("{:?}-{:x}", self.mode, std::hash::Hasher::finish(&hasher))
+    }
+
+    fn is_noise(&self, callee: &str) -> bool {
+        self.exact.contains(callee) || self.prefixes.iter().any(|prefix| callee.starts_with(prefix.as_str()))
+    }
+
+    /// Whether `edge` calls into the standard library. Calls resolved to a
+    /// symbol of the repository (semantically, or to a `{file}#{fqn}` id)
+    /// never are, whatever the callee is named: `log.cpp#log::write` is not
+    /// `log.*`, and a user's own `map` is not the builtin.
+    fn is_noisy_call(&self, edge: &EdgeIR) -> bool {
+        edge.edge_type == EdgeType::Calls
+            && edge.resolution != Resolution::Semantic
+            && edge.dst.as_deref().is_some_and(|dst| !dst.contains('#') && self.is_noise(dst))
+    }
+
+    /// Node a noisy call is collapsed into: the callee's qualifier
+    /// (`fmt` of `fmt.Println`, `std::mem` of `std::mem::swap`), or
+    /// `builtins` for bare names
+    fn collapsed(callee: &str) -> String {
+        let qualifier = match (callee.rfind("::"), callee.rfind('.')) {
+            (Some(colons), Some(dot)) if dot > colons => &callee[..dot],
+            (Some(colons), _) => &callee[..colons],
+            (None, Some(dot)) => &callee[..dot],
+            (None, None) => "builtins",
+        };
+        format!("stdlib:{}", qualifier)
+    }
+
+    /// Apply the mode to the calls among `edges`; other edges pass through
+    pub fn filter_edges(&self, edges: Vec<EdgeIR>) -> Vec<EdgeIR> {
+        if self.mode == NoiseMode::Keep {
+            return edges;
+        }
+        let mut seen = HashSet::new();
+        edges
+            .into_iter()
+            .filter_map(|mut edge| {
+                if !self.is_noisy_call(&edge) {
+                    return Some(edge);
+                }
+                if self.mode == NoiseMode::Drop {
+                    return None;
+                }
+                edge.dst = edge.dst.as_deref().map(Self::collapsed);
+                // One collapsed call per caller and library is enough
+                seen.insert((edge.src.clone(), edge.dst.clone())).then_some(edge)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(src: &str, dst: &str) -> EdgeIR {
+        EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some(src.to_string()),
+            dst: Some(dst.to_string()),
+            file_src: None,
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta: Default::default(),
+            provenance: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_noise_filter() {
+        let edges = vec![
+            call("main", "fmt.Println"),
+            call("main", "fmt.Sprintf"),
+            call("main", "server.Start"),
+            call("main", "len"),
+            call("run", "std::mem::swap"),
+            call("run", "audit.Record"),
+        ];
+        let dsts = |edges: Vec<EdgeIR>| -> Vec<String> { edges.into_iter().filter_map(|e| e.dst).collect() };
+
+        assert_eq!(NoiseFilter::default().filter_edges(edges.clone()).len(), 6);
+        assert_eq!(
+            dsts(NoiseFilter::new(NoiseMode::Drop, &[]).filter_edges(edges.clone())),
+            ["server.Start", "audit.Record"]
+        );
+        assert_eq!(
+            dsts(NoiseFilter::new(NoiseMode::Collapse, &["audit.*".to_string()]).filter_edges(edges)),
+            ["stdlib:fmt", "server.Start", "stdlib:builtins", "stdlib:std::mem", "stdlib:audit"]
+        );
+    }
+
+    #[test]
+    fn test_noise_filter_keeps_resolved_calls() {
+        let edges = vec![
+            // Into a root-level `log.cpp` and `math.cs`
+            call("main", "log.cpp#log::write"),
+            call("main", "math.cs#Geometry.Area"),
+            EdgeIR { resolution: Resolution::Semantic, ..call("main", "map") },
+            call("main", "map"),
+        ];
+        let kept: Vec<String> =
+            NoiseFilter::new(NoiseMode::Drop, &[]).filter_edges(edges).into_iter().filter_map(|e| e.dst).collect();
+        assert_eq!(kept, ["log.cpp#log::write", "math.cs#Geometry.Area", "map"]);
+    }
+}
@@ -0,0 +1,212 @@
+use protocol::{EdgeIR, EdgeType, SymbolIR, SymbolKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::aggregate::directory;
+
+/// How completely a file was understood
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseQuality {
+    /// Parsed by a language harness
+    Syntactic,
+    /// Only pattern-matched by the fallback indexer
+    Heuristic,
+    /// Not valid in its encoding; undecodable bytes were replaced, so parts
+    /// of it may be missing
+    Lossy,
+}
+
+/// Figures for one file, kept at scan time so roll-ups read a row per file
+/// instead of aggregating the symbol table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileSummary {
+    pub path: String,
+    pub language: String,
+    pub symbols: usize,
+    /// Symbol counts by kind, e.g. `Method: 12`
+    pub symbols_by_kind: BTreeMap<String, usize>,
+    /// Distinct files or modules imported
+    pub imports: usize,
+    pub quality: ParseQuality,
+    /// One per function or method, plus one per branch, loop, `case`,
+    /// `catch` and short-circuit operator in the file's code
+    pub complexity: usize,
+}
+
+impl FileSummary {
+    /// Summarize `content` as parsed by the harness for `language` (the
+    /// scan's name for it, e.g. `java` or `ruby`)
+    pub fn new(
+        path: &str,
+        language: &str,
+        content: &str,
+        symbols: &[SymbolIR],
+        edges: &[EdgeIR],
+        quality: ParseQuality,
+    ) -> Self {
+        let mut symbols_by_kind = BTreeMap::new();
+        for symbol in symbols {
+            *symbols_by_kind.entry(format!("{:?}", symbol.kind)).or_insert(0) += 1;
+        }
+        let functions = symbols
+            .iter()
+            .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+            .count();
+        let hash_comments = matches!(language, "python" | "ruby" | "shell" | "perl" | "elixir");
+        FileSummary {
+            path: path.to_string(),
+            language: language.to_string(),
+            symbols: symbols.len(),
+            symbols_by_kind,
+            imports: edges
+                .iter()
+                .filter(|e| e.edge_type == EdgeType::Imports)
+                .filter_map(|e| e.file_dst.as_deref().or(e.dst.as_deref()))
+                .collect::<BTreeSet<_>>()
+                .len(),
+            quality,
+            complexity: functions + decision_points(content, hash_comments),
+        }
+    }
+}
+
+/// File summaries of a directory added up
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DirectorySummary {
+    pub directory: String,
+    pub files: usize,
+    /// Files per language
+    pub languages: BTreeMap<String, usize>,
+    pub symbols: usize,
+    pub symbols_by_kind: BTreeMap<String, usize>,
+    pub imports: usize,
+    pub complexity: usize,
+    /// Files only the fallback indexer or a lossy decode got through
+    pub degraded_files: usize,
+}
+
+/// Roll `files` up to their directories, cut to `depth` components when
+/// set, sorted by directory
+pub fn by_directory(files: &[FileSummary], depth: Option<usize>) -> Vec<DirectorySummary> {
+    let mut directories: BTreeMap<String, DirectorySummary> = BTreeMap::new();
+    for file in files {
+        let name = directory(&file.path, depth);
+        let summary = directories
+            .entry(name.clone())
+            .or_insert_with(|| DirectorySummary { directory: name, ..Default::default() });
+        summary.files += 1;
+        *summary.languages.entry(file.language.clone()).or_insert(0) += 1;
+        summary.symbols += file.symbols;
+        for (kind, count) in &file.symbols_by_kind {
+            *summary.symbols_by_kind.entry(kind.clone()).or_insert(0) += count;
+        }
+        summary.imports += file.imports;
+        summary.complexity += file.complexity;
+        if file.quality != ParseQuality::Syntactic {
+            summary.degraded_files += 1;
+        }
+    }
+    directories.into_values().collect()
+}
+
+/// Branch and loop keywords and short-circuit operators outside comments
+/// and string literals. A token count, so the same in every language
+/// rather than exact in any.
+fn decision_points(content: &str, hash_comments: bool) -> usize {
+    const KEYWORDS: &[&str] = &["if", "elif", "elsif", "for", "foreach", "while", "case", "catch", "except", "rescue"];
+    let bytes = content.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &content[i..];
+        let skip_to = if rest.starts_with("//") || (hash_comments && rest.starts_with('#')) {
+            rest.find('\n').map_or(bytes.len(), |end| i + end)
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            comment.find("*/").map_or(bytes.len(), |end| i + 2 + end + 2)
+        } else if let Some(quote) = rest.chars().next().filter(|c| matches!(c, '"' | '\'' | '`')) {
+            // To the closing quote on the same line, past escapes; an
+            // unclosed `'` is a Rust lifetime or a stray apostrophe
+            let line = &rest[1..rest.find('\n').unwrap_or(rest.len())];
+            let mut escaped = false;
+            let close = line.char_indices().find(|&(_, c)| {
+                let closes = c == quote && !escaped;
+                escaped = c == '\\' && !escaped;
+                closes
+            });
+            match close {
+                Some((end, _)) => i + 1 + end + 1,
+                None => i + 1,
+            }
+        } else if rest.starts_with("&&") || rest.starts_with("||") {
+            count += 1;
+            i + 2
+        } else if rest.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            let word_end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            if KEYWORDS.contains(&&rest[..word_end]) {
+                count += 1;
+            }
+            i + word_end
+        } else {
+            i + rest.chars().next().map_or(1, char::len_utf8)
+        };
+        i = skip_to;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decision_points_skip_comments_and_strings() {
+        let java = r#"
+// if this were code it would count
+int pick(int a, String s) {
+    /* for each */
+    if (a > 0 && s != null) {
+        for (char c : s.toCharArray()) { System.out.println("while " + c); }
+    }
+    switch (a) { case 1: return 1; case 2: return 2; }
+    return verify(s) || ifDefined(a) ? 1 : 0;
+}
+"#;
+        // if, &&, for, case, case, ||
+        assert_eq!(decision_points(java, false), 6);
+
+        let python = "# if not\ndef f(x):\n    if x and 'elif':\n        return [y for y in x]\n    while True:\n        pass\n";
+        assert_eq!(decision_points(python, true), 3);
+
+        // Lifetimes aren't strings
+        assert_eq!(decision_points("fn f<'a>(x: &'a str) { if x.is_empty() {} }", false), 1);
+    }
+
+    #[test]
+    fn test_by_directory() {
+        let file = |path: &str, language: &str, symbols: usize, quality| FileSummary {
+            path: path.to_string(),
+            language: language.to_string(),
+            symbols,
+            symbols_by_kind: BTreeMap::from([("Function".to_string(), symbols)]),
+            imports: 1,
+            quality,
+            complexity: 2,
+        };
+        let files = [
+            file("src/api/a.ts", "typescript", 3, ParseQuality::Syntactic),
+            file("src/api/v1/b.ts", "typescript", 2, ParseQuality::Lossy),
+            file("src/tools/c.rb", "ruby", 1, ParseQuality::Heuristic),
+            file("main.go", "go", 4, ParseQuality::Syntactic),
+        ];
+
+        let rolled = by_directory(&files, Some(2));
+        let totals: Vec<(&str, usize, usize, usize, usize)> = rolled
+            .iter()
+            .map(|d| (d.directory.as_str(), d.files, d.symbols, d.complexity, d.degraded_files))
+            .collect();
+        assert_eq!(totals, [(".", 1, 4, 2, 0), ("src/api", 2, 5, 4, 1), ("src/tools", 1, 1, 2, 1)]);
+        assert_eq!(rolled[1].symbols_by_kind["Function"], 5);
+        assert_eq!(rolled[1].languages["typescript"], 2);
+    }
+}
@@ -0,0 +1,305 @@
+//! Property tests: whatever the harnesses emit must come back out of the
+//! store unchanged
+
+use proptest::prelude::*;
+use protocol::{
+    DocTag, DocTags, EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Parameter, Resolution, Span,
+    SymbolFlags, SymbolIR, SymbolKind, Version,
+};
+use std::collections::HashMap;
+use store::GraphStore;
+use tempfile::TempDir;
+
+fn language() -> impl Strategy<Value = Language> {
+    prop_oneof![
+        Just(Language::TypeScript),
+        Just(Language::JavaScript),
+        Just(Language::Python),
+        Just(Language::Go),
+        Just(Language::Rust),
+        Just(Language::Java),
+        Just(Language::C),
+        Just(Language::Cpp),
+        Just(Language::CSharp),
+        Just(Language::Unknown),
+    ]
+}
+
+fn version() -> impl Strategy<Value = Version> {
+    prop_oneof![
+        Just(Version::C11),
+        Just(Version::Cpp20),
+        Just(Version::Java17),
+        Just(Version::Python312),
+        Just(Version::ES2022),
+        Just(Version::Go121),
+        Just(Version::DotNet8),
+        Just(Version::Unknown),
+    ]
+}
+
+fn symbol_kind() -> impl Strategy<Value = SymbolKind> {
+    prop_oneof![
+        Just(SymbolKind::Function),
+        Just(SymbolKind::Method),
+        Just(SymbolKind::Class),
+        Just(SymbolKind::Interface),
+        Just(SymbolKind::Variable),
+        Just(SymbolKind::Parameter),
+        Just(SymbolKind::Type),
+        Just(SymbolKind::Module),
+        Just(SymbolKind::Package),
+        Just(SymbolKind::Namespace),
+        Just(SymbolKind::Enum),
+        Just(SymbolKind::EnumMember),
+        Just(SymbolKind::Struct),
+        Just(SymbolKind::Trait),
+        Just(SymbolKind::Constant),
+        Just(SymbolKind::Field),
+        Just(SymbolKind::Property),
+        Just(SymbolKind::Event),
+        Just(SymbolKind::TypeAlias),
+        Just(SymbolKind::Typedef),
+        Just(SymbolKind::Union),
+        Just(SymbolKind::Crate),
+    ]
+}
+
+fn edge_type() -> impl Strategy<Value = EdgeType> {
+    prop_oneof![
+        Just(EdgeType::Contains),
+        Just(EdgeType::Declares),
+        Just(EdgeType::Calls),
+        Just(EdgeType::Imports),
+        Just(EdgeType::Extends),
+        Just(EdgeType::Implements),
+        Just(EdgeType::Overrides),
+        Just(EdgeType::Returns),
+        Just(EdgeType::Reads),
+        Just(EdgeType::Writes),
+        Just(EdgeType::Aliases),
+        Just(EdgeType::DependsOn),
+        Just(EdgeType::Exports),
+        Just(EdgeType::AnnotatedBy),
+        Just(EdgeType::References),
+        Just(EdgeType::Throws),
+        Just(EdgeType::Permits),
+        Just(EdgeType::PublishesTo),
+        Just(EdgeType::SubscribesTo),
+    ]
+}
+
+fn occurrence_role() -> impl Strategy<Value = OccurrenceRole> {
+    prop_oneof![
+        Just(OccurrenceRole::Reference),
+        Just(OccurrenceRole::Read),
+        Just(OccurrenceRole::Write),
+        Just(OccurrenceRole::Call),
+        Just(OccurrenceRole::Extend),
+        Just(OccurrenceRole::Implement),
+        Just(OccurrenceRole::Definition),
+        Just(OccurrenceRole::Declaration),
+    ]
+}
+
+/// Paths in the normalized form the store keeps (see `protocol::path`)
+fn file_path() -> impl Strategy<Value = String> {
+    prop::collection::vec("[a-zA-Z0-9_][a-zA-Z0-9_.-]{0,7}", 1..4).prop_map(|segments| segments.join("/"))
+}
+
+fn span() -> impl Strategy<Value = Span> {
+    (any::<u32>(), any::<u32>(), any::<u32>(), any::<u32>()).prop_map(
+        |(start_line, start_col, end_line, end_col)| Span {
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+        },
+    )
+}
+
+fn parameter() -> impl Strategy<Value = Parameter> {
+    (
+        "\\PC{1,10}",
+        prop::option::of("\\PC*"),
+        prop::option::of("\\PC*"),
+        any::<bool>(),
+    )
+        .prop_map(|(name, type_name, default, variadic)| Parameter {
+            name,
+            type_name,
+            default,
+            variadic,
+        })
+}
+
+fn doc_tag() -> impl Strategy<Value = DocTag> {
+    ("\\PC{1,10}", "\\PC*").prop_map(|(name, text)| DocTag { name, text })
+}
+
+fn doc_tags() -> impl Strategy<Value = DocTags> {
+    (
+        prop::collection::vec(doc_tag(), 0..3),
+        prop::option::of("\\PC*"),
+        prop::collection::vec(doc_tag(), 0..2),
+        prop::option::of("\\PC*"),
+    )
+        .prop_map(|(params, returns, throws, deprecated)| DocTags { params, returns, throws, deprecated })
+}
+
+/// JSON values without floats, whose text form may not round-trip exactly
+fn meta_value() -> impl Strategy<Value = serde_json::Value> {
+    let leaf = prop_oneof![
+        Just(serde_json::Value::Null),
+        any::<bool>().prop_map(serde_json::Value::from),
+        any::<i64>().prop_map(serde_json::Value::from),
+        "\\PC*".prop_map(serde_json::Value::from),
+    ];
+    leaf.prop_recursive(2, 8, 4, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(serde_json::Value::from),
+            prop::collection::hash_map("[a-z]{1,6}", inner, 0..4)
+                .prop_map(|m| serde_json::Value::Object(m.into_iter().collect())),
+        ]
+    })
+}
+
+prop_compose! {
+    fn symbol()(
+        id in "\\PC{1,40}",
+        lang in language(),
+        lang_version in prop::option::of(version()),
+        kind in symbol_kind(),
+        name in "\\PC{1,20}",
+        fqn in "\\PC{1,40}",
+        signature in prop::option::of("\\PC*"),
+        parameters in prop::collection::vec(parameter(), 0..4),
+        return_type in prop::option::of("\\PC*"),
+        file_path in file_path(),
+        name_span in span(),
+        full_span in span(),
+        visibility in prop::option::of("\\PC*"),
+        flags in any::<u32>().prop_map(SymbolFlags::from_bits_truncate),
+        configs in prop::collection::vec("\\PC{1,12}", 0..3),
+        doc in prop::option::of("\\PC*"),
+        doc_tags in prop::option::of(doc_tags()),
+        sig_hash in "[0-9a-f]{0,16}",
+    ) -> SymbolIR {
+        SymbolIR {
+            id,
+            lang,
+            lang_version,
+            kind,
+            name,
+            fqn,
+            signature,
+            parameters,
+            return_type,
+            file_path,
+            name_span,
+            full_span,
+            visibility,
+            flags,
+            configs,
+            doc,
+            doc_tags,
+            sig_hash,
+        }
+    }
+}
+
+prop_compose! {
+    fn edge()(
+        edge_type in edge_type(),
+        src in "src:\\PC{1,20}",
+        dst in prop::option::of("dst:\\PC{1,20}"),
+        file_src in prop::option::of(file_path()),
+        file_dst in prop::option::of(file_path()),
+        resolution in prop_oneof![Just(Resolution::Syntactic), Just(Resolution::Semantic), Just(Resolution::Heuristic)],
+        meta in prop::collection::hash_map("\\PC{1,10}", meta_value(), 0..4),
+        provenance in prop::collection::hash_map("\\PC{1,10}", "\\PC*", 0..4),
+    ) -> EdgeIR {
+        EdgeIR { edge_type, src: Some(src), dst, file_src, file_dst, resolution, meta, provenance }
+    }
+}
+
+prop_compose! {
+    fn occurrence()(
+        symbol_id in prop::option::of("\\PC{1,40}"),
+        role in occurrence_role(),
+        span in span(),
+        token in "\\PC*",
+    ) -> OccurrenceIR {
+        OccurrenceIR { file_path: "occurrences.ts".to_string(), symbol_id, role, span, token }
+    }
+}
+
+/// The IR types don't implement `PartialEq`; compare their serialized form
+fn json<T: serde::Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap()
+}
+
+fn new_store() -> (GraphStore, TempDir) {
+    let dir = TempDir::new().unwrap();
+    let store = GraphStore::new(dir.path()).unwrap();
+    (store, dir)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn symbol_roundtrip(symbol in symbol()) {
+        let (store, _dir) = new_store();
+        let commit_id = store.get_or_create_commit("abc123").unwrap();
+        store.insert_symbol(commit_id, &symbol).unwrap();
+
+        let loaded = store.get_symbol(&symbol.id).unwrap().expect("symbol was inserted");
+        prop_assert_eq!(json(&loaded), json(&symbol));
+    }
+
+    #[test]
+    fn edge_roundtrip(edge in edge()) {
+        let (store, _dir) = new_store();
+        let commit_id = store.get_or_create_commit("abc123").unwrap();
+        store.insert_edge(commit_id, &edge).unwrap();
+
+        let loaded = store.get_edges(edge.src.as_deref().unwrap()).unwrap();
+        prop_assert_eq!(loaded.len(), 1);
+        prop_assert_eq!(json(&loaded[0]), json(&edge));
+    }
+
+    #[test]
+    fn occurrence_roundtrip(occurrences in prop::collection::vec(occurrence(), 1..8)) {
+        let (store, _dir) = new_store();
+        let commit_id = store.get_or_create_commit("abc123").unwrap();
+        for occurrence in &occurrences {
+            store.insert_occurrence(commit_id, occurrence).unwrap();
+        }
+
+        let mut expected: Vec<_> = occurrences.iter().map(json).collect();
+        let mut loaded: Vec<_> = store.get_occurrences_in_file("occurrences.ts").unwrap().iter().map(json).collect();
+        let key = |v: &serde_json::Value| v.to_string();
+        expected.sort_by_key(key);
+        loaded.sort_by_key(key);
+        prop_assert_eq!(loaded, expected);
+    }
+}
+
+#[test]
+fn empty_meta_roundtrips_as_empty() {
+    let (store, _dir) = new_store();
+    let commit_id = store.get_or_create_commit("abc123").unwrap();
+    let edge = EdgeIR {
+        edge_type: EdgeType::Calls,
+        src: Some("a".to_string()),
+        dst: Some("b".to_string()),
+        file_src: None,
+        file_dst: None,
+        resolution: Resolution::Syntactic,
+        meta: HashMap::new(),
+        provenance: HashMap::new(),
+    };
+    store.insert_edge(commit_id, &edge).unwrap();
+    assert_eq!(json(&store.get_edges("a").unwrap()[0]), json(&edge));
+}
@@ -0,0 +1,141 @@
+//! JSDoc blocks (`/** ... */`), which carry the only type information a
+//! plain JavaScript codebase has
+
+/// A parsed `/** ... */` comment
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct JsDoc {
+    /// Text before the first tag
+    pub description: String,
+    pub params: Vec<JsDocParam>,
+    /// Type of `@returns {T}`
+    pub returns: Option<String>,
+    /// `@typedef {T} Name`, as (name, type)
+    pub typedefs: Vec<(String, String)>,
+}
+
+/// `@param {T} [name=default] description`
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct JsDocParam {
+    pub name: String,
+    pub type_name: Option<String>,
+    pub default: Option<String>,
+    /// `{...T}`
+    pub variadic: bool,
+}
+
+impl JsDoc {
+    /// `None` for comments that aren't JSDoc blocks
+    pub fn parse(comment: &str) -> Option<Self> {
+        let body = comment.strip_prefix("/**")?.strip_suffix("*/")?;
+        let lines = body.lines().map(|line| {
+            let line = line.trim();
+            line.strip_prefix('*').map(|rest| rest.strip_prefix(' ').unwrap_or(rest)).unwrap_or(line)
+        });
+
+        // Description lines, then one entry per tag with its continuation lines
+        let mut description = Vec::new();
+        let mut tags: Vec<String> = Vec::new();
+        for line in lines {
+            if line.trim_start().starts_with('@') {
+                tags.push(line.trim().to_string());
+            } else if let Some(tag) = tags.last_mut() {
+                tag.push(' ');
+                tag.push_str(line.trim());
+            } else {
+                description.push(line);
+            }
+        }
+
+        let mut doc = JsDoc { description: description.join("\n").trim().to_string(), ..Default::default() };
+        for tag in &tags {
+            let (name, rest) = tag[1..].split_once(char::is_whitespace).unwrap_or((&tag[1..], ""));
+            let (type_name, rest) = split_type(rest.trim_start());
+            match name {
+                "param" | "arg" | "argument" => {
+                    let Some(word) = rest.split_whitespace().next() else {
+                        continue;
+                    };
+                    // `[name]` and `[name=default]` are optional
+                    let (word, default) = match word.strip_prefix('[').and_then(|w| w.strip_suffix(']')) {
+                        Some(inner) => match inner.split_once('=') {
+                            Some((name, default)) => (name, Some(default.to_string())),
+                            None => (inner, None),
+                        },
+                        None => (word, None),
+                    };
+                    // `options.verbose` documents a property of a parameter
+                    if word.contains('.') {
+                        continue;
+                    }
+                    let variadic = type_name.as_deref().is_some_and(|t| t.starts_with("..."));
+                    doc.params.push(JsDocParam {
+                        name: word.to_string(),
+                        type_name: type_name.map(|t| t.trim_start_matches("...").to_string()),
+                        default,
+                        variadic,
+                    });
+                }
+                "returns" | "return" => doc.returns = type_name,
+                "typedef" => {
+                    if let (Some(type_name), Some(name)) = (type_name, rest.split_whitespace().next()) {
+                        doc.typedefs.push((name.to_string(), type_name));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(doc)
+    }
+}
+
+/// `{Object.<string, {a: number}>} rest` into the type and the rest
+fn split_type(text: &str) -> (Option<String>, &str) {
+    if !text.starts_with('{') {
+        return (None, text);
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (Some(text[1..i].trim().to_string()), text[i + 1..].trim_start());
+                }
+            }
+            _ => {}
+        }
+    }
+    (None, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jsdoc() {
+        let doc = JsDoc::parse(
+            "/**\n * Sum some numbers.\n * Ignores NaN.\n *\n * @param {number} base - where to start\n * @param {{step: number}} [options] spanning\n *   two lines\n * @param {string} [options.unit] skipped\n * @param {...number} values\n * @param [scale=1]\n * @returns {Promise<number>} the total\n */",
+        )
+        .unwrap();
+        assert_eq!(doc.description, "Sum some numbers.\nIgnores NaN.");
+        assert_eq!(
+            doc.params,
+            [
+                JsDocParam { name: "base".into(), type_name: Some("number".into()), ..Default::default() },
+                JsDocParam { name: "options".into(), type_name: Some("{step: number}".into()), ..Default::default() },
+                JsDocParam { name: "values".into(), type_name: Some("number".into()), variadic: true, ..Default::default() },
+                JsDocParam { name: "scale".into(), default: Some("1".into()), ..Default::default() },
+            ]
+        );
+        assert_eq!(doc.returns.as_deref(), Some("Promise<number>"));
+
+        let doc = JsDoc::parse("/** @typedef {{x: number, y: number}} Point */").unwrap();
+        assert_eq!(doc.description, "");
+        assert_eq!(doc.typedefs, [("Point".to_string(), "{x: number, y: number}".to_string())]);
+
+        assert!(JsDoc::parse("// @param {number} x").is_none());
+        assert!(JsDoc::parse("/* @param {number} x */").is_none());
+    }
+}
@@ -1,11 +1,46 @@
 use anyhow::Result;
-use protocol::{EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Resolution, Span, SymbolIR, SymbolKind};
+use protocol::{Capabilities, EdgeIR, EdgeType, Language, OccurrenceIR, OccurrenceRole, Parameter, Resolution, Span, SymbolFlags, SymbolIR, SymbolKind};
 use std::collections::HashMap;
 use tree_sitter::{Node, Parser};
 
+mod jsdoc;
+pub mod resolver;
+pub mod workspace;
+
+use jsdoc::JsDoc;
+
+pub use resolver::ImportResolver;
+pub use workspace::Workspace;
+
+/// What this harness extracts, for `reviewbot query languages`
+pub const CAPABILITIES: Capabilities = Capabilities {
+    name: "TypeScript/JavaScript",
+    extensions: &["ts", "tsx", "js", "jsx"],
+    edges: &[
+        EdgeType::Contains,
+        EdgeType::Imports,
+        EdgeType::Exports,
+        EdgeType::Aliases,
+        EdgeType::Calls,
+        EdgeType::Extends,
+        EdgeType::Implements,
+        EdgeType::References,
+    ],
+    occurrences: &[
+        OccurrenceRole::Definition,
+        OccurrenceRole::Declaration,
+        OccurrenceRole::Call,
+        OccurrenceRole::Reference,
+    ],
+    docs: true,
+    resolution: Resolution::Syntactic,
+};
+
 pub struct TypeScriptHarness {
     js_parser: Parser,
     ts_parser: Parser,
+    tsx_parser: Parser,
+    resolver: ImportResolver,
 }
 
 impl TypeScriptHarness {
@@ -16,7 +51,28 @@ impl TypeScriptHarness {
         let mut ts_parser = Parser::new();
         ts_parser.set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())?;
         
-        Ok(Self { js_parser, ts_parser })
+        // `.tsx` needs its own grammar: JSX and `<T>expr` casts conflict
+        let mut tsx_parser = Parser::new();
+        tsx_parser.set_language(&tree_sitter_typescript::LANGUAGE_TSX.into())?;
+        
+        Ok(Self {
+            js_parser,
+            ts_parser,
+            tsx_parser,
+            resolver: ImportResolver::new(),
+        })
+    }
+    
+    /// Resolve imports of monorepo workspace packages to their source files
+    pub fn with_workspace(mut self, workspace: Workspace) -> Self {
+        self.resolver = self.resolver.with_workspace(workspace);
+        self
+    }
+    
+    /// Resolve imports against the repository's files and tsconfig `paths`
+    pub fn with_resolver(mut self, resolver: ImportResolver) -> Self {
+        self.resolver = resolver;
+        self
     }
     
     pub fn parse_file(
@@ -27,7 +83,9 @@ impl TypeScriptHarness {
     ) -> Result<(Vec<SymbolIR>, Vec<EdgeIR>, Vec<OccurrenceIR>)> {
         
         // Choose the appropriate parser based on file extension
-        let parser = if file_path.ends_with(".ts") || file_path.ends_with(".tsx") {
+        let parser = if file_path.ends_with(".tsx") {
+            &mut self.tsx_parser
+        } else if file_path.ends_with(".ts") {
             &mut self.ts_parser
         } else {
             &mut self.js_parser
@@ -101,7 +159,7 @@ impl TypeScriptHarness {
                 }
                 return Ok(());
             }
-            "function_declaration" | "function_expression" | "arrow_function" => {
+            "function_declaration" | "function_expression" | "arrow_function" | "function_signature" => {
                 if let Some(name_node) = node.child_by_field_name("name") {
                     let name = self.node_text(name_node, source);
                     let symbol = self.create_symbol(
@@ -109,6 +167,7 @@ impl TypeScriptHarness {
                         SymbolKind::Function,
                         lang.clone(),
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
                         source,
@@ -132,7 +191,7 @@ impl TypeScriptHarness {
                     occurrences.push(OccurrenceIR {
                         file_path: file_path.to_string(),
                         symbol_id: Some(symbol.id.clone()),
-                        role: OccurrenceRole::Definition,
+                        role: self.definition_role(&symbol),
                         span: self.node_to_span(name_node),
                         token: name.clone(),
                     });
@@ -163,11 +222,13 @@ impl TypeScriptHarness {
                         SymbolKind::Interface,
                         lang.clone(),
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
                         source,
                     );
                     
+                    self.extract_heritage(node, source, file_path, &symbol.id, edges);
                     symbols.push(symbol.clone());
                     
                     // Add parent edge if applicable
@@ -185,7 +246,7 @@ impl TypeScriptHarness {
                     }
                 }
             }
-            "class_declaration" => {
+            "class_declaration" | "abstract_class_declaration" => {
                 if let Some(name_node) = node.child_by_field_name("name") {
                     let name = self.node_text(name_node, source);
                     let symbol = self.create_symbol(
@@ -193,17 +254,19 @@ impl TypeScriptHarness {
                         SymbolKind::Class,
                         lang.clone(),
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
                         source,
                     );
                     
+                    self.extract_heritage(node, source, file_path, &symbol.id, edges);
                     symbols.push(symbol.clone());
                     
                     // Process class body for methods
                     if let Some(body) = node.child_by_field_name("body") {
                         for child in body.children(&mut body.walk()) {
-                            if child.kind() == "method_definition" {
+                            if matches!(child.kind(), "method_definition" | "method_signature") {
                                 self.extract_method(
                                     child,
                                     source,
@@ -215,6 +278,21 @@ impl TypeScriptHarness {
                                     edges,
                                     occurrences,
                                 )?;
+                            } else if let Some(value) = child
+                                .child_by_field_name("value")
+                                .filter(|_| matches!(child.kind(), "public_field_definition" | "field_definition"))
+                            {
+                                // Calls in a field initializer are made by the class
+                                self.extract_symbols_recursive(
+                                    value,
+                                    source,
+                                    file_path,
+                                    commit_sha,
+                                    Some(&symbol.id),
+                                    symbols,
+                                    edges,
+                                    occurrences,
+                                )?;
                             }
                         }
                     }
@@ -243,6 +321,7 @@ impl TypeScriptHarness {
                                 kind,
                                 lang.clone(),
                                 decl,
+                                name_node,
                                 file_path,
                                 commit_sha,
                                 source,
@@ -252,21 +331,54 @@ impl TypeScriptHarness {
                             occurrences.push(OccurrenceIR {
                                 file_path: file_path.to_string(),
                                 symbol_id: Some(symbol.id.clone()),
-                                role: OccurrenceRole::Definition,
+                                role: self.definition_role(&symbol),
                                 span: self.node_to_span(name_node),
                                 token: name.clone(),
                             });
                             
+                            // Calls in a function's body are made by the function
+                            let is_function = decl
+                                .child_by_field_name("value")
+                                .is_some_and(|value| matches!(value.kind(), "arrow_function" | "function_expression" | "function"));
+                            let caller = is_function.then(|| symbol.id.clone());
                             symbols.push(symbol);
+                            for child in decl.children(&mut decl.walk()) {
+                                self.extract_symbols_recursive(
+                                    child,
+                                    source,
+                                    file_path,
+                                    commit_sha,
+                                    caller.as_deref().or(parent_symbol),
+                                    symbols,
+                                    edges,
+                                    occurrences,
+                                )?;
+                            }
+                            continue;
                         }
                     }
+                    self.extract_symbols_recursive(
+                        decl,
+                        source,
+                        file_path,
+                        commit_sha,
+                        parent_symbol,
+                        symbols,
+                        edges,
+                        occurrences,
+                    )?;
                 }
+                return Ok(());
             }
             "call_expression" => {
                 if let Some(func) = node.child_by_field_name("function") {
                     let callee_name = self.node_text(func, source);
                     
-                    // Create a CALLS edge (unresolved for now)
+                    // Module-level calls have no caller to attribute them to
+                    if let Some(caller) = parent_symbol {
+                        self.push_call(func, source, file_path, caller, edges);
+                    }
+                    
                     occurrences.push(OccurrenceIR {
                         file_path: file_path.to_string(),
                         symbol_id: None,
@@ -276,6 +388,12 @@ impl TypeScriptHarness {
                     });
                 }
             }
+            // Closing tags name the same component again
+            "jsx_opening_element" | "jsx_self_closing_element" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    self.extract_jsx_reference(name, source, file_path, parent_symbol, edges, occurrences);
+                }
+            }
             "enum_declaration" => {
                 if let Some(name_node) = node.child_by_field_name("name") {
                     let name = self.node_text(name_node, source);
@@ -284,6 +402,7 @@ impl TypeScriptHarness {
                         SymbolKind::Enum,
                         lang.clone(),
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
                         source,
@@ -316,6 +435,7 @@ impl TypeScriptHarness {
                         SymbolKind::Type,
                         lang.clone(),
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
                         source,
@@ -348,6 +468,7 @@ impl TypeScriptHarness {
                         SymbolKind::Namespace,
                         lang.clone(),
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
                         source,
@@ -387,6 +508,46 @@ impl TypeScriptHarness {
                     return Ok(());
                 }
             }
+            // `/** @typedef {{x: number}} Point */` declares a type in plain JS
+            "comment" => {
+                let comment = self.node_text(node, source);
+                for (name, type_name) in JsDoc::parse(&comment).map(|doc| doc.typedefs).unwrap_or_default() {
+                    let mut symbol = self.create_symbol(
+                        &name,
+                        SymbolKind::Type,
+                        lang.clone(),
+                        node,
+                        node,
+                        file_path,
+                        commit_sha,
+                        source,
+                    );
+                    symbol.signature = Some(format!("type {} = {}", name, type_name));
+                    symbol.doc = JsDoc::parse(&comment).map(|doc| doc.description).filter(|d| !d.is_empty());
+                    
+                    if let Some(parent_id) = parent_symbol {
+                        edges.push(EdgeIR {
+                            src: Some(parent_id.to_string()),
+                            dst: Some(symbol.id.clone()),
+                            file_src: None,
+                            file_dst: None,
+                            edge_type: EdgeType::Contains,
+                            resolution: Resolution::Syntactic,
+                            meta: HashMap::new(),
+                            provenance: HashMap::new(),
+                        });
+                    }
+                    occurrences.push(OccurrenceIR {
+                        file_path: file_path.to_string(),
+                        symbol_id: Some(symbol.id.clone()),
+                        role: self.definition_role(&symbol),
+                        span: self.node_to_span(node),
+                        token: name,
+                    });
+                    symbols.push(symbol);
+                }
+                return Ok(());
+            }
             "generator_function_declaration" => {
                 if let Some(name_node) = node.child_by_field_name("name") {
                     let name = self.node_text(name_node, source);
@@ -395,6 +556,7 @@ impl TypeScriptHarness {
                         SymbolKind::Function,
                         lang.clone(),
                         node,
+                        name_node,
                         file_path,
                         commit_sha,
                         source,
@@ -458,6 +620,7 @@ impl TypeScriptHarness {
                 SymbolKind::Method,
                 lang,
                 node,
+                name_node,
                 file_path,
                 commit_sha,
                 source,
@@ -475,11 +638,169 @@ impl TypeScriptHarness {
                 provenance: HashMap::new(),
             });
             
+            let method_id = symbol.id.clone();
             symbols.push(symbol);
+            
+            // Calls in the body are made by the method
+            if let Some(body) = node.child_by_field_name("body") {
+                self.extract_symbols_recursive(
+                    body,
+                    source,
+                    file_path,
+                    commit_sha,
+                    Some(&method_id),
+                    symbols,
+                    edges,
+                    occurrences,
+                )?;
+            }
         }
         Ok(())
     }
     
+    /// A `Calls` edge from `caller` to the name called: `save` for `save()`,
+    /// `this.save()` and `repo?.save()`, with the object in `meta.receiver`
+    /// when it is a plain name or property path. Calls of computed callees
+    /// such as `handlers[key]()` or `make()()` name nothing and are skipped.
+    fn push_call(&self, func: Node, source: &[u8], file_path: &str, caller: &str, edges: &mut Vec<EdgeIR>) {
+        let mut meta = HashMap::new();
+        let callee = match func.kind() {
+            "identifier" => func,
+            "member_expression" => {
+                let (Some(object), Some(property)) =
+                    (func.child_by_field_name("object"), func.child_by_field_name("property"))
+                else {
+                    return;
+                };
+                let receiver = self.node_text(object, source);
+                if matches!(object.kind(), "identifier" | "this" | "super" | "member_expression")
+                    && !receiver.contains(|c: char| c == '(' || c.is_whitespace())
+                {
+                    meta.insert("receiver".to_string(), serde_json::json!(receiver));
+                }
+                property
+            }
+            _ => return,
+        };
+        edges.push(EdgeIR {
+            edge_type: EdgeType::Calls,
+            src: Some(caller.to_string()),
+            dst: Some(self.node_text(callee, source)),
+            file_src: Some(file_path.to_string()),
+            file_dst: None,
+            resolution: Resolution::Syntactic,
+            meta,
+            provenance: HashMap::new(),
+        });
+    }
+    
+    /// A `References` edge, tagged `meta.jsx`, from the component rendering
+    /// a JSX element to the component it names: `Button` for `<Button />`,
+    /// `Header` with `meta.receiver` `Layout` for `<Layout.Header>`.
+    /// Lowercase names are DOM elements and are skipped, as are elements
+    /// rendered outside any symbol.
+    fn extract_jsx_reference(
+        &self,
+        name: Node,
+        source: &[u8],
+        file_path: &str,
+        parent_symbol: Option<&str>,
+        edges: &mut Vec<EdgeIR>,
+        occurrences: &mut Vec<OccurrenceIR>,
+    ) {
+        let mut meta = HashMap::new();
+        meta.insert("jsx".to_string(), serde_json::json!(true));
+        let component = match name.kind() {
+            "identifier" if self.node_text(name, source).starts_with(|c: char| c.is_ascii_uppercase()) => name,
+            "member_expression" => {
+                let (Some(object), Some(property)) =
+                    (name.child_by_field_name("object"), name.child_by_field_name("property"))
+                else {
+                    return;
+                };
+                meta.insert("receiver".to_string(), serde_json::json!(self.node_text(object, source)));
+                property
+            }
+            _ => return,
+        };
+        let component_name = self.node_text(component, source);
+        
+        occurrences.push(OccurrenceIR {
+            file_path: file_path.to_string(),
+            symbol_id: None,
+            role: OccurrenceRole::Reference,
+            span: self.node_to_span(name),
+            token: self.node_text(name, source),
+        });
+        if let Some(parent) = parent_symbol {
+            edges.push(EdgeIR {
+                edge_type: EdgeType::References,
+                src: Some(parent.to_string()),
+                dst: Some(component_name),
+                file_src: Some(file_path.to_string()),
+                file_dst: None,
+                resolution: Resolution::Syntactic,
+                meta,
+                provenance: HashMap::new(),
+            });
+        }
+    }
+    
+    /// `Extends` and `Implements` edges from the heritage clauses of a class
+    /// (`class A extends B implements C, D`) or interface (`interface I
+    /// extends J, K`), to the names as written without type arguments
+    fn extract_heritage(&self, node: Node, source: &[u8], file_path: &str, symbol_id: &str, edges: &mut Vec<EdgeIR>) {
+        let mut push = |edge_type: EdgeType, type_node: Node| {
+            // A mixin call such as `extends Timestamped(Base)` names no type
+            let target = match type_node.kind() {
+                "generic_type" => type_node.child_by_field_name("name"),
+                "identifier" | "type_identifier" | "member_expression" | "nested_identifier"
+                | "nested_type_identifier" => Some(type_node),
+                _ => None,
+            };
+            if let Some(target) = target {
+                edges.push(EdgeIR {
+                    edge_type,
+                    src: Some(symbol_id.to_string()),
+                    dst: Some(self.node_text(target, source)),
+                    file_src: Some(file_path.to_string()),
+                    file_dst: None,
+                    resolution: Resolution::Syntactic,
+                    meta: HashMap::new(),
+                    provenance: HashMap::new(),
+                });
+            }
+        };
+        for child in node.children(&mut node.walk()) {
+            match child.kind() {
+                "class_heritage" => {
+                    for clause in child.named_children(&mut child.walk()) {
+                        match clause.kind() {
+                            "extends_clause" => {
+                                for base in clause.children_by_field_name("value", &mut clause.walk()) {
+                                    push(EdgeType::Extends, base);
+                                }
+                            }
+                            "implements_clause" => {
+                                for interface in clause.named_children(&mut clause.walk()) {
+                                    push(EdgeType::Implements, interface);
+                                }
+                            }
+                            // Plain JS has the superclass right in the heritage
+                            _ => push(EdgeType::Extends, clause),
+                        }
+                    }
+                }
+                "extends_type_clause" => {
+                    for base in child.named_children(&mut child.walk()) {
+                        push(EdgeType::Extends, base);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    
     fn extract_imports(&self, node: Node, source: &[u8], file_path: &str, edges: &mut Vec<EdgeIR>) -> Result<()> {
         let mut cursor = node.walk();
         
@@ -492,6 +813,10 @@ impl TypeScriptHarness {
                     // Create file-to-file import edge
                     let resolved_path = self.resolve_import_path(file_path, import_path);
                     
+                    // `import { a as b }` makes `b` an alias of the imported `a`
+                    self.extract_aliases(child, source, file_path, &resolved_path, "import_as", edges);
+                    self.extract_import_specifiers(child, source, file_path, import_path, &resolved_path, edges);
+                    
                     edges.push(EdgeIR {
                         edge_type: EdgeType::Imports,
                         src: None,
@@ -499,11 +824,13 @@ impl TypeScriptHarness {
                         file_src: Some(file_path.to_string()),
                         file_dst: Some(resolved_path),
                         resolution: Resolution::Syntactic,
-                        meta: HashMap::new(),
+                        meta: self.import_meta(import_path),
                         provenance: HashMap::new(),
                     });
                 }
             } else if child.kind() == "export_statement" {
+                self.extract_exports(child, source, file_path, edges);
+                
                 // Handle re-exports
                 if let Some(source_node) = child.child_by_field_name("source") {
                     let import_path = self.node_text(source_node, source);
@@ -511,6 +838,28 @@ impl TypeScriptHarness {
                     
                     let resolved_path = self.resolve_import_path(file_path, import_path);
                     
+                    self.extract_aliases(child, source, file_path, &resolved_path, "reexport", edges);
+                    
+                    // `export * from './x'` (barrel) forwards every name of the target module
+                    let is_wildcard = child.children(&mut child.walk()).any(|c| c.kind() == "*")
+                        && !child.children(&mut child.walk()).any(|c| c.kind() == "namespace_export");
+                    if is_wildcard {
+                        let mut meta = HashMap::new();
+                        meta.insert("alias".to_string(), serde_json::json!("*"));
+                        meta.insert("alias_kind".to_string(), serde_json::json!("reexport_all"));
+                        
+                        edges.push(EdgeIR {
+                            edge_type: EdgeType::Aliases,
+                            src: Some(format!("{}/*", self.module_fqn(file_path))),
+                            dst: Some(format!("{}/*", self.module_fqn(&resolved_path))),
+                            file_src: Some(file_path.to_string()),
+                            file_dst: Some(resolved_path.clone()),
+                            resolution: Resolution::Syntactic,
+                            meta,
+                            provenance: HashMap::new(),
+                        });
+                    }
+                    
                     edges.push(EdgeIR {
                         edge_type: EdgeType::Imports,
                         src: None,
@@ -518,9 +867,12 @@ impl TypeScriptHarness {
                         file_src: Some(file_path.to_string()),
                         file_dst: Some(resolved_path),
                         resolution: Resolution::Syntactic,
-                        meta: HashMap::new(),
+                        meta: self.import_meta(import_path),
                         provenance: HashMap::new(),
                     });
+                } else {
+                    // Local `export { a as b }` renames a symbol of this file
+                    self.extract_aliases(child, source, file_path, file_path, "export_rename", edges);
                 }
             }
         }
@@ -528,24 +880,256 @@ impl TypeScriptHarness {
         Ok(())
     }
     
+    /// Emit an Imports edge from this module to each name an `import`
+    /// statement binds, beside the file-to-file one: `imported` is the
+    /// export's name (`default` for a default import, `*` for a namespace),
+    /// `local` the binding, `import_kind` `named`, `default` or `namespace`,
+    /// and `type_only` marks `import type`.
+    fn extract_import_specifiers(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &str,
+        import_path: &str,
+        target_path: &str,
+        edges: &mut Vec<EdgeIR>,
+    ) {
+        let statement_type_only = node.children(&mut node.walk()).any(|c| c.kind() == "type");
+        let Some(clause) = node.children(&mut node.walk()).find(|c| c.kind() == "import_clause") else {
+            // `import './polyfills'` binds nothing
+            return;
+        };
+        let mut bindings: Vec<(String, String, &str, bool)> = Vec::new();
+        for child in clause.named_children(&mut clause.walk()) {
+            match child.kind() {
+                "identifier" => {
+                    bindings.push(("default".to_string(), self.node_text(child, source), "default", false));
+                }
+                "namespace_import" => {
+                    if let Some(name) = child.named_children(&mut child.walk()).find(|c| c.kind() == "identifier") {
+                        bindings.push(("*".to_string(), self.node_text(name, source), "namespace", false));
+                    }
+                }
+                "named_imports" => {
+                    for specifier in child.named_children(&mut child.walk()).filter(|c| c.kind() == "import_specifier") {
+                        let Some(name_node) = specifier.child_by_field_name("name") else {
+                            continue;
+                        };
+                        let imported = self.node_text(name_node, source);
+                        let local = specifier
+                            .child_by_field_name("alias")
+                            .map(|alias| self.node_text(alias, source))
+                            .unwrap_or_else(|| imported.clone());
+                        // `import { type A }`
+                        let type_only = specifier.children(&mut specifier.walk()).any(|c| c.kind() == "type");
+                        bindings.push((imported, local, "named", type_only));
+                    }
+                }
+                _ => {}
+            }
+        }
+        
+        let module = self.module_fqn(file_path);
+        let target = self.module_fqn(target_path);
+        for (imported, local, kind, type_only) in bindings {
+            let mut meta = self.import_meta(import_path);
+            meta.insert("imported".to_string(), serde_json::json!(imported));
+            meta.insert("local".to_string(), serde_json::json!(local));
+            meta.insert("import_kind".to_string(), serde_json::json!(kind));
+            if type_only || statement_type_only {
+                meta.insert("type_only".to_string(), serde_json::json!(true));
+            }
+            edges.push(EdgeIR {
+                edge_type: EdgeType::Imports,
+                src: Some(module.clone()),
+                dst: Some(format!("{}/{}", target, imported)),
+                file_src: Some(file_path.to_string()),
+                file_dst: Some(target_path.to_string()),
+                resolution: Resolution::Syntactic,
+                meta,
+                provenance: HashMap::new(),
+            });
+        }
+    }
+    
+    /// Emit an Exports edge from this module for every name an `export`
+    /// statement adds to its public surface. `export_kind` is `named`,
+    /// `default`, `reexport`, `reexport_all` (`export *`) or `namespace`
+    /// (`export * as ns`).
+    fn extract_exports(&self, node: Node, source: &[u8], file_path: &str, edges: &mut Vec<EdgeIR>) {
+        let module = self.module_fqn(file_path);
+        let target_path = node.child_by_field_name("source").map(|source_node| {
+            let import_path = self.node_text(source_node, source);
+            self.resolve_import_path(file_path, import_path.trim_matches(|c| c == '\'' || c == '"'))
+        });
+        let target = target_path.as_deref().map(|path| self.module_fqn(path)).unwrap_or_else(|| module.clone());
+        let mut exports: Vec<(String, String, &str)> = Vec::new();
+        
+        let children: Vec<Node> = node.children(&mut node.walk()).collect();
+        let is_default = children.iter().any(|c| c.kind() == "default");
+        for child in &children {
+            match child.kind() {
+                "export_clause" => {
+                    for specifier in child.children(&mut child.walk()).filter(|c| c.kind() == "export_specifier") {
+                        let Some(name_node) = specifier.child_by_field_name("name") else {
+                            continue;
+                        };
+                        let name = self.node_text(name_node, source);
+                        let exported = specifier
+                            .child_by_field_name("alias")
+                            .map(|alias| self.node_text(alias, source))
+                            .unwrap_or_else(|| name.clone());
+                        let kind = if target_path.is_some() { "reexport" } else { "named" };
+                        exports.push((exported, format!("{}/{}", target, name), kind));
+                    }
+                }
+                "namespace_export" => {
+                    if let Some(name_node) = child.named_children(&mut child.walk()).last() {
+                        exports.push((self.node_text(name_node, source), target.clone(), "namespace"));
+                    }
+                }
+                "*" if target_path.is_some() && !children.iter().any(|c| c.kind() == "namespace_export") => {
+                    exports.push(("*".to_string(), format!("{}/*", target), "reexport_all"));
+                }
+                _ => {}
+            }
+        }
+        
+        if let Some(declaration) = node.child_by_field_name("declaration") {
+            for name in self.declared_names(declaration, source) {
+                let exported = if is_default { "default".to_string() } else { name.clone() };
+                exports.push((exported, format!("{}/{}", module, name), if is_default { "default" } else { "named" }));
+            }
+        } else if is_default {
+            // `export default <expression>`: an identifier points at its binding
+            let local = node
+                .child_by_field_name("value")
+                .filter(|value| value.kind() == "identifier")
+                .map(|value| self.node_text(value, source))
+                .unwrap_or_else(|| "default".to_string());
+            exports.push(("default".to_string(), format!("{}/{}", module, local), "default"));
+        }
+        
+        for (exported, dst, kind) in exports {
+            let mut meta = HashMap::new();
+            meta.insert("export_name".to_string(), serde_json::json!(exported));
+            meta.insert("export_kind".to_string(), serde_json::json!(kind));
+            edges.push(EdgeIR {
+                edge_type: EdgeType::Exports,
+                src: Some(module.clone()),
+                dst: Some(dst),
+                file_src: Some(file_path.to_string()),
+                file_dst: Some(target_path.clone().unwrap_or_else(|| file_path.to_string())),
+                resolution: Resolution::Syntactic,
+                meta,
+                provenance: HashMap::new(),
+            });
+        }
+    }
+    
+    /// Names bound by an exported declaration (`const a = 1, b = 2` binds two)
+    fn declared_names(&self, declaration: Node, source: &[u8]) -> Vec<String> {
+        match declaration.kind() {
+            "lexical_declaration" | "variable_declaration" => declaration
+                .named_children(&mut declaration.walk())
+                .filter(|c| c.kind() == "variable_declarator")
+                .filter_map(|c| c.child_by_field_name("name"))
+                .filter(|name| name.kind() == "identifier")
+                .map(|name| self.node_text(name, source))
+                .collect(),
+            _ => declaration
+                .child_by_field_name("name")
+                .map(|name| vec![self.node_text(name, source)])
+                .unwrap_or_default(),
+        }
+    }
+    
+    /// Emit Aliases edges for renamed import/export specifiers (`a as b`).
+    /// Re-exports from another module alias the name even without a rename.
+    fn extract_aliases(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &str,
+        target_path: &str,
+        alias_kind: &str,
+        edges: &mut Vec<EdgeIR>,
+    ) {
+        for child in node.children(&mut node.walk()) {
+            match child.kind() {
+                "import_specifier" | "export_specifier" => {
+                    let Some(name_node) = child.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let name = self.node_text(name_node, source);
+                    let alias = match child.child_by_field_name("alias") {
+                        Some(alias_node) => self.node_text(alias_node, source),
+                        None if alias_kind == "reexport" => name.clone(),
+                        None => continue,
+                    };
+                    
+                    let mut meta = HashMap::new();
+                    meta.insert("alias".to_string(), serde_json::json!(alias));
+                    meta.insert("alias_kind".to_string(), serde_json::json!(alias_kind));
+                    
+                    edges.push(EdgeIR {
+                        edge_type: EdgeType::Aliases,
+                        src: Some(format!("{}/{}", self.module_fqn(file_path), alias)),
+                        dst: Some(format!("{}/{}", self.module_fqn(target_path), name)),
+                        file_src: Some(file_path.to_string()),
+                        file_dst: Some(target_path.to_string()),
+                        resolution: Resolution::Syntactic,
+                        meta,
+                        provenance: HashMap::new(),
+                    });
+                }
+                "import_clause" | "named_imports" | "export_clause" => {
+                    self.extract_aliases(child, source, file_path, target_path, alias_kind, edges);
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    /// FQN prefix for symbols declared in the given file. A `.d.ts` file
+    /// shares it with the implementation it describes.
+    fn module_fqn(&self, file_path: &str) -> String {
+        file_path
+            .trim_end_matches(".d.ts")
+            .trim_end_matches(".ts")
+            .trim_end_matches(".tsx")
+            .trim_end_matches(".js")
+            .to_string()
+    }
+    
     fn resolve_import_path(&self, current_file: &str, import_path: &str) -> String {
-        // Simple resolution for relative imports
+        if let Some(resolved) = self.resolver.resolve(current_file, import_path) {
+            return resolved;
+        }
+        
+        // Nothing on disk to check against, or the file is missing: resolve
+        // relative imports lexically and guess a TypeScript source
         if import_path.starts_with("./") || import_path.starts_with("../") {
             let current_dir = std::path::Path::new(current_file)
                 .parent()
                 .unwrap_or(std::path::Path::new(""));
             
-            let resolved = current_dir.join(import_path);
+            // Resolve `.`/`..` lexically so paths line up with scanned file paths
+            let mut resolved = std::path::PathBuf::new();
+            for component in current_dir.join(import_path).components() {
+                match component {
+                    std::path::Component::CurDir => {}
+                    std::path::Component::ParentDir => {
+                        resolved.pop();
+                    }
+                    other => resolved.push(other),
+                }
+            }
             
-            // Add .ts/.tsx/.js extension if missing
             let path_str = resolved.to_string_lossy();
             if !path_str.ends_with(".ts") && !path_str.ends_with(".tsx") && 
                !path_str.ends_with(".js") && !path_str.ends_with(".jsx") {
-                // Try common extensions
-                if let Some(ext) = [".ts", ".tsx", ".js", ".jsx", "/index.ts", "/index.js"].iter().next() {
-                    let with_ext = format!("{}{}", path_str, ext);
-                    return with_ext;
-                }
+                return format!("{}.ts", path_str);
             }
             
             path_str.to_string()
@@ -555,21 +1139,46 @@ impl TypeScriptHarness {
         }
     }
     
+    /// Record which workspace package a bare import was resolved through
+    fn import_meta(&self, import_path: &str) -> HashMap<String, serde_json::Value> {
+        let mut meta = HashMap::new();
+        if let Some(package) = self.resolver.workspace().package_for(import_path) {
+            meta.insert("workspace_package".to_string(), serde_json::json!(package.name));
+        }
+        meta
+    }
+    
     fn create_symbol(
         &self,
         name: &str,
         kind: SymbolKind,
         lang: Language,
         node: Node,
+        name_node: Node,
         file_path: &str,
         commit_sha: &str,
         source: &[u8],
     ) -> SymbolIR {
-        let fqn = format!("{}/{}", file_path.trim_end_matches(".ts").trim_end_matches(".tsx").trim_end_matches(".js"), name);
+        let fqn = format!("{}/{}", self.module_fqn(file_path), name);
         let sig_hash = format!("{:x}", name.len()); // Simple hash for now
         
         let id = SymbolIR::generate_id(commit_sha, file_path, &lang, &fqn, &sig_hash);
         
+        // JSDoc fills in what type annotations don't say
+        let jsdoc = self.leading_comment(node, source).and_then(|comment| JsDoc::parse(&comment));
+        let mut parameters = self.extract_parameters(node, source);
+        let mut return_type = self.extract_return_type(node, source);
+        if let Some(jsdoc) = &jsdoc {
+            for parameter in &mut parameters {
+                if let Some(documented) = jsdoc.params.iter().find(|p| p.name == parameter.name) {
+                    parameter.type_name = parameter.type_name.take().or_else(|| documented.type_name.clone());
+                    parameter.default = parameter.default.take().or_else(|| documented.default.clone());
+                    parameter.variadic |= documented.variadic;
+                }
+            }
+            return_type = return_type.or_else(|| jsdoc.returns.clone());
+        }
+        
         SymbolIR {
             id,
             lang,
@@ -578,14 +1187,145 @@ impl TypeScriptHarness {
             name: name.to_string(),
             fqn,
             signature: None, // Will be enhanced later
+            parameters,
+            return_type,
             file_path: file_path.to_string(),
-            span: self.node_to_span(node),
+            name_span: self.node_to_span(name_node),
+            full_span: self.node_to_span(node),
             visibility: Some("public".to_string()),
-            doc: None,
+            flags: self.symbol_flags(node, file_path, source),
+            configs: Vec::new(),
+            doc: jsdoc.map(|doc| doc.description).filter(|description| !description.is_empty()),
+            doc_tags: None,
             sig_hash,
         }
     }
     
+    /// Flags from JSDoc tags on the declaration, from test-file naming and
+    /// for ambient declarations
+    fn symbol_flags(&self, node: Node, file_path: &str, source: &[u8]) -> SymbolFlags {
+        let mut flags = SymbolFlags::empty();
+        flags.set_if(SymbolFlags::DECLARATION, self.is_ambient(node, file_path));
+        if let Some(doc) = self.leading_comment(node, source) {
+            flags.set_if(SymbolFlags::DEPRECATED, doc.contains("@deprecated"));
+            flags.set_if(
+                SymbolFlags::EXPERIMENTAL,
+                ["@experimental", "@alpha", "@beta"].iter().any(|tag| doc.contains(tag)),
+            );
+        }
+        flags.set_if(
+            SymbolFlags::TEST_ONLY,
+            file_path.contains(".test.") || file_path.contains(".spec.") || file_path.contains("__tests__/"),
+        );
+        flags
+    }
+    
+    /// Everything in a `.d.ts` file, `declare ...` and bodiless overload
+    /// signatures describe code that lives elsewhere
+    fn is_ambient(&self, node: Node, file_path: &str) -> bool {
+        if file_path.ends_with(".d.ts") || matches!(node.kind(), "function_signature" | "method_signature") {
+            return true;
+        }
+        let mut ancestor = node.parent();
+        while let Some(parent) = ancestor {
+            if parent.kind() == "ambient_declaration" {
+                return true;
+            }
+            ancestor = parent.parent();
+        }
+        false
+    }
+    
+    /// Occurrence role for the name of a symbol where it is introduced
+    fn definition_role(&self, symbol: &SymbolIR) -> OccurrenceRole {
+        if symbol.flags.contains(SymbolFlags::DECLARATION) {
+            OccurrenceRole::Declaration
+        } else {
+            OccurrenceRole::Definition
+        }
+    }
+    
+    /// Comment directly above a declaration, looking through `export` and
+    /// `const` wrappers
+    fn leading_comment(&self, node: Node, source: &[u8]) -> Option<String> {
+        let mut anchor = node;
+        while let Some(parent) = anchor.parent() {
+            match parent.kind() {
+                "export_statement" | "lexical_declaration" | "variable_declaration" => anchor = parent,
+                _ => break,
+            }
+        }
+        anchor
+            .prev_named_sibling()
+            .filter(|prev| prev.kind() == "comment")
+            .map(|comment| self.node_text(comment, source))
+    }
+    
+    /// The function-like node of a declaration: itself, or the function a
+    /// `const f = (...) => ...` declarator is initialized with
+    fn function_node<'a>(&self, node: Node<'a>) -> Node<'a> {
+        match node.child_by_field_name("value") {
+            Some(value) if matches!(value.kind(), "arrow_function" | "function_expression" | "function") => value,
+            _ => node,
+        }
+    }
+    
+    fn extract_parameters(&self, node: Node, source: &[u8]) -> Vec<Parameter> {
+        let function = self.function_node(node);
+        if let Some(params) = function.child_by_field_name("parameters") {
+            params
+                .named_children(&mut params.walk())
+                .filter_map(|param| self.parameter(param, source))
+                .collect()
+        } else if let Some(param) = function.child_by_field_name("parameter") {
+            // `x => x * 2`
+            vec![Parameter { name: self.node_text(param, source), ..Default::default() }]
+        } else {
+            Vec::new()
+        }
+    }
+    
+    /// Return type annotation without its leading `:`
+    fn extract_return_type(&self, node: Node, source: &[u8]) -> Option<String> {
+        self.function_node(node)
+            .child_by_field_name("return_type")
+            .map(|annotation| self.node_text(annotation, source).trim_start_matches(':').trim().to_string())
+    }
+    
+    fn parameter(&self, param: Node, source: &[u8]) -> Option<Parameter> {
+        match param.kind() {
+            // TypeScript: `x: T = d`, `x?: T`, `...xs: T[]`
+            "required_parameter" | "optional_parameter" => {
+                let pattern = param.child_by_field_name("pattern")?;
+                let variadic = pattern.kind() == "rest_pattern";
+                let name_node = if variadic { pattern.named_child(0)? } else { pattern };
+                Some(Parameter {
+                    name: self.node_text(name_node, source),
+                    type_name: param.child_by_field_name("type").map(|annotation| {
+                        self.node_text(annotation, source).trim_start_matches(':').trim().to_string()
+                    }),
+                    default: param.child_by_field_name("value").map(|value| self.node_text(value, source)),
+                    variadic,
+                })
+            }
+            // JavaScript: `x`, `{ a, b }`, `x = d`, `...xs`
+            "identifier" | "object_pattern" | "array_pattern" => {
+                Some(Parameter { name: self.node_text(param, source), ..Default::default() })
+            }
+            "assignment_pattern" => Some(Parameter {
+                name: self.node_text(param.child_by_field_name("left")?, source),
+                default: param.child_by_field_name("right").map(|value| self.node_text(value, source)),
+                ..Default::default()
+            }),
+            "rest_pattern" => Some(Parameter {
+                name: self.node_text(param.named_child(0)?, source),
+                variadic: true,
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+    
     fn node_text(&self, node: Node, source: &[u8]) -> String {
         std::str::from_utf8(&source[node.byte_range()])
             .unwrap_or("")
@@ -665,6 +1405,81 @@ mod tests {
         Ok(())
     }
     
+    #[test]
+    fn test_parameters() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let source = "function greet(name: string, greeting = \"hi\", ...rest: string[]) {}\nconst twice = (x) => x * 2;\n";
+        let (symbols, _, _) = harness.parse_file(source, "greet.ts", "abc123")?;
+        
+        let greet = symbols.iter().find(|s| s.name == "greet").expect("Should find greet");
+        assert_eq!(greet.parameters, vec![
+            Parameter { name: "name".to_string(), type_name: Some("string".to_string()), default: None, variadic: false },
+            Parameter { name: "greeting".to_string(), type_name: None, default: Some("\"hi\"".to_string()), variadic: false },
+            Parameter { name: "rest".to_string(), type_name: Some("string[]".to_string()), default: None, variadic: true },
+        ]);
+        
+        let twice = symbols.iter().find(|s| s.name == "twice").expect("Should find twice");
+        assert_eq!(twice.parameters.len(), 1);
+        assert_eq!(twice.parameters[0].name, "x");
+        assert_eq!(twice.return_type, None);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_symbol_flags() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let source = r#"
+/** @deprecated use loadV2 */
+export function load() {}
+
+/** @beta */
+export const loadV2 = () => {};
+
+function helper() {}
+"#;
+        let (symbols, _, _) = harness.parse_file(source, "src/load.ts", "abc123")?;
+        let flags = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().flags;
+        assert_eq!(flags("load"), SymbolFlags::DEPRECATED);
+        assert_eq!(flags("loadV2"), SymbolFlags::EXPERIMENTAL);
+        assert!(flags("helper").is_empty());
+        
+        let (symbols, _, _) = harness.parse_file(source, "src/load.test.ts", "abc123")?;
+        assert!(symbols.iter().all(|s| s.flags.contains(SymbolFlags::TEST_ONLY)));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_return_type() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let source = "function load(id: string): Promise<User | null> {}\nconst size = (): number => 0;\n";
+        let (symbols, _, _) = harness.parse_file(source, "load.ts", "abc123")?;
+        
+        let return_type = |name: &str| symbols.iter().find(|s| s.name == name).and_then(|s| s.return_type.clone());
+        assert_eq!(return_type("load").as_deref(), Some("Promise<User | null>"));
+        assert_eq!(return_type("size").as_deref(), Some("number"));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_name_span_and_full_span() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let (symbols, _, _) = harness.parse_file(
+            fixtures::CLASS_WITH_METHODS,
+            "calculator.ts",
+            "abc123"
+        )?;
+        
+        let class = symbols.iter().find(|s| s.name == "Calculator").expect("Should find Calculator class");
+        assert_eq!(class.name_span, Span { start_line: 1, start_col: 13, end_line: 1, end_col: 23 });
+        assert_eq!(class.full_span.start_line, 1);
+        assert!(class.full_span.end_line > class.name_span.end_line, "full span should cover the class body");
+        
+        Ok(())
+    }
+    
     #[test]
     fn test_parse_imports() -> Result<()> {
         let mut harness = TypeScriptHarness::new()?;
@@ -689,6 +1504,48 @@ mod tests {
         Ok(())
     }
     
+    #[test]
+    fn test_import_specifier_edges() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let content = r#"
+import React, { useState as useLocal, type FC } from 'react';
+import * as api from './api';
+import type { User } from '../models/user';
+import './polyfills';
+"#;
+        let (_, edges, _) = harness.parse_file(content, "src/app/view.tsx", "abc123")?;
+        let specifiers: Vec<(&str, &str, &str, &str, bool)> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Imports && e.src.is_some())
+            .map(|e| {
+                (
+                    e.dst.as_deref().unwrap(),
+                    e.meta["imported"].as_str().unwrap(),
+                    e.meta["local"].as_str().unwrap(),
+                    e.meta["import_kind"].as_str().unwrap(),
+                    e.meta.contains_key("type_only"),
+                )
+            })
+            .collect();
+        assert_eq!(
+            specifiers,
+            [
+                ("react/default", "default", "React", "default", false),
+                ("react/useState", "useState", "useLocal", "named", false),
+                ("react/FC", "FC", "FC", "named", true),
+                ("src/app/api/*", "*", "api", "namespace", false),
+                ("src/models/user/User", "User", "User", "named", true),
+            ]
+        );
+        assert!(edges
+            .iter()
+            .filter(|e| e.src.as_deref() == Some("src/app/view"))
+            .all(|e| e.edge_type == EdgeType::Imports));
+        // The file-to-file edges are still there, one per statement
+        assert_eq!(edges.iter().filter(|e| e.edge_type == EdgeType::Imports && e.src.is_none()).count(), 4);
+        Ok(())
+    }
+    
     #[test]
     fn test_stable_symbol_ids() -> Result<()> {
         let mut harness = TypeScriptHarness::new()?;
@@ -865,6 +1722,46 @@ mod tests {
         Ok(())
     }
     
+    #[test]
+    fn test_heritage_edges() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let content = r#"
+interface Named { name: string }
+interface Entity<K> extends Named, models.Keyed<K> {}
+abstract class Base<T> implements Entity<T> {}
+export class User extends Base<string> implements Named, Serializable {}
+class Audited extends Timestamped(Base) {}
+"#;
+        let (symbols, edges, _) = harness.parse_file(content, "src/models.ts", "abc123")?;
+        let name = |id: &str| symbols.iter().find(|s| s.id == id).map(|s| s.name.as_str()).unwrap();
+        let heritage: Vec<(EdgeType, &str, &str)> = edges
+            .iter()
+            .filter(|e| matches!(e.edge_type, EdgeType::Extends | EdgeType::Implements))
+            .map(|e| (e.edge_type.clone(), name(e.src.as_deref().unwrap()), e.dst.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            heritage,
+            [
+                (EdgeType::Extends, "Entity", "Named"),
+                (EdgeType::Extends, "Entity", "models.Keyed"),
+                (EdgeType::Implements, "Base", "Entity"),
+                (EdgeType::Extends, "User", "Base"),
+                (EdgeType::Implements, "User", "Named"),
+                (EdgeType::Implements, "User", "Serializable"),
+            ]
+        );
+        
+        // Plain JS has no implements clause
+        let (_, edges, _) = harness.parse_file("class Admin extends User {}", "src/admin.js", "abc123")?;
+        let extends: Vec<&str> = edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Extends)
+            .filter_map(|e| e.dst.as_deref())
+            .collect();
+        assert_eq!(extends, ["User"]);
+        Ok(())
+    }
+    
     #[test]
     fn test_property_accessors() -> Result<()> {
         let mut harness = TypeScriptHarness::new()?;
@@ -905,6 +1802,275 @@ mod tests {
         Ok(())
     }
     
+    #[test]
+    fn test_alias_edges() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let (_, edges, _) = harness.parse_file(
+            r#"
+import { parse as parseDate, format } from './lib/date';
+export { foo as bar, baz } from './items';
+const local = 1;
+export { local as publicName };
+"#,
+            "src/index.ts",
+            "abc123"
+        )?;
+        
+        let aliases: Vec<_> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Aliases)
+            .collect();
+        assert_eq!(aliases.len(), 4, "Only renamed specifiers and re-exports are aliases");
+        
+        let import_as = aliases.iter()
+            .find(|e| e.src.as_deref() == Some("src/index/parseDate"))
+            .expect("Should alias parseDate");
+        assert_eq!(import_as.dst.as_deref(), Some("src/lib/date/parse"));
+        assert_eq!(import_as.meta["alias_kind"], "import_as");
+        
+        let reexport = aliases.iter()
+            .find(|e| e.src.as_deref() == Some("src/index/bar"))
+            .expect("Should alias re-exported bar");
+        assert_eq!(reexport.dst.as_deref(), Some("src/items/foo"));
+        assert_eq!(reexport.meta["alias_kind"], "reexport");
+        
+        let plain_reexport = aliases.iter()
+            .find(|e| e.src.as_deref() == Some("src/index/baz"))
+            .expect("Re-exports alias the name even without a rename");
+        assert_eq!(plain_reexport.dst.as_deref(), Some("src/items/baz"));
+        
+        let rename = aliases.iter()
+            .find(|e| e.src.as_deref() == Some("src/index/publicName"))
+            .expect("Should alias local export rename");
+        assert_eq!(rename.dst.as_deref(), Some("src/index/local"));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_export_edges() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let (_, edges, _) = harness.parse_file(
+            r#"
+export function parse() {}
+export const a = 1, b = 2;
+export interface Options {}
+const local = 1;
+export { local as publicName };
+export default class Client {}
+export { foo as bar } from './items';
+export * from './util';
+export * as models from './models';
+import { hidden } from './hidden';
+"#,
+            "src/index.ts",
+            "abc123"
+        )?;
+        
+        let exports: Vec<(&str, &str, &str)> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Exports)
+            .inspect(|e| assert_eq!(e.src.as_deref(), Some("src/index")))
+            .map(|e| (
+                e.meta["export_name"].as_str().unwrap(),
+                e.dst.as_deref().unwrap(),
+                e.meta["export_kind"].as_str().unwrap(),
+            ))
+            .collect();
+        assert_eq!(exports, vec![
+            ("parse", "src/index/parse", "named"),
+            ("a", "src/index/a", "named"),
+            ("b", "src/index/b", "named"),
+            ("Options", "src/index/Options", "named"),
+            ("publicName", "src/index/local", "named"),
+            ("default", "src/index/Client", "default"),
+            ("bar", "src/items/foo", "reexport"),
+            ("*", "src/util/*", "reexport_all"),
+            ("models", "src/models", "namespace"),
+        ]);
+        
+        let (_, edges, _) = harness.parse_file("const app = 1;\nexport default app;\n", "src/app.js", "abc123")?;
+        let default = edges.iter()
+            .find(|e| e.edge_type == EdgeType::Exports)
+            .expect("Should export the default binding");
+        assert_eq!(default.dst.as_deref(), Some("src/app/app"));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_barrel_wildcard_reexport() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let (_, edges, _) = harness.parse_file(
+            fixtures::COMPLEX_EXPORTS,
+            "src/index.ts",
+            "abc123"
+        )?;
+        
+        let wildcards: Vec<_> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Aliases && e.meta["alias_kind"] == "reexport_all")
+            .collect();
+        // `export * as utils` is a namespace object, not a barrel
+        assert_eq!(wildcards.len(), 1);
+        assert_eq!(wildcards[0].src.as_deref(), Some("src/index/*"));
+        assert_eq!(wildcards[0].dst.as_deref(), Some("src/types/*"));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_workspace_package_imports() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("ts_harness_ws_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("packages/core/src"))?;
+        std::fs::write(root.join("package.json"), r#"{"workspaces": ["packages/*"]}"#)?;
+        std::fs::write(root.join("packages/core/package.json"), r#"{"name": "@acme/core", "main": "dist/index.js"}"#)?;
+        std::fs::write(root.join("packages/core/src/index.ts"), "export const x = 1;")?;
+        
+        let mut harness = TypeScriptHarness::new()?.with_workspace(Workspace::detect(&root));
+        let (_, edges, _) = harness.parse_file(
+            "import { x } from '@acme/core';\nimport React from 'react';",
+            "apps/web/src/main.ts",
+            "abc123"
+        )?;
+        
+        let imports: Vec<_> = edges.iter().filter(|e| e.edge_type == EdgeType::Imports).collect();
+        let core = imports.iter()
+            .find(|e| e.file_dst.as_deref() == Some("packages/core/src/index.ts"))
+            .expect("workspace import resolved to source");
+        assert_eq!(core.meta["workspace_package"], "@acme/core");
+        assert!(imports.iter().any(|e| e.file_dst.as_deref() == Some("react")));
+        
+        let _ = std::fs::remove_dir_all(&root);
+        Ok(())
+    }
+    
+    #[test]
+    fn test_call_edges_from_enclosing_function() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let (symbols, edges, _) = harness.parse_file(
+            r#"
+bootstrap();
+
+function main() {
+    const config = load();
+    function inner() { log(config); }
+    handlers[config.mode]();
+}
+
+const run = async () => { await main(); };
+
+class Service {
+    onSave = () => this.notify();
+
+    save(item: Item) {
+        this.validate(item);
+        this.repo?.insert(item);
+        make()();
+    }
+}
+"#,
+            "src/service.ts",
+            "abc123"
+        )?;
+        
+        let names: HashMap<&str, &str> = symbols.iter().map(|s| (s.id.as_str(), s.name.as_str())).collect();
+        let calls: Vec<(&str, &str, Option<&str>)> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Calls)
+            .map(|e| (
+                names[e.src.as_deref().unwrap()],
+                e.dst.as_deref().unwrap(),
+                e.meta.get("receiver").and_then(|r| r.as_str()),
+            ))
+            .collect();
+        assert_eq!(calls, vec![
+            ("main", "load", None),
+            ("inner", "log", None),
+            ("run", "main", None),
+            ("Service", "notify", Some("this")),
+            ("save", "validate", Some("this")),
+            ("save", "insert", Some("this.repo")),
+            ("save", "make", None),
+        ]);
+        assert!(edges.iter().all(|e| e.file_src.as_deref() == Some("src/service.ts") || e.edge_type != EdgeType::Calls));
+        Ok(())
+    }
+    
+    #[test]
+    fn test_jsx_component_references() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let content = r#"
+import { Button, Layout } from './ui';
+
+export function Toolbar({ items }: Props) {
+    const label = <T,>(x: T) => x;
+    return (
+        <Layout.Header>
+            {items.map(item => <Button key={item.id} icon={<Icon name="x" />} />)}
+            <div><Button /></div>
+        </Layout.Header>
+    );
+}
+
+render(<Toolbar items={[]} />);
+"#;
+        for file in ["src/toolbar.tsx", "src/toolbar.jsx"] {
+            // Type parameters are TypeScript only
+            let content = if file.ends_with(".jsx") {
+                content.replace("Props", "").replace("<T,>(x: T)", "(x)").replace("({ items }: )", "({ items })")
+            } else {
+                content.to_string()
+            };
+            let (symbols, edges, occurrences) = harness.parse_file(&content, file, "abc123")?;
+            let toolbar = symbols.iter().find(|s| s.name == "Toolbar").expect("component symbol");
+            
+            let references: Vec<(&str, Option<&str>)> = edges.iter()
+                .filter(|e| e.edge_type == EdgeType::References)
+                .map(|e| {
+                    assert_eq!(e.src.as_deref(), Some(toolbar.id.as_str()));
+                    assert_eq!(e.meta["jsx"], true);
+                    (e.dst.as_deref().unwrap(), e.meta.get("receiver").and_then(|r| r.as_str()))
+                })
+                .collect();
+            assert_eq!(references, [
+                ("Header", Some("Layout")),
+                ("Button", None),
+                ("Icon", None),
+                ("Button", None),
+            ], "{}", file);
+            
+            // Rendered outside any component: an occurrence but no edge
+            assert!(occurrences.iter().any(|o| o.role == OccurrenceRole::Reference && o.token == "Toolbar"));
+        }
+        Ok(())
+    }
+    
+    #[test]
+    fn test_imports_resolve_to_existing_files() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("ts_harness_resolve_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src/components/nav"))?;
+        std::fs::write(root.join("tsconfig.json"), r#"{"compilerOptions": {"paths": {"@/*": ["./src/*"]}}}"#)?;
+        std::fs::write(root.join("src/components/nav/index.tsx"), "export const Nav = 1;")?;
+        std::fs::write(root.join("src/util.js"), "export const util = 1;")?;
+        
+        let mut harness = TypeScriptHarness::new()?.with_resolver(ImportResolver::detect(&root));
+        let (_, edges, _) = harness.parse_file(
+            "import { Nav } from '@/components/nav';\nimport { util } from './util';\nimport { gone } from './gone';",
+            "src/app.ts",
+            "abc123"
+        )?;
+        
+        let mut targets: Vec<&str> = edges.iter()
+            .filter(|e| e.edge_type == EdgeType::Imports && e.src.is_none())
+            .filter_map(|e| e.file_dst.as_deref())
+            .collect();
+        targets.sort();
+        // A missing file keeps the lexical guess
+        assert_eq!(targets, ["src/components/nav/index.tsx", "src/gone.ts", "src/util.js"]);
+        
+        let _ = std::fs::remove_dir_all(&root);
+        Ok(())
+    }
+    
     #[test]
     fn test_type_guards_and_assertions() -> Result<()> {
         let mut harness = TypeScriptHarness::new()?;
@@ -1109,6 +2275,100 @@ mod tests {
         Ok(())
     }
     
+    #[test]
+    fn test_jsdoc() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let source = r#"
+/**
+ * @typedef {Object} Point
+ * @property {number} x
+ */
+
+/**
+ * Distance between two points.
+ * @param {Point} a
+ * @param {Point} b
+ * @param {number} [scale=1]
+ * @returns {number}
+ */
+function distance(a, b, scale) {}
+
+/** @param {...string} parts */
+export const join = (...parts) => parts.join("/");
+"#;
+        let (symbols, _, _) = harness.parse_file(source, "geometry.js", "abc123")?;
+        
+        let point = symbols.iter().find(|s| s.name == "Point").expect("typedef should be a symbol");
+        assert_eq!(point.kind, SymbolKind::Type);
+        assert_eq!(point.fqn, "geometry/Point");
+        assert_eq!(point.signature.as_deref(), Some("type Point = Object"));
+        
+        let distance = symbols.iter().find(|s| s.name == "distance").unwrap();
+        assert_eq!(distance.doc.as_deref(), Some("Distance between two points."));
+        assert_eq!(distance.return_type.as_deref(), Some("number"));
+        let params: Vec<(&str, Option<&str>, Option<&str>)> = distance
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.type_name.as_deref(), p.default.as_deref()))
+            .collect();
+        assert_eq!(params, [("a", Some("Point"), None), ("b", Some("Point"), None), ("scale", Some("number"), Some("1"))]);
+        
+        let join = symbols.iter().find(|s| s.name == "join").unwrap();
+        assert_eq!(join.doc, None);
+        assert_eq!(join.parameters[0].type_name.as_deref(), Some("string"));
+        assert!(join.parameters[0].variadic);
+        
+        // Annotations win over JSDoc
+        let (symbols, _, _) =
+            harness.parse_file("/** @param {string} id */\nfunction load(id: number) {}", "load.ts", "abc123")?;
+        assert_eq!(symbols[0].parameters[0].type_name.as_deref(), Some("number"));
+        Ok(())
+    }
+    
+    #[test]
+    fn test_declaration_files() -> Result<()> {
+        let mut harness = TypeScriptHarness::new()?;
+        let source = r#"
+export declare function format(value: number): string;
+export declare class Formatter {
+    constructor(locale: string);
+    format(value: number): string;
+}
+export interface Options { locale: string }
+"#;
+        let (symbols, _, occurrences) = harness.parse_file(source, "src/format.d.ts", "abc123")?;
+        let declared: Vec<(&str, &str, bool)> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.fqn.as_str(), s.flags.contains(SymbolFlags::DECLARATION)))
+            .collect();
+        assert_eq!(
+            declared,
+            [
+                ("format", "src/format/format", true),
+                ("Formatter", "src/format/Formatter", true),
+                ("constructor", "src/format/constructor", true),
+                ("format", "src/format/format", true),
+                ("Options", "src/format/Options", true),
+            ]
+        );
+        assert!(occurrences.iter().all(|o| o.role != OccurrenceRole::Definition));
+        assert!(occurrences.iter().any(|o| o.role == OccurrenceRole::Declaration && o.token == "format"));
+        
+        // Same FQN as the declaration, but real code
+        let (symbols, _, occurrences) =
+            harness.parse_file("export function format(value: number): string { return `${value}`; }", "src/format.ts", "abc123")?;
+        assert_eq!(symbols[0].fqn, "src/format/format");
+        assert!(symbols[0].flags.is_empty());
+        assert_eq!(occurrences[0].role, OccurrenceRole::Definition);
+        
+        // `declare` and overload signatures in ordinary files
+        let source = "declare function ambient(): void;\nfunction pick(a: string): string;\nfunction pick(a: any) { return a; }\n";
+        let (symbols, _, _) = harness.parse_file(source, "src/pick.ts", "abc123")?;
+        let flags: Vec<bool> = symbols.iter().map(|s| s.flags.contains(SymbolFlags::DECLARATION)).collect();
+        assert_eq!(flags, [true, true, false]);
+        Ok(())
+    }
+    
     #[test]
     fn test_symbol_span_accuracy() -> Result<()> {
         let mut harness = TypeScriptHarness::new()?;
@@ -1120,10 +2380,10 @@ mod tests {
         
         for symbol in &symbols {
             // Spans should have valid line/column numbers
-            assert!(symbol.span.start_line <= symbol.span.end_line, 
+            assert!(symbol.full_span.start_line <= symbol.full_span.end_line, 
                 "Start line should be <= end line");
-            if symbol.span.start_line == symbol.span.end_line {
-                assert!(symbol.span.start_col <= symbol.span.end_col, 
+            if symbol.full_span.start_line == symbol.full_span.end_line {
+                assert!(symbol.full_span.start_col <= symbol.full_span.end_col, 
                     "Start col should be <= end col on same line");
             }
         }
@@ -0,0 +1,386 @@
+use crate::workspace::{join, read_json, Workspace};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Extensions tried after an extensionless specifier, in `tsc`'s order
+const EXTENSIONS: &[&str] = &["ts", "tsx", "d.ts", "js", "jsx", "mts", "cts", "mjs", "cjs"];
+
+/// TypeScript sources an ESM-style `./x.js` specifier is compiled from
+const JS_TO_TS: &[(&str, &[&str])] = &[
+    (".js", &[".ts", ".tsx", ".d.ts"]),
+    (".jsx", &[".tsx"]),
+    (".mjs", &[".mts"]),
+    (".cjs", &[".cts"]),
+];
+
+/// `package.json` fields naming a directory's entry point
+const MAIN_FIELDS: &[&str] = &["types", "typings", "module", "main"];
+
+/// The `compilerOptions` of a tsconfig.json/jsconfig.json that steer module lookup
+#[derive(Debug, Clone, Default)]
+struct TsConfig {
+    /// Repo-relative directory of the config file
+    dir: String,
+    /// Repo-relative `baseUrl`
+    base_url: Option<String>,
+    /// `paths` patterns and their targets
+    paths: Vec<(String, Vec<String>)>,
+    /// Directory `paths` targets are relative to
+    paths_base: String,
+}
+
+/// Resolves import specifiers to repo-relative files the way `tsc` does:
+/// relative paths with extension, `package.json` and `index` fallbacks,
+/// then tsconfig `paths` and `baseUrl`, then workspace packages. Only files
+/// that exist are returned.
+#[derive(Debug, Clone, Default)]
+pub struct ImportResolver {
+    repo_root: Option<PathBuf>,
+    workspace: Workspace,
+    /// Deepest directory first, so the first enclosing config is the nearest
+    configs: Vec<TsConfig>,
+}
+
+impl ImportResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the workspace packages and every tsconfig.json (or jsconfig.json)
+    /// under `repo_root`
+    pub fn detect(repo_root: &Path) -> Self {
+        let mut files = Vec::new();
+        collect_configs(repo_root, "", &mut files);
+        let mut configs: Vec<TsConfig> = files.iter().filter_map(|file| load_config(repo_root, file, 0)).collect();
+        configs.sort_by(|a, b| b.dir.len().cmp(&a.dir.len()).then_with(|| a.dir.cmp(&b.dir)));
+
+        Self {
+            repo_root: Some(repo_root.to_path_buf()),
+            workspace: Workspace::detect(repo_root),
+            configs,
+        }
+    }
+
+    pub fn with_workspace(mut self, workspace: Workspace) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    pub fn workspace(&self) -> &Workspace {
+        &self.workspace
+    }
+
+    /// The repo-relative file `specifier`, imported from `current_file`,
+    /// refers to
+    pub fn resolve(&self, current_file: &str, specifier: &str) -> Option<String> {
+        if is_relative(specifier) {
+            return self.find_module(&normalize(&join(parent(current_file), specifier)));
+        }
+        if let Some(config) = self.config_for(current_file) {
+            if let Some(found) = self.resolve_with_config(config, specifier) {
+                return Some(found);
+            }
+        }
+        self.workspace.resolve(specifier)
+    }
+
+    fn config_for(&self, file: &str) -> Option<&TsConfig> {
+        self.configs.iter().find(|config| {
+            config.dir.is_empty() || file.strip_prefix(config.dir.as_str()).is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    /// `paths` first, the most specific matching pattern winning, then `baseUrl`
+    fn resolve_with_config(&self, config: &TsConfig, specifier: &str) -> Option<String> {
+        let mut best: Option<(usize, &[String], &str)> = None;
+        for (pattern, targets) in &config.paths {
+            let (rank, matched) = match pattern.split_once('*') {
+                None if pattern == specifier => (usize::MAX, ""),
+                None => continue,
+                Some((prefix, suffix)) => {
+                    let Some(matched) = specifier
+                        .strip_prefix(prefix)
+                        .and_then(|rest| rest.strip_suffix(suffix))
+                    else {
+                        continue;
+                    };
+                    (prefix.len(), matched)
+                }
+            };
+            if best.is_none_or(|(best_rank, _, _)| rank > best_rank) {
+                best = Some((rank, targets.as_slice(), matched));
+            }
+        }
+
+        if let Some((_, targets, matched)) = best {
+            let found = targets.iter().find_map(|target| {
+                let target = target.replacen('*', matched, 1);
+                self.find_module(&normalize(&join(&config.paths_base, &target)))
+            });
+            if found.is_some() {
+                return found;
+            }
+        }
+
+        let base_url = config.base_url.as_deref()?;
+        self.find_module(&normalize(&join(base_url, specifier)))
+    }
+
+    /// The file a module path stands for: itself, its TypeScript source,
+    /// with an extension added, or the entry point of the directory it names
+    fn find_module(&self, path: &str) -> Option<String> {
+        let root = self.repo_root.as_ref()?;
+        let mut candidates = file_candidates(path);
+        if let Some(entry) = read_json(&root.join(path).join("package.json")).and_then(|manifest| {
+            MAIN_FIELDS.iter().find_map(|field| manifest.get(*field).and_then(Value::as_str).map(str::to_string))
+        }) {
+            let entry = normalize(&join(path, &entry));
+            candidates.extend(file_candidates(&entry));
+            candidates.extend(index_candidates(&entry));
+        }
+        candidates.extend(index_candidates(path));
+
+        candidates
+            .into_iter()
+            .find(|candidate| !candidate.is_empty() && root.join(candidate).is_file())
+    }
+}
+
+fn file_candidates(path: &str) -> Vec<String> {
+    let mut candidates = Vec::new();
+    for (js, sources) in JS_TO_TS {
+        if let Some(stem) = path.strip_suffix(js) {
+            candidates.extend(sources.iter().map(|ext| format!("{}{}", stem, ext)));
+        }
+    }
+    candidates.push(path.to_string());
+    candidates.extend(EXTENSIONS.iter().map(|ext| format!("{}.{}", path, ext)));
+    candidates
+}
+
+fn index_candidates(dir: &str) -> impl Iterator<Item = String> + '_ {
+    EXTENSIONS.iter().map(move |ext| join(dir, &format!("index.{}", ext)))
+}
+
+fn is_relative(specifier: &str) -> bool {
+    specifier == "." || specifier == ".." || specifier.starts_with("./") || specifier.starts_with("../")
+}
+
+fn parent(file: &str) -> &str {
+    file.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// Resolve `.` and `..` lexically; `..` past the repository root is dropped
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+fn collect_configs(repo_root: &Path, dir: &str, files: &mut Vec<String>) {
+    if let Some(name) = ["tsconfig.json", "jsconfig.json"]
+        .iter()
+        .find(|name| repo_root.join(dir).join(name).is_file())
+    {
+        files.push(join(dir, name));
+    }
+    let Ok(entries) = std::fs::read_dir(repo_root.join(dir)) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.path().is_dir() && !name.starts_with('.') && name != "node_modules" {
+            collect_configs(repo_root, &join(dir, &name), files);
+        }
+    }
+}
+
+/// Load a config with whatever it inherits through relative `extends`.
+/// Configs from packages (`@tsconfig/node20`) live in node_modules and are
+/// not followed.
+fn load_config(repo_root: &Path, file: &str, depth: usize) -> Option<TsConfig> {
+    let text = std::fs::read_to_string(repo_root.join(file)).ok()?;
+    let json: Value = serde_json::from_str(&strip_jsonc(&text)).ok()?;
+    let dir = parent(file).to_string();
+
+    let mut config = TsConfig::default();
+    let extends: Vec<&str> = match json.get("extends") {
+        Some(Value::String(base)) => vec![base.as_str()],
+        Some(Value::Array(bases)) => bases.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    for base in extends.into_iter().filter(|base| is_relative(base) && depth < 8) {
+        let mut base = normalize(&join(&dir, base));
+        if !base.ends_with(".json") {
+            base.push_str(".json");
+        }
+        if let Some(inherited) = load_config(repo_root, &base, depth + 1) {
+            if inherited.base_url.is_some() {
+                config.base_url = inherited.base_url;
+            }
+            if !inherited.paths.is_empty() {
+                config.paths = inherited.paths;
+                config.paths_base = inherited.paths_base;
+            }
+        }
+    }
+
+    let options = json.get("compilerOptions");
+    if let Some(base_url) = options.and_then(|o| o.get("baseUrl")).and_then(Value::as_str) {
+        config.base_url = Some(normalize(&join(&dir, base_url)));
+    }
+    if let Some(Value::Object(paths)) = options.and_then(|o| o.get("paths")) {
+        config.paths = paths
+            .iter()
+            .map(|(pattern, targets)| {
+                let targets = targets.as_array().into_iter().flatten().filter_map(Value::as_str);
+                (pattern.clone(), targets.map(str::to_string).collect())
+            })
+            .collect();
+        config.paths_base = dir.clone();
+    }
+    // `paths` are relative to `baseUrl` when there is one
+    if let Some(base_url) = &config.base_url {
+        config.paths_base = base_url.clone();
+    }
+    config.dir = dir;
+    Some(config)
+}
+
+/// tsconfig files are JSON with comments and trailing commas
+fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                out.extend(chars.next());
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|&next| next != '\n').is_some() {}
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            '}' | ']' => {
+                let kept = out.trim_end().len();
+                if out[..kept].ends_with(',') {
+                    out.remove(kept - 1);
+                }
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_against_filesystem_and_tsconfig() {
+        let root = std::env::temp_dir().join(format!("ts_resolver_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        write(
+            &root,
+            "tsconfig.base.json",
+            r#"{
+                // Shared by every app
+                "compilerOptions": {
+                    "baseUrl": ".",
+                    "paths": {
+                        "@lib/*": ["libs/*/src", "vendor/*"],
+                        "@config": ["config/index.ts"],
+                    },
+                },
+            }"#,
+        );
+        write(&root, "tsconfig.json", r#"{"extends": "./tsconfig.base.json"}"#);
+        write(&root, "apps/web/tsconfig.json", r#"{"compilerOptions": {"baseUrl": "src", "paths": {"~/*": ["./*"]}}}"#);
+        write(&root, "apps/web/src/main.ts", "");
+        write(&root, "apps/web/src/ui/button.tsx", "");
+        write(&root, "apps/web/src/state/index.ts", "");
+        write(&root, "apps/web/src/legacy.js", "");
+        write(&root, "apps/web/src/types.d.ts", "");
+        write(&root, "libs/auth/src/index.ts", "");
+        write(&root, "vendor/charts.js", "");
+        write(&root, "config/index.ts", "");
+        write(&root, "tools/gen/package.json", r#"{"main": "lib/cli.js"}"#);
+        write(&root, "tools/gen/lib/cli.ts", "");
+        write(&root, "scripts/run.ts", "");
+
+        let resolver = ImportResolver::detect(&root);
+        let resolve = |from: &str, specifier: &str| resolver.resolve(from, specifier);
+        let main = "apps/web/src/main.ts";
+
+        // Relative: extensions, index files, ESM `.js` specifiers, declarations
+        assert_eq!(resolve(main, "./ui/button").as_deref(), Some("apps/web/src/ui/button.tsx"));
+        assert_eq!(resolve(main, "./state").as_deref(), Some("apps/web/src/state/index.ts"));
+        assert_eq!(resolve(main, "./ui/button.js").as_deref(), Some("apps/web/src/ui/button.tsx"));
+        assert_eq!(resolve(main, "./legacy").as_deref(), Some("apps/web/src/legacy.js"));
+        assert_eq!(resolve(main, "./types").as_deref(), Some("apps/web/src/types.d.ts"));
+        assert_eq!(resolve("scripts/run.ts", "../tools/gen").as_deref(), Some("tools/gen/lib/cli.ts"));
+        assert_eq!(resolve(main, "./missing"), None);
+
+        // The nearest tsconfig applies: apps/web maps `~/*` and its baseUrl
+        assert_eq!(resolve(main, "~/state").as_deref(), Some("apps/web/src/state/index.ts"));
+        assert_eq!(resolve(main, "ui/button").as_deref(), Some("apps/web/src/ui/button.tsx"));
+        assert_eq!(resolve(main, "@lib/auth"), None);
+
+        // The root one, through `extends`: fallback targets and exact patterns
+        assert_eq!(resolve("scripts/run.ts", "@lib/auth").as_deref(), Some("libs/auth/src/index.ts"));
+        assert_eq!(resolve("scripts/run.ts", "@lib/charts").as_deref(), Some("vendor/charts.js"));
+        assert_eq!(resolve("scripts/run.ts", "@config").as_deref(), Some("config/index.ts"));
+        assert_eq!(resolve("scripts/run.ts", "config").as_deref(), Some("config/index.ts"));
+        assert_eq!(resolve("scripts/run.ts", "react"), None);
+
+        // Without a repository there is nothing to check against
+        assert_eq!(ImportResolver::new().resolve(main, "./state"), None);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_strip_jsonc() {
+        let text = "{\n  // note\n  \"a\": \"http://x/*y*/\", /* gone */\n  \"b\": [1, 2,],\n}";
+        let value: Value = serde_json::from_str(&strip_jsonc(text)).unwrap();
+        assert_eq!(value, serde_json::json!({"a": "http://x/*y*/", "b": [1, 2]}));
+        assert_eq!(normalize("a/./b/../c/"), "a/c");
+        assert_eq!(normalize("../x"), "x");
+    }
+}
@@ -0,0 +1,377 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Conditions tried, in order, when an `exports` entry is a condition object.
+/// `source`/`types` come first because they usually point at TypeScript sources.
+const EXPORT_CONDITIONS: &[&str] = &["source", "types", "import", "module", "require", "node", "default"];
+
+/// Build output directories that typically mirror `src/`
+const BUILD_DIRS: &[&str] = &["dist", "lib", "build", "out", "esm", "cjs"];
+
+const SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mts", "cts", "mjs", "cjs"];
+
+/// A package of a JS/TS monorepo
+#[derive(Debug, Clone)]
+pub struct WorkspacePackage {
+    pub name: String,
+    /// Repo-relative package directory
+    pub dir: String,
+    /// `exports` map, keyed by subpath (`.`, `./utils`, `./*`)
+    exports: HashMap<String, String>,
+    /// Fallback entry from `source`/`types`/`module`/`main`
+    main: Option<String>,
+}
+
+/// pnpm/yarn/npm workspace packages of a repository.
+///
+/// Lets a bare import such as `@acme/ui/button` resolve to the in-repo source
+/// file instead of ending at the package name.
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    repo_root: PathBuf,
+    packages: HashMap<String, WorkspacePackage>,
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Discover workspace packages from the root `package.json` `workspaces`
+    /// field and `pnpm-workspace.yaml`
+    pub fn detect(repo_root: &Path) -> Self {
+        let mut workspace = Self {
+            repo_root: repo_root.to_path_buf(),
+            packages: HashMap::new(),
+        };
+
+        let mut patterns = Vec::new();
+        if let Some(manifest) = read_json(&repo_root.join("package.json")) {
+            match manifest.get("workspaces") {
+                Some(Value::Array(list)) => patterns.extend(string_values(list)),
+                Some(Value::Object(obj)) => {
+                    if let Some(Value::Array(list)) = obj.get("packages") {
+                        patterns.extend(string_values(list));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Ok(yaml) = std::fs::read_to_string(repo_root.join("pnpm-workspace.yaml")) {
+            patterns.extend(parse_pnpm_packages(&yaml));
+        }
+
+        for pattern in patterns {
+            if pattern.starts_with('!') {
+                continue;
+            }
+            for dir in expand_pattern(repo_root, &pattern) {
+                workspace.add_package_dir(&dir);
+            }
+        }
+
+        workspace
+    }
+
+    /// Register the package in a repo-relative directory, if it has a manifest
+    pub fn add_package_dir(&mut self, dir: &str) {
+        let dir = dir.trim_start_matches("./").trim_end_matches('/');
+        let Some(manifest) = read_json(&self.repo_root.join(dir).join("package.json")) else {
+            return;
+        };
+        let Some(name) = manifest.get("name").and_then(|n| n.as_str()) else {
+            return;
+        };
+
+        let mut exports = HashMap::new();
+        match manifest.get("exports") {
+            Some(Value::Object(map)) if map.keys().any(|k| k.starts_with('.')) => {
+                for (subpath, target) in map {
+                    if let Some(target) = pick_export_target(target) {
+                        exports.insert(subpath.clone(), target);
+                    }
+                }
+            }
+            // Sugar for `{ ".": ... }`
+            Some(target) => {
+                if let Some(target) = pick_export_target(target) {
+                    exports.insert(".".to_string(), target);
+                }
+            }
+            None => {}
+        }
+
+        let main = ["source", "types", "typings", "module", "main"]
+            .iter()
+            .find_map(|field| manifest.get(*field).and_then(|v| v.as_str()))
+            .map(|s| s.to_string());
+
+        self.packages.insert(
+            name.to_string(),
+            WorkspacePackage {
+                name: name.to_string(),
+                dir: dir.to_string(),
+                exports,
+                main,
+            },
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    pub fn package(&self, name: &str) -> Option<&WorkspacePackage> {
+        self.packages.get(name)
+    }
+
+    /// The workspace package a bare import specifier refers to
+    pub fn package_for(&self, specifier: &str) -> Option<&WorkspacePackage> {
+        let (name, _) = split_specifier(specifier);
+        self.packages.get(name)
+    }
+
+    /// Resolve a bare import specifier to a repo-relative source file
+    pub fn resolve(&self, specifier: &str) -> Option<String> {
+        let (name, subpath) = split_specifier(specifier);
+        let package = self.packages.get(name)?;
+        let subpath_key = match subpath {
+            Some(sub) => format!("./{}", sub),
+            None => ".".to_string(),
+        };
+
+        let target = match package.exports.get(&subpath_key) {
+            Some(target) => Some(target.clone()),
+            None => package
+                .exports
+                .iter()
+                .find_map(|(pattern, target)| match_export_pattern(pattern, target, &subpath_key)),
+        };
+
+        let target = match (target, subpath) {
+            (Some(target), _) => target,
+            // Packages with an `exports` map only expose what it lists
+            (None, _) if !package.exports.is_empty() => return None,
+            (None, None) => package.main.clone().unwrap_or_else(|| "index".to_string()),
+            (None, Some(sub)) => sub.to_string(),
+        };
+
+        self.find_source(&package.dir, &target)
+    }
+
+    /// Map an entry point (often a build artifact) onto an existing source file
+    fn find_source(&self, package_dir: &str, target: &str) -> Option<String> {
+        let target = target.trim_start_matches("./");
+        let stem = strip_extension(target);
+
+        let mut stems = vec![stem.to_string()];
+        // `dist/button.js` was most likely built from `src/button.ts`
+        if let Some((first, rest)) = stem.split_once('/') {
+            if BUILD_DIRS.contains(&first) {
+                stems.push(format!("src/{}", rest));
+            }
+        } else {
+            stems.push(format!("src/{}", stem));
+        }
+
+        let mut candidates = vec![target.to_string()];
+        for stem in &stems {
+            for ext in SOURCE_EXTENSIONS {
+                candidates.push(format!("{}.{}", stem, ext));
+            }
+            for ext in SOURCE_EXTENSIONS {
+                candidates.push(format!("{}/index.{}", stem, ext));
+            }
+        }
+
+        candidates
+            .into_iter()
+            .map(|candidate| join(package_dir, &candidate))
+            .find(|candidate| {
+                !candidate.ends_with(".d.ts") && self.repo_root.join(candidate).is_file()
+            })
+    }
+}
+
+/// Split `@scope/pkg/sub/path` into (`@scope/pkg`, `sub/path`)
+fn split_specifier(specifier: &str) -> (&str, Option<&str>) {
+    let name_segments = if specifier.starts_with('@') { 2 } else { 1 };
+    let mut end = 0;
+    for (seen, (idx, _)) in specifier.match_indices('/').enumerate() {
+        if seen + 1 == name_segments {
+            end = idx;
+            break;
+        }
+    }
+    if end == 0 {
+        (specifier, None)
+    } else {
+        let sub = &specifier[end + 1..];
+        (&specifier[..end], if sub.is_empty() { None } else { Some(sub) })
+    }
+}
+
+/// Pick a concrete file from an `exports` value (string, condition object or array)
+fn pick_export_target(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(items) => items.iter().find_map(pick_export_target),
+        Value::Object(conditions) => EXPORT_CONDITIONS
+            .iter()
+            .find_map(|c| conditions.get(*c).and_then(pick_export_target))
+            .or_else(|| conditions.values().find_map(pick_export_target)),
+        _ => None,
+    }
+}
+
+/// Match `./*`-style subpath patterns: `"./*": "./src/*.ts"` maps `./a` to `./src/a.ts`
+fn match_export_pattern(pattern: &str, target: &str, subpath: &str) -> Option<String> {
+    let (prefix, suffix) = pattern.split_once('*')?;
+    let matched = subpath.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    Some(target.replacen('*', matched, 1))
+}
+
+/// Expand a workspace glob. Only the forms used in practice are supported:
+/// exact directories, `dir/*` and `dir/**`.
+fn expand_pattern(repo_root: &Path, pattern: &str) -> Vec<String> {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    let Some(base) = pattern.strip_suffix("/**").or_else(|| pattern.strip_suffix("/*")) else {
+        return vec![pattern.to_string()];
+    };
+    let recursive = pattern.ends_with("/**");
+
+    let mut dirs = Vec::new();
+    collect_package_dirs(repo_root, base, recursive, &mut dirs);
+    dirs.sort();
+    dirs
+}
+
+fn collect_package_dirs(repo_root: &Path, base: &str, recursive: bool, dirs: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(repo_root.join(base)) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entry.path().is_dir() || name.starts_with('.') || name == "node_modules" {
+            continue;
+        }
+        let dir = join(base, &name);
+        if recursive {
+            collect_package_dirs(repo_root, &dir, true, dirs);
+        }
+        dirs.push(dir);
+    }
+}
+
+/// Read the `packages:` list of a pnpm-workspace.yaml
+fn parse_pnpm_packages(yaml: &str) -> Vec<String> {
+    let mut packages = Vec::new();
+    let mut in_packages = false;
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(' ') && !line.starts_with('-') {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if in_packages {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                packages.push(item.trim().trim_matches(|c| c == '\'' || c == '"').to_string());
+            }
+        }
+    }
+    packages
+}
+
+pub(crate) fn read_json(path: &Path) -> Option<Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn string_values(list: &[Value]) -> impl Iterator<Item = String> + '_ {
+    list.iter().filter_map(|v| v.as_str().map(|s| s.to_string()))
+}
+
+fn strip_extension(path: &str) -> &str {
+    for ext in [".d.ts", ".ts", ".tsx", ".js", ".jsx", ".mjs", ".cjs", ".mts", ".cts"] {
+        if let Some(stem) = path.strip_suffix(ext) {
+            return stem;
+        }
+    }
+    path
+}
+
+pub(crate) fn join(base: &str, path: &str) -> String {
+    if base.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", base, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_workspace_packages() {
+        let root = std::env::temp_dir().join(format!("ts_workspace_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        write(&root, "package.json", r#"{"private": true, "workspaces": ["packages/*"]}"#);
+        write(
+            &root,
+            "packages/ui/package.json",
+            r#"{
+                "name": "@acme/ui",
+                "exports": {
+                    ".": {"types": "./dist/index.d.ts", "import": "./dist/index.js"},
+                    "./button": "./dist/button.js",
+                    "./icons/*": "./src/icons/*.tsx"
+                }
+            }"#,
+        );
+        write(&root, "packages/ui/src/index.ts", "");
+        write(&root, "packages/ui/src/button.tsx", "");
+        write(&root, "packages/ui/src/icons/star.tsx", "");
+        write(&root, "packages/utils/package.json", r#"{"name": "utils", "main": "lib/index.js"}"#);
+        write(&root, "packages/utils/src/index.ts", "");
+        write(&root, "packages/utils/src/date.ts", "");
+
+        let workspace = Workspace::detect(&root);
+        assert_eq!(workspace.resolve("@acme/ui"), Some("packages/ui/src/index.ts".to_string()));
+        assert_eq!(workspace.resolve("@acme/ui/button"), Some("packages/ui/src/button.tsx".to_string()));
+        assert_eq!(
+            workspace.resolve("@acme/ui/icons/star"),
+            Some("packages/ui/src/icons/star.tsx".to_string())
+        );
+        // Not listed in the exports map
+        assert_eq!(workspace.resolve("@acme/ui/internal"), None);
+
+        assert_eq!(workspace.resolve("utils"), Some("packages/utils/src/index.ts".to_string()));
+        assert_eq!(workspace.resolve("utils/date"), Some("packages/utils/src/date.ts".to_string()));
+        assert_eq!(workspace.resolve("react"), None);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_pnpm_workspace_and_specifiers() {
+        let yaml = "packages:\n  - 'apps/*'\n  - \"libs/**\"\n  - '!**/test/**'\ncatalog:\n  - nope\n";
+        assert_eq!(parse_pnpm_packages(yaml), vec!["apps/*", "libs/**", "!**/test/**"]);
+
+        assert_eq!(split_specifier("@scope/pkg/a/b"), ("@scope/pkg", Some("a/b")));
+        assert_eq!(split_specifier("@scope/pkg"), ("@scope/pkg", None));
+        assert_eq!(split_specifier("lodash/fp"), ("lodash", Some("fp")));
+        assert_eq!(split_specifier("lodash"), ("lodash", None));
+    }
+}
@@ -0,0 +1,24 @@
+//! Snapshot of the full IR emitted for the `testdata/typescript-basic` fixture.
+//!
+//! Review changes with `cargo insta review`, or rerun with
+//! `INSTA_UPDATE=always` to accept them.
+
+use ts_harness::TypeScriptHarness;
+use std::path::Path;
+
+fn fixture(path: &str) -> String {
+    std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../testdata").join(path)).unwrap()
+}
+
+#[test]
+fn test_typescript_basic_snapshot() {
+    let mut harness = TypeScriptHarness::new().unwrap();
+    let content = fixture("typescript-basic/service.ts");
+    let (symbols, edges, occurrences) = harness.parse_file(&content, "service.ts", "snapshot").unwrap();
+
+    insta::with_settings!({ sort_maps => true, snapshot_suffix => "typescript-basic" }, {
+        insta::assert_json_snapshot!("symbols", symbols);
+        insta::assert_json_snapshot!("edges", edges);
+        insta::assert_json_snapshot!("occurrences", occurrences);
+    });
+}
@@ -0,0 +1,19 @@
+#include <vector>
+#include "models.h"
+
+namespace app {
+/// A service
+template <typename T>
+class Service : public Base {
+public:
+    explicit Service(Repo* repo) : repo_(repo) {}
+    virtual ~Service() = default;
+    T find(const std::string& id) const { return repo_->load(id); }
+    static int count;
+private:
+    Repo* repo_;
+};
+
+struct Point { int x, y; };
+int main() { auto s = Service<int>(nullptr); return s.find("x"); }
+}
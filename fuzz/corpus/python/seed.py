@@ -0,0 +1,15 @@
+from .models import User as U
+import os.path
+
+@dataclass
+class Service(Base, metaclass=Meta):
+    """A service."""
+    def __init__(self, repo: "Repo") -> None:
+        self.repo = repo
+
+    async def find(self, id: str, *args, **kwargs) -> U | None:
+        return await self.repo.load(id) if id else None
+
+def main():
+    lambda x: x + 1
+    return Service(None).find("x")
@@ -0,0 +1,14 @@
+import { helper } from './util';
+export * from './models';
+
+/** A service */
+export class UserService<T> extends Base implements Service {
+  private cache = new Map<string, T>();
+  constructor(private readonly repo: Repo) { super(); }
+  async find(id: string): Promise<T | undefined> {
+    return this.cache.get(id) ?? helper(await this.repo.load(id));
+  }
+}
+
+export const handler = ({ a, b }: Args) => a + b;
+export default function main() { return new UserService(null as any).find(`x${1}`); }
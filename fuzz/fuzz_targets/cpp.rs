@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use cpp_harness::CppHarness;
+
+// Harnesses must return Ok or Err for any input, never panic
+fuzz_target!(|data: &[u8]| {
+    let source = String::from_utf8_lossy(data);
+    let mut cpp = CppHarness::new_cpp().unwrap();
+    let _ = cpp.parse("fuzz.cpp", &source);
+    let mut c = CppHarness::new_c().unwrap();
+    let _ = c.parse("fuzz.c", &source);
+});
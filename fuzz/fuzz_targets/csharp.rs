@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use csharp_harness::CSharpHarness;
+
+// Harnesses must return Ok or Err for any input, never panic
+fuzz_target!(|data: &[u8]| {
+    let source = String::from_utf8_lossy(data);
+    let mut harness = CSharpHarness::new().unwrap();
+    let _ = harness.parse_file("Fuzz.cs", &source);
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use go_harness::GoHarness;
+
+// Harnesses must return Ok or Err for any input, never panic
+fuzz_target!(|data: &[u8]| {
+    let source = String::from_utf8_lossy(data);
+    let mut harness = GoHarness::new().unwrap();
+    let _ = harness.parse_file(&source, "fuzz.go", "fuzz");
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use java_harness::JavaHarness;
+
+// Harnesses must return Ok or Err for any input, never panic
+fuzz_target!(|data: &[u8]| {
+    let source = String::from_utf8_lossy(data);
+    let mut harness = JavaHarness::new().unwrap();
+    let _ = harness.parse("src/main/java/fuzz/Fuzz.java", &source);
+});
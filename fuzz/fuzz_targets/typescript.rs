@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ts_harness::TypeScriptHarness;
+
+// Harnesses must return Ok or Err for any input, never panic
+fuzz_target!(|data: &[u8]| {
+    let source = String::from_utf8_lossy(data);
+    let mut harness = TypeScriptHarness::new().unwrap();
+    for path in ["fuzz.ts", "fuzz.tsx", "fuzz.js"] {
+        let _ = harness.parse_file(&source, path, "fuzz");
+    }
+});
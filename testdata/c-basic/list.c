@@ -0,0 +1,25 @@
+#include <stdlib.h>
+#include "list.h"
+
+/* A singly linked list node */
+typedef struct node {
+    int value;
+    struct node *next;
+} node_t;
+
+enum color { RED, GREEN };
+
+static node_t *node_new(int value) {
+    node_t *n = malloc(sizeof *n);
+    n->value = value;
+    n->next = NULL;
+    return n;
+}
+
+int list_sum(const node_t *head) {
+    int sum = 0;
+    for (; head; head = head->next) sum += head->value;
+    return sum;
+}
+
+int main(void) { return list_sum(node_new(1)); }
@@ -0,0 +1,18 @@
+using System;
+using App.Models;
+
+namespace App.Services
+{
+    /// <summary>A service</summary>
+    public class Service<T> : Base, IFinder where T : class
+    {
+        private readonly IRepo _repo;
+        public Service(IRepo repo) { _repo = repo; }
+        public T Find(string id) => _repo.Load<T>(id);
+        public event EventHandler Changed;
+        public int Count { get; set; }
+    }
+
+    public interface IFinder { }
+    public enum Kind { A, B }
+}
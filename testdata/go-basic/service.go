@@ -0,0 +1,21 @@
+package main
+
+import (
+	"fmt"
+	m "example.com/app/models"
+)
+
+// Service does things
+type Service[T any] struct {
+	repo m.Repo
+	m.Base
+}
+
+type Finder interface{ Find(id string) (T, error) }
+
+func (s *Service[T]) Find(id string) (T, error) {
+	defer fmt.Println("done")
+	return s.repo.Load(id)
+}
+
+func main() { go func() { _, _ = (&Service[int]{}).Find("x") }() }
@@ -0,0 +1,19 @@
+package com.acme.service;
+
+import java.util.*;
+import static java.util.Collections.emptyList;
+
+/** A service */
+@Service
+public class Service<T extends Comparable<T>> extends Base implements Finder<T> {
+    @Autowired private Repo repo;
+    public Service(Repo repo) { this.repo = repo; }
+
+    @Override
+    public Optional<T> find(String id) {
+        return repo.load(id).map(x -> x);
+    }
+
+    enum Kind { A, B }
+    record Point(int x, int y) {}
+}
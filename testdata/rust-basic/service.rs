@@ -0,0 +1,18 @@
+use crate::models::{User, Repo as R};
+
+/// A service
+pub struct Service<T: Clone> {
+    repo: R,
+    cache: std::collections::HashMap<String, T>,
+}
+
+pub trait Finder { fn find(&self, id: &str) -> Option<User>; }
+
+impl<T: Clone> Finder for Service<T> {
+    fn find(&self, id: &str) -> Option<User> {
+        self.repo.load(id).map(|u| u.clone())
+    }
+}
+
+macro_rules! m { ($x:expr) => { $x + 1 }; }
+fn main() { let _ = m!(1); }